@@ -0,0 +1,48 @@
+// Make-style dependency files (--emit-depfile of `axis-compiler` and
+// `axis-rust-bridge build`), for Make and Ninja
+//
+// One rule: the file written, then every file the run read, so an
+// external build reruns the tool when any of them changes:
+//
+//   coreir/main.coreir: \
+//     src/main.ax \
+//     registries/axis.axreg
+
+use std::fs;
+
+/// The rule for `target`; inputs in order, each once
+pub fn format(target: &str, inputs: &[String]) -> String {
+    let mut out = escape(target);
+    out.push(':');
+    let mut seen = Vec::new();
+    for input in inputs {
+        if seen.contains(&input) {
+            continue;
+        }
+        seen.push(input);
+        out.push_str(" \\\n  ");
+        out.push_str(&escape(input));
+    }
+    out.push('\n');
+    out
+}
+
+pub fn write(path: &str, target: &str, inputs: &[String]) -> Result<(), String> {
+    fs::write(path, format(target, inputs)).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+// Make splits on spaces, starts a comment at `#` and expands `$`
+fn escape(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            ' ' | '#' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '$' => out.push_str("$$"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,19 @@
+// Tests for the --emit-depfile rules (depfile.rs)
+
+use crate::depfile;
+
+#[test]
+fn test_one_rule_with_each_input_once() {
+    let inputs = ["src/main.ax", "registries/axis.axreg", "src/main.ax"].map(String::from);
+    assert_eq!(
+        depfile::format("coreir/main.coreir", &inputs),
+        "coreir/main.coreir: \\\n  src/main.ax \\\n  registries/axis.axreg\n"
+    );
+    assert_eq!(depfile::format("out.coreir", &[]), "out.coreir:\n");
+}
+
+#[test]
+fn test_make_characters_are_escaped() {
+    let inputs = ["my src/a#1.ax".to_string(), "$HOME.ax".to_string()];
+    assert_eq!(depfile::format("out dir/p.coreir", &inputs), "out\\ dir/p.coreir: \\\n  my\\ src/a\\#1.ax \\\n  $$HOME.ax\n");
+}
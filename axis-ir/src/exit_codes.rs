@@ -0,0 +1,80 @@
+// Process exit codes of axis-compiler and axis-rust-bridge
+//
+// A script can tell why a compile or build failed without parsing stderr:
+//
+//   0  success
+//   1  the command ran and reported failure (the compiler's failing tests)
+//   2  usage: bad arguments or flag values (clap's own code)
+//   3  parse: a source, Core text, manifest or registry file is malformed
+//      (the compiler's only)
+//   4  validation: the compiler rejected the program (or, for
+//      check-registry, a registry), or the bridge's emitter did (codegen
+//      errors, --allow)
+//   5  I/O: a file could not be read or written, a bundle could not be
+//      loaded, or the bridge could not start the toolchain
+//   6  internal: the tool crashed, or the bridge emitted a project that
+//      does not build, which is a bug
+//
+// `axis-compiler run` exits with the program's own code instead.
+
+use std::fmt;
+
+pub const SUCCESS: i32 = 0;
+pub const FAILURE: i32 = 1;
+pub const USAGE: i32 = 2;
+pub const PARSE: i32 = 3;
+pub const VALIDATION: i32 = 4;
+pub const IO: i32 = 5;
+pub const INTERNAL: i32 = 6;
+
+/// The table above as axis-compiler uses it, for --help
+pub const COMPILER_HELP: &str = "\
+Exit codes:
+  0  success
+  1  the command reported failure (failing tests)
+  2  usage error: bad arguments or flag values
+  3  parse error: a source, Core text, manifest or registry file is malformed
+  4  validation error: the program was rejected
+  5  I/O error: a file could not be read or written, or a bundle could not be loaded
+  6  internal error (a bug)
+`run` exits with the program's own code.";
+
+/// The table above as axis-rust-bridge uses it, for the usage text
+pub const BRIDGE_HELP: &str = "\
+Exit codes:
+  0  success
+  2  usage error: bad arguments or flag values
+  4  validation error: the emitter rejected the program
+  5  I/O error: a file could not be read or written, a bundle could not be loaded, or the toolchain could not be started
+  6  internal error (a bug), including an emitted project that does not build";
+
+/// An error message and the exit code it ends the process with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub code: i32,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Failure { code, message: message.into() }
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        Failure::new(PARSE, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Failure::new(VALIDATION, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Failure::new(IO, message)
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
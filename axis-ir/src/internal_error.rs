@@ -0,0 +1,135 @@
+// Internal errors: bugs in the compiler or the bridge rather than problems
+// with the program
+//
+// guard() runs the tool under catch_unwind. A panic inside it is not
+// printed as a bare backtrace: the panic hook records it as a CompilerError
+// naming the phase it happened in (the innermost phase entered with
+// enter_phase(); the compiler's trace::span enters one, the bridge's
+// `build` one per step), and the caller renders that as an "internal
+// error, please report" message naming the tool. RUST_BACKTRACE=1 prints
+// the usual panic report and backtrace as well. Panics outside a guard
+// (the test harness's, for one) are left to the default hook.
+//
+// Code that finds a broken invariant without panicking reports it with
+// CompilerError::new and the same rendering.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic;
+use std::sync::Once;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilerError {
+    // The innermost phase open when it happened
+    pub phase: Option<&'static str>,
+    pub message: String,
+    // Where in the tool's own sources, for a panic
+    pub location: Option<String>,
+}
+
+impl CompilerError {
+    pub fn new(phase: &'static str, message: impl Into<String>) -> Self {
+        CompilerError { phase: Some(phase), message: message.into(), location: None }
+    }
+
+    /// The message for stderr, ending in a newline; `program` names the
+    /// tool that failed
+    pub fn render(&self, program: &str) -> String {
+        // The outermost phase may be the whole run, named after the program
+        let mut out = match self.phase.filter(|phase| *phase != program) {
+            Some(phase) => format!("error: internal error in {} during {}: {}\n", program, phase, self.message),
+            None => format!("error: internal error in {}: {}\n", program, self.message),
+        };
+        if let Some(location) = &self.location {
+            out.push_str(&format!("  --> {}\n", location));
+        }
+        out.push_str(&format!(
+            "note: this is a bug in {}, not in the program; please report it with the command that triggered it\n",
+            program
+        ));
+        if std::env::var_os("RUST_BACKTRACE").is_none() {
+            out.push_str("note: run with RUST_BACKTRACE=1 for a backtrace\n");
+        }
+        out
+    }
+}
+
+thread_local! {
+    // The entered phases, innermost last
+    static PHASES: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    // Open guard() calls on this thread
+    static GUARDS: Cell<usize> = const { Cell::new(0) };
+    // The panic the hook recorded inside a guard
+    static PANIC: RefCell<Option<CompilerError>> = const { RefCell::new(None) };
+}
+
+/// Guard of an entered phase; the phase (and any entered after it) ends
+/// when it is dropped
+pub struct Phase {
+    depth: usize,
+}
+
+pub fn enter_phase(name: &'static str) -> Phase {
+    PHASES.with(|phases| {
+        let mut phases = phases.borrow_mut();
+        phases.push(name);
+        Phase { depth: phases.len() - 1 }
+    })
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        PHASES.with(|phases| phases.borrow_mut().truncate(self.depth));
+    }
+}
+
+pub fn current_phase() -> Option<&'static str> {
+    PHASES.with(|phases| phases.borrow().last().copied())
+}
+
+/// Run `body`; a panic in it is returned as a CompilerError
+pub fn guard<T>(body: impl FnOnce() -> T) -> Result<T, CompilerError> {
+    install_hook();
+    GUARDS.with(|guards| guards.set(guards.get() + 1));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(body));
+    GUARDS.with(|guards| guards.set(guards.get() - 1));
+    result.map_err(|payload| {
+        PANIC.with(|recorded| recorded.borrow_mut().take()).unwrap_or_else(|| CompilerError {
+            phase: None,
+            message: panic_message(&*payload),
+            location: None,
+        })
+    })
+}
+
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if GUARDS.with(Cell::get) == 0 {
+                default_hook(info);
+                return;
+            }
+            let error = CompilerError {
+                phase: current_phase(),
+                message: panic_message(info.payload()),
+                location: info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            };
+            PANIC.with(|recorded| *recorded.borrow_mut() = Some(error));
+            if std::env::var_os("RUST_BACKTRACE").is_some() {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
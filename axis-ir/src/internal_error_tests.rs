@@ -0,0 +1,48 @@
+// Tests for internal errors (internal_error.rs): panics under guard()
+// become CompilerErrors naming the phase
+
+use crate::internal_error::{self, CompilerError};
+
+#[test]
+fn test_guard_passes_results_through() {
+    assert_eq!(internal_error::guard(|| 7), Ok(7));
+}
+
+#[test]
+fn test_a_panic_names_the_innermost_phase() {
+    let error = internal_error::guard(|| {
+        let _outer = internal_error::enter_phase("validate");
+        let _inner = internal_error::enter_phase("lower");
+        panic!("no arm for {}", "x")
+    })
+    .unwrap_err();
+    assert_eq!(error.phase, Some("lower"));
+    assert_eq!(error.message, "no arm for x");
+    assert!(error.location.as_deref().is_some_and(|l| l.starts_with("src/internal_error_tests.rs:")), "{:?}", error);
+    // Unwinding left the phases
+    assert_eq!(internal_error::current_phase(), None);
+}
+
+#[test]
+fn test_phases_end_with_their_guard() {
+    let outer = internal_error::enter_phase("parse");
+    {
+        let _inner = internal_error::enter_phase("resolve imports");
+        assert_eq!(internal_error::current_phase(), Some("resolve imports"));
+    }
+    assert_eq!(internal_error::current_phase(), Some("parse"));
+    drop(outer);
+    assert_eq!(internal_error::current_phase(), None);
+}
+
+#[test]
+fn test_render_names_the_tool_and_asks_for_a_report() {
+    let rendered = CompilerError::new("optimize", "inlined a missing function").render("axis-compiler");
+    assert!(rendered.starts_with("error: internal error in axis-compiler during optimize: inlined a missing function\n"));
+    assert!(rendered.contains("note: this is a bug in axis-compiler, not in the program; please report it"));
+    let rendered = CompilerError::new("build", "the c backend has no build command").render("axis-rust-bridge");
+    assert!(rendered.starts_with("error: internal error in axis-rust-bridge during build: the c backend has no build command\n"));
+    // The run as a whole is not a phase worth naming
+    let rendered = CompilerError::new("axis-compiler", "boom").render("axis-compiler");
+    assert!(rendered.starts_with("error: internal error in axis-compiler: boom\n"), "{}", rendered);
+}
@@ -0,0 +1,25 @@
+// axis-ir: the types and tables axis-compiler and axis-rust-bridge share
+//
+// Both tools read and write Core IR and report their failures the same
+// way; what they have to agree on lives here once instead of as a copy in
+// each crate.
+
+pub mod depfile;
+#[cfg(test)]
+mod depfile_tests;
+pub mod exit_codes;
+pub mod internal_error;
+#[cfg(test)]
+mod internal_error_tests;
+pub mod operators;
+#[cfg(test)]
+mod operators_tests;
+pub mod symbol;
+#[cfg(test)]
+mod symbol_tests;
+pub mod symbol_index;
+#[cfg(test)]
+mod symbol_index_tests;
+pub mod term_kind;
+#[cfg(test)]
+mod term_kind_tests;
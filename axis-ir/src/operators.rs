@@ -0,0 +1,69 @@
+// Infix operators of the surface syntax
+//
+// The one table of operators: the compiler's parser reads each operator's
+// precedence, associativity and Core function from it, its builtin
+// allowlists (registry_loader, validation_registry) accept operators by
+// it, and the bridge's emitters find each function's implementation by it
+// (rust-bridge/src/runtime/operators.rs). `a + b` parses to
+// Call("__add__", [a, b]) and lowers to `(app (app (var __add__) a) b)`.
+//
+// Adding an operator: an entry here (its symbol must lex as one Operator
+// token, see TWO_CHAR_OPERATORS in core-compiler/src/lexer.rs), the
+// function in the interpreter's PRIMITIVES (core-compiler/src/runtime.rs),
+// and its implementations in the bridge.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    // `a - b - c` is `(a - b) - c`
+    Left,
+    // `a ++ b ++ c` is `a ++ (b ++ c)`
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operator {
+    pub symbol: &'static str,
+    // Higher binds tighter: `a + b * c` is `a + (b * c)`
+    pub precedence: u8,
+    pub assoc: Assoc,
+    // The Core function the operator applies
+    pub function: &'static str,
+    pub arity: usize,
+}
+
+const fn binary(symbol: &'static str, precedence: u8, assoc: Assoc, function: &'static str) -> Operator {
+    Operator { symbol, precedence, assoc, function, arity: 2 }
+}
+
+// `x |> f(a)` is `f(x, a)`: the parser gives Call(PIPE, [x, f(a)]), which
+// surface_lower rewrites, so PIPE never reaches Core
+pub const PIPE: &str = "__pipe__";
+
+/// Loosest first
+pub const OPERATORS: &[Operator] = &[
+    binary("|>", 1, Assoc::Left, PIPE),
+    binary("||", 2, Assoc::Left, "__or__"),
+    binary("&&", 3, Assoc::Left, "__and__"),
+    binary("==", 4, Assoc::Left, "__eq__"),
+    binary("!=", 4, Assoc::Left, "__neq__"),
+    binary("<", 4, Assoc::Left, "__lt__"),
+    binary("<=", 4, Assoc::Left, "__lte__"),
+    binary(">", 4, Assoc::Left, "__gt__"),
+    binary(">=", 4, Assoc::Left, "__gte__"),
+    binary("++", 5, Assoc::Right, "__concat__"),
+    binary("+", 6, Assoc::Left, "__add__"),
+    binary("-", 6, Assoc::Left, "__sub__"),
+    binary("*", 7, Assoc::Left, "__mul__"),
+    binary("/", 7, Assoc::Left, "__div__"),
+    binary("%", 7, Assoc::Left, "__mod__"),
+];
+
+/// The infix operator written `symbol`
+pub fn infix(symbol: &str) -> Option<&'static Operator> {
+    OPERATORS.iter().find(|op| op.symbol == symbol && op.arity == 2)
+}
+
+/// Whether `name` is an operator's symbol or the Core function it applies
+pub fn is_operator(name: &str) -> bool {
+    OPERATORS.iter().any(|op| op.symbol == name || (op.function == name && name != PIPE))
+}
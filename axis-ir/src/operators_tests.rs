@@ -0,0 +1,23 @@
+// Tests for the operator table (operators.rs); how the compiler parses
+// with it is in core-compiler/src/operators_tests.rs
+
+use crate::operators::{infix, is_operator, Assoc, OPERATORS, PIPE};
+
+#[test]
+fn test_table_is_loosest_first_with_one_function_each() {
+    assert!(OPERATORS.windows(2).all(|pair| pair[0].precedence <= pair[1].precedence));
+    let mut functions: Vec<&str> = OPERATORS.iter().map(|op| op.function).collect();
+    functions.sort();
+    functions.dedup();
+    assert_eq!(functions.len(), OPERATORS.len());
+}
+
+#[test]
+fn test_lookup_by_symbol_and_function() {
+    let add = infix("+").unwrap();
+    assert_eq!((add.function, add.assoc), ("__add__", Assoc::Left));
+    assert_eq!(infix("++").map(|op| op.assoc), Some(Assoc::Right));
+    assert!(is_operator("+") && is_operator("__add__") && is_operator("|>"));
+    // The pipe is rewritten before Core: its function is not an operator's
+    assert!(!is_operator(PIPE) && !is_operator("__not__") && infix("=").is_none());
+}
@@ -0,0 +1,140 @@
+// Interned Core IR variable names
+//
+// Var, Lam and Let name their variable with a Symbol rather than an owned
+// String. Self-hosting bundles use a few thousand distinct names tens of
+// thousands of times each; interned, every distinct name is stored once
+// and a term holds a 4-byte index.
+//
+// The symbol table is process-wide, like the bridge runtime's string and
+// tag tables: a Symbol can be created and read anywhere without threading a
+// table through the lowering, loaders, passes and emitters.
+// Each name's text is leaked once, so `as_str` returns &'static str and
+// reading a name never allocates or copies.
+//
+// Symbols compare and hash by index but order by text, so sorted output
+// does not depend on the order names were first interned in.
+//
+// POLICY: the table only grows. A compiler or bridge run handles one
+// program, so the leaked text is bounded by the distinct names of its
+// input.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+struct SymbolTable {
+    names: Vec<&'static str>,
+    ids: HashMap<&'static str, u32>,
+}
+
+static SYMBOLS: OnceLock<Mutex<SymbolTable>> = OnceLock::new();
+
+fn table() -> std::sync::MutexGuard<'static, SymbolTable> {
+    SYMBOLS.get_or_init(|| Mutex::new(SymbolTable::default())).lock().unwrap()
+}
+
+impl Symbol {
+    pub fn intern(name: &str) -> Symbol {
+        let mut table = table();
+        if let Some(&id) = table.ids.get(name) {
+            return Symbol(id);
+        }
+        let id = u32::try_from(table.names.len()).expect("symbol table overflow");
+        let text: &'static str = Box::leak(name.to_string().into_boxed_str());
+        table.names.push(text);
+        table.ids.insert(text, id);
+        Symbol(id)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        table().names[self.0 as usize]
+    }
+
+    /// Index in the symbol table
+    pub fn id(self) -> u32 {
+        self.0
+    }
+}
+
+/// Distinct names interned so far
+pub fn symbol_count() -> usize {
+    table().names.len()
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Symbol {
+        Symbol::intern(&name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Symbol) -> Ordering {
+        if self.0 == other.0 {
+            Ordering::Equal
+        } else {
+            self.as_str().cmp(other.as_str())
+        }
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Symbol) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
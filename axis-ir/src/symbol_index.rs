@@ -0,0 +1,64 @@
+// Known names, for "did you mean" suggestions on unknown identifiers
+//
+// Holds the names a program can refer to at some point: registry entries,
+// top-level definitions and locals in scope. Lookups rank them by edit
+// distance (Levenshtein, over characters) to the unknown name. The
+// compiler's validator and the bridge's emitters both suggest from one.
+
+use std::collections::BTreeSet;
+
+// Suggestions listed per unknown name
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    names: BTreeSet<String>,
+}
+
+impl SymbolIndex {
+    pub fn extend<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        self.names.extend(names.into_iter().map(str::to_string));
+    }
+
+    /// Known names closest to `name`, best first. A name qualifies within an
+    /// edit distance of a third of its length (at least 1); lowering's own
+    /// `__` names are only offered for names that look like them.
+    pub fn suggest(&self, name: &str) -> Vec<&str> {
+        let limit = (name.chars().count() / 3).max(1);
+        let mut ranked: Vec<(usize, &str)> = self
+            .names
+            .iter()
+            .filter(|known| known.as_str() != name && (!known.starts_with("__") || name.starts_with("__")))
+            .map(|known| (edit_distance(name, known), known.as_str()))
+            .filter(|(distance, _)| *distance <= limit)
+            .collect();
+        ranked.sort();
+        ranked.into_iter().take(MAX_SUGGESTIONS).map(|(_, known)| known).collect()
+    }
+
+    /// "did you mean `a`?" / "did you mean one of `a`, `b`?", if any name is close
+    pub fn did_you_mean(&self, name: &str) -> Option<String> {
+        let suggestions = self.suggest(name);
+        let quoted: Vec<String> = suggestions.iter().map(|s| format!("`{}`", s)).collect();
+        match quoted.len() {
+            0 => None,
+            1 => Some(format!("did you mean {}?", quoted[0])),
+            _ => Some(format!("did you mean one of {}?", quoted.join(", "))),
+        }
+    }
+}
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // previous[j]: distance between the prefix of `a` seen so far and b[..j]
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
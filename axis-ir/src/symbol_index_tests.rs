@@ -0,0 +1,33 @@
+// Tests for "did you mean" suggestions (symbol_index.rs)
+
+use crate::symbol_index::{edit_distance, SymbolIndex};
+
+fn index(names: &[&str]) -> SymbolIndex {
+    let mut index = SymbolIndex::default();
+    index.extend(names.iter().copied());
+    index
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("lst_cons", "list_cons"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("same", "same"), 0);
+    assert_eq!(edit_distance("é", "e"), 1);
+}
+
+#[test]
+fn test_suggestions_are_close_and_ranked() {
+    let symbols = index(&["list_cons", "list_cons2", "list_map", "axis_io_print", "__tuple__"]);
+
+    assert_eq!(symbols.suggest("lst_cons"), vec!["list_cons", "list_cons2"]);
+    assert_eq!(symbols.did_you_mean("axis_io_prnt").as_deref(), Some("did you mean `axis_io_print`?"));
+    assert_eq!(
+        symbols.did_you_mean("lst_cons").as_deref(),
+        Some("did you mean one of `list_cons`, `list_cons2`?")
+    );
+    // Too far off, or only lowering internals nearby
+    assert_eq!(symbols.did_you_mean("frobnicate"), None);
+    assert_eq!(symbols.did_you_mean("tuple"), None);
+}
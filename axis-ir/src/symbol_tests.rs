@@ -0,0 +1,23 @@
+// Tests for interned Core IR variable names (symbol.rs)
+
+use crate::symbol::Symbol;
+
+#[test]
+fn test_intern_is_idempotent() {
+    let a = Symbol::intern("list_cons");
+    let b = Symbol::from(String::from("list_cons"));
+    assert_eq!(a, b);
+    assert_eq!(a.id(), b.id());
+    assert_eq!(a.as_str(), "list_cons");
+    assert_ne!(a, Symbol::intern("list_nil"));
+}
+
+#[test]
+fn test_symbols_order_by_text() {
+    // Interned out of order: comparison must not follow the table index
+    let z = Symbol::intern("symbol_tests_z");
+    let a = Symbol::intern("symbol_tests_a");
+    let mut names = vec![z, a];
+    names.sort();
+    assert_eq!(names, ["symbol_tests_a", "symbol_tests_z"].map(Symbol::intern));
+}
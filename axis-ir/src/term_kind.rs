@@ -0,0 +1,77 @@
+// The kinds of Core IR term and how tools name them
+//
+// The compiler's CoreTerm and the bridge's arena views have the same
+// fourteen kinds. Node counts (--stats, `inspect --deep`), term paths and
+// the graph of --view-core-ir name a kind as kind_name() does, and a
+// node's one-line label is its kind followed by the literal, bound name
+// or index it carries (label()).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TermKind {
+    IntLit,
+    BoolLit,
+    UnitLit,
+    StrLit,
+    Var,
+    Lam,
+    App,
+    Tuple,
+    Proj,
+    CtorField,
+    Let,
+    If,
+    Ctor,
+    Match,
+}
+
+impl TermKind {
+    pub const ALL: [TermKind; 14] = [
+        TermKind::IntLit,
+        TermKind::BoolLit,
+        TermKind::UnitLit,
+        TermKind::StrLit,
+        TermKind::Var,
+        TermKind::Lam,
+        TermKind::App,
+        TermKind::Tuple,
+        TermKind::Proj,
+        TermKind::CtorField,
+        TermKind::Let,
+        TermKind::If,
+        TermKind::Ctor,
+        TermKind::Match,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TermKind::IntLit => "literal.int",
+            TermKind::BoolLit => "literal.bool",
+            TermKind::UnitLit => "literal.unit",
+            TermKind::StrLit => "literal.str",
+            TermKind::Var => "var",
+            TermKind::Lam => "lam",
+            TermKind::App => "app",
+            TermKind::Tuple => "tuple",
+            TermKind::Proj => "proj",
+            TermKind::CtorField => "ctor_field",
+            TermKind::Let => "let",
+            TermKind::If => "if",
+            TermKind::Ctor => "ctor",
+            TermKind::Match => "match",
+        }
+    }
+
+    /// The node's line: its kind, then `detail` (the literal, bound name
+    /// or index), which a string literal gets quoted and an index marked
+    pub fn label(self, detail: Option<&str>) -> String {
+        let Some(detail) = detail else {
+            return self.name().to_string();
+        };
+        match self {
+            TermKind::StrLit => format!("{} {:?}", self.name(), detail),
+            TermKind::Proj => format!("{} .{}", self.name(), detail),
+            TermKind::CtorField => format!("{} [{}]", self.name(), detail),
+            _ => format!("{} {}", self.name(), detail),
+        }
+    }
+}
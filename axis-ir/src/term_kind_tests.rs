@@ -0,0 +1,21 @@
+// Tests for Core term kinds (term_kind.rs)
+
+use crate::term_kind::TermKind;
+
+#[test]
+fn test_every_kind_has_its_own_name() {
+    let mut names: Vec<&str> = TermKind::ALL.iter().map(|kind| kind.name()).collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), TermKind::ALL.len());
+}
+
+#[test]
+fn test_labels() {
+    assert_eq!(TermKind::IntLit.label(Some("42")), "literal.int 42");
+    assert_eq!(TermKind::StrLit.label(Some("a \"b\"")), "literal.str \"a \\\"b\\\"\"");
+    assert_eq!(TermKind::Let.label(Some("x")), "let x");
+    assert_eq!(TermKind::Proj.label(Some("1")), "proj .1");
+    assert_eq!(TermKind::CtorField.label(Some("0")), "ctor_field [0]");
+    assert_eq!(TermKind::App.label(None), "app");
+}
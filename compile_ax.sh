@@ -196,7 +196,8 @@ if [[ ${#SOURCE_ABS_LIST[@]} -eq 1 ]]; then
 else
     echo "Step 2/3: Compiling ${#SOURCE_ABS_LIST[@]} source files to Core IR..."
 fi
-if ! "$COMPILER_BIN" --sources "${SOURCE_ABS_LIST[@]}" --registries "${REGISTRY_ARGS[@]}" --out "$COREIR_FILE"; then
+# The intermediate bundle is rebuilt on every run
+if ! "$COMPILER_BIN" --sources "${SOURCE_ABS_LIST[@]}" --registries "${REGISTRY_ARGS[@]}" --out "$COREIR_FILE" --force; then
     echo "Error: Compilation to Core IR failed"
     exit 1
 fi
@@ -4,4 +4,15 @@ fn main() {
         .file("../axis_core_ir_0_1.capnp")
         .run()
         .expect("schema compiler");
+
+    // Commit recorded in bundle metadata (bundle_metadata.rs); an AXIS_COMMIT
+    // set by the caller wins, outside a git checkout there is none. No
+    // rerun-if directives: cargo reruns this on any change to the package.
+    let commit = std::env::var("AXIS_COMMIT").ok().or_else(|| {
+        let output = std::process::Command::new("git").args(["rev-parse", "--short=12", "HEAD"]).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=AXIS_COMMIT={}", commit);
+    }
 }
@@ -0,0 +1,10 @@
+// cargo fuzz run fuzz_parse
+// Drives tokenize_with_location + parse_module_with_file with arbitrary input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    axis_compiler::fuzz_parse(data);
+});
@@ -0,0 +1,296 @@
+// `--dump-ast`: snapshot of the parsed surface Module before lowering
+//
+// Two renderings of the same tree, both deterministic so they can be
+// golden-tested:
+//   text - one node per line, children indented two spaces
+//   json - pretty-printed JSON, one object per node tagged with "kind"
+//
+// The parser caps expression nesting (MAX_NESTING_DEPTH), so the recursive
+// renderers here are bounded.
+
+use crate::diagnostics::json_string;
+use crate::surface_parser::{Attribute, FnDef, ForeignFnDef, Import, Module, SurfaceExpr, SurfaceStmt, TypeDef};
+
+// ============================================================================
+// Text
+// ============================================================================
+
+pub fn dump_text(module: &Module) -> String {
+    let mut out = String::new();
+    for import in &module.imports {
+        match import.module.as_str() {
+            "" => out.push_str(&format!("use {}\n", import.target())),
+            scope => out.push_str(&format!("use {} (in {})\n", import.target(), scope)),
+        }
+    }
+    for type_def in &module.types {
+        out.push_str(&format!("type {}{}\n", type_def.name, type_params(type_def)));
+        for variant in &type_def.variants {
+            line(&mut out, 1, &format!("{}({})", variant.name, variant.fields.join(", ")));
+        }
+    }
+    for foreign in &module.foreign_functions {
+        out.push_str(&format!("foreign fn {}({})\n", foreign.name, foreign.params.join(", ")));
+    }
+    for func in &module.functions {
+        for attr in &func.attributes {
+            out.push_str(&format_attribute(attr));
+            out.push('\n');
+        }
+        let visibility = if func.public { "pub " } else { "" };
+        out.push_str(&format!("{}fn {}({})\n", visibility, func.name, func.params.join(", ")));
+        text_expr(&func.body, 1, &mut out);
+    }
+    out
+}
+
+fn type_params(type_def: &TypeDef) -> String {
+    if type_def.params.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", type_def.params.join(", "))
+    }
+}
+
+fn format_attribute(attr: &Attribute) -> String {
+    if attr.args.is_empty() {
+        format!("@{}", attr.name)
+    } else {
+        format!("@{}({})", attr.name, attr.args.join(", "))
+    }
+}
+
+fn line(out: &mut String, indent: usize, text: &str) {
+    out.push_str(&"  ".repeat(indent));
+    out.push_str(text);
+    out.push('\n');
+}
+
+fn text_expr(expr: &SurfaceExpr, indent: usize, out: &mut String) {
+    match expr {
+        SurfaceExpr::IntLit(n) => line(out, indent, &format!("int {}", n)),
+        SurfaceExpr::BoolLit(b) => line(out, indent, &format!("bool {}", b)),
+        SurfaceExpr::StringLit(s) => line(out, indent, &format!("str {:?}", s)),
+        SurfaceExpr::UnitLit => line(out, indent, "unit"),
+        SurfaceExpr::Ident(name) => line(out, indent, &format!("ident {}", name)),
+        SurfaceExpr::Proj(inner, index) => {
+            line(out, indent, &format!("proj {}", index));
+            text_expr(inner, indent + 1, out);
+        }
+        SurfaceExpr::Call(name, args) => {
+            line(out, indent, &format!("call {}", name));
+            for arg in args {
+                text_expr(arg, indent + 1, out);
+            }
+        }
+        SurfaceExpr::Block(stmts) => {
+            line(out, indent, "block");
+            for stmt in stmts {
+                text_stmt(stmt, indent + 1, out);
+            }
+        }
+        SurfaceExpr::Match(scrutinee, arms) => {
+            line(out, indent, "match");
+            text_expr(scrutinee, indent + 1, out);
+            for arm in arms {
+                line(out, indent + 1, &format!("arm {}", arm.pattern));
+                text_expr(&arm.expr, indent + 2, out);
+            }
+        }
+        SurfaceExpr::If { cond, then_branch, else_branch } => {
+            line(out, indent, "if");
+            text_expr(cond, indent + 1, out);
+            line(out, indent, "then");
+            text_expr(then_branch, indent + 1, out);
+            line(out, indent, "else");
+            text_expr(else_branch, indent + 1, out);
+        }
+    }
+}
+
+fn text_stmt(stmt: &SurfaceStmt, indent: usize, out: &mut String) {
+    match stmt {
+        SurfaceStmt::Let(name, expr) => {
+            line(out, indent, &format!("let {}", name));
+            text_expr(expr, indent + 1, out);
+        }
+        SurfaceStmt::LetPattern(pattern, expr, else_expr) => {
+            line(out, indent, &format!("let {}", pattern));
+            text_expr(expr, indent + 1, out);
+            if let Some(else_expr) = else_expr {
+                line(out, indent, "else");
+                text_expr(else_expr, indent + 1, out);
+            }
+        }
+        SurfaceStmt::Expr(expr) => text_expr(expr, indent, out),
+    }
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+enum Json {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(&'static str, Json)>),
+}
+
+fn str_array(items: &[String]) -> Json {
+    Json::Arr(items.iter().map(|s| Json::Str(s.clone())).collect())
+}
+
+pub fn dump_json(module: &Module) -> String {
+    let doc = Json::Obj(vec![
+        ("functions", Json::Arr(module.functions.iter().map(json_fn).collect())),
+        ("foreign_functions", Json::Arr(module.foreign_functions.iter().map(json_foreign).collect())),
+        ("types", Json::Arr(module.types.iter().map(json_type).collect())),
+        ("imports", Json::Arr(module.imports.iter().map(json_import).collect())),
+    ]);
+    let mut out = String::new();
+    render(&doc, 0, &mut out);
+    out.push('\n');
+    out
+}
+
+fn json_fn(func: &FnDef) -> Json {
+    let attributes = func
+        .attributes
+        .iter()
+        .map(|attr| Json::Obj(vec![("name", Json::Str(attr.name.clone())), ("args", str_array(&attr.args))]))
+        .collect();
+    Json::Obj(vec![
+        ("name", Json::Str(func.name.clone())),
+        ("params", str_array(&func.params)),
+        ("attributes", Json::Arr(attributes)),
+        ("public", Json::Bool(func.public)),
+        ("body", json_expr(&func.body)),
+    ])
+}
+
+fn json_import(import: &Import) -> Json {
+    Json::Obj(vec![("module", Json::Str(import.module.clone())), ("path", str_array(&import.path))])
+}
+
+fn json_foreign(foreign: &ForeignFnDef) -> Json {
+    Json::Obj(vec![
+        ("name", Json::Str(foreign.name.clone())),
+        ("params", str_array(&foreign.params)),
+    ])
+}
+
+fn json_type(type_def: &TypeDef) -> Json {
+    let variants = type_def
+        .variants
+        .iter()
+        .map(|variant| Json::Obj(vec![("name", Json::Str(variant.name.clone())), ("fields", str_array(&variant.fields))]))
+        .collect();
+    Json::Obj(vec![
+        ("name", Json::Str(type_def.name.clone())),
+        ("params", str_array(&type_def.params)),
+        ("variants", Json::Arr(variants)),
+    ])
+}
+
+fn node(kind: &str, mut fields: Vec<(&'static str, Json)>) -> Json {
+    fields.insert(0, ("kind", Json::Str(kind.to_string())));
+    Json::Obj(fields)
+}
+
+fn json_expr(expr: &SurfaceExpr) -> Json {
+    match expr {
+        SurfaceExpr::IntLit(n) => node("int", vec![("value", Json::Int(*n))]),
+        SurfaceExpr::BoolLit(b) => node("bool", vec![("value", Json::Bool(*b))]),
+        SurfaceExpr::StringLit(s) => node("str", vec![("value", Json::Str(s.clone()))]),
+        SurfaceExpr::UnitLit => node("unit", vec![]),
+        SurfaceExpr::Ident(name) => node("ident", vec![("name", Json::Str(name.clone()))]),
+        SurfaceExpr::Proj(inner, index) => node(
+            "proj",
+            vec![("expr", json_expr(inner)), ("index", Json::Int(*index))],
+        ),
+        SurfaceExpr::Call(name, args) => node(
+            "call",
+            vec![
+                ("name", Json::Str(name.clone())),
+                ("args", Json::Arr(args.iter().map(json_expr).collect())),
+            ],
+        ),
+        SurfaceExpr::Block(stmts) => node(
+            "block",
+            vec![("stmts", Json::Arr(stmts.iter().map(json_stmt).collect()))],
+        ),
+        SurfaceExpr::Match(scrutinee, arms) => {
+            let arms = arms
+                .iter()
+                .map(|arm| {
+                    Json::Obj(vec![
+                        ("pattern", Json::Str(arm.pattern.clone())),
+                        ("expr", json_expr(&arm.expr)),
+                    ])
+                })
+                .collect();
+            node("match", vec![("scrutinee", json_expr(scrutinee)), ("arms", Json::Arr(arms))])
+        }
+        SurfaceExpr::If { cond, then_branch, else_branch } => node(
+            "if",
+            vec![
+                ("cond", json_expr(cond)),
+                ("then", json_expr(then_branch)),
+                ("else", json_expr(else_branch)),
+            ],
+        ),
+    }
+}
+
+fn json_stmt(stmt: &SurfaceStmt) -> Json {
+    match stmt {
+        SurfaceStmt::Let(name, expr) => node(
+            "let",
+            vec![("name", Json::Str(name.clone())), ("expr", json_expr(expr))],
+        ),
+        SurfaceStmt::LetPattern(pattern, expr, else_expr) => {
+            let mut fields = vec![("pattern", Json::Str(pattern.clone())), ("expr", json_expr(expr))];
+            if let Some(else_expr) = else_expr {
+                fields.push(("else", json_expr(else_expr)));
+            }
+            node("let_pattern", fields)
+        }
+        SurfaceStmt::Expr(expr) => node("expr", vec![("expr", json_expr(expr))]),
+    }
+}
+
+// Two-space indentation; empty arrays and objects stay on one line
+fn render(value: &Json, indent: usize, out: &mut String) {
+    let pad = "  ".repeat(indent + 1);
+    match value {
+        Json::Int(n) => out.push_str(&n.to_string()),
+        Json::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Json::Str(s) => out.push_str(&json_string(s)),
+        Json::Arr(items) if items.is_empty() => out.push_str("[]"),
+        Json::Arr(items) => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(&pad);
+                render(item, indent + 1, out);
+                out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&"  ".repeat(indent));
+            out.push(']');
+        }
+        Json::Obj(fields) if fields.is_empty() => out.push_str("{}"),
+        Json::Obj(fields) => {
+            out.push_str("{\n");
+            for (i, (key, field)) in fields.iter().enumerate() {
+                out.push_str(&pad);
+                out.push_str(&json_string(key));
+                out.push_str(": ");
+                render(field, indent + 1, out);
+                out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&"  ".repeat(indent));
+            out.push('}');
+        }
+    }
+}
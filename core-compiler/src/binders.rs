@@ -0,0 +1,311 @@
+// Binder analysis over Core IR: let bindings nothing uses (AX1504),
+// parameters nothing uses (AX1505) and bindings that hide another local of
+// the same function (AX1506), all warnings
+//
+//   fn area(w, h) {            // AX1505: h
+//       let w = w * 2;         // AX1506: w hides the parameter
+//       let scaled = w * 3;    // AX1504: scaled
+//       w
+//   }
+//
+// Each top-level function is checked on its own; its parameters are the
+// Lams at the head of its value. Names starting with `_` are exempt: that is
+// how a program marks a binding unused on purpose, and the names lowering
+// makes up (_unit, _discard) start with it. Match pattern variables can
+// hide and be hidden but are not reported unused.
+//
+// Core IR built from source has no spans, so a warning is placed by
+// counting: the nth binding of `x` in a function, in source order, is the
+// nth binding site of `x` in its definition (surface_parser::binding_sites).
+
+use crate::core_validator::ValidationError;
+use crate::diagnostics::SourceMap;
+use crate::error_codes::ErrorCode;
+use crate::runtime::{CoreTerm, Pattern, Span};
+use crate::surface_parser;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Parameter,
+    Let,
+    Pattern,
+}
+
+impl Kind {
+    fn describe(self) -> &'static str {
+        match self {
+            Kind::Parameter => "parameter",
+            Kind::Let => "let binding",
+            Kind::Pattern => "pattern variable",
+        }
+    }
+}
+
+// A binding in scope; `ordinal` counts the function's earlier bindings of
+// the same name
+struct Binder {
+    name: String,
+    kind: Kind,
+    ordinal: usize,
+    used: bool,
+    // The entry function's parameters are passed by the runtime
+    required: bool,
+}
+
+struct Finding {
+    code: ErrorCode,
+    function: String,
+    name: String,
+    ordinal: usize,
+    // For AX1506: the hidden binding's kind and ordinal
+    hidden: Option<(Kind, usize)>,
+}
+
+#[derive(Default)]
+struct Walker {
+    function: String,
+    scope: Vec<Binder>,
+    counts: HashMap<String, usize>,
+    findings: Vec<Finding>,
+}
+
+/// AX1504-AX1506 for the top-level functions of `term` not in `skip` (the
+/// prelude's). `source` is the surface text and file the term was lowered
+/// from, to place the warnings; `entry` names the entry function.
+pub fn binding_warnings(
+    term: &CoreTerm,
+    skip: &HashSet<String>,
+    entry: &str,
+    source: Option<(&str, &str)>,
+    sources: &SourceMap,
+) -> Vec<ValidationError> {
+    let mut findings = Vec::new();
+    let mut current = term;
+    while let CoreTerm::Let(name, value, body, _) = current {
+        let name = name.to_string();
+        if !skip.contains(&name) && matches!(value.as_ref(), CoreTerm::Lam(..)) {
+            let mut walker = Walker { function: name.clone(), ..Default::default() };
+            walker.function(value, entry == name);
+            findings.append(&mut walker.findings);
+        }
+        current = body;
+    }
+
+    let locator = Locator::new(term, skip, source);
+    let mut warnings: Vec<((usize, usize), ValidationError)> = findings
+        .iter()
+        .map(|finding| {
+            let site = locator.site(&finding.function, &finding.name, finding.ordinal);
+            (site.unwrap_or((usize::MAX, 0)), warning(finding, &locator, sources))
+        })
+        .collect();
+    warnings.sort_by_key(|(site, _)| *site);
+    warnings.into_iter().map(|(_, warning)| warning).collect()
+}
+
+// Binding sites in the surface source, when there is one
+struct Locator<'a> {
+    // Text and file name
+    source: Option<(&'a str, &'a str)>,
+    // The `fn NAME` site of each function
+    definitions: HashMap<String, (usize, usize)>,
+}
+
+impl<'a> Locator<'a> {
+    fn new(term: &CoreTerm, skip: &HashSet<String>, source: Option<(&'a str, &'a str)>) -> Self {
+        let mut definitions = HashMap::new();
+        if let Some((text, _)) = source {
+            // Which `fn NAME` each function is, among those with its last segment
+            let mut seen: HashMap<String, usize> = HashMap::new();
+            let mut current = term;
+            while let CoreTerm::Let(name, value, body, _) = current {
+                let name = name.to_string();
+                if !skip.contains(&name) && matches!(value.as_ref(), CoreTerm::Lam(..)) {
+                    let short = name.rsplit('.').next().unwrap_or(&name).to_string();
+                    let index = seen.entry(short.clone()).or_default();
+                    if let Some(&site) = surface_parser::function_definitions(text, &short).get(*index) {
+                        definitions.insert(name, site);
+                    }
+                    *index += 1;
+                }
+                current = body;
+            }
+        }
+        Locator { source, definitions }
+    }
+
+    // Line and column of the `ordinal`th binding of `name` in `function`
+    fn site(&self, function: &str, name: &str, ordinal: usize) -> Option<(usize, usize)> {
+        let (text, _) = self.source?;
+        let site = *self.definitions.get(function)?;
+        surface_parser::binding_sites(text, site, name).get(ordinal).copied()
+    }
+
+    fn span(&self, function: &str, name: &str, ordinal: usize) -> Option<Span> {
+        let (_, file) = self.source?;
+        let (line, column) = self.site(function, name, ordinal)?;
+        Some(Span { file: file.to_string(), line, column })
+    }
+}
+
+fn warning(finding: &Finding, locator: &Locator, sources: &SourceMap) -> ValidationError {
+    let name = &finding.name;
+    let message = match finding.code {
+        ErrorCode::UnusedParameter => format!("parameter `{}` of `{}` is never used", name, finding.function),
+        ErrorCode::UnusedBinding => format!("`{}` is bound but never used", name),
+        _ => {
+            let kind = finding.hidden.map_or("binding", |(kind, _)| kind.describe());
+            format!("`{}` hides an earlier {} of the same name", name, kind)
+        }
+    };
+    let mut warning = ValidationError::new(format!("{}: {}", finding.code, message));
+    warning.name = Some(name.clone());
+    warning.span = locator.span(&finding.function, name, finding.ordinal);
+    match finding.code {
+        ErrorCode::UnusedParameter => {
+            warning.help.push(format!("if the function does not need it, name it `_` or `_{}`", name));
+        }
+        ErrorCode::UnusedBinding => {
+            warning.help.push(format!("remove it, or write `let _ = ...;` (or `_{}`) to keep only its effects", name));
+        }
+        _ => {
+            let hidden = finding.hidden.and_then(|(_, ordinal)| locator.span(&finding.function, name, ordinal));
+            let earlier = match hidden {
+                Some(span) => format!("the earlier `{}` is bound at {}; ", name, sources.location(&span.file, span.line, span.column)),
+                None => String::new(),
+            };
+            warning.help.push(format!("{}rename one of them if hiding it is unintended", earlier));
+        }
+    }
+    warning
+}
+
+impl Walker {
+    // A top-level function: its leading Lams are its parameters
+    fn function(&mut self, value: &CoreTerm, entry: bool) {
+        let mut params = 0;
+        let mut current = value;
+        while let CoreTerm::Lam(param, body, _) = current {
+            self.enter(&param.to_string(), Kind::Parameter, entry);
+            params += 1;
+            current = body;
+        }
+        self.term(current);
+        for _ in 0..params {
+            self.exit();
+        }
+    }
+
+    fn term(&mut self, term: &CoreTerm) {
+        match term {
+            CoreTerm::Var(name, _) => {
+                let name = name.to_string();
+                if let Some(binder) = self.scope.iter_mut().rev().find(|binder| binder.name == name) {
+                    binder.used = true;
+                }
+            }
+            CoreTerm::Lam(param, body, _) => {
+                self.enter(&param.to_string(), Kind::Parameter, false);
+                self.term(body);
+                self.exit();
+            }
+            CoreTerm::Let(name, value, body, _) => {
+                // `let x = value`: x comes before the bindings in value
+                let ordinal = self.number(&name.to_string());
+                self.term(value);
+                self.push(&name.to_string(), Kind::Let, ordinal, false);
+                self.term(body);
+                self.exit();
+            }
+            CoreTerm::Match(scrutinee, arms, _) => {
+                self.term(scrutinee);
+                for (pattern, body) in arms {
+                    let mut variables = Vec::new();
+                    pattern_variables(pattern, &mut variables);
+                    for variable in &variables {
+                        self.enter(variable, Kind::Pattern, false);
+                    }
+                    self.term(body);
+                    for _ in &variables {
+                        self.exit();
+                    }
+                }
+            }
+            CoreTerm::App(function, argument, _) => {
+                self.term(function);
+                self.term(argument);
+            }
+            CoreTerm::If(condition, then_branch, else_branch, _) => {
+                self.term(condition);
+                self.term(then_branch);
+                self.term(else_branch);
+            }
+            CoreTerm::Tuple(items, _) | CoreTerm::Ctor(_, items, _) => items.iter().for_each(|item| self.term(item)),
+            CoreTerm::Proj(inner, _, _) | CoreTerm::CtorField(inner, _, _) => self.term(inner),
+            CoreTerm::IntLit(..) | CoreTerm::BoolLit(..) | CoreTerm::UnitLit(_) | CoreTerm::StrLit(..) => {}
+        }
+    }
+
+    fn number(&mut self, name: &str) -> usize {
+        let count = self.counts.entry(name.to_string()).or_default();
+        *count += 1;
+        *count - 1
+    }
+
+    fn enter(&mut self, name: &str, kind: Kind, required: bool) {
+        let ordinal = self.number(name);
+        self.push(name, kind, ordinal, required);
+    }
+
+    fn push(&mut self, name: &str, kind: Kind, ordinal: usize, required: bool) {
+        if !name.starts_with('_')
+            && let Some(hidden) = self.scope.iter().rev().find(|binder| binder.name == name)
+        {
+            self.findings.push(Finding {
+                code: ErrorCode::ShadowedBinding,
+                function: self.function.clone(),
+                name: name.to_string(),
+                ordinal,
+                hidden: Some((hidden.kind, hidden.ordinal)),
+            });
+        }
+        self.scope.push(Binder { name: name.to_string(), kind, ordinal, used: false, required });
+    }
+
+    fn exit(&mut self) {
+        let Some(binder) = self.scope.pop() else {
+            return;
+        };
+        if binder.used || binder.required || binder.name.starts_with('_') {
+            return;
+        }
+        let code = match binder.kind {
+            Kind::Parameter => ErrorCode::UnusedParameter,
+            Kind::Let => ErrorCode::UnusedBinding,
+            Kind::Pattern => return,
+        };
+        self.findings.push(Finding {
+            code,
+            function: self.function.clone(),
+            name: binder.name,
+            ordinal: binder.ordinal,
+            hidden: None,
+        });
+    }
+}
+
+// The variables a pattern binds, left to right
+fn pattern_variables(pattern: &Pattern, variables: &mut Vec<String>) {
+    match pattern {
+        Pattern::PVar(name) => variables.push(name.clone()),
+        Pattern::PTuple(items) | Pattern::PEnum(_, items) => {
+            items.iter().for_each(|item| pattern_variables(item, variables));
+        }
+        Pattern::PBind(name, inner) => {
+            variables.push(name.clone());
+            pattern_variables(inner, variables);
+        }
+        Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit => {}
+    }
+}
@@ -0,0 +1,95 @@
+// Tests for the unused and shadowed binding warnings (binders.rs)
+
+use crate::binders::binding_warnings;
+use crate::core_validator::ValidationError;
+use crate::diagnostics::SourceMap;
+use crate::error_codes::ErrorCode;
+use crate::runtime::CoreTerm;
+use crate::{surface_lower, surface_parser, surface_to_core};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+fn warnings(source: &str) -> Vec<ValidationError> {
+    crate::init_string_table();
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let mut sources = SourceMap::default();
+    sources.add_file("main.ax", source);
+    binding_warnings(&term, &HashSet::new(), "axis_entry", Some((source, "main.ax")), &sources)
+}
+
+fn summary(warnings: &[ValidationError]) -> Vec<(String, usize, usize)> {
+    warnings
+        .iter()
+        .map(|warning| {
+            let span = warning.span.clone().expect("every warning is located");
+            (warning.message.clone(), span.line, span.column)
+        })
+        .collect()
+}
+
+#[test]
+fn test_unused_bindings_and_parameters() {
+    let source = "\
+fn area(w, h) {
+    let scaled = w * 2;
+    let _ignored = w;
+    w
+}
+fn axis_entry(args) { area(1, 2) }
+";
+    let warnings = warnings(source);
+    assert_eq!(
+        summary(&warnings),
+        [
+            ("AX1505: parameter `h` of `area` is never used".to_string(), 1, 12),
+            ("AX1504: `scaled` is bound but never used".to_string(), 2, 9),
+        ]
+    );
+    assert_eq!(warnings[0].help, ["if the function does not need it, name it `_` or `_h`"]);
+    let diagnostic = warnings[1].to_diagnostic();
+    assert_eq!(diagnostic.code, Some(ErrorCode::UnusedBinding));
+    assert_eq!(diagnostic.severity, crate::diagnostics::Severity::Warning);
+}
+
+#[test]
+fn test_shadowing_points_at_both_bindings() {
+    let source = "\
+fn total(xs, n) {
+    let n = n + 1;
+    match xs {
+        Cons(n, rest) => n + list_sum(rest),
+        Nil => n,
+    }
+}
+";
+    let warnings = warnings(source);
+    assert_eq!(
+        summary(&warnings),
+        [
+            ("AX1506: `n` hides an earlier parameter of the same name".to_string(), 2, 9),
+            ("AX1506: `n` hides an earlier let binding of the same name".to_string(), 4, 14),
+        ]
+    );
+    assert_eq!(warnings[1].help, ["the earlier `n` is bound at main.ax:2:9; rename one of them if hiding it is unintended"]);
+}
+
+#[test]
+fn test_entry_parameters_and_skipped_functions_are_exempt() {
+    assert!(warnings("fn main(args) { 0 }\nfn axis_entry(args) { 1 }\n")
+        .iter()
+        .all(|warning| warning.message.contains("`main`")));
+
+    // Core IR without a source: reported without a span
+    let unused = CoreTerm::Let(
+        "f".into(),
+        Rc::new(CoreTerm::Lam("x".into(), Rc::new(CoreTerm::IntLit(1, None)), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None,
+    );
+    let found = binding_warnings(&unused, &HashSet::new(), "axis_entry", None, &SourceMap::default());
+    assert_eq!(found.len(), 1);
+    assert!(found[0].span.is_none());
+    let skip = HashSet::from(["f".to_string()]);
+    assert!(binding_warnings(&unused, &skip, "axis_entry", None, &SourceMap::default()).is_empty());
+}
@@ -0,0 +1,85 @@
+// Provenance of a compiled bundle (CoreBundle.metadata)
+//
+// Records which compiler wrote the bundle (version, and the commit it was
+// built from when known), the inputs with a digest of their contents, and
+// the flags that shape the output. `--view-core-ir` and
+// `axis-rust-bridge inspect` print it, so a self-hosted compiler can be
+// traced back through the generations that built it.
+//
+// Paths are recorded as given on the command line and flags in a fixed
+// order, so the same build from the same directory writes the same
+// metadata. Digests are FNV-1a over the file contents: stable across Rust
+// releases and enough to tell inputs apart, not a security property.
+
+use std::fs;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileDigest {
+    pub path: String,
+    // "fnv1a:" and 16 hex digits
+    pub digest: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BundleMetadata {
+    pub compiler_version: String,
+    // "" when the compiler was built outside a git checkout
+    pub compiler_commit: String,
+    // Source files; for `link`, the input bundles
+    pub sources: Vec<FileDigest>,
+    pub registries: Vec<FileDigest>,
+    pub flags: Vec<String>,
+}
+
+impl FileDigest {
+    pub fn of(path: &str, contents: &[u8]) -> Self {
+        FileDigest { path: path.to_string(), digest: digest(contents) }
+    }
+
+    pub fn of_file(path: &str) -> Result<Self, String> {
+        let contents = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Ok(FileDigest::of(path, &contents))
+    }
+}
+
+impl BundleMetadata {
+    /// Metadata naming this compiler; the caller adds inputs and flags
+    pub fn current() -> Self {
+        BundleMetadata {
+            compiler_version: format!("axis-compiler {}", env!("CARGO_PKG_VERSION")),
+            compiler_commit: option_env!("AXIS_COMMIT").unwrap_or("").to_string(),
+            ..BundleMetadata::default()
+        }
+    }
+
+    /// Bundles written before metadata existed have none
+    pub fn is_empty(&self) -> bool {
+        *self == BundleMetadata::default()
+    }
+
+    /// The `Metadata:` section of `--view-core-ir`
+    pub fn format(&self) -> String {
+        let mut out = String::from("Metadata:\n");
+        let commit = if self.compiler_commit.is_empty() { String::new() } else { format!(" ({})", self.compiler_commit) };
+        out.push_str(&format!("  compiler: {}{}\n", self.compiler_version, commit));
+        for (label, files) in [("source", &self.sources), ("registry", &self.registries)] {
+            for file in files {
+                out.push_str(&format!("  {}: {} {}\n", label, file.path, file.digest));
+            }
+        }
+        if !self.flags.is_empty() {
+            out.push_str(&format!("  flags: {}\n", self.flags.join(" ")));
+        }
+        out
+    }
+}
+
+// FNV-1a, as axis-rust-bridge hashes its build artifacts
+pub fn digest(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("fnv1a:{:016x}", hash)
+}
@@ -0,0 +1,46 @@
+// Tests for bundle provenance metadata (bundle_metadata.rs)
+
+use crate::bundle_metadata::{digest, BundleMetadata, FileDigest};
+
+#[test]
+fn test_digest_is_fnv1a_of_the_contents() {
+    // FNV-1a test vectors
+    assert_eq!(digest(b""), "fnv1a:cbf29ce484222325");
+    assert_eq!(digest(b"a"), "fnv1a:af63dc4c8601ec8c");
+    assert_ne!(digest(b"fn main() { 1 }"), digest(b"fn main() { 2 }"));
+}
+
+#[test]
+fn test_current_names_this_compiler() {
+    let metadata = BundleMetadata::current();
+    assert_eq!(metadata.compiler_version, format!("axis-compiler {}", env!("CARGO_PKG_VERSION")));
+    assert!(!metadata.is_empty());
+    assert!(BundleMetadata::default().is_empty());
+}
+
+#[test]
+fn test_format_lists_inputs_and_flags() {
+    let metadata = BundleMetadata {
+        compiler_version: "axis-compiler 0.1.0".to_string(),
+        compiler_commit: "8e2c80a1b2c3".to_string(),
+        sources: vec![FileDigest::of("main.ax", b"a")],
+        registries: vec![FileDigest::of("axis.axreg", b"")],
+        flags: vec!["--no-prelude".to_string(), "--allow=io".to_string()],
+    };
+    assert_eq!(
+        metadata.format(),
+        "Metadata:\n  compiler: axis-compiler 0.1.0 (8e2c80a1b2c3)\n  source: main.ax fnv1a:af63dc4c8601ec8c\n  registry: axis.axreg fnv1a:cbf29ce484222325\n  flags: --no-prelude --allow=io\n"
+    );
+}
+
+#[test]
+fn test_format_leaves_out_an_unknown_commit() {
+    let metadata = BundleMetadata { compiler_commit: String::new(), ..BundleMetadata::current() };
+    assert_eq!(metadata.format(), format!("Metadata:\n  compiler: axis-compiler {}\n", env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn test_of_file_reports_a_missing_file() {
+    let error = FileDigest::of_file("/nonexistent/axis.axreg").unwrap_err();
+    assert!(error.starts_with("Failed to read /nonexistent/axis.axreg"), "{}", error);
+}
@@ -0,0 +1,273 @@
+// Program-level constructor table, from `type` declarations
+//
+// Every variant of a declared type is a Constructor (name, arity, owning
+// type); a record type has one constructor named after the type. A type
+// declared in a `module` block is named by its path (`geo.Shape`), as the
+// block's functions are. The table is written into the bundle
+// (CoreBundle.constructors) and merged by `axis-compiler link`;
+// core_validator::check_constructors checks Ctor terms and match patterns
+// against it.
+//
+// Core refers to a constructor by its bare name (`Some`) or qualified by
+// its type (`Option::Some`, `Option.Some`, `geo.Shape::Circle`). Two types
+// may declare constructors of the same name: qualify() rewrites every
+// reference to a declared constructor to its canonical name, the type's
+// path and the constructor (`geo.Shape.Circle`), and the backends intern
+// that name as its tag, so `A::Ok` and `B::Ok` stay different values. A
+// bare name more than one type declares means the one declared in the
+// module of the function using it, and is AX1019 anywhere else.
+//
+// POLICY: only declared constructors are checked. Undeclared ones (the
+// prelude's Cons, Nil, Some, Ok, ...) are accepted as before.
+
+use crate::core_validator::ValidationError;
+use crate::error_codes::ErrorCode;
+use crate::runtime::{CoreTerm, Pattern, Span};
+use crate::surface_parser::TypeDef;
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constructor {
+    pub name: String,
+    pub arity: u32,
+    pub type_name: String,
+}
+
+impl Constructor {
+    /// The name qualify() gives every reference to it and the backends
+    /// intern as its tag: `geo.Shape.Circle`
+    pub fn canonical_name(&self) -> String {
+        format!("{}.{}", self.type_name, self.name)
+    }
+
+    // The module block the type is declared in, "" at top level
+    fn module(&self) -> &str {
+        self.type_name.rsplit_once('.').map_or("", |(module, _)| module)
+    }
+}
+
+/// The prelude's Option, Result and List: undeclared (POLICY above), but
+/// known to have more than one variant
+pub const PRELUDE_VARIANTS: &[(&str, &str)] =
+    &[("Some", "Option"), ("None", "Option"), ("Ok", "Result"), ("Err", "Result"), ("Cons", "List"), ("Nil", "List")];
+
+#[derive(Clone, Debug, Default)]
+pub struct ConstructorTable {
+    // Declaration order, which is also bundle order
+    constructors: Vec<Constructor>,
+}
+
+// `Option::Some` / `Option.Some` -> (Some("Option"), "Some")
+pub fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    if let Some(idx) = name.rfind("::") {
+        (Some(&name[..idx]), &name[idx + 2..])
+    } else if let Some(idx) = name.rfind('.') {
+        (Some(&name[..idx]), &name[idx + 1..])
+    } else {
+        (None, name)
+    }
+}
+
+// Does `qualifier` name the type `type_name` (`geo.Shape`): its path or
+// the end of it (`Shape`)
+fn names_type(qualifier: &str, type_name: &str) -> bool {
+    type_name == qualifier || type_name.strip_suffix(qualifier).is_some_and(|module| module.ends_with('.'))
+}
+
+impl ConstructorTable {
+    pub fn from_types(types: &[TypeDef]) -> Result<Self, String> {
+        let mut table = ConstructorTable::default();
+        for type_def in types {
+            for variant in &type_def.variants {
+                table.add(Constructor {
+                    name: variant.name.clone(),
+                    arity: variant.fields.len() as u32,
+                    type_name: type_def.name.clone(),
+                })?;
+            }
+        }
+        Ok(table)
+    }
+
+    /// Add one constructor; declaring the same one again is a no-op
+    /// (separately compiled units may share a declaration)
+    pub fn add(&mut self, constructor: Constructor) -> Result<(), String> {
+        let existing = self.constructors.iter().find(|c| c.type_name == constructor.type_name && c.name == constructor.name);
+        match existing {
+            Some(existing) if *existing == constructor => Ok(()),
+            Some(existing) => Err(format!(
+                "{}: {}::{} is declared with {} field(s) and with {}",
+                ErrorCode::ConflictingConstructor,
+                constructor.type_name,
+                constructor.name,
+                existing.arity,
+                constructor.arity
+            )),
+            None => {
+                self.constructors.push(constructor);
+                Ok(())
+            }
+        }
+    }
+
+    /// The declared constructors a (possibly qualified) Core name can
+    /// refer to
+    pub fn candidates(&self, name: &str) -> Vec<&Constructor> {
+        let (qualifier, bare) = split_qualified(name);
+        self.constructors
+            .iter()
+            .filter(|c| c.name == bare && qualifier.is_none_or(|q| names_type(q, &c.type_name)))
+            .collect()
+    }
+
+    /// The declared constructor a Core name refers to, when it refers to
+    /// exactly one
+    pub fn get(&self, name: &str) -> Option<&Constructor> {
+        match self.candidates(name).as_slice() {
+            [constructor] => Some(constructor),
+            _ => None,
+        }
+    }
+
+    /// The declared type a qualifier names, by its path or the end of it
+    pub fn type_named(&self, qualifier: &str) -> Option<&str> {
+        self.constructors.iter().map(|c| c.type_name.as_str()).find(|type_name| names_type(qualifier, type_name))
+    }
+
+    /// Constructors of one type, in declaration order
+    pub fn variants<'a>(&'a self, type_name: &'a str) -> impl Iterator<Item = &'a Constructor> {
+        self.constructors.iter().filter(move |c| c.type_name == type_name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Constructor> {
+        self.constructors.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.constructors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constructors.is_empty()
+    }
+}
+
+/// Every constructor reference in `term` under its canonical name
+/// (`Shape.Circle`); an undeclared one by its bare name, the runtime's
+/// `Some` for `Option::Some`. A top-level function's module is the part
+/// of its name before the last `.`; a name that still refers to more than
+/// one constructor is AX1019.
+pub fn qualify(term: &CoreTerm, table: &ConstructorTable) -> Result<CoreTerm, ValidationError> {
+    // The top-level chain without recursion: programs have thousands of
+    // functions
+    let mut definitions = Vec::new();
+    let mut current = term;
+    while let CoreTerm::Let(name, value, body, span) = current {
+        definitions.push((name, value, span));
+        current = body;
+    }
+    let values = definitions
+        .iter()
+        .map(|(name, value, _)| {
+            let module = name.as_str().rsplit_once('.').map_or("", |(module, _)| module);
+            Qualifier { table, module }.term(value)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut result = Qualifier { table, module: "" }.term(current)?;
+    for ((name, _, span), value) in definitions.into_iter().zip(values).rev() {
+        result = CoreTerm::Let(*name, Rc::new(value), Rc::new(result), span.clone());
+    }
+    Ok(result)
+}
+
+struct Qualifier<'a> {
+    table: &'a ConstructorTable,
+    // Of the function being rewritten
+    module: &'a str,
+}
+
+impl Qualifier<'_> {
+    fn term(&self, term: &CoreTerm) -> Result<CoreTerm, ValidationError> {
+        let term_rc = |term: &CoreTerm| self.term(term).map(Rc::new);
+        Ok(match term {
+            CoreTerm::IntLit(..) | CoreTerm::BoolLit(..) | CoreTerm::UnitLit(_) | CoreTerm::StrLit(..) | CoreTerm::Var(..) => {
+                term.clone()
+            }
+            CoreTerm::Ctor(name, fields, span) => {
+                let fields = fields.iter().map(|field| self.term(field)).collect::<Result<_, _>>()?;
+                CoreTerm::Ctor(self.name(name, span)?, fields, span.clone())
+            }
+            CoreTerm::Lam(param, body, span) => CoreTerm::Lam(*param, term_rc(body)?, span.clone()),
+            CoreTerm::App(func, arg, span) => CoreTerm::App(term_rc(func)?, term_rc(arg)?, span.clone()),
+            CoreTerm::Tuple(elems, span) => {
+                CoreTerm::Tuple(elems.iter().map(|elem| self.term(elem)).collect::<Result<_, _>>()?, span.clone())
+            }
+            CoreTerm::Proj(expr, index, span) => CoreTerm::Proj(term_rc(expr)?, *index, span.clone()),
+            CoreTerm::CtorField(expr, index, span) => CoreTerm::CtorField(term_rc(expr)?, *index, span.clone()),
+            CoreTerm::Let(name, value, body, span) => CoreTerm::Let(*name, term_rc(value)?, term_rc(body)?, span.clone()),
+            CoreTerm::If(cond, then_branch, else_branch, span) => {
+                CoreTerm::If(term_rc(cond)?, term_rc(then_branch)?, term_rc(else_branch)?, span.clone())
+            }
+            CoreTerm::Match(scrutinee, arms, span) => {
+                let arms = arms
+                    .iter()
+                    .map(|(pattern, body)| Ok((self.pattern(pattern, span)?, self.term(body)?)))
+                    .collect::<Result<_, ValidationError>>()?;
+                CoreTerm::Match(term_rc(scrutinee)?, arms, span.clone())
+            }
+        })
+    }
+
+    // Patterns carry no spans of their own; errors point at the match
+    fn pattern(&self, pattern: &Pattern, span: &Option<Span>) -> Result<Pattern, ValidationError> {
+        Ok(match pattern {
+            Pattern::PEnum(name, fields) => {
+                let fields = fields.iter().map(|field| self.pattern(field, span)).collect::<Result<_, _>>()?;
+                Pattern::PEnum(self.name(name, span)?, fields)
+            }
+            Pattern::PTuple(elems) => {
+                Pattern::PTuple(elems.iter().map(|elem| self.pattern(elem, span)).collect::<Result<_, _>>()?)
+            }
+            Pattern::PBind(name, inner) => Pattern::PBind(name.clone(), Box::new(self.pattern(inner, span)?)),
+            Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit | Pattern::PVar(_) => pattern.clone(),
+        })
+    }
+
+    fn name(&self, name: &str, span: &Option<Span>) -> Result<String, ValidationError> {
+        let candidates = self.table.candidates(name);
+        if candidates.is_empty() {
+            return Ok(undeclared_name(name));
+        }
+        // More than one: the one this function's module declares
+        let local: Vec<&Constructor> = candidates.iter().copied().filter(|c| c.module() == self.module).collect();
+        match (candidates.as_slice(), local.as_slice()) {
+            ([constructor], _) | (_, [constructor]) => Ok(constructor.canonical_name()),
+            _ => Err(ambiguous(name, &candidates, span)),
+        }
+    }
+}
+
+fn ambiguous(name: &str, candidates: &[&Constructor], span: &Option<Span>) -> ValidationError {
+    let (_, bare) = split_qualified(name);
+    let types: Vec<String> = candidates.iter().map(|c| format!("`{}`", c.type_name)).collect();
+    let mut error = ValidationError::new(format!(
+        "{}: `{}` is a constructor of {}",
+        ErrorCode::AmbiguousConstructor,
+        name,
+        types.join(" and ")
+    ));
+    error.span = span.clone();
+    error.name = Some(bare.to_string());
+    let qualified: Vec<String> = candidates.iter().map(|c| format!("`{}::{}`", c.type_name, c.name)).collect();
+    error.help.push(format!("qualify it with its type: {}", qualified.join(" or ")));
+    error
+}
+
+// An undeclared type has no constructors of its own: `Option::Some` is
+// `Some`, as the backends have always read it
+fn undeclared_name(name: &str) -> String {
+    match name.rfind("::") {
+        Some(idx) => name[idx + 2..].to_string(),
+        None => name.to_string(),
+    }
+}
@@ -0,0 +1,213 @@
+// Tests for `type` declarations, the constructor table (constructors.rs),
+// canonical constructor names (constructors::qualify) and the checks made
+// against the table (core_validator::check_constructors, check_let_patterns)
+
+use crate::constructors::{qualify, Constructor, ConstructorTable};
+use crate::core_text::print_core_text;
+use crate::core_validator::{check_constructors, check_let_patterns, ValidationError};
+use crate::{surface_lower, surface_parser, surface_to_core};
+
+const SHAPES: &str = "type Shape { Circle(Int), Rect(Int, Int) | Empty }\n";
+
+fn check(source: &str) -> Result<(), ValidationError> {
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let constructors = ConstructorTable::from_types(&module.types).unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    check_constructors(&term, &constructors)
+}
+
+fn constructor(name: &str, arity: u32, type_name: &str) -> Constructor {
+    Constructor { name: name.to_string(), arity, type_name: type_name.to_string() }
+}
+
+#[test]
+fn test_type_declarations_are_parsed() {
+    let source = "type Pair[A, B] { MkPair(A, List[B]) }\ntype Point { x: Int, y: Int }\nfn main() { 0 }\n";
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+
+    assert_eq!(module.types.len(), 2);
+    assert_eq!(module.types[0].params, vec!["A".to_string(), "B".to_string()]);
+    assert_eq!(module.types[0].variants[0].fields, vec!["A".to_string(), "List[B]".to_string()]);
+    // A record has one constructor named after the type
+    assert_eq!(module.types[1].variants[0].name, "Point");
+    assert_eq!(module.types[1].variants[0].fields, vec!["x: Int".to_string(), "y: Int".to_string()]);
+    assert_eq!(module.functions.len(), 1);
+}
+
+#[test]
+fn test_malformed_type_declarations_are_parse_errors() {
+    for source in ["type Shape { Circle(Int), Circle }", "type Shape { circle }", "type Shape { Circle(Int)"] {
+        assert!(surface_parser::parse_module_with_file(source, "main.ax").is_err(), "{}", source);
+    }
+    let twice = "type Shape { Circle }\ntype Shape { Square }\n";
+    assert!(surface_parser::parse_module_with_file(twice, "main.ax").is_err());
+}
+
+#[test]
+fn test_table_looks_up_bare_and_qualified_names() {
+    let module = surface_parser::parse_module_with_file(SHAPES, "main.ax").unwrap();
+    let table = ConstructorTable::from_types(&module.types).unwrap();
+
+    assert_eq!(table.len(), 3);
+    assert_eq!(table.get("Rect"), Some(&constructor("Rect", 2, "Shape")));
+    assert_eq!(table.get("Shape::Empty"), Some(&constructor("Empty", 0, "Shape")));
+    assert!(table.get("Cons").is_none());
+}
+
+#[test]
+fn test_conflicting_declarations_are_rejected() {
+    let mut table = ConstructorTable::default();
+    table.add(constructor("Leaf", 1, "Tree")).unwrap();
+    // The same declaration again (another unit) is fine
+    table.add(constructor("Leaf", 1, "Tree")).unwrap();
+    let error = table.add(constructor("Leaf", 0, "Tree")).unwrap_err();
+    assert!(error.starts_with("AX1014: Tree::Leaf "), "{}", error);
+    // Another type may have a constructor of the same name
+    table.add(constructor("Leaf", 0, "Heap")).unwrap();
+    assert_eq!(table.len(), 2);
+    assert!(table.get("Leaf").is_none(), "Leaf is ambiguous");
+    assert_eq!(table.get("Heap::Leaf"), Some(&constructor("Leaf", 0, "Heap")));
+}
+
+// The program lowered, qualified and printed as .coretext
+fn qualified(source: &str) -> Result<String, ValidationError> {
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let constructors = ConstructorTable::from_types(&module.types).unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let term = qualify(&term, &constructors)?;
+    check_constructors(&term, &constructors)?;
+    Ok(print_core_text(&term))
+}
+
+#[test]
+fn test_declared_constructors_get_canonical_names() {
+    let source = format!(
+        "{}fn area(s: Shape) -> Int {{\n    match s {{\n        Shape::Circle(r) => r,\n        Rect(w, h) => w,\n        Empty => 0,\n    }}\n}}\nfn main() {{ (area(Circle(1)), Some(Empty)) }}\n",
+        SHAPES
+    );
+    let text = qualified(&source).unwrap();
+    for name in ["(penum Shape.Circle", "(penum Shape.Rect", "(penum Shape.Empty", "(ctor Shape.Circle", "(ctor Shape.Empty"] {
+        assert!(text.contains(name), "missing `{}`:\n{}", name, text);
+    }
+    // Undeclared constructors keep their names
+    assert!(text.contains("(ctor Some"), "{}", text);
+}
+
+#[test]
+fn test_undeclared_qualified_constructors() {
+    let source = "fn main() {\n    (Option::Some(1), Color::Red)\n}\n";
+    let text = qualified(source).unwrap();
+    // Without a declaration the type does not name the constructor
+    assert!(text.contains("(ctor Some (int 1))") && text.contains("(ctor Red)"), "{}", text);
+}
+
+const TWO_MODULES: &str = "module http {\n    type Status { Ok, Failed(Str) }\n    fn ok() { Ok }\n}\nmodule db {\n    type Reply { Ok, Missing }\n    fn ok() { Ok }\n}\n";
+
+#[test]
+fn test_same_constructor_in_two_modules() {
+    let source = format!("{}fn main() {{\n    (http.ok(), db.ok(), db.Reply::Ok)\n}}\n", TWO_MODULES);
+    let text = qualified(&source).unwrap();
+    // A bare name means the type of the function's own module
+    assert!(text.contains("(ctor http.Status.Ok)") && text.contains("(ctor db.Reply.Ok)"), "{}", text);
+    assert_eq!(text.matches("(ctor db.Reply.Ok)").count(), 2, "{}", text);
+}
+
+#[test]
+fn test_ambiguous_constructor_must_be_qualified() {
+    let source = format!("{}fn main() {{\n    Ok\n}}\n", TWO_MODULES);
+    let error = qualified(&source).expect_err("Ok is declared twice");
+    assert_eq!(error.message, "AX1019: `Ok` is a constructor of `http.Status` and `db.Reply`");
+    assert_eq!(error.name.as_deref(), Some("Ok"));
+    assert_eq!(error.help, vec!["qualify it with its type: `http.Status::Ok` or `db.Reply::Ok`".to_string()]);
+}
+
+#[test]
+fn test_constructor_arity_is_checked() {
+    let ok = format!("{}fn main() {{\n    Rect(1, 2)\n}}\n", SHAPES);
+    assert!(check(&ok).is_ok());
+
+    let wrong = format!("{}fn main() {{\n    Rect(1)\n}}\n", SHAPES);
+    let error = check(&wrong).expect_err("Rect has two fields");
+    assert_eq!(error.message, "AX1010: `Rect` has 2 field(s), found 1");
+    assert_eq!(error.name.as_deref(), Some("Rect"));
+}
+
+#[test]
+fn test_qualified_name_must_be_a_variant_of_its_type() {
+    let source = format!("{}fn main() {{\n    Shape::Triangle(1)\n}}\n", SHAPES);
+    let error = check(&source).expect_err("Shape has no Triangle");
+    assert!(error.message.starts_with("AX1009: `Shape` has no variant `Triangle`"), "{}", error);
+    assert_eq!(error.help, vec!["the variants of `Shape` are Circle, Rect, Empty".to_string()]);
+}
+
+#[test]
+fn test_undeclared_constructors_are_not_checked() {
+    let source = format!("{}fn main() {{\n    Cons(1, Nil)\n}}\n", SHAPES);
+    assert!(check(&source).is_ok());
+}
+
+#[test]
+fn test_pattern_arity_is_checked() {
+    let source = format!(
+        "{}fn area(s: Shape) -> Int {{\n    match s {{\n        Circle(r, x) => r,\n        _ => 0,\n    }}\n}}\n",
+        SHAPES
+    );
+    let error = check(&source).expect_err("Circle has one field");
+    assert!(error.message.starts_with("AX1010: `Circle` has 1 field(s), found 2"), "{}", error);
+}
+
+#[test]
+fn test_match_must_cover_every_variant() {
+    let partial = format!(
+        "{}fn area(s: Shape) -> Int {{\n    match s {{\n        Circle(r) => r,\n        Rect(w, 1) => w,\n    }}\n}}\n",
+        SHAPES
+    );
+    let error = check(&partial).expect_err("Rect(w, 1) and Empty are not covered");
+    assert_eq!(error.message, "AX1011: match on `Shape` does not cover `Rect`, `Empty`");
+
+    let complete = format!(
+        "{}fn area(s: Shape) -> Int {{\n    match s {{\n        Circle(r) => r,\n        Rect(w, h) => w * h,\n        Empty => 0,\n    }}\n}}\n",
+        SHAPES
+    );
+    assert!(check(&complete).is_ok());
+
+    let catch_all = format!(
+        "{}fn area(s: Shape) -> Int {{\n    match s {{\n        Circle(r) => r,\n        _ => 0,\n    }}\n}}\n",
+        SHAPES
+    );
+    assert!(check(&catch_all).is_ok());
+}
+
+fn check_lets(source: &str) -> Result<(), ValidationError> {
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let constructors = ConstructorTable::from_types(&module.types).unwrap();
+    check_let_patterns(&module, &constructors)
+}
+
+#[test]
+fn test_irrefutable_let_patterns_are_accepted() {
+    let source = "type Pair { MkPair(Int, Int) }\nfn main(p: Pair) -> Int {\n    let (a, (b, _)) = (1, (2, 3));\n    let MkPair(x, y) = p;\n    a + b + x + y\n}\n";
+    assert!(check_lets(source).is_ok());
+}
+
+#[test]
+fn test_refutable_let_pattern_needs_an_else() {
+    let source = "fn main(o: Unit) -> Int {\n    let Some(x) = o;\n    x\n}\n";
+    let error = check_lets(source).expect_err("None does not match");
+    assert_eq!(error.message, "AX1012: `let Some(x)` does not match every value: `Some` is one variant of `Option`");
+    assert_eq!(error.name.as_deref(), Some("Some"));
+
+    let with_else = "fn main(o: Unit) -> Int {\n    let Some(x) = o else { 0 };\n    x\n}\n";
+    assert!(check_lets(with_else).is_ok());
+}
+
+#[test]
+fn test_declared_variants_and_nested_literals_are_refutable() {
+    let source = format!("{}fn main(s: Shape) -> Int {{\n    let (Circle(r), n) = (s, 1);\n    r\n}}\n", SHAPES);
+    let error = check_lets(&source).expect_err("Shape has three variants");
+    assert!(error.message.ends_with("`Circle` is one variant of `Shape`"), "{}", error);
+
+    let source = "fn main(p: Unit) -> Int {\n    let (a, 0) = (1, 2);\n    a\n}\n";
+    let error = check_lets(source).expect_err("0 is one Int");
+    assert!(error.message.ends_with("`0` is one Int"), "{}", error);
+}
@@ -0,0 +1,191 @@
+// Export/import manifest of a compiled bundle (separate compilation)
+//
+// Exports are the top-level Let bindings of the bundle, with the number of
+// leading lambdas as arity. Imports are the free names the bundle refers
+// to: `foreign` ones are provided by the registry, the rest must be
+// exported by another bundle at `axis-compiler link` time.
+//
+// An export is public unless the compiler marks it private: a source
+// function without `pub`. Other bundles may only import public exports, and
+// the linker drops private definitions nothing reaches.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use crate::registry_loader::Registry;
+use crate::runtime::{CoreTerm, Pattern};
+use crate::validation_registry;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Export {
+    pub name: String,
+    pub arity: u32,
+    pub public: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Import {
+    pub name: String,
+    pub foreign: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BundleInterface {
+    pub exports: Vec<Export>,
+    pub imports: Vec<Import>,
+}
+
+impl BundleInterface {
+    pub fn is_empty(&self) -> bool {
+        self.exports.is_empty() && self.imports.is_empty()
+    }
+
+    pub fn mark_private(&mut self, names: &HashSet<String>) {
+        for export in &mut self.exports {
+            if names.contains(&export.name) {
+                export.public = false;
+            }
+        }
+    }
+
+    // Imports that another bundle has to provide
+    pub fn external_imports(&self) -> impl Iterator<Item = &Import> {
+        self.imports.iter().filter(|import| !import.foreign)
+    }
+}
+
+fn lambda_arity(term: &CoreTerm) -> u32 {
+    let mut arity = 0;
+    let mut current = term;
+    while let CoreTerm::Lam(_, body, _) = current {
+        arity += 1;
+        current = body;
+    }
+    arity
+}
+
+// Derive the manifest from a bundle's root term. Exports keep definition
+// order; imports are sorted by name.
+pub fn compute_interface(term: &CoreTerm, registry: &Registry) -> BundleInterface {
+    let mut exports = Vec::new();
+    let mut current = term;
+    while let CoreTerm::Let(name, value, body, _) = current {
+        exports.push(Export { name: name.to_string(), arity: lambda_arity(value), public: true });
+        current = body;
+    }
+
+    // Top-level names are in scope everywhere (mutual recursion)
+    let mut bound: HashMap<String, usize> = HashMap::new();
+    for export in &exports {
+        *bound.entry(export.name.clone()).or_insert(0) += 1;
+    }
+    let mut free = BTreeSet::new();
+    collect_free_names(term, &mut bound, &mut free);
+
+    let imports = free
+        .into_iter()
+        .map(|name| {
+            let foreign = validation_registry::is_known_function(registry, &name);
+            Import { name, foreign }
+        })
+        .collect();
+
+    BundleInterface { exports, imports }
+}
+
+// Free names of `term` that resolve neither to a binding nor to the
+// registry: these are the imports a separate unit needs from other bundles
+pub fn unresolved_names(term: &CoreTerm, registry: &Registry) -> Vec<String> {
+    compute_interface(term, registry)
+        .external_imports()
+        .map(|import| import.name.clone())
+        .collect()
+}
+
+// Every name `term` refers to without binding it, registry functions and
+// top-level definitions included
+pub fn free_names(term: &CoreTerm) -> BTreeSet<String> {
+    let mut free = BTreeSet::new();
+    collect_free_names(term, &mut HashMap::new(), &mut free);
+    free
+}
+
+fn bind(bound: &mut HashMap<String, usize>, name: &str) {
+    *bound.entry(name.to_string()).or_insert(0) += 1;
+}
+
+fn unbind(bound: &mut HashMap<String, usize>, name: &str) {
+    if let Some(count) = bound.get_mut(name) {
+        *count -= 1;
+        if *count == 0 {
+            bound.remove(name);
+        }
+    }
+}
+
+fn pattern_vars<'a>(pattern: &'a Pattern, out: &mut Vec<&'a str>) {
+    match pattern {
+        Pattern::PVar(name) => out.push(name),
+        Pattern::PTuple(patterns) | Pattern::PEnum(_, patterns) => {
+            for p in patterns {
+                pattern_vars(p, out);
+            }
+        }
+        Pattern::PBind(name, inner) => {
+            out.push(name);
+            pattern_vars(inner, out);
+        }
+        Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit => {}
+    }
+}
+
+fn collect_free_names(term: &CoreTerm, bound: &mut HashMap<String, usize>, free: &mut BTreeSet<String>) {
+    match term {
+        CoreTerm::IntLit(..) | CoreTerm::BoolLit(..) | CoreTerm::UnitLit(..) | CoreTerm::StrLit(..) => {}
+        CoreTerm::Var(name, _) => {
+            if !bound.contains_key(name.as_str()) {
+                free.insert(name.to_string());
+            }
+        }
+        CoreTerm::Lam(param, body, _) => {
+            bind(bound, param);
+            collect_free_names(body, bound, free);
+            unbind(bound, param);
+        }
+        CoreTerm::App(func, arg, _) => {
+            collect_free_names(func, bound, free);
+            collect_free_names(arg, bound, free);
+        }
+        CoreTerm::Tuple(elems, _) | CoreTerm::Ctor(_, elems, _) => {
+            for elem in elems {
+                collect_free_names(elem, bound, free);
+            }
+        }
+        CoreTerm::Proj(expr, _, _) | CoreTerm::CtorField(expr, _, _) => {
+            collect_free_names(expr, bound, free)
+        }
+        CoreTerm::Let(name, value, body, _) => {
+            collect_free_names(value, bound, free);
+            bind(bound, name);
+            collect_free_names(body, bound, free);
+            unbind(bound, name);
+        }
+        CoreTerm::If(cond, then_br, else_br, _) => {
+            collect_free_names(cond, bound, free);
+            collect_free_names(then_br, bound, free);
+            collect_free_names(else_br, bound, free);
+        }
+        CoreTerm::Match(scrutinee, arms, _) => {
+            collect_free_names(scrutinee, bound, free);
+            for (pattern, body) in arms {
+                let mut vars = Vec::new();
+                pattern_vars(pattern, &mut vars);
+                for var in &vars {
+                    bind(bound, var);
+                }
+                collect_free_names(body, bound, free);
+                for var in &vars {
+                    unbind(bound, var);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+// `axis-compiler link`: combine separately compiled Core bundles
+//
+// Each bundle is a chain of top-level Let bindings ending in a tail term
+// (Unit for compiled modules). Linking concatenates the chains in input
+// order, so every top-level name is visible to every bundle; references
+// across bundles are then resolved by the validator, which pre-binds all
+// top-level names of the merged chain.
+//
+// POLICY: a top-level name may be defined by at most one bundle, and at
+// most one bundle may carry a non-Unit tail. Bundles may declare the same
+// constructor, but not with a different arity or type. Violations are link
+// errors.
+//
+// Private definitions (functions declared without `pub`) that no public
+// definition and no tail term reaches are dead code and are dropped.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use crate::constructors::ConstructorTable;
+use crate::core_interface::free_names;
+use crate::core_loader::CoreProgram;
+use crate::error_codes::ErrorCode;
+use crate::runtime::{CoreTerm, Span};
+use crate::symbol::Symbol;
+
+// Result of a successful link, ready for create_core_bundle_with
+pub struct LinkedProgram {
+    pub root_term: CoreTerm,
+    pub strings: Vec<String>,
+    pub constructors: ConstructorTable,
+    // Private definitions kept, for the linked bundle's manifest
+    pub private: HashSet<String>,
+    // Private definitions dropped as unreachable
+    pub removed: Vec<String>,
+}
+
+// Split a top-level Let chain into its bindings and tail
+fn split_chain(term: &CoreTerm) -> (Vec<(&Symbol, &Rc<CoreTerm>, &Option<Span>)>, &CoreTerm) {
+    let mut bindings = Vec::new();
+    let mut current = term;
+    while let CoreTerm::Let(name, value, body, span) = current {
+        bindings.push((name, value, span));
+        current = body;
+    }
+    (bindings, current)
+}
+
+pub fn link_programs(inputs: &[(String, CoreProgram)]) -> Result<LinkedProgram, String> {
+    if inputs.is_empty() {
+        return Err("link: no input bundles".to_string());
+    }
+
+    let mut errors = Vec::new();
+    let mut defined_in: HashMap<&str, &str> = HashMap::new();
+    let mut bindings = Vec::new();
+    let mut tail: Option<(&str, &CoreTerm)> = None;
+    let mut constructors = ConstructorTable::default();
+
+    for (path, program) in inputs {
+        let (chain, chain_tail) = split_chain(&program.root_term);
+        for (name, value, span) in chain {
+            if let Some(first) = defined_in.get(name.as_str()) {
+                errors.push(format!(
+                    "{}: {} is defined in both {} and {}",
+                    ErrorCode::DuplicateDefinition, name, first, path
+                ));
+                continue;
+            }
+            defined_in.insert(name, path);
+            bindings.push((name, value, span));
+        }
+
+        for constructor in program.constructors.iter() {
+            if let Err(e) = constructors.add(constructor.clone()) {
+                errors.push(format!("{} (in {})", e, path));
+            }
+        }
+
+        if !matches!(chain_tail, CoreTerm::UnitLit(_)) {
+            match tail {
+                Some((first, _)) => errors.push(format!(
+                    "{}: {} and {} both end in a non-Unit top-level term",
+                    ErrorCode::MultipleTails, first, path
+                )),
+                None => tail = Some((path, chain_tail)),
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    let mut private: HashSet<String> = inputs
+        .iter()
+        .flat_map(|(_, program)| program.interface.exports.iter())
+        .filter(|export| !export.public)
+        .map(|export| export.name.clone())
+        .collect();
+    let live = reachable(&bindings, tail.map(|(_, term)| term), &private);
+    let mut removed = Vec::new();
+    bindings.retain(|(name, _, _)| {
+        let keep = live.contains(name.as_str());
+        if !keep {
+            removed.push(name.to_string());
+        }
+        keep
+    });
+    private.retain(|name| live.contains(name.as_str()));
+
+    // Rebuild the chain innermost-first
+    let mut root_term = tail
+        .map(|(_, term)| term.clone())
+        .unwrap_or(CoreTerm::UnitLit(None));
+    for (name, value, span) in bindings.into_iter().rev() {
+        root_term = CoreTerm::Let(*name, value.clone(), Rc::new(root_term), span.clone());
+    }
+
+    // Merge string tables: first occurrence wins, order preserved
+    let mut strings = Vec::new();
+    let mut seen = HashSet::new();
+    for (_, program) in inputs {
+        for s in &program.strings {
+            if seen.insert(s.as_str()) {
+                strings.push(s.clone());
+            }
+        }
+    }
+
+    Ok(LinkedProgram { root_term, strings, constructors, private, removed })
+}
+
+// Names of the bindings that are public or referred to, directly or through
+// other bindings, by a public one or the tail
+fn reachable<'a>(
+    bindings: &[(&'a Symbol, &'a Rc<CoreTerm>, &Option<Span>)],
+    tail: Option<&'a CoreTerm>,
+    private: &HashSet<String>,
+) -> HashSet<&'a str> {
+    let values: HashMap<&str, &CoreTerm> = bindings.iter().map(|(name, value, _)| (name.as_str(), value.as_ref())).collect();
+    let mut live = HashSet::new();
+    let mut pending: Vec<&CoreTerm> = tail.into_iter().collect();
+    for (name, value, _) in bindings {
+        if !private.contains(name.as_str()) {
+            live.insert(name.as_str());
+            pending.push(value.as_ref());
+        }
+    }
+    while let Some(term) = pending.pop() {
+        for name in free_names(term) {
+            if let Some((&name, &value)) = values.get_key_value(name.as_str())
+                && live.insert(name)
+            {
+                pending.push(value);
+            }
+        }
+    }
+    live
+}
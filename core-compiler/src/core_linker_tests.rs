@@ -0,0 +1,203 @@
+// Tests for `axis-compiler link` (core_linker.rs, core_validator::validate_link)
+
+use crate::bundle_metadata::BundleMetadata;
+use crate::constructors::{Constructor, ConstructorTable};
+use crate::core_interface::{compute_interface, BundleInterface, Export, Import};
+use crate::core_linker::link_programs;
+use crate::core_loader::CoreProgram;
+use crate::core_validator::{validate_core, validate_link};
+use crate::error_codes::ErrorCode;
+use crate::registry_loader::Registry;
+use crate::runtime::CoreTerm;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn lam(param: &str, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Lam(param.into(), Rc::new(body), None)
+}
+
+// fn name(x) { body } for each (name, body), ending in Unit
+fn chain(functions: &[(&str, CoreTerm)]) -> CoreTerm {
+    functions.iter().rev().fold(CoreTerm::UnitLit(None), |body, (name, value)| {
+        CoreTerm::Let((*name).into(), Rc::new(lam("x", value.clone())), Rc::new(body), None)
+    })
+}
+
+// A bundle whose `private` functions were declared without `pub`
+fn bundle(functions: &[(&str, CoreTerm)], private: &[&str]) -> CoreProgram {
+    let root_term = chain(functions);
+    let mut interface = compute_interface(&root_term, &Registry::new());
+    interface.mark_private(&private.iter().map(|name| name.to_string()).collect::<HashSet<_>>());
+    CoreProgram {
+        strings: Vec::new(),
+        root_term,
+        entrypoint_id: 0,
+        interface,
+        constructors: ConstructorTable::default(),
+        metadata: BundleMetadata::default(),
+    }
+}
+
+fn names(term: &CoreTerm) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut current = term;
+    while let CoreTerm::Let(name, _, body, _) = current {
+        names.push(name.to_string());
+        current = body;
+    }
+    names
+}
+
+#[test]
+fn test_unreachable_private_definitions_are_removed() {
+    // pad is reached through the public trim; unused and dead only reach each other
+    let util = bundle(
+        &[("pad", var("x")), ("trim", var("pad")), ("unused", var("dead")), ("dead", var("unused"))],
+        &["pad", "unused", "dead"],
+    );
+    let main = bundle(&[("axis_entry", CoreTerm::App(Rc::new(var("trim")), Rc::new(var("x")), None))], &[]);
+    let linked = link_programs(&[("util.coreir".into(), util), ("main.coreir".into(), main)]).unwrap();
+
+    assert_eq!(names(&linked.root_term), ["pad", "trim", "axis_entry"]);
+    assert_eq!(linked.removed, ["unused", "dead"]);
+    assert_eq!(linked.private, HashSet::from(["pad".to_string()]));
+}
+
+#[test]
+fn test_bundles_without_visibility_keep_everything() {
+    let old = bundle(&[("helper", var("x")), ("other", var("x"))], &[]);
+    let linked = link_programs(&[("old.coreir".into(), old)]).unwrap();
+    assert_eq!(names(&linked.root_term), ["helper", "other"]);
+    assert!(linked.removed.is_empty());
+}
+
+#[test]
+fn test_imports_must_name_public_exports() {
+    let util = bundle(&[("pad", var("x")), ("trim", var("x"))], &["pad"]);
+    let mut main = BundleInterface::default();
+    for name in ["pad", "trim"] {
+        main.imports.push(Import { name: name.into(), foreign: false });
+    }
+    let units = [("util.coreir", &util.interface), ("main.coreir", &main)];
+    let error = validate_link(&units).expect_err("pad is private");
+    assert_eq!(
+        error.message,
+        "AX3005: pad (imported by main.coreir) is private to util.coreir; declare it `pub fn`"
+    );
+}
+
+fn app(func: CoreTerm, arg: CoreTerm) -> CoreTerm {
+    CoreTerm::App(Rc::new(func), Rc::new(arg), None)
+}
+
+// `program` ending in `tail` instead of Unit
+fn with_tail(mut program: CoreProgram, tail: CoreTerm) -> CoreProgram {
+    fn replace(term: &CoreTerm, tail: CoreTerm) -> CoreTerm {
+        match term {
+            CoreTerm::Let(name, value, body, span) => CoreTerm::Let(*name, value.clone(), Rc::new(replace(body, tail)), span.clone()),
+            _ => tail,
+        }
+    }
+    program.root_term = replace(&program.root_term, tail);
+    program
+}
+
+#[test]
+fn test_link_concatenates_bundles_in_order() {
+    assert_eq!(link_programs(&[]).err().as_deref(), Some("link: no input bundles"));
+
+    let mut util = bundle(&[("pad", var("x")), ("trim", var("x"))], &[]);
+    util.strings = vec!["a".into(), "b".into()];
+    let mut main = with_tail(bundle(&[("axis_entry", var("x"))], &[]), app(var("axis_entry"), CoreTerm::UnitLit(None)));
+    main.strings = vec!["b".into(), "c".into()];
+    let linked = link_programs(&[("util.coreir".into(), util), ("main.coreir".into(), main)]).unwrap();
+
+    assert_eq!(names(&linked.root_term), ["pad", "trim", "axis_entry"]);
+    let mut tail = &linked.root_term;
+    while let CoreTerm::Let(_, _, body, _) = tail {
+        tail = body;
+    }
+    assert!(matches!(tail, CoreTerm::App(..)), "the entry bundle's tail is kept: {:?}", tail);
+    assert_eq!(linked.strings, ["a", "b", "c"]);
+}
+
+#[test]
+fn test_duplicate_definitions_and_tails_are_link_errors() {
+    let a = with_tail(bundle(&[("helper", var("x")), ("trim", var("x"))], &[]), CoreTerm::IntLit(1, None));
+    let b = bundle(&[("trim", var("x"))], &[]);
+    let c = with_tail(bundle(&[("helper", var("x"))], &[]), CoreTerm::IntLit(2, None));
+    let error = link_programs(&[("a.coreir".into(), a), ("b.coreir".into(), b), ("c.coreir".into(), c)]).err().unwrap();
+    assert_eq!(
+        error.lines().collect::<Vec<_>>(),
+        [
+            format!("{}: trim is defined in both a.coreir and b.coreir", ErrorCode::DuplicateDefinition),
+            format!("{}: helper is defined in both a.coreir and c.coreir", ErrorCode::DuplicateDefinition),
+            format!("{}: a.coreir and c.coreir both end in a non-Unit top-level term", ErrorCode::MultipleTails),
+        ]
+    );
+}
+
+#[test]
+fn test_constructors_must_agree_across_bundles() {
+    let option = |arity| Constructor { name: "Some".into(), arity, type_name: "Option".into() };
+    let with = |arity| {
+        let mut program = bundle(&[], &[]);
+        program.constructors.add(option(arity)).unwrap();
+        program
+    };
+    let linked = link_programs(&[("a.coreir".into(), with(1)), ("b.coreir".into(), with(1))]).unwrap();
+    assert_eq!(linked.constructors.iter().count(), 1);
+
+    let error = link_programs(&[("a.coreir".into(), with(1)), ("b.coreir".into(), with(2))]).err().unwrap();
+    assert_eq!(
+        error,
+        format!("{}: Option::Some is declared with 1 field(s) and with 2 (in b.coreir)", ErrorCode::ConflictingConstructor)
+    );
+}
+
+#[test]
+fn test_references_resolve_across_bundles() {
+    // main calls util's trim, which calls main's helper
+    let util = bundle(&[("trim", app(var("helper"), var("x")))], &[]);
+    let main = bundle(&[("helper", var("x")), ("axis_entry", app(var("trim"), var("x")))], &[]);
+    let imports = |program: &CoreProgram| program.interface.imports.iter().map(|import| import.name.clone()).collect::<Vec<_>>();
+    assert_eq!(imports(&util), ["helper"]);
+    assert_eq!(imports(&main), ["trim"]);
+    assert!(validate_core(&util.root_term, &Registry::new()).has_errors());
+
+    let units = [("util.coreir", &util.interface), ("main.coreir", &main.interface)];
+    validate_link(&units).unwrap();
+    let linked = link_programs(&[("util.coreir".into(), util), ("main.coreir".into(), main)]).unwrap();
+    let report = validate_core(&linked.root_term, &Registry::new());
+    assert!(!report.has_errors(), "{}", report);
+}
+
+#[test]
+fn test_manifests_are_checked_before_linking() {
+    let export = |name: &str| Export { name: name.into(), arity: 1, public: true };
+    let import = |name: &str, foreign| Import { name: name.into(), foreign };
+    let util = BundleInterface { exports: vec![export("trim"), export("pad")], imports: vec![] };
+    let other = BundleInterface { exports: vec![export("pad")], imports: vec![] };
+    let main = BundleInterface {
+        exports: vec![export("axis_entry")],
+        // Foreign imports come from the registry, not from another bundle
+        imports: vec![import("trim", false), import("axis_str_len", true), import("missing", false)],
+    };
+
+    let units = [("util.coreir", &util), ("other.coreir", &other), ("main.coreir", &main)];
+    let error = validate_link(&units).expect_err("pad is exported twice and missing by no one");
+    assert_eq!(
+        error.message.lines().collect::<Vec<_>>(),
+        [
+            format!("{}: pad is exported by both util.coreir and other.coreir", ErrorCode::DuplicateExport),
+            format!("{}: missing (imported by main.coreir) is not exported by any linked bundle", ErrorCode::UnresolvedImport),
+        ]
+    );
+
+    let main = BundleInterface { imports: vec![import("trim", false), import("axis_str_len", true)], ..main };
+    validate_link(&[("util.coreir", &util), ("main.coreir", &main)]).unwrap();
+}
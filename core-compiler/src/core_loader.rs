@@ -1,6 +1,10 @@
 use std::fs;
 use std::rc::Rc;
+use crate::bundle_metadata::{BundleMetadata, FileDigest};
+use crate::constructors::{Constructor, ConstructorTable};
+use crate::core_interface::{BundleInterface, Export, Import};
 use crate::runtime::CoreTerm;
+use crate::symbol::Symbol;
 use crate::trace;
 
 #[allow(dead_code)]
@@ -9,9 +13,72 @@ pub struct CoreProgram {
     pub strings: Vec<String>,
     pub root_term: CoreTerm,
     pub entrypoint_id: usize,
+    // Export/import manifest (empty for bundles written without one)
+    pub interface: BundleInterface,
+    // Declared constructors (empty for bundles written without them)
+    pub constructors: ConstructorTable,
+    // Provenance (empty for bundles written without it)
+    pub metadata: BundleMetadata,
 }
 
-pub fn load_core_bundle(path: &str) -> Result<CoreProgram, String> {
+// Resource limits applied while loading a Core bundle.
+// Defaults are safe for untrusted input; `trusted()` lifts them (CLI: --trusted-input).
+#[derive(Clone, Debug)]
+pub struct BundleLoadOptions {
+    pub max_size: usize,   // encoded bundle size in bytes
+    pub max_nodes: usize,  // CoreTerm + Pattern nodes
+    pub max_depth: usize,  // term nesting depth
+    pub trusted: bool,     // skip all limits
+}
+
+impl Default for BundleLoadOptions {
+    fn default() -> Self {
+        BundleLoadOptions {
+            max_size: 64 * 1024 * 1024,
+            max_nodes: 8_000_000,
+            max_depth: 4096,
+            trusted: false,
+        }
+    }
+}
+
+impl BundleLoadOptions {
+    pub fn trusted() -> Self {
+        BundleLoadOptions { trusted: true, ..BundleLoadOptions::default() }
+    }
+}
+
+// Running node/depth budget for one bundle load
+struct LoadBudget {
+    nodes_left: usize,
+    max_depth: usize,
+}
+
+impl LoadBudget {
+    fn new(options: &BundleLoadOptions) -> Self {
+        if options.trusted {
+            LoadBudget { nodes_left: usize::MAX, max_depth: usize::MAX }
+        } else {
+            LoadBudget { nodes_left: options.max_nodes, max_depth: options.max_depth }
+        }
+    }
+
+    fn enter(&mut self, depth: usize) -> Result<(), String> {
+        if self.nodes_left == 0 {
+            return Err("Core bundle exceeds node limit (use --trusted-input to lift)".to_string());
+        }
+        if depth > self.max_depth {
+            return Err(format!(
+                "Core bundle exceeds nesting depth limit of {} (use --trusted-input to lift)",
+                self.max_depth
+            ));
+        }
+        self.nodes_left -= 1;
+        Ok(())
+    }
+}
+
+pub fn load_core_bundle(path: &str, options: &BundleLoadOptions) -> Result<CoreProgram, String> {
     eprintln!("[TRACE] loading core bundle path={}", path);
     
     let bytes = fs::read(path)
@@ -20,16 +87,34 @@ pub fn load_core_bundle(path: &str) -> Result<CoreProgram, String> {
     eprintln!("[TRACE] core bundle loaded size={} bytes", bytes.len());
     trace::trace(&format!("Loading Core bundle: {} ({} bytes)", path, bytes.len()));
     
-    deserialize_core_bundle(&bytes)
+    if !options.trusted && bytes.len() > options.max_size {
+        return Err(format!(
+            "Core bundle is {} bytes, exceeding the {} byte limit (use --trusted-input to lift)",
+            bytes.len(), options.max_size
+        ));
+    }
+    
+    deserialize_core_bundle(&bytes, options)
 }
 
-fn deserialize_core_bundle(bytes: &[u8]) -> Result<CoreProgram, String> {
+fn deserialize_core_bundle(bytes: &[u8], options: &BundleLoadOptions) -> Result<CoreProgram, String> {
     use capnp::message::ReaderOptions;
     use capnp::serialize;
     
+    let mut reader_options = ReaderOptions::new();
+    if options.trusted {
+        reader_options.traversal_limit_in_words = Some(1024 * 1024 * 1024);
+        reader_options.nesting_limit = 1_000_000;
+    } else {
+        // Each term level is two pointer hops (CoreTerm union -> variant struct)
+        reader_options.traversal_limit_in_words = Some(options.max_size / 8);
+        reader_options.nesting_limit = i32::try_from(options.max_depth.saturating_mul(2).saturating_add(8))
+            .unwrap_or(i32::MAX);
+    }
+    
     let reader = serialize::read_message(
         &mut &bytes[..],
-        ReaderOptions::new()
+        reader_options
     ).map_err(|e| format!("Failed to read Cap'n Proto message: {}", e))?;
     
     let bundle = reader.get_root::<crate::axis_core_ir_0_1_capnp::core_bundle::Reader>()
@@ -59,18 +144,98 @@ fn deserialize_core_bundle(bytes: &[u8]) -> Result<CoreProgram, String> {
     let core_term_reader = bundle.get_core_term()
         .map_err(|e| format!("Failed to get core term: {}", e))?;
     
-    let root_term = deserialize_core_term(core_term_reader)?;
+    let mut budget = LoadBudget::new(options);
+    let root_term = deserialize_core_term(core_term_reader, &mut budget, 0)?;
+    
+    let mut interface = BundleInterface::default();
+    let exports_reader = bundle.get_exports()
+        .map_err(|e| format!("Failed to get exports: {}", e))?;
+    for export in exports_reader.iter() {
+        let name = export.get_name()
+            .map_err(|e| format!("Failed to get export name: {}", e))?;
+        interface.exports.push(Export {
+            name: name.to_str().map_err(|e| format!("Invalid UTF-8 in export name: {}", e))?.to_string(),
+            arity: export.get_arity(),
+            public: export.get_public(),
+        });
+    }
+    let imports_reader = bundle.get_imports()
+        .map_err(|e| format!("Failed to get imports: {}", e))?;
+    for import in imports_reader.iter() {
+        let name = import.get_name()
+            .map_err(|e| format!("Failed to get import name: {}", e))?;
+        interface.imports.push(Import {
+            name: name.to_str().map_err(|e| format!("Invalid UTF-8 in import name: {}", e))?.to_string(),
+            foreign: import.get_foreign(),
+        });
+    }
+
+    let mut constructors = ConstructorTable::default();
+    let constructors_reader = bundle.get_constructors()
+        .map_err(|e| format!("Failed to get constructors: {}", e))?;
+    for constructor in constructors_reader.iter() {
+        let name = constructor.get_name()
+            .map_err(|e| format!("Failed to get constructor name: {}", e))?;
+        let type_name = constructor.get_type_name()
+            .map_err(|e| format!("Failed to get constructor type: {}", e))?;
+        constructors.add(Constructor {
+            name: name.to_str().map_err(|e| format!("Invalid UTF-8 in constructor name: {}", e))?.to_string(),
+            arity: constructor.get_arity(),
+            type_name: type_name.to_str().map_err(|e| format!("Invalid UTF-8 in constructor type: {}", e))?.to_string(),
+        })?;
+    }
+
+    let metadata = if bundle.has_metadata() {
+        deserialize_metadata(bundle.get_metadata().map_err(|e| format!("Failed to get metadata: {}", e))?)?
+    } else {
+        BundleMetadata::default()
+    };
     
     Ok(CoreProgram {
         strings,
         root_term,
         entrypoint_id,
+        interface,
+        constructors,
+        metadata,
     })
 }
 
-fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reader) -> Result<CoreTerm, String> {
+fn deserialize_metadata(
+    reader: crate::axis_core_ir_0_1_capnp::bundle_metadata::Reader,
+) -> Result<BundleMetadata, String> {
+    fn text(text: capnp::Result<capnp::text::Reader>, what: &str) -> Result<String, String> {
+        let text = text.map_err(|e| format!("Failed to get metadata {}: {}", what, e))?;
+        Ok(text.to_str().map_err(|e| format!("Invalid UTF-8 in metadata {}: {}", what, e))?.to_string())
+    }
+    let mut metadata = BundleMetadata {
+        compiler_version: text(reader.get_compiler_version(), "compiler version")?,
+        compiler_commit: text(reader.get_compiler_commit(), "compiler commit")?,
+        ..BundleMetadata::default()
+    };
+    let sources = reader.get_sources().map_err(|e| format!("Failed to get metadata sources: {}", e))?;
+    for file in sources.iter() {
+        metadata.sources.push(FileDigest { path: text(file.get_path(), "source")?, digest: text(file.get_digest(), "source")? });
+    }
+    let registries = reader.get_registries().map_err(|e| format!("Failed to get metadata registries: {}", e))?;
+    for file in registries.iter() {
+        metadata.registries.push(FileDigest { path: text(file.get_path(), "registry")?, digest: text(file.get_digest(), "registry")? });
+    }
+    let flags = reader.get_flags().map_err(|e| format!("Failed to get metadata flags: {}", e))?;
+    for i in 0..flags.len() {
+        metadata.flags.push(text(flags.get(i), "flag")?);
+    }
+    Ok(metadata)
+}
+
+fn deserialize_core_term(
+    reader: crate::axis_core_ir_0_1_capnp::core_term::Reader,
+    budget: &mut LoadBudget,
+    depth: usize,
+) -> Result<CoreTerm, String> {
     use crate::axis_core_ir_0_1_capnp::core_term::Which;
     
+    budget.enter(depth)?;
     match reader.which() {
         Ok(Which::CIntLit(lit)) => {
             let lit = lit.map_err(|e| format!("Failed to read CIntLit: {}", e))?;
@@ -99,7 +264,7 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
             let name = var.get_name()
                 .map_err(|e| format!("Failed to get var name: {}", e))?;
             Ok(CoreTerm::Var(
-                name.to_str().map_err(|e| format!("Invalid UTF-8 in var name: {}", e))?.to_string(),
+                Symbol::intern(name.to_str().map_err(|e| format!("Invalid UTF-8 in var name: {}", e))?),
                 None
             ))
         },
@@ -109,9 +274,9 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                 .map_err(|e| format!("Failed to get param: {}", e))?;
             let body_reader = lam.get_body()
                 .map_err(|e| format!("Failed to get body: {}", e))?;
-            let body = deserialize_core_term(body_reader)?;
+            let body = deserialize_core_term(body_reader, budget, depth + 1)?;
             Ok(CoreTerm::Lam(
-                param.to_str().map_err(|e| format!("Invalid UTF-8 in param: {}", e))?.to_string(),
+                Symbol::intern(param.to_str().map_err(|e| format!("Invalid UTF-8 in param: {}", e))?),
                 Rc::new(body),
                 None
             ))
@@ -122,8 +287,8 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                 .map_err(|e| format!("Failed to get func: {}", e))?;
             let arg_reader = app.get_arg()
                 .map_err(|e| format!("Failed to get arg: {}", e))?;
-            let func = deserialize_core_term(func_reader)?;
-            let arg = deserialize_core_term(arg_reader)?;
+            let func = deserialize_core_term(func_reader, budget, depth + 1)?;
+            let arg = deserialize_core_term(arg_reader, budget, depth + 1)?;
             Ok(CoreTerm::App(Rc::new(func), Rc::new(arg), None))
         },
         Ok(Which::CTuple(tup)) => {
@@ -133,7 +298,7 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
             let mut elems = Vec::new();
             for i in 0..elems_reader.len() {
                 let elem = elems_reader.get(i);
-                elems.push(deserialize_core_term(elem)?);
+                elems.push(deserialize_core_term(elem, budget, depth + 1)?);
             }
             Ok(CoreTerm::Tuple(elems, None))
         },
@@ -142,9 +307,17 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
             let expr_reader = proj.get_expr()
                 .map_err(|e| format!("Failed to get expr: {}", e))?;
             let index = proj.get_index() as usize;
-            let expr = deserialize_core_term(expr_reader)?;
+            let expr = deserialize_core_term(expr_reader, budget, depth + 1)?;
             Ok(CoreTerm::Proj(Rc::new(expr), index, None))
         },
+        Ok(Which::CCtorField(field)) => {
+            let field = field.map_err(|e| format!("Failed to read CCtorField: {}", e))?;
+            let expr_reader = field.get_expr()
+                .map_err(|e| format!("Failed to get expr: {}", e))?;
+            let index = field.get_index() as usize;
+            let expr = deserialize_core_term(expr_reader, budget, depth + 1)?;
+            Ok(CoreTerm::CtorField(Rc::new(expr), index, None))
+        },
         Ok(Which::CLet(let_)) => {
             let let_ = let_.map_err(|e| format!("Failed to read CLet: {}", e))?;
             let name = let_.get_name()
@@ -153,10 +326,10 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                 .map_err(|e| format!("Failed to get value: {}", e))?;
             let body_reader = let_.get_body()
                 .map_err(|e| format!("Failed to get body: {}", e))?;
-            let value = deserialize_core_term(value_reader)?;
-            let body = deserialize_core_term(body_reader)?;
+            let value = deserialize_core_term(value_reader, budget, depth + 1)?;
+            let body = deserialize_core_term(body_reader, budget, depth + 1)?;
             Ok(CoreTerm::Let(
-                name.to_str().map_err(|e| format!("Invalid UTF-8 in let name: {}", e))?.to_string(),
+                Symbol::intern(name.to_str().map_err(|e| format!("Invalid UTF-8 in let name: {}", e))?),
                 Rc::new(value),
                 Rc::new(body),
                 None
@@ -170,9 +343,9 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                 .map_err(|e| format!("Failed to get then: {}", e))?;
             let else_reader = if_.get_else_branch()
                 .map_err(|e| format!("Failed to get else: {}", e))?;
-            let cond = deserialize_core_term(cond_reader)?;
-            let then_branch = deserialize_core_term(then_reader)?;
-            let else_branch = deserialize_core_term(else_reader)?;
+            let cond = deserialize_core_term(cond_reader, budget, depth + 1)?;
+            let then_branch = deserialize_core_term(then_reader, budget, depth + 1)?;
+            let else_branch = deserialize_core_term(else_reader, budget, depth + 1)?;
             Ok(CoreTerm::If(Rc::new(cond), Rc::new(then_branch), Rc::new(else_branch), None))
         },
         Ok(Which::CCtor(ctor)) => {
@@ -184,7 +357,7 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
             let mut fields = Vec::new();
             for i in 0..fields_reader.len() {
                 let field = fields_reader.get(i);
-                fields.push(deserialize_core_term(field)?);
+                fields.push(deserialize_core_term(field, budget, depth + 1)?);
             }
             Ok(CoreTerm::Ctor(
                 name.to_str().map_err(|e| format!("Invalid UTF-8 in ctor name: {}", e))?.to_string(),
@@ -196,7 +369,7 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
             let match_ = match_.map_err(|e| format!("Failed to read CMatch: {}", e))?;
             let scrutinee_reader = match_.get_scrutinee()
                 .map_err(|e| format!("Failed to get scrutinee: {}", e))?;
-            let scrutinee = deserialize_core_term(scrutinee_reader)?;
+            let scrutinee = deserialize_core_term(scrutinee_reader, budget, depth + 1)?;
             
             // Deserialize match arms
             let arms_reader = match_.get_arms()
@@ -205,11 +378,11 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
             for arm_reader in arms_reader.iter() {
                 let pattern_reader = arm_reader.get_pattern()
                     .map_err(|e| format!("Failed to get pattern: {}", e))?;
-                let pattern = deserialize_pattern(pattern_reader)?;
+                let pattern = deserialize_pattern(pattern_reader, budget, depth + 1)?;
                 
                 let body_reader = arm_reader.get_body()
                     .map_err(|e| format!("Failed to get body: {}", e))?;
-                let body = deserialize_core_term(body_reader)?;
+                let body = deserialize_core_term(body_reader, budget, depth + 1)?;
                 
                 arms.push((pattern, body));
             }
@@ -221,10 +394,15 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
 }
 
 // Deserialize Pattern from Cap'n Proto
-fn deserialize_pattern(reader: crate::axis_core_ir_0_1_capnp::pattern::Reader) -> Result<crate::runtime::Pattern, String> {
+fn deserialize_pattern(
+    reader: crate::axis_core_ir_0_1_capnp::pattern::Reader,
+    budget: &mut LoadBudget,
+    depth: usize,
+) -> Result<crate::runtime::Pattern, String> {
     use crate::axis_core_ir_0_1_capnp::pattern::Which;
     use crate::runtime::Pattern;
     
+    budget.enter(depth)?;
     match reader.which() {
         Ok(Which::PInt(p)) => {
             let p = p.map_err(|e| format!("Failed to read PInt: {}", e))?;
@@ -251,7 +429,7 @@ fn deserialize_pattern(reader: crate::axis_core_ir_0_1_capnp::pattern::Reader) -
                 .map_err(|e| format!("Failed to get tuple patterns: {}", e))?;
             let mut patterns = Vec::new();
             for pat_reader in patterns_reader.iter() {
-                patterns.push(deserialize_pattern(pat_reader)?);
+                patterns.push(deserialize_pattern(pat_reader, budget, depth + 1)?);
             }
             Ok(Pattern::PTuple(patterns))
         },
@@ -263,13 +441,24 @@ fn deserialize_pattern(reader: crate::axis_core_ir_0_1_capnp::pattern::Reader) -
                 .map_err(|e| format!("Failed to get enum patterns: {}", e))?;
             let mut patterns = Vec::new();
             for pat_reader in patterns_reader.iter() {
-                patterns.push(deserialize_pattern(pat_reader)?);
+                patterns.push(deserialize_pattern(pat_reader, budget, depth + 1)?);
             }
             Ok(Pattern::PEnum(
                 name.to_str().map_err(|e| format!("Invalid UTF-8 in enum name: {}", e))?.to_string(),
                 patterns
             ))
         },
+        Ok(Which::PBind(p)) => {
+            let p = p.map_err(|e| format!("Failed to read PBind: {}", e))?;
+            let name = p.get_name()
+                .map_err(|e| format!("Failed to get bind name: {}", e))?;
+            let inner = p.get_pattern()
+                .map_err(|e| format!("Failed to get bound pattern: {}", e))?;
+            Ok(Pattern::PBind(
+                name.to_str().map_err(|e| format!("Invalid UTF-8 in bind name: {}", e))?.to_string(),
+                Box::new(deserialize_pattern(inner, budget, depth + 1)?)
+            ))
+        },
         Err(e) => Err(format!("Unknown Pattern variant: {:?}", e)),
     }
 }
@@ -297,11 +486,11 @@ pub fn serialize_core_term<'a>(
         },
         CoreTerm::Var(name, _) => {
             let mut var_builder = builder.init_c_var();
-            var_builder.set_name(name);
+            var_builder.set_name(name.as_str());
         },
         CoreTerm::Lam(param, body, _) => {
             let mut lam_builder = builder.init_c_lam();
-            lam_builder.set_param(param);
+            lam_builder.set_param(param.as_str());
             let body_builder = lam_builder.init_body();
             serialize_core_term(body, body_builder);
         },
@@ -326,9 +515,15 @@ pub fn serialize_core_term<'a>(
             let expr_builder = proj_builder.init_expr();
             serialize_core_term(expr, expr_builder);
         },
+        CoreTerm::CtorField(expr, index, _) => {
+            let mut field_builder = builder.init_c_ctor_field();
+            field_builder.set_index(*index as u32);
+            let expr_builder = field_builder.init_expr();
+            serialize_core_term(expr, expr_builder);
+        },
         CoreTerm::Let(name, value, body, _) => {
             let mut let_builder = builder.init_c_let();
-            let_builder.set_name(name);
+            let_builder.set_name(name.as_str());
             let value_builder = let_builder.reborrow().init_value();
             serialize_core_term(value, value_builder);
             let body_builder = let_builder.init_body();
@@ -410,11 +605,38 @@ fn serialize_pattern<'a>(
                 serialize_pattern(pat, pat_builder);
             }
         },
+        Pattern::PBind(name, inner) => {
+            let mut p_builder = builder.init_p_bind();
+            p_builder.set_name(name);
+            serialize_pattern(inner, p_builder.init_pattern());
+        },
     }
 }
 
+#[allow(dead_code)]
+// Manifest-less form; the compile and link paths use create_core_bundle_with
 // Create a core bundle binary
 pub fn create_core_bundle(term: &CoreTerm, entrypoint_name: &str) -> Vec<u8> {
+    create_core_bundle_with(
+        term,
+        entrypoint_name,
+        &[],
+        &BundleInterface::default(),
+        &ConstructorTable::default(),
+        &BundleMetadata::default(),
+    )
+}
+
+// Create a core bundle binary carrying a string table, export/import
+// manifest, constructor table and provenance metadata
+pub fn create_core_bundle_with(
+    term: &CoreTerm,
+    entrypoint_name: &str,
+    strings: &[String],
+    interface: &BundleInterface,
+    constructors: &ConstructorTable,
+    metadata: &BundleMetadata,
+) -> Vec<u8> {
     use capnp::message::Builder;
     use capnp::serialize;
     
@@ -425,7 +647,34 @@ pub fn create_core_bundle(term: &CoreTerm, entrypoint_name: &str) -> Vec<u8> {
         bundle.set_version("0.1");
         bundle.set_entrypoint_name(entrypoint_name);
         bundle.set_entrypoint_id(0);
-        bundle.reborrow().init_string_table(0);
+        let mut string_table = bundle.reborrow().init_string_table(strings.len() as u32);
+        for (i, s) in strings.iter().enumerate() {
+            string_table.set(i as u32, s);
+        }
+        
+        let mut exports = bundle.reborrow().init_exports(interface.exports.len() as u32);
+        for (i, export) in interface.exports.iter().enumerate() {
+            let mut export_builder = exports.reborrow().get(i as u32);
+            export_builder.set_name(&export.name);
+            export_builder.set_arity(export.arity);
+            export_builder.set_public(export.public);
+        }
+        let mut imports = bundle.reborrow().init_imports(interface.imports.len() as u32);
+        for (i, import) in interface.imports.iter().enumerate() {
+            let mut import_builder = imports.reborrow().get(i as u32);
+            import_builder.set_name(&import.name);
+            import_builder.set_foreign(import.foreign);
+        }
+        let mut constructors_builder = bundle.reborrow().init_constructors(constructors.len() as u32);
+        for (i, constructor) in constructors.iter().enumerate() {
+            let mut constructor_builder = constructors_builder.reborrow().get(i as u32);
+            constructor_builder.set_name(&constructor.name);
+            constructor_builder.set_arity(constructor.arity);
+            constructor_builder.set_type_name(&constructor.type_name);
+        }
+        if !metadata.is_empty() {
+            serialize_metadata(metadata, bundle.reborrow().init_metadata());
+        }
         
         let core_term_builder = bundle.init_core_term();
         serialize_core_term(term, core_term_builder);
@@ -436,3 +685,24 @@ pub fn create_core_bundle(term: &CoreTerm, entrypoint_name: &str) -> Vec<u8> {
     buf
 }
 
+fn serialize_metadata(metadata: &BundleMetadata, mut builder: crate::axis_core_ir_0_1_capnp::bundle_metadata::Builder) {
+    builder.set_compiler_version(&metadata.compiler_version);
+    builder.set_compiler_commit(&metadata.compiler_commit);
+    let mut sources = builder.reborrow().init_sources(metadata.sources.len() as u32);
+    for (i, file) in metadata.sources.iter().enumerate() {
+        let mut file_builder = sources.reborrow().get(i as u32);
+        file_builder.set_path(&file.path);
+        file_builder.set_digest(&file.digest);
+    }
+    let mut registries = builder.reborrow().init_registries(metadata.registries.len() as u32);
+    for (i, file) in metadata.registries.iter().enumerate() {
+        let mut file_builder = registries.reborrow().get(i as u32);
+        file_builder.set_path(&file.path);
+        file_builder.set_digest(&file.digest);
+    }
+    let mut flags = builder.init_flags(metadata.flags.len() as u32);
+    for (i, flag) in metadata.flags.iter().enumerate() {
+        flags.set(i as u32, flag);
+    }
+}
+
@@ -0,0 +1,143 @@
+// `axis-compiler stats`: size report for a Core IR bundle
+//
+// Tracks IR bloat across compiler generations: node counts by kind,
+// maximum nesting depth, top-level functions and their sizes, and the
+// string table. Traversal is iterative so very deep bundles are safe.
+
+use std::collections::BTreeMap;
+use crate::core_loader::CoreProgram;
+use crate::runtime::CoreTerm;
+
+pub struct FunctionStats {
+    pub name: String,
+    pub nodes: usize,
+    pub max_depth: usize,
+}
+
+pub struct CoreStats {
+    pub total_nodes: usize,
+    pub max_depth: usize,
+    pub nodes_by_kind: BTreeMap<&'static str, usize>,
+    // Top-level Let bindings whose value is a lambda, in definition order
+    pub functions: Vec<FunctionStats>,
+    pub string_count: usize,
+    pub string_bytes: usize,
+}
+
+// Walk `root`, tallying kinds into `by_kind`; returns (nodes, max depth)
+// with the root itself at depth 1
+fn walk(root: &CoreTerm, by_kind: &mut BTreeMap<&'static str, usize>) -> (usize, usize) {
+    let mut nodes = 0;
+    let mut max_depth = 0;
+    let mut stack: Vec<(&CoreTerm, usize)> = vec![(root, 1)];
+
+    while let Some((term, depth)) = stack.pop() {
+        nodes += 1;
+        max_depth = max_depth.max(depth);
+        *by_kind.entry(term.kind_name()).or_insert(0) += 1;
+
+        match term {
+            CoreTerm::IntLit(..)
+            | CoreTerm::BoolLit(..)
+            | CoreTerm::UnitLit(..)
+            | CoreTerm::StrLit(..)
+            | CoreTerm::Var(..) => {}
+            CoreTerm::Lam(_, body, _) => stack.push((body, depth + 1)),
+            CoreTerm::App(func, arg, _) => {
+                stack.push((func, depth + 1));
+                stack.push((arg, depth + 1));
+            }
+            CoreTerm::Tuple(elems, _) | CoreTerm::Ctor(_, elems, _) => {
+                stack.extend(elems.iter().map(|e| (e, depth + 1)));
+            }
+            CoreTerm::Proj(expr, _, _) | CoreTerm::CtorField(expr, _, _) => stack.push((expr, depth + 1)),
+            CoreTerm::Let(_, value, body, _) => {
+                stack.push((value, depth + 1));
+                stack.push((body, depth + 1));
+            }
+            CoreTerm::If(cond, then_br, else_br, _) => {
+                stack.push((cond, depth + 1));
+                stack.push((then_br, depth + 1));
+                stack.push((else_br, depth + 1));
+            }
+            CoreTerm::Match(scrutinee, arms, _) => {
+                stack.push((scrutinee, depth + 1));
+                stack.extend(arms.iter().map(|(_, body)| (body, depth + 1)));
+            }
+        }
+    }
+    (nodes, max_depth)
+}
+
+pub fn compute(program: &CoreProgram) -> CoreStats {
+    let mut nodes_by_kind = BTreeMap::new();
+    let (total_nodes, max_depth) = walk(&program.root_term, &mut nodes_by_kind);
+
+    // Top-level functions: the program is a chain of Let bindings
+    let mut functions = Vec::new();
+    let mut scratch = BTreeMap::new();
+    let mut current = &program.root_term;
+    while let CoreTerm::Let(name, value, body, _) = current {
+        if let CoreTerm::Lam(..) = value.as_ref() {
+            let (nodes, depth) = walk(value, &mut scratch);
+            functions.push(FunctionStats { name: name.to_string(), nodes, max_depth: depth });
+        }
+        current = body;
+    }
+
+    CoreStats {
+        total_nodes,
+        max_depth,
+        nodes_by_kind,
+        functions,
+        string_count: program.strings.len(),
+        string_bytes: program.strings.iter().map(|s| s.len()).sum(),
+    }
+}
+
+pub fn format_report(path: &str, stats: &CoreStats) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Core IR statistics: {}\n", path));
+    out.push('\n');
+    out.push_str(&format!("  nodes:            {}\n", stats.total_nodes));
+    out.push_str(&format!("  max depth:        {}\n", stats.max_depth));
+    out.push_str(&format!("  functions:        {}\n", stats.functions.len()));
+    // Ties keep definition order
+    let largest = stats
+        .functions
+        .iter()
+        .rev()
+        .max_by_key(|f| f.nodes);
+    match largest {
+        Some(f) => out.push_str(&format!("  largest function: {} ({} nodes)\n", f.name, f.nodes)),
+        None => out.push_str("  largest function: -\n"),
+    }
+    out.push_str(&format!(
+        "  string table:     {} strings ({} bytes)\n",
+        stats.string_count, stats.string_bytes
+    ));
+
+    out.push('\n');
+    out.push_str("Nodes by kind:\n");
+    for (kind, count) in &stats.nodes_by_kind {
+        out.push_str(&format!("  {:<14} {:>8}\n", kind, count));
+    }
+
+    if !stats.functions.is_empty() {
+        out.push('\n');
+        out.push_str("Functions (largest first):\n");
+        let mut by_size: Vec<&FunctionStats> = stats.functions.iter().collect();
+        by_size.sort_by(|a, b| b.nodes.cmp(&a.nodes));
+        let name_width = by_size.iter().map(|f| f.name.len()).max().unwrap_or(0);
+        for f in by_size {
+            out.push_str(&format!(
+                "  {:<width$} {:>8} nodes  depth {}\n",
+                f.name,
+                f.nodes,
+                f.max_depth,
+                width = name_width
+            ));
+        }
+    }
+    out
+}
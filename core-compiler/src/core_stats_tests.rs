@@ -0,0 +1,71 @@
+// Tests for `axis-compiler stats` (core_stats.rs)
+
+use crate::bundle_metadata::BundleMetadata;
+use crate::constructors::ConstructorTable;
+use crate::core_interface::BundleInterface;
+use crate::core_loader::CoreProgram;
+use crate::core_stats::{compute, format_report};
+use crate::core_text::parse_core_text;
+use crate::runtime::CoreTerm;
+use crate::session::{CompileOptions, Session};
+use std::fs;
+use std::path::Path;
+
+fn program(root_term: CoreTerm, strings: &[&str]) -> CoreProgram {
+    CoreProgram {
+        strings: strings.iter().map(|s| s.to_string()).collect(),
+        root_term,
+        entrypoint_id: 0,
+        interface: BundleInterface::default(),
+        constructors: ConstructorTable::default(),
+        metadata: BundleMetadata::default(),
+    }
+}
+
+#[test]
+fn test_counters() {
+    let term = parse_core_text(
+        "(let f (lam x (app (var g) (tuple (var x) (int 1))))
+           (let n (int 3)
+             (let g (lam y (var y)) (unit))))",
+    )
+    .unwrap();
+    let stats = compute(&program(term, &["ab", "cde"]));
+    assert_eq!(stats.total_nodes, 13);
+    // let f, lam x, app, tuple, var x
+    assert_eq!(stats.max_depth, 5);
+    assert_eq!(stats.nodes_by_kind.get("let"), Some(&3));
+    assert_eq!(stats.nodes_by_kind.get("lam"), Some(&2));
+    assert_eq!(stats.nodes_by_kind.get("var"), Some(&3));
+    // n is not a lambda, so not a function
+    let functions: Vec<(&str, usize, usize)> =
+        stats.functions.iter().map(|f| (f.name.as_str(), f.nodes, f.max_depth)).collect();
+    assert_eq!(functions, [("f", 6, 4), ("g", 2, 2)]);
+    assert_eq!((stats.string_count, stats.string_bytes), (2, 5));
+}
+
+// The report for tests/golden/phases.ax as the compiler bundles it (with
+// an empty string table, as compiled bundles have) must match
+// tests/golden/phases.stats; AXIS_BLESS=1 rewrites it
+#[test]
+fn test_report_matches_its_golden() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let golden = root.join("tests/golden");
+    let source = fs::read_to_string(golden.join("phases.ax")).unwrap();
+    let options = CompileOptions {
+        registries: vec![root.join("registries/axis.axreg").to_string_lossy().into_owned()],
+        prelude: false,
+        ..CompileOptions::default()
+    };
+    let compiled = Session::new().compile(&[("phases.ax".to_string(), source)], &options).unwrap();
+    let actual = format_report("phases.coreir", &compute(&program(compiled.core, &[])));
+
+    let expected_path = golden.join("phases.stats");
+    if std::env::var("AXIS_BLESS").is_ok_and(|v| v == "1") {
+        fs::write(&expected_path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&expected_path)
+        .unwrap_or_else(|e| panic!("{}: {} (AXIS_BLESS=1 creates it)", expected_path.display(), e));
+    assert_eq!(actual, expected, "{} differs", expected_path.display());
+}
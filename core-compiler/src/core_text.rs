@@ -0,0 +1,712 @@
+// Canonical textual Core IR (.coretext)
+//
+// One Core term per file, written as an s-expression:
+//
+//   term    := (int N) | (bool true|false) | (unit) | (str "...") | (var NAME)
+//            | (lam NAME term) | (app term term) | (tuple term*)
+//            | (proj term INDEX) | (field term INDEX) | (let NAME term term)
+//            | (if term term term) | (ctor NAME term*)
+//            | (match term (case pattern term)*)
+//   pattern := (pint N) | (pbool true|false) | (punit) | (pvar NAME)
+//            | (ptuple pattern*) | (penum NAME pattern*) | (pbind NAME pattern)
+//   NAME    := bare atom, or "quoted" when it contains whitespace, ( ) " ;
+//
+// `;` starts a comment that runs to end of line. Projection and constructor
+// field indices are 0-based, as in CoreTerm::Proj and CoreTerm::CtorField.
+//
+// ROUND-TRIP CONTRACT: parse_core_text(&print_core_text(t)) rebuilds `t`
+// exactly, except for spans (the text format carries none). The printer is
+// canonical: equal terms always print to identical text.
+
+use std::rc::Rc;
+use crate::runtime::{CoreTerm, Pattern};
+
+// Forms that fit in this many columns are printed on a single line
+const LINE_WIDTH: usize = 80;
+
+// Parser recursion guard; keeps debug builds well inside the main-thread stack
+const MAX_NESTING_DEPTH: usize = 1024;
+
+// ============================================================================
+// Printer
+// ============================================================================
+
+pub fn print_core_text(term: &CoreTerm) -> String {
+    let mut out = String::new();
+    write_term(&mut out, term, 0);
+    out.push('\n');
+    out
+}
+
+// Write `term` at the current position, breaking children onto indented
+// lines when the flat form does not fit in LINE_WIDTH
+fn write_term(out: &mut String, term: &CoreTerm, indent: usize) {
+    let mut flat = String::new();
+    if write_flat(&mut flat, term, LINE_WIDTH.saturating_sub(indent)) {
+        out.push_str(&flat);
+        return;
+    }
+
+    let child_indent = indent + 2;
+    let newline = |out: &mut String| {
+        out.push('\n');
+        out.push_str(&" ".repeat(child_indent));
+    };
+    match term {
+        CoreTerm::Lam(param, body, _) => {
+            out.push_str("(lam ");
+            out.push_str(&format_name(param));
+            newline(out);
+            write_term(out, body, child_indent);
+        }
+        CoreTerm::App(func, arg, _) => {
+            out.push_str("(app");
+            newline(out);
+            write_term(out, func, child_indent);
+            newline(out);
+            write_term(out, arg, child_indent);
+        }
+        CoreTerm::Tuple(elems, _) => {
+            out.push_str("(tuple");
+            for elem in elems {
+                newline(out);
+                write_term(out, elem, child_indent);
+            }
+        }
+        CoreTerm::Proj(expr, index, _) | CoreTerm::CtorField(expr, index, _) => {
+            out.push_str(if matches!(term, CoreTerm::Proj(..)) { "(proj" } else { "(field" });
+            newline(out);
+            write_term(out, expr, child_indent);
+            newline(out);
+            out.push_str(&index.to_string());
+        }
+        CoreTerm::Let(name, value, body, _) => {
+            out.push_str("(let ");
+            out.push_str(&format_name(name));
+            newline(out);
+            write_term(out, value, child_indent);
+            newline(out);
+            write_term(out, body, child_indent);
+        }
+        CoreTerm::If(cond, then_br, else_br, _) => {
+            out.push_str("(if");
+            for branch in [cond, then_br, else_br] {
+                newline(out);
+                write_term(out, branch, child_indent);
+            }
+        }
+        CoreTerm::Ctor(name, fields, _) => {
+            out.push_str("(ctor ");
+            out.push_str(&format_name(name));
+            for field in fields {
+                newline(out);
+                write_term(out, field, child_indent);
+            }
+        }
+        CoreTerm::Match(scrutinee, arms, _) => {
+            out.push_str("(match");
+            newline(out);
+            write_term(out, scrutinee, child_indent);
+            for (pattern, body) in arms {
+                newline(out);
+                out.push_str("(case ");
+                write_pattern(out, pattern);
+                out.push('\n');
+                out.push_str(&" ".repeat(child_indent + 2));
+                write_term(out, body, child_indent + 2);
+                out.push(')');
+            }
+        }
+        // Leaves always fit unless a string literal alone exceeds the width
+        _ => {
+            write_flat(out, term, usize::MAX);
+            return;
+        }
+    }
+    out.push(')');
+}
+
+// Single-line form of `term`; returns false (leaving `out` partial) as soon
+// as the output exceeds `limit` bytes
+fn write_flat(out: &mut String, term: &CoreTerm, limit: usize) -> bool {
+    match term {
+        CoreTerm::IntLit(n, _) => out.push_str(&format!("(int {})", n)),
+        CoreTerm::BoolLit(b, _) => out.push_str(&format!("(bool {})", b)),
+        CoreTerm::UnitLit(_) => out.push_str("(unit)"),
+        CoreTerm::StrLit(s, _) => {
+            out.push_str("(str ");
+            out.push_str(&quote(s));
+            out.push(')');
+        }
+        CoreTerm::Var(name, _) => {
+            out.push_str("(var ");
+            out.push_str(&format_name(name));
+            out.push(')');
+        }
+        CoreTerm::Lam(param, body, _) => {
+            out.push_str("(lam ");
+            out.push_str(&format_name(param));
+            out.push(' ');
+            if !write_flat(out, body, limit) {
+                return false;
+            }
+            out.push(')');
+        }
+        CoreTerm::App(func, arg, _) => {
+            out.push_str("(app ");
+            if !write_flat(out, func, limit) {
+                return false;
+            }
+            out.push(' ');
+            if !write_flat(out, arg, limit) {
+                return false;
+            }
+            out.push(')');
+        }
+        CoreTerm::Tuple(elems, _) => {
+            out.push_str("(tuple");
+            for elem in elems {
+                out.push(' ');
+                if !write_flat(out, elem, limit) {
+                    return false;
+                }
+            }
+            out.push(')');
+        }
+        CoreTerm::Proj(expr, index, _) | CoreTerm::CtorField(expr, index, _) => {
+            out.push_str(if matches!(term, CoreTerm::Proj(..)) { "(proj " } else { "(field " });
+            if !write_flat(out, expr, limit) {
+                return false;
+            }
+            out.push_str(&format!(" {})", index));
+        }
+        CoreTerm::Let(name, value, body, _) => {
+            out.push_str("(let ");
+            out.push_str(&format_name(name));
+            out.push(' ');
+            if !write_flat(out, value, limit) {
+                return false;
+            }
+            out.push(' ');
+            if !write_flat(out, body, limit) {
+                return false;
+            }
+            out.push(')');
+        }
+        CoreTerm::If(cond, then_br, else_br, _) => {
+            out.push_str("(if");
+            for branch in [cond, then_br, else_br] {
+                out.push(' ');
+                if !write_flat(out, branch, limit) {
+                    return false;
+                }
+            }
+            out.push(')');
+        }
+        CoreTerm::Ctor(name, fields, _) => {
+            out.push_str("(ctor ");
+            out.push_str(&format_name(name));
+            for field in fields {
+                out.push(' ');
+                if !write_flat(out, field, limit) {
+                    return false;
+                }
+            }
+            out.push(')');
+        }
+        CoreTerm::Match(scrutinee, arms, _) => {
+            out.push_str("(match ");
+            if !write_flat(out, scrutinee, limit) {
+                return false;
+            }
+            for (pattern, body) in arms {
+                out.push_str(" (case ");
+                write_pattern(out, pattern);
+                out.push(' ');
+                if !write_flat(out, body, limit) {
+                    return false;
+                }
+                out.push(')');
+            }
+            out.push(')');
+        }
+    }
+    out.len() <= limit
+}
+
+fn write_pattern(out: &mut String, pattern: &Pattern) {
+    match pattern {
+        Pattern::PInt(n) => out.push_str(&format!("(pint {})", n)),
+        Pattern::PBool(b) => out.push_str(&format!("(pbool {})", b)),
+        Pattern::PUnit => out.push_str("(punit)"),
+        Pattern::PVar(name) => {
+            out.push_str("(pvar ");
+            out.push_str(&format_name(name));
+            out.push(')');
+        }
+        Pattern::PTuple(patterns) => {
+            out.push_str("(ptuple");
+            for p in patterns {
+                out.push(' ');
+                write_pattern(out, p);
+            }
+            out.push(')');
+        }
+        Pattern::PEnum(name, patterns) => {
+            out.push_str("(penum ");
+            out.push_str(&format_name(name));
+            for p in patterns {
+                out.push(' ');
+                write_pattern(out, p);
+            }
+            out.push(')');
+        }
+        Pattern::PBind(name, inner) => {
+            out.push_str("(pbind ");
+            out.push_str(&format_name(name));
+            out.push(' ');
+            write_pattern(out, inner);
+            out.push(')');
+        }
+    }
+}
+
+fn is_atom_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | '"' | ';')
+}
+
+// Names print bare when they are a valid atom, quoted otherwise
+fn format_name(name: &str) -> String {
+    if !name.is_empty() && name.chars().all(is_atom_char) {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Atom(String),
+    Quoted(String),
+}
+
+// Token plus its 1-based (line, column)
+type Located = (Token, usize, usize);
+
+/// Parse a .coretext document into a CoreTerm.
+/// Errors are reported as "LINE:COLUMN: message".
+pub fn parse_core_text(source: &str) -> Result<CoreTerm, String> {
+    let tokens = tokenize(source)?;
+    let mut parser = TextParser { tokens, pos: 0, depth: 0 };
+    let term = parser.parse_term()?;
+    if let Some((_, line, column)) = parser.tokens.get(parser.pos) {
+        return Err(format!("{}:{}: expected end of input after the Core term", line, column));
+    }
+    Ok(term)
+}
+
+fn tokenize(source: &str) -> Result<Vec<Located>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let (mut line, mut column) = (1usize, 1usize);
+
+    while let Some(&c) = chars.peek() {
+        let (start_line, start_column) = (line, column);
+        let mut bump = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            let c = chars.next();
+            if c == Some('\n') {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+            c
+        };
+        match c {
+            c if c.is_whitespace() => {
+                bump(&mut chars);
+            }
+            ';' => {
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    bump(&mut chars);
+                }
+            }
+            '(' => {
+                bump(&mut chars);
+                tokens.push((Token::Open, start_line, start_column));
+            }
+            ')' => {
+                bump(&mut chars);
+                tokens.push((Token::Close, start_line, start_column));
+            }
+            '"' => {
+                bump(&mut chars);
+                let mut text = String::new();
+                loop {
+                    match bump(&mut chars) {
+                        None => {
+                            return Err(format!(
+                                "{}:{}: unterminated string literal",
+                                start_line, start_column
+                            ))
+                        }
+                        Some('"') => break,
+                        Some('\\') => {
+                            let escaped = match bump(&mut chars) {
+                                Some('"') => '"',
+                                Some('\\') => '\\',
+                                Some('n') => '\n',
+                                Some('r') => '\r',
+                                Some('t') => '\t',
+                                Some('u') => {
+                                    let mut hex = String::new();
+                                    if bump(&mut chars) != Some('{') {
+                                        return Err(format!(
+                                            "{}:{}: expected '{{' after \\u",
+                                            start_line, start_column
+                                        ));
+                                    }
+                                    loop {
+                                        match bump(&mut chars) {
+                                            Some('}') => break,
+                                            Some(h) if h.is_ascii_hexdigit() && hex.len() < 6 => hex.push(h),
+                                            _ => {
+                                                return Err(format!(
+                                                    "{}:{}: malformed \\u{{...}} escape",
+                                                    start_line, start_column
+                                                ))
+                                            }
+                                        }
+                                    }
+                                    u32::from_str_radix(&hex, 16)
+                                        .ok()
+                                        .and_then(char::from_u32)
+                                        .ok_or_else(|| {
+                                            format!(
+                                                "{}:{}: invalid unicode escape \\u{{{}}}",
+                                                start_line, start_column, hex
+                                            )
+                                        })?
+                                }
+                                other => {
+                                    return Err(format!(
+                                        "{}:{}: unknown escape \\{}",
+                                        start_line,
+                                        start_column,
+                                        other.map(String::from).unwrap_or_default()
+                                    ))
+                                }
+                            };
+                            text.push(escaped);
+                        }
+                        Some(c) => text.push(c),
+                    }
+                }
+                tokens.push((Token::Quoted(text), start_line, start_column));
+            }
+            _ => {
+                let mut text = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !is_atom_char(c) {
+                        break;
+                    }
+                    text.push(c);
+                    bump(&mut chars);
+                }
+                tokens.push((Token::Atom(text), start_line, start_column));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct TextParser {
+    tokens: Vec<Located>,
+    pos: usize,
+    depth: usize,
+}
+
+impl TextParser {
+    // Position of the current token, or of the last token at end of input
+    fn location(&self) -> (usize, usize) {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map(|(_, line, column)| (*line, *column))
+            .unwrap_or((1, 1))
+    }
+
+    fn error(&self, msg: &str) -> String {
+        let (line, column) = self.location();
+        format!("{}:{}: {}", line, column, msg)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _, _)| token)
+    }
+
+    fn expect_open(&mut self, what: &str) -> Result<(), String> {
+        if self.peek() == Some(&Token::Open) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '(' to start {}", what)))
+        }
+    }
+
+    fn expect_close(&mut self, form: &str) -> Result<(), String> {
+        if self.peek() == Some(&Token::Close) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected ')' to close ({} ...)", form)))
+        }
+    }
+
+    fn at_close(&self) -> bool {
+        self.peek() == Some(&Token::Close)
+    }
+
+    // Form keyword right after '('
+    fn keyword(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Atom(word)) => {
+                let word = word.clone();
+                self.pos += 1;
+                Ok(word)
+            }
+            _ => Err(self.error("expected a form keyword")),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Atom(text)) | Some(Token::Quoted(text)) => {
+                let text = text.clone();
+                self.pos += 1;
+                Ok(text)
+            }
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn atom(&mut self, what: &str) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Atom(text)) => {
+                let text = text.clone();
+                self.pos += 1;
+                Ok(text)
+            }
+            _ => Err(self.error(&format!("expected {}", what))),
+        }
+    }
+
+    fn int(&mut self) -> Result<i64, String> {
+        let (line, column) = self.location();
+        let text = self.atom("an integer")?;
+        text.parse::<i64>()
+            .map_err(|_| format!("{}:{}: invalid integer '{}'", line, column, text))
+    }
+
+    fn bool(&mut self) -> Result<bool, String> {
+        let (line, column) = self.location();
+        match self.atom("true or false")?.as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            other => Err(format!("{}:{}: expected true or false, got '{}'", line, column, other)),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err(self.error(&format!("nesting exceeds {} levels", MAX_NESTING_DEPTH)));
+        }
+        Ok(())
+    }
+
+    // Each form is parsed by its own small method so the recursive path
+    // keeps a small stack frame (deeply nested let-chains are common)
+    fn parse_term(&mut self) -> Result<CoreTerm, String> {
+        self.enter()?;
+        self.expect_open("a Core term")?;
+        let form_location = self.location();
+        let form = self.keyword()?;
+        let term = match form.as_str() {
+            "int" => self.int().map(|n| CoreTerm::IntLit(n, None)),
+            "bool" => self.bool().map(|b| CoreTerm::BoolLit(b, None)),
+            "unit" => Ok(CoreTerm::UnitLit(None)),
+            "str" => self.quoted().map(|text| CoreTerm::StrLit(text, None)),
+            "var" => self.name().map(|name| CoreTerm::Var(name.into(), None)),
+            "lam" => self.parse_lam(),
+            "app" => self.parse_app(),
+            "tuple" => self.parse_terms().map(|elems| CoreTerm::Tuple(elems, None)),
+            "proj" => self.parse_proj(),
+            "field" => self.parse_field(),
+            "let" => self.parse_let(),
+            "if" => self.parse_if(),
+            "ctor" => self.parse_ctor(),
+            "match" => self.parse_match(),
+            other => Err(unknown_form("Core term", other, form_location)),
+        }?;
+        self.expect_close(&form)?;
+        self.depth -= 1;
+        Ok(term)
+    }
+
+    fn quoted(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Quoted(text)) => {
+                let text = text.clone();
+                self.pos += 1;
+                Ok(text)
+            }
+            _ => Err(self.error("expected a quoted string")),
+        }
+    }
+
+    // Terms up to the closing ')' of the enclosing form
+    fn parse_terms(&mut self) -> Result<Vec<CoreTerm>, String> {
+        let mut terms = Vec::new();
+        while !self.at_close() {
+            terms.push(self.parse_term()?);
+        }
+        Ok(terms)
+    }
+
+    fn parse_lam(&mut self) -> Result<CoreTerm, String> {
+        let param = self.name()?;
+        let body = self.parse_term()?;
+        Ok(CoreTerm::Lam(param.into(), Rc::new(body), None))
+    }
+
+    fn parse_app(&mut self) -> Result<CoreTerm, String> {
+        let func = self.parse_term()?;
+        let arg = self.parse_term()?;
+        Ok(CoreTerm::App(Rc::new(func), Rc::new(arg), None))
+    }
+
+    fn index(&mut self, what: &str) -> Result<usize, String> {
+        let (line, column) = self.location();
+        let text = self.atom(&format!("a {} index", what))?;
+        text.parse::<usize>().map_err(|_| {
+            format!("{}:{}: invalid {} index '{}'", line, column, what, text)
+        })
+    }
+
+    fn parse_proj(&mut self) -> Result<CoreTerm, String> {
+        let expr = self.parse_term()?;
+        let index = self.index("projection")?;
+        Ok(CoreTerm::Proj(Rc::new(expr), index, None))
+    }
+
+    fn parse_field(&mut self) -> Result<CoreTerm, String> {
+        let expr = self.parse_term()?;
+        let index = self.index("field")?;
+        Ok(CoreTerm::CtorField(Rc::new(expr), index, None))
+    }
+
+    fn parse_let(&mut self) -> Result<CoreTerm, String> {
+        let name = self.name()?;
+        let value = self.parse_term()?;
+        let body = self.parse_term()?;
+        Ok(CoreTerm::Let(name.into(), Rc::new(value), Rc::new(body), None))
+    }
+
+    fn parse_if(&mut self) -> Result<CoreTerm, String> {
+        let cond = self.parse_term()?;
+        let then_br = self.parse_term()?;
+        let else_br = self.parse_term()?;
+        Ok(CoreTerm::If(Rc::new(cond), Rc::new(then_br), Rc::new(else_br), None))
+    }
+
+    fn parse_ctor(&mut self) -> Result<CoreTerm, String> {
+        let name = self.name()?;
+        let fields = self.parse_terms()?;
+        Ok(CoreTerm::Ctor(name, fields, None))
+    }
+
+    fn parse_match(&mut self) -> Result<CoreTerm, String> {
+        let scrutinee = self.parse_term()?;
+        let mut arms = Vec::new();
+        while !self.at_close() {
+            self.expect_open("a match case")?;
+            let case = self.keyword()?;
+            if case != "case" {
+                return Err(self.error(&format!("expected (case ...), got ({} ...)", case)));
+            }
+            let pattern = self.parse_pattern()?;
+            let body = self.parse_term()?;
+            self.expect_close("case")?;
+            arms.push((pattern, body));
+        }
+        Ok(CoreTerm::Match(Rc::new(scrutinee), arms, None))
+    }
+
+    fn parse_pattern(&mut self) -> Result<Pattern, String> {
+        self.enter()?;
+        self.expect_open("a pattern")?;
+        let form_location = self.location();
+        let form = self.keyword()?;
+        let pattern = match form.as_str() {
+            "pint" => Pattern::PInt(self.int()?),
+            "pbool" => Pattern::PBool(self.bool()?),
+            "punit" => Pattern::PUnit,
+            "pvar" => Pattern::PVar(self.name()?),
+            "ptuple" => {
+                let mut patterns = Vec::new();
+                while !self.at_close() {
+                    patterns.push(self.parse_pattern()?);
+                }
+                Pattern::PTuple(patterns)
+            }
+            "penum" => {
+                let name = self.name()?;
+                let mut patterns = Vec::new();
+                while !self.at_close() {
+                    patterns.push(self.parse_pattern()?);
+                }
+                Pattern::PEnum(name, patterns)
+            }
+            "pbind" => {
+                let name = self.name()?;
+                Pattern::PBind(name, Box::new(self.parse_pattern()?))
+            }
+            other => return Err(unknown_form("pattern", other, form_location)),
+        };
+        self.expect_close(&form)?;
+        self.depth -= 1;
+        Ok(pattern)
+    }
+}
+
+fn unknown_form(kind: &str, form: &str, (line, column): (usize, usize)) -> String {
+    format!("{}:{}: unknown {} form '{}'", line, column, kind, form)
+}
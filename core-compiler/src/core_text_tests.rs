@@ -0,0 +1,159 @@
+// Tests for the Core IR text format (core_text.rs)
+
+use crate::core_text::{parse_core_text, print_core_text};
+use crate::runtime::{CoreTerm, Pattern};
+use crate::symbol::Symbol;
+use std::rc::Rc;
+
+#[test]
+fn test_names_survive_core_text_round_trip() {
+    let term = CoreTerm::Let(
+        "id".into(),
+        Rc::new(CoreTerm::Lam("x".into(), Rc::new(CoreTerm::Var("x".into(), None)), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None,
+    );
+    let text = print_core_text(&term);
+    let parsed = parse_core_text(&text).unwrap();
+    assert_eq!(print_core_text(&parsed), text);
+    let CoreTerm::Let(name, value, _, _) = parsed else {
+        panic!("expected Let, got {}", text);
+    };
+    assert_eq!(name, Symbol::intern("id"));
+    assert!(matches!(value.as_ref(), CoreTerm::Lam(param, _, _) if *param == "x"));
+}
+
+fn rc(term: CoreTerm) -> Rc<CoreTerm> {
+    Rc::new(term)
+}
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+// Printing, parsing and printing again gives the same text and the same term
+fn round_trip(term: &CoreTerm) -> String {
+    let text = print_core_text(term);
+    let parsed = parse_core_text(&text).unwrap_or_else(|e| panic!("{}\n{}", e, text));
+    assert_eq!(print_core_text(&parsed), text);
+    assert_eq!(format!("{:?}", parsed), format!("{:?}", term));
+    text
+}
+
+#[test]
+fn test_every_form_round_trips() {
+    let patterns = vec![
+        Pattern::PInt(-3),
+        Pattern::PBool(false),
+        Pattern::PUnit,
+        Pattern::PVar("x".into()),
+        Pattern::PTuple(vec![]),
+        Pattern::PTuple(vec![Pattern::PVar("a".into()), Pattern::PInt(1)]),
+        Pattern::PEnum("Nil".into(), vec![]),
+        Pattern::PEnum("Cons".into(), vec![Pattern::PVar("h".into()), Pattern::PVar("t".into())]),
+        Pattern::PBind("all".into(), Box::new(Pattern::PEnum("Some".into(), vec![Pattern::PUnit]))),
+    ];
+    let arms = patterns.into_iter().map(|pattern| (pattern, CoreTerm::IntLit(0, None))).collect();
+    let term = CoreTerm::Let(
+        "f".into(),
+        rc(CoreTerm::Lam("x".into(), rc(CoreTerm::App(rc(var("g")), rc(var("x")), None)), None)),
+        rc(CoreTerm::If(
+            rc(CoreTerm::BoolLit(true, None)),
+            rc(CoreTerm::Tuple(
+                vec![
+                    CoreTerm::Tuple(vec![], None),
+                    CoreTerm::UnitLit(None),
+                    CoreTerm::StrLit("s".into(), None),
+                    CoreTerm::IntLit(i64::MIN, None),
+                    CoreTerm::Proj(rc(var("p")), 1, None),
+                    CoreTerm::CtorField(rc(var("c")), 0, None),
+                ],
+                None,
+            )),
+            rc(CoreTerm::Match(
+                rc(CoreTerm::Ctor("Pair".into(), vec![CoreTerm::IntLit(1, None), CoreTerm::Ctor("Nil".into(), vec![], None)], None)),
+                arms,
+                None,
+            )),
+            None,
+        )),
+        None,
+    );
+    let text = round_trip(&term);
+    // Too wide for one line, so the printer broke it up
+    assert!(text.lines().count() > 1 && text.lines().all(|line| line.len() <= 80), "{}", text);
+
+    let small = CoreTerm::Match(
+        rc(CoreTerm::Proj(rc(var("p")), 0, None)),
+        vec![(Pattern::PBind("n".into(), Box::new(Pattern::PInt(1))), var("n"))],
+        None,
+    );
+    assert_eq!(round_trip(&small), "(match (proj (var p) 0) (case (pbind n (pint 1)) (var n)))\n");
+}
+
+#[test]
+fn test_strings_are_escaped_and_quoted() {
+    let text = "say \"hi\"\\ \n\r\t\u{1}é;()";
+    let printed = round_trip(&CoreTerm::StrLit(text.into(), None));
+    assert_eq!(printed, "(str \"say \\\"hi\\\"\\\\ \\n\\r\\t\\u{1}é;()\")\n");
+    assert_eq!(round_trip(&CoreTerm::StrLit(String::new(), None)), "(str \"\")\n");
+
+    // Escapes the printer never writes still parse
+    let parsed = parse_core_text("(str \"\\u{48}\\u{10FFFF}\")").unwrap();
+    assert!(matches!(parsed, CoreTerm::StrLit(s, _) if s == "H\u{10FFFF}"));
+
+    for (source, error) in [
+        ("(str \"a\\q\")", "1:6: unknown escape \\q"),
+        ("(str \"\\u48\")", "1:6: expected '{' after \\u"),
+        ("(str \"\\u{4g}\")", "1:6: malformed \\u{...} escape"),
+        ("(str \"\\u{D800}\")", "1:6: invalid unicode escape \\u{D800}"),
+        ("(str \"open)", "1:6: unterminated string literal"),
+        ("(str plain)", "1:6: expected a quoted string"),
+    ] {
+        assert_eq!(parse_core_text(source).unwrap_err(), error, "{}", source);
+    }
+}
+
+#[test]
+fn test_names_are_quoted_when_they_are_not_atoms() {
+    for name in ["", "a b", "f(x)", "say\"hi\"", "semi;colon", "tab\there"] {
+        let term = CoreTerm::Let(
+            name.into(),
+            rc(CoreTerm::Lam(name.into(), rc(var(name)), None)),
+            rc(CoreTerm::Match(
+                rc(CoreTerm::Ctor(name.into(), vec![], None)),
+                vec![(
+                    Pattern::PBind(name.into(), Box::new(Pattern::PEnum(name.into(), vec![Pattern::PVar(name.into())]))),
+                    var(name),
+                )],
+                None,
+            )),
+            None,
+        );
+        let text = round_trip(&term);
+        assert!(text.starts_with("(let \""), "{}", text);
+    }
+    // Anything else that is an atom stays bare
+    for name in ["axis.str_len", "x'", "+", "__tmp1", "λ"] {
+        assert_eq!(round_trip(&var(name)), format!("(var {})\n", name));
+    }
+}
+
+// The guard is sized for the main thread's 8 MiB stack, larger than a test
+// thread's, so the deep inputs are parsed on a thread of that size
+#[test]
+fn test_nesting_is_limited() {
+    let parse = |source: String| {
+        std::thread::Builder::new()
+            .stack_size(8 << 20)
+            .spawn(move || parse_core_text(&source).map(|_| ()))
+            .unwrap()
+            .join()
+            .unwrap()
+    };
+    let nested = |form: &str, leaf: &str, depth: usize| format!("{}{}{}", form.repeat(depth), leaf, ")".repeat(depth));
+    assert!(parse(nested("(tuple ", "(unit)", 1023)).is_ok());
+    assert_eq!(parse(nested("(tuple ", "(unit)", 1024)).unwrap_err(), "1:7169: nesting exceeds 1024 levels");
+    let pattern = format!("(match (unit) (case {} (unit)))", nested("(ptuple ", "(punit)", 1023));
+    assert!(parse(pattern).unwrap_err().ends_with(": nesting exceeds 1024 levels"));
+}
@@ -1,17 +1,310 @@
 // Core IR validation for deterministic failure behavior
-use crate::runtime::CoreTerm;
+use crate::constructors::{split_qualified, Constructor, ConstructorTable, PRELUDE_VARIANTS};
+use crate::runtime::{CoreTerm, Pattern, Span};
 use crate::validation_registry;
-use crate::registry_loader::Registry;
-use std::collections::HashMap;
+use crate::registry_loader::{Capability, Registry, RegistryEntry};
+use crate::core_interface::{self, BundleInterface};
+use crate::diagnostics::{Diagnostic, Severity, SourceSpan};
+use crate::error_codes::ErrorCode;
+use crate::surface_parser;
+use crate::term_path;
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ValidationError {
+    // "AXnnnn: description" (error_codes.rs)
     pub message: String,
+    pub span: Option<Span>,
+    // Identifier the error is about, if any
+    pub name: Option<String>,
+    pub help: Vec<String>,
 }
 
 impl ValidationError {
     pub fn new(message: String) -> Self {
-        Self { message }
+        Self { message, span: None, name: None, help: Vec::new() }
+    }
+
+    // Suggests the closest names in scope or in the registry
+    fn unbound_var(name: &str, span: &Option<Span>, bound_vars: &HashMap<String, VarInfo>, registry: &Registry) -> Self {
+        if validation_registry::is_io_primitive(name) {
+            return ValidationError::io_unregistered(name, span);
+        }
+        let mut error = ValidationError::new(format!("{}: unbound variable `{}`", ErrorCode::UnboundVar, name));
+        error.span = span.clone();
+        error.name = Some(name.to_string());
+        let mut symbols = registry.symbol_index();
+        symbols.extend(bound_vars.keys().map(String::as_str));
+        error.help.push(symbols.did_you_mean(name).unwrap_or_else(|| {
+            format!("define `{}`, or pass the registry that declares it with --registries", name)
+        }));
+        error
+    }
+
+    // C1 for an IO primitive: the registries given (or the built-in prelude)
+    // do not declare it
+    fn io_unregistered(name: &str, span: &Option<Span>) -> Self {
+        let mut error = ValidationError::new(format!(
+            "{}: `{}` does IO, but no loaded registry declares it",
+            ErrorCode::IoUnregistered, name
+        ));
+        error.span = span.clone();
+        error.name = Some(name.to_string());
+        error.help.push("pass the registry that declares it, e.g. --registries registries/axis.axreg".to_string());
+        error.help.push("without --registries only the built-in prelude of pure primitives is loaded".to_string());
+        error
+    }
+
+    // `index` is the 0-based argument position
+    fn arg_type(entry: &RegistryEntry, index: usize, expected: &str, found: &str, span: &Option<Span>) -> Self {
+        let mut error = ValidationError::new(format!(
+            "{}: argument {} of `{}` is a {} literal, expected {}",
+            ErrorCode::ArgType, index + 1, entry.name, found, expected
+        ));
+        error.span = span.clone();
+        error.name = Some(entry.name.clone());
+        error.help.push(format!("the registry declares `{}`", entry.signature()));
+        error
+    }
+
+    // Points at the head when it has a span, else at the call
+    fn apply_non_function(func: &CoreTerm, call_span: &Option<Span>) -> Self {
+        let mut error = ValidationError::new(format!(
+            "{}: head={}",
+            ErrorCode::ApplyNonFunction, format_term_for_error(func)
+        ));
+        error.span = term_span(func).cloned().or_else(|| call_span.clone());
+        error
+    }
+
+    // Points at the target when it has a span, else at the call
+    fn spawn_target(target: &CoreTerm, call_span: &Option<Span>) -> Self {
+        let mut error = ValidationError::new(format!(
+            "{}: {} runs a top-level function, got {}",
+            ErrorCode::SpawnTarget,
+            SPAWN,
+            format_term_for_error(target)
+        ));
+        error.span = term_span(target).cloned().or_else(|| call_span.clone());
+        error.help.push(format!(
+            "move the code into a top-level function and pass what it needs as {}'s second argument",
+            SPAWN
+        ));
+        error
+    }
+
+    // Points at the function argument when it has a span, else at the call
+    fn function_arg(primitive: &str, function: &CoreTerm, call_span: &Option<Span>) -> Self {
+        let mut error = ValidationError::new(format!(
+            "{}: {} takes a top-level function or a lambda, got {}",
+            ErrorCode::FunctionArg,
+            primitive,
+            format_term_for_error(function)
+        ));
+        error.span = term_span(function).cloned().or_else(|| call_span.clone());
+        error.help.push("name the function itself; a local holding a function is not one".to_string());
+        error
+    }
+
+    fn proj_out_of_range(index: usize, arity: usize, span: &Option<Span>) -> Self {
+        let mut error = ValidationError::new(format!(
+            "{}: projection {} of a {}-tuple",
+            ErrorCode::ProjOutOfRange, index, arity
+        ));
+        error.span = span.clone();
+        error.help.push(format!("tuple fields are numbered from 0; the last one is {}", arity.saturating_sub(1)));
+        error
+    }
+
+    /// Without a span, point at the first use of the error's name in the
+    /// surface source it was compiled from
+    pub fn locate_name(&mut self, source: &str, file: &str) {
+        locate(&mut self.span, self.name.as_deref(), source, file);
+    }
+
+    /// For the diagnostics renderer. The CLI renders the Findings of a
+    /// compile; tests and library callers render single errors.
+    #[allow(dead_code)]
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Finding::from(self.clone()).to_diagnostic()
+    }
+}
+
+// The first use of `name` in the surface source, for a finding without a span
+fn locate(span: &mut Option<Span>, name: Option<&str>, source: &str, file: &str) {
+    if span.is_some() {
+        return;
+    }
+    if let Some((line, column)) = name.and_then(|name| surface_parser::find_identifier(source, name)) {
+        *span = Some(Span { file: file.to_string(), line, column });
+    }
+}
+
+fn label(code: Option<ErrorCode>) -> Option<String> {
+    let label = match code? {
+        ErrorCode::UnboundVar => "not defined in this program or its registries",
+        ErrorCode::IoUnregistered => "IO primitive not in the loaded registries",
+        ErrorCode::ApplyNonFunction => "this is not a function",
+        ErrorCode::ArgType => "called with an argument of the wrong type",
+        ErrorCode::Capability => "needs a capability that is not allowed",
+        ErrorCode::ProjOutOfRange => "no such tuple field",
+        ErrorCode::SpawnTarget => "not a top-level function",
+        ErrorCode::FunctionArg => "not a function",
+        ErrorCode::CtorArity => "wrong number of constructor fields",
+        ErrorCode::UnknownCtor => "not a variant of this type",
+        ErrorCode::NonExhaustiveMatch => "some variants are not matched",
+        ErrorCode::RefutableLetPattern => "some values do not match this pattern",
+        ErrorCode::DuplicateFunction => "defined again here",
+        ErrorCode::DuplicateFunctionIgnored => "this definition is ignored",
+        ErrorCode::UnusedBinding | ErrorCode::UnusedParameter => "never used",
+        ErrorCode::ShadowedBinding => "hides an earlier binding",
+        _ => return None,
+    };
+    Some(label.to_string())
+}
+
+/// One problem validation found. Errors block emission; warnings are
+/// printed and the build goes on.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    pub code: Option<ErrorCode>,
+    pub span: Option<Span>,
+    // The offending subterm's term path, e.g. `main/lam/app.arg`
+    pub offender: Option<String>,
+    // What is wrong, without the code
+    pub note: String,
+    // Identifier the finding is about, if any
+    pub name: Option<String>,
+    pub help: Vec<String>,
+}
+
+impl From<ValidationError> for Finding {
+    fn from(error: ValidationError) -> Self {
+        let (code, note) = match error.message.split_once(": ") {
+            Some((code, rest)) => match ErrorCode::parse(code) {
+                Some(code) => (Some(code), rest.to_string()),
+                None => (None, error.message.clone()),
+            },
+            None => (None, error.message.clone()),
+        };
+        let severity = match code {
+            Some(code) if code.is_warning() => Severity::Warning,
+            _ => Severity::Error,
+        };
+        Finding { severity, code, span: error.span, offender: None, note, name: error.name, help: error.help }
+    }
+}
+
+impl Finding {
+    /// "AXnnnn: note", as a ValidationError's message
+    pub fn message(&self) -> String {
+        match self.code {
+            Some(code) => format!("{}: {}", code, self.note),
+            None => self.note.clone(),
+        }
+    }
+
+    /// For the diagnostics renderer
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let mut diagnostic = match self.severity {
+            Severity::Warning => Diagnostic::warning(self.code, self.note.as_str()),
+            Severity::Error => Diagnostic::error(self.code, self.note.as_str()),
+        };
+        let width = self.name.as_ref().map_or(1, |name| name.chars().count());
+        diagnostic.span = self
+            .span
+            .as_ref()
+            .map(|s| SourceSpan::point(&s.file, s.line, s.column, width));
+        diagnostic.label = label(self.code);
+        if let Some(offender) = &self.offender {
+            diagnostic.notes.push(format!("in term {}", offender));
+        }
+        diagnostic.help = self.help.clone();
+        diagnostic
+    }
+}
+
+// Plain form: the message, then where, then the help
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())?;
+        if let Some(s) = &self.span {
+            write!(f, "\n  at {}:{}:{}", s.file, s.line, s.column)?;
+        }
+        if let Some(offender) = &self.offender {
+            write!(f, "\n  in term {}", offender)?;
+        }
+        for help in &self.help {
+            write!(f, "\n  help: {}", help)?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything validation found, errors and warnings alike
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    pub fn push(&mut self, finding: impl Into<Finding>) {
+        self.findings.push(finding.into());
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|finding| finding.severity == Severity::Error)
+    }
+
+    /// Place the findings without a span at the first use of their name in
+    /// the surface source
+    pub fn locate_names(&mut self, source: &str, file: &str) {
+        for finding in &mut self.findings {
+            locate(&mut finding.span, finding.name.as_deref(), source, file);
+        }
+    }
+
+    /// By file, line and column; findings without a span last, in the
+    /// order they were found
+    pub fn sort_by_location(&mut self) {
+        self.findings.sort_by(|a, b| match (&a.span, &b.span) {
+            (Some(a), Some(b)) => (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+    }
+}
+
+// One finding after another, for callers that fold the report into a String
+impl std::fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, finding) in self.findings.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", finding)?;
+        }
+        Ok(())
+    }
+}
+
+// Plain form, for callers that fold the error into a String
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(s) = &self.span {
+            write!(f, "\n  at {}:{}:{}", s.file, s.line, s.column)?;
+        }
+        for help in &self.help {
+            write!(f, "\n  help: {}", help)?;
+        }
+        Ok(())
     }
 }
 
@@ -19,26 +312,540 @@ impl ValidationError {
 enum VarInfo {
     Lambda,    // Variable is bound to a lambda
     NonLambda, // Variable is bound to a non-lambda value
+    Tuple(usize), // Variable is bound to a tuple literal of this arity
     Unknown,   // Variable binding is complex (could be lambda or not)
 }
 
+// What a Let binds, as far as the value's own shape tells
+fn binding_info(val: &CoreTerm) -> VarInfo {
+    match val {
+        CoreTerm::Lam(_, _, _) => VarInfo::Lambda,
+        CoreTerm::Tuple(elems, _) => VarInfo::Tuple(elems.len()),
+        CoreTerm::IntLit(_, _) | CoreTerm::BoolLit(_, _) | CoreTerm::UnitLit(_) |
+        CoreTerm::StrLit(_, _) | CoreTerm::Ctor(_, _, _) => VarInfo::NonLambda,
+        _ => VarInfo::Unknown, // Complex expressions
+    }
+}
+
+/// C4: Projection range - Proj(expr, i) with i >= the arity of expr, when
+/// expr is a tuple literal or a variable let-bound to one. Indices are
+/// 0-based (see CoreTerm::Proj).
+fn check_projection(tuple_expr: &CoreTerm, index: usize, span: &Option<Span>, bound_vars: &HashMap<String, VarInfo>) -> Result<(), ValidationError> {
+    let arity = match tuple_expr {
+        CoreTerm::Tuple(elems, _) => Some(elems.len()),
+        CoreTerm::Var(name, _) => match bound_vars.get(name.as_str()) {
+            Some(VarInfo::Tuple(arity)) => Some(*arity),
+            _ => None,
+        },
+        _ => None,
+    };
+    match arity {
+        Some(arity) if index >= arity => Err(ValidationError::proj_out_of_range(index, arity, span)),
+        _ => Ok(()),
+    }
+}
+
+/// C3: Foreign argument types - a literal passed to a foreign function whose
+/// version 2 registry entry declares another type for that parameter.
+/// Only literals are checked: Core carries no other type information.
+fn check_argument_type(func: &CoreTerm, arg: &CoreTerm, bound_vars: &HashMap<String, VarInfo>, registry: &Registry) -> Result<(), ValidationError> {
+    // Position of `arg` in the call: one per App between `func` and the head
+    let mut head = func;
+    let mut index = 0;
+    while let CoreTerm::App(inner, _, _) = head {
+        head = inner;
+        index += 1;
+    }
+    let CoreTerm::Var(name, _) = head else {
+        return Ok(());
+    };
+    if bound_vars.contains_key(name.as_str()) {
+        return Ok(());
+    }
+    let Some(entry) = registry.entries.get(name.as_str()) else {
+        return Ok(());
+    };
+    let Some(expected) = entry.params.as_ref().and_then(|params| params.get(index)) else {
+        return Ok(());
+    };
+    let found = match arg {
+        CoreTerm::IntLit(..) => "Int",
+        CoreTerm::StrLit(..) => "Str",
+        CoreTerm::BoolLit(..) => "Bool",
+        CoreTerm::UnitLit(..) => "Unit",
+        _ => return Ok(()),
+    };
+    // Other declared types (Any, List, ...) are not checked
+    if ["Int", "Str", "Bool", "Unit"].contains(&expected.as_str()) && expected != found {
+        return Err(ValidationError::arg_type(entry, index, expected, found, &term_span(arg).cloned()));
+    }
+    Ok(())
+}
+
+/// AX1501 (deprecated): registry functions the program uses that a version 2
+/// registry marks `deprecated`, one warning per function, sorted by name
+pub fn deprecation_warnings(term: &CoreTerm, registry: &Registry) -> Vec<ValidationError> {
+    core_interface::compute_interface(term, registry)
+        .imports
+        .iter()
+        .filter(|import| import.foreign)
+        .filter_map(|import| {
+            let entry = registry.entries.get(&import.name)?;
+            let note = entry.deprecated.as_ref()?;
+            let mut warning = ValidationError::new(format!("{}: `{}` is deprecated", ErrorCode::Deprecated, entry.name));
+            warning.name = Some(entry.name.clone());
+            warning.help.push(note.clone());
+            Some(warning)
+        })
+        .collect()
+}
+
+/// AX1008 (`--allow`): registry functions the program uses whose class
+/// tags or declared capabilities go beyond `allowed`. Reports the first by
+/// name; the help names the others and the --allow that admits them all.
+pub fn check_capabilities(term: &CoreTerm, registry: &Registry, allowed: &[Capability]) -> Result<(), ValidationError> {
+    let interface = core_interface::compute_interface(term, registry);
+    let mut needed: Vec<Capability> = allowed.to_vec();
+    let mut denied: Vec<(&RegistryEntry, Vec<Capability>)> = Vec::new();
+    for import in interface.imports.iter().filter(|import| import.foreign) {
+        let Some(entry) = registry.entries.get(&import.name) else {
+            continue;
+        };
+        let missing: Vec<Capability> = entry
+            .required_capabilities()
+            .into_iter()
+            .filter(|capability| !allowed.contains(capability))
+            .collect();
+        if !missing.is_empty() {
+            needed.extend(&missing);
+            denied.push((entry, missing));
+        }
+    }
+    let Some((entry, missing)) = denied.first() else {
+        return Ok(());
+    };
+
+    let names: Vec<&str> = missing.iter().map(|capability| capability.name()).collect();
+    let mut error = ValidationError::new(format!(
+        "{}: `{}` needs capability {}, which --allow does not grant",
+        ErrorCode::Capability,
+        entry.name,
+        names.join(", ")
+    ));
+    error.name = Some(entry.name.clone());
+    if denied.len() > 1 {
+        let others: Vec<String> = denied[1..].iter().map(|(entry, _)| format!("`{}`", entry.name)).collect();
+        error.help.push(format!("also outside --allow: {}", others.join(", ")));
+    }
+    needed.sort();
+    needed.dedup();
+    let all: Vec<&str> = needed.iter().map(|capability| capability.name()).collect();
+    error.help.push(format!("to permit these calls, compile with --allow {}", all.join(",")));
+    Err(error)
+}
+
 /// Validate Core IR and return deterministic error messages
 /// 
 /// Validation uses the CLI-loaded Registry as the sole authority.
 /// No filesystem access is permitted here.
 /// 
 /// Invariants:
+/// C5: Constructor usage - a Ctor term or constructor pattern of a declared
+/// type (constructors.rs) has that constructor's number of fields, and a
+/// name qualified by a declared type (`Shape::Circle`) is one of its variants
+/// C6: Exhaustiveness - a match whose arms are constructors of one declared
+/// type, without a catch-all arm, has an arm for every variant
+pub fn check_constructors(term: &CoreTerm, constructors: &ConstructorTable) -> Result<(), ValidationError> {
+    if constructors.is_empty() {
+        return Ok(());
+    }
+    match term {
+        CoreTerm::IntLit(..) | CoreTerm::BoolLit(..) | CoreTerm::UnitLit(..) | CoreTerm::StrLit(..) | CoreTerm::Var(..) => Ok(()),
+        CoreTerm::Ctor(name, fields, span) => {
+            check_constructor_use(name, fields.len(), span, constructors)?;
+            fields.iter().try_for_each(|field| check_constructors(field, constructors))
+        }
+        CoreTerm::Lam(_, body, _) => check_constructors(body, constructors),
+        CoreTerm::App(func, arg, _) => {
+            check_constructors(func, constructors)?;
+            check_constructors(arg, constructors)
+        }
+        CoreTerm::Tuple(elems, _) => elems.iter().try_for_each(|elem| check_constructors(elem, constructors)),
+        CoreTerm::Proj(expr, _, _) | CoreTerm::CtorField(expr, _, _) => check_constructors(expr, constructors),
+        CoreTerm::Let(_, value, body, _) => {
+            check_constructors(value, constructors)?;
+            check_constructors(body, constructors)
+        }
+        CoreTerm::If(cond, then_branch, else_branch, _) => {
+            check_constructors(cond, constructors)?;
+            check_constructors(then_branch, constructors)?;
+            check_constructors(else_branch, constructors)
+        }
+        CoreTerm::Match(scrutinee, arms, span) => {
+            check_constructors(scrutinee, constructors)?;
+            for (pattern, body) in arms {
+                check_pattern_constructors(pattern, span, constructors)?;
+                check_constructors(body, constructors)?;
+            }
+            check_exhaustive(arms, span, constructors)
+        }
+    }
+}
+
+fn check_constructor_use(name: &str, fields: usize, span: &Option<Span>, constructors: &ConstructorTable) -> Result<(), ValidationError> {
+    let (qualifier, bare) = split_qualified(name);
+    let declared = constructors.get(name);
+    if let Some(type_name) = qualifier.and_then(|q| constructors.type_named(q))
+        && declared.is_none_or(|c| c.type_name != type_name)
+    {
+        let mut error = ValidationError::new(format!("{}: `{}` has no variant `{}`", ErrorCode::UnknownCtor, type_name, bare));
+        error.span = span.clone();
+        error.name = Some(bare.to_string());
+        let variants: Vec<&str> = constructors.variants(type_name).map(|c| c.name.as_str()).collect();
+        error.help.push(format!("the variants of `{}` are {}", type_name, variants.join(", ")));
+        return Err(error);
+    }
+    match declared {
+        Some(constructor) if constructor.arity as usize != fields => {
+            let mut error = ValidationError::new(format!(
+                "{}: `{}` has {} field(s), found {}",
+                ErrorCode::CtorArity, constructor.name, constructor.arity, fields
+            ));
+            error.span = span.clone();
+            error.name = Some(bare.to_string());
+            error.help.push(format!("`{}` is a variant of `{}`", constructor.name, constructor.type_name));
+            Err(error)
+        }
+        _ => Ok(()),
+    }
+}
+
+// Patterns carry no spans of their own; errors point at the match
+fn check_pattern_constructors(pattern: &Pattern, span: &Option<Span>, constructors: &ConstructorTable) -> Result<(), ValidationError> {
+    match pattern {
+        Pattern::PEnum(name, fields) => {
+            check_constructor_use(name, fields.len(), span, constructors)?;
+            fields.iter().try_for_each(|field| check_pattern_constructors(field, span, constructors))
+        }
+        Pattern::PTuple(elems) => elems.iter().try_for_each(|elem| check_pattern_constructors(elem, span, constructors)),
+        Pattern::PBind(_, inner) => check_pattern_constructors(inner, span, constructors),
+        Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit | Pattern::PVar(_) => Ok(()),
+    }
+}
+
+// Matches every value of its type
+fn is_irrefutable(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::PVar(_) | Pattern::PUnit => true,
+        Pattern::PTuple(elems) => elems.iter().all(is_irrefutable),
+        Pattern::PBind(_, inner) => is_irrefutable(inner),
+        Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PEnum(..) => false,
+    }
+}
+
+fn check_exhaustive(arms: &[(Pattern, CoreTerm)], span: &Option<Span>, constructors: &ConstructorTable) -> Result<(), ValidationError> {
+    if arms.iter().any(|(pattern, _)| is_irrefutable(pattern)) {
+        return Ok(());
+    }
+    // Only matches over one declared type are checked
+    let mut matched_type: Option<&str> = None;
+    let mut covered: Vec<&Constructor> = Vec::new();
+    for (pattern, _) in arms {
+        let Pattern::PEnum(name, fields) = pattern.shape() else {
+            return Ok(());
+        };
+        let Some(constructor) = constructors.get(name) else {
+            return Ok(());
+        };
+        if matched_type.is_some_and(|t| t != constructor.type_name) {
+            return Ok(());
+        }
+        matched_type = Some(&constructor.type_name);
+        // An arm with a refutable field pattern does not cover its variant
+        if fields.iter().all(is_irrefutable) {
+            covered.push(constructor);
+        }
+    }
+    let Some(type_name) = matched_type else {
+        return Ok(());
+    };
+
+    let missing: Vec<&str> = constructors
+        .variants(type_name)
+        .filter(|variant| !covered.contains(variant))
+        .map(|variant| variant.name.as_str())
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let mut error = ValidationError::new(format!(
+        "{}: match on `{}` does not cover {}",
+        ErrorCode::NonExhaustiveMatch,
+        type_name,
+        missing.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>().join(", ")
+    ));
+    error.span = span.clone();
+    error.help.push("add an arm for each, or a catch-all `_` arm".to_string());
+    Err(error)
+}
+
+/// C7: Let patterns - `let <pattern> = <expr>;` without an `else` branch
+/// has a pattern every value matches: variables, tuples, and constructors
+/// of a type with one variant. Constructors of undeclared types are
+/// accepted, except the prelude's PRELUDE_VARIANTS.
+/// Patterns are only seen in the surface syntax, so this runs on the Module.
+pub fn check_let_patterns(module: &surface_parser::Module, constructors: &ConstructorTable) -> Result<(), ValidationError> {
+    module.functions.iter().try_for_each(|func| check_let_patterns_in(&func.body, constructors))
+}
+
+fn check_let_patterns_in(expr: &surface_parser::SurfaceExpr, constructors: &ConstructorTable) -> Result<(), ValidationError> {
+    use surface_parser::{SurfaceExpr, SurfaceStmt};
+    match expr {
+        SurfaceExpr::Block(stmts) => stmts.iter().try_for_each(|stmt| match stmt {
+            SurfaceStmt::Let(_, value) | SurfaceStmt::Expr(value) => check_let_patterns_in(value, constructors),
+            SurfaceStmt::LetPattern(pattern, value, else_expr) => {
+                check_let_patterns_in(value, constructors)?;
+                match else_expr {
+                    Some(else_expr) => check_let_patterns_in(else_expr, constructors),
+                    None => check_let_pattern(pattern, constructors),
+                }
+            }
+        }),
+        SurfaceExpr::Call(_, args) => args.iter().try_for_each(|arg| check_let_patterns_in(arg, constructors)),
+        SurfaceExpr::Proj(inner, _) => check_let_patterns_in(inner, constructors),
+        SurfaceExpr::Match(scrutinee, arms) => {
+            check_let_patterns_in(scrutinee, constructors)?;
+            arms.iter().try_for_each(|arm| check_let_patterns_in(&arm.expr, constructors))
+        }
+        SurfaceExpr::If { cond, then_branch, else_branch } => {
+            check_let_patterns_in(cond, constructors)?;
+            check_let_patterns_in(then_branch, constructors)?;
+            check_let_patterns_in(else_branch, constructors)
+        }
+        SurfaceExpr::IntLit(_) | SurfaceExpr::BoolLit(_) | SurfaceExpr::StringLit(_) | SurfaceExpr::UnitLit | SurfaceExpr::Ident(_) => Ok(()),
+    }
+}
+
+fn check_let_pattern(pattern_text: &str, constructors: &ConstructorTable) -> Result<(), ValidationError> {
+    let Some((name, reason)) = refutable_part(&crate::surface_to_core::parse_pattern(pattern_text), constructors) else {
+        return Ok(());
+    };
+    let mut error = ValidationError::new(format!(
+        "{}: `let {}` does not match every value: {}",
+        ErrorCode::RefutableLetPattern, pattern_text, reason
+    ));
+    error.name = Some(name);
+    error.help.push(format!("add an `else` branch for the other values: `let {} = ... else {{ ... }};`, or use `match`", pattern_text));
+    Err(error)
+}
+
+// The first part of a pattern some value of its type fails to match, and
+// why: (name to point at, reason)
+fn refutable_part(pattern: &Pattern, constructors: &ConstructorTable) -> Option<(String, String)> {
+    match pattern {
+        Pattern::PVar(_) | Pattern::PUnit => None,
+        Pattern::PInt(n) => Some((n.to_string(), format!("`{}` is one Int", n))),
+        Pattern::PBool(b) => Some((b.to_string(), format!("`{}` is one Bool", b))),
+        Pattern::PTuple(elems) => elems.iter().find_map(|elem| refutable_part(elem, constructors)),
+        Pattern::PBind(_, inner) => refutable_part(inner, constructors),
+        Pattern::PEnum(name, fields) => {
+            let (_, bare) = split_qualified(name);
+            let type_name = match constructors.get(name) {
+                Some(constructor) if constructors.variants(&constructor.type_name).count() > 1 => {
+                    Some(constructor.type_name.as_str())
+                }
+                Some(_) => None,
+                None => PRELUDE_VARIANTS.iter().find(|(ctor, _)| *ctor == bare).map(|(_, type_name)| *type_name),
+            };
+            match type_name {
+                Some(type_name) => Some((bare.to_string(), format!("`{}` is one variant of `{}`", bare, type_name))),
+                None => fields.iter().find_map(|field| refutable_part(field, constructors)),
+            }
+        }
+    }
+}
+
+// The primitive that runs a function on a new thread
+const SPAWN: &str = "axis_spawn";
+
+// The primitives that take a function as their first argument
+const FUNCTION_ARG_PRIMITIVES: [&str; 4] = ["axis_seq_map", "axis_seq_filter", "axis_seq_unfold", "axis_list_sort_by"];
+
+/// C8: Spawn targets - the function given to axis_spawn is a top-level
+/// function (or an import) named directly. A lambda or a local could
+/// capture values of the spawning thread; this way the new thread gets
+/// only the argument passed with it.
+/// C9: Function arguments - the function given to axis_seq_map,
+/// axis_seq_filter, axis_seq_unfold or axis_list_sort_by is a top-level
+/// function or a lambda, which the Rust backend can turn into a closure
+/// (AX1006)
+fn check_spawn_targets<'a>(term: &'a CoreTerm, top_level: &HashMap<String, VarInfo>, locals: &HashSet<String>, found: &mut Found<'a>) {
+    let is_function = |name: &str| {
+        !locals.contains(name) && matches!(top_level.get(name), Some(VarInfo::Lambda | VarInfo::Unknown))
+    };
+    let primitive = |func: &CoreTerm, names: &[&str]| match func {
+        CoreTerm::Var(name, _) if names.contains(&name.as_str()) && !locals.contains(name.as_str()) && !top_level.contains_key(name.as_str()) => {
+            Some(name.to_string())
+        }
+        _ => None,
+    };
+    match term {
+        CoreTerm::IntLit(..) | CoreTerm::BoolLit(..) | CoreTerm::UnitLit(..) | CoreTerm::StrLit(..) | CoreTerm::Var(..) => {}
+        CoreTerm::App(func, target, span) if primitive(func, &[SPAWN]).is_some() => {
+            match target.as_ref() {
+                CoreTerm::Var(name, _) if is_function(name) => {}
+                _ => found.push((ValidationError::spawn_target(target, span), target)),
+            }
+        }
+        CoreTerm::App(func, function, span) if primitive(func, &FUNCTION_ARG_PRIMITIVES).is_some() => {
+            match function.as_ref() {
+                CoreTerm::Var(name, _) if is_function(name) => {}
+                CoreTerm::Lam(..) => check_spawn_targets(function, top_level, locals, found),
+                _ => {
+                    let primitive = primitive(func, &FUNCTION_ARG_PRIMITIVES).unwrap_or_default();
+                    found.push((ValidationError::function_arg(&primitive, function, span), function));
+                }
+            }
+        }
+        CoreTerm::Ctor(_, elems, _) | CoreTerm::Tuple(elems, _) => {
+            elems.iter().for_each(|elem| check_spawn_targets(elem, top_level, locals, found))
+        }
+        CoreTerm::Lam(param, body, _) => {
+            let mut locals = locals.clone();
+            locals.insert(param.to_string());
+            check_spawn_targets(body, top_level, &locals, found)
+        }
+        CoreTerm::App(func, arg, _) => {
+            check_spawn_targets(func, top_level, locals, found);
+            check_spawn_targets(arg, top_level, locals, found)
+        }
+        CoreTerm::Proj(expr, _, _) | CoreTerm::CtorField(expr, _, _) => check_spawn_targets(expr, top_level, locals, found),
+        CoreTerm::Let(name, value, body, _) => {
+            check_spawn_targets(value, top_level, locals, found);
+            // Top-level Lets are in `top_level`; deeper ones are locals
+            if top_level.contains_key(name.as_str()) && locals.is_empty() {
+                return check_spawn_targets(body, top_level, locals, found);
+            }
+            let mut locals = locals.clone();
+            locals.insert(name.to_string());
+            check_spawn_targets(body, top_level, &locals, found)
+        }
+        CoreTerm::If(cond, then_branch, else_branch, _) => {
+            check_spawn_targets(cond, top_level, locals, found);
+            check_spawn_targets(then_branch, top_level, locals, found);
+            check_spawn_targets(else_branch, top_level, locals, found)
+        }
+        CoreTerm::Match(scrutinee, arms, _) => {
+            check_spawn_targets(scrutinee, top_level, locals, found);
+            for (pattern, body) in arms {
+                let mut locals = locals.clone();
+                pattern_binders(pattern, &mut locals);
+                check_spawn_targets(body, top_level, &locals, found);
+            }
+        }
+    }
+}
+
+pub(crate) fn pattern_binders(pattern: &Pattern, out: &mut HashSet<String>) {
+    match pattern {
+        Pattern::PVar(name) => {
+            out.insert(name.clone());
+        }
+        Pattern::PTuple(patterns) | Pattern::PEnum(_, patterns) => {
+            patterns.iter().for_each(|p| pattern_binders(p, out));
+        }
+        Pattern::PBind(name, inner) => {
+            out.insert(name.clone());
+            pattern_binders(inner, out);
+        }
+        Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit => {}
+    }
+}
+
 /// C1: Unbound variable detection - Any Var(name) not bound by Let or Lam is an error
+/// (AX1002 when it is an IO primitive, see is_io_primitive)
 /// C2: Application correctness - Reject App where function position is not a function
-pub fn validate_core(term: &CoreTerm, registry: &Registry) -> Result<(), ValidationError> {
+/// C3: see check_argument_type
+/// C4: see check_projection
+/// C8, C9: see check_spawn_targets
+///
+/// Every violation is an error of the report, which also carries the
+/// AX1501 deprecation warnings; it is sorted by location.
+pub fn validate_core(term: &CoreTerm, registry: &Registry) -> ValidationReport {
+    validate_core_with_imports(term, registry, &[])
+}
+
+/// Validate a separately compiled unit: `imports` are names another bundle
+/// will provide at link time, so they are treated as bound (shape unknown).
+pub fn validate_core_with_imports(term: &CoreTerm, registry: &Registry, imports: &[String]) -> ValidationReport {
     //  Pre-bind all top-level function names for mutual recursion
     // Scan through nested Let bindings at the top level and collect all names
-    let mut bound_vars = HashMap::new();    collect_top_level_bindings(term, &mut bound_vars);
-    
-    // Validate the entire term tree
-    validate_term(term, &bound_vars, registry)?;
-    
-    Ok(())
+    let mut bound_vars = HashMap::new();
+    for name in imports {
+        bound_vars.insert(name.clone(), VarInfo::Unknown);
+    }
+    collect_top_level_bindings(term, &mut bound_vars);
+
+    // Every error in the term tree, each with the subterm it is about
+    let mut found = Vec::new();
+    validate_term(term, &bound_vars, registry, &mut found);
+    check_spawn_targets(term, &bound_vars, &HashSet::new(), &mut found);
+
+    let mut report = ValidationReport::default();
+    for (error, offender) in found {
+        let mut finding = Finding::from(error);
+        finding.offender = term_path::path_of(term, offender);
+        report.push(finding);
+    }
+    for warning in deprecation_warnings(term, registry) {
+        report.push(warning);
+    }
+    report.sort_by_location();
+    report
+}
+
+/// Link-time manifest check over the bundles being linked, in link order.
+///
+/// L1: Every external (non-foreign) import must be exported by a linked bundle
+/// L2: A name must not be exported by more than one bundle
+/// L3: An import must name a public export
+pub fn validate_link(units: &[(&str, &BundleInterface)]) -> Result<(), ValidationError> {
+    let mut errors = Vec::new();
+    let mut exported_by: HashMap<&str, (&str, bool)> = HashMap::new();
+    for (path, interface) in units {
+        for export in &interface.exports {
+            if let Some((first, _)) = exported_by.get(export.name.as_str()) {
+                errors.push(format!(
+                    "{}: {} is exported by both {} and {}",
+                    ErrorCode::DuplicateExport, export.name, first, path
+                ));
+            } else {
+                exported_by.insert(&export.name, (path, export.public));
+            }
+        }
+    }
+
+    for (path, interface) in units {
+        for import in interface.external_imports() {
+            match exported_by.get(import.name.as_str()) {
+                None => errors.push(format!(
+                    "{}: {} (imported by {}) is not exported by any linked bundle",
+                    ErrorCode::UnresolvedImport, import.name, path
+                )),
+                Some((owner, false)) => errors.push(format!(
+                    "{}: {} (imported by {}) is private to {}; declare it `pub fn`",
+                    ErrorCode::PrivateImport, import.name, path, owner
+                )),
+                Some((_, true)) => {}
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::new(errors.join("\n")))
+    }
 }
 
 /// Collect all top-level Let bindings (assumes module structure is nested Lets)
@@ -47,13 +854,7 @@ fn collect_top_level_bindings(term: &CoreTerm, bound_vars: &mut HashMap<String,
     match term {
         CoreTerm::Let(name, val, body, _) => {
             // Determine what kind of value we're binding to
-            let var_info = match val.as_ref() {
-                CoreTerm::Lam(_, _, _) => VarInfo::Lambda,
-                CoreTerm::IntLit(_, _) | CoreTerm::BoolLit(_, _) | CoreTerm::UnitLit(_) | 
-                CoreTerm::StrLit(_, _) | CoreTerm::Tuple(_, _) | CoreTerm::Ctor(_, _, _) => VarInfo::NonLambda,
-                _ => VarInfo::Unknown,
-            };
-            bound_vars.insert(name.clone(), var_info);
+            bound_vars.insert(name.to_string(), binding_info(val));
             
             // Continue scanning through the Let chain
             collect_top_level_bindings(body, bound_vars);
@@ -64,200 +865,191 @@ fn collect_top_level_bindings(term: &CoreTerm, bound_vars: &mut HashMap<String,
     }
 }
 
-fn validate_term(term: &CoreTerm, bound_vars: &HashMap<String, VarInfo>, registry: &Registry) -> Result<(), ValidationError> {
+// Errors found so far, each with the subterm it is about
+type Found<'a> = Vec<(ValidationError, &'a CoreTerm)>;
+
+fn validate_term<'a>(term: &'a CoreTerm, bound_vars: &HashMap<String, VarInfo>, registry: &Registry, found: &mut Found<'a>) {
     match term {
-        CoreTerm::IntLit(_, _) | CoreTerm::BoolLit(_, _) | CoreTerm::UnitLit(_) | CoreTerm::StrLit(_, _) => {
-            Ok(())
-        }
+        CoreTerm::IntLit(_, _) | CoreTerm::BoolLit(_, _) | CoreTerm::UnitLit(_) | CoreTerm::StrLit(_, _) => {}
 
         CoreTerm::Ctor(_, fields, _) => {
             for field in fields {
-                validate_term(field, bound_vars, registry)?;
+                validate_term(field, bound_vars, registry, found);
             }
-            Ok(())
         }
         
         CoreTerm::Var(name, span) => {
             // Step 4: Resolution attempt logging
             if name == "axis_io_print" {
                 eprintln!("[RESOLVE] attempting axis_io_print");
-                eprintln!("[RESOLVE]   in bound_vars: {}", bound_vars.contains_key(name));
+                eprintln!("[RESOLVE]   in bound_vars: {}", bound_vars.contains_key(name.as_str()));
             }
             
             // C1: Use registry-based classification for foreign function resolution
-            if bound_vars.contains_key(name) {
-                // Variable is locally bound
-                Ok(())
-            } else if validation_registry::is_known_function(registry, name) {
-                // Known builtin or foreign function
-                Ok(())
-            } else {
-                // Unknown function
-                let mut msg = format!("E_UNBOUND_VAR: {}", name);
-                if let Some(s) = span {
-                    msg.push_str(&format!("\n  at {}:{}:{}", s.file, s.line, s.column));
-                }
-                return Err(ValidationError::new(msg));
+            // (locally bound, or a known builtin or foreign function)
+            if !bound_vars.contains_key(name.as_str()) && !validation_registry::is_known_function(registry, name) {
+                found.push((ValidationError::unbound_var(name, span, bound_vars, registry), term));
             }
         }
         
         CoreTerm::Lam(param, body, _) => {
             // Lambda binds the parameter to a Lambda
             let mut new_bound = bound_vars.clone();
-            new_bound.insert(param.clone(), VarInfo::Lambda);
-            validate_term(body, &new_bound, registry)
+            new_bound.insert(param.to_string(), VarInfo::Lambda);
+            validate_term(body, &new_bound, registry, found)
         }
         
-        CoreTerm::App(func, arg, _) => {
+        CoreTerm::App(func, arg, span) => {
             // First validate both subterms
             // For func, use special validation that doesn't check arity to avoid partial application errors
-            validate_term_no_arity(func, bound_vars, registry)?;
-            validate_term(arg, bound_vars, registry)?;
+            let before = found.len();
+            validate_term_no_arity(func, bound_vars, registry, found);
+            let func_valid = found.len() == before;
+            validate_term(arg, bound_vars, registry, found);
             
-            // C2: Check if function position can be applied
-            if !can_be_function(func, bound_vars, registry) {
-                let head_desc = format_term_for_error(func);
-                return Err(ValidationError::new(
-                    format!("E_APPLY_NON_FUNCTION: head={}", head_desc)
-                ));
+            // C2: Check if function position can be applied (an unbound
+            // head is already reported)
+            if func_valid {
+                check_application(func, arg, span, bound_vars, registry, found);
             }
-            
-            Ok(())
         }
         
         CoreTerm::Let(name, val, body, _) => {
             // First validate the value expression
-            validate_term(val, bound_vars, registry)?;
-            
-            // Determine what kind of value we're binding to
-            let var_info = match val.as_ref() {
-                CoreTerm::Lam(_, _, _) => VarInfo::Lambda,
-                CoreTerm::IntLit(_, _) | CoreTerm::BoolLit(_, _) | CoreTerm::UnitLit(_) | 
-                CoreTerm::StrLit(_, _) | CoreTerm::Tuple(_, _) | CoreTerm::Ctor(_, _, _) => VarInfo::NonLambda,
-                _ => VarInfo::Unknown, // Complex expressions
-            };
+            validate_term(val, bound_vars, registry, found);
             
             // Then validate body with the new binding
             let mut new_bound = bound_vars.clone();
-            new_bound.insert(name.clone(), var_info);
-            validate_term(body, &new_bound, registry)
+            new_bound.insert(name.to_string(), binding_info(val));
+            validate_term(body, &new_bound, registry, found)
         }
         
         CoreTerm::Tuple(elems, _) => {
             for elem in elems {
-                validate_term(elem, bound_vars, registry)?;
+                validate_term(elem, bound_vars, registry, found);
+            }
+        }
+
+        CoreTerm::Proj(tuple_expr, idx, span) => {
+            validate_term(tuple_expr, bound_vars, registry, found);
+            if let Err(error) = check_projection(tuple_expr, *idx, span, bound_vars) {
+                found.push((error, term));
             }
-            Ok(())
         }
 
-        CoreTerm::Proj(tuple_expr, _idx, _) => {
-            validate_term(tuple_expr, bound_vars, registry)
+        CoreTerm::CtorField(ctor_expr, _idx, _) => {
+            validate_term(ctor_expr, bound_vars, registry, found)
         }
 
         CoreTerm::If(cond, then_branch, else_branch, _) => {
-            validate_term(cond, bound_vars, registry)?;
-            validate_term(then_branch, bound_vars, registry)?;
-            validate_term(else_branch, bound_vars, registry)
+            validate_term(cond, bound_vars, registry, found);
+            validate_term(then_branch, bound_vars, registry, found);
+            validate_term(else_branch, bound_vars, registry, found)
         }
 
         CoreTerm::Match(scrutinee, _patterns, _) => {
-            validate_term(scrutinee, bound_vars, registry)?;
+            validate_term(scrutinee, bound_vars, registry, found);
             // For simplicity, we don't validate match patterns in Batch C
-            Ok(())
         }
     }
 }
 
 /// Validate a term without checking arity (used for function position validation to avoid partial application errors)
-fn validate_term_no_arity(term: &CoreTerm, bound_vars: &HashMap<String, VarInfo>, registry: &Registry) -> Result<(), ValidationError> {
+fn validate_term_no_arity<'a>(term: &'a CoreTerm, bound_vars: &HashMap<String, VarInfo>, registry: &Registry, found: &mut Found<'a>) {
     match term {
         CoreTerm::IntLit(_, _) | CoreTerm::BoolLit(_, _) | CoreTerm::UnitLit(_) | 
-        CoreTerm::StrLit(_, _) => Ok(()),
+        CoreTerm::StrLit(_, _) => {}
 
         CoreTerm::Ctor(_, fields, _) => {
             for field in fields {
-                validate_term_no_arity(field, bound_vars, registry)?;
+                validate_term_no_arity(field, bound_vars, registry, found);
             }
-            Ok(())
         }
         
         CoreTerm::Tuple(elements, _) => {
             for elem in elements {
-                validate_term_no_arity(elem, bound_vars, registry)?;
+                validate_term_no_arity(elem, bound_vars, registry, found);
             }
-            Ok(())
         }
         
         CoreTerm::Var(name, span) => {
-            if !bound_vars.contains_key(name) && !validation_registry::is_known_function(registry, name) {
-                let mut msg = format!("E_UNBOUND_VAR: {}", name);
-                if let Some(s) = span {
-                    msg.push_str(&format!("\n  at {}:{}:{}", s.file, s.line, s.column));
-                }
-                Err(ValidationError::new(msg))
-            } else {
-                Ok(())
+            if !bound_vars.contains_key(name.as_str()) && !validation_registry::is_known_function(registry, name) {
+                found.push((ValidationError::unbound_var(name, span, bound_vars, registry), term));
             }
         }
         
         CoreTerm::Lam(param, body, _) => {
             let mut new_bound = bound_vars.clone();
-            new_bound.insert(param.clone(), VarInfo::Lambda);
-            validate_term_no_arity(body, &new_bound, registry)
+            new_bound.insert(param.to_string(), VarInfo::Lambda);
+            validate_term_no_arity(body, &new_bound, registry, found)
         }
         
-        CoreTerm::App(func, arg, _) => {
+        CoreTerm::App(func, arg, span) => {
             // Recursively validate without arity checking
-            validate_term_no_arity(func, bound_vars, registry)?;
-            validate_term_no_arity(arg, bound_vars, registry)?;
+            let before = found.len();
+            validate_term_no_arity(func, bound_vars, registry, found);
+            let func_valid = found.len() == before;
+            validate_term_no_arity(arg, bound_vars, registry, found);
             
             // Check if function position can be applied
-            if !can_be_function(func, bound_vars, registry) {
-                let head_desc = format_term_for_error(func);
-                return Err(ValidationError::new(
-                    format!("E_APPLY_NON_FUNCTION: head={}", head_desc)
-                ));
-            }
-            
             // NO arity checking here - that's the whole point
-            Ok(())
+            if func_valid {
+                check_application(func, arg, span, bound_vars, registry, found);
+            }
         }
         
         CoreTerm::Let(name, val, body, _) => {
-            validate_term_no_arity(val, bound_vars, registry)?;
-            let var_info = match val.as_ref() {
-                CoreTerm::Lam(_, _, _) => VarInfo::Lambda,
-                CoreTerm::IntLit(_, _) | CoreTerm::BoolLit(_, _) | CoreTerm::UnitLit(_) | 
-                CoreTerm::StrLit(_, _) | CoreTerm::Tuple(_, _) | CoreTerm::Ctor(_, _, _) => VarInfo::NonLambda,
-                _ => VarInfo::Unknown,
-            };
+            validate_term_no_arity(val, bound_vars, registry, found);
             let mut new_bound = bound_vars.clone();
-            new_bound.insert(name.clone(), var_info);
-            validate_term_no_arity(body, &new_bound, registry)
+            new_bound.insert(name.to_string(), binding_info(val));
+            validate_term_no_arity(body, &new_bound, registry, found)
         }
         
-        CoreTerm::Proj(tuple_expr, _idx, _) => {
-            validate_term_no_arity(tuple_expr, bound_vars, registry)
+        CoreTerm::Proj(tuple_expr, idx, span) => {
+            validate_term_no_arity(tuple_expr, bound_vars, registry, found);
+            if let Err(error) = check_projection(tuple_expr, *idx, span, bound_vars) {
+                found.push((error, term));
+            }
+        }
+
+        CoreTerm::CtorField(ctor_expr, _idx, _) => {
+            validate_term_no_arity(ctor_expr, bound_vars, registry, found)
         }
         
         CoreTerm::If(cond, then_branch, else_branch, _) => {
-            validate_term_no_arity(cond, bound_vars, registry)?;
-            validate_term_no_arity(then_branch, bound_vars, registry)?;
-            validate_term_no_arity(else_branch, bound_vars, registry)
+            validate_term_no_arity(cond, bound_vars, registry, found);
+            validate_term_no_arity(then_branch, bound_vars, registry, found);
+            validate_term_no_arity(else_branch, bound_vars, registry, found)
         }
         
         CoreTerm::Match(scrutinee, _patterns, _) => {
-            validate_term_no_arity(scrutinee, bound_vars, registry)?;
-            Ok(())
+            validate_term_no_arity(scrutinee, bound_vars, registry, found);
         }
     }
 }
 
+// C2 and C3 for `func arg`, once `func` itself is valid
+fn check_application<'a>(
+    func: &'a CoreTerm,
+    arg: &'a CoreTerm,
+    span: &Option<Span>,
+    bound_vars: &HashMap<String, VarInfo>,
+    registry: &Registry,
+    found: &mut Found<'a>,
+) {
+    if !can_be_function(func, bound_vars, registry) {
+        found.push((ValidationError::apply_non_function(func, span), func));
+    } else if let Err(error) = check_argument_type(func, arg, bound_vars, registry) {
+        found.push((error, arg));
+    }
+}
+
 /// Check if a term can be applied as a function
 /// Returns true for:
 /// - Lam (lambda expressions) 
 /// - Var that is bound to a Lambda
 /// - Var that refers to a known builtin/foreign function ( ONLY)
+///
 /// Returns false for:
 /// - IntLit, BoolLit, UnitLit, StrLit, Tuple, etc.
 /// - Var that is bound to a NonLambda
@@ -265,9 +1057,9 @@ fn can_be_function(term: &CoreTerm, bound_vars: &HashMap<String, VarInfo>, regis
     match term {
         CoreTerm::Lam(_, _, _) => true,
         CoreTerm::Var(name, _) => {
-            match bound_vars.get(name) {
+            match bound_vars.get(name.as_str()) {
                 Some(VarInfo::Lambda) => true,
-                Some(VarInfo::NonLambda) => false,
+                Some(VarInfo::NonLambda) | Some(VarInfo::Tuple(_)) => false,
                 Some(VarInfo::Unknown) => true, // Be conservative for complex cases
                 None => {
                     //  ONLY: Allow known builtins to be called
@@ -281,11 +1073,30 @@ fn can_be_function(term: &CoreTerm, bound_vars: &HashMap<String, VarInfo>, regis
         // For more complex terms, be conservative and allow them
         // (they might evaluate to functions)
         CoreTerm::App(_, _, _) | CoreTerm::Let(_, _, _, _) | CoreTerm::If(_, _, _, _) | 
-        CoreTerm::Proj(_, _, _) | CoreTerm::Match(_, _, _) => true,
+        CoreTerm::Proj(_, _, _) | CoreTerm::CtorField(_, _, _) | CoreTerm::Match(_, _, _) => true,
     }
 }
 
 /// Format a term for error messages (showing only the top-level structure)
+fn term_span(term: &CoreTerm) -> Option<&Span> {
+    match term {
+        CoreTerm::IntLit(_, span)
+        | CoreTerm::BoolLit(_, span)
+        | CoreTerm::UnitLit(span)
+        | CoreTerm::StrLit(_, span)
+        | CoreTerm::Var(_, span)
+        | CoreTerm::Ctor(_, _, span)
+        | CoreTerm::Lam(_, _, span)
+        | CoreTerm::App(_, _, span)
+        | CoreTerm::Let(_, _, _, span)
+        | CoreTerm::Tuple(_, span)
+        | CoreTerm::Proj(_, _, span)
+        | CoreTerm::CtorField(_, _, span)
+        | CoreTerm::If(_, _, _, span)
+        | CoreTerm::Match(_, _, span) => span.as_ref(),
+    }
+}
+
 fn format_term_for_error(term: &CoreTerm) -> String {
     match term {
         CoreTerm::IntLit(n, _) => format!("IntLit({})", n),
@@ -302,6 +1113,7 @@ fn format_term_for_error(term: &CoreTerm) -> String {
         CoreTerm::Let(name, _, _, _) => format!("Let({}, <val>, <body>)", name),
         CoreTerm::Tuple(_, _) => "Tuple(<...>)".to_string(),
         CoreTerm::Proj(_, idx, _) => format!("Proj(<tuple>, {})", idx),
+        CoreTerm::CtorField(_, idx, _) => format!("CtorField(<ctor>, {})", idx),
         CoreTerm::If(_, _, _, _) => "If(<cond>, <then>, <else>)".to_string(),
         CoreTerm::Match(_, _, _) => "Match(<scrutinee>, <arms>)".to_string(),
     }
@@ -324,7 +1136,7 @@ fn extract_function_call(term: &CoreTerm) -> Option<(String, u32)> {
     // Check if the head is a Var (function name)
     if let CoreTerm::Var(name, _) = current {
 
-        Some((name.clone(), args))
+        Some((name.to_string(), args))
     } else {
         None
     }
@@ -0,0 +1,152 @@
+// Tests for the Core validator rules (core_validator.rs)
+
+use crate::core_validator::{validate_core, ValidationError};
+use crate::diagnostics::Severity;
+use crate::registry_loader::Registry;
+use crate::runtime::{CoreTerm, Span};
+use std::rc::Rc;
+
+fn ints(values: &[i64]) -> CoreTerm {
+    CoreTerm::Tuple(values.iter().map(|n| CoreTerm::IntLit(*n, None)).collect(), None)
+}
+
+fn proj(expr: CoreTerm, index: usize) -> CoreTerm {
+    CoreTerm::Proj(Rc::new(expr), index, None)
+}
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn let_in(name: &str, value: CoreTerm, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Let(name.into(), Rc::new(value), Rc::new(body), None)
+}
+
+fn lam(param: &str, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Lam(param.into(), Rc::new(body), None)
+}
+
+#[test]
+fn test_last_field_of_a_tuple_literal_is_in_range() {
+    assert!(!validate_core(&proj(ints(&[1, 2]), 1), &Registry::new()).has_errors());
+    assert!(!validate_core(&proj(ints(&[1, 2]), 0), &Registry::new()).has_errors());
+}
+
+#[test]
+fn test_projection_past_a_tuple_literal_is_rejected() {
+    let error = validate_core(&proj(ints(&[1, 2]), 2), &Registry::new()).errors().next().cloned().expect("a pair has no field 2");
+    assert_eq!(error.message(), "AX1007: projection 2 of a 2-tuple");
+    assert_eq!(error.help, vec!["tuple fields are numbered from 0; the last one is 1".to_string()]);
+}
+
+#[test]
+fn test_projection_through_a_let_bound_tuple_is_checked() {
+    let term = let_in("p", ints(&[1, 2, 3]), proj(var("p"), 3));
+    let error = validate_core(&term, &Registry::new()).errors().next().cloned().expect("a triple has no field 3");
+    assert!(error.message().starts_with("AX1007: "), "{}", error);
+
+    assert!(!validate_core(&let_in("p", ints(&[1, 2, 3]), proj(var("p"), 2)), &Registry::new()).has_errors());
+}
+
+#[test]
+fn test_projection_of_unknown_arity_is_left_to_runtime() {
+    // A parameter's arity is unknown, and so is a name rebound by a lambda
+    assert!(!validate_core(&lam("t", proj(var("t"), 7)), &Registry::new()).has_errors());
+    let shadowed = let_in("p", ints(&[1]), lam("p", proj(var("p"), 4)));
+    assert!(!validate_core(&shadowed, &Registry::new()).has_errors());
+}
+
+fn app(func: CoreTerm, args: Vec<CoreTerm>) -> CoreTerm {
+    args.into_iter().fold(func, |f, arg| CoreTerm::App(Rc::new(f), Rc::new(arg), None))
+}
+
+// work(x) = x; axis_entry(args) = axis_join(<spawn>)
+fn spawn_program(spawn: CoreTerm) -> CoreTerm {
+    let entry = lam("args", app(var("axis_join"), vec![spawn]));
+    let_in("work", lam("x", var("x")), let_in("axis_entry", entry, CoreTerm::UnitLit(None)))
+}
+
+#[test]
+fn test_spawn_takes_a_top_level_function() {
+    let one = CoreTerm::IntLit(1, None);
+    let program = spawn_program(app(var("axis_spawn"), vec![var("work"), one.clone()]));
+    assert!(!validate_core(&program, &Registry::prelude()).has_errors());
+
+    let lambda = spawn_program(app(var("axis_spawn"), vec![lam("y", var("args")), one.clone()]));
+    let error = validate_core(&lambda, &Registry::prelude()).errors().next().cloned().expect("a lambda could capture `args`");
+    assert_eq!(error.message(), "AX1005: axis_spawn runs a top-level function, got Lam(y, <body>)");
+
+    // A local of the same name is not the top-level function
+    let local = spawn_program(let_in("work", lam("y", var("args")), app(var("axis_spawn"), vec![var("work"), one])));
+    let error = validate_core(&local, &Registry::prelude()).errors().next().cloned().expect("`work` is a local here");
+    assert!(error.message().starts_with("AX1005: "), "{}", error);
+}
+
+// work(x) = x; axis_entry(args) = axis_seq_to_list(<seq>)
+fn seq_program(seq: CoreTerm) -> CoreTerm {
+    let entry = lam("args", app(var("axis_seq_to_list"), vec![seq]));
+    let_in("work", lam("x", var("x")), let_in("axis_entry", entry, CoreTerm::UnitLit(None)))
+}
+
+#[test]
+fn test_sequence_primitives_take_a_function_or_a_lambda() {
+    let items = || app(var("axis_seq_from_list"), vec![var("args")]);
+    let named = seq_program(app(var("axis_seq_map"), vec![var("work"), items()]));
+    assert!(!validate_core(&named, &Registry::prelude()).has_errors());
+    let lambda = seq_program(app(var("axis_seq_filter"), vec![lam("y", var("args")), items()]));
+    assert!(!validate_core(&lambda, &Registry::prelude()).has_errors());
+
+    let local = seq_program(let_in("f", lam("y", var("y")), app(var("axis_seq_map"), vec![var("f"), items()])));
+    let error = validate_core(&local, &Registry::prelude()).errors().next().cloned().expect("`f` is a local");
+    assert_eq!(error.message(), "AX1006: axis_seq_map takes a top-level function or a lambda, got Var(f)");
+
+    let sort = |cmp: CoreTerm| let_in("f", lam("y", var("y")), app(var("axis_list_sort_by"), vec![cmp, var("args")]));
+    let sorted = seq_program(sort(lam("a", lam("b", app(var("axis_value_cmp"), vec![var("a"), var("b")])))));
+    assert!(!validate_core(&sorted, &Registry::prelude()).has_errors());
+    let error = validate_core(&seq_program(sort(var("f"))), &Registry::prelude()).errors().next().cloned().expect("`f` is a local");
+    assert!(error.message().starts_with("AX1006: axis_list_sort_by "), "{}", error);
+}
+
+fn var_at(name: &str, line: usize) -> CoreTerm {
+    CoreTerm::Var(name.into(), Some(Span { file: "main.ax".to_string(), line, column: 5 }))
+}
+
+#[test]
+fn test_every_error_is_reported_with_its_term() {
+    // main(x) = nope((1,).3)
+    let program = let_in("main", lam("x", app(var("nope"), vec![proj(ints(&[1]), 3)])), CoreTerm::UnitLit(None));
+    let report = validate_core(&program, &Registry::new());
+    let found: Vec<(String, Option<&str>)> = report
+        .errors()
+        .map(|error| (error.message(), error.offender.as_deref()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("AX1001: unbound variable `nope`".to_string(), Some("main/lam/app.func")),
+            ("AX1007: projection 3 of a 1-tuple".to_string(), Some("main/lam/app.arg")),
+        ]
+    );
+    // The head is unbound, so it is not also reported as not a function
+    assert_eq!(report.findings.len(), 2);
+}
+
+#[test]
+fn test_findings_are_sorted_by_location() {
+    let program = CoreTerm::Tuple(vec![var_at("late", 9), var("nowhere"), var_at("early", 2)], None);
+    let report = validate_core(&program, &Registry::new());
+    let names: Vec<&str> = report.findings.iter().filter_map(|finding| finding.name.as_deref()).collect();
+    assert_eq!(names, vec!["early", "late", "nowhere"]);
+    assert!(report.to_string().starts_with("AX1001: unbound variable `early`\n  at main.ax:2:5\n  in term /tuple[2]\n"), "{}", report);
+}
+
+#[test]
+fn test_a_report_of_warnings_does_not_block() {
+    let mut report = validate_core(&lam("t", var("t")), &Registry::new());
+    let mut warning = ValidationError::new("AX1501: `old` is deprecated".to_string());
+    warning.help.push("use `new`".to_string());
+    report.push(warning);
+    assert!(!report.has_errors());
+    assert_eq!(report.findings[0].severity, Severity::Warning);
+    assert_eq!(report.findings[0].note, "`old` is deprecated");
+}
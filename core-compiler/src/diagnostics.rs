@@ -0,0 +1,450 @@
+// Compiler diagnostics rendering
+//
+// Parse and validation errors are turned into a Diagnostic and printed with
+// the source they point at:
+//
+//   error[AX1001]: unbound variable `lst_cons`
+//    --> src/main.ax:3:5
+//     |
+//   3 |     lst_cons(1, xs)
+//     |     ^^^^^^^^ not defined in this program or its registries
+//     |
+//     = help: ...
+//
+// Warnings (AX15xx codes) are rendered the same way, titled `warning`.
+// With --error-format json each diagnostic is instead one line of JSON,
+// for editors and CI tools.
+//
+// A span may cover several lines; each covered line is underlined, and
+// spans longer than MAX_SNIPPET_LINES show their first and last lines.
+//
+// POLICY: color only when asked for (--color always) or when stderr is a
+// terminal and NO_COLOR is unset or empty (https://no-color.org).
+
+use std::io::IsTerminal;
+use crate::error_codes::ErrorCode;
+use crate::surface_parser::{ParseError, ParseErrorKind};
+
+const MAX_SNIPPET_LINES: usize = 6;
+
+// ANSI styles (SGR parameters)
+const STYLE_ERROR: &str = "1;31";
+const STYLE_WARNING: &str = "1;33";
+const STYLE_GUTTER: &str = "1;34";
+const STYLE_HELP: &str = "1;36";
+const STYLE_BOLD: &str = "1";
+
+/// Columns are 1-based characters; `end_column` is just past the last one
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSpan {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl SourceSpan {
+    /// `width` characters of one line
+    pub fn point(file: &str, line: usize, column: usize, width: usize) -> Self {
+        SourceSpan {
+            file: file.to_string(),
+            line,
+            column,
+            end_line: line,
+            end_column: column + width.max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable error code, e.g. AX1001 (error_codes.rs)
+    pub code: Option<ErrorCode>,
+    pub message: String,
+    pub span: Option<SourceSpan>,
+    /// Printed after the underline
+    pub label: Option<String>,
+    /// Printed before the help, e.g. the term a validation finding is in
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: Option<ErrorCode>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            span: None,
+            label: None,
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    pub fn warning(code: Option<ErrorCode>, message: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Warning, ..Diagnostic::error(code, message) }
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        let span = Some(SourceSpan::point(&error.file, error.line, error.column, error.width));
+        match &error.kind {
+            ParseErrorKind::Unexpected { expected, found } => {
+                let mut diagnostic = Diagnostic::error(Some(ErrorCode::Parse), format!("expected '{}', found '{}'", expected, found));
+                diagnostic.span = span;
+                if found == "EOF" || found.starts_with("end of input") {
+                    diagnostic.help.push("the input ends early; check for an unclosed '{' or '('".to_string());
+                } else if expected == "else" {
+                    diagnostic.help.push("an `if` without `else` is a statement; where its value is used, it needs an `else`".to_string());
+                }
+                diagnostic
+            }
+            ParseErrorKind::Keyword { keyword, expected } => {
+                let mut diagnostic = Diagnostic::error(Some(ErrorCode::Keyword), format!("expected {}, found keyword `{}`", expected, keyword));
+                diagnostic.span = span;
+                if *expected != "expression" {
+                    diagnostic.help.push(format!("keywords cannot be names; rename it, e.g. `{}_`", keyword));
+                }
+                diagnostic
+            }
+            ParseErrorKind::Lexical { message, .. } => {
+                let mut diagnostic = Diagnostic::error(Some(ErrorCode::Lex), message.clone());
+                diagnostic.span = span;
+                if message == "unterminated string literal" {
+                    diagnostic.help.push("strings may span lines; add the closing '\"'".to_string());
+                }
+                diagnostic
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Result<ColorChoice, String> {
+        match value {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => Err(format!("unknown color mode '{}' (expected auto, always or never)", other)),
+        }
+    }
+
+    /// Whether diagnostics written to stderr get ANSI colors
+    pub fn use_color(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && std::io::stderr().is_terminal()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+impl ErrorFormat {
+    pub fn parse(value: &str) -> Result<ErrorFormat, String> {
+        match value {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            other => Err(format!("unknown error format '{}' (expected human or json)", other)),
+        }
+    }
+}
+
+/// How diagnostics are written to stderr (--error-format, --color)
+#[derive(Debug, Clone, Copy)]
+pub struct Emitter {
+    pub format: ErrorFormat,
+    pub color: bool,
+}
+
+impl Emitter {
+    pub fn render(&self, diagnostic: &Diagnostic, sources: &SourceMap) -> String {
+        match self.format {
+            ErrorFormat::Human => render(diagnostic, sources, self.color),
+            ErrorFormat::Json => render_json(diagnostic, sources),
+        }
+    }
+}
+
+// ============================================================================
+// Source text
+// ============================================================================
+
+struct SourceFile {
+    // Name spans use for this text: sources are concatenated before parsing,
+    // so every span names the first file
+    origin: String,
+    // Real file name; `lines` start at line `first_line` of `origin` and
+    // line `first_local` of `name`
+    name: String,
+    first_line: usize,
+    first_local: usize,
+    lines: Vec<String>,
+}
+
+/// Source lines by file, for snippets
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    /// `files` joined with a newline after each, as the compiler parses them;
+    /// spans into the joined text are shown in the file they came from
+    pub fn concatenated(files: &[(String, String)]) -> Self {
+        let Some((origin, _)) = files.first() else {
+            return SourceMap::default();
+        };
+        let mut first_line = 1;
+        let mut map = SourceMap::default();
+        for (name, text) in files {
+            let mut lines: Vec<String> = format!("{}\n", text).split('\n').map(str::to_string).collect();
+            // The text after the last newline is the next file's first line
+            let count = lines.len() - 1;
+            lines.truncate(count);
+            map.files.push(SourceFile { origin: origin.clone(), name: name.clone(), first_line, first_local: 1, lines });
+            first_line += count;
+        }
+        map
+    }
+
+    /// Separate files; spans name the file itself
+    pub fn add_file(&mut self, name: &str, text: &str) {
+        self.files.push(SourceFile {
+            origin: name.to_string(),
+            name: name.to_string(),
+            first_line: 1,
+            first_local: 1,
+            lines: text.split('\n').map(str::to_string).collect(),
+        });
+    }
+
+    /// Just line `line` of `file`
+    pub fn single_line(file: &str, line: usize, text: &str) -> Self {
+        SourceMap {
+            files: vec![SourceFile {
+                origin: file.to_string(),
+                name: file.to_string(),
+                first_line: line,
+                first_local: line,
+                lines: vec![text.to_string()],
+            }],
+        }
+    }
+
+    /// `file:line:column` in the file the line came from
+    pub fn location(&self, file: &str, line: usize, column: usize) -> String {
+        match self.resolve(file, line) {
+            Some((name, local, _)) => format!("{}:{}:{}", name, local, column),
+            None => format!("{}:{}:{}", file, line, column),
+        }
+    }
+
+    // (real file name, line within it, text) for a line as spans name it
+    fn resolve(&self, file: &str, line: usize) -> Option<(&str, usize, &str)> {
+        self.files.iter().find_map(|source| {
+            let index = line.checked_sub(source.first_line)?;
+            let text = source.lines.get(index).filter(|_| source.origin == file)?;
+            Some((source.name.as_str(), source.first_local + index, text.as_str()))
+        })
+    }
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color && !text.is_empty() {
+            format!("\x1b[{}m{}\x1b[0m", style, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// The diagnostic as printed, ending in a newline
+pub fn render(diagnostic: &Diagnostic, sources: &SourceMap, color: bool) -> String {
+    let painter = Painter { color };
+    let mut out = String::new();
+    let (kind, style) = match diagnostic.severity {
+        Severity::Error => ("error", STYLE_ERROR),
+        Severity::Warning => ("warning", STYLE_WARNING),
+    };
+    let title = match diagnostic.code {
+        Some(code) => format!("{}[{}]", kind, code),
+        None => kind.to_string(),
+    };
+    out.push_str(&format!(
+        "{}{}\n",
+        painter.paint(style, &title),
+        painter.paint(STYLE_BOLD, &format!(": {}", diagnostic.message))
+    ));
+
+    // Snippet lines: (shown line number, text, underline start, underline end)
+    let mut snippet: Vec<Option<(usize, &str, usize, usize)>> = Vec::new();
+    let mut location = None;
+    if let Some(span) = &diagnostic.span {
+        let last = span.end_line.max(span.line);
+        location = Some(match sources.resolve(&span.file, span.line) {
+            Some((name, line, _)) => format!("{}:{}:{}", name, line, span.column),
+            None => format!("{}:{}:{}", span.file, span.line, span.column),
+        });
+        for line in span.line..=last {
+            let Some((_, shown, text)) = sources.resolve(&span.file, line) else {
+                continue;
+            };
+            let start = if line == span.line {
+                span.column
+            } else {
+                text.chars().take_while(|c| c.is_whitespace()).count() + 1
+            };
+            let end = if line == last { span.end_column } else { text.chars().count() + 1 };
+            snippet.push(Some((shown, text, start, end.max(start + 1))));
+        }
+        if snippet.len() > MAX_SNIPPET_LINES {
+            let tail = snippet.split_off(snippet.len() - 2);
+            snippet.truncate(MAX_SNIPPET_LINES - 3);
+            snippet.push(None);
+            snippet.extend(tail);
+        }
+    }
+
+    let width = snippet.iter().flatten().map(|(line, ..)| line.to_string().len()).max().unwrap_or(0);
+    let pad = " ".repeat(width);
+    let bar = painter.paint(STYLE_GUTTER, "|");
+    if let Some(location) = location {
+        out.push_str(&format!("{}{} {}\n", pad, painter.paint(STYLE_GUTTER, "-->"), location));
+    }
+    if !snippet.is_empty() {
+        out.push_str(&format!("{} {}\n", pad, bar));
+        let count = snippet.len();
+        for (i, entry) in snippet.iter().enumerate() {
+            let Some((line, text, start, end)) = entry else {
+                out.push_str(&format!("{}\n", painter.paint(STYLE_GUTTER, "...")));
+                continue;
+            };
+            let number = painter.paint(STYLE_GUTTER, &format!("{:>width$}", line, width = width));
+            out.push_str(&format!("{} {} {}\n", number, bar, text));
+            // Keep tabs so the carets line up with the text above
+            let indent: String = text
+                .chars()
+                .chain(std::iter::repeat(' '))
+                .take(start - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let carets = painter.paint(style, &"^".repeat(end - start));
+            let label = match &diagnostic.label {
+                Some(label) if i + 1 == count => format!(" {}", painter.paint(style, label)),
+                _ => String::new(),
+            };
+            out.push_str(&format!("{} {} {}{}{}\n", pad, bar, indent, carets, label));
+        }
+    }
+    if !diagnostic.notes.is_empty() || !diagnostic.help.is_empty() {
+        if !snippet.is_empty() {
+            out.push_str(&format!("{} {}\n", pad, bar));
+        }
+        for note in &diagnostic.notes {
+            out.push_str(&format!("{} = {} {}\n", pad, painter.paint(STYLE_BOLD, "note:"), note));
+        }
+        for help in &diagnostic.help {
+            out.push_str(&format!("{} = {} {}\n", pad, painter.paint(STYLE_HELP, "help:"), help));
+        }
+    }
+    out
+}
+
+/// The diagnostic as one line of JSON, ending in a newline. Absent fields
+/// are null; the span names the real file and its lines, as `-->` does:
+///
+///   {"severity":"error","code":"AX1001","name":"E_UNBOUND_VAR",
+///    "message":"...","span":{"file":"src/main.ax","line":3,"column":5,
+///    "end_line":3,"end_column":13},"label":"...","notes":["..."],
+///    "help":["..."]}
+pub fn render_json(diagnostic: &Diagnostic, sources: &SourceMap) -> String {
+    let severity = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let optional = |value: Option<&str>| value.map_or("null".to_string(), json_string);
+    let span = match &diagnostic.span {
+        Some(span) => {
+            let (file, line) = match sources.resolve(&span.file, span.line) {
+                Some((name, line, _)) => (name, line),
+                None => (span.file.as_str(), span.line),
+            };
+            let end_line = line + span.end_line.saturating_sub(span.line);
+            format!(
+                "{{\"file\":{},\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{}}}",
+                json_string(file),
+                line,
+                span.column,
+                end_line,
+                span.end_column
+            )
+        }
+        None => "null".to_string(),
+    };
+    let notes: Vec<String> = diagnostic.notes.iter().map(|note| json_string(note)).collect();
+    let help: Vec<String> = diagnostic.help.iter().map(|help| json_string(help)).collect();
+    format!(
+        "{{\"severity\":\"{}\",\"code\":{},\"name\":{},\"message\":{},\"span\":{},\"label\":{},\"notes\":[{}],\"help\":[{}]}}\n",
+        severity,
+        optional(diagnostic.code.map(ErrorCode::code)),
+        optional(diagnostic.code.map(ErrorCode::name)),
+        json_string(&diagnostic.message),
+        span,
+        optional(diagnostic.label.as_deref()),
+        notes.join(","),
+        help.join(",")
+    )
+}
+
+/// `s` as a JSON string literal
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,162 @@
+// Tests for diagnostics rendering (diagnostics.rs), and for parse and
+// validation errors rendered through it
+
+use crate::diagnostics::{render, render_json, ColorChoice, Diagnostic, Emitter, ErrorFormat, SourceMap, SourceSpan};
+use crate::error_codes::ErrorCode;
+use crate::registry_loader::Registry;
+use crate::{core_validator, surface_lower, surface_parser, surface_to_core};
+
+fn sources(files: &[(&str, &str)]) -> SourceMap {
+    let files: Vec<(String, String)> = files.iter().map(|(n, t)| (n.to_string(), t.to_string())).collect();
+    SourceMap::concatenated(&files)
+}
+
+#[test]
+fn test_render_single_line() {
+    let map = sources(&[("main.ax", "fn main() {\n    lst_cons(1, xs)\n}")]);
+    let mut diagnostic = Diagnostic::error(Some(ErrorCode::UnboundVar), "unbound variable `lst_cons`");
+    diagnostic.span = Some(SourceSpan::point("main.ax", 2, 5, 8));
+    diagnostic.label = Some("not defined".to_string());
+    diagnostic.help.push("did you mean `list_cons`?".to_string());
+
+    assert_eq!(
+        render(&diagnostic, &map, false),
+        "error[AX1001]: unbound variable `lst_cons`\n\
+         \x20--> main.ax:2:5\n\
+         \x20 |\n\
+         2 |     lst_cons(1, xs)\n\
+         \x20 |     ^^^^^^^^ not defined\n\
+         \x20 |\n\
+         \x20 = help: did you mean `list_cons`?\n"
+    );
+}
+
+#[test]
+fn test_render_multi_line_span() {
+    let text = "fn f() {\n  let x = g(\n    1,\n    2);\n  x\n}";
+    let map = sources(&[("f.ax", text)]);
+    let mut diagnostic = Diagnostic::error(None, "bad call");
+    diagnostic.span = Some(SourceSpan { file: "f.ax".to_string(), line: 2, column: 11, end_line: 4, end_column: 7 });
+
+    let rendered = render(&diagnostic, &map, false);
+    assert_eq!(
+        rendered,
+        "error: bad call\n\
+         \x20--> f.ax:2:11\n\
+         \x20 |\n\
+         2 |   let x = g(\n\
+         \x20 |           ^^\n\
+         3 |     1,\n\
+         \x20 |     ^^\n\
+         4 |     2);\n\
+         \x20 |     ^^\n"
+    );
+}
+
+#[test]
+fn test_render_elides_long_spans() {
+    let text: Vec<String> = (1..=20).map(|i| format!("line{}", i)).collect();
+    let map = sources(&[("long.ax", &text.join("\n"))]);
+    let mut diagnostic = Diagnostic::error(None, "long");
+    diagnostic.span = Some(SourceSpan { file: "long.ax".to_string(), line: 2, column: 1, end_line: 15, end_column: 7 });
+
+    let rendered = render(&diagnostic, &map, false);
+    assert!(rendered.contains("\n 2 | line2\n"), "{}", rendered);
+    assert!(rendered.contains("\n...\n"), "{}", rendered);
+    assert!(rendered.contains("\n15 | line15\n"), "{}", rendered);
+    assert!(!rendered.contains("line8"), "{}", rendered);
+}
+
+#[test]
+fn test_concatenated_sources_show_the_real_file() {
+    // Spans name the first file and count lines through the joined text
+    let map = sources(&[("a.ax", "fn a() { 1 }\n"), ("b.ax", "fn b() {\n  oops\n}")]);
+    let mut diagnostic = Diagnostic::error(None, "here");
+    diagnostic.span = Some(SourceSpan::point("a.ax", 4, 3, 4));
+
+    let rendered = render(&diagnostic, &map, false);
+    assert!(rendered.contains("--> b.ax:2:3\n"), "{}", rendered);
+    assert!(rendered.contains("2 |   oops\n"), "{}", rendered);
+}
+
+#[test]
+fn test_color_is_opt_in() {
+    let map = sources(&[("main.ax", "x")]);
+    let mut diagnostic = Diagnostic::error(Some(ErrorCode::ArgType), "bad");
+    diagnostic.span = Some(SourceSpan::point("main.ax", 1, 1, 1));
+
+    assert!(!render(&diagnostic, &map, false).contains('\x1b'));
+    let colored = render(&diagnostic, &map, true);
+    assert!(colored.starts_with("\x1b[1;31merror[AX1003]\x1b[0m"), "{:?}", colored);
+
+    assert!(ColorChoice::Always.use_color());
+    assert!(!ColorChoice::Never.use_color());
+    assert_eq!(ColorChoice::parse("never"), Ok(ColorChoice::Never));
+    assert!(ColorChoice::parse("sometimes").is_err());
+}
+
+#[test]
+fn test_json_format() {
+    // The span is resolved to the real file, as in the human format
+    let map = sources(&[("a.ax", "fn a() { 1 }\n"), ("b.ax", "fn b() {\n  \"oops\"\n}")]);
+    let mut diagnostic = Diagnostic::error(Some(ErrorCode::UnboundVar), "unbound variable `oops`");
+    diagnostic.span = Some(SourceSpan::point("a.ax", 4, 3, 6));
+    diagnostic.label = Some("not \"defined\"".to_string());
+    diagnostic.notes.push("in term main/app.arg".to_string());
+    diagnostic.help.push("define it".to_string());
+
+    assert_eq!(
+        render_json(&diagnostic, &map),
+        "{\"severity\":\"error\",\"code\":\"AX1001\",\"name\":\"E_UNBOUND_VAR\",\
+         \"message\":\"unbound variable `oops`\",\
+         \"span\":{\"file\":\"b.ax\",\"line\":2,\"column\":3,\"end_line\":2,\"end_column\":9},\
+         \"label\":\"not \\\"defined\\\"\",\"notes\":[\"in term main/app.arg\"],\"help\":[\"define it\"]}\n"
+    );
+
+    let bare = Diagnostic::warning(None, "note");
+    assert_eq!(
+        render_json(&bare, &map),
+        "{\"severity\":\"warning\",\"code\":null,\"name\":null,\"message\":\"note\",\"span\":null,\"label\":null,\"notes\":[],\"help\":[]}\n"
+    );
+
+    let emitter = Emitter { format: ErrorFormat::Json, color: false };
+    assert_eq!(emitter.render(&diagnostic, &map), render_json(&diagnostic, &map));
+    assert_eq!(ErrorFormat::parse("json"), Ok(ErrorFormat::Json));
+    assert!(ErrorFormat::parse("xml").is_err());
+}
+
+#[test]
+fn test_parse_error_underlines_the_token() {
+    let error = surface_parser::parse_module_with_file("fn helper() { 1 }\n@frobnicate\nfn main() { 1 }\n", "main.ax")
+        .expect_err("unknown attribute");
+    let rendered = error.to_string();
+    assert!(rendered.starts_with("error[AX0001]: expected 'attribute (entry, inline, memo, test)', found '@frobnicate'\n"), "{}", rendered);
+    assert!(rendered.contains("--> main.ax:2:2\n"), "{}", rendered);
+    assert!(rendered.ends_with("2 | @frobnicate\n  |  ^^^^^^^^^^"), "{}", rendered);
+}
+
+#[test]
+fn test_unbound_variable_points_at_the_name() {
+    let source = "fn main() {\n    lst_cons(1, 2)\n}\n";
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let mut report = core_validator::validate_core(&term, &Registry::new());
+    report.locate_names(source, "main.ax");
+    let error = report.errors().next().expect("lst_cons is unbound");
+
+    let rendered = render(&error.to_diagnostic(), &sources(&[("main.ax", source)]), false);
+    assert!(rendered.starts_with("error[AX1001]: unbound variable `lst_cons`\n"), "{}", rendered);
+    assert!(rendered.contains("2 |     lst_cons(1, 2)\n  |     ^^^^^^^^ "), "{}", rendered);
+    assert!(rendered.contains("= help: "), "{}", rendered);
+}
+
+#[test]
+fn test_unbound_variable_suggests_close_names() {
+    let source = "fn list_cons(a, b) { a }\nfn main() {\n    lst_cons(1, 2)\n}\n";
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let error = core_validator::validate_core(&term, &Registry::new()).errors().next().cloned().expect("lst_cons is unbound");
+
+    assert_eq!(error.help, vec!["did you mean `list_cons`?".to_string()]);
+    assert!(error.to_string().ends_with("\n  help: did you mean `list_cons`?"), "{}", error);
+}
@@ -0,0 +1,355 @@
+// `axis-compiler doc`: reference documentation for Axis sources
+//
+// One page, Markdown or HTML: the top-level functions with their
+// parameters and attributes, the foreign functions the sources declare or
+// call with their registry metadata, and the types with their
+// constructors. Each function lists what it calls and what calls it, linked
+// to their entries.
+//
+// Built from the parsed module after imports are resolved, so calls are
+// full names (text.util.pad) and link across module blocks. The prelude is
+// not added: only the sources' own functions are documented.
+//
+// NOTE: the lexer drops comments, so there is no doc text yet; when doc
+// comments are kept they go in FunctionDoc.
+
+use crate::prelude::collect_references;
+use crate::registry_loader::{Registry, RegistryEntry};
+use crate::surface_parser::Module;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    pub fn parse(name: &str) -> Option<DocFormat> {
+        match name {
+            "markdown" | "md" => Some(DocFormat::Markdown),
+            "html" => Some(DocFormat::Html),
+            _ => None,
+        }
+    }
+
+    /// The page written to the output directory
+    pub fn file_name(self) -> &'static str {
+        match self {
+            DocFormat::Markdown => "index.md",
+            DocFormat::Html => "index.html",
+        }
+    }
+}
+
+pub struct FunctionDoc {
+    pub name: String,
+    pub params: Vec<String>,
+    // `@entry`, `@test(...)`, as written
+    pub attributes: Vec<String>,
+    pub public: bool,
+    // Functions of the sources, then foreign functions; sorted
+    pub calls: Vec<String>,
+    pub foreign_calls: Vec<String>,
+    pub called_by: Vec<String>,
+}
+
+pub struct ForeignDoc {
+    pub name: String,
+    // From the `foreign fn` declaration, if the sources have one
+    pub params: Option<Vec<String>>,
+    pub entry: Option<RegistryEntry>,
+    pub called_by: Vec<String>,
+}
+
+pub struct TypeDoc {
+    pub name: String,
+    pub params: Vec<String>,
+    // Constructor name and field types as written
+    pub constructors: Vec<(String, Vec<String>)>,
+}
+
+pub struct Reference {
+    pub functions: Vec<FunctionDoc>,
+    pub foreign: Vec<ForeignDoc>,
+    pub types: Vec<TypeDoc>,
+}
+
+pub fn compute(module: &Module, registry: &Registry) -> Reference {
+    let defined: HashSet<&str> = module.functions.iter().map(|f| f.name.as_str()).collect();
+    let declared: HashSet<&str> = module.foreign_functions.iter().map(|f| f.name.as_str()).collect();
+    // Operators lower to `__` registry functions; they are not documented
+    let is_foreign =
+        |name: &str| !name.starts_with("__") && (declared.contains(name) || registry.entries.contains_key(name));
+
+    let mut functions = Vec::new();
+    let mut callers: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for function in &module.functions {
+        let mut references = HashSet::new();
+        collect_references(&function.body, &mut references);
+        // A parameter named like a function hides it
+        references.retain(|name| !function.params.contains(name));
+        let mut calls: Vec<String> = references.iter().filter(|name| defined.contains(name.as_str())).cloned().collect();
+        let mut foreign_calls: Vec<String> = references.iter().filter(|name| is_foreign(name)).cloned().collect();
+        calls.sort();
+        foreign_calls.sort();
+        for callee in calls.iter().chain(&foreign_calls) {
+            callers.entry(callee.clone()).or_default().insert(function.name.clone());
+        }
+        let attributes = function
+            .attributes
+            .iter()
+            .map(|attr| {
+                if attr.args.is_empty() {
+                    format!("@{}", attr.name)
+                } else {
+                    format!("@{}({})", attr.name, attr.args.join(", "))
+                }
+            })
+            .collect();
+        functions.push(FunctionDoc {
+            name: function.name.clone(),
+            params: function.params.clone(),
+            attributes,
+            public: function.public,
+            calls,
+            foreign_calls,
+            called_by: Vec::new(),
+        });
+    }
+    let called_by = |name: &str| callers.get(name).map(|set| set.iter().cloned().collect()).unwrap_or_default();
+    for function in &mut functions {
+        function.called_by = called_by(&function.name);
+    }
+
+    // Declared or called, by name
+    let mut foreign_names: BTreeSet<&str> = declared.iter().copied().collect();
+    foreign_names.extend(functions.iter().flat_map(|f| f.foreign_calls.iter().map(String::as_str)));
+    let foreign = foreign_names
+        .into_iter()
+        .map(|name| ForeignDoc {
+            name: name.to_string(),
+            params: module.foreign_functions.iter().find(|f| f.name == name).map(|f| f.params.clone()),
+            entry: registry.entries.get(name).cloned(),
+            called_by: called_by(name),
+        })
+        .collect();
+
+    let types = module
+        .types
+        .iter()
+        .map(|ty| TypeDoc {
+            name: ty.name.clone(),
+            params: ty.params.clone(),
+            constructors: ty.variants.iter().map(|v| (v.name.clone(), v.fields.clone())).collect(),
+        })
+        .collect();
+
+    Reference { functions, foreign, types }
+}
+
+pub fn render(reference: &Reference, format: DocFormat, title: &str) -> String {
+    match format {
+        DocFormat::Markdown => render_markdown(reference, title),
+        DocFormat::Html => render_html(reference, title),
+    }
+}
+
+// Anchor of a documented name: fn-text-util-pad, foreign-io_print
+fn anchor(kind: &str, name: &str) -> String {
+    let name: String = name.chars().map(|c| if c.is_alphanumeric() || c == '_' { c } else { '-' }).collect();
+    format!("{}-{}", kind, name)
+}
+
+fn signature(name: &str, params: &[String]) -> String {
+    format!("{}({})", name, params.join(", "))
+}
+
+fn type_name(ty: &TypeDoc) -> String {
+    if ty.params.is_empty() {
+        ty.name.clone()
+    } else {
+        format!("{}[{}]", ty.name, ty.params.join(", "))
+    }
+}
+
+// What a foreign function's page entry says about it: (label, value)
+fn foreign_facts(foreign: &ForeignDoc) -> Vec<(&'static str, String)> {
+    let Some(entry) = &foreign.entry else {
+        return vec![("Registry", "not in the loaded registries".to_string())];
+    };
+    let mut facts = vec![("Signature", entry.signature())];
+    let capabilities: Vec<&str> = entry.required_capabilities().iter().map(|c| c.name()).collect();
+    facts.push(("Capabilities", if capabilities.is_empty() { "none".to_string() } else { capabilities.join(", ") }));
+    facts.push(("Deterministic", if entry.deterministic { "yes" } else { "no" }.to_string()));
+    if !entry.profiles.is_empty() {
+        facts.push(("Profiles", entry.profiles.join(", ")));
+    }
+    if let Some(module) = &entry.module {
+        facts.push(("Module", module.clone()));
+    }
+    if let Some(note) = &entry.deprecated {
+        facts.push(("Deprecated", note.clone()));
+    }
+    facts.push(("Declared at", format!("{}:{}", entry.file, entry.line)));
+    facts
+}
+
+// With its parameters when the sources declare it; the registry's
+// signature is among the facts
+fn foreign_heading(foreign: &ForeignDoc) -> String {
+    match &foreign.params {
+        Some(params) => signature(&foreign.name, params),
+        None => foreign.name.clone(),
+    }
+}
+
+fn render_markdown(reference: &Reference, title: &str) -> String {
+    let links = |names: &[String], kind: &str| -> String {
+        names.iter().map(|name| format!("[`{}`](#{})", name, anchor(kind, name))).collect::<Vec<_>>().join(", ")
+    };
+    let mut out = format!("# {}\n\nGenerated by `axis-compiler doc`.\n", title);
+
+    if !reference.functions.is_empty() {
+        out.push_str("\n## Functions\n");
+    }
+    for function in &reference.functions {
+        out.push_str(&format!("\n<a id=\"{}\"></a>\n", anchor("fn", &function.name)));
+        out.push_str(&format!("### `{}`\n\n", signature(&function.name, &function.params)));
+        let mut tags: Vec<String> = function.attributes.iter().map(|a| format!("`{}`", a)).collect();
+        if function.public {
+            tags.insert(0, "`pub`".to_string());
+        }
+        if !tags.is_empty() {
+            out.push_str(&format!("{}\n\n", tags.join(" ")));
+        }
+        if !function.calls.is_empty() {
+            out.push_str(&format!("- Calls: {}\n", links(&function.calls, "fn")));
+        }
+        if !function.foreign_calls.is_empty() {
+            out.push_str(&format!("- Foreign calls: {}\n", links(&function.foreign_calls, "foreign")));
+        }
+        if !function.called_by.is_empty() {
+            out.push_str(&format!("- Called by: {}\n", links(&function.called_by, "fn")));
+        }
+    }
+
+    if !reference.foreign.is_empty() {
+        out.push_str("\n## Foreign functions\n");
+    }
+    for foreign in &reference.foreign {
+        out.push_str(&format!("\n<a id=\"{}\"></a>\n", anchor("foreign", &foreign.name)));
+        out.push_str(&format!("### `{}`\n\n", foreign_heading(foreign)));
+        for (label, value) in foreign_facts(foreign) {
+            out.push_str(&format!("- {}: {}\n", label, value));
+        }
+        if !foreign.called_by.is_empty() {
+            out.push_str(&format!("- Called by: {}\n", links(&foreign.called_by, "fn")));
+        }
+    }
+
+    if !reference.types.is_empty() {
+        out.push_str("\n## Types\n");
+    }
+    for ty in &reference.types {
+        out.push_str(&format!("\n<a id=\"{}\"></a>\n", anchor("type", &ty.name)));
+        out.push_str(&format!("### `{}`\n\n", type_name(ty)));
+        for (name, fields) in &ty.constructors {
+            out.push_str(&format!("- `{}`\n", signature(name, fields)));
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn render_html(reference: &Reference, title: &str) -> String {
+    let links = |names: &[String], kind: &str| -> String {
+        names
+            .iter()
+            .map(|name| format!("<a href=\"#{}\"><code>{}</code></a>", anchor(kind, name), escape_html(name)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let title = escape_html(title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n<p>Generated by <code>axis-compiler doc</code>.</p>\n",
+        title, title
+    );
+
+    if !reference.functions.is_empty() {
+        out.push_str("<h2>Functions</h2>\n");
+    }
+    for function in &reference.functions {
+        out.push_str(&format!(
+            "<h3 id=\"{}\"><code>{}</code></h3>\n",
+            anchor("fn", &function.name),
+            escape_html(&signature(&function.name, &function.params))
+        ));
+        let mut tags: Vec<String> = function.attributes.iter().map(|a| format!("<code>{}</code>", escape_html(a))).collect();
+        if function.public {
+            tags.insert(0, "<code>pub</code>".to_string());
+        }
+        if !tags.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", tags.join(" ")));
+        }
+        let mut items = Vec::new();
+        if !function.calls.is_empty() {
+            items.push(format!("Calls: {}", links(&function.calls, "fn")));
+        }
+        if !function.foreign_calls.is_empty() {
+            items.push(format!("Foreign calls: {}", links(&function.foreign_calls, "foreign")));
+        }
+        if !function.called_by.is_empty() {
+            items.push(format!("Called by: {}", links(&function.called_by, "fn")));
+        }
+        push_html_list(&mut out, &items);
+    }
+
+    if !reference.foreign.is_empty() {
+        out.push_str("<h2>Foreign functions</h2>\n");
+    }
+    for foreign in &reference.foreign {
+        out.push_str(&format!(
+            "<h3 id=\"{}\"><code>{}</code></h3>\n",
+            anchor("foreign", &foreign.name),
+            escape_html(&foreign_heading(foreign))
+        ));
+        let mut items: Vec<String> = foreign_facts(foreign)
+            .into_iter()
+            .map(|(label, value)| format!("{}: {}", label, escape_html(&value)))
+            .collect();
+        if !foreign.called_by.is_empty() {
+            items.push(format!("Called by: {}", links(&foreign.called_by, "fn")));
+        }
+        push_html_list(&mut out, &items);
+    }
+
+    if !reference.types.is_empty() {
+        out.push_str("<h2>Types</h2>\n");
+    }
+    for ty in &reference.types {
+        out.push_str(&format!("<h3 id=\"{}\"><code>{}</code></h3>\n", anchor("type", &ty.name), escape_html(&type_name(ty))));
+        let items: Vec<String> = ty
+            .constructors
+            .iter()
+            .map(|(name, fields)| format!("<code>{}</code>", escape_html(&signature(name, fields))))
+            .collect();
+        push_html_list(&mut out, &items);
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn push_html_list(out: &mut String, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str("<ul>\n");
+    for item in items {
+        out.push_str(&format!("<li>{}</li>\n", item));
+    }
+    out.push_str("</ul>\n");
+}
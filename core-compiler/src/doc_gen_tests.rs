@@ -0,0 +1,102 @@
+// Tests for `axis-compiler doc` (doc_gen.rs)
+
+use crate::doc_gen::{self, DocFormat, Reference};
+use crate::registry_loader::Registry;
+use crate::{imports, surface_parser};
+
+const REGISTRY: &str = "\
+axreg 2
+fn axis_fs_read
+  arity 1
+  deterministic false
+  profile core
+  params Str
+  returns Str
+  capability fs
+  deprecated use axis_fs_read_text
+end
+";
+
+const SOURCE: &str = "
+module text.util {
+    pub fn pad(s) { s ++ \"-\" }
+    pub fn twice(s) { pad(pad(s)) }
+}
+type Shape { Circle(Int), Rect(Int, Int) }
+foreign fn io_print(msg: Str) -> Unit
+use text.util.twice;
+@entry
+fn main(args) {
+    io_print(twice(axis_fs_read(\"a\")));
+    area(Circle(1))
+}
+fn area(shape) { match shape { Circle(r) => r * r, Rect(w, h) => w * h } }
+";
+
+fn reference() -> Reference {
+    crate::init_string_table();
+    let mut registry = Registry::new();
+    registry.load_from_str(REGISTRY, "test.axreg").unwrap();
+    let mut module = surface_parser::parse_module_with_file(SOURCE, "main.ax").unwrap();
+    imports::resolve(&mut module, &registry).unwrap();
+    doc_gen::compute(&module, &registry)
+}
+
+#[test]
+fn test_calls_and_callers_use_full_names() {
+    let reference = reference();
+    let names: Vec<&str> = reference.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["text.util.pad", "text.util.twice", "main", "area"]);
+
+    let main = &reference.functions[2];
+    assert_eq!(main.calls, ["area", "text.util.twice"]);
+    assert_eq!(main.attributes, ["@entry"]);
+    assert_eq!(main.foreign_calls, ["axis_fs_read", "io_print"]);
+    assert_eq!(reference.functions[0].called_by, ["text.util.twice"]);
+    assert!(reference.functions[0].public && !main.public);
+    // Constructors and operators are not calls
+    assert!(reference.functions[3].calls.is_empty() && reference.functions[3].foreign_calls.is_empty());
+}
+
+#[test]
+fn test_foreign_functions_carry_registry_metadata() {
+    let reference = reference();
+    let names: Vec<&str> = reference.foreign.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["axis_fs_read", "io_print"]);
+    // Declared in the sources but not in the registry
+    assert!(reference.foreign[1].entry.is_none());
+    assert_eq!(reference.foreign[1].params.as_deref(), Some(&["msg".to_string()][..]));
+
+    let page = doc_gen::render(&reference, DocFormat::Markdown, "main");
+    for line in [
+        "### `axis_fs_read`",
+        "- Signature: axis_fs_read(Str) -> Str",
+        "- Capabilities: fs",
+        "- Deprecated: use axis_fs_read_text",
+        "- Declared at: test.axreg:2",
+        "- Called by: [`main`](#fn-main)",
+        "### `io_print(msg)`",
+        "- Registry: not in the loaded registries",
+    ] {
+        assert!(page.lines().any(|l| l == line), "missing {:?} in\n{}", line, page);
+    }
+}
+
+#[test]
+fn test_markdown_links_each_call_to_its_entry() {
+    let page = doc_gen::render(&reference(), DocFormat::Markdown, "main");
+    assert!(page.starts_with("# main\n"));
+    assert!(page.contains("<a id=\"fn-text-util-pad\"></a>\n### `text.util.pad(s)`\n\n`pub`\n"), "{}", page);
+    assert!(page.contains("- Calls: [`area`](#fn-area), [`text.util.twice`](#fn-text-util-twice)\n"), "{}", page);
+    assert!(page.contains("### `Shape`\n\n- `Circle(Int)`\n- `Rect(Int, Int)`\n"), "{}", page);
+}
+
+#[test]
+fn test_html_is_escaped_and_anchored() {
+    let page = doc_gen::render(&reference(), DocFormat::Html, "a<b");
+    assert!(page.contains("<title>a&lt;b</title>"));
+    assert!(page.contains("<h3 id=\"fn-main\"><code>main(args)</code></h3>\n<p><code>@entry</code></p>\n"), "{}", page);
+    assert!(page.contains("<a href=\"#foreign-io_print\"><code>io_print</code></a>"), "{}", page);
+    assert!(page.contains("<li>Signature: axis_fs_read(Str) -&gt; Str</li>"), "{}", page);
+    assert!(page.ends_with("</body>\n</html>\n"));
+}
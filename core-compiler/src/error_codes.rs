@@ -0,0 +1,572 @@
+// Stable diagnostic codes
+//
+// Every diagnostic the compiler reports carries a code AXnnnn that never
+// changes meaning once released, so it can be searched for and explained
+// with `axis-compiler explain AXnnnn`. The leading digit is the phase:
+//
+//   AX0xxx  parsing
+//   AX1xxx  validation (AX15xx are warnings)
+//   AX2xxx  code generation (reported by the rust-bridge backends)
+//   AX3xxx  linking
+//   AX4xxx  the interpreter (`axis-compiler run`)
+//
+// Messages start with the code ("AX1001: unbound variable `x`"); the
+// diagnostics renderer splits it off again. Retired codes are not reused.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    Parse,
+    Lex,
+    Keyword,
+    UnboundVar,
+    IoUnregistered,
+    ArgType,
+    ApplyNonFunction,
+    SpawnTarget,
+    FunctionArg,
+    ProjOutOfRange,
+    Capability,
+    UnknownCtor,
+    CtorArity,
+    NonExhaustiveMatch,
+    RefutableLetPattern,
+    MemoNondeterministic,
+    ConflictingConstructor,
+    UnknownImport,
+    AmbiguousName,
+    PrivateFunction,
+    DuplicateFunction,
+    AmbiguousConstructor,
+    Deprecated,
+    ShadowsPrelude,
+    DuplicateFunctionIgnored,
+    UnusedBinding,
+    UnusedParameter,
+    ShadowedBinding,
+    UnresolvedReference,
+    DuplicateDefinition,
+    MultipleTails,
+    DuplicateExport,
+    UnresolvedImport,
+    PrivateImport,
+    InterpreterUnsupported,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 35] = [
+        ErrorCode::Parse,
+        ErrorCode::Lex,
+        ErrorCode::Keyword,
+        ErrorCode::UnboundVar,
+        ErrorCode::IoUnregistered,
+        ErrorCode::ArgType,
+        ErrorCode::ApplyNonFunction,
+        ErrorCode::SpawnTarget,
+        ErrorCode::FunctionArg,
+        ErrorCode::ProjOutOfRange,
+        ErrorCode::Capability,
+        ErrorCode::UnknownCtor,
+        ErrorCode::CtorArity,
+        ErrorCode::NonExhaustiveMatch,
+        ErrorCode::RefutableLetPattern,
+        ErrorCode::MemoNondeterministic,
+        ErrorCode::ConflictingConstructor,
+        ErrorCode::UnknownImport,
+        ErrorCode::AmbiguousName,
+        ErrorCode::PrivateFunction,
+        ErrorCode::DuplicateFunction,
+        ErrorCode::AmbiguousConstructor,
+        ErrorCode::Deprecated,
+        ErrorCode::ShadowsPrelude,
+        ErrorCode::DuplicateFunctionIgnored,
+        ErrorCode::UnusedBinding,
+        ErrorCode::UnusedParameter,
+        ErrorCode::ShadowedBinding,
+        ErrorCode::UnresolvedReference,
+        ErrorCode::DuplicateDefinition,
+        ErrorCode::MultipleTails,
+        ErrorCode::DuplicateExport,
+        ErrorCode::UnresolvedImport,
+        ErrorCode::PrivateImport,
+        ErrorCode::InterpreterUnsupported,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorCode::Parse => "AX0001",
+            ErrorCode::Lex => "AX0002",
+            ErrorCode::Keyword => "AX0003",
+            ErrorCode::UnboundVar => "AX1001",
+            ErrorCode::IoUnregistered => "AX1002",
+            ErrorCode::ArgType => "AX1003",
+            ErrorCode::ApplyNonFunction => "AX1004",
+            ErrorCode::SpawnTarget => "AX1005",
+            ErrorCode::FunctionArg => "AX1006",
+            ErrorCode::ProjOutOfRange => "AX1007",
+            ErrorCode::Capability => "AX1008",
+            ErrorCode::UnknownCtor => "AX1009",
+            ErrorCode::CtorArity => "AX1010",
+            ErrorCode::NonExhaustiveMatch => "AX1011",
+            ErrorCode::RefutableLetPattern => "AX1012",
+            ErrorCode::MemoNondeterministic => "AX1013",
+            ErrorCode::ConflictingConstructor => "AX1014",
+            ErrorCode::UnknownImport => "AX1015",
+            ErrorCode::AmbiguousName => "AX1016",
+            ErrorCode::PrivateFunction => "AX1017",
+            ErrorCode::DuplicateFunction => "AX1018",
+            ErrorCode::AmbiguousConstructor => "AX1019",
+            ErrorCode::Deprecated => "AX1501",
+            ErrorCode::ShadowsPrelude => "AX1502",
+            ErrorCode::DuplicateFunctionIgnored => "AX1503",
+            ErrorCode::UnusedBinding => "AX1504",
+            ErrorCode::UnusedParameter => "AX1505",
+            ErrorCode::ShadowedBinding => "AX1506",
+            ErrorCode::UnresolvedReference => "AX2001",
+            ErrorCode::DuplicateDefinition => "AX3001",
+            ErrorCode::MultipleTails => "AX3002",
+            ErrorCode::DuplicateExport => "AX3003",
+            ErrorCode::UnresolvedImport => "AX3004",
+            ErrorCode::PrivateImport => "AX3005",
+            ErrorCode::InterpreterUnsupported => "AX4001",
+        }
+    }
+
+    /// The symbolic name the code had before numbering, e.g. E_UNBOUND_VAR
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::Parse => "E_PARSE",
+            ErrorCode::Lex => "E_LEX",
+            ErrorCode::Keyword => "E_KEYWORD",
+            ErrorCode::UnboundVar => "E_UNBOUND_VAR",
+            ErrorCode::IoUnregistered => "E_IO_UNREGISTERED",
+            ErrorCode::ArgType => "E_ARG_TYPE",
+            ErrorCode::ApplyNonFunction => "E_APPLY_NON_FUNCTION",
+            ErrorCode::SpawnTarget => "E_SPAWN_TARGET",
+            ErrorCode::FunctionArg => "E_FUNCTION_ARG",
+            ErrorCode::ProjOutOfRange => "E_PROJ_OUT_OF_RANGE",
+            ErrorCode::Capability => "E_CAPABILITY",
+            ErrorCode::UnknownCtor => "E_UNKNOWN_CTOR",
+            ErrorCode::CtorArity => "E_CTOR_ARITY",
+            ErrorCode::NonExhaustiveMatch => "E_NON_EXHAUSTIVE_MATCH",
+            ErrorCode::RefutableLetPattern => "E_REFUTABLE_LET_PATTERN",
+            ErrorCode::MemoNondeterministic => "E_MEMO_NONDETERMINISTIC",
+            ErrorCode::ConflictingConstructor => "E_CONFLICTING_CONSTRUCTOR",
+            ErrorCode::UnknownImport => "E_UNKNOWN_IMPORT",
+            ErrorCode::AmbiguousName => "E_AMBIGUOUS_NAME",
+            ErrorCode::PrivateFunction => "E_PRIVATE_FUNCTION",
+            ErrorCode::DuplicateFunction => "E_DUPLICATE_FUNCTION",
+            ErrorCode::AmbiguousConstructor => "E_AMBIGUOUS_CONSTRUCTOR",
+            ErrorCode::Deprecated => "W_DEPRECATED",
+            ErrorCode::ShadowsPrelude => "W_SHADOWS_PRELUDE",
+            ErrorCode::DuplicateFunctionIgnored => "W_DUPLICATE_FUNCTION",
+            ErrorCode::UnusedBinding => "W_UNUSED_BINDING",
+            ErrorCode::UnusedParameter => "W_UNUSED_PARAMETER",
+            ErrorCode::ShadowedBinding => "W_SHADOWED_BINDING",
+            ErrorCode::UnresolvedReference => "E_UNRESOLVED_REFERENCE",
+            ErrorCode::DuplicateDefinition => "E_DUPLICATE_DEFINITION",
+            ErrorCode::MultipleTails => "E_MULTIPLE_TAILS",
+            ErrorCode::DuplicateExport => "E_DUPLICATE_EXPORT",
+            ErrorCode::UnresolvedImport => "E_UNRESOLVED_IMPORT",
+            ErrorCode::PrivateImport => "E_PRIVATE_IMPORT",
+            ErrorCode::InterpreterUnsupported => "E_INTERPRETER_UNSUPPORTED",
+        }
+    }
+
+    /// `AX1004`, any case, or the symbolic name
+    pub fn parse(text: &str) -> Option<ErrorCode> {
+        ErrorCode::ALL
+            .into_iter()
+            .find(|code| code.code().eq_ignore_ascii_case(text) || code.name() == text)
+    }
+
+    pub fn is_warning(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::Deprecated
+                | ErrorCode::ShadowsPrelude
+                | ErrorCode::DuplicateFunctionIgnored
+                | ErrorCode::UnusedBinding
+                | ErrorCode::UnusedParameter
+                | ErrorCode::ShadowedBinding
+        )
+    }
+
+    /// One line, for listings
+    pub fn summary(self) -> &'static str {
+        match self {
+            ErrorCode::Parse => "the source does not parse",
+            ErrorCode::Lex => "the source has an unterminated string or a character no token starts with",
+            ErrorCode::Keyword => "a keyword is used where a name is required",
+            ErrorCode::UnboundVar => "a name is not defined in the program or its registries",
+            ErrorCode::IoUnregistered => "an IO primitive is not declared by a loaded registry",
+            ErrorCode::ArgType => "a literal argument has the wrong type for a registry function",
+            ErrorCode::ApplyNonFunction => "a value that is not a function is called",
+            ErrorCode::SpawnTarget => "axis_spawn is given something other than a top-level function",
+            ErrorCode::FunctionArg => "a higher-order primitive is given something other than a function",
+            ErrorCode::ProjOutOfRange => "a tuple projection is past the end of the tuple",
+            ErrorCode::Capability => "a function needs a capability that --allow does not grant",
+            ErrorCode::UnknownCtor => "a qualified constructor names no variant of its type",
+            ErrorCode::CtorArity => "a constructor is given the wrong number of fields",
+            ErrorCode::NonExhaustiveMatch => "a match on a declared type misses some variants",
+            ErrorCode::RefutableLetPattern => "a let pattern without else does not match every value",
+            ErrorCode::MemoNondeterministic => "an @memo function calls a non-deterministic function",
+            ErrorCode::ConflictingConstructor => "a type declares a constructor twice with different shapes",
+            ErrorCode::UnknownImport => "a `use` names no module or function",
+            ErrorCode::AmbiguousName => "a name refers to more than one function in scope",
+            ErrorCode::PrivateFunction => "a function that is not `pub` is used outside its module",
+            ErrorCode::DuplicateFunction => "a function is defined more than once",
+            ErrorCode::AmbiguousConstructor => "a constructor name is declared by more than one type",
+            ErrorCode::Deprecated => "a deprecated registry function is used",
+            ErrorCode::ShadowsPrelude => "a program function replaces a prelude function",
+            ErrorCode::DuplicateFunctionIgnored => "a later definition of a function is ignored (--allow-duplicate-defs)",
+            ErrorCode::UnusedBinding => "a let binding is never used",
+            ErrorCode::UnusedParameter => "a function parameter is never used",
+            ErrorCode::ShadowedBinding => "a binding hides another of the same name in the function",
+            ErrorCode::UnresolvedReference => "a backend cannot resolve a name in the Core IR",
+            ErrorCode::DuplicateDefinition => "two linked bundles define the same name",
+            ErrorCode::MultipleTails => "more than one linked bundle has a top-level result",
+            ErrorCode::DuplicateExport => "two linked bundles export the same name",
+            ErrorCode::UnresolvedImport => "a bundle imports a name no linked bundle exports",
+            ErrorCode::PrivateImport => "a bundle imports a function another bundle does not declare `pub`",
+            ErrorCode::InterpreterUnsupported => "the program uses something axis-compiler run does not implement",
+        }
+    }
+
+    /// The longer text `axis-compiler explain` prints, with an example
+    pub fn explanation(self) -> &'static str {
+        match self {
+            ErrorCode::Parse => "\
+The parser expected one token and found another. The location points at
+the unexpected token; an early end of input usually means an unclosed
+`{` or `(`.
+
+    fn axis_entry(args) {
+        let x = 1
+    }
+
+Here `let x = 1` is missing its `;`.",
+            ErrorCode::Lex => "\
+The tokenizer found a string literal that is never closed, or a character
+that starts no token (`#`, `$`, `?`, a lone `!` or `&`). The location
+points at the opening quote or the character. After each such error the
+tokenizer skips to the next line, so every line with one is reported.
+
+    fn axis_entry(args) {
+        let x = 1 # 2;
+        x
+    }
+
+Here `#` starts no token; an unclosed string is reported at its opening
+quote and runs to the end of the file, as strings may span lines.",
+            ErrorCode::Keyword => "\
+Keywords (else, false, fn, foreign, if, in, let, match, module, proj, pub,
+true, type, use) cannot name a function, parameter, variable, pattern
+binding, field or module. The location points at the keyword.
+
+    fn first(pair, type) {
+        proj(pair, 0)
+    }
+
+Here the parameter `type` needs another name, such as `kind` or `type_`.",
+            ErrorCode::UnboundVar => "\
+A variable or function is used but neither the program nor the loaded
+registries define it. The help line suggests the closest name in scope.
+
+    fn axis_entry(args) {
+        lst_cons(1, Nil)
+    }
+
+Define the function, correct the name (`list_cons`), or pass the registry
+that declares it with --registries.",
+            ErrorCode::IoUnregistered => "\
+An IO primitive (`axis_io_*`, `axis_fs_*`, `axis_net_*`, ...) is called but
+no loaded registry declares it. Without --registries only the built-in
+prelude of pure primitives is loaded.
+
+    fn axis_entry(args) {
+        axis_io_print(\"hi\")
+    }
+
+Compile with --registries registries/axis.axreg.",
+            ErrorCode::ArgType => "\
+A literal argument contradicts the type a registry declares for that
+parameter.
+
+    fn axis_entry(args) {
+        axis_str_len(42)
+    }
+
+`axis_str_len` takes a Str; pass a string.",
+            ErrorCode::ApplyNonFunction => "\
+The head of a call is a literal or another value that cannot be a
+function.
+
+    fn axis_entry(args) {
+        let n = 3;
+        5(n)
+    }
+
+Call a function, or remove the argument list.",
+            ErrorCode::SpawnTarget => "\
+axis_spawn runs a top-level function on a new thread, so the thread shares
+nothing with its parent. A lambda or a local variable would capture the
+parent's locals.
+
+    fn axis_entry(args) {
+        let y = 1;
+        axis_spawn(fn(x) { x + y }, 0)
+    }
+
+Define the body as a top-level function and pass what it needs as the
+argument.",
+            ErrorCode::FunctionArg => "\
+axis_seq_map, axis_list_sort_by and the other higher-order primitives take
+a top-level function or a lambda, not an arbitrary value.
+
+    fn axis_entry(args) {
+        let f = 3;
+        axis_seq_map(f, xs)
+    }
+
+Pass a function name or a lambda.",
+            ErrorCode::ProjOutOfRange => "\
+A projection reads a field past the end of a tuple whose size is known.
+Fields count from 0.
+
+    fn axis_entry(args) {
+        let t = (1, 2);
+        proj(t, 2)
+    }
+
+A 2-tuple has fields 0 and 1.",
+            ErrorCode::Capability => "\
+With --allow, only functions whose capabilities are granted may be called.
+The registry gives each foreign function its capability (io, fs or proc).
+
+    fn axis_entry(args) { axis_fs_read(\"x\") }
+
+compiled with --allow io fails: axis_fs_read needs fs. Grant it
+(--allow io,fs) or remove the call.",
+            ErrorCode::UnknownCtor => "\
+A qualified constructor `Type::Name` names a variant the type does not
+declare.
+
+    type Shape = Circle(Int) | Rect(Int, Int)
+    fn axis_entry(args) { Shape::Triangle(1) }
+
+Use one of the declared variants.",
+            ErrorCode::CtorArity => "\
+A constructor is built or matched with a different number of fields than
+its declaration.
+
+    type Shape = Circle(Int) | Rect(Int, Int)
+    fn axis_entry(args) { Rect(1) }
+
+`Rect` has 2 fields.",
+            ErrorCode::NonExhaustiveMatch => "\
+A match on a value of a declared type has no arm for some variants and no
+catch-all arm.
+
+    type Shape = Circle(Int) | Rect(Int, Int)
+    fn area(s) { match s { Circle(r) => r * r } }
+
+Add the missing arms or a `_` arm.",
+            ErrorCode::RefutableLetPattern => "\
+A `let` pattern that only some values match needs an `else` block to run
+when it does not match.
+
+    fn axis_entry(args) {
+        let Some(x) = lookup(args);
+        x
+    }
+
+Write `let Some(x) = lookup(args) else { 0 };` or use a match.",
+            ErrorCode::MemoNondeterministic => "\
+@memo caches a function's results by argument, which is only sound if the
+function is deterministic. It calls, directly or through other functions,
+a primitive whose result can change between calls.
+
+    @memo
+    fn lookup(k) { axis_io_print(k); k }
+
+Move the effect out of the memoized function.",
+            ErrorCode::ConflictingConstructor => "\
+Two declarations of the same type, usually in separately compiled units
+passed to `axis-compiler link`, give one of its constructors different
+numbers of fields.
+
+    // a.ax
+    type Tree { Leaf, Node(Tree, Tree) }
+    // b.ax
+    type Tree { Leaf(Int), Node(Tree, Tree) }
+
+Declare the type once, in a module both units use.",
+            ErrorCode::UnknownImport => "\
+`use` takes the path of a `module` block, of a function in one, or of a
+registry module or function. The path names none of them.
+
+    module text.util { fn pad(s) { s ++ \" \" } }
+    use text.utils;
+
+Correct the path (`use text.util;`).",
+            ErrorCode::AmbiguousName => "\
+An unqualified name matches more than one function: a function of the
+enclosing module (or a top-level function, at top level) and an imported
+one, or two imported ones.
+
+    module text.util { fn trim(s) { s } }
+    use text.util.trim;
+    fn trim(s) { s }
+    fn main() { trim(\"x\") }
+
+Qualify the reference (`util.trim`, after `use text.util;`), or rename
+one of the functions.",
+            ErrorCode::PrivateFunction => "\
+A function in a `module` block without `pub` can only be used by that
+block and the blocks nested in it, including through `use`.
+
+    module text.util { fn pad(s) { s ++ \" \" } }
+    fn main() { text.util.pad(\"x\") }
+
+Declare it `pub fn pad(s)` to make it part of the module's API.",
+            ErrorCode::DuplicateFunction => "\
+Two functions have the same name, usually because concatenated source
+files both define it. Only one of them could be called, so the message
+lists both definitions.
+
+    // util.ax
+    fn trim(s) { s }
+    // main.ax
+    fn trim(s) { axis_str_trim(s) }
+
+Rename or remove one of them. --allow-duplicate-defs keeps the first
+definition instead, as older compilers did.",
+            ErrorCode::AmbiguousConstructor => "\
+A bare constructor name is declared by more than one type, and the
+function using it is not in the module of one of them, so it could be
+either.
+
+    module http { type Status { Ok, Failed(Str) } }
+    module db { type Reply { Ok, Missing } }
+    fn main() { Ok }
+
+Qualify it with its type (`http.Status::Ok`). Inside `module http` a bare
+`Ok` means `http.Status::Ok`.",
+            ErrorCode::Deprecated => "\
+The program calls a registry function marked deprecated. The help line
+names its replacement when the registry gives one.
+
+    fn axis_entry(args) { axis_fs_read(\"x\") }
+
+Switch to the replacement before the function is removed.",
+            ErrorCode::ShadowsPrelude => "\
+A program function has the name of a standard prelude function and
+replaces it for the whole program.
+
+    fn list_length(xs) { 0 }
+
+Rename the function, or compile with --no-prelude if the replacement is
+intended.",
+            ErrorCode::DuplicateFunctionIgnored => "\
+With --allow-duplicate-defs a function defined more than once keeps its
+first definition, and each later one is dropped with this warning.
+
+    fn trim(s) { s }
+    fn trim(s) { axis_str_trim(s) }
+
+The second `trim` is never compiled. Remove it to silence the warning.",
+            ErrorCode::UnusedBinding => "\
+A `let` binds a name that nothing after it uses.
+
+    fn area(w, h) {
+        let scaled = w * 2;
+        w * h
+    }
+
+Remove the binding, or write `let _ = ...;` if only the value's effects
+are wanted. Names starting with `_` are never reported.",
+            ErrorCode::UnusedParameter => "\
+A function never uses one of its parameters.
+
+    fn first(a, b) { a }
+
+Remove the parameter, or name it `_` (or `_b`) to say it is unused on
+purpose. The entry function's parameter is not reported: the runtime
+always passes it.",
+            ErrorCode::ShadowedBinding => "\
+A parameter, `let` or match pattern binds a name that an enclosing binding
+of the same function already has, so the outer value cannot be reached
+after it.
+
+    fn total(xs) {
+        let xs = list_reverse(xs);
+        list_sum(xs)
+    }
+
+Rename one of the bindings if hiding the outer one is unintended.",
+            ErrorCode::UnresolvedReference => "\
+The code generator met a name that is neither bound in the program nor a
+known runtime or registry function, so it cannot emit a call to it.
+Validation normally reports this first as AX1001; this code means the Core
+IR was built or linked without validation.
+
+    (app (var json_body) (var req))
+
+Validate the bundle, or link the bundle that defines the name.",
+            ErrorCode::DuplicateDefinition => "\
+Two bundles passed to `axis-compiler link` define the same top-level name.
+
+    axis-compiler link a.coreir b.coreir --out app.coreir
+
+Rename one definition, or link only one of the bundles.",
+            ErrorCode::MultipleTails => "\
+More than one linked bundle ends in a top-level term other than Unit, so
+the linked program would have two results.
+
+    axis-compiler link main.coreir tool.coreir --out app.coreir
+
+Keep the top-level result in a single bundle.",
+            ErrorCode::DuplicateExport => "\
+Two `--separate` bundles export the same name, so an import of it would be
+ambiguous.
+
+    axis-compiler link util.coreir strings.coreir --out app.coreir
+
+Rename one of the exported functions.",
+            ErrorCode::UnresolvedImport => "\
+A `--separate` bundle imports a name that no other bundle being linked
+exports.
+
+    axis-compiler link main.coreir --out app.coreir
+
+Link the bundle that defines the name.",
+            ErrorCode::PrivateImport => "\
+A `--separate` bundle imports a name that the bundle defining it does not
+export as public: only `pub fn` functions are a bundle's API. The entry
+function is always public.
+
+    // util.ax, compiled to util.coreir
+    fn pad(s) { s ++ \" \" }
+
+    axis-compiler link util.coreir main.coreir --out app.coreir
+
+Declare the function `pub fn pad(s)` and recompile its bundle.",
+            ErrorCode::InterpreterUnsupported => "\
+`axis-compiler run` interprets a subset of Axis: it has no threads,
+channels, network or process primitives, and no compound values in some
+positions. Such programs exit 125 instead of running.
+
+    axis-compiler run app.coreir
+
+Build the program with `axis-rust-bridge build` and run the binary.",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
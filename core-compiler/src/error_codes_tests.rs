@@ -0,0 +1,73 @@
+// Tests for the diagnostic code catalog (error_codes.rs)
+
+use crate::diagnostics::Diagnostic;
+use crate::error_codes::ErrorCode;
+use crate::registry_loader::Registry;
+use crate::{core_validator, surface_lower, surface_parser, surface_to_core};
+use std::collections::HashSet;
+
+#[test]
+fn test_codes_and_names_are_unique() {
+    let codes: HashSet<&str> = ErrorCode::ALL.iter().map(|code| code.code()).collect();
+    let names: HashSet<&str> = ErrorCode::ALL.iter().map(|code| code.name()).collect();
+    assert_eq!(codes.len(), ErrorCode::ALL.len());
+    assert_eq!(names.len(), ErrorCode::ALL.len());
+}
+
+#[test]
+fn test_code_format_and_phase() {
+    for code in ErrorCode::ALL {
+        let text = code.code();
+        assert!(text.len() == 6 && text.starts_with("AX"), "{}", text);
+        assert!(text[2..].bytes().all(|b| b.is_ascii_digit()), "{}", text);
+        // AX15xx are the validation warnings
+        assert_eq!(code.is_warning(), text.starts_with("AX15"), "{}", text);
+        assert_eq!(code.is_warning(), code.name().starts_with("W_"), "{}", text);
+    }
+    assert_eq!(ErrorCode::Parse.code(), "AX0001");
+    assert!(ErrorCode::UnboundVar.code().starts_with("AX1"));
+    assert!(ErrorCode::UnresolvedReference.code().starts_with("AX2"));
+    assert!(ErrorCode::DuplicateDefinition.code().starts_with("AX3"));
+    assert!(ErrorCode::InterpreterUnsupported.code().starts_with("AX4"));
+}
+
+#[test]
+fn test_parse_round_trip() {
+    for code in ErrorCode::ALL {
+        assert_eq!(ErrorCode::parse(code.code()), Some(code));
+        assert_eq!(ErrorCode::parse(code.name()), Some(code));
+        assert_eq!(code.to_string(), code.code());
+        assert!(!code.summary().is_empty() && !code.explanation().is_empty());
+    }
+    assert_eq!(ErrorCode::parse("ax1004"), Some(ErrorCode::ApplyNonFunction));
+    assert_eq!(ErrorCode::parse("AX9999"), None);
+    assert_eq!(ErrorCode::parse("E_NOPE"), None);
+}
+
+#[test]
+fn test_validation_errors_carry_the_code() {
+    let module = surface_parser::parse_module_with_file("fn main() { nope(1) }\n", "main.ax").unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let error = core_validator::validate_core(&term, &Registry::new()).errors().next().cloned().expect("nope is unbound");
+
+    assert!(error.message().starts_with("AX1001: "), "{}", error);
+    let diagnostic = error.to_diagnostic();
+    assert_eq!(diagnostic.code, Some(ErrorCode::UnboundVar));
+    assert_eq!(diagnostic.message, "unbound variable `nope`");
+}
+
+#[test]
+fn test_lexical_errors_carry_the_code() {
+    let source = "fn main() {\n    let x = 1 # 2;\n    print(\"x)\n}\n";
+    let error = surface_parser::parse_module_with_file(source, "main.ax").expect_err("`#` is no token");
+
+    let diagnostic = Diagnostic::from(&error);
+    assert_eq!(diagnostic.code, Some(ErrorCode::Lex));
+    assert_eq!(diagnostic.message, "unknown character \"#\"");
+    assert_eq!((error.line, error.column, error.width), (2, 15, 1));
+    // The lexer skipped to the next line and found the unclosed string too
+    let [string] = error.following() else { panic!("{}", error) };
+    assert_eq!((string.line, string.column, string.width), (3, 11, 3));
+    assert_eq!(Diagnostic::from(string).message, "unterminated string literal");
+    assert!(error.to_string().contains("AX0002"), "{}", error);
+}
@@ -0,0 +1,290 @@
+// Import resolution: `module` blocks and `use` declarations
+//
+//   module text.util {
+//       fn pad(s) { s ++ " " }
+//       fn trim(s) { pad(s) }      // a sibling: text.util.pad
+//   }
+//   use text.util;                 // util.pad is text.util.pad
+//   use text.util.trim;            // trim is text.util.trim
+//
+// A function in a module block is named by its full path, in the Core IR
+// too. resolve() rewrites every reference to one to that name before
+// lowering, so the rest of the pipeline keeps a flat namespace.
+//
+// An unqualified name is, in order: a local; a function of the enclosing
+// module (at top level: a top-level function) or a function the scope
+// imports; anything else (top-level, prelude and registry functions). Two
+// candidates at the second step are AX1016. A qualified name a.f is f of
+// the module imported as a, or a full path.
+//
+// `use` also takes registry modules (`module axis.fs` in a .axreg) and their
+// functions, which keep their flat names. A path that names nothing is
+// AX1015.
+//
+// A function of a module block that is not `pub fn` is private to that
+// block and the blocks nested in it; using or importing it anywhere else is
+// AX1017. Top-level functions are visible everywhere.
+//
+// POLICY: imports apply to the scope they are written in, not to nested
+// module blocks.
+
+use crate::core_validator::{pattern_binders, ValidationError};
+use crate::error_codes::ErrorCode;
+use crate::registry_loader::Registry;
+use crate::surface_parser::{Import, Module, SurfaceExpr, SurfaceStmt};
+use crate::surface_to_core::parse_pattern;
+use crate::symbol_index::SymbolIndex;
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Module(String),
+    // The function's name in the Core IR
+    Function(String),
+}
+
+struct Scope<'a> {
+    // Enclosing module block ("" at top level)
+    module: &'a str,
+    imports: Vec<(&'a Import, Target)>,
+}
+
+struct Resolver<'a> {
+    functions: HashSet<&'a str>,
+    // Module block functions without `pub`
+    private: HashSet<&'a str>,
+    modules: HashSet<String>,
+    registry: &'a Registry,
+}
+
+/// Resolve the imports of `module` and rewrite the references they and the
+/// module blocks introduce to full function names
+pub fn resolve(module: &mut Module, registry: &Registry) -> Result<(), ValidationError> {
+    if module.imports.is_empty() && !module.functions.iter().any(|f| f.name.contains('.')) {
+        return Ok(());
+    }
+    let names: Vec<String> = module.functions.iter().map(|f| f.name.clone()).collect();
+    let private: Vec<String> = module
+        .functions
+        .iter()
+        .filter(|f| !f.public && !f.module().is_empty())
+        .map(|f| f.name.clone())
+        .collect();
+    let resolver = Resolver::new(&names, &private, registry);
+    let mut targets = Vec::new();
+    for import in &module.imports {
+        let target = resolver.import_target(import)?;
+        if let Target::Function(function) = &target {
+            resolver.check_visible(function, &import.module, import.binds())?;
+        }
+        targets.push(target);
+    }
+
+    for function in &mut module.functions {
+        let function_module = function.module().to_string();
+        let scope = Scope {
+            module: &function_module,
+            imports: module
+                .imports
+                .iter()
+                .zip(&targets)
+                .filter(|(import, _)| import.module == function_module)
+                .map(|(import, target)| (import, target.clone()))
+                .collect(),
+        };
+        let locals: HashSet<String> = function.params.iter().cloned().collect();
+        resolver.expr(&mut function.body, &scope, &locals)?;
+    }
+    Ok(())
+}
+
+impl<'a> Resolver<'a> {
+    fn new(names: &'a [String], private: &'a [String], registry: &'a Registry) -> Self {
+        let mut modules = HashSet::new();
+        let module_paths = names
+            .iter()
+            .filter_map(|name| name.rsplit_once('.').map(|(module, _)| module))
+            .chain(registry.entries.values().filter_map(|entry| entry.module.as_deref()));
+        for path in module_paths {
+            // a.b.c declares a.b and a as well
+            let mut prefix = path;
+            modules.insert(prefix.to_string());
+            while let Some((parent, _)) = prefix.rsplit_once('.') {
+                modules.insert(parent.to_string());
+                prefix = parent;
+            }
+        }
+        Resolver {
+            functions: names.iter().map(String::as_str).collect(),
+            private: private.iter().map(String::as_str).collect(),
+            modules,
+            registry,
+        }
+    }
+
+    fn import_target(&self, import: &Import) -> Result<Target, ValidationError> {
+        let path = import.target();
+        if self.modules.contains(&path) {
+            return Ok(Target::Module(path));
+        }
+        if let Some(function) = self.function(&path) {
+            return Ok(Target::Function(function));
+        }
+        let mut error = ValidationError::new(format!(
+            "{}: `use {}` names no module or function",
+            ErrorCode::UnknownImport,
+            path
+        ));
+        error.name = Some(import.binds().to_string());
+        let mut known = SymbolIndex::default();
+        known.extend(self.modules.iter().map(String::as_str));
+        known.extend(self.functions.iter().copied().filter(|name| name.contains('.')));
+        error.help.push(known.did_you_mean(&path).unwrap_or_else(|| {
+            "import a `module` block, a function in one, or a registry module".to_string()
+        }));
+        Err(error)
+    }
+
+    // The Core IR name of the function at `path`: a module block function,
+    // a top-level function, or a registry function qualified by its module
+    fn function(&self, path: &str) -> Option<String> {
+        if self.functions.contains(path) {
+            return Some(path.to_string());
+        }
+        let (module, name) = path.rsplit_once('.')?;
+        let entry = self.registry.entries.get(name)?;
+        (entry.module.as_deref() == Some(module)).then(|| name.to_string())
+    }
+
+    // What `name`, written in `scope`, refers to; None leaves it unchanged
+    fn reference(&self, name: &str, scope: &Scope) -> Result<Option<String>, ValidationError> {
+        // Constructors, Type::Variant and lowering's own names
+        if name.starts_with(|c: char| c.is_uppercase()) || name.contains("::") || name.starts_with("__") {
+            return Ok(None);
+        }
+        let mut candidates = BTreeSet::new();
+        match name.split_once('.') {
+            Some((head, rest)) => {
+                for (import, target) in &scope.imports {
+                    if let Target::Module(path) = target
+                        && import.binds() == head
+                        && let Some(function) = self.function(&format!("{}.{}", path, rest))
+                    {
+                        candidates.insert(function);
+                    }
+                }
+                if candidates.is_empty()
+                    && let Some(function) = self.function(name)
+                {
+                    candidates.insert(function);
+                }
+            }
+            None => {
+                let sibling = if scope.module.is_empty() { name.to_string() } else { format!("{}.{}", scope.module, name) };
+                if self.functions.contains(sibling.as_str()) {
+                    candidates.insert(sibling);
+                }
+                for (import, target) in &scope.imports {
+                    if let Target::Function(function) = target
+                        && import.binds() == name
+                    {
+                        candidates.insert(function.clone());
+                    }
+                }
+            }
+        }
+        if candidates.len() > 1 {
+            let quoted: Vec<String> = candidates.iter().map(|c| format!("`{}`", c)).collect();
+            let mut error = ValidationError::new(format!(
+                "{}: `{}` could be {}",
+                ErrorCode::AmbiguousName,
+                name,
+                quoted.join(" or ")
+            ));
+            error.name = Some(name.rsplit('.').next().unwrap_or(name).to_string());
+            error.help.push("qualify the name with the module it comes from, or rename one of the functions".to_string());
+            return Err(error);
+        }
+        let resolved = candidates.pop_first();
+        if let Some(function) = &resolved {
+            self.check_visible(function, scope.module, name.rsplit('.').next().unwrap_or(name))?;
+        }
+        Ok(resolved.filter(|resolved| resolved != name))
+    }
+
+    // A private function is visible in its own module block and the blocks
+    // nested in it; `written` is the name as it appears in the source
+    fn check_visible(&self, function: &str, module: &str, written: &str) -> Result<(), ValidationError> {
+        if !self.private.contains(function) {
+            return Ok(());
+        }
+        let (owner, name) = function.rsplit_once('.').unwrap_or(("", function));
+        if module == owner || module.strip_prefix(owner).is_some_and(|rest| rest.starts_with('.')) {
+            return Ok(());
+        }
+        let mut error = ValidationError::new(format!(
+            "{}: `{}` is private to module `{}`",
+            ErrorCode::PrivateFunction,
+            function,
+            owner
+        ));
+        error.name = Some(written.to_string());
+        error.help.push(format!("declare it `pub fn {}` to use it outside `{}`", name, owner));
+        Err(error)
+    }
+
+    fn rename(&self, name: &mut String, scope: &Scope, locals: &HashSet<String>) -> Result<(), ValidationError> {
+        if !locals.contains(name.as_str())
+            && let Some(resolved) = self.reference(name, scope)?
+        {
+            *name = resolved;
+        }
+        Ok(())
+    }
+
+    fn expr(&self, expr: &mut SurfaceExpr, scope: &Scope, locals: &HashSet<String>) -> Result<(), ValidationError> {
+        match expr {
+            SurfaceExpr::Ident(name) => self.rename(name, scope, locals),
+            SurfaceExpr::Call(name, args) => {
+                self.rename(name, scope, locals)?;
+                args.iter_mut().try_for_each(|arg| self.expr(arg, scope, locals))
+            }
+            SurfaceExpr::Proj(inner, _) => self.expr(inner, scope, locals),
+            SurfaceExpr::Block(stmts) => {
+                let mut locals = locals.clone();
+                for stmt in stmts {
+                    match stmt {
+                        SurfaceStmt::Let(name, value) => {
+                            self.expr(value, scope, &locals)?;
+                            locals.insert(name.clone());
+                        }
+                        SurfaceStmt::LetPattern(pattern, value, else_expr) => {
+                            self.expr(value, scope, &locals)?;
+                            if let Some(else_expr) = else_expr {
+                                self.expr(else_expr, scope, &locals)?;
+                            }
+                            pattern_binders(&parse_pattern(pattern), &mut locals);
+                        }
+                        SurfaceStmt::Expr(value) => self.expr(value, scope, &locals)?,
+                    }
+                }
+                Ok(())
+            }
+            SurfaceExpr::Match(scrutinee, arms) => {
+                self.expr(scrutinee, scope, locals)?;
+                for arm in arms {
+                    let mut locals = locals.clone();
+                    pattern_binders(&parse_pattern(&arm.pattern), &mut locals);
+                    self.expr(&mut arm.expr, scope, &locals)?;
+                }
+                Ok(())
+            }
+            SurfaceExpr::If { cond, then_branch, else_branch } => {
+                self.expr(cond, scope, locals)?;
+                self.expr(then_branch, scope, locals)?;
+                self.expr(else_branch, scope, locals)
+            }
+            SurfaceExpr::IntLit(_) | SurfaceExpr::BoolLit(_) | SurfaceExpr::StringLit(_) | SurfaceExpr::UnitLit => Ok(()),
+        }
+    }
+}
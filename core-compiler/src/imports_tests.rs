@@ -0,0 +1,137 @@
+// Tests for `module` blocks and `use` resolution (imports.rs)
+
+use crate::error_codes::ErrorCode;
+use crate::imports;
+use crate::registry_loader::Registry;
+use crate::surface_parser::{self, Import, Module, SurfaceExpr};
+use crate::{core_validator, interpret, surface_lower, surface_to_core};
+
+const UTIL: &str = "
+module text.util {
+    pub fn pad(s) { s ++ \"-\" }
+    pub fn twice(s) { pad(pad(s)) }
+}
+";
+
+fn parse(source: &str) -> Module {
+    crate::init_string_table();
+    surface_parser::parse_module_with_file(source, "main.ax").unwrap()
+}
+
+fn resolved(source: &str) -> Module {
+    let mut module = parse(source);
+    imports::resolve(&mut module, &Registry::new()).unwrap();
+    module
+}
+
+fn body(module: &Module, name: &str) -> SurfaceExpr {
+    module.functions.iter().find(|f| f.name == name).unwrap().body.clone()
+}
+
+#[test]
+fn test_module_blocks_and_imports_are_recorded() {
+    let module = parse(&format!("{}use text.util;\nmodule app {{ use text.util.pad; fn main() {{ 0 }} }}\n", UTIL));
+    let names: Vec<&str> = module.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["text.util.pad", "text.util.twice", "app.main"]);
+    assert_eq!(module.functions[0].module(), "text.util");
+    assert!(module.functions[0].public && !module.functions[2].public);
+    assert_eq!(
+        module.imports,
+        [
+            Import { module: String::new(), path: vec!["text".into(), "util".into()] },
+            Import { module: "app".into(), path: vec!["text".into(), "util".into(), "pad".into()] },
+        ]
+    );
+    assert!(surface_parser::parse_module_with_file("module a { fn f() { 1 }", "main.ax").is_err());
+}
+
+#[test]
+fn test_references_resolve_to_full_names() {
+    let module = resolved(&format!(
+        "{}use text.util;\nuse text.util.twice;\nfn main() {{ let pad = 1; twice(util.pad(text.util.pad(\"x\"))) }}\n",
+        UTIL
+    ));
+    // The sibling call inside the module block
+    let twice = format!("{:?}", body(&module, "text.util.twice"));
+    assert!(twice.contains("Call(\"text.util.pad\", [Call(\"text.util.pad\""), "{}", twice);
+    // An item import, a module import and a full path; the local `pad` is untouched
+    let main = format!("{:?}", body(&module, "main"));
+    assert!(
+        main.contains("Call(\"text.util.twice\", [Call(\"text.util.pad\", [Call(\"text.util.pad\""),
+        "{}",
+        main
+    );
+    assert!(main.contains("Let(\"pad\", IntLit(1))"), "{}", main);
+}
+
+#[test]
+fn test_imports_run() {
+    let source = format!("{}use text.util.twice;\nfn axis_entry(args) {{ axis_str_len(twice(\"ab\")) }}\n", UTIL);
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(resolved(&source)));
+    assert!(!core_validator::validate_core(&term, &Registry::prelude()).has_errors());
+    assert_eq!(interpret::run_program(&term, &[]), 4);
+}
+
+#[test]
+fn test_unknown_import() {
+    let mut module = parse(&format!("{}use text.utils;\nfn main() {{ 0 }}\n", UTIL));
+    let error = imports::resolve(&mut module, &Registry::new()).expect_err("text.utils does not exist");
+    assert_eq!(error.message, "AX1015: `use text.utils` names no module or function");
+    assert_eq!(error.help, ["did you mean `text.util`?"]);
+    assert_eq!(error.to_diagnostic().code, Some(ErrorCode::UnknownImport));
+}
+
+#[test]
+fn test_ambiguous_names() {
+    // A top-level function and an imported one
+    let mut module = parse(&format!("{}use text.util.pad;\nfn pad(s) {{ s }}\nfn main() {{ pad(\"x\") }}\n", UTIL));
+    let error = imports::resolve(&mut module, &Registry::new()).expect_err("pad is ambiguous");
+    assert_eq!(error.message, "AX1016: `pad` could be `pad` or `text.util.pad`");
+
+    // Two module imports bound to the same name
+    let source = format!("{}module io.util {{ fn pad(s) {{ s }} }}\nuse text.util;\nuse io.util;\nfn main() {{ util.pad(\"x\") }}\n", UTIL);
+    let error = imports::resolve(&mut parse(&source), &Registry::new()).expect_err("util.pad is ambiguous");
+    assert_eq!(error.message, "AX1016: `util.pad` could be `io.util.pad` or `text.util.pad`");
+
+    // Without a reference there is nothing to resolve
+    resolved(&format!("{}use text.util.pad;\nfn pad(s) {{ s }}\n", UTIL));
+}
+
+#[test]
+fn test_registry_modules_can_be_imported() {
+    let mut registry = Registry::new();
+    registry
+        .load_from_str("axreg 2\nfn axis_fs_read\n  arity 1\n  deterministic false\n  profile core\n  module axis.fs\nend\n", "fs.axreg")
+        .unwrap();
+    let mut module = parse("use axis.fs;\nuse axis.fs.axis_fs_read;\nfn main() { fs.axis_fs_read(axis_fs_read(\"a\")) }\n");
+    imports::resolve(&mut module, &registry).unwrap();
+    let main = format!("{:?}", body(&module, "main"));
+    assert!(main.contains("Call(\"axis_fs_read\", [Call(\"axis_fs_read\""), "{}", main);
+}
+
+#[test]
+fn test_private_functions_stay_in_their_module() {
+    let source = "
+module text.util {
+    fn pad(s) { s ++ \"-\" }
+    @inline pub fn twice(s) { pad(pad(s)) }
+    module inner { fn f(s) { text.util.pad(s) } }
+}
+";
+    let module = parse(source);
+    assert!(!module.functions[0].public);
+    assert!(module.functions[1].public && module.functions[1].has_attribute("inline"));
+    // The module itself and the blocks nested in it see pad
+    resolved(&format!("{}fn main() {{ text.util.twice(\"x\") }}\n", source));
+
+    let mut module = parse(&format!("{}fn main() {{ text.util.pad(\"x\") }}\n", source));
+    let error = imports::resolve(&mut module, &Registry::new()).expect_err("pad is private");
+    assert_eq!(error.message, "AX1017: `text.util.pad` is private to module `text.util`");
+    assert_eq!(error.name.as_deref(), Some("pad"));
+    assert_eq!(error.help, ["declare it `pub fn pad` to use it outside `text.util`"]);
+
+    // Importing it is using it
+    let mut module = parse(&format!("{}use text.util.pad;\nfn main() {{ 0 }}\n", source));
+    let error = imports::resolve(&mut module, &Registry::new()).expect_err("pad is private");
+    assert_eq!(error.to_diagnostic().code, Some(ErrorCode::PrivateFunction));
+}
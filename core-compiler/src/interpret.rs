@@ -0,0 +1,290 @@
+// `axis-compiler run`: execute a Core bundle with the tree-walking
+// interpreter (runtime.rs)
+//
+// The interpreter is the reference for what a program means; running the
+// same bundle here and as a bridge-built binary and comparing the two
+// (tests/e2e/run.sh --differential) catches emitter bugs that no golden
+// file was written for.
+//
+// RESULT CONTRACT (exit code), as for a bridge-built binary
+// (rust-bridge/src/runtime/error.rs):
+//   n      - axis_entry returned Int(n)
+//   0      - axis_entry returned Unit or Ok(..); any other value but
+//            Err(..) also exits 0, after a warning
+//   1      - axis_entry returned Err(e), printed to stderr
+//   3      - an axis_assert_eq / axis_assert_true / axis_fail call failed
+//   70     - runtime error
+//   125    - the program needs something the interpreter does not
+//            implement; nothing was compared (as for `git bisect run`)
+//
+// POLICY: the effectful primitives here are only those whose output is
+// the same in both runtimes. Printing a tuple or constructor is reported
+// as unsupported: the bridge prints its internal representation.
+
+use crate::core_interface;
+use crate::registry_loader::Registry;
+use crate::runtime::{self, CoreTerm, Native, Value};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+pub const ASSERTION_FAILED_EXIT_CODE: i32 = 3;
+pub const ENTRY_ERR_EXIT_CODE: i32 = 1;
+pub const RUNTIME_ERROR_EXIT_CODE: i32 = 70;
+pub const UNSUPPORTED_EXIT_CODE: i32 = 125;
+
+// Runtime errors are string handles; this prefix (ErrorCode::InterpreterUnsupported)
+// marks the unsupported ones
+const UNSUPPORTED: &str = "AX4001: ";
+
+thread_local! {
+    static ASSERTION_FAILURES: Cell<usize> = const { Cell::new(0) };
+}
+
+// The bridge runtime's spellings of each effect (emit_rust's foreign mapping)
+const EFFECTS: &[(&str, usize, Native)] = &[
+    ("io_print", 1, io_print),
+    ("axis_io_print", 1, io_print),
+    ("io_print_buffered", 1, io_print_buffered),
+    ("axis_io_print_buffered", 1, io_print_buffered),
+    ("io_println", 1, io_println),
+    ("axis_io_println", 1, io_println),
+    ("io_flush", 1, io_flush),
+    ("axis_io_flush", 1, io_flush),
+    ("assert_eq", 2, assert_eq),
+    ("axis_assert_eq", 2, assert_eq),
+    ("assert_true", 1, assert_true),
+    ("axis_assert_true", 1, assert_true),
+    ("fail", 1, fail),
+    ("axis_fail", 1, fail),
+    ("axis_memo_get", 2, memo_get),
+    ("axis_memo_put", 3, memo_put),
+    ("io_read_bytes", 1, io_read_bytes),
+    ("axis_io_read_bytes", 1, io_read_bytes),
+    ("fs_read_bytes", 1, fs_read_bytes),
+    ("axis_fs_read_bytes", 1, fs_read_bytes),
+    ("fs_write_bytes", 2, fs_write_bytes),
+    ("axis_fs_write_bytes", 2, fs_write_bytes),
+];
+
+fn error(message: String) -> i64 {
+    -crate::intern_string(message)
+}
+
+// What io_print and its buffered forms write for a value
+fn display_text(args: Vec<Value>, what: &str) -> Result<String, i64> {
+    match args.into_iter().next().unwrap_or(Value::Unit) {
+        Value::Str(handle) => Ok(crate::get_string(handle)),
+        Value::Int(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Unit => Ok("()".to_string()),
+        _ => Err(error(format!("{}{} of a compound value", UNSUPPORTED, what))),
+    }
+}
+
+fn io_print(args: Vec<Value>) -> Result<Value, i64> {
+    print!("{}", display_text(args, "io_print")?);
+    let _ = std::io::stdout().flush();
+    Ok(Value::Unit)
+}
+
+// Rust's stdout holds a partial line until a newline, io_flush or exit
+fn io_print_buffered(args: Vec<Value>) -> Result<Value, i64> {
+    print!("{}", display_text(args, "io_print_buffered")?);
+    Ok(Value::Unit)
+}
+
+fn io_println(args: Vec<Value>) -> Result<Value, i64> {
+    println!("{}", display_text(args, "io_println")?);
+    Ok(Value::Unit)
+}
+
+fn io_flush(_args: Vec<Value>) -> Result<Value, i64> {
+    let _ = std::io::stdout().flush();
+    Ok(Value::Unit)
+}
+
+// Ok(value), or Err with the error's text, as the bridge's IO primitives
+// (rust-bridge/src/runtime/io.rs) return them
+fn io_result(result: std::io::Result<Value>) -> Value {
+    match result {
+        Ok(value) => Value::Enum("Ok".to_string(), vec![value]),
+        Err(e) => Value::Enum("Err".to_string(), vec![Value::Str(crate::intern_string(e.to_string()))]),
+    }
+}
+
+fn io_read_bytes(_args: Vec<Value>) -> Result<Value, i64> {
+    let mut data = Vec::new();
+    Ok(io_result(std::io::stdin().lock().read_to_end(&mut data).map(|_| Value::Bytes(data))))
+}
+
+fn fs_read_bytes(args: Vec<Value>) -> Result<Value, i64> {
+    match args.into_iter().next() {
+        Some(Value::Str(path)) => Ok(io_result(std::fs::read(crate::get_string(path)).map(Value::Bytes))),
+        _ => Ok(io_result(Err(std::io::Error::other("Invalid path")))),
+    }
+}
+
+fn fs_write_bytes(args: Vec<Value>) -> Result<Value, i64> {
+    match args.into_iter().next() {
+        Some(Value::Tuple(elems)) => match elems.as_slice() {
+            [Value::Str(path), Value::Bytes(data), ..] => {
+                Ok(io_result(std::fs::write(crate::get_string(*path), data).map(|_| Value::Unit)))
+            }
+            _ => Ok(io_result(Err(std::io::Error::other("Invalid arguments")))),
+        },
+        _ => Ok(io_result(Err(std::io::Error::other("Invalid arguments")))),
+    }
+}
+
+// Failed assertions are counted and the program carries on, as in the bridge
+fn assertion_failure(message: &str) {
+    eprintln!("{}", message);
+    ASSERTION_FAILURES.with(|failures| failures.set(failures.get() + 1));
+}
+
+fn assert_eq(args: Vec<Value>) -> Result<Value, i64> {
+    match args.into_iter().next() {
+        Some(Value::Tuple(elems)) if elems.len() == 2 => {
+            if !runtime::values_equal(&elems[0], &elems[1]) {
+                assertion_failure("assertion failed: left == right");
+            }
+            Ok(Value::Unit)
+        }
+        _ => Err(error("assert_eq expects (left, right)".to_string())),
+    }
+}
+
+fn assert_true(args: Vec<Value>) -> Result<Value, i64> {
+    if !matches!(args.first(), Some(Value::Bool(true))) {
+        assertion_failure("assertion failed: expected true");
+    }
+    Ok(Value::Unit)
+}
+
+fn fail(args: Vec<Value>) -> Result<Value, i64> {
+    let text = match args.into_iter().next() {
+        Some(Value::Str(handle)) => crate::get_string(handle),
+        other => format!("{:?}", other),
+    };
+    assertion_failure(&format!("failed: {}", text));
+    Ok(Value::Unit)
+}
+
+// A @memo cache (memo.rs) never changes a result, so the interpreter keeps
+// none: every lookup misses and the function body runs
+fn memo_get(_args: Vec<Value>) -> Result<Value, i64> {
+    Ok(Value::Enum("None".to_string(), vec![]))
+}
+
+fn memo_put(args: Vec<Value>) -> Result<Value, i64> {
+    match args.into_iter().next() {
+        Some(Value::Tuple(mut elems)) if elems.len() == 3 => Ok(elems.pop().unwrap_or(Value::Unit)),
+        _ => Err(error("memo_put expects (name, key, value)".to_string())),
+    }
+}
+
+// `Cons(arg, ...)` ending in `Nil`, as the bridge's main passes them
+fn args_list(args: &[String]) -> Value {
+    args.iter().rev().fold(Value::Enum("Nil".to_string(), vec![]), |tail, arg| {
+        Value::Enum("Cons".to_string(), vec![Value::Str(crate::intern_string(arg.clone())), tail])
+    })
+}
+
+/// Run a program's `axis_entry` (or `main`) with `args`; returns the exit code
+pub fn run_program(term: &CoreTerm, args: &[String]) -> i32 {
+    crate::init_string_table();
+    runtime::clear_globals();
+    ASSERTION_FAILURES.with(|failures| failures.set(0));
+    let mut env = (*runtime::primitive_env()).clone();
+    runtime::bind_primitives(&mut env, EFFECTS);
+    // The emitters read these names as literals
+    env.insert("true".to_string(), Value::Bool(true));
+    env.insert("false".to_string(), Value::Bool(false));
+
+    // FAIL-FAST: a name the interpreter cannot bind would only surface when
+    // (and if) it is reached. eval builds `__tuple__` chains itself.
+    let missing: Vec<String> = core_interface::compute_interface(term, &Registry::new())
+        .imports
+        .into_iter()
+        .map(|import| import.name)
+        .filter(|name| !env.contains_key(name) && name != "__tuple__")
+        .collect();
+    if !missing.is_empty() {
+        eprintln!("{}not implemented by the interpreter: {}", UNSUPPORTED, missing.join(", "));
+        return UNSUPPORTED_EXIT_CODE;
+    }
+    let env = Rc::new(env);
+
+    // Top-level definitions, in order. Functions look each other up when
+    // called, so only values are limited to the definitions before them.
+    let mut current = term;
+    while let CoreTerm::Let(name, value, body, _) = current {
+        match runtime::eval(value, &env) {
+            Ok(v) => runtime::define_global(name.as_str(), v),
+            Err(handle) => return runtime_error(handle),
+        }
+        current = body;
+    }
+
+    // The bridge emits `main` as axis_entry
+    let Some(entry) = ["axis_entry", "main"].iter().find_map(|name| runtime::global(name)) else {
+        eprintln!("{}no axis_entry or main function", UNSUPPORTED);
+        return UNSUPPORTED_EXIT_CODE;
+    };
+    let call = CoreTerm::App(
+        Rc::new(CoreTerm::Var("#entry".into(), None)),
+        Rc::new(CoreTerm::Var("#args".into(), None)),
+        None,
+    );
+    let mut call_env = HashMap::new();
+    call_env.insert("#entry".to_string(), entry);
+    call_env.insert("#args".to_string(), args_list(args));
+    let result = runtime::eval(&call, &Rc::new(call_env));
+
+    match result {
+        Err(handle) => runtime_error(handle),
+        Ok(_) if ASSERTION_FAILURES.with(Cell::get) > 0 => ASSERTION_FAILED_EXIT_CODE,
+        Ok(value) => exit_code(value),
+    }
+}
+
+// The exit code of axis_entry's result, as the bridge's main wrapper
+// (rust-bridge/src/runtime/entry.rs)
+fn exit_code(result: Value) -> i32 {
+    match result {
+        Value::Int(n) => n as i32,
+        Value::Unit => 0,
+        Value::Enum(tag, fields) if tag == "Ok" && fields.len() == 1 => 0,
+        Value::Enum(tag, fields) if tag == "Err" && fields.len() == 1 => {
+            match &fields[0] {
+                Value::Str(handle) => eprintln!("error: {}", crate::get_string(*handle)),
+                other => eprintln!("error: {:?}", other),
+            }
+            ENTRY_ERR_EXIT_CODE
+        }
+        other => {
+            let kind = match &other {
+                Value::Enum(tag, _) => format!("constructor {}", tag),
+                Value::Bool(_) => "Bool".to_string(),
+                Value::Str(_) => "Str".to_string(),
+                Value::Bytes(_) => "Bytes".to_string(),
+                Value::Tuple(_) => "Tuple".to_string(),
+                _ => "function".to_string(),
+            };
+            eprintln!("warning: axis_entry returned a {}, not an Int, Unit or Result; exiting with 0", kind);
+            0
+        }
+    }
+}
+
+fn runtime_error(handle: i64) -> i32 {
+    let message = crate::get_string(-handle);
+    if message.starts_with(UNSUPPORTED) {
+        eprintln!("{}", message);
+        return UNSUPPORTED_EXIT_CODE;
+    }
+    eprintln!("Axis runtime error: {}", message);
+    RUNTIME_ERROR_EXIT_CODE
+}
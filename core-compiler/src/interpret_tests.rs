@@ -0,0 +1,218 @@
+// Tests for `axis-compiler run` (interpret.rs): the exit codes the
+// differential e2e mode compares against a bridge-built binary
+
+use crate::interpret::{
+    self, ASSERTION_FAILED_EXIT_CODE, ENTRY_ERR_EXIT_CODE, RUNTIME_ERROR_EXIT_CODE, UNSUPPORTED_EXIT_CODE,
+};
+use crate::runtime::CoreTerm;
+use crate::{surface_lower, surface_parser, surface_to_core};
+use std::rc::Rc;
+
+fn compile(source: &str) -> CoreTerm {
+    // Lowering interns string literals
+    crate::init_string_table();
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    surface_to_core::value_to_core(&surface_lower::lower_module(module))
+}
+
+fn run(source: &str) -> i32 {
+    interpret::run_program(&compile(source), &[])
+}
+
+#[test]
+fn test_entry_result_is_the_exit_code() {
+    let source = "fn fact(n: Int) -> Int {\n    if n == 0 { 1 } else { n * fact(n - 1) }\n}\n\
+                  fn axis_entry(args: Unit) -> Int {\n    fact(4) - 20\n}\n";
+    assert_eq!(run(source), 4);
+}
+
+#[test]
+fn test_result_and_unit_entry_results() {
+    let source = "fn axis_entry(args: Unit) -> Result[Int, Str] {\n    Err(\"no input\")\n}\n";
+    assert_eq!(run(source), ENTRY_ERR_EXIT_CODE);
+    assert_eq!(run("fn axis_entry(args: Unit) -> Result[Int, Str] {\n    Ok(5)\n}\n"), 0);
+    assert_eq!(run("fn axis_entry(args: Unit) -> Unit {\n    ()\n}\n"), 0);
+    // Anything else exits 0 after a warning
+    assert_eq!(run("fn axis_entry(args: Unit) -> Bool {\n    true\n}\n"), 0);
+}
+
+#[test]
+fn test_functions_can_call_later_definitions() {
+    let source = "fn is_even(n: Int) -> Bool {\n    if n == 0 { true } else { is_odd(n - 1) }\n}\n\
+                  fn is_odd(n: Int) -> Bool {\n    if n == 0 { false } else { is_even(n - 1) }\n}\n\
+                  fn axis_entry(args: Unit) -> Int {\n    if is_even(10) { 7 } else { 8 }\n}\n";
+    assert_eq!(run(source), 7);
+}
+
+#[test]
+fn test_failed_assertions_exit_3_after_running_to_the_end() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    axis_assert_eq(1, 2);\n    axis_assert_true(1 == 1);\n    42\n}\n";
+    assert_eq!(run(source), ASSERTION_FAILED_EXIT_CODE);
+    // The count starts again for every program
+    assert_eq!(run("fn axis_entry(args: Unit) -> Int {\n    axis_assert_eq(2, 2);\n    0\n}\n"), 0);
+}
+
+#[test]
+fn test_runtime_errors_exit_70() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    let f = 1;\n    f(2)\n}\n";
+    assert_eq!(run(source), RUNTIME_ERROR_EXIT_CODE);
+}
+
+#[test]
+fn test_unimplemented_primitives_are_skipped_not_failed() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    axis_fs_read(\"x\");\n    0\n}\n";
+    assert_eq!(run(source), UNSUPPORTED_EXIT_CODE);
+}
+
+#[test]
+fn test_program_without_entry_is_unsupported() {
+    assert_eq!(run("fn helper() -> Int { 1 }\n"), UNSUPPORTED_EXIT_CODE);
+}
+
+#[test]
+fn test_projection_is_zero_based() {
+    // axis_entry = \args. proj((10, 20, 30), 1)
+    let pair = CoreTerm::Tuple(vec![CoreTerm::IntLit(10, None), CoreTerm::IntLit(20, None), CoreTerm::IntLit(30, None)], None);
+    let entry = CoreTerm::Lam("args".into(), Rc::new(CoreTerm::Proj(Rc::new(pair), 1, None)), None);
+    let program = CoreTerm::Let("axis_entry".into(), Rc::new(entry), Rc::new(CoreTerm::UnitLit(None)), None);
+    assert_eq!(interpret::run_program(&program, &[]), 20);
+}
+
+#[test]
+fn test_tuple_literals_are_one_tuple() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    let t = (1, (20, 300));\n    let (a, (b, c)) = t;\n    \
+                  if t != (1, (20, 0)) { a + b + c + proj(t, 0) } else { 0 }\n}\n";
+    assert_eq!(run(source), 322);
+}
+
+#[test]
+fn test_let_patterns_destructure_nested_values() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    let Pair(a, Pair(b, c)) = Pair(1, Pair(20, 300));\n    let Some(Cons(x, _)) = Some(Cons(4000, Nil)) else { 0 };\n    a + b + c + x - 4321\n}\n";
+    assert_eq!(run(source), 0);
+}
+
+#[test]
+fn test_as_patterns_bind_the_whole_value_too() {
+    let source = "fn rest(xs: Unit) -> Unit {\n    match xs {\n        Cons(_, tail @ Cons(_, _)) => tail,\n        all @ _ => all,\n    }\n}\n\
+                  fn axis_entry(args: Unit) -> Int {\n    let whole @ Pair(a, b @ Some(c)) = Pair(1, Some(20));\n    \
+                  let Pair(_, Some(d)) = whole;\n    let Cons(e, _) = rest(Cons(1, Cons(300, Nil)));\n    \
+                  let Some(f) = b;\n    a + c + d + e + f - 361\n}\n";
+    assert_eq!(run(source), 0);
+}
+
+#[test]
+fn test_let_else_runs_when_the_pattern_does_not_match() {
+    let source = "fn first(o: Unit) -> Int {\n    let Some(x) = o else { 9 };\n    x\n}\n\
+                  fn axis_entry(args: Unit) -> Int {\n    first(None) + first(Some(1))\n}\n";
+    assert_eq!(run(source), 10);
+}
+
+#[test]
+fn test_checked_arithmetic_returns_an_option() {
+    let source = "fn or_minus_one(result: Option[Int]) -> Int {\n    match result {\n        Some(n) => n,\n        None => 0 - 1,\n    }\n}\n\
+                  fn axis_entry(args: Unit) -> Int {\n    \
+                  let big = axis_checked_add(9223372036854775807, 1);\n    \
+                  let zero = axis_checked_div(7, 0);\n    \
+                  or_minus_one(axis_checked_mul(6, 7)) + or_minus_one(big) + or_minus_one(zero)\n}\n";
+    assert_eq!(run(source), 40);
+}
+
+#[test]
+fn test_strings_and_constructors_are_ordered() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    \
+                  let a = if \"apple\" < \"banana\" { 1 } else { 0 };\n    \
+                  let b = if Some(\"b\") > Some(\"a\") { 10 } else { 0 };\n    \
+                  let c = axis_value_cmp(\"b\", \"a\") + axis_value_cmp(2, 2);\n    \
+                  a + b + 100 * c\n}\n";
+    assert_eq!(run(source), 111);
+}
+
+#[test]
+fn test_list_sort_by_takes_a_compare_function() {
+    let source = "fn ascending(a: Int, b: Int) -> Int {\n    axis_value_cmp(a, b)\n}\n\n\
+                  fn descending(a: Int, b: Int) -> Int {\n    axis_value_cmp(b, a)\n}\n\n\
+                  fn digits(xs: List[Int], acc: Int) -> Int {\n    \
+                  match xs {\n        Cons(x, rest) => digits(rest, acc * 10 + x),\n        Nil => acc,\n    }\n}\n\n\
+                  fn axis_entry(args: Unit) -> Int {\n    \
+                  let xs = Cons(2, Cons(3, Cons(1, Nil)));\n    \
+                  digits(axis_list_sort_by(descending, xs), 0) - \
+                  digits(axis_list_sort_by(ascending, xs), 0)\n}\n";
+    assert_eq!(run(source), 321 - 123);
+}
+
+#[test]
+fn test_string_utilities() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    \
+                  let parts = axis_str_split(axis_str_trim(\"  a,bb,ccc \"), \",\");\n    \
+                  let joined = axis_str_join(parts, \"--\");\n    \
+                  let a = if axis_str_starts_with(joined, \"a--\") && axis_str_ends_with(joined, \"ccc\") { 1 } else { 0 };\n    \
+                  let b = if axis_str_contains(axis_str_to_upper(joined), \"BB\") { 10 } else { 0 };\n    \
+                  let c = 100 * axis_str_len(axis_str_replace(axis_str_to_lower(\"XoXo\"), \"o\", \"oo\"));\n    \
+                  let d = 1000 * axis_str_len(joined);\n    \
+                  a + b + c + d\n}\n";
+    assert_eq!(run(source), 1 + 10 + 600 + 10_000);
+}
+
+#[test]
+fn test_char_primitives() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    \
+                  let c = axis_char_from_str(\"7_\");\n    \
+                  let a = if axis_char_is_digit(c) && axis_char_is_ident_char(c) { 1 } else { 0 };\n    \
+                  let b = if axis_char_is_alpha(c) || axis_char_is_whitespace(c) { 0 } else { 10 };\n    \
+                  let u = axis_char_to_str(axis_char_to_upper(axis_char_from_str(\"q\")));\n    \
+                  let d = if u == \"Q\" { 1000 } else { 0 };\n    \
+                  let n = 100 * axis_char_to_digit(c);\n    \
+                  a + b + n + d\n}\n";
+    assert_eq!(run(source), 1 + 10 + 700 + 1000);
+}
+
+#[test]
+fn test_bytes_primitives() {
+    let path = std::env::temp_dir().join(format!("axis-interpret-bytes-{}", std::process::id()));
+    let copy = path.with_extension("copy");
+    std::fs::write(&path, [7u8, 200, 3, 4]).unwrap();
+    let source = format!(
+        "fn axis_entry(args: Unit) -> Int {{\n    \
+         match axis_fs_read_bytes({:?}) {{\n        \
+         Ok(data) => {{\n            \
+         let tail = axis_bytes_slice(data, 1, 10);\n            \
+         let copied = match axis_fs_write_bytes({:?}, tail) {{ Ok(u) => 1, Err(e) => 0 }};\n            \
+         let missing = if axis_bytes_get(data, 4) < 0 {{ 10 }} else {{ 0 }};\n            \
+         copied + missing + 100 * axis_bytes_len(tail) + 1000 * axis_bytes_get(tail, 0)\n        \
+         }},\n        \
+         Err(e) => 1\n    \
+         }}\n}}\n",
+        path.to_string_lossy(),
+        copy.to_string_lossy()
+    );
+    assert_eq!(run(&source), 1 + 10 + 300 + 200_000);
+    assert_eq!(std::fs::read(&copy).unwrap(), vec![200, 3, 4]);
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&copy).unwrap();
+}
+
+#[test]
+fn test_if_without_else_runs_its_block_only_when_true() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    if 1 == 2 { axis_assert_eq(1, 2) }\n    if 1 == 1 { axis_assert_eq(1, 1); }\n    0\n}\n";
+    assert_eq!(run(source), 0);
+    let source = "fn axis_entry(args: Unit) -> Int {\n    if 1 == 1 { axis_assert_eq(1, 2) };\n    0\n}\n";
+    assert_eq!(run(source), ASSERTION_FAILED_EXIT_CODE);
+}
+
+#[test]
+fn test_and_or_evaluate_the_right_operand_only_when_needed() {
+    // boom() records a failed assertion each time it is called
+    let program = |condition: &str| {
+        format!(
+            "fn boom() -> Bool {{\n    axis_assert_eq(1, 2);\n    true\n}}\n\
+             fn axis_entry(args: Unit) -> Int {{\n    if {} {{ 1 }} else {{ 0 }}\n}}\n",
+            condition
+        )
+    };
+    assert_eq!(run(&program("false && boom()")), 0);
+    assert_eq!(run(&program("true || boom()")), 1);
+    assert_eq!(run(&program("true && boom()")), ASSERTION_FAILED_EXIT_CODE);
+    assert_eq!(run(&program("false || boom()")), ASSERTION_FAILED_EXIT_CODE);
+    // The left operand always runs
+    assert_eq!(run(&program("boom() || false")), ASSERTION_FAILED_EXIT_CODE);
+}
@@ -0,0 +1,201 @@
+// Tokenizer for Axis surface syntax
+//
+// lex() turns source text into tokens with their kind and location, for
+// the surface parser and for tools that only need the token stream
+// (syntax highlighting, the formatter). Line comments and whitespace are
+// dropped.
+//
+// A string literal keeps its quotes and escapes as written; the parser
+// unescapes it. Strings may span lines.
+//
+// An unterminated string or a character no token starts with is a
+// lexical error. The lexer then skips to the next line and carries on, so
+// lex() reports every such error (at most one per line) at once; tokens()
+// returns them as TokenKind::Error tokens instead.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    // Names and keywords (KEYWORDS): `main`, `fn`, `list_cons`
+    Identifier,
+    // Decimal digits; `-` is a separate operator token
+    Int,
+    // A string literal with its quotes: `"a\n"`
+    String,
+    // `+ - * / % < > = == != <= >= && || ++ |>`
+    Operator,
+    // Brackets and punctuation: `( ) { } [ ] , ; : . :: -> => @ |`
+    Delimiter,
+    // Text lex() rejects: an unterminated string (from its quote to the
+    // end of that line) or a character no token starts with
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    // 1-based, counted in characters
+    pub line: usize,
+    pub column: usize,
+    pub byte_offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub location: SourceLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub line: usize,
+    pub column: usize,
+    // Characters of the offending text
+    pub width: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Words the grammar gives a meaning. They lex as identifiers but cannot
+/// name a function, parameter, variable, field or module; `proj` is only
+/// ever called (`proj(pair, 0)`).
+pub const KEYWORDS: &[&str] =
+    &["else", "false", "fn", "foreign", "if", "in", "let", "match", "module", "proj", "pub", "true", "type", "use"];
+
+pub fn is_keyword(text: &str) -> bool {
+    KEYWORDS.contains(&text)
+}
+
+const TWO_CHAR_OPERATORS: &[&str] = &["++", "==", "!=", ">=", "<=", "&&", "||", "|>"];
+const TWO_CHAR_DELIMITERS: &[&str] = &["->", "=>", "::"];
+const OPERATORS: &str = "+-*/%<>=";
+const DELIMITERS: &str = "(){}[],.;:@|";
+
+/// The tokens of `source`, or every text no token can be made of
+pub fn lex(source: &str) -> Result<Vec<Token>, Vec<LexError>> {
+    let tokens = tokens(source);
+    let errors: Vec<LexError> = tokens.iter().filter(|token| token.kind == TokenKind::Error).map(error_of).collect();
+    if errors.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Every token of `source`, with TokenKind::Error tokens where lex() fails
+pub fn tokens(source: &str) -> Vec<Token> {
+    let mut lexer = Lexer { rest: source, line: 1, column: 1, byte_offset: 0, tokens: Vec::new() };
+    lexer.run();
+    lexer.tokens
+}
+
+/// Why lex() rejects an Error token
+pub fn error_of(token: &Token) -> LexError {
+    let message = if token.text.starts_with('"') {
+        "unterminated string literal".to_string()
+    } else {
+        format!("unknown character {:?}", token.text)
+    };
+    LexError {
+        line: token.location.line,
+        column: token.location.column,
+        width: token.text.chars().count().max(1),
+        message,
+    }
+}
+
+struct Lexer<'a> {
+    // Not yet tokenized
+    rest: &'a str,
+    line: usize,
+    column: usize,
+    byte_offset: usize,
+    tokens: Vec<Token>,
+}
+
+impl Lexer<'_> {
+    fn run(&mut self) {
+        while let Some(ch) = self.rest.chars().next() {
+            let location = SourceLocation { line: self.line, column: self.column, byte_offset: self.byte_offset };
+            let two = self.rest.get(..2).unwrap_or("");
+            if ch.is_whitespace() {
+                self.advance(ch.len_utf8());
+            } else if two == "//" {
+                self.skip_line();
+            } else if ch.is_alphabetic() || ch == '_' {
+                let end = self.rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(self.rest.len());
+                self.push(TokenKind::Identifier, end, location);
+            } else if ch.is_numeric() {
+                let end = self.rest.find(|c: char| !c.is_numeric()).unwrap_or(self.rest.len());
+                self.push(TokenKind::Int, end, location);
+            } else if TWO_CHAR_OPERATORS.contains(&two) {
+                self.push(TokenKind::Operator, 2, location);
+            } else if TWO_CHAR_DELIMITERS.contains(&two) {
+                self.push(TokenKind::Delimiter, 2, location);
+            } else if ch == '"' {
+                match string_end(self.rest) {
+                    Some(end) => self.push(TokenKind::String, end, location),
+                    None => {
+                        let end = self.rest.find(['\r', '\n']).unwrap_or(self.rest.len());
+                        self.push(TokenKind::Error, end, location);
+                    }
+                }
+            } else if OPERATORS.contains(ch) {
+                self.push(TokenKind::Operator, 1, location);
+            } else if DELIMITERS.contains(ch) {
+                self.push(TokenKind::Delimiter, 1, location);
+            } else {
+                self.push(TokenKind::Error, ch.len_utf8(), location);
+                self.skip_line();
+            }
+        }
+    }
+
+    // The next `len` bytes as one token
+    fn push(&mut self, kind: TokenKind, len: usize, location: SourceLocation) {
+        let text = self.rest[..len].to_string();
+        self.advance(len);
+        self.tokens.push(Token { kind, text, location });
+    }
+
+    // Up to the end of the line, leaving the newline
+    fn skip_line(&mut self) {
+        let end = self.rest.find('\n').unwrap_or(self.rest.len());
+        self.advance(end);
+    }
+
+    fn advance(&mut self, len: usize) {
+        for ch in self.rest[..len].chars() {
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.byte_offset += len;
+        self.rest = &self.rest[len..];
+    }
+}
+
+// Byte length of the string literal `text` starts with, through its
+// closing quote; None if the input ends first
+fn string_end(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some(i + 1),
+            '\\' => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,115 @@
+// Tests for the surface tokenizer (lexer.rs)
+
+use crate::lexer::{is_keyword, lex, tokens, LexError, TokenKind, KEYWORDS};
+
+fn kinds(source: &str) -> Vec<(TokenKind, String)> {
+    lex(source).unwrap().into_iter().map(|token| (token.kind, token.text)).collect()
+}
+
+#[test]
+fn test_lex_classifies_tokens() {
+    use TokenKind::*;
+    assert_eq!(
+        kinds(r#"fn main() { let s = "a\"b"; f(s, 42) -> x :: y }"#),
+        vec![
+            (Identifier, "fn".to_string()),
+            (Identifier, "main".to_string()),
+            (Delimiter, "(".to_string()),
+            (Delimiter, ")".to_string()),
+            (Delimiter, "{".to_string()),
+            (Identifier, "let".to_string()),
+            (Identifier, "s".to_string()),
+            (Operator, "=".to_string()),
+            (String, r#""a\"b""#.to_string()),
+            (Delimiter, ";".to_string()),
+            (Identifier, "f".to_string()),
+            (Delimiter, "(".to_string()),
+            (Identifier, "s".to_string()),
+            (Delimiter, ",".to_string()),
+            (Int, "42".to_string()),
+            (Delimiter, ")".to_string()),
+            (Delimiter, "->".to_string()),
+            (Identifier, "x".to_string()),
+            (Delimiter, "::".to_string()),
+            (Identifier, "y".to_string()),
+            (Delimiter, "}".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_two_character_operators_are_one_token() {
+    let texts: Vec<String> = kinds("a++b == c != d <= e >= f && g || h => i |> j")
+        .into_iter()
+        .filter(|(kind, _)| *kind != TokenKind::Identifier)
+        .map(|(_, text)| text)
+        .collect();
+    assert_eq!(texts, ["++", "==", "!=", "<=", ">=", "&&", "||", "=>", "|>"]);
+}
+
+#[test]
+fn test_locations_skip_comments_and_count_characters() {
+    let tokens = lex("// héllo\n  x\n\"é\" y").unwrap();
+    let locations: Vec<(usize, usize, usize)> =
+        tokens.iter().map(|t| (t.location.line, t.location.column, t.location.byte_offset)).collect();
+    // `é` is two bytes but one column
+    assert_eq!(locations, [(2, 3, 12), (3, 1, 14), (3, 5, 19)]);
+}
+
+#[test]
+fn test_string_spanning_lines_starts_where_it_opens() {
+    let tokens = lex("x \"a\nb\" y").unwrap();
+    assert_eq!(tokens[1].text, "\"a\nb\"");
+    assert_eq!((tokens[1].location.line, tokens[1].location.column), (1, 3));
+    assert_eq!((tokens[2].location.line, tokens[2].location.column), (2, 4));
+}
+
+#[test]
+fn test_unterminated_string_is_an_error_to_the_end_of_its_line() {
+    assert_eq!(
+        lex("let s = \"abc\\\"\n").unwrap_err(),
+        [LexError { line: 1, column: 9, width: 6, message: "unterminated string literal".to_string() }]
+    );
+}
+
+#[test]
+fn test_unknown_character_is_an_error() {
+    let errors = lex("fn main() { 1 # 2 }").unwrap_err();
+    let [error] = errors.as_slice() else { panic!("{:?}", errors) };
+    assert_eq!((error.line, error.column, error.width), (1, 15, 1));
+    assert_eq!(error.message, "unknown character \"#\"");
+    assert_eq!(error.to_string(), "1:15: unknown character \"#\"");
+}
+
+#[test]
+fn test_lex_reports_one_error_per_line() {
+    let errors = lex("a $ ? b\nc\nd \"e\nf ~ g\n").unwrap_err();
+    let found: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+    assert_eq!(found, ["1:3: unknown character \"$\"", "3:3: unterminated string literal", "4:3: unknown character \"~\""]);
+}
+
+#[test]
+fn test_tokens_resume_on_the_next_line() {
+    let all = tokens("a $ b\nc \"d\ne");
+    let kinds: Vec<(TokenKind, usize)> = all.iter().map(|token| (token.kind, token.location.line)).collect();
+    assert_eq!(
+        kinds,
+        [
+            (TokenKind::Identifier, 1),
+            (TokenKind::Error, 1),
+            (TokenKind::Identifier, 2),
+            (TokenKind::Error, 2),
+            (TokenKind::Identifier, 3),
+        ]
+    );
+    assert_eq!(all[3].text, "\"d");
+}
+
+#[test]
+fn test_keywords_lex_as_identifiers() {
+    for &keyword in KEYWORDS {
+        assert_eq!(kinds(keyword), [(TokenKind::Identifier, keyword.to_string())]);
+        assert!(is_keyword(keyword));
+    }
+    assert!(!is_keyword("matches") && !is_keyword("Type") && !is_keyword("projection"));
+}
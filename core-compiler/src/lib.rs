@@ -1,11 +1,34 @@
 // Library interface for axis-compiler
-// Exposes the surface parser for testing
+// Exposes the surface parser for testing, its lexer for tools
+// (highlighting, formatting), and the pipeline up to validated Core
+// through session::Session, for tools that compile repeatedly
 
+pub mod binders;
+pub mod constructors;
+pub mod core_interface;
+pub mod core_validator;
+pub mod diagnostics;
+pub mod error_codes;
+pub mod imports;
+pub mod lexer;
+pub mod memo;
+pub mod prelude;
+pub mod runtime;
+pub mod session;
+pub mod string_table;
+pub mod surface_lower;
 pub mod surface_parser;
+pub mod surface_to_core;
+pub mod term_path;
+pub mod trace;
 pub mod runtime_value;
 pub mod registry_loader;
 pub mod validation_registry;
 
+// Shared with axis-rust-bridge (axis-ir), under the paths they had here
+pub use axis_ir::{internal_error, operators, symbol, symbol_index};
+pub use string_table::{get_string, init_string_table, intern_string};
+
 use runtime_value::Value;
 
 /// Public compile entry that can be called from generated Rust code.
@@ -16,3 +39,11 @@ pub fn compile_entry(_args: Value) -> Value {
     println!("[compile_entry] this will be replaced by self-hosted compiler");
     Value::Unit
 }
+
+/// Fuzzing entry point: run arbitrary bytes through the surface tokenizer
+/// and parser. Must return for every input, never panic or overflow the stack.
+pub fn fuzz_parse(data: &[u8]) {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = surface_parser::parse_module_with_file(source, "<fuzz>");
+    }
+}
@@ -1,31 +1,118 @@
 use clap::{Arg, Command};
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs;
+use std::ops::ControlFlow;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
-use std::time::Instant;
 
 // Generated Cap'n Proto schema
 mod axis_core_ir_0_1_capnp {
     include!(concat!(env!("OUT_DIR"), "/axis_core_ir_0_1_capnp.rs"));
 }
 
+mod ast_dump;
+mod binders;
+#[cfg(test)]
+mod binders_tests;
+mod bundle_metadata;
+#[cfg(test)]
+mod bundle_metadata_tests;
+mod constructors;
+#[cfg(test)]
+mod constructors_tests;
+mod core_interface;
+mod core_linker;
+#[cfg(test)]
+mod core_linker_tests;
 mod core_loader;
+mod core_stats;
+#[cfg(test)]
+mod core_stats_tests;
+mod core_text;
+#[cfg(test)]
+mod core_text_tests;
 mod core_validator;
-// emit_rust module removed for pure Core IR compiler (disabled Rust codegen)
+#[cfg(test)]
+mod core_validator_tests;
+mod diagnostics;
+#[cfg(test)]
+mod diagnostics_tests;
+mod doc_gen;
+#[cfg(test)]
+mod doc_gen_tests;
+mod error_codes;
+#[cfg(test)]
+mod error_codes_tests;
+mod imports;
+#[cfg(test)]
+mod imports_tests;
+mod interpret;
+#[cfg(test)]
+mod interpret_tests;
+mod lexer;
+#[cfg(test)]
+mod lexer_tests;
+mod manifest;
+#[cfg(test)]
+mod manifest_tests;
+mod memo;
+#[cfg(test)]
+mod memo_tests;
+#[cfg(test)]
+mod operators_tests;
+mod optimize;
+#[cfg(test)]
+mod optimize_tests;
+mod phase_dump;
+mod prelude;
+#[cfg(test)]
+mod prelude_tests;
+// emit_rust removed: Rust codegen is axis-rust-bridge's job, and generated
+// programs link its runtime rather than embedding a copy
 // foreign_impl removed - no runtime execution in compiler
 // REGIME COMPLIANCE: module_loader removed (violates rules 7-8)
+mod registry_check;
+#[cfg(test)]
+mod registry_check_tests;
 mod registry_loader;
+#[cfg(test)]
+mod registry_loader_tests;
 mod runtime;
+// compile() and NoHooks are for library callers
+#[allow(dead_code)]
+mod session;
+#[cfg(test)]
+mod session_tests;
 mod surface_lower;
+#[cfg(test)]
+mod surface_lower_tests;
 mod surface_parser;
+#[cfg(test)]
+mod surface_parser_tests;
 mod surface_to_core;
+mod string_table;
+mod term_path;
+#[cfg(test)]
+mod term_path_tests;
+mod test_runner;
+#[cfg(test)]
+mod test_runner_tests;
 mod trace;
+#[cfg(test)]
+mod trace_tests;
 mod validation_registry;
 
+// Shared with axis-rust-bridge (axis-ir), under the paths they had here
+use axis_ir::{depfile, exit_codes, internal_error, operators, symbol, symbol_index};
+
 // runtime::Value not used by the Core-IR-only compiler
-use registry_loader::Registry;
+use bundle_metadata::{BundleMetadata, FileDigest};
+use exit_codes::Failure;
+use registry_loader::{Capability, Registry};
 use trace::trace;
+pub use string_table::{get_string, init_string_table, intern_string};
+
+// Names the compiler in internal errors, and its outermost trace span
+const PROGRAM: &str = "axis-compiler";
 
 static TRACE_PARSE_ENABLED: AtomicBool = AtomicBool::new(false);
 
@@ -33,63 +120,13 @@ pub fn trace_parse_enabled() -> bool {
     TRACE_PARSE_ENABLED.load(Ordering::Relaxed)
 }
 
-static STRING_TABLE: Mutex<Option<StringTable>> = Mutex::new(None);
-
-struct StringTable {
-    strings: Vec<String>,
-    reverse: HashMap<String, i64>,
-}
-
-impl StringTable {
-    fn new() -> Self {
-        StringTable {
-            strings: vec![String::new()],
-            reverse: HashMap::new(),
-        }
-    }
-
-    fn intern(&mut self, s: String) -> i64 {
-        if let Some(&handle) = self.reverse.get(&s) {
-            return handle;
-        }
-        let handle = self.strings.len() as i64;
-        self.reverse.insert(s.clone(), handle);
-        self.strings.push(s);
-        handle
-    }
-
-    fn get(&self, handle: i64) -> &str {
-        if handle < 0 || handle >= self.strings.len() as i64 {
-            return "";
-        }
-        &self.strings[handle as usize]
-    }
-}
-
-fn init_string_table() {
-    let mut table = STRING_TABLE.lock().unwrap();
-    if table.is_none() {
-        *table = Some(StringTable::new());
-    }
-}
-
-pub fn intern_string(s: String) -> i64 {
-    let mut table = STRING_TABLE.lock().unwrap();
-    let table = table.as_mut().unwrap();
-    table.intern(s)
-}
-
-pub fn get_string(handle: i64) -> String {
-    let table = STRING_TABLE.lock().unwrap();
-    let table = table.as_ref().unwrap();
-    table.get(handle).to_string()
-}
-
 fn main() {
-    let phase_start = Instant::now();
-    eprintln!("[PHASE] phase3_axis_compiler_run=start");
-    
-    let exit_code = (|| {
+    // --timings and --profile-out, once the arguments are parsed
+    let mut profile_request: Option<(bool, Option<String>)> = None;
+
+    // A panic is a compiler bug: reported as an internal error naming the
+    // phase, not as a backtrace
+    let exit_code = internal_error::guard(|| {
         // TRACE: remove after flow is understood
         eprintln!("[TRACE] axis-compiler start");
         
@@ -98,6 +135,7 @@ fn main() {
         // Parse arguments using Clap
         let matches = Command::new("axis-compiler")
             .about("compiler Axis compiler - produces Core IR bundles from Axis source")
+            .after_help(exit_codes::COMPILER_HELP)
             .arg(
                 Arg::new("trace-parse")
                     .long("trace-parse")
@@ -111,22 +149,54 @@ fn main() {
                     .long("sources")
                     .help("Input Axis source files (concatenated in order)")
                     .num_args(1..)
-                    .value_name("FILES"),
+                    .value_name("FILES")
+                    .global(true),
             )
             .arg(
                 Arg::new("registries")
                     .short('r')
                     .long("registries")
-                    .help("Registry files to load (.axreg) in order")
+                    .help("Registry files to load (.axreg) in order (default: the built-in prelude of pure primitives)")
                     .num_args(1..)
-                    .value_name("REGS"),
+                    .value_name("REGS")
+                    .global(true),
             )
             .arg(
                 Arg::new("output")
                     .short('o')
                     .long("out")
-                    .help("Output file path for Core IR bundle (default: ./coreir/<source>.coreir)")
-                    .value_name("FILE"),
+                    .help("Output file path for Core IR bundle (default: ./coreir/<name>.coreir, see --out-dir)")
+                    .value_name("FILE")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("out-dir")
+                    .long("out-dir")
+                    .help("Directory for the Core IR bundle, named after the manifest's project name or the source file defining the entry point (default: ./coreir)")
+                    .value_name("DIR")
+                    .conflicts_with("output")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("force")
+                    .long("force")
+                    .help("Overwrite the output file if it already exists")
+                    .action(clap::ArgAction::SetTrue)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("emit-depfile")
+                    .long("emit-depfile")
+                    .help("Also write a Make-style dependency file listing the output and every source, registry and manifest read (for Make/Ninja)")
+                    .value_name("FILE")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("manifest")
+                    .long("manifest")
+                    .help("Project manifest (axis.toml); CLI arguments override its values")
+                    .value_name("FILE")
+                    .global(true),
             )
             .arg(
                 Arg::new("view-core-ir")
@@ -134,139 +204,773 @@ fn main() {
                     .help("Print textual Core IR graph from a .coreir file and exit")
                     .value_name("FILE")
                     .num_args(1)
-                    .conflicts_with_all(["sources", "registries", "output"]),
+                    .conflicts_with_all(["sources", "registries", "output", "out-dir", "manifest", "emit-depfile"]),
+            )
+            .arg(
+                Arg::new("trusted-input")
+                    .long("trusted-input")
+                    .help("Lift size/node/depth limits when loading a .coreir file (--view-core-ir, stats)")
+                    .action(clap::ArgAction::SetTrue)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("core-text")
+                    .long("core-text")
+                    .help("With --view-core-ir, print canonical textual Core IR (.coretext) instead of the graph")
+                    .action(clap::ArgAction::SetTrue)
+                    .requires("view-core-ir"),
+            )
+            .arg(
+                Arg::new("focus")
+                    .long("focus")
+                    .help("With --view-core-ir, print only the subterm at this term path (e.g. main/lam/app.arg)")
+                    .value_name("PATH")
+                    .num_args(1)
+                    .requires("view-core-ir"),
+            )
+            .arg(
+                Arg::new("separate")
+                    .long("separate")
+                    .help("Compile a separate unit: names not defined here or in the registries become imports resolved by `link`")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("no-prelude")
+                    .long("no-prelude")
+                    .help("Do not add the standard prelude's functions (list_length, str_repeat, ...) to the program")
+                    .action(clap::ArgAction::SetTrue)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("allow-duplicate-defs")
+                    .long("allow-duplicate-defs")
+                    .help("Keep the first definition of a function defined more than once, with a warning, instead of failing (legacy concatenated sources)")
+                    .action(clap::ArgAction::SetTrue)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("optimize")
+                    .short('O')
+                    .long("optimize")
+                    .help("Optimize the Core IR before writing the bundle (inlines small functions, folds deterministic calls on literals, shares repeated subterms)")
+                    .action(clap::ArgAction::SetTrue)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("allow")
+                    .long("allow")
+                    .value_name("CAPS")
+                    .help("Sandbox foreign calls: comma-separated capabilities they may use (io, fs, proc, or none); calls needing others fail validation")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("color")
+                    .long("color")
+                    .value_name("WHEN")
+                    .help("Color diagnostics: auto (terminal, unless NO_COLOR is set), always or never")
+                    .value_parser(["auto", "always", "never"])
+                    .default_value("auto")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("error-format")
+                    .long("error-format")
+                    .value_name("FORMAT")
+                    .help("Diagnostics as human-readable text or as JSON, one object per line")
+                    .value_parser(["human", "json"])
+                    .default_value("human")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("timings")
+                    .long("timings")
+                    .help("Print how long each compiler phase took, as a tree of nested phases")
+                    .action(clap::ArgAction::SetTrue)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("profile-out")
+                    .long("profile-out")
+                    .help("Write the phase timings to FILE as Chrome trace-event JSON (chrome://tracing, Perfetto)")
+                    .value_name("FILE")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .help("Report optimizer decisions (which functions are inlined and why not)")
+                    .action(clap::ArgAction::SetTrue)
+                    .global(true),
+            )
+            .arg(
+                Arg::new("dump-ast")
+                    .long("dump-ast")
+                    .help("Write the parsed surface AST to FILE (JSON if it ends in .json, text otherwise; - for stdout) and stop before lowering")
+                    .value_name("FILE")
+                    .num_args(1)
+                    .conflicts_with_all(["from-core-text", "view-core-ir"]),
+            )
+            .arg(
+                Arg::new("dump-phase")
+                    .long("dump-phase")
+                    .help("Write the IR after PHASE (tokens, ast, core-value, core-term, validated) to FILE (default <source>.<phase>.dump, - for stdout); repeatable")
+                    .value_name("PHASE[=FILE]")
+                    .action(clap::ArgAction::Append)
+                    .conflicts_with("view-core-ir"),
+            )
+            .arg(
+                Arg::new("from-core-text")
+                    .long("from-core-text")
+                    .help("Compile a textual Core IR file (.coretext) instead of Axis sources")
+                    .value_name("FILE")
+                    .num_args(1)
+                    .conflicts_with_all(["sources", "view-core-ir"]),
+            )
+            .subcommand(
+                Command::new("build")
+                    .about("Compile the project described by axis.toml (discovered upward from the current directory)"),
+            )
+            .subcommand(
+                Command::new("stats")
+                    .about("Report node counts, depth, and per-function sizes of a .coreir bundle")
+                    .arg(
+                        Arg::new("file")
+                            .help("Core IR bundle to analyse")
+                            .value_name("FILE")
+                            .required(true),
+                    ),
+            )
+            .subcommand(
+                Command::new("run")
+                    .about("Run a .coreir bundle with the reference interpreter (exit 125: uses something the interpreter lacks)")
+                    .arg(
+                        Arg::new("file")
+                            .help("Core IR bundle to run")
+                            .value_name("FILE")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("args")
+                            .help("Program arguments")
+                            .value_name("ARGS")
+                            .num_args(0..)
+                            .trailing_var_arg(true)
+                            .allow_hyphen_values(true),
+                    ),
+            )
+            .subcommand(
+                Command::new("check-registry")
+                    .about("Lint .axreg files: syntax, duplicate or conflicting entries, unknown profiles, shadowed built-ins")
+                    .arg(
+                        Arg::new("files")
+                            .help("Registry files, in load order")
+                            .value_name("FILES")
+                            .num_args(1..)
+                            .required(true),
+                    ),
             )
-            .group(
-                clap::ArgGroup::new("mode")
-                    .args(["sources", "view-core-ir"])
-                    .required(true),
+            .subcommand(
+                Command::new("link")
+                    .about("Combine separately compiled .coreir bundles into one program (requires --out)")
+                    .arg(
+                        Arg::new("bundles")
+                            .help("Core IR bundles to link, in order")
+                            .value_name("FILES")
+                            .num_args(1..)
+                            .required(true),
+                    ),
+            )
+            .subcommand(
+                Command::new("explain")
+                    .about("Describe a diagnostic code (e.g. AX1004) with an example; without a code, list them all")
+                    .arg(
+                        Arg::new("code")
+                            .help("Error code, e.g. AX1004")
+                            .value_name("CODE"),
+                    ),
+            )
+            .subcommand(
+                Command::new("doc")
+                    .about("Write a reference of the sources' functions, foreign functions and types to --out (a directory, default ./docs)")
+                    .arg(
+                        Arg::new("format")
+                            .long("format")
+                            .help("Page format: index.md or index.html")
+                            .value_name("FORMAT")
+                            .value_parser(["markdown", "html"])
+                            .default_value("markdown"),
+                    ),
+            )
+            .subcommand(
+                Command::new("test")
+                    .about("Run the @test (or test_*) functions of the given sources through axis-rust-bridge")
+                    .arg(
+                        Arg::new("bridge")
+                            .long("bridge")
+                            .help("axis-rust-bridge executable used to build the test harness")
+                            .value_name("PATH")
+                            .default_value("axis-rust-bridge"),
+                    ),
             )
             .get_matches();
 
-        // Check if trace-parse is enabled
-        if matches.get_flag("trace-parse") {
+        if matches.get_flag("timings") || matches.contains_id("profile-out") {
+            trace::start_profiling();
+            profile_request = Some((matches.get_flag("timings"), matches.get_one::<String>("profile-out").cloned()));
+        }
+        let _run = trace::span(PROGRAM);
+
+        // Early exit: explain subcommand
+        if let Some(explain_matches) = matches.subcommand_matches("explain") {
+            let Some(code) = explain_matches.get_one::<String>("code") else {
+                for code in error_codes::ErrorCode::ALL {
+                    println!("{}  {}", code, code.summary());
+                }
+                return exit_codes::SUCCESS;
+            };
+            return match error_codes::ErrorCode::parse(code) {
+                Some(code) => {
+                    println!("{} ({}): {}\n", code, code.name(), code.summary());
+                    println!("{}", code.explanation());
+                    exit_codes::SUCCESS
+                }
+                None => {
+                    eprintln!("Error: unknown error code '{}' (`axis-compiler explain` lists them)", code);
+                    exit_codes::USAGE
+                }
+            };
+        }
+
+        // Load the project manifest: explicit --manifest, otherwise discover
+        // axis.toml upward from CWD when `build` is used or no --sources given.
+        // POLICY: CLI arguments override manifest values.
+        let project = if let Some(path) = matches.get_one::<String>("manifest") {
+            match manifest::Manifest::load(std::path::Path::new(path)) {
+                Ok(m) => Some(m),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return e.code;
+                }
+            }
+        } else if matches.subcommand_matches("build").is_some()
+            || (matches.get_many::<String>("sources").is_none()
+                && matches.get_one::<String>("view-core-ir").is_none()
+                && matches.subcommand_matches("stats").is_none()
+                && matches.subcommand_matches("run").is_none()
+                && matches.subcommand_matches("check-registry").is_none()
+                && matches.subcommand_matches("explain").is_none())
+        {
+            let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+            match manifest::discover(&cwd) {
+                Some(path) => match manifest::Manifest::load(&path) {
+                    Ok(m) => Some(m),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        return e.code;
+                    }
+                },
+                None => None,
+            }
+        } else {
+            None
+        };
+        if let Some(m) = &project {
+            eprintln!("Using manifest {}", m.path.display());
+        }
+
+        // Check if trace-parse is enabled (CLI flag or manifest [build].flags)
+        if matches.get_flag("trace-parse")
+            || project.as_ref().is_some_and(|m| m.has_flag("trace-parse"))
+        {
             TRACE_PARSE_ENABLED.store(true, Ordering::Relaxed);
         }
 
+        // Standard prelude (CLI flag or manifest [build].flags)
+        let use_prelude = !(matches.get_flag("no-prelude")
+            || project.as_ref().is_some_and(|m| m.has_flag("no-prelude")));
+        let allow_duplicates = matches.get_flag("allow-duplicate-defs")
+            || project.as_ref().is_some_and(|m| m.has_flag("allow-duplicate-defs"));
+
+        let load_options = if matches.get_flag("trusted-input") {
+            core_loader::BundleLoadOptions::trusted()
+        } else {
+            core_loader::BundleLoadOptions::default()
+        };
+
+        // Early exit: stats subcommand
+        if let Some(stats_matches) = matches.subcommand_matches("stats") {
+            let coreir_path = stats_matches.get_one::<String>("file").unwrap();
+            let _span = trace::span_with("load", coreir_path);
+            return match core_loader::load_core_bundle(coreir_path, &load_options) {
+                Ok(program) => {
+                    let stats = core_stats::compute(&program);
+                    print!("{}", core_stats::format_report(coreir_path, &stats));
+                    exit_codes::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit_codes::IO
+                }
+            };
+        }
+
+        // Early exit: run subcommand
+        if let Some(run_matches) = matches.subcommand_matches("run") {
+            let coreir_path = run_matches.get_one::<String>("file").unwrap().clone();
+            let args: Vec<String> = run_matches.get_many::<String>("args").map(|a| a.cloned().collect()).unwrap_or_default();
+            // The interpreter recurses on the Rust stack: give it room for
+            // the deep recursion compiled programs get from their loops
+            let runner = std::thread::Builder::new().stack_size(1 << 30).spawn(move || {
+                internal_error::guard(|| match core_loader::load_core_bundle(&coreir_path, &load_options) {
+                    Ok(program) => {
+                        let _span = trace::span("interpret");
+                        interpret::run_program(&program.root_term, &args)
+                    }
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit_codes::IO
+                    }
+                })
+            });
+            let outcome = match runner {
+                Ok(thread) => thread.join().unwrap_or_else(|_| {
+                    Err(internal_error::CompilerError::new("interpret", "the interpreter thread died"))
+                }),
+                Err(e) => Err(internal_error::CompilerError::new(
+                    "interpret",
+                    format!("cannot start the interpreter thread: {}", e),
+                )),
+            };
+            return match outcome {
+                Ok(code) => code,
+                Err(error) => {
+                    eprint!("{}", error.render(PROGRAM));
+                    interpret::RUNTIME_ERROR_EXIT_CODE
+                }
+            };
+        }
+
+        // Early exit: check-registry subcommand
+        if let Some(check_matches) = matches.subcommand_matches("check-registry") {
+            let mut files = Vec::new();
+            for path in check_matches.get_many::<String>("files").unwrap() {
+                match fs::read_to_string(path) {
+                    Ok(content) => files.push((path.clone(), content)),
+                    Err(e) => {
+                        eprintln!("Failed to read {}: {}", path, e);
+                        return exit_codes::IO;
+                    }
+                }
+            }
+            let emitter = diagnostic_emitter(&matches);
+            let report = registry_check::check_registries(&files);
+            for diagnostic in &report.diagnostics {
+                eprint!("{}", emitter.render(diagnostic, &report.sources));
+            }
+            println!("{}", report.summary());
+            return if report.count(diagnostics::Severity::Error) > 0 {
+                exit_codes::VALIDATION
+            } else {
+                exit_codes::SUCCESS
+            };
+        }
+
+        // --allow: None when absent (foreign calls are not sandboxed)
+        let allowed = match matches.get_one::<String>("allow").map(|list| Capability::parse_list(list)).transpose() {
+            Ok(allowed) => allowed,
+            Err(e) => {
+                eprintln!("Error: --allow: {}", e);
+                return exit_codes::USAGE;
+            }
+        };
+
+        // Early exit: link subcommand
+        if let Some(link_matches) = matches.subcommand_matches("link") {
+            let bundle_paths: Vec<String> = link_matches.get_many::<String>("bundles").unwrap().cloned().collect();
+            let registry_paths: Vec<String> = match matches.get_many::<String>("registries") {
+                Some(regs) => regs.cloned().collect(),
+                None => project.as_ref().map(|m| m.registries.clone()).unwrap_or_default(),
+            };
+            let output_path = match matches.get_one::<String>("output") {
+                Some(path) => Some(path.clone()),
+                None => project.as_ref().and_then(|m| m.out.clone()),
+            };
+            let Some(output_path) = output_path else {
+                eprintln!("Error: link requires --out");
+                return exit_codes::USAGE;
+            };
+            if let Err(e) = check_overwrite(&output_path, matches.get_flag("force")) {
+                eprintln!("Error: {}", e);
+                return exit_codes::IO;
+            }
+            let mut metadata = BundleMetadata::current();
+            metadata.flags = output_flags(&matches, project.as_ref());
+            return match link_bundles(&bundle_paths, &registry_paths, &output_path, &load_options, allowed.as_deref(), use_prelude, metadata) {
+                Ok(()) => {
+                    eprintln!("Linked {} bundles -> {}", bundle_paths.len(), output_path);
+                    let inputs = [bundle_paths, registry_paths].concat();
+                    if let Err(e) = emit_depfile(&matches, &output_path, inputs, project.as_ref()) {
+                        eprintln!("Error: {}", e);
+                        return exit_codes::IO;
+                    }
+                    print_output_path(&output_path);
+                    exit_codes::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    e.code
+                }
+            };
+        }
+
         // Early exit: --view-core-ir mode
         if let Some(coreir_path) = matches.get_one::<String>("view-core-ir") {
-            return match view_core_ir(coreir_path) {
-                Ok(()) => 0,
+            let focus = matches.get_one::<String>("focus").map(String::as_str);
+            return match view_core_ir(coreir_path, &load_options, matches.get_flag("core-text"), focus) {
+                Ok(()) => exit_codes::SUCCESS,
                 Err(e) => {
                     eprintln!("Error: {}", e);
-                    1
+                    e.code
                 }
             };
         }
 
+        // --from-core-text: the input is already Core IR, skip parse + lower
+        let core_text_path = matches.get_one::<String>("from-core-text");
+
         // REGIME COMPLIANCE: Concatenate explicit file list
-        let files: Vec<&String> = matches
-            .get_many::<String>("sources")
-            .unwrap()
-            .collect();
+        let files: Vec<String> = match (core_text_path, matches.get_many::<String>("sources")) {
+            (Some(path), _) => vec![path.clone()],
+            (None, Some(srcs)) => srcs.cloned().collect(),
+            (None, None) => project.as_ref().map(|m| m.sources.clone()).unwrap_or_default(),
+        };
+        if files.is_empty() {
+            eprintln!("Error: no sources given (use --sources, --from-core-text, --view-core-ir, or an axis.toml manifest)");
+            return exit_codes::USAGE;
+        }
 
-        // Registries are required for compilation mode
-        let registry_paths: Vec<&String> = match matches.get_many::<String>("registries") {
-            Some(regs) => regs.collect(),
-            None => {
-                eprintln!("Error: --registries is required when compiling sources");
-                return 1;
+        // --dump-phase requests are checked before any work is done
+        let source_stem = std::path::Path::new(files.first().unwrap())
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("output")
+            .to_string();
+        let mut dump_requests = Vec::new();
+        for arg in matches.get_many::<String>("dump-phase").into_iter().flatten() {
+            match phase_dump::parse_request(arg, &source_stem) {
+                Ok(request) if core_text_path.is_some() && request.phase.is_surface() => {
+                    eprintln!("Error: --dump-phase {} is not available with --from-core-text", request.phase.name());
+                    return exit_codes::USAGE;
+                }
+                Ok(request) => dump_requests.push(request),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return exit_codes::USAGE;
+                }
             }
+        }
+
+        // Without registries, sources compile against the built-in prelude
+        let registry_paths: Vec<String> = match matches.get_many::<String>("registries") {
+            Some(regs) => regs.cloned().collect(),
+            None => project.as_ref().map(|m| m.registries.clone()).unwrap_or_default(),
         };
 
         // REGIME COMPLIANCE: Simple concatenation in the order given
         let mut full_source = String::new();
+        let mut file_sources = Vec::new();
         for file_path in &files {
-            let content = fs::read_to_string(file_path)
-                .unwrap_or_else(|e| {
+            let _span = trace::span_with("read", file_path);
+            let content = match fs::read_to_string(file_path) {
+                Ok(content) => content,
+                Err(e) => {
                     eprintln!("Failed to read {}: {}", file_path, e);
-                    std::process::exit(1);
-                });
+                    return exit_codes::IO;
+                }
+            };
             full_source.push_str(&content);
             full_source.push('\n');
+            file_sources.push(content);
         }
 
         let input_path = files.first().unwrap(); // For diagnostics only
+        let sources: Vec<(String, String)> = files.iter().cloned().zip(file_sources.iter().cloned()).collect();
+        let source_map = diagnostics::SourceMap::concatenated(&sources);
+        let emitter = diagnostic_emitter(&matches);
 
         trace("axis-compiler: loading registries");
-        let mut reg = Registry::new();
-        let reg_strs: Vec<&str> = registry_paths.iter().map(|s| s.as_str()).collect();
-        if let Err(e) = reg.load_from_files(&reg_strs) {
-            eprintln!("Failed to load registries: {}", e);
-            return 1;
-        }
-
-        trace("axis-compiler: parsing and lowering");
-
-        // Parse
-        let module = match surface_parser::parse_module_with_file(&full_source, input_path) {
-            Ok(m) => m,
+        let mut session = session::Session::new();
+        let registry_span = trace::span("load registries");
+        let reg = match session.registry(&registry_paths) {
+            Ok(reg) => reg,
             Err(e) => {
-                eprintln!("{}", e);
-                return 1;
+                eprintln!("Failed to load registries: {}", e);
+                return registry_exit_code(&e);
             }
         };
+        drop(registry_span);
+        let options = session::CompileOptions {
+            registries: registry_paths.clone(),
+            prelude: use_prelude,
+            allow_duplicates,
+            allowed: allowed.clone(),
+            separate: matches.get_flag("separate"),
+        };
 
-        // Lower to Core AST (as Value)
-        let core_value = surface_lower::lower_module(module);
-
-        // Convert to CoreTerm
-        let core_term = surface_to_core::value_to_core(&core_value);
+        // Early exit: test subcommand
+        if let Some(test_matches) = matches.subcommand_matches("test") {
+            if core_text_path.is_some() {
+                eprintln!("Error: test requires Axis sources, not --from-core-text");
+                return exit_codes::USAGE;
+            }
+            let bridge = test_matches.get_one::<String>("bridge").unwrap();
+            return match run_test_command(&mut session, &sources, &options, bridge) {
+                Ok(true) => exit_codes::SUCCESS,
+                Ok(false) => exit_codes::FAILURE,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    e.code
+                }
+            };
+        }
 
-        // Tripwire: ensure axis_io_print is in registry before validation
-        assert!(
-            reg.entries.contains_key("axis_io_print"),
-            "registry missing axis_io_print before validation"
-        );
+        // Early exit: doc subcommand
+        if let Some(doc_matches) = matches.subcommand_matches("doc") {
+            if core_text_path.is_some() {
+                eprintln!("Error: doc requires Axis sources, not --from-core-text");
+                return exit_codes::USAGE;
+            }
+            let format = doc_gen::DocFormat::parse(doc_matches.get_one::<String>("format").unwrap()).unwrap();
+            let out_dir = matches.get_one::<String>("output").map_or("docs", String::as_str);
+            return match run_doc_command(&full_source, input_path, &source_map, &reg, out_dir, format, allow_duplicates) {
+                Ok(path) => {
+                    eprintln!("Wrote documentation -> {}", path);
+                    exit_codes::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    e.code
+                }
+            };
+        }
 
-        // Validate Core IR - fail hard on validation error (do not emit bundle)
-        // Validation uses the CLI-loaded Registry as the sole authority.
-        if let Err(validation_error) = core_validator::validate_core(&core_term, &reg) {
-            eprintln!("VALIDATION ERROR: {}", validation_error.message);
-            return 1;
+        let mut hooks = CliHooks { dumps: &dump_requests, dump_ast: matches.get_one::<String>("dump-ast"), stop: None };
+        let compiled = if let Some(text_path) = core_text_path {
+            trace("axis-compiler: parsing Core text");
+            let core_term = match core_text::parse_core_text(&full_source) {
+                Ok(term) => term,
+                Err(e) => {
+                    eprintln!("{}:{}", text_path, e);
+                    return exit_codes::PARSE;
+                }
+            };
+            session.compile_core(core_term, &options, &mut hooks)
+        } else {
+            trace("axis-compiler: parsing and lowering");
+            session.compile_with(&sources, &options, &mut hooks)
+        };
+        // Every finding is reported, sorted by location; any error fails
+        // the build (no bundle is emitted), warnings do not
+        let compiled = match compiled {
+            Ok(compiled) => compiled,
+            Err(error) => {
+                for diagnostic in error.diagnostics() {
+                    eprint!("{}", emitter.render(&diagnostic, &source_map));
+                }
+                return match error {
+                    session::CompileError::Registry(e) => registry_exit_code(&e),
+                    session::CompileError::Parse(_) => exit_codes::PARSE,
+                    session::CompileError::Validation(_) => exit_codes::VALIDATION,
+                    session::CompileError::Stopped(_) => hooks.stop.unwrap_or(exit_codes::SUCCESS),
+                };
+            }
+        };
+        for finding in &compiled.warnings.findings {
+            eprint!("{}", emitter.render(&finding.to_diagnostic(), &source_map));
+        }
+        if !compiled.imports.is_empty() {
+            eprintln!("Separate unit imports: {}", compiled.imports.join(", "));
         }
+        let session::Compiled { core: core_term, entry: entry_function, constructors, inline: inline_hints, private: private_functions, .. } =
+            compiled;
 
-        // Create binary core bundle
-        let binary_bundle = core_loader::create_core_bundle(&core_term, "main");
+        // Optimizer passes run on validated Core only (CLI flag or manifest [build].flags)
+        let core_term = if matches.get_flag("optimize")
+            || project.as_ref().is_some_and(|m| m.has_flag("optimize"))
+        {
+            trace("axis-compiler: optimizing");
+            let _span = trace::span("optimize");
+            let options = optimize::OptimizeOptions { inline_hints, ..Default::default() };
+            let (optimized, stats) = optimize::optimize(&core_term, &reg, &options);
+            if matches.get_flag("verbose") {
+                for decision in &stats.inline_decisions {
+                    eprintln!("Optimizer: {}", decision);
+                }
+            }
+            eprintln!(
+                "Optimizer: inlined {} call(s), folded {} call(s), shared {} subterm(s)",
+                stats.inlined_calls, stats.folded_calls, stats.shared_subterms
+            );
+            optimized
+        } else {
+            core_term
+        };
 
-        // Determine output path
+        // Determine output path: --out, then --out-dir, then the manifest's
+        // out, then ./coreir/ with a derived name
+        let derived_name = || {
+            let stem = bundle_stem(project.as_ref(), &files, &file_sources, entry_function.as_deref());
+            format!("{}.coreir", stem)
+        };
         let output_path = if let Some(explicit_path) = matches.get_one::<String>("output") {
             // Use explicit path as-is
             explicit_path.clone()
+        } else if let Some(out_dir) = matches.get_one::<String>("out-dir") {
+            std::path::Path::new(out_dir).join(derived_name()).to_string_lossy().into_owned()
+        } else if let Some(manifest_out) = project.as_ref().and_then(|m| m.out.clone()) {
+            manifest_out
         } else {
-            // Default: derive from first source file, output to ./coreir/
-            let first_source = files.first().unwrap();
-            let source_stem = std::path::Path::new(first_source)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("output");
-            format!("./coreir/{}.coreir", source_stem)
+            format!("./coreir/{}", derived_name())
+        };
+        if let Err(e) = check_overwrite(&output_path, matches.get_flag("force")) {
+            eprintln!("Error: {}", e);
+            return exit_codes::IO;
+        }
+
+        // Create binary core bundle with its export/import manifest
+        let interface_span = trace::span("interface");
+        let mut interface = core_interface::compute_interface(&core_term, &reg);
+        interface.mark_private(&private_functions);
+        drop(interface_span);
+        if let Err(e) = phase_dump::dump(&dump_requests, phase_dump::Phase::Validated, || {
+            phase_dump::format_validated(&core_term, &interface)
+        }) {
+            eprintln!("Error: {}", e);
+            return exit_codes::IO;
+        }
+        let mut metadata = BundleMetadata::current();
+        metadata.sources = files
+            .iter()
+            .zip(&file_sources)
+            .map(|(path, contents)| FileDigest::of(path, contents.as_bytes()))
+            .collect();
+        metadata.registries = match registry_paths.iter().map(|path| FileDigest::of_file(path)).collect() {
+            Ok(registries) => registries,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return exit_codes::IO;
+            }
         };
+        metadata.flags = output_flags(&matches, project.as_ref());
+        let serialize_span = trace::span("serialize");
+        let binary_bundle =
+            core_loader::create_core_bundle_with(&core_term, "main", &[], &interface, &constructors, &metadata);
+        drop(serialize_span);
 
         // Ensure output directory exists
         if let Some(parent) = std::path::Path::new(&output_path).parent() {
             if let Err(e) = fs::create_dir_all(parent) {
                 eprintln!("Failed to create output directory: {}", e);
-                return 1;
+                return exit_codes::IO;
             }
         }
 
         // Write output
+        let _span = trace::span_with("write", &output_path);
         if let Err(e) = fs::write(&output_path, &binary_bundle) {
             eprintln!("Failed to write output: {}", e);
-            return 1;
+            return exit_codes::IO;
         }
         eprintln!("Emitted Core bundle -> {}", output_path);
-        println!("Axis compiler ready");
-        0
-    })();
-    
-    eprintln!(
-        "[PHASE] phase3_axis_compiler_run=end ms={}",
-        phase_start.elapsed().as_millis()
-    );
+        if let Err(e) = emit_depfile(&matches, &output_path, [files, registry_paths].concat(), project.as_ref()) {
+            eprintln!("Error: {}", e);
+            return exit_codes::IO;
+        }
+        print_output_path(&output_path);
+        exit_codes::SUCCESS
+    })
+    .unwrap_or_else(|error| {
+        eprint!("{}", error.render(PROGRAM));
+        exit_codes::INTERNAL
+    });
+
+    if let Some((timings, profile_out)) = profile_request {
+        let spans = trace::finish();
+        if timings {
+            eprint!("Timings:\n{}", trace::format_timings(&spans));
+        }
+        if let Some(path) = profile_out {
+            match fs::write(&path, trace::chrome_trace(&spans)) {
+                Ok(()) => eprintln!("Wrote profile -> {}", path),
+                Err(e) => eprintln!("Failed to write {}: {}", path, e),
+            }
+        }
+    }
     std::process::exit(exit_code);
 }
+// Default bundle name: the manifest's project name, otherwise the source
+// file that defines the entry point, otherwise the first source
+fn bundle_stem(
+    project: Option<&manifest::Manifest>,
+    files: &[String],
+    sources: &[String],
+    entry_function: Option<&str>,
+) -> String {
+    if let Some(name) = project.and_then(|m| m.name.clone()) {
+        return name;
+    }
+    let entry_file = entry_function
+        .and_then(|entry| files.iter().zip(sources).find(|(_, source)| surface_parser::defines_function(source, entry)))
+        .map(|(file, _)| file);
+    let file = entry_file.or(files.first());
+    file.and_then(|f| std::path::Path::new(f).file_stem())
+        .and_then(|s| s.to_str())
+        .unwrap_or("output")
+        .to_string()
+}
+
+// POLICY: an existing output is never replaced silently
+// --emit-depfile: `output` depends on `inputs` and the manifest, if one
+// was read
+fn emit_depfile(
+    matches: &clap::ArgMatches,
+    output: &str,
+    mut inputs: Vec<String>,
+    project: Option<&manifest::Manifest>,
+) -> Result<(), String> {
+    let Some(path) = matches.get_one::<String>("emit-depfile") else {
+        return Ok(());
+    };
+    if let Some(m) = project {
+        inputs.push(m.path.display().to_string());
+    }
+    depfile::write(path, output, &inputs)?;
+    eprintln!("Wrote dependency file -> {}", path);
+    Ok(())
+}
+
+// A registry that could not be read is an I/O error, one that could not be
+// parsed a parse error
+fn registry_exit_code(error: &registry_loader::RegistryError) -> i32 {
+    if error.line == 0 { exit_codes::IO } else { exit_codes::PARSE }
+}
+
+fn check_overwrite(output_path: &str, force: bool) -> Result<(), String> {
+    if !force && std::path::Path::new(output_path).exists() {
+        return Err(format!("{} already exists (use --force to overwrite)", output_path));
+    }
+    Ok(())
+}
+
+// The written file as a single absolute path on stdout, for build scripts
+fn print_output_path(output_path: &str) {
+    match std::path::absolute(output_path) {
+        Ok(path) => println!("{}", path.display()),
+        Err(_) => println!("{}", output_path),
+    }
+}
+
 #[allow(dead_code)]
 // Superseded by --view-core-ir textual graph printer
 // Pretty-print Core IR for debugging/proof
@@ -328,6 +1032,15 @@ fn format_core(term: &runtime::CoreTerm, indent: usize) -> String {
                 ind
             )
         }
+        CoreTerm::CtorField(ctor, idx, _) => {
+            format!(
+                "{}CtorField({},\n{}\n{})",
+                ind,
+                idx,
+                format_core(ctor, indent + 1),
+                ind
+            )
+        }
         CoreTerm::If(c, t, e, _) => {
             format!(
                 "{}If(\n{},\n{},\n{}\n{})",
@@ -349,15 +1062,411 @@ fn format_core(term: &runtime::CoreTerm, indent: usize) -> String {
     }
 }
 
-// --view-core-ir: Load and print Core IR graph
-fn view_core_ir(path: &str) -> Result<(), String> {
-    let program = core_loader::load_core_bundle(path)?;
-    print_core_bundle(&program);
+// link: merge bundles, then validate so cross-bundle Var references resolve
+// against the combined top-level scope (and the registries) before writing
+fn link_bundles(
+    bundle_paths: &[String],
+    registry_paths: &[String],
+    output_path: &str,
+    options: &core_loader::BundleLoadOptions,
+    allowed: Option<&[Capability]>,
+    prelude: bool,
+    mut metadata: BundleMetadata,
+) -> Result<(), Failure> {
+    let reg = Registry::load_or_prelude(registry_paths)
+        .map_err(|e| Failure::new(registry_exit_code(&e), format!("Failed to load registries: {}", e)))?;
+    // A linked bundle's sources are the bundles it was linked from
+    metadata.sources = bundle_paths
+        .iter()
+        .map(|path| FileDigest::of_file(path))
+        .collect::<Result<_, _>>()
+        .map_err(Failure::io)?;
+    metadata.registries = registry_paths
+        .iter()
+        .map(|path| FileDigest::of_file(path))
+        .collect::<Result<_, _>>()
+        .map_err(Failure::io)?;
+
+    let mut inputs = Vec::new();
+    for path in bundle_paths {
+        let _span = trace::span_with("load", path);
+        let mut program = core_loader::load_core_bundle(path, options)
+            .map_err(|e| Failure::io(format!("{}: {}", path, e)))?;
+        // Bundles written without a manifest get one derived from their term
+        if program.interface.is_empty() {
+            program.interface = core_interface::compute_interface(&program.root_term, &reg);
+        }
+        inputs.push((path.clone(), program));
+    }
+    // Prelude functions the bundles import but none defines
+    if prelude && let Some(unit) = prelude_unit(&inputs, &reg) {
+        inputs.push((prelude::PRELUDE_FILE.to_string(), unit));
+    }
+
+    // Manifest consistency first: reports every unresolved import at once
+    let units: Vec<(&str, &core_interface::BundleInterface)> = inputs
+        .iter()
+        .map(|(path, program)| (path.as_str(), &program.interface))
+        .collect();
+    core_validator::validate_link(&units)
+        .map_err(|e| Failure::validation(format!("LINK ERROR: {}", e.message)))?;
+
+    let link_span = trace::span("link");
+    let mut linked = core_linker::link_programs(&inputs).map_err(Failure::validation)?;
+    drop(link_span);
+    let validate_span = trace::span("validate");
+    let report = core_validator::validate_core(&linked.root_term, &reg);
+    if report.has_errors() {
+        return Err(Failure::validation(format!("VALIDATION ERROR: {}", report)));
+    }
+    // Bundles of older compilers name constructors as written
+    linked.root_term = constructors::qualify(&linked.root_term, &linked.constructors)
+        .map_err(|e| Failure::validation(format!("VALIDATION ERROR: {}", e)))?;
+    core_validator::check_constructors(&linked.root_term, &linked.constructors)
+        .map_err(|e| Failure::validation(format!("VALIDATION ERROR: {}", e)))?;
+    if let Some(allowed) = allowed {
+        core_validator::check_capabilities(&linked.root_term, &reg, allowed)
+            .map_err(|e| Failure::validation(format!("VALIDATION ERROR: {}", e)))?;
+    }
+    drop(validate_span);
+
+    if !linked.removed.is_empty() {
+        eprintln!("Linker: removed {} unused private definition(s): {}", linked.removed.len(), linked.removed.join(", "));
+    }
+    let mut interface = core_interface::compute_interface(&linked.root_term, &reg);
+    interface.mark_private(&linked.private);
+    let serialize_span = trace::span("serialize");
+    let binary_bundle = core_loader::create_core_bundle_with(
+        &linked.root_term,
+        "main",
+        &linked.strings,
+        &interface,
+        &linked.constructors,
+        &metadata,
+    );
+    drop(serialize_span);
+    if let Some(parent) = std::path::Path::new(output_path).parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| Failure::io(format!("Failed to create output directory: {}", e)))?;
+    }
+    fs::write(output_path, &binary_bundle)
+        .map_err(|e| Failure::io(format!("Failed to write output: {}", e)))
+}
+
+// The prelude functions imported by `inputs` and exported by none of them,
+// as one more unit to link
+fn prelude_unit(inputs: &[(String, core_loader::CoreProgram)], reg: &Registry) -> Option<core_loader::CoreProgram> {
+    let exported: HashSet<String> = inputs
+        .iter()
+        .flat_map(|(_, program)| program.interface.exports.iter().map(|export| export.name.clone()))
+        .collect();
+    let wanted: Vec<String> = inputs
+        .iter()
+        .flat_map(|(_, program)| program.interface.external_imports())
+        .filter(|import| !exported.contains(&import.name) && prelude::is_prelude_function(&import.name))
+        .map(|import| import.name.clone())
+        .collect();
+    let root_term = prelude::core_unit(&wanted, &exported)?;
+    let interface = core_interface::compute_interface(&root_term, reg);
+    Some(core_loader::CoreProgram {
+        strings: Vec::new(),
+        root_term,
+        entrypoint_id: 0,
+        interface,
+        constructors: constructors::ConstructorTable::default(),
+        metadata: BundleMetadata::default(),
+    })
+}
+
+// The flags that change what compile or link writes, for the bundle's
+// metadata: set on the command line or in the manifest's [build].flags
+fn output_flags(matches: &clap::ArgMatches, project: Option<&manifest::Manifest>) -> Vec<String> {
+    let set = |flag: &str| matches.get_flag(flag) || project.is_some_and(|m| m.has_flag(flag));
+    let mut flags: Vec<String> = ["no-prelude", "separate", "allow-duplicate-defs", "optimize"]
+        .into_iter()
+        .filter(|flag| set(flag))
+        .map(|flag| format!("--{}", flag))
+        .collect();
+    if matches.get_one::<String>("from-core-text").is_some() {
+        flags.push("--from-core-text".to_string());
+    }
+    if let Some(allowed) = matches.get_one::<String>("allow") {
+        flags.push(format!("--allow={}", allowed));
+    }
+    flags
+}
+
+// --error-format, and whether diagnostics get ANSI colors (--color,
+// NO_COLOR, terminal); JSON is never colored
+fn diagnostic_emitter(matches: &clap::ArgMatches) -> diagnostics::Emitter {
+    let format = diagnostics::ErrorFormat::parse(matches.get_one::<String>("error-format").unwrap())
+        .unwrap_or(diagnostics::ErrorFormat::Human);
+    let color = format == diagnostics::ErrorFormat::Human
+        && diagnostics::ColorChoice::parse(matches.get_one::<String>("color").unwrap())
+            .is_ok_and(diagnostics::ColorChoice::use_color);
+    diagnostics::Emitter { format, color }
+}
+
+// The CLI's look into a compile: --dump-phase and --dump-ast
+struct CliHooks<'a> {
+    dumps: &'a [phase_dump::DumpRequest],
+    // --dump-ast: where the parsed module goes; the compile stops there
+    dump_ast: Option<&'a String>,
+    // Exit code, once a hook stopped the compile
+    stop: Option<i32>,
+}
+
+impl CliHooks<'_> {
+    fn dump(&mut self, phase: phase_dump::Phase, render: impl FnOnce() -> String) -> ControlFlow<()> {
+        match phase_dump::dump(self.dumps, phase, render) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                self.stop = Some(exit_codes::IO);
+                ControlFlow::Break(())
+            }
+        }
+    }
+}
+
+impl session::CompileHooks for CliHooks<'_> {
+    fn source(&mut self, source: &str) -> ControlFlow<()> {
+        self.dump(phase_dump::Phase::Tokens, || surface_parser::dump_tokens(source))
+    }
+
+    fn parsed(&mut self, module: &surface_parser::Module) -> ControlFlow<()> {
+        self.dump(phase_dump::Phase::Ast, || ast_dump::dump_text(module))?;
+        // --dump-ast: snapshot the parsed module and stop before lowering
+        let Some(dump_path) = self.dump_ast else {
+            return ControlFlow::Continue(());
+        };
+        let dump = if dump_path.ends_with(".json") { ast_dump::dump_json(module) } else { ast_dump::dump_text(module) };
+        if dump_path == "-" {
+            print!("{}", dump);
+        } else if let Err(e) = fs::write(dump_path, dump) {
+            eprintln!("Failed to write {}: {}", dump_path, e);
+            self.stop = Some(exit_codes::IO);
+        } else {
+            eprintln!("Wrote surface AST -> {}", dump_path);
+        }
+        ControlFlow::Break(())
+    }
+
+    fn lowered(&mut self, value: runtime::Value) -> ControlFlow<(), runtime::Value> {
+        self.dump(phase_dump::Phase::CoreValue, || phase_dump::format_value(&value))?;
+        ControlFlow::Continue(value)
+    }
+
+    fn core(&mut self, core: &runtime::CoreTerm) -> ControlFlow<()> {
+        self.dump(phase_dump::Phase::CoreTerm, || core_text::print_core_text(core))
+    }
+}
+
+// `test`'s look into a compile: it finds the tests and swaps the
+// program's entry point for a harness that runs them
+#[derive(Default)]
+struct TestHooks {
+    tests: Vec<String>,
+    // Why discovery stopped the compile; None when there are no tests
+    failure: Option<Failure>,
+}
+
+impl session::CompileHooks for TestHooks {
+    fn resolved(&mut self, module: &mut surface_parser::Module) -> ControlFlow<()> {
+        match test_runner::discover_tests(module) {
+            Ok(tests) if tests.is_empty() => ControlFlow::Break(()),
+            Ok(tests) => {
+                self.tests = tests;
+                test_runner::strip_entry(module);
+                ControlFlow::Continue(())
+            }
+            Err(e) => {
+                self.failure = Some(Failure::validation(e));
+                ControlFlow::Break(())
+            }
+        }
+    }
+
+    fn lowered(&mut self, value: runtime::Value) -> ControlFlow<(), runtime::Value> {
+        ControlFlow::Continue(test_runner::with_harness(value, &self.tests))
+    }
+}
+
+// `test`: compile the sources with a generated test harness as entry,
+// build them with axis-rust-bridge and run every test.
+// Returns Ok(false) when at least one test failed.
+fn run_test_command(
+    session: &mut session::Session,
+    sources: &[(String, String)],
+    options: &session::CompileOptions,
+    bridge: &str,
+) -> Result<bool, Failure> {
+    let allowed = options.allowed.as_deref();
+    let mut hooks = TestHooks::default();
+    let compiled = match session.compile_with(sources, options, &mut hooks) {
+        Ok(compiled) => compiled,
+        Err(session::CompileError::Stopped(report)) => {
+            for warning in &report.findings {
+                eprintln!("warning: {}", warning);
+            }
+            return match hooks.failure {
+                Some(failure) => Err(failure),
+                None => {
+                    println!("no tests found in {}", sources.first().map_or("<input>", |(file, _)| file.as_str()));
+                    Ok(true)
+                }
+            };
+        }
+        Err(session::CompileError::Registry(e)) => {
+            return Err(Failure::new(registry_exit_code(&e), format!("Failed to load registries: {}", e)));
+        }
+        Err(error @ session::CompileError::Parse(_)) => return Err(Failure::parse(error.to_string())),
+        Err(error) => return Err(Failure::validation(error.to_string())),
+    };
+    for warning in &compiled.warnings.findings {
+        eprintln!("warning: {}", warning);
+    }
+    let tests = hooks.tests;
+    let reg = session.registry(&options.registries).map_err(|e| Failure::io(e.to_string()))?;
+    let (core_term, constructors) = (compiled.core, compiled.constructors);
+
+    let work_dir = std::env::temp_dir().join(format!("axis-test-{}", std::process::id()));
+    fs::create_dir_all(&work_dir)
+        .map_err(|e| Failure::io(format!("Failed to create {}: {}", work_dir.display(), e)))?;
+    let bundle_path = work_dir.join("tests.coreir");
+    let binary_path = work_dir.join("tests");
+
+    let interface = core_interface::compute_interface(&core_term, &reg);
+    // A throwaway bundle: the compiler, without inputs
+    let bundle =
+        core_loader::create_core_bundle_with(&core_term, "main", &[], &interface, &constructors, &BundleMetadata::current());
+    fs::write(&bundle_path, &bundle)
+        .map_err(|e| Failure::io(format!("Failed to write {}: {}", bundle_path.display(), e)))?;
+
+    eprintln!("Building test harness with {}", bridge);
+    let mut command = std::process::Command::new(bridge);
+    command.arg("build").arg(&bundle_path).arg("--out").arg(&binary_path);
+    if let Some(allowed) = allowed {
+        let names: Vec<&str> = allowed.iter().map(|capability| capability.name()).collect();
+        command.arg("--allow").arg(if names.is_empty() { "none".to_string() } else { names.join(",") });
+    }
+    let build = command
+        .output()
+        .map_err(|e| Failure::io(format!("Failed to run {}: {}", bridge, e)))?;
+    if !build.status.success() {
+        let _ = fs::remove_dir_all(&work_dir);
+        return Err(Failure::new(exit_codes::FAILURE, format!(
+            "{} build failed:\n{}",
+            bridge,
+            String::from_utf8_lossy(&build.stderr).trim_end()
+        )));
+    }
+
+    let summary = test_runner::run_tests(&binary_path, &tests, &mut std::io::stdout());
+    let _ = fs::remove_dir_all(&work_dir);
+    Ok(summary.map_err(|e| Failure::io(e.to_string()))?.failed.is_empty())
+}
+
+// `doc`: parse the sources, resolve their imports and write the reference
+// page into `out_dir`. Returns the page's path.
+fn run_doc_command(
+    source: &str,
+    input_path: &str,
+    sources: &diagnostics::SourceMap,
+    reg: &Registry,
+    out_dir: &str,
+    format: doc_gen::DocFormat,
+    allow_duplicates: bool,
+) -> Result<String, Failure> {
+    let mut module = surface_parser::parse_module_with_file(source, input_path)
+        .map_err(|e| Failure::parse(e.to_string()))?;
+    let warnings = surface_lower::check_duplicates(&mut module, source, input_path, sources, allow_duplicates)
+        .map_err(|e| Failure::validation(format!("VALIDATION ERROR: {}", e)))?;
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+    imports::resolve(&mut module, reg).map_err(|mut e| {
+        e.locate_name(source, input_path);
+        Failure::validation(format!("VALIDATION ERROR: {}", e))
+    })?;
+
+    let title = std::path::Path::new(input_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("Axis");
+    let page = doc_gen::render(&doc_gen::compute(&module, reg), format, title);
+    fs::create_dir_all(out_dir).map_err(|e| Failure::io(format!("Failed to create {}: {}", out_dir, e)))?;
+    let path = std::path::Path::new(out_dir).join(format.file_name());
+    fs::write(&path, page).map_err(|e| Failure::io(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(path.display().to_string())
+}
+
+// --view-core-ir: Load and print Core IR graph; with --focus, only the
+// subterm at that term path
+fn view_core_ir(
+    path: &str,
+    options: &core_loader::BundleLoadOptions,
+    as_text: bool,
+    focus: Option<&str>,
+) -> Result<(), Failure> {
+    let program = {
+        let _span = trace::span_with("load", path);
+        core_loader::load_core_bundle(path, options).map_err(Failure::io)?
+    };
+    match focus {
+        Some(focus) => {
+            let term = term_path::resolve(&program.root_term, focus)
+                .map_err(|e| Failure::new(exit_codes::USAGE, format!("--focus {}", e)))?;
+            if as_text {
+                print!("{}", core_text::print_core_text(term));
+            } else {
+                println!("CoreTerm {}", focus);
+                println!();
+                let entry_id = print_core_graph(term);
+                println!();
+                println!("Entry: {}", entry_id);
+            }
+        }
+        None if as_text => print!("{}", core_text::print_core_text(&program.root_term)),
+        None => print_core_bundle(&program),
+    }
     Ok(())
 }
 
 // Print a textual Core IR graph representation (DAG, not tree)
 fn print_core_bundle(program: &core_loader::CoreProgram) {
+    println!("CoreBundle");
+    println!();
+    let entry_id = print_core_graph(&program.root_term);
+    println!();
+    println!("Functions:");
+    println!("  main:");
+    println!("    entry: {}", entry_id);
+
+    if !program.interface.exports.is_empty() {
+        println!();
+        println!("Exports:");
+        for export in &program.interface.exports {
+            println!("  {}/{}{}", export.name, export.arity, if export.public { "" } else { " (private)" });
+        }
+    }
+    if !program.interface.imports.is_empty() {
+        println!();
+        println!("Imports:");
+        for import in &program.interface.imports {
+            let source = if import.foreign { "foreign" } else { "external" };
+            println!("  {} ({})", import.name, source);
+        }
+    }
+    if !program.metadata.is_empty() {
+        println!();
+        print!("{}", program.metadata.format());
+    }
+}
+
+// The `Graph:` section: every node under `root`, children first; returns
+// the id of `root`
+fn print_core_graph(root: &runtime::CoreTerm) -> String {
     use std::collections::HashMap;
     use runtime::CoreTerm;
 
@@ -380,42 +1489,46 @@ fn print_core_bundle(program: &core_loader::CoreProgram) {
         }
 
         // Recurse into children first (post-order)
-        let (kind, def) = match term {
-            IntLit(n, _) => ("literal.int".to_string(), format!("{}", n)),
-            BoolLit(b, _) => ("literal.bool".to_string(), format!("{}", b)),
-            UnitLit(_) => ("literal.unit".to_string(), String::new()),
-            StrLit(s, _) => ("literal.str".to_string(), format!("{:?}", s)),
-            Var(name, _) => ("var".to_string(), name.clone()),
+        let def = match term {
+            IntLit(n, _) => format!("{}", n),
+            BoolLit(b, _) => format!("{}", b),
+            UnitLit(_) => String::new(),
+            StrLit(s, _) => format!("{:?}", s),
+            Var(name, _) => name.to_string(),
             Lam(param, body, _) => {
                 let body_id = assign_ids(body, nodes, node_ids, counter);
-                ("lam".to_string(), format!("{} -> {}", param, body_id))
+                format!("{} -> {}", param, body_id)
             }
             App(func, arg, _) => {
                 let func_id = assign_ids(func, nodes, node_ids, counter);
                 let arg_id = assign_ids(arg, nodes, node_ids, counter);
-                ("app".to_string(), format!("{}({})", func_id, arg_id))
+                format!("{}({})", func_id, arg_id)
             }
             Tuple(elems, _) => {
                 let elem_ids: Vec<String> = elems
                     .iter()
                     .map(|e| assign_ids(e, nodes, node_ids, counter))
                     .collect();
-                ("tuple".to_string(), format!("({})", elem_ids.join(", ")))
+                format!("({})", elem_ids.join(", "))
             }
             Proj(expr, index, _) => {
                 let expr_id = assign_ids(expr, nodes, node_ids, counter);
-                ("proj".to_string(), format!("{}.{}", expr_id, index))
+                format!("{}.{}", expr_id, index)
+            }
+            CtorField(expr, index, _) => {
+                let expr_id = assign_ids(expr, nodes, node_ids, counter);
+                format!("{}[{}]", expr_id, index)
             }
             Let(name, value, body, _) => {
                 let val_id = assign_ids(value, nodes, node_ids, counter);
                 let body_id = assign_ids(body, nodes, node_ids, counter);
-                ("let".to_string(), format!("{} = {} in {}", name, val_id, body_id))
+                format!("{} = {} in {}", name, val_id, body_id)
             }
             If(cond, then_br, else_br, _) => {
                 let cond_id = assign_ids(cond, nodes, node_ids, counter);
                 let then_id = assign_ids(then_br, nodes, node_ids, counter);
                 let else_id = assign_ids(else_br, nodes, node_ids, counter);
-                ("if".to_string(), format!("{} ? {} : {}", cond_id, then_id, else_id))
+                format!("{} ? {} : {}", cond_id, then_id, else_id)
             }
             Ctor(name, fields, _) => {
                 let field_ids: Vec<String> = fields
@@ -423,9 +1536,9 @@ fn print_core_bundle(program: &core_loader::CoreProgram) {
                     .map(|f| assign_ids(f, nodes, node_ids, counter))
                     .collect();
                 if field_ids.is_empty() {
-                    ("ctor".to_string(), name.clone())
+                    name.clone()
                 } else {
-                    ("ctor".to_string(), format!("{}({})", name, field_ids.join(", ")))
+                    format!("{}({})", name, field_ids.join(", "))
                 }
             }
             Match(scrutinee, arms, _) => {
@@ -437,28 +1550,22 @@ fn print_core_bundle(program: &core_loader::CoreProgram) {
                         format!("{:?} => {}", pat, body_id)
                     })
                     .collect();
-                ("match".to_string(), format!("{} {{ {} }}", scrut_id, arm_strs.join("; ")))
+                format!("{} {{ {} }}", scrut_id, arm_strs.join("; "))
             }
         };
 
         *counter += 1;
         let id = format!("n{}", *counter);
         node_ids.insert(ptr, id.clone());
-        nodes.push((id.clone(), format!("{} {}", kind, def)));
+        nodes.push((id.clone(), format!("{} {}", term.kind_name(), def)));
         id
     }
 
-    let entry_id = assign_ids(&program.root_term, &mut nodes, &mut node_ids, &mut counter);
+    let entry_id = assign_ids(root, &mut nodes, &mut node_ids, &mut counter);
 
-    // Print output
-    println!("CoreBundle");
-    println!();
     println!("Graph:");
     for (id, def) in &nodes {
         println!("  {} = {}", id, def);
     }
-    println!();
-    println!("Functions:");
-    println!("  main:");
-    println!("    entry: {}", entry_id);
+    entry_id
 }
@@ -0,0 +1,310 @@
+// Project manifest (axis.toml) loading
+// Lets `axis-compiler build` run without repeating --sources/--registries/--out.
+//
+// Supported format (a small TOML subset, no external dependency):
+//
+//   [project]
+//   name = "hello"
+//   sources = ["src/main.ax", "src/utils.ax"]
+//   registries = ["registries/axis.axreg"]
+//   out = "coreir/main.coreir"
+//
+//   [build]
+//   flags = ["trace-parse"]
+//
+// Values are double-quoted strings, integers, booleans, or (possibly
+// multi-line) arrays of strings. `#` starts a comment outside strings.
+// Relative paths are resolved against the manifest's own directory.
+//
+// PRECEDENCE: CLI arguments override manifest values field by field;
+// boolean flags are OR-ed (a flag set in either place is on).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::exit_codes::Failure;
+
+pub const MANIFEST_FILE_NAME: &str = "axis.toml";
+
+// Build flags recognised in [build].flags (named after their CLI long options)
+const KNOWN_FLAGS: &[&str] = &["trace-parse", "optimize", "no-prelude", "allow-duplicate-defs"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum TomlValue {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+    Array(Vec<String>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    pub path: PathBuf,
+    // Names the bundle when `out` is not given
+    pub name: Option<String>,
+    pub sources: Vec<String>,
+    pub registries: Vec<String>,
+    pub out: Option<String>,
+    pub flags: Vec<String>,
+}
+
+impl Manifest {
+    // Load and validate a manifest file
+    pub fn load(path: &Path) -> Result<Manifest, Failure> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| Failure::io(format!("Failed to read manifest {}: {}", path.display(), e)))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&content, path, base_dir).map_err(Failure::parse)
+    }
+
+    fn parse(content: &str, path: &Path, base_dir: &Path) -> Result<Manifest, String> {
+        let tables = parse_tables(content, path)?;
+        let mut manifest = Manifest {
+            path: path.to_path_buf(),
+            ..Manifest::default()
+        };
+
+        for (table, keys) in &tables {
+            for (key, (line, value)) in keys {
+                let location = format!("{}:{}", path.display(), line);
+                match (table.as_str(), key.as_str(), value) {
+                    ("project", "sources", TomlValue::Array(items)) => {
+                        manifest.sources = items.iter().map(|p| resolve(base_dir, p)).collect();
+                    }
+                    ("project", "registries", TomlValue::Array(items)) => {
+                        manifest.registries = items.iter().map(|p| resolve(base_dir, p)).collect();
+                    }
+                    ("project", "out", TomlValue::Str(p)) => {
+                        manifest.out = Some(resolve(base_dir, p));
+                    }
+                    ("project", "name", TomlValue::Str(name)) => {
+                        manifest.name = Some(name.clone());
+                    }
+                    ("build", "flags", TomlValue::Array(items)) => {
+                        for flag in items {
+                            if !KNOWN_FLAGS.contains(&flag.as_str()) {
+                                return Err(format!(
+                                    "{}: unknown build flag '{}' (known: {})",
+                                    location, flag, KNOWN_FLAGS.join(", ")
+                                ));
+                            }
+                        }
+                        manifest.flags = items.clone();
+                    }
+                    ("project", "sources" | "registries", _) | ("build", "flags", _) => {
+                        return Err(format!("{}: '{}' must be an array of strings", location, key));
+                    }
+                    ("project", "out" | "name", _) => {
+                        return Err(format!("{}: '{}' must be a string", location, key));
+                    }
+                    _ => {
+                        return Err(format!("{}: unknown key '{}' in [{}]", location, key, table));
+                    }
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+}
+
+// Search for axis.toml in `start` and each of its ancestors
+pub fn discover(start: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start);
+    while let Some(current) = dir {
+        let candidate = current.join(MANIFEST_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        dir = current.parent();
+    }
+    None
+}
+
+fn resolve(base_dir: &Path, p: &str) -> String {
+    let path = Path::new(p);
+    if path.is_absolute() || base_dir.as_os_str().is_empty() {
+        p.to_string()
+    } else {
+        base_dir.join(path).to_string_lossy().into_owned()
+    }
+}
+
+// Table name -> key -> (line number, value), in file order
+type Tables = Vec<(String, Vec<(String, (usize, TomlValue))>)>;
+
+fn parse_tables(content: &str, path: &Path) -> Result<Tables, String> {
+    let mut tables: Tables = Vec::new();
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line_no = i + 1;
+        let line = strip_comment(lines[i]).trim().to_string();
+        i += 1;
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            let name = line
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix(']'))
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .ok_or_else(|| format!("{}:{}: malformed table header: {}", path.display(), line_no, line))?;
+            if tables.iter().any(|(t, _)| t == name) {
+                return Err(format!("{}:{}: duplicate table [{}]", path.display(), line_no, name));
+            }
+            tables.push((name.to_string(), Vec::new()));
+            continue;
+        }
+
+        let (key, raw_value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("{}:{}: expected `key = value`, found: {}", path.display(), line_no, line))?;
+        let key = key.trim().to_string();
+        let mut raw_value = raw_value.trim().to_string();
+
+        // Multi-line arrays: keep consuming lines until brackets balance
+        if raw_value.starts_with('[') {
+            while !array_closed(&raw_value) {
+                if i >= lines.len() {
+                    return Err(format!("{}:{}: unterminated array for '{}'", path.display(), line_no, key));
+                }
+                raw_value.push(' ');
+                raw_value.push_str(strip_comment(lines[i]).trim());
+                i += 1;
+            }
+        }
+
+        let value = parse_value(&raw_value)
+            .map_err(|e| format!("{}:{}: {}", path.display(), line_no, e))?;
+
+        let table = match tables.last_mut() {
+            Some((name, keys)) => {
+                keys.push((key.clone(), (line_no, value)));
+                name.clone()
+            }
+            None => {
+                return Err(format!("{}:{}: key '{}' outside of any [table]", path.display(), line_no, key));
+            }
+        };
+        if let Some(prev) = seen.insert((table.clone(), key.clone()), line_no) {
+            return Err(format!(
+                "{}:{}: duplicate key '{}' in [{}] (first set on line {})",
+                path.display(), line_no, key, table, prev
+            ));
+        }
+    }
+
+    Ok(tables)
+}
+
+// Remove a trailing `# comment`, ignoring `#` inside strings
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            '\\' if in_string => escaped = !escaped,
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => escaped = false,
+        }
+        if c != '\\' {
+            escaped = false;
+        }
+    }
+    line
+}
+
+fn array_closed(raw: &str) -> bool {
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut depth = 0i32;
+    for c in raw.chars() {
+        match c {
+            '\\' if in_string => {
+                escaped = !escaped;
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            '[' if !in_string => depth += 1,
+            ']' if !in_string => depth -= 1,
+            _ => {}
+        }
+        escaped = false;
+    }
+    depth <= 0
+}
+
+fn parse_value(raw: &str) -> Result<TomlValue, String> {
+    if raw == "true" {
+        return Ok(TomlValue::Bool(true));
+    }
+    if raw == "false" {
+        return Ok(TomlValue::Bool(false));
+    }
+    if raw.starts_with('"') {
+        let (s, rest) = parse_string(raw)?;
+        if !rest.trim().is_empty() {
+            return Err(format!("unexpected text after string: {}", rest.trim()));
+        }
+        return Ok(TomlValue::Str(s));
+    }
+    if let Some(inner) = raw.strip_prefix('[') {
+        let mut items = Vec::new();
+        let mut rest = inner.trim_start();
+        loop {
+            if let Some(after) = rest.strip_prefix(']') {
+                if !after.trim().is_empty() {
+                    return Err(format!("unexpected text after array: {}", after.trim()));
+                }
+                return Ok(TomlValue::Array(items));
+            }
+            if !rest.starts_with('"') {
+                return Err("arrays may only contain double-quoted strings".to_string());
+            }
+            let (item, after) = parse_string(rest)?;
+            items.push(item);
+            rest = after.trim_start();
+            if let Some(after_comma) = rest.strip_prefix(',') {
+                rest = after_comma.trim_start();
+            } else if !rest.starts_with(']') {
+                return Err("expected ',' or ']' in array".to_string());
+            }
+        }
+    }
+    raw.parse::<i64>()
+        .map(TomlValue::Int)
+        .map_err(|_| format!("unsupported value: {}", raw))
+}
+
+// Parse a leading double-quoted string; returns (contents, remaining input)
+fn parse_string(raw: &str) -> Result<(String, &str), String> {
+    let mut out = String::new();
+    let mut chars = raw.char_indices().skip(1);
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => return Ok((out, &raw[idx + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => out.push('\n'),
+                Some((_, 't')) => out.push('\t'),
+                Some((_, '\\')) => out.push('\\'),
+                Some((_, '"')) => out.push('"'),
+                Some((_, other)) => return Err(format!("unsupported escape '\\{}'", other)),
+                None => break,
+            },
+            _ => out.push(c),
+        }
+    }
+    Err("unterminated string".to_string())
+}
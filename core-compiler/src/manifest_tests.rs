@@ -0,0 +1,126 @@
+// Tests for axis.toml loading (manifest.rs)
+
+use crate::exit_codes;
+use crate::manifest::{self, Manifest, MANIFEST_FILE_NAME};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// A fresh directory for one test's manifest
+fn project_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("axis-manifest-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_manifest(dir: &Path, content: &str) -> PathBuf {
+    let path = dir.join(MANIFEST_FILE_NAME);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn in_dir(dir: &Path, path: &str) -> String {
+    dir.join(path).to_string_lossy().into_owned()
+}
+
+// The error a manifest fails to load with
+fn error(name: &str, content: &str) -> String {
+    let dir = project_dir(name);
+    let failure = Manifest::load(&write_manifest(&dir, content)).unwrap_err();
+    assert_eq!(failure.code, exit_codes::PARSE);
+    let location = format!("{}:", dir.join(MANIFEST_FILE_NAME).display());
+    failure.message.strip_prefix(&location).expect("starts with the manifest path").to_string()
+}
+
+#[test]
+fn test_comments_and_multi_line_arrays() {
+    let dir = project_dir("arrays");
+    let path = write_manifest(
+        &dir,
+        "# the project\n\
+         [project]   # its table\n\
+         name = \"hello # not a comment\"\n\
+         sources = [\n\
+             \"src/main.ax\",   # the entry point\n\
+             # a line of its own\n\
+             \"src/utils.ax\",\n\
+         ]\n\
+         registries = [\"axis.axreg\"]\n\
+         \n\
+         [build]\n\
+         flags = [\"optimize\", \"no-prelude\"]\n",
+    );
+    let manifest = Manifest::load(&path).unwrap();
+    assert_eq!(manifest.path, path);
+    assert_eq!(manifest.name.as_deref(), Some("hello # not a comment"));
+    assert_eq!(manifest.sources, vec![in_dir(&dir, "src/main.ax"), in_dir(&dir, "src/utils.ax")]);
+    assert_eq!(manifest.registries, vec![in_dir(&dir, "axis.axreg")]);
+    assert_eq!(manifest.out, None);
+    assert!(manifest.has_flag("optimize") && manifest.has_flag("no-prelude"));
+    assert!(!manifest.has_flag("trace-parse"));
+}
+
+#[test]
+fn test_unknown_keys_and_flags_are_refused() {
+    assert_eq!(error("key", "[project]\nname = \"a\"\nsource = [\"a.ax\"]\n"), "3: unknown key 'source' in [project]");
+    assert_eq!(error("table", "[tools]\nout = \"a.coreir\"\n"), "2: unknown key 'out' in [tools]");
+    assert_eq!(
+        error("flag", "[build]\nflags = [\"optimize\",\n  \"fast\"]\n"),
+        "2: unknown build flag 'fast' (known: trace-parse, optimize, no-prelude, allow-duplicate-defs)"
+    );
+    assert_eq!(error("type", "[project]\nsources = \"a.ax\"\n"), "2: 'sources' must be an array of strings");
+    assert_eq!(error("out", "[project]\nout = 3\n"), "2: 'out' must be a string");
+}
+
+#[test]
+fn test_malformed_lines_name_their_line() {
+    assert_eq!(error("no-table", "name = \"a\"\n"), "1: key 'name' outside of any [table]");
+    assert_eq!(error("header", "[project]\n\n[build\n"), "3: malformed table header: [build");
+    assert_eq!(error("equals", "[project]\nname \"a\"\n"), "2: expected `key = value`, found: name \"a\"");
+    assert_eq!(error("string", "[project]\nname = \"a\n"), "2: unterminated string");
+    assert_eq!(error("escape", "[project]\nname = \"a\\q\"\n"), "2: unsupported escape '\\q'");
+    assert_eq!(error("trailing", "[project]\nname = \"a\" b\n"), "2: unexpected text after string: b");
+    assert_eq!(error("value", "[project]\nname = hello\n"), "2: unsupported value: hello");
+    assert_eq!(error("array", "[project]\nsources = [\"a.ax\"\n\"b.ax\"]\n"), "2: expected ',' or ']' in array");
+    assert_eq!(error("unterminated", "[project]\nsources = [\n\"a.ax\",\n"), "2: unterminated array for 'sources'");
+    assert_eq!(error("items", "[project]\nsources = [1, 2]\n"), "2: arrays may only contain double-quoted strings");
+    assert_eq!(
+        error("duplicate-key", "[project]\nname = \"a\"\n\nname = \"b\"\n"),
+        "4: duplicate key 'name' in [project] (first set on line 2)"
+    );
+    assert_eq!(error("duplicate-table", "[project]\n[build]\n[project]\n"), "3: duplicate table [project]");
+}
+
+#[test]
+fn test_paths_are_relative_to_the_manifest() {
+    let dir = project_dir("paths");
+    let absolute = std::env::temp_dir().join("shared.axreg").to_string_lossy().into_owned();
+    let content = format!(
+        "[project]\nsources = [\"src/main.ax\", \"../lib/util.ax\"]\nregistries = [{:?}]\nout = \"build/main.coreir\"\n",
+        absolute
+    );
+    let manifest = Manifest::load(&write_manifest(&dir, &content)).unwrap();
+    assert_eq!(manifest.sources, vec![in_dir(&dir, "src/main.ax"), in_dir(&dir, "../lib/util.ax")]);
+    // Absolute paths are kept as written
+    assert_eq!(manifest.registries, vec![absolute]);
+    assert_eq!(manifest.out, Some(in_dir(&dir, "build/main.coreir")));
+}
+
+#[test]
+fn test_discover_searches_upward() {
+    let dir = project_dir("discover");
+    let nested = dir.join("src").join("deep");
+    fs::create_dir_all(&nested).unwrap();
+    assert!(manifest::discover(&nested).is_none_or(|found| !found.starts_with(&dir)));
+    let path = write_manifest(&dir, "[project]\n");
+    assert_eq!(manifest::discover(&nested), Some(path.clone()));
+    assert_eq!(manifest::discover(&dir), Some(path));
+}
+
+#[test]
+fn test_a_missing_manifest_is_an_io_error() {
+    let dir = project_dir("missing");
+    let failure = Manifest::load(&dir.join(MANIFEST_FILE_NAME)).unwrap_err();
+    assert_eq!(failure.code, exit_codes::IO);
+    assert!(failure.message.starts_with("Failed to read manifest"), "{}", failure);
+}
@@ -0,0 +1,102 @@
+// @memo: functions whose results are cached per argument value
+//
+// A function marked @memo is lowered (surface_lower) so that it looks its
+// arguments up in a runtime cache first:
+//
+//   @memo fn f(a, b) { body }
+//     ==>  fn f(a, b) {
+//              match axis_memo_get("f", (a, b)) {
+//                  Some(cached) => cached,
+//                  None => axis_memo_put("f", (a, b), body),
+//              }
+//          }
+//
+// The key is the argument itself for one parameter and () for none. The
+// cache belongs to the runtime (rust-bridge runtime/memo.rs), so the
+// emitters need nothing new and the Core stays plain Core.
+//
+// POLICY: a cached result is only right if the function depends on nothing
+// but its arguments. check() rejects a @memo function that can reach,
+// directly or through other functions of the program, a primitive whose
+// registry entry is not deterministic (AX1013). The
+// hardcoded builtins count as deterministic, as for the optimizer.
+
+use crate::core_validator::ValidationError;
+use crate::error_codes::ErrorCode;
+use crate::prelude::collect_references;
+use crate::registry_loader::Registry;
+use crate::runtime::Value;
+use crate::surface_parser::{FnDef, Module};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const MEMO_GET: &str = "axis_memo_get";
+const MEMO_PUT: &str = "axis_memo_put";
+
+/// Check that every @memo function only reaches deterministic primitives
+pub fn check(module: &Module, registry: &Registry) -> Result<(), ValidationError> {
+    let functions: HashMap<&str, &FnDef> = module.functions.iter().map(|f| (f.name.as_str(), f)).collect();
+    for memo in module.functions.iter().filter(|f| f.has_attribute("memo")) {
+        if let Some((primitive, through)) = nondeterministic_call(memo, &functions, registry) {
+            let via = if through == memo.name { String::new() } else { format!(" (through `{}`)", through) };
+            let mut error = ValidationError::new(format!(
+                "{}: @memo function `{}` calls `{}`{}, which is not deterministic",
+                ErrorCode::MemoNondeterministic, memo.name, primitive, via
+            ));
+            error.name = Some(memo.name.clone());
+            error.help.push(format!("a cached result would hide the effects of `{}`; remove @memo", primitive));
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+// The first nondeterministic primitive `memo` reaches, and the function
+// that calls it
+fn nondeterministic_call(
+    memo: &FnDef,
+    functions: &HashMap<&str, &FnDef>,
+    registry: &Registry,
+) -> Option<(String, String)> {
+    let mut seen = HashSet::from([memo.name.clone()]);
+    let mut queue = VecDeque::from([memo]);
+    while let Some(function) = queue.pop_front() {
+        let mut references = HashSet::new();
+        collect_references(&function.body, &mut references);
+        let mut references: Vec<String> = references.into_iter().collect();
+        references.sort();
+        for name in references {
+            if function.params.contains(&name) {
+                continue;
+            }
+            if let Some(callee) = functions.get(name.as_str()) {
+                if seen.insert(name) {
+                    queue.push_back(callee);
+                }
+            } else if registry.entries.get(&name).is_some_and(|entry| !entry.deterministic)
+                || crate::validation_registry::is_io_primitive(&name)
+            {
+                return Some((name, function.name.clone()));
+            }
+        }
+    }
+    None
+}
+
+/// A @memo function's lowered body, made to go through the runtime cache
+pub fn cached_body(function: &FnDef, body: Value) -> Value {
+    let key = match function.params.as_slice() {
+        [] => Value::Unit,
+        [param] => Value::Var(param.clone()),
+        params => Value::Tuple(params.iter().cloned().map(Value::Var).collect()),
+    };
+    let name = Value::Str(crate::intern_string(function.name.clone()));
+    let call = |primitive: &str, args: Vec<Value>| {
+        args.into_iter().fold(Value::Var(primitive.to_string()), |f, arg| Value::App(Box::new(f), Box::new(arg)))
+    };
+    let lookup = call(MEMO_GET, vec![name.clone(), key.clone()]);
+    let store = call(MEMO_PUT, vec![name, key, body]);
+    Value::Match(
+        Box::new(lookup),
+        vec![("Some(cached)".to_string(), Value::Var("cached".to_string())), ("None".to_string(), store)],
+    )
+}
@@ -0,0 +1,61 @@
+// Tests for @memo functions (memo.rs)
+
+use crate::memo;
+use crate::registry_loader::Registry;
+use crate::surface_parser::{self, Module};
+use crate::{core_validator, interpret, surface_lower, surface_to_core};
+
+fn parse(source: &str) -> Module {
+    // Lowering interns the function names the cache is keyed on
+    crate::init_string_table();
+    surface_parser::parse_module_with_file(source, "main.ax").unwrap()
+}
+
+const FIB: &str = "@memo\nfn fib(n: Int) -> Int {\n    if n < 2 { n } else { add(fib(n - 1), fib(n - 2)) }\n}\n\n\
+                   fn add(a: Int, b: Int) -> Int {\n    a + b\n}\n\n\
+                   fn axis_entry(args: Unit) -> Int {\n    fib(20) - 6700\n}\n";
+
+#[test]
+fn test_memo_functions_go_through_the_cache() {
+    let module = parse(FIB);
+    assert!(memo::check(&module, &Registry::prelude()).is_ok());
+    let core = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let text = crate::core_text::print_core_text(&core);
+    assert!(text.contains("(var axis_memo_get)") && text.contains("(var axis_memo_put)"), "{}", text);
+    // Only add's body is left as it was
+    assert_eq!(text.matches("axis_memo_get").count(), 1, "{}", text);
+
+    assert!(!core_validator::validate_core(&core, &Registry::prelude()).has_errors());
+    // fib(20) = 6765
+    assert_eq!(interpret::run_program(&core, &[]), 65);
+}
+
+#[test]
+fn test_memo_keys_on_every_parameter() {
+    let source = "@memo\nfn pick(a: Int, b: Int) -> Int {\n    a * 10 + b\n}\n\n\
+                  @memo\nfn seven() -> Int {\n    7\n}\n\n\
+                  fn axis_entry(args: Unit) -> Int {\n    pick(3, 4) + pick(4, 3) + seven()\n}\n";
+    let module = parse(source);
+    let core = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    assert_eq!(interpret::run_program(&core, &[]), 34 + 43 + 7);
+}
+
+#[test]
+fn test_memo_rejects_nondeterministic_call_graphs() {
+    let source = "@memo\nfn lookup(key: Str) -> Int {\n    log(key);\n    str_len(key)\n}\n\n\
+                  fn log(message: Str) -> Unit {\n    axis_io_print(message)\n}\n";
+    let error = memo::check(&parse(source), &Registry::prelude()).unwrap_err();
+    assert_eq!(
+        error.message,
+        "AX1013: @memo function `lookup` calls `axis_io_print` (through `log`), which is not deterministic"
+    );
+    assert_eq!(error.name.as_deref(), Some("lookup"));
+
+    // Without @memo the same function is fine
+    assert!(memo::check(&parse(&source.replacen("@memo\n", "", 1)), &Registry::prelude()).is_ok());
+
+    // A registry primitive declared nondeterministic, called directly
+    let source = "@memo\nfn roll(sides: Int) -> Int {\n    axis_memo_get(\"roll\", sides)\n}\n";
+    let error = memo::check(&parse(source), &Registry::prelude()).unwrap_err();
+    assert!(error.message.contains("calls `axis_memo_get`, which"), "{}", error.message);
+}
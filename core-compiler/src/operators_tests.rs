@@ -0,0 +1,113 @@
+// Tests for the operator table (operators.rs) and how the parser reads it
+
+use crate::diagnostics::Diagnostic;
+use crate::lexer::{lex, TokenKind};
+use crate::operators::{infix, is_operator, OPERATORS, PIPE};
+use crate::{ast_dump, interpret, surface_lower, surface_parser, surface_to_core};
+
+fn ast(source: &str) -> String {
+    match surface_parser::parse_module_with_file(source, "main.ax") {
+        Ok(module) => ast_dump::dump_text(&module),
+        Err(error) => panic!("{}\n{}", source, error),
+    }
+}
+
+fn run(source: &str) -> i32 {
+    crate::init_string_table();
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    interpret::run_program(&surface_to_core::value_to_core(&surface_lower::lower_module(module)), &[])
+}
+
+#[test]
+fn test_every_symbol_lexes_as_one_operator() {
+    for op in OPERATORS {
+        let tokens = lex(&format!("a {} b", op.symbol)).unwrap();
+        assert_eq!((tokens[1].kind, tokens[1].text.as_str()), (TokenKind::Operator, op.symbol), "{}", op.symbol);
+        assert_eq!(infix(op.symbol), Some(op));
+        assert!(is_operator(op.symbol) && (is_operator(op.function) || op.function == PIPE));
+    }
+    assert!(infix("=").is_none() && !is_operator("__not__") && !is_operator(PIPE));
+}
+
+#[test]
+fn test_tighter_operators_group_first() {
+    for (source, grouped) in [
+        ("fn f(a, b, c) { a + b * c }", "fn f(a, b, c) { a + (b * c) }"),
+        ("fn f(a, b, c) { a * b + c }", "fn f(a, b, c) { (a * b) + c }"),
+        ("fn f(n) { n - 1 == 0 }", "fn f(n) { (n - 1) == 0 }"),
+        ("fn f(a, b, c) { a || b && c }", "fn f(a, b, c) { a || (b && c) }"),
+        ("fn f(a, b) { a < b && b < 9 }", "fn f(a, b) { (a < b) && (b < 9) }"),
+        ("fn f(s, n) { s ++ \"x\" == s }", "fn f(s, n) { (s ++ \"x\") == s }"),
+    ] {
+        assert_eq!(ast(source), ast(grouped), "{}", source);
+    }
+}
+
+#[test]
+fn test_associativity() {
+    assert_eq!(ast("fn f(a, b, c) { a - b - c }"), ast("fn f(a, b, c) { (a - b) - c }"));
+    assert_eq!(ast("fn f(a, b, c) { a / b * c }"), ast("fn f(a, b, c) { (a / b) * c }"));
+    assert_eq!(ast("fn f(a, b, c) { a ++ b ++ c }"), ast("fn f(a, b, c) { a ++ (b ++ c) }"));
+}
+
+#[test]
+fn test_parentheses_group() {
+    assert_eq!(
+        ast("fn f(a, b, c) { (a + b) * c }"),
+        "fn f(a, b, c)\n  block\n    call __mul__\n      call __add__\n        ident a\n        ident b\n      ident c\n"
+    );
+    // With a trailing comma it is a one-element tuple
+    assert!(ast("fn f(a) { (a,) }").contains("call __tuple__\n      ident a\n"));
+}
+
+#[test]
+fn test_only_a_comma_makes_a_tuple() {
+    let grouped = "fn f(a, b)\n  block\n    ident a\n";
+    let single = "fn f(a, b)\n  block\n    call __tuple__\n      ident a\n";
+    let pair = "fn f(a, b)\n  block\n    call __tuple__\n      ident a\n      ident b\n";
+    assert_eq!(ast("fn f(a, b) { (a) }"), grouped);
+    assert_eq!(ast("fn f(a, b) { (a,) }"), single);
+    assert_eq!(ast("fn f(a, b) { (a, b) }"), pair);
+    assert_eq!(ast("fn f(a, b) { (a, b,) }"), pair);
+    // Comments and line breaks between the tokens change nothing
+    assert_eq!(ast("fn f(a, b) { (\n    a // only a\n) }"), grouped);
+    assert_eq!(ast("fn f(a, b) { (\n    a, // one element\n) }"), single);
+    assert_eq!(ast("fn f(a, b) { (a // first\n, b) }"), pair);
+}
+
+#[test]
+fn test_every_operator_runs() {
+    // 2 + 12 - 2, not ((2 + 3) * 4 - 10) / 5
+    assert_eq!(run("fn axis_entry(args) { 2 + 3 * 4 - 10 / 5 }"), 12);
+    let source = "fn axis_entry(args) {\n    \
+                  if 7 % 4 == 3 && 1 != 2 && 1 < 2 && 2 <= 2 && 3 > 2 && 3 >= 3 && (\"a\" ++ \"b\") == \"ab\" || false { 0 } else { 1 }\n}\n";
+    assert_eq!(run(source), 0);
+}
+
+#[test]
+fn test_pipeline_runs() {
+    let source = "fn double(x) { x * 2 }\nfn sub(a, b) { a - b }\nfn axis_entry(args) { 5 + 1 |> double |> sub(2) }\n";
+    assert_eq!(run(source), 10);
+}
+
+#[test]
+fn test_pipeline_takes_any_function_name() {
+    let source = "fn __scale(x, by) { x * by }\nfn axis_entry(args) { 3 |> __scale(4) }\n";
+    assert_eq!(run(source), 12);
+}
+
+#[test]
+fn test_pipeline_needs_a_function_on_the_right() {
+    for (source, found) in [
+        ("fn f(x) { x |> 1 }", "1"),
+        ("fn f(x) { x |> (1, 2) }", "("),
+        ("fn f(x) { x |> g(1) + 1 }", "g"),
+        ("fn f(x) { x |> (g |> h) }", "("),
+        ("fn f(x) { x |> Point { y: 1 } }", "Point"),
+        ("fn f(x) { x |> \"g\" }", "\"g\""),
+    ] {
+        let error = surface_parser::parse_module_with_file(source, "main.ax").expect_err(source);
+        let expected = format!("expected 'function or call', found '{}'", found);
+        assert_eq!(Diagnostic::from(&error).message, expected, "{}", source);
+    }
+}
@@ -0,0 +1,1095 @@
+// Core IR optimizer (`--optimize`)
+//
+// Passes run on validated Core IR, before the bundle interface is computed,
+// and must not change what a program does under axis-rust-bridge:
+//
+//   inline  small nonrecursive top-level functions are substituted at
+//           their call sites, saving the argument tuple packing and
+//           unpacking of the unary calling convention
+//   fold    partial evaluation: a call to a deterministic primitive whose
+//           arguments are all literals is replaced by its result
+//   share   common subexpression elimination: a pure subterm computed more
+//           than once in a function body is bound once by a Let
+//
+// Inlining goes first so that folding and sharing see the inlined bodies.
+//
+// FOLDING RULES:
+//   - Only full applications are folded: f(a)(b) for a 2-ary primitive,
+//     never f(a) or f(a)(b)(c).
+//   - A primitive is deterministic when its registry entry says so; the
+//     hardcoded builtins (validation_registry) are deterministic unless the
+//     registry overrides them.
+//   - A name bound anywhere in the program (Let, Lam, pattern) is never
+//     folded, so user definitions that shadow a primitive keep working.
+//   - Results follow the bridge runtime (runtime/value.rs, shim.rs):
+//     str_len counts chars. Calls the runtime would reject (a wrong
+//     argument type) are left for the runtime to report.
+//   - Int arithmetic that overflows or divides by zero is left alone: what
+//     it does depends on the --int-overflow mode the program is built with
+//     (rust-bridge/src/runtime/overflow.rs), which is not known here.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use crate::registry_loader::Registry;
+use crate::runtime::{CoreTerm, Pattern, Span};
+use crate::symbol::Symbol;
+
+// Largest function body (in Core nodes, parameters excluded) inlined
+// without an @inline hint
+pub const INLINE_THRESHOLD: usize = 24;
+
+pub struct OptimizeOptions {
+    pub inline_threshold: usize,
+    // Functions marked @inline: inlined whatever their size
+    pub inline_hints: HashSet<String>,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        OptimizeOptions { inline_threshold: INLINE_THRESHOLD, inline_hints: HashSet::new() }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OptimizeStats {
+    pub inlined_calls: usize,
+    pub folded_calls: usize,
+    pub shared_subterms: usize,
+    // One line per called top-level function: inlined or why not
+    pub inline_decisions: Vec<String>,
+}
+
+pub fn optimize(term: &CoreTerm, registry: &Registry, options: &OptimizeOptions) -> (CoreTerm, OptimizeStats) {
+    let mut stats = OptimizeStats::default();
+    let mut bound = HashSet::new();
+    let mut referenced = HashSet::new();
+    collect_names(term, &mut bound, &mut referenced);
+    let mut names = NameSupply { used: bound.union(&referenced).cloned().collect(), next: HashMap::new() };
+
+    let inlined = inline_functions(term, options, &mut names, &mut stats);
+    let folder = Folder { registry, bound: &bound };
+    let folded = folder.fold(&inlined, &mut stats);
+    let mut sharer = Sharer { registry, bound: &bound, names: &mut names };
+    let optimized = sharer.process(&folded, false, false, &mut stats);
+    (optimized, stats)
+}
+
+// Fresh binder names, distinct from every name in the program
+struct NameSupply {
+    used: HashSet<String>,
+    // Next number per prefix
+    next: HashMap<&'static str, usize>,
+}
+
+impl NameSupply {
+    // `__<prefix><n>`, or `__<prefix><n>_<base>` to keep the original name readable
+    fn fresh(&mut self, prefix: &'static str, base: Option<&str>) -> String {
+        loop {
+            let next = self.next.entry(prefix).or_insert(0);
+            let name = match base {
+                Some(base) => format!("__{}{}_{}", prefix, next, base),
+                None => format!("__{}{}", prefix, next),
+            };
+            *next += 1;
+            if self.used.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+}
+
+// Primitive calls the fold and share passes may treat as values: deterministic
+// and not redefined by the program
+fn is_pure_primitive(registry: &Registry, bound: &HashSet<String>, name: &str) -> bool {
+    if bound.contains(name) {
+        return false;
+    }
+    match registry.entries.get(name) {
+        Some(entry) => entry.deterministic,
+        None => crate::validation_registry::is_known_function(registry, name),
+    }
+}
+
+// ============================================================================
+// Inlining
+// ============================================================================
+//
+// Every call of a top-level function packs its arguments into a tuple Value
+// that the callee unpacks again (the unary calling convention of the
+// emitters). For small functions that costs more than the body, so calls
+// are replaced by the body, its parameters bound to the arguments:
+//
+//   let sq = \x. x * x in ... sq(n + 1) ...
+//     ==>  ... (let __inl0_x = n + 1 in __inl0_x * __inl0_x) ...
+//
+// INLINING RULES:
+//   - Only calls inside function bodies, with exactly as many arguments as
+//     the callee has parameters and the callee's name as the head.
+//   - Recursive functions (those that can reach themselves in the call
+//     graph) are never inlined, @inline or not.
+//   - The callee's body, after inlining its own calls, must be at most
+//     `inline_threshold` nodes, unless the function is marked @inline.
+//   - A call where a local of the caller shadows the callee or a name its
+//     body uses is left alone.
+//   - Arguments are bound by Lets in order, so each is evaluated once, before
+//     the body, as in the call.
+//   - Definitions stay in place: other bundles and function values may still
+//     refer to them.
+
+struct Function {
+    params: Vec<String>,
+    // Below the parameters
+    body: CoreTerm,
+}
+
+// What happened to the call sites of one function
+#[derive(Default)]
+struct InlineDecision {
+    inlined: usize,
+    captured: usize,
+    // Why no call site is inlined
+    refused: Option<String>,
+}
+
+// A function body after inlining, with what call sites need to know
+struct InlinedBody {
+    body: CoreTerm,
+    size: usize,
+    // Free names other than the parameters
+    free: HashSet<String>,
+}
+
+struct Inliner<'a> {
+    options: &'a OptimizeOptions,
+    names: &'a mut NameSupply,
+    functions: HashMap<String, Function>,
+    recursive: HashSet<String>,
+    bodies: HashMap<String, Rc<InlinedBody>>,
+    decisions: HashMap<String, InlineDecision>,
+}
+
+fn inline_functions(
+    term: &CoreTerm,
+    options: &OptimizeOptions,
+    names: &mut NameSupply,
+    stats: &mut OptimizeStats,
+) -> CoreTerm {
+    // The top-level Let chain is the function table; a name defined twice is
+    // ambiguous and never inlined
+    let mut order = Vec::new();
+    let mut functions = HashMap::new();
+    let mut defined = HashSet::new();
+    let mut current = term;
+    while let CoreTerm::Let(name, value, body, _) = current {
+        if !defined.insert(*name) {
+            functions.remove(name.as_str());
+        } else if let Some((params, function_body)) = split_params(value) {
+            functions.insert(name.to_string(), Function { params, body: function_body.clone() });
+            order.push(name.to_string());
+        }
+        current = body;
+    }
+    order.retain(|name| functions.contains_key(name));
+
+    let recursive = find_recursive(&functions);
+    let mut inliner = Inliner {
+        options,
+        names,
+        functions,
+        recursive,
+        bodies: HashMap::new(),
+        decisions: HashMap::new(),
+    };
+    let result = inliner.rewrite_top_level(term);
+
+    for name in &order {
+        let Some(decision) = inliner.decisions.get(name) else {
+            continue;
+        };
+        stats.inlined_calls += decision.inlined;
+        if let Some(reason) = &decision.refused {
+            stats.inline_decisions.push(format!("not inlined {}: {}", name, reason));
+            continue;
+        }
+        if decision.inlined > 0 {
+            let size = inliner.bodies[name].size;
+            stats.inline_decisions.push(format!(
+                "inlined {} ({} nodes) at {} call site(s)",
+                name, size, decision.inlined
+            ));
+        }
+        if decision.captured > 0 {
+            stats.inline_decisions.push(format!(
+                "not inlined {} at {} call site(s): a local of the caller shadows a name it uses",
+                name, decision.captured
+            ));
+        }
+    }
+    result
+}
+
+// `\p1. ... \pn. body` as ([p1, ..., pn], body); None for non-functions
+fn split_params(term: &CoreTerm) -> Option<(Vec<String>, &CoreTerm)> {
+    let mut params = Vec::new();
+    let mut body = term;
+    while let CoreTerm::Lam(param, inner, _) = body {
+        params.push(param.to_string());
+        body = inner;
+    }
+    if params.is_empty() {
+        None
+    } else {
+        Some((params, body))
+    }
+}
+
+// Functions that can reach themselves through the top-level functions their
+// bodies mention (called or not)
+fn find_recursive(functions: &HashMap<String, Function>) -> HashSet<String> {
+    let callees: HashMap<&str, Vec<&str>> = functions
+        .iter()
+        .map(|(name, function)| {
+            let mut bound = HashSet::new();
+            let mut referenced = HashSet::new();
+            collect_names(&function.body, &mut bound, &mut referenced);
+            let mut called: Vec<&str> = functions
+                .keys()
+                .filter(|callee| referenced.contains(*callee))
+                .map(|callee| callee.as_str())
+                .collect();
+            called.sort_unstable();
+            (name.as_str(), called)
+        })
+        .collect();
+
+    let mut recursive = HashSet::new();
+    for name in functions.keys() {
+        let mut seen = HashSet::new();
+        let mut stack = callees[name.as_str()].clone();
+        while let Some(next) = stack.pop() {
+            if next == name {
+                recursive.insert(name.clone());
+                break;
+            }
+            if seen.insert(next) {
+                stack.extend(&callees[next]);
+            }
+        }
+    }
+    recursive
+}
+
+impl Inliner<'_> {
+    fn rewrite_top_level(&mut self, term: &CoreTerm) -> CoreTerm {
+        let CoreTerm::Let(name, value, body, span) = term else {
+            return term.clone();
+        };
+        let value = match split_params(value) {
+            // Ambiguous names are rewritten in place, not through `bodies`
+            Some((params, function_body)) => {
+                let new_body = if self.functions.contains_key(name.as_str()) {
+                    self.inlined_body(name).body.clone()
+                } else {
+                    let mut scope = params.clone();
+                    self.rewrite(function_body, &mut scope)
+                };
+                rewrap_params(value, new_body)
+            }
+            None => value.as_ref().clone(),
+        };
+        CoreTerm::Let(*name, Rc::new(value), Rc::new(self.rewrite_top_level(body)), span.clone())
+    }
+
+    // Body of `name` with its own calls inlined, computed once. The call
+    // graph below a nonrecursive function is acyclic, so this terminates.
+    fn inlined_body(&mut self, name: &str) -> Rc<InlinedBody> {
+        if let Some(body) = self.bodies.get(name) {
+            return body.clone();
+        }
+        let function = &self.functions[name];
+        let params = function.params.clone();
+        let original = function.body.clone();
+        let mut scope = params.clone();
+        let body = self.rewrite(&original, &mut scope);
+        let mut free = HashSet::new();
+        free_vars(&body, &mut params.clone(), &mut free);
+        let inlined = Rc::new(InlinedBody { size: node_count(&body), body, free });
+        self.bodies.insert(name.to_string(), inlined.clone());
+        inlined
+    }
+
+    // `scope` holds the locals bound around `term` in the function
+    fn rewrite(&mut self, term: &CoreTerm, scope: &mut Vec<String>) -> CoreTerm {
+        match term {
+            CoreTerm::App(..) => self.rewrite_call(term, scope),
+            CoreTerm::Lam(param, body, span) => {
+                scope.push(param.to_string());
+                let body = self.rewrite(body, scope);
+                scope.pop();
+                CoreTerm::Lam(*param, Rc::new(body), span.clone())
+            }
+            CoreTerm::Let(name, value, body, span) => {
+                let value = self.rewrite(value, scope);
+                scope.push(name.to_string());
+                let body = self.rewrite(body, scope);
+                scope.pop();
+                CoreTerm::Let(*name, Rc::new(value), Rc::new(body), span.clone())
+            }
+            CoreTerm::Match(scrutinee, arms, span) => {
+                let scrutinee = self.rewrite(scrutinee, scope);
+                let arms = arms
+                    .iter()
+                    .map(|(pattern, body)| {
+                        let mut binders = HashSet::new();
+                        collect_pattern_binders(pattern, &mut binders);
+                        let depth = scope.len();
+                        scope.extend(binders);
+                        let body = self.rewrite(body, scope);
+                        scope.truncate(depth);
+                        (pattern.clone(), body)
+                    })
+                    .collect();
+                CoreTerm::Match(Rc::new(scrutinee), arms, span.clone())
+            }
+            _ => {
+                let new = children(term).into_iter().map(|child| self.rewrite(child, scope)).collect();
+                with_children(term, new)
+            }
+        }
+    }
+
+    // A whole call spine f(a1)...(an): arguments first, then the call itself.
+    // Partial applications inside the spine are never inlined, which keeps
+    // the head of every remaining spine a variable.
+    fn rewrite_call(&mut self, call: &CoreTerm, scope: &mut Vec<String>) -> CoreTerm {
+        let mut apps = Vec::new();
+        let mut head = call;
+        while let CoreTerm::App(func, _, _) = head {
+            apps.push(head);
+            head = func;
+        }
+        apps.reverse();
+        let args: Vec<CoreTerm> = apps
+            .iter()
+            .map(|app| match app {
+                CoreTerm::App(_, arg, _) => self.rewrite(arg, scope),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        if let CoreTerm::Var(name, _) = head
+            && let Some(inlined) = self.try_inline(name, &args, span_of(call), scope)
+        {
+            return inlined;
+        }
+        let head = match head {
+            CoreTerm::Var(..) => head.clone(),
+            _ => self.rewrite(head, scope),
+        };
+        apps.iter()
+            .zip(args)
+            .fold(head, |func, (app, arg)| CoreTerm::App(Rc::new(func), Rc::new(arg), span_of(app)))
+    }
+
+    fn try_inline(&mut self, name: &str, args: &[CoreTerm], span: Option<Span>, scope: &[String]) -> Option<CoreTerm> {
+        let params = match self.functions.get(name) {
+            Some(function) if !scope.iter().any(|local| local == name) => function.params.clone(),
+            _ => return None,
+        };
+        if params.len() != args.len() {
+            return None;
+        }
+        if self.recursive.contains(name) {
+            self.decisions.entry(name.to_string()).or_default().refused = Some("recursive".to_string());
+            return None;
+        }
+        let inlined = self.inlined_body(name);
+        if inlined.size > self.options.inline_threshold && !self.options.inline_hints.contains(name) {
+            self.decisions.entry(name.to_string()).or_default().refused = Some(format!(
+                "{} nodes > threshold {}",
+                inlined.size, self.options.inline_threshold
+            ));
+            return None;
+        }
+        let decision = self.decisions.entry(name.to_string()).or_default();
+        if scope.iter().any(|local| inlined.free.contains(local)) {
+            decision.captured += 1;
+            return None;
+        }
+        decision.inlined += 1;
+
+        // Innermost parameter first, so a repeated name keeps its inner binding
+        let fresh: Vec<String> = params.iter().map(|param| self.names.fresh("inl", Some(param))).collect();
+        let mut body = inlined.body.clone();
+        for (param, fresh) in params.iter().zip(&fresh).rev() {
+            body = rename_free(&body, param, fresh);
+        }
+        Some(fresh.into_iter().zip(args).rev().fold(body, |body, (fresh, arg)| {
+            CoreTerm::Let(fresh.into(), Rc::new(arg.clone()), Rc::new(body), span.clone())
+        }))
+    }
+}
+
+// `function` (a Lam chain) with the body below its parameters replaced
+fn rewrap_params(function: &CoreTerm, body: CoreTerm) -> CoreTerm {
+    match function {
+        CoreTerm::Lam(param, inner, span) => CoreTerm::Lam(*param, Rc::new(rewrap_params(inner, body)), span.clone()),
+        _ => body,
+    }
+}
+
+fn node_count(term: &CoreTerm) -> usize {
+    1 + children(term).into_iter().map(node_count).sum::<usize>()
+}
+
+// Names `term` uses without binding them, beyond those in `scope`
+fn free_vars(term: &CoreTerm, scope: &mut Vec<String>, free: &mut HashSet<String>) {
+    let depth = scope.len();
+    match term {
+        CoreTerm::Var(name, _) => {
+            if !scope.iter().any(|local| name == local) {
+                free.insert(name.to_string());
+            }
+        }
+        CoreTerm::Lam(param, body, _) => {
+            scope.push(param.to_string());
+            free_vars(body, scope, free);
+        }
+        CoreTerm::Let(name, value, body, _) => {
+            free_vars(value, scope, free);
+            scope.push(name.to_string());
+            free_vars(body, scope, free);
+        }
+        CoreTerm::Match(scrutinee, arms, _) => {
+            free_vars(scrutinee, scope, free);
+            for (pattern, body) in arms {
+                let mut binders = HashSet::new();
+                collect_pattern_binders(pattern, &mut binders);
+                scope.extend(binders);
+                free_vars(body, scope, free);
+                scope.truncate(depth);
+            }
+        }
+        _ => {
+            for child in children(term) {
+                free_vars(child, scope, free);
+            }
+        }
+    }
+    scope.truncate(depth);
+}
+
+// `term` with the free occurrences of `from` renamed to `to`, which is fresh
+fn rename_free(term: &CoreTerm, from: &str, to: &str) -> CoreTerm {
+    match term {
+        CoreTerm::Var(name, span) if name == from => CoreTerm::Var(to.into(), span.clone()),
+        CoreTerm::Lam(param, _, _) if param == from => term.clone(),
+        CoreTerm::Let(name, value, body, span) if name == from => {
+            CoreTerm::Let(*name, Rc::new(rename_free(value, from, to)), body.clone(), span.clone())
+        }
+        CoreTerm::Match(scrutinee, arms, span) => {
+            let arms = arms
+                .iter()
+                .map(|(pattern, body)| {
+                    let mut binders = HashSet::new();
+                    collect_pattern_binders(pattern, &mut binders);
+                    let body = if binders.contains(from) { body.clone() } else { rename_free(body, from, to) };
+                    (pattern.clone(), body)
+                })
+                .collect();
+            CoreTerm::Match(Rc::new(rename_free(scrutinee, from, to)), arms, span.clone())
+        }
+        _ => {
+            let new = children(term).into_iter().map(|child| rename_free(child, from, to)).collect();
+            with_children(term, new)
+        }
+    }
+}
+
+// ============================================================================
+// Partial evaluation
+// ============================================================================
+
+struct Folder<'a> {
+    registry: &'a Registry,
+    bound: &'a HashSet<String>,
+}
+
+impl Folder<'_> {
+    fn fold(&self, term: &CoreTerm, stats: &mut OptimizeStats) -> CoreTerm {
+        match term {
+            CoreTerm::IntLit(..)
+            | CoreTerm::BoolLit(..)
+            | CoreTerm::UnitLit(..)
+            | CoreTerm::StrLit(..)
+            | CoreTerm::Var(..) => term.clone(),
+            CoreTerm::Lam(param, body, span) => {
+                CoreTerm::Lam(*param, Rc::new(self.fold(body, stats)), span.clone())
+            }
+            CoreTerm::App(func, arg, span) => {
+                let folded = CoreTerm::App(
+                    Rc::new(self.fold(func, stats)),
+                    Rc::new(self.fold(arg, stats)),
+                    span.clone(),
+                );
+                // Arguments are folded first, so nested calls collapse bottom-up
+                match self.try_fold_call(&folded) {
+                    Some(result) => {
+                        stats.folded_calls += 1;
+                        result
+                    }
+                    None => folded,
+                }
+            }
+            CoreTerm::Tuple(elems, span) => {
+                CoreTerm::Tuple(elems.iter().map(|e| self.fold(e, stats)).collect(), span.clone())
+            }
+            CoreTerm::Proj(expr, index, span) => {
+                CoreTerm::Proj(Rc::new(self.fold(expr, stats)), *index, span.clone())
+            }
+            CoreTerm::CtorField(expr, index, span) => {
+                CoreTerm::CtorField(Rc::new(self.fold(expr, stats)), *index, span.clone())
+            }
+            CoreTerm::Let(name, value, body, span) => CoreTerm::Let(
+                *name,
+                Rc::new(self.fold(value, stats)),
+                Rc::new(self.fold(body, stats)),
+                span.clone(),
+            ),
+            CoreTerm::If(cond, then_branch, else_branch, span) => CoreTerm::If(
+                Rc::new(self.fold(cond, stats)),
+                Rc::new(self.fold(then_branch, stats)),
+                Rc::new(self.fold(else_branch, stats)),
+                span.clone(),
+            ),
+            CoreTerm::Match(scrutinee, arms, span) => CoreTerm::Match(
+                Rc::new(self.fold(scrutinee, stats)),
+                arms.iter().map(|(pattern, body)| (pattern.clone(), self.fold(body, stats))).collect(),
+                span.clone(),
+            ),
+            CoreTerm::Ctor(name, fields, span) => {
+                CoreTerm::Ctor(name.clone(), fields.iter().map(|f| self.fold(f, stats)).collect(), span.clone())
+            }
+        }
+    }
+
+    // `call` is App(...App(Var f, a1)..., an); its span goes to the result
+    fn try_fold_call(&self, call: &CoreTerm) -> Option<CoreTerm> {
+        let CoreTerm::App(_, _, span) = call else {
+            return None;
+        };
+        let mut args = Vec::new();
+        let mut head = call;
+        while let CoreTerm::App(func, arg, _) = head {
+            args.push(arg.as_ref());
+            head = func.as_ref();
+        }
+        args.reverse();
+        let CoreTerm::Var(name, _) = head else {
+            return None;
+        };
+        if !is_pure_primitive(self.registry, self.bound, name) {
+            return None;
+        }
+        let literals: Option<Vec<Literal>> = args.iter().map(|arg| Literal::from_term(arg)).collect();
+        let result = eval_primitive(name, &literals?)?;
+        Some(result.into_term(span.clone()))
+    }
+}
+
+// Every name a Let, Lam or pattern binds (`bound`) and every variable
+// reference (`referenced`), anywhere in the term
+fn collect_names(term: &CoreTerm, bound: &mut HashSet<String>, referenced: &mut HashSet<String>) {
+    match term {
+        CoreTerm::Var(name, _) => {
+            referenced.insert(name.to_string());
+        }
+        CoreTerm::Lam(name, _, _) | CoreTerm::Let(name, _, _, _) => {
+            bound.insert(name.to_string());
+        }
+        CoreTerm::Match(_, arms, _) => {
+            for (pattern, _) in arms {
+                collect_pattern_binders(pattern, bound);
+            }
+        }
+        _ => {}
+    }
+    for child in children(term) {
+        collect_names(child, bound, referenced);
+    }
+}
+
+fn collect_pattern_binders(pattern: &Pattern, bound: &mut HashSet<String>) {
+    match pattern {
+        Pattern::PVar(name) => {
+            bound.insert(name.clone());
+        }
+        Pattern::PTuple(items) | Pattern::PEnum(_, items) => {
+            for item in items {
+                collect_pattern_binders(item, bound);
+            }
+        }
+        Pattern::PBind(name, inner) => {
+            bound.insert(name.clone());
+            collect_pattern_binders(inner, bound);
+        }
+        Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit => {}
+    }
+}
+
+// ============================================================================
+// Primitive evaluation
+// ============================================================================
+
+#[derive(Clone, Debug, PartialEq)]
+enum Literal {
+    Int(i64),
+    Bool(bool),
+    Unit,
+    Str(String),
+}
+
+impl Literal {
+    fn from_term(term: &CoreTerm) -> Option<Literal> {
+        match term {
+            CoreTerm::IntLit(n, _) => Some(Literal::Int(*n)),
+            CoreTerm::BoolLit(b, _) => Some(Literal::Bool(*b)),
+            CoreTerm::UnitLit(_) => Some(Literal::Unit),
+            CoreTerm::StrLit(s, _) => Some(Literal::Str(s.clone())),
+            _ => None,
+        }
+    }
+
+    fn into_term(self, span: Option<Span>) -> CoreTerm {
+        match self {
+            Literal::Int(n) => CoreTerm::IntLit(n, span),
+            Literal::Bool(b) => CoreTerm::BoolLit(b, span),
+            Literal::Unit => CoreTerm::UnitLit(span),
+            Literal::Str(s) => CoreTerm::StrLit(s, span),
+        }
+    }
+}
+
+// Compile-time implementation of the foldable primitives, under the names
+// the bridge maps them from (emit_rust.rs get_foreign_symbol_mapping).
+// None: not foldable (unknown name, wrong arity or argument types, or a
+// call the runtime reports as an error).
+fn eval_primitive(name: &str, args: &[Literal]) -> Option<Literal> {
+    use Literal::*;
+    let result = match (name, args) {
+        ("__add__" | "axis_int_add", [Int(x), Int(y)]) => Int(x.checked_add(*y)?),
+        ("__sub__", [Int(x), Int(y)]) => Int(x.checked_sub(*y)?),
+        ("__mul__", [Int(x), Int(y)]) => Int(x.checked_mul(*y)?),
+        ("__div__", [Int(x), Int(y)]) => Int(x.checked_div(*y)?),
+        ("__mod__", [Int(x), Int(y)]) => Int(x.checked_rem(*y)?),
+
+        // Structural equality; literals of different kinds are never equal
+        ("__eq__", [a, b]) => Bool(a == b),
+        ("__neq__", [a, b]) => Bool(a != b),
+        ("__lt__", [Int(x), Int(y)]) => Bool(x < y),
+        ("__lte__", [Int(x), Int(y)]) => Bool(x <= y),
+        ("__gt__", [Int(x), Int(y)]) => Bool(x > y),
+        ("__gte__", [Int(x), Int(y)]) => Bool(x >= y),
+
+        ("__and__", [Bool(a), Bool(b)]) => Bool(*a && *b),
+        ("__or__", [Bool(a), Bool(b)]) => Bool(*a || *b),
+        ("__not__", [Bool(a)]) => Bool(!*a),
+
+        ("str_len" | "axis_str_len", [Str(s)]) => Int(s.chars().count() as i64),
+        ("str_concat" | "axis_str_concat" | "__concat__", [Str(a), Str(b)]) => Str(format!("{}{}", a, b)),
+        ("int_to_str", [Int(n)]) => Str(n.to_string()),
+        ("str_to_int", [Str(s)]) => Int(s.parse().unwrap_or(0)),
+        _ => return None,
+    };
+    Some(result)
+}
+
+// ============================================================================
+// Common subexpression elimination
+// ============================================================================
+//
+// A region is the part of a function body that runs unconditionally once
+// the body is entered; lambda bodies, If branches and Match arms start
+// regions of their own. Within a region, a pure subterm occurring more than
+// once is hashed structurally, and equal occurrences are bound once by a
+// Let at their lowest common ancestor:
+//
+//   let w = (hi - lo) + 1 in ((hi - lo) + 1) * 2
+//     ==>  let __cse0 = (hi - lo) + 1 in let w = __cse0 in __cse0 * 2
+//
+// SHARING RULES:
+//   - Pure: variables, literals, tuples, constructors, projections and full
+//     calls of TOTAL_PRIMITIVES. Evaluating such a term earlier than before
+//     can neither fail nor have an effect, so the Let may sit above code
+//     that used to run first.
+//     NOTE: under `--int-overflow checked` (or saturate, for division) the
+//     arithmetic primitives can fail, as can comparing two channels, tasks
+//     or sequences. The shared term still runs on every path its first
+//     occurrence did, so the program fails all the same, but possibly
+//     before an effect that used to precede the failure.
+//   - Nothing is hoisted out of a branch or a lambda: no path does work it
+//     did not do before.
+//   - An occurrence below a Let that rebinds one of the term's variables
+//     means something else and is not shared.
+//   - The Let never lands in the function position of an App, where the
+//     emitters expect a call spine.
+//   - Code outside functions is left alone: the emitters read the top-level
+//     Let chain as the program's function table.
+//   - The largest shared term goes first, then the region is scanned again.
+
+// Primitives that return a value for any arguments, in both bridge runtimes
+// (the arithmetic ones under the default --int-overflow wrap)
+const TOTAL_PRIMITIVES: &[(&str, usize)] = &[
+    ("__add__", 2),
+    ("__sub__", 2),
+    ("__mul__", 2),
+    ("__eq__", 2),
+    ("__neq__", 2),
+    ("__lt__", 2),
+    ("__lte__", 2),
+    ("__gt__", 2),
+    ("__gte__", 2),
+    ("__and__", 2),
+    ("__or__", 2),
+    ("__not__", 1),
+    ("str_to_int", 1),
+];
+
+struct Sharer<'a> {
+    registry: &'a Registry,
+    bound: &'a HashSet<String>,
+    names: &'a mut NameSupply,
+}
+
+// Result of scanning one node
+struct NodeInfo {
+    hash: u64,
+    size: usize,
+    pure: bool,
+    // App spine headed by a variable: (head, argument count) while every
+    // argument so far is pure
+    spine: Option<(String, usize)>,
+}
+
+// Child indices from the region root, and the Lets above the node as
+// (depth of the Let, bound name)
+struct Occurrence {
+    path: Vec<usize>,
+    lets: Vec<(usize, String)>,
+}
+
+struct Candidate<'t> {
+    term: &'t CoreTerm,
+    size: usize,
+    occurrences: Vec<Occurrence>,
+}
+
+#[derive(Default)]
+struct Candidates<'t> {
+    // First-seen order, so the chosen term does not depend on hashing
+    list: Vec<Candidate<'t>>,
+    by_hash: HashMap<u64, Vec<usize>>,
+}
+
+impl<'t> Candidates<'t> {
+    fn add(&mut self, term: &'t CoreTerm, size: usize, hash: u64, occurrence: Occurrence) {
+        let slots = self.by_hash.entry(hash).or_default();
+        for &index in slots.iter() {
+            if same_term(self.list[index].term, term) {
+                self.list[index].occurrences.push(occurrence);
+                return;
+            }
+        }
+        slots.push(self.list.len());
+        self.list.push(Candidate { term, size, occurrences: vec![occurrence] });
+    }
+}
+
+impl Sharer<'_> {
+    // Rebuild `term` with every region below it optimized; `region_root`
+    // marks the start of a region inside a function
+    fn process(&mut self, term: &CoreTerm, region_root: bool, in_function: bool, stats: &mut OptimizeStats) -> CoreTerm {
+        let processed: Vec<CoreTerm> = children(term)
+            .into_iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let (child_root, child_in_function) = match term {
+                    CoreTerm::Lam(..) => (true, true),
+                    _ => (in_function && starts_region(term, index), in_function),
+                };
+                self.process(child, child_root, child_in_function, stats)
+            })
+            .collect();
+        let mut rebuilt = with_children(term, processed);
+        if region_root {
+            while let Some(shared) = self.share_once(&rebuilt) {
+                rebuilt = shared;
+                stats.shared_subterms += 1;
+            }
+        }
+        rebuilt
+    }
+
+    // Bind the largest repeated pure term of the region rooted at `root`
+    fn share_once(&mut self, root: &CoreTerm) -> Option<CoreTerm> {
+        let mut candidates = Candidates::default();
+        self.scan(root, &mut Vec::new(), &mut Vec::new(), &mut candidates);
+
+        let mut order: Vec<usize> = (0..candidates.list.len())
+            .filter(|&index| candidates.list[index].occurrences.len() > 1)
+            .collect();
+        order.sort_by_key(|&index| std::cmp::Reverse(candidates.list[index].size));
+
+        for index in order {
+            let candidate = &candidates.list[index];
+            let mut free = HashSet::new();
+            collect_names(candidate.term, &mut HashSet::new(), &mut free);
+
+            // Drop occurrences whose variables are rebound below the common
+            // ancestor, then place the Let above the rest
+            let lca = common_prefix(candidate.occurrences.iter().map(|o| o.path.as_slice()));
+            let valid: Vec<&Occurrence> = candidate
+                .occurrences
+                .iter()
+                .filter(|o| !o.lets.iter().any(|(depth, name)| *depth >= lca.len() && free.contains(name)))
+                .collect();
+            if valid.len() < 2 {
+                continue;
+            }
+            let mut lca = common_prefix(valid.iter().map(|o| o.path.as_slice()));
+            while lca.last() == Some(&0) && is_app(node_at(root, &lca[..lca.len() - 1])) {
+                lca.pop();
+            }
+
+            let name = Symbol::from(self.names.fresh("cse", None));
+            let value = candidate.term.clone();
+            let paths: Vec<&[usize]> = valid.iter().map(|o| &o.path[lca.len()..]).collect();
+            return Some(map_at(root, &lca, &mut |ancestor| {
+                let mut body = ancestor.clone();
+                for path in &paths {
+                    body = map_at(&body, path, &mut |occurrence| CoreTerm::Var(name, span_of(occurrence)));
+                }
+                CoreTerm::Let(name, Rc::new(value.clone()), Rc::new(body), span_of(&value))
+            }));
+        }
+        None
+    }
+
+    fn scan<'t>(
+        &self,
+        term: &'t CoreTerm,
+        path: &mut Vec<usize>,
+        lets: &mut Vec<(usize, String)>,
+        candidates: &mut Candidates<'t>,
+    ) -> NodeInfo {
+        let mut infos = Vec::new();
+        for (index, child) in children(term).into_iter().enumerate() {
+            if matches!(term, CoreTerm::Lam(..)) || starts_region(term, index) {
+                infos.push(NodeInfo { hash: 0, size: 1, pure: false, spine: None });
+                continue;
+            }
+            let binds = match term {
+                CoreTerm::Let(name, ..) if index == 1 => Some(name.to_string()),
+                _ => None,
+            };
+            if let Some(name) = &binds {
+                lets.push((path.len(), name.clone()));
+            }
+            path.push(index);
+            infos.push(self.scan(child, path, lets, candidates));
+            path.pop();
+            if binds.is_some() {
+                lets.pop();
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        std::mem::discriminant(term).hash(&mut hasher);
+        let mut spine = None;
+        let pure = match term {
+            CoreTerm::IntLit(n, _) => {
+                n.hash(&mut hasher);
+                true
+            }
+            CoreTerm::BoolLit(b, _) => {
+                b.hash(&mut hasher);
+                true
+            }
+            CoreTerm::UnitLit(_) => true,
+            CoreTerm::StrLit(s, _) => {
+                s.hash(&mut hasher);
+                true
+            }
+            CoreTerm::Var(name, _) => {
+                name.as_str().hash(&mut hasher);
+                spine = Some((name.to_string(), 0));
+                true
+            }
+            CoreTerm::App(..) => {
+                spine = match (&infos[0].spine, infos[1].pure) {
+                    (Some((head, count)), true) => Some((head.clone(), count + 1)),
+                    _ => None,
+                };
+                spine.as_ref().is_some_and(|(head, count)| self.is_total_call(head, *count))
+            }
+            CoreTerm::Tuple(..) | CoreTerm::Proj(..) | CoreTerm::CtorField(..) => infos.iter().all(|i| i.pure),
+            CoreTerm::Ctor(name, ..) => {
+                name.hash(&mut hasher);
+                infos.iter().all(|i| i.pure)
+            }
+            CoreTerm::Lam(..) | CoreTerm::Let(..) | CoreTerm::If(..) | CoreTerm::Match(..) => false,
+        };
+        if let CoreTerm::Proj(_, index, _) | CoreTerm::CtorField(_, index, _) = term {
+            index.hash(&mut hasher);
+        }
+        for info in &infos {
+            info.hash.hash(&mut hasher);
+        }
+        let hash = hasher.finish();
+        let size = 1 + infos.iter().map(|i| i.size).sum::<usize>();
+
+        if pure && size > 1 {
+            candidates.add(term, size, hash, Occurrence { path: path.clone(), lets: lets.clone() });
+        }
+        NodeInfo { hash, size, pure, spine }
+    }
+
+    fn is_total_call(&self, head: &str, args: usize) -> bool {
+        TOTAL_PRIMITIVES.iter().any(|(name, arity)| *name == head && *arity == args)
+            && is_pure_primitive(self.registry, self.bound, head)
+    }
+}
+
+// Children that run only sometimes: If branches and Match arm bodies
+// (lambda bodies are handled by the callers)
+fn starts_region(term: &CoreTerm, index: usize) -> bool {
+    matches!(term, CoreTerm::If(..) | CoreTerm::Match(..)) && index > 0
+}
+
+fn is_app(term: &CoreTerm) -> bool {
+    matches!(term, CoreTerm::App(..))
+}
+
+fn common_prefix<'p>(mut paths: impl Iterator<Item = &'p [usize]>) -> Vec<usize> {
+    let Some(first) = paths.next() else {
+        return Vec::new();
+    };
+    let mut prefix = first.to_vec();
+    for path in paths {
+        let shared = prefix.iter().zip(path).take_while(|(a, b)| a == b).count();
+        prefix.truncate(shared);
+    }
+    prefix
+}
+
+// Structural equality of pure terms, ignoring spans
+fn same_term(a: &CoreTerm, b: &CoreTerm) -> bool {
+    match (a, b) {
+        (CoreTerm::IntLit(x, _), CoreTerm::IntLit(y, _)) => x == y,
+        (CoreTerm::BoolLit(x, _), CoreTerm::BoolLit(y, _)) => x == y,
+        (CoreTerm::UnitLit(_), CoreTerm::UnitLit(_)) => true,
+        (CoreTerm::StrLit(x, _), CoreTerm::StrLit(y, _)) => x == y,
+        (CoreTerm::Var(x, _), CoreTerm::Var(y, _)) => x == y,
+        (CoreTerm::App(f1, a1, _), CoreTerm::App(f2, a2, _)) => same_term(f1, f2) && same_term(a1, a2),
+        (CoreTerm::Tuple(xs, _), CoreTerm::Tuple(ys, _)) => same_terms(xs, ys),
+        (CoreTerm::Ctor(n1, xs, _), CoreTerm::Ctor(n2, ys, _)) => n1 == n2 && same_terms(xs, ys),
+        (CoreTerm::Proj(x, i, _), CoreTerm::Proj(y, j, _))
+        | (CoreTerm::CtorField(x, i, _), CoreTerm::CtorField(y, j, _)) => i == j && same_term(x, y),
+        _ => false,
+    }
+}
+
+fn same_terms(xs: &[CoreTerm], ys: &[CoreTerm]) -> bool {
+    xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| same_term(x, y))
+}
+
+fn span_of(term: &CoreTerm) -> Option<Span> {
+    match term {
+        CoreTerm::IntLit(_, span)
+        | CoreTerm::BoolLit(_, span)
+        | CoreTerm::UnitLit(span)
+        | CoreTerm::StrLit(_, span)
+        | CoreTerm::Var(_, span)
+        | CoreTerm::Lam(_, _, span)
+        | CoreTerm::App(_, _, span)
+        | CoreTerm::Tuple(_, span)
+        | CoreTerm::Proj(_, _, span)
+        | CoreTerm::CtorField(_, _, span)
+        | CoreTerm::Let(_, _, _, span)
+        | CoreTerm::If(_, _, _, span)
+        | CoreTerm::Match(_, _, span)
+        | CoreTerm::Ctor(_, _, span) => span.clone(),
+    }
+}
+
+// ============================================================================
+// Term positions
+// ============================================================================
+//
+// Child i of a node: Lam body; App function, argument; Let value, body;
+// If condition, then, else; Match scrutinee, then each arm body; the
+// elements of a Tuple or Ctor; the operand of Proj and CtorField.
+
+fn children(term: &CoreTerm) -> Vec<&CoreTerm> {
+    match term {
+        CoreTerm::IntLit(..)
+        | CoreTerm::BoolLit(..)
+        | CoreTerm::UnitLit(..)
+        | CoreTerm::StrLit(..)
+        | CoreTerm::Var(..) => Vec::new(),
+        CoreTerm::Lam(_, body, _) => vec![body],
+        CoreTerm::App(func, arg, _) => vec![func, arg],
+        CoreTerm::Tuple(elems, _) | CoreTerm::Ctor(_, elems, _) => elems.iter().collect(),
+        CoreTerm::Proj(expr, _, _) | CoreTerm::CtorField(expr, _, _) => vec![expr],
+        CoreTerm::Let(_, value, body, _) => vec![value, body],
+        CoreTerm::If(cond, then_branch, else_branch, _) => vec![cond, then_branch, else_branch],
+        CoreTerm::Match(scrutinee, arms, _) => {
+            std::iter::once(scrutinee.as_ref()).chain(arms.iter().map(|(_, body)| body)).collect()
+        }
+    }
+}
+
+// `term` with its children replaced, in `children` order
+fn with_children(term: &CoreTerm, new: Vec<CoreTerm>) -> CoreTerm {
+    let mut new = new.into_iter();
+    let mut next = || new.next().unwrap();
+    match term {
+        CoreTerm::IntLit(..)
+        | CoreTerm::BoolLit(..)
+        | CoreTerm::UnitLit(..)
+        | CoreTerm::StrLit(..)
+        | CoreTerm::Var(..) => term.clone(),
+        CoreTerm::Lam(param, _, span) => CoreTerm::Lam(*param, Rc::new(next()), span.clone()),
+        CoreTerm::App(_, _, span) => CoreTerm::App(Rc::new(next()), Rc::new(next()), span.clone()),
+        CoreTerm::Tuple(_, span) => CoreTerm::Tuple(new.collect(), span.clone()),
+        CoreTerm::Ctor(name, _, span) => CoreTerm::Ctor(name.clone(), new.collect(), span.clone()),
+        CoreTerm::Proj(_, index, span) => CoreTerm::Proj(Rc::new(next()), *index, span.clone()),
+        CoreTerm::CtorField(_, index, span) => CoreTerm::CtorField(Rc::new(next()), *index, span.clone()),
+        CoreTerm::Let(name, _, _, span) => CoreTerm::Let(*name, Rc::new(next()), Rc::new(next()), span.clone()),
+        CoreTerm::If(_, _, _, span) => CoreTerm::If(Rc::new(next()), Rc::new(next()), Rc::new(next()), span.clone()),
+        CoreTerm::Match(_, arms, span) => {
+            let scrutinee = Rc::new(next());
+            let arms = arms.iter().map(|(pattern, _)| (pattern.clone(), next())).collect();
+            CoreTerm::Match(scrutinee, arms, span.clone())
+        }
+    }
+}
+
+fn node_at<'t>(term: &'t CoreTerm, path: &[usize]) -> &'t CoreTerm {
+    path.iter().fold(term, |node, &index| children(node)[index])
+}
+
+// `term` with the node at `path` replaced by `f(node)`
+fn map_at(term: &CoreTerm, path: &[usize], f: &mut dyn FnMut(&CoreTerm) -> CoreTerm) -> CoreTerm {
+    let Some((&index, rest)) = path.split_first() else {
+        return f(term);
+    };
+    let new: Vec<CoreTerm> = children(term)
+        .into_iter()
+        .enumerate()
+        .map(|(i, child)| if i == index { map_at(child, rest, f) } else { child.clone() })
+        .collect();
+    with_children(term, new)
+}
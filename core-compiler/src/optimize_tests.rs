@@ -0,0 +1,387 @@
+// Differential tests for the optimizer (optimize.rs): every program is run
+// through the interpreter (runtime.rs) before and after optimization and
+// must produce the same value.
+
+use std::rc::Rc;
+use crate::optimize::{optimize, OptimizeOptions, OptimizeStats};
+use crate::registry_loader::{Registry, RegistryEntry};
+use crate::runtime::{self, CoreTerm, Value};
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn int(n: i64) -> CoreTerm {
+    CoreTerm::IntLit(n, None)
+}
+
+fn str_lit(s: &str) -> CoreTerm {
+    CoreTerm::StrLit(s.to_string(), None)
+}
+
+// Curried application f(a)(b)..., as lowered from f(a, b)
+fn call(f: &str, args: Vec<CoreTerm>) -> CoreTerm {
+    args.into_iter().fold(var(f), |func, arg| CoreTerm::App(Rc::new(func), Rc::new(arg), None))
+}
+
+fn lam(param: &str, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Lam(param.into(), Rc::new(body), None)
+}
+
+fn let_(name: &str, value: CoreTerm, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Let(name.into(), Rc::new(value), Rc::new(body), None)
+}
+
+fn registry(entries: &[(&str, u32, bool)]) -> Registry {
+    let mut registry = Registry::new();
+    for (name, arity, deterministic) in entries {
+        registry.entries.insert(name.to_string(), RegistryEntry {
+            name: name.to_string(),
+            arity: *arity,
+            deterministic: *deterministic,
+            profiles: vec!["core".to_string()],
+            ..Default::default()
+        });
+    }
+    registry
+}
+
+fn interpret(term: &CoreTerm) -> Result<Value, String> {
+    runtime::eval(term, &runtime::primitive_env()).map_err(|handle| crate::get_string(-handle))
+}
+
+// Run `term` before and after optimization; returns the optimized term
+fn assert_same_result(term: &CoreTerm, registry: &Registry) -> (CoreTerm, OptimizeStats) {
+    assert_same_result_with(term, registry, &OptimizeOptions::default())
+}
+
+fn assert_same_result_with(term: &CoreTerm, registry: &Registry, options: &OptimizeOptions) -> (CoreTerm, OptimizeStats) {
+    crate::init_string_table();
+    let (optimized, stats) = optimize(term, registry, options);
+    let before = interpret(term);
+    let after = interpret(&optimized);
+    match (&before, &after) {
+        (Ok(a), Ok(b)) => assert!(runtime::values_equal(a, b), "{:?} became {:?}", a, b),
+        (Err(a), Err(b)) => assert_eq!(a, b),
+        _ => panic!("{:?} became {:?}", before, after),
+    }
+    (optimized, stats)
+}
+
+fn is_literal(term: &CoreTerm) -> bool {
+    matches!(term, CoreTerm::IntLit(..) | CoreTerm::BoolLit(..) | CoreTerm::UnitLit(..) | CoreTerm::StrLit(..))
+}
+
+#[test]
+fn test_fold_matches_interpreter() {
+    let reg = registry(&[("axis_str_len", 1, true), ("axis_str_concat", 2, true)]);
+    let cases = vec![
+        // (1 + 2) * (10 - 4) / 4 % 5
+        call("__mod__", vec![
+            call("__div__", vec![
+                call("__mul__", vec![call("__add__", vec![int(1), int(2)]), call("__sub__", vec![int(10), int(4)])]),
+                int(4),
+            ]),
+            int(5),
+        ]),
+        call("__mod__", vec![int(-7), int(3)]),
+        call("__lt__", vec![int(-1), int(0)]),
+        call("__gte__", vec![int(3), int(4)]),
+        call("__eq__", vec![str_lit("abc"), call("str_concat", vec![str_lit("ab"), str_lit("c")])]),
+        call("__eq__", vec![int(1), CoreTerm::BoolLit(true, None)]),
+        call("__eq__", vec![CoreTerm::UnitLit(None), CoreTerm::UnitLit(None)]),
+        call("__and__", vec![CoreTerm::BoolLit(true, None), call("__not__", vec![CoreTerm::BoolLit(false, None)])]),
+        call("__or__", vec![CoreTerm::BoolLit(false, None), CoreTerm::BoolLit(false, None)]),
+        call("str_len", vec![str_lit("abc")]),
+        call("axis_str_len", vec![str_lit("héllo")]),
+        call("axis_str_concat", vec![str_lit("a"), call("__concat__", vec![str_lit("b"), str_lit("c")])]),
+        call("int_to_str", vec![call("__sub__", vec![int(0), int(42)])]),
+        call("str_to_int", vec![call("int_to_str", vec![int(1234)])]),
+        call("str_to_int", vec![str_lit("12x")]),
+    ];
+    for case in &cases {
+        let (optimized, stats) = assert_same_result(case, &reg);
+        assert!(is_literal(&optimized), "not fully folded: {:?}", optimized);
+        assert!(stats.folded_calls > 0);
+    }
+}
+
+#[test]
+fn test_fold_inside_functions() {
+    // let scale = \x. x * (60 * 60) in if 3 > 2 then scale(2) else 0
+    let program = let_(
+        "scale",
+        lam("x", call("__mul__", vec![var("x"), call("__mul__", vec![int(60), int(60)])])),
+        CoreTerm::If(
+            Rc::new(call("__gt__", vec![int(3), int(2)])),
+            Rc::new(call("scale", vec![int(2)])),
+            Rc::new(int(0)),
+            None,
+        ),
+    );
+    let (optimized, stats) = assert_same_result(&program, &Registry::new());
+    assert_eq!(stats.folded_calls, 2);
+    let CoreTerm::Let(_, scale, body, _) = &optimized else {
+        panic!("{:?}", optimized);
+    };
+    // Only the literal half of x * (60 * 60) folds
+    assert!(matches!(scale.as_ref(), CoreTerm::Lam(_, body, _) if matches!(body.as_ref(), CoreTerm::App(..))));
+    assert!(matches!(body.as_ref(), CoreTerm::If(cond, ..) if matches!(cond.as_ref(), CoreTerm::BoolLit(true, _))));
+}
+
+#[test]
+fn test_fold_leaves_unsafe_calls() {
+    let reg = registry(&[("axis_str_len", 1, false)]);
+
+    // Overflow and division by zero depend on the bridge's --int-overflow
+    // mode, so they must still happen at run time
+    for overflow in [
+        call("__div__", vec![int(i64::MIN), int(-1)]),
+        call("__add__", vec![int(i64::MAX), int(1)]),
+        call("__mul__", vec![int(i64::MIN), int(-1)]),
+        call("__div__", vec![int(7), int(0)]),
+        call("__mod__", vec![int(7), int(0)]),
+    ] {
+        let (optimized, stats) = assert_same_result(&overflow, &reg);
+        assert_eq!(stats.folded_calls, 0);
+        assert!(!is_literal(&optimized));
+    }
+
+    // Ill-typed arguments are left for the runtime to report
+    let (_, stats) = assert_same_result(&call("str_len", vec![int(5)]), &reg);
+    assert_eq!(stats.folded_calls, 0);
+
+    // Registry says nondeterministic
+    let (_, stats) = assert_same_result(&call("axis_str_len", vec![str_lit("abc")]), &reg);
+    assert_eq!(stats.folded_calls, 0);
+
+    // Partial application
+    let partial = let_("inc", call("__add__", vec![int(1)]), call("inc", vec![int(2)]));
+    let (_, stats) = assert_same_result(&partial, &reg);
+    assert_eq!(stats.folded_calls, 0);
+
+    // A user definition shadows the primitive everywhere
+    let shadowed = let_("str_len", lam("s", int(99)), call("str_len", vec![str_lit("abc")]));
+    let (optimized, stats) = assert_same_result(&shadowed, &reg);
+    assert_eq!(stats.folded_calls, 0);
+    assert!(matches!(interpret(&optimized), Ok(Value::Int(99))));
+}
+
+fn if_(cond: CoreTerm, then_branch: CoreTerm, else_branch: CoreTerm) -> CoreTerm {
+    CoreTerm::If(Rc::new(cond), Rc::new(then_branch), Rc::new(else_branch), None)
+}
+
+// Body of the first top-level function, below its parameters
+fn function_body(program: &CoreTerm) -> &CoreTerm {
+    let CoreTerm::Let(_, function, _, _) = program else {
+        panic!("{:?}", program);
+    };
+    let mut body = function.as_ref();
+    while let CoreTerm::Lam(_, inner, _) = body {
+        body = inner;
+    }
+    body
+}
+
+#[test]
+fn test_share_matches_interpreter() {
+    // span(lo, hi) = let width = hi - lo + 1 in let twice = (hi - lo + 1) * 2 in twice - width
+    let width = || call("__add__", vec![call("__sub__", vec![var("hi"), var("lo")]), int(1)]);
+    let span = lam("lo", lam("hi", let_(
+        "width",
+        width(),
+        let_(
+            "twice",
+            call("__mul__", vec![width(), int(2)]),
+            call("__sub__", vec![var("twice"), var("width")]),
+        ),
+    )));
+    let program = let_("span", span, call("span", vec![int(3), int(10)]));
+
+    let (optimized, stats) = assert_same_result(&program, &Registry::new());
+    assert_eq!(stats.shared_subterms, 1);
+    // Bound once above both uses; the inner hi - lo now occurs once
+    let CoreTerm::Let(name, value, body, _) = function_body(&optimized) else {
+        panic!("{:?}", optimized);
+    };
+    assert_eq!(name, "__cse0");
+    assert!(matches!(value.as_ref(), CoreTerm::App(..)));
+    assert!(matches!(body.as_ref(), CoreTerm::Let(n, v, ..) if n == "width" && matches!(v.as_ref(), CoreTerm::Var(x, _) if x == "__cse0")));
+}
+
+#[test]
+fn test_share_keeps_semantics_boundaries() {
+    let square = || call("__mul__", vec![var("n"), var("n")]);
+    let plus_one = || call("__add__", vec![var("x"), int(1)]);
+    let cases = vec![
+        // Branches run only sometimes: n * n is not hoisted out of them
+        lam("n", if_(
+            call("__gt__", vec![square(), int(50)]),
+            call("__add__", vec![square(), int(1)]),
+            call("__sub__", vec![square(), int(1)]),
+        )),
+        // x + 1 below `let x = 5` is a different value
+        lam("x", let_("a", plus_one(), let_("x", int(5), call("__add__", vec![var("a"), plus_one()])))),
+        // str_len fails on non-strings, so it is never moved
+        lam("x", call("__add__", vec![call("str_len", vec![var("x")]), call("str_len", vec![var("x")])])),
+    ];
+    for function in cases {
+        let program = let_("f", function, call("f", vec![int(8)]));
+        let (_, stats) = assert_same_result(&program, &Registry::new());
+        assert_eq!(stats.shared_subterms, 0);
+    }
+
+    // Code outside functions is left alone
+    let (_, stats) = assert_same_result(&call("__add__", vec![plus_one(), plus_one()]), &Registry::new());
+    assert_eq!(stats.shared_subterms, 0);
+}
+
+#[test]
+fn test_share_never_splits_a_call_spine() {
+    // f(x + 1, x + 1, x): the common ancestor of the two arguments is the
+    // partial application f(x + 1)(x + 1); the Let must go above the call
+    let f = lam("a", lam("b", lam("c", call("__add__", vec![call("__add__", vec![var("a"), var("b")]), var("c")]))));
+    let plus_one = || call("__add__", vec![var("x"), int(1)]);
+    let g = lam("x", call("f", vec![plus_one(), plus_one(), var("x")]));
+    let program = let_("g", g, let_("f", f, call("g", vec![int(4)])));
+
+    let (optimized, stats) = assert_same_result_with(&program, &Registry::new(), &no_inlining());
+    assert_eq!(stats.shared_subterms, 1);
+    let CoreTerm::Let(_, _, body, _) = function_body(&optimized) else {
+        panic!("{:?}", optimized);
+    };
+    let mut head = body.as_ref();
+    while let CoreTerm::App(func, _, _) = head {
+        head = func;
+    }
+    assert!(matches!(head, CoreTerm::Var(name, _) if name == "f"), "{:?}", body);
+}
+
+fn no_inlining() -> OptimizeOptions {
+    OptimizeOptions { inline_threshold: 0, ..Default::default() }
+}
+
+fn lams(params: &[&str], body: CoreTerm) -> CoreTerm {
+    params.iter().rev().fold(body, |body, param| lam(param, body))
+}
+
+// Top-level definitions in order, then `main(0)`
+fn program(definitions: Vec<(&str, CoreTerm)>) -> CoreTerm {
+    definitions
+        .into_iter()
+        .rev()
+        .fold(call("main", vec![int(0)]), |rest, (name, value)| let_(name, value, rest))
+}
+
+// Body of the top-level function `name`, below its parameters
+fn body_of<'t>(program: &'t CoreTerm, name: &str) -> &'t CoreTerm {
+    let mut current = program;
+    while let CoreTerm::Let(defined, value, rest, _) = current {
+        if defined == name {
+            let mut body = value.as_ref();
+            while let CoreTerm::Lam(_, inner, _) = body {
+                body = inner;
+            }
+            return body;
+        }
+        current = rest;
+    }
+    panic!("no function {} in {:?}", name, program);
+}
+
+fn calls(term: &CoreTerm, name: &str) -> bool {
+    match term {
+        CoreTerm::Var(var, _) => var == name,
+        CoreTerm::App(func, arg, _) => calls(func, name) || calls(arg, name),
+        CoreTerm::Lam(_, body, _) => calls(body, name),
+        CoreTerm::Let(_, value, body, _) => calls(value, name) || calls(body, name),
+        CoreTerm::If(c, t, e, _) => calls(c, name) || calls(t, name) || calls(e, name),
+        CoreTerm::Match(scrutinee, arms, _) => calls(scrutinee, name) || arms.iter().any(|(_, body)| calls(body, name)),
+        CoreTerm::Tuple(elems, _) | CoreTerm::Ctor(_, elems, _) => elems.iter().any(|e| calls(e, name)),
+        CoreTerm::Proj(expr, _, _) | CoreTerm::CtorField(expr, _, _) => calls(expr, name),
+        _ => false,
+    }
+}
+
+#[test]
+fn test_inline_small_functions() {
+    // sq(x) = x * x; sum_sq(a, b) = sq(a) + sq(b); main(n) = sum_sq(n + 3, 4)
+    let prog = program(vec![
+        ("sq", lams(&["x"], call("__mul__", vec![var("x"), var("x")]))),
+        ("sum_sq", lams(&["a", "b"], call("__add__", vec![call("sq", vec![var("a")]), call("sq", vec![var("b")])]))),
+        ("main", lams(&["n"], call("sum_sq", vec![call("__add__", vec![var("n"), int(3)]), int(4)]))),
+    ]);
+    let (optimized, stats) = assert_same_result(&prog, &Registry::new());
+    assert_eq!(stats.inlined_calls, 3);
+    assert!(!calls(body_of(&optimized, "main"), "sum_sq"));
+    assert!(!calls(body_of(&optimized, "main"), "sq"));
+    // Definitions stay for other callers
+    assert!(calls(body_of(&optimized, "sum_sq"), "__add__"));
+    assert_eq!(stats.inline_decisions, vec![
+        "inlined sq (5 nodes) at 2 call site(s)",
+        "inlined sum_sq (17 nodes) at 1 call site(s)",
+    ]);
+
+    // A failing argument still fails: twice(str_len(0))
+    let prog = program(vec![
+        ("twice", lams(&["x"], call("__add__", vec![var("x"), var("x")]))),
+        ("main", lams(&["n"], call("twice", vec![call("str_len", vec![var("n")])]))),
+    ]);
+    let (_, stats) = assert_same_result(&prog, &Registry::new());
+    assert_eq!(stats.inlined_calls, 1);
+}
+
+#[test]
+fn test_inline_refusals() {
+    // Recursive: countdown(n) = if n > 0 then countdown(n - 1) else 0
+    let countdown = lams(&["n"], if_(
+        call("__gt__", vec![var("n"), int(0)]),
+        call("countdown", vec![call("__sub__", vec![var("n"), int(1)])]),
+        int(0),
+    ));
+    // Larger than the threshold: nested sums of x
+    let big = lams(&["x"], (0..10).fold(var("x"), |acc, i| call("__add__", vec![acc, int(i)])));
+    let prog = program(vec![
+        ("countdown", countdown.clone()),
+        ("big", big.clone()),
+        ("main", lams(&["n"], call("__add__", vec![call("countdown", vec![int(5)]), call("big", vec![var("n")])]))),
+    ]);
+    let (_, stats) = assert_same_result(&prog, &Registry::new());
+    assert_eq!(stats.inlined_calls, 0);
+    assert_eq!(stats.inline_decisions, vec![
+        "not inlined countdown: recursive",
+        "not inlined big: 41 nodes > threshold 24",
+    ]);
+
+    // @inline lifts the threshold, but never for a recursive function
+    let options = OptimizeOptions {
+        inline_hints: ["big", "countdown"].iter().map(|s| s.to_string()).collect(),
+        ..Default::default()
+    };
+    let (optimized, stats) = assert_same_result_with(&prog, &Registry::new(), &options);
+    assert_eq!(stats.inlined_calls, 1);
+    assert!(!calls(body_of(&optimized, "main"), "big"));
+    assert!(calls(body_of(&optimized, "main"), "countdown"));
+}
+
+#[test]
+fn test_inline_respects_scopes() {
+    // get() = limit reads the top-level `limit`; main binds its own `limit`
+    let prog = program(vec![
+        ("limit", int(10)),
+        ("get", lams(&["_unit"], var("limit"))),
+        ("main", lams(&["n"], let_(
+            "limit",
+            int(99),
+            call("__add__", vec![call("get", vec![CoreTerm::UnitLit(None)]), var("limit")]),
+        ))),
+        ("other", lams(&["get"], call("get", vec![CoreTerm::UnitLit(None)]))),
+    ]);
+    let (optimized, stats) = assert_same_result(&prog, &Registry::new());
+    assert_eq!(stats.inlined_calls, 0);
+    assert!(calls(body_of(&optimized, "main"), "get"));
+    // In `other`, get(()) calls the parameter: not a call site of get at all
+    assert_eq!(stats.inline_decisions, vec![
+        "not inlined get at 1 call site(s): a local of the caller shadows a name it uses",
+    ]);
+}
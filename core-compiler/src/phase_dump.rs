@@ -0,0 +1,182 @@
+// `--dump-phase PHASE[=FILE]`: write the intermediate representation after
+// a pipeline phase, to bisect miscompilations between the parser, lowering,
+// Core conversion and validation.
+//
+//   tokens      surface_parser tokenizer   LINE:COL "TEXT" per token
+//   ast         surface_parser             ast_dump text tree
+//   core-value  surface_lower              indented Value tree
+//   core-term   surface_to_core            canonical Core text (core_text)
+//   validated   core_validator             Core text headed by the manifest
+//                                          (after the optimizer with --optimize)
+//
+// Dumps are written as each phase completes, so the phases before a
+// failing one are still available. FILE defaults to
+// <first source stem>.<phase>.dump; `-` writes to stdout.
+
+use std::fs;
+use crate::core_interface::BundleInterface;
+use crate::runtime::{CoreTerm, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    Tokens,
+    Ast,
+    CoreValue,
+    CoreTerm,
+    Validated,
+}
+
+const PHASES: [(&str, Phase); 5] = [
+    ("tokens", Phase::Tokens),
+    ("ast", Phase::Ast),
+    ("core-value", Phase::CoreValue),
+    ("core-term", Phase::CoreTerm),
+    ("validated", Phase::Validated),
+];
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        PHASES.iter().find(|(_, p)| *p == self).map(|(name, _)| *name).unwrap()
+    }
+
+    // Phases that only exist when compiling surface sources
+    pub fn is_surface(self) -> bool {
+        matches!(self, Phase::Tokens | Phase::Ast | Phase::CoreValue)
+    }
+}
+
+pub struct DumpRequest {
+    pub phase: Phase,
+    pub path: String,
+}
+
+// Parse `PHASE` or `PHASE=FILE`
+pub fn parse_request(arg: &str, source_stem: &str) -> Result<DumpRequest, String> {
+    let (name, path) = match arg.split_once('=') {
+        Some((name, path)) if !path.is_empty() => (name, Some(path)),
+        Some((name, _)) => return Err(format!("--dump-phase {}=: missing file name", name)),
+        None => (arg, None),
+    };
+    let Some((_, phase)) = PHASES.iter().find(|(n, _)| *n == name) else {
+        let known: Vec<&str> = PHASES.iter().map(|(n, _)| *n).collect();
+        return Err(format!("unknown phase '{}' (expected one of: {})", name, known.join(", ")));
+    };
+    let path = path
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| format!("{}.{}.dump", source_stem, name));
+    Ok(DumpRequest { phase: *phase, path })
+}
+
+// Write the dump for `phase` if requested; `render` runs only when needed
+pub fn dump(requests: &[DumpRequest], phase: Phase, render: impl FnOnce() -> String) -> Result<(), String> {
+    let targets: Vec<&DumpRequest> = requests.iter().filter(|r| r.phase == phase).collect();
+    if targets.is_empty() {
+        return Ok(());
+    }
+    let content = render();
+    for request in targets {
+        if request.path == "-" {
+            print!("{}", content);
+        } else {
+            fs::write(&request.path, &content)
+                .map_err(|e| format!("Failed to write {} dump {}: {}", phase.name(), request.path, e))?;
+            eprintln!("Wrote {} dump -> {}", phase.name(), request.path);
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// core-value
+// ============================================================================
+
+pub fn format_value(value: &Value) -> String {
+    let mut out = String::new();
+    value_lines(value, 0, &mut out);
+    out
+}
+
+fn line(out: &mut String, indent: usize, text: &str) {
+    out.push_str(&"  ".repeat(indent));
+    out.push_str(text);
+    out.push('\n');
+}
+
+fn value_lines(value: &Value, indent: usize, out: &mut String) {
+    match value {
+        Value::Int(n) => line(out, indent, &format!("int {}", n)),
+        Value::Bool(b) => line(out, indent, &format!("bool {}", b)),
+        Value::Str(handle) => line(out, indent, &format!("str {:?}", crate::get_string(*handle))),
+        Value::Bytes(data) => line(out, indent, &format!("bytes {:?}", data)),
+        Value::Unit => line(out, indent, "unit"),
+        Value::Var(name) => line(out, indent, &format!("var {}", name)),
+        Value::Lam(param, body) => {
+            line(out, indent, &format!("lam {}", param));
+            value_lines(body, indent + 1, out);
+        }
+        Value::App(func, arg) => {
+            line(out, indent, "app");
+            value_lines(func, indent + 1, out);
+            value_lines(arg, indent + 1, out);
+        }
+        Value::Let(name, bound, body) => {
+            line(out, indent, &format!("let {}", name));
+            value_lines(bound, indent + 1, out);
+            value_lines(body, indent, out);
+        }
+        Value::If(cond, then_val, else_val) => {
+            line(out, indent, "if");
+            value_lines(cond, indent + 1, out);
+            line(out, indent, "then");
+            value_lines(then_val, indent + 1, out);
+            line(out, indent, "else");
+            value_lines(else_val, indent + 1, out);
+        }
+        Value::Match(scrutinee, arms) => {
+            line(out, indent, "match");
+            value_lines(scrutinee, indent + 1, out);
+            for (pattern, body) in arms {
+                line(out, indent + 1, &format!("arm {}", pattern));
+                value_lines(body, indent + 2, out);
+            }
+        }
+        Value::Tuple(elems) => {
+            line(out, indent, "tuple");
+            for elem in elems {
+                value_lines(elem, indent + 1, out);
+            }
+        }
+        Value::Enum(tag, fields) => {
+            line(out, indent, &format!("enum {}", tag));
+            for field in fields {
+                value_lines(field, indent + 1, out);
+            }
+        }
+        // Runtime-only values never come out of lowering
+        Value::Closure(..) => line(out, indent, "<closure>"),
+        Value::NativeFn(..) => line(out, indent, "<native fn>"),
+    }
+}
+
+// ============================================================================
+// validated
+// ============================================================================
+
+pub fn format_validated(term: &CoreTerm, interface: &BundleInterface) -> String {
+    let exports: Vec<String> = interface
+        .exports
+        .iter()
+        .map(|e| format!("{}/{}{}", e.name, e.arity, if e.public { "" } else { " (private)" }))
+        .collect();
+    let imports: Vec<String> = interface
+        .imports
+        .iter()
+        .map(|i| if i.foreign { format!("{} (foreign)", i.name) } else { i.name.clone() })
+        .collect();
+
+    let mut out = String::new();
+    out.push_str(&format!("; exports: {}\n", if exports.is_empty() { "-".to_string() } else { exports.join(", ") }));
+    out.push_str(&format!("; imports: {}\n", if imports.is_empty() { "-".to_string() } else { imports.join(", ") }));
+    out.push_str(&crate::core_text::print_core_text(term));
+    out
+}
@@ -0,0 +1,160 @@
+// Standard prelude (prelude.ax): Axis utility functions embedded in the
+// compiler and available to every program unless --no-prelude is given
+//
+// The prelude is parsed on its own and its functions are added to the
+// program's module before lowering; only those the program reaches
+// (directly or through other prelude functions) are added, so a program
+// that uses none compiles to the same Core as before. A program function
+// with a prelude function's name replaces it (AX1502).
+//
+// A separately compiled unit (--separate) leaves prelude calls as imports;
+// `link` then adds the prelude functions no linked bundle defines
+// (core_unit).
+//
+// POLICY: prelude.ax starts with `// axis-prelude N`, N = PRELUDE_VERSION.
+// A change that alters what an existing function does bumps the version.
+
+use crate::core_validator::ValidationError;
+use crate::error_codes::ErrorCode;
+use crate::runtime::CoreTerm;
+use crate::surface_parser::{self, Attribute, FnDef, Module, SurfaceExpr, SurfaceStmt};
+use crate::{surface_lower, surface_to_core};
+use std::collections::HashSet;
+
+pub const PRELUDE_SOURCE: &str = include_str!("prelude.ax");
+pub const PRELUDE_VERSION: u32 = 2;
+// File name of prelude spans
+pub const PRELUDE_FILE: &str = "<prelude>";
+
+/// The parsed prelude. The free functions below parse it on each call;
+/// compiles keep one in their session (session.rs).
+pub struct Prelude {
+    functions: Vec<FnDef>,
+}
+
+impl Prelude {
+    /// Parse the embedded prelude, marking each function with a `prelude`
+    /// attribute
+    pub fn parse() -> Self {
+        let module = surface_parser::parse_module_with_file(PRELUDE_SOURCE, PRELUDE_FILE)
+            .expect("the embedded prelude parses");
+        let functions = module
+            .functions
+            .into_iter()
+            .map(|mut function| {
+                function.attributes.push(Attribute { name: "prelude".to_string(), args: Vec::new() });
+                function
+            })
+            .collect();
+        Prelude { functions }
+    }
+
+    pub fn functions(&self) -> &[FnDef] {
+        &self.functions
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions().iter().any(|function| function.name == name)
+    }
+
+    /// AX1502 for each program function named like a prelude one
+    pub fn shadowing_warnings(&self, module: &Module) -> Vec<ValidationError> {
+        module
+            .functions
+            .iter()
+            .filter(|function| self.contains(&function.name))
+            .map(|function| {
+                let mut warning = ValidationError::new(format!(
+                    "{}: `{}` replaces the prelude function of the same name",
+                    ErrorCode::ShadowsPrelude, function.name
+                ));
+                warning.name = Some(function.name.clone());
+                warning.help.push(format!("rename it to call the prelude's `{}`, or compile with --no-prelude", function.name));
+                warning
+            })
+            .collect()
+    }
+
+    /// Add the prelude functions `module` reaches and does not define itself
+    pub fn add_reachable(&self, module: &mut Module) {
+        let defined: HashSet<String> = module.functions.iter().map(|f| f.name.clone()).collect();
+        let mut referenced = HashSet::new();
+        for function in &module.functions {
+            collect_references(&function.body, &mut referenced);
+        }
+        let added = self.reachable(referenced, &defined);
+        module.functions.extend(added);
+    }
+
+    // Prelude functions reached from `roots`, skipping (and not following)
+    // names in `defined`; in prelude order
+    fn reachable(&self, roots: HashSet<String>, defined: &HashSet<String>) -> Vec<FnDef> {
+        let mut reached: HashSet<String> = HashSet::new();
+        let mut pending: Vec<String> = roots.into_iter().collect();
+        while let Some(name) = pending.pop() {
+            if defined.contains(&name) || reached.contains(&name) {
+                continue;
+            }
+            let Some(function) = self.functions().iter().find(|f| f.name == name) else {
+                continue;
+            };
+            reached.insert(name);
+            let mut references = HashSet::new();
+            collect_references(&function.body, &mut references);
+            pending.extend(references);
+        }
+        self.functions().iter().filter(|f| reached.contains(&f.name)).cloned().collect()
+    }
+}
+
+pub fn is_prelude_function(name: &str) -> bool {
+    Prelude::parse().contains(name)
+}
+
+/// The prelude functions among `names` and those they reach, except the
+/// ones in `defined`, as a Core Let chain ending in Unit; None when empty
+pub fn core_unit(names: &[String], defined: &HashSet<String>) -> Option<CoreTerm> {
+    let functions = Prelude::parse().reachable(names.iter().cloned().collect(), defined);
+    if functions.is_empty() {
+        return None;
+    }
+    let module = Module { functions, foreign_functions: Vec::new(), types: Vec::new(), imports: Vec::new() };
+    Some(surface_to_core::value_to_core(&surface_lower::lower_module(module)))
+}
+
+// Every identifier and called name in `expr`
+pub(crate) fn collect_references(expr: &SurfaceExpr, out: &mut HashSet<String>) {
+    match expr {
+        SurfaceExpr::Ident(name) => {
+            out.insert(name.clone());
+        }
+        SurfaceExpr::Call(name, args) => {
+            out.insert(name.clone());
+            args.iter().for_each(|arg| collect_references(arg, out));
+        }
+        SurfaceExpr::Block(stmts) => {
+            for stmt in stmts {
+                match stmt {
+                    SurfaceStmt::Let(_, value) | SurfaceStmt::Expr(value) => collect_references(value, out),
+                    SurfaceStmt::LetPattern(_, value, else_expr) => {
+                        collect_references(value, out);
+                        if let Some(else_expr) = else_expr {
+                            collect_references(else_expr, out);
+                        }
+                    }
+                }
+            }
+        }
+        SurfaceExpr::Proj(inner, _) => collect_references(inner, out),
+        SurfaceExpr::Match(scrutinee, arms) => {
+            collect_references(scrutinee, out);
+            arms.iter().for_each(|arm| collect_references(&arm.expr, out));
+        }
+        SurfaceExpr::If { cond, then_branch, else_branch } => {
+            collect_references(cond, out);
+            collect_references(then_branch, out);
+            collect_references(else_branch, out);
+        }
+        SurfaceExpr::IntLit(_) | SurfaceExpr::BoolLit(_) | SurfaceExpr::StringLit(_) | SurfaceExpr::UnitLit => {}
+    }
+}
@@ -0,0 +1,84 @@
+// Tests for the standard prelude (prelude.rs, prelude.ax)
+
+use crate::prelude::{self, Prelude, PRELUDE_SOURCE, PRELUDE_VERSION};
+use crate::registry_loader::Registry;
+use crate::surface_parser::{self, Module};
+use crate::{core_validator, interpret, surface_lower, surface_to_core};
+use std::collections::HashSet;
+
+fn parse(source: &str) -> Module {
+    surface_parser::parse_module_with_file(source, "main.ax").unwrap()
+}
+
+fn names(module: &Module) -> Vec<&str> {
+    module.functions.iter().map(|f| f.name.as_str()).collect()
+}
+
+fn run_with_prelude(source: &str) -> i32 {
+    let mut module = parse(source);
+    Prelude::parse().add_reachable(&mut module);
+    let core = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    interpret::run_program(&core, &[])
+}
+
+#[test]
+fn test_prelude_is_versioned_and_validates() {
+    assert_eq!(PRELUDE_SOURCE.lines().next(), Some(format!("// axis-prelude {}", PRELUDE_VERSION).as_str()));
+
+    let functions = Prelude::parse().functions().to_vec();
+    assert!(functions.iter().all(|f| f.has_attribute("prelude")));
+    let module = Module { functions, foreign_functions: Vec::new(), types: Vec::new(), imports: Vec::new() };
+    let core = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    // Only pure primitives: the prelude needs no registry beyond the default
+    assert!(!core_validator::validate_core(&core, &Registry::prelude()).has_errors());
+}
+
+#[test]
+fn test_programs_can_call_prelude_functions() {
+    let source = "fn axis_entry(args: Unit) -> Int {\n    list_sum(list_range(0, 5)) + list_length(list_reverse(list_range(0, 3)))\n}\n";
+    assert_eq!(run_with_prelude(source), 13);
+
+    let source = "fn axis_entry(args: Unit) -> Int {\n    \
+                  let words = Cons(\"a\", Cons(\"bc\", Cons(\"d\", Nil)));\n    \
+                  str_len(str_join(words, \", \")) + str_len(str_repeat(\"xy\", 3))\n}\n";
+    assert_eq!(run_with_prelude(source), 14);
+}
+
+#[test]
+fn test_only_reachable_functions_are_added() {
+    let mut module = parse("fn axis_entry(args: Unit) -> Int {\n    list_length(Nil)\n}\n");
+    Prelude::parse().add_reachable(&mut module);
+    assert_eq!(names(&module), ["axis_entry", "list_length", "list_length_from"]);
+
+    let mut module = parse("fn axis_entry(args: Unit) -> Int {\n    7\n}\n");
+    Prelude::parse().add_reachable(&mut module);
+    assert_eq!(names(&module), ["axis_entry"]);
+}
+
+#[test]
+fn test_program_definitions_shadow_the_prelude() {
+    let source = "fn list_length(xs: List[Int]) -> Int {\n    42\n}\n\
+                  fn axis_entry(args: Unit) -> Int {\n    list_length(list_reverse(Nil))\n}\n";
+    let mut module = parse(source);
+    let warnings = Prelude::parse().shadowing_warnings(&module);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].message.starts_with("AX1502: `list_length`"));
+    assert_eq!(warnings[0].name.as_deref(), Some("list_length"));
+
+    Prelude::parse().add_reachable(&mut module);
+    assert_eq!(names(&module), ["list_length", "axis_entry", "list_reverse", "list_reverse_onto"]);
+    assert_eq!(run_with_prelude(source), 42);
+}
+
+#[test]
+fn test_link_unit_skips_definitions_of_other_bundles() {
+    let defined: HashSet<String> = ["list_reverse_onto".to_string()].into_iter().collect();
+    let Some(unit) = prelude::core_unit(&["list_append".to_string()], &defined) else {
+        panic!("list_append is a prelude function");
+    };
+    let exports = crate::core_interface::compute_interface(&unit, &Registry::prelude()).exports;
+    let exported: Vec<&str> = exports.iter().map(|export| export.name.as_str()).collect();
+    assert_eq!(exported, ["list_reverse", "list_append"]);
+
+    assert!(prelude::core_unit(&["no_such_function".to_string()], &HashSet::new()).is_none());
+}
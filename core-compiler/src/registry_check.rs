@@ -0,0 +1,112 @@
+// `axis-compiler check-registry`: lint .axreg files as one active registry
+//
+// Errors (the registry would not load, or loads ambiguously):
+//   - syntax and field errors, all of them rather than the first
+//   - a function declared twice, within a file or across files; with
+//     different arities the declarations conflict
+// Warnings (legal, but probably a mistake):
+//   - a profile (class) tag outside KNOWN_PROFILES
+//   - an entry named like a built-in primitive, which the validator
+//     resolves without the registry
+//
+// Findings are diagnostics pointing at the offending line, followed by a
+// summary; any error makes the command fail.
+
+use std::collections::HashMap;
+use crate::diagnostics::{Diagnostic, Severity, SourceMap, SourceSpan};
+use crate::registry_loader::{self, RegistryEntry, RegistryError, KNOWN_PROFILES};
+use crate::validation_registry;
+
+pub struct RegistryReport {
+    pub diagnostics: Vec<Diagnostic>,
+    pub sources: SourceMap,
+    pub files: usize,
+    pub functions: usize,
+}
+
+impl RegistryReport {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == severity).count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "checked {} file(s), {} function(s): {} error(s), {} warning(s)",
+            self.files,
+            self.functions,
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+}
+
+// Underlines the whole line, when known
+fn finding(severity: Severity, error: &RegistryError, text: &HashMap<&str, &str>) -> Diagnostic {
+    let mut diagnostic = match severity {
+        Severity::Error => Diagnostic::error(None, error.message.clone()),
+        Severity::Warning => Diagnostic::warning(None, error.message.clone()),
+    };
+    if error.line > 0 {
+        let width = text
+            .get(error.file.as_str())
+            .and_then(|content| content.lines().nth(error.line - 1))
+            .map_or(1, |line| line.trim_end().chars().count());
+        diagnostic.span = Some(SourceSpan::point(&error.file, error.line, 1, width));
+    }
+    diagnostic
+}
+
+/// Lint `files` (path, content), in the order they would be loaded
+pub fn check_registries(files: &[(String, String)]) -> RegistryReport {
+    let mut sources = SourceMap::default();
+    let text: HashMap<&str, &str> = files.iter().map(|(path, content)| (path.as_str(), content.as_str())).collect();
+    let mut diagnostics = Vec::new();
+    let mut first: HashMap<String, RegistryEntry> = HashMap::new();
+    let mut functions = 0;
+
+    for (path, content) in files {
+        sources.add_file(path, content);
+        let (entries, errors) = registry_loader::parse_registry(content, path);
+        for error in &errors {
+            diagnostics.push(finding(Severity::Error, error, &text));
+        }
+
+        for entry in entries {
+            let at = |message: String| RegistryError::new(&entry.file, entry.line, message);
+            if let Some(existing) = first.get(&entry.name) {
+                let message = if existing.arity == entry.arity {
+                    format!("'{}' is declared twice", entry.name)
+                } else {
+                    format!("'{}' has conflicting arities {} and {}", entry.name, existing.arity, entry.arity)
+                };
+                let mut diagnostic = finding(Severity::Error, &at(message), &text);
+                diagnostic.help.push(format!("first declared at {}:{}", existing.file, existing.line));
+                diagnostics.push(diagnostic);
+                continue;
+            }
+
+            for profile in entry.profiles.iter().filter(|p| !KNOWN_PROFILES.contains(&p.as_str())) {
+                let mut diagnostic = finding(
+                    Severity::Warning,
+                    &at(format!("'{}' has unknown profile '{}'", entry.name, profile)),
+                    &text,
+                );
+                diagnostic.help.push(format!("known profiles: {}", KNOWN_PROFILES.join(", ")));
+                diagnostics.push(diagnostic);
+            }
+            if validation_registry::is_builtin_function(&entry.name) {
+                let mut diagnostic = finding(
+                    Severity::Warning,
+                    &at(format!("'{}' shadows a built-in primitive", entry.name)),
+                    &text,
+                );
+                diagnostic.help.push("built-in primitives need no registry entry; rename or remove it".to_string());
+                diagnostics.push(diagnostic);
+            }
+            functions += 1;
+            first.insert(entry.name.clone(), entry);
+        }
+    }
+
+    RegistryReport { diagnostics, sources, files: files.len(), functions }
+}
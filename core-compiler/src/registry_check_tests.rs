@@ -0,0 +1,66 @@
+// Tests for `check-registry` (registry_check.rs)
+
+use crate::diagnostics::{render, Severity};
+use crate::registry_check::{check_registries, RegistryReport};
+
+fn check(files: &[(&str, &str)]) -> RegistryReport {
+    let files: Vec<(String, String)> = files.iter().map(|(n, t)| (n.to_string(), t.to_string())).collect();
+    check_registries(&files)
+}
+
+fn messages(report: &RegistryReport, severity: Severity) -> Vec<String> {
+    report.diagnostics.iter().filter(|d| d.severity == severity).map(|d| d.message.clone()).collect()
+}
+
+const CLEAN: &str = "fn axis_a\narity 1\ndeterministic true\nprofile core\nend\n";
+
+#[test]
+fn test_bundled_registry_is_clean() {
+    let report = check(&[("axis.axreg", include_str!("../../registries/axis.axreg"))]);
+    assert!(report.diagnostics.is_empty(), "{:?}", messages(&report, Severity::Error));
+    assert!(report.functions > 0);
+}
+
+#[test]
+fn test_duplicates_within_and_across_files() {
+    let report = check(&[
+        ("a.axreg", "fn axis_a\narity 1\ndeterministic true\nend\n\nfn axis_a\narity 1\ndeterministic true\nend\n"),
+        ("b.axreg", "fn axis_a\narity 2\ndeterministic true\nend\n"),
+    ]);
+    assert_eq!(
+        messages(&report, Severity::Error),
+        vec!["'axis_a' is declared twice".to_string(), "'axis_a' has conflicting arities 1 and 2".to_string()]
+    );
+    let rendered = render(&report.diagnostics[1], &report.sources, false);
+    assert!(rendered.contains("--> b.axreg:1:1\n"), "{}", rendered);
+    assert!(rendered.contains("= help: first declared at a.axreg:1"), "{}", rendered);
+    assert_eq!(report.functions, 1);
+}
+
+#[test]
+fn test_collects_every_syntax_error() {
+    let report = check(&[(
+        "bad.axreg",
+        "fn axis_a\narity x\ndeterministic true\nend\n\nstray line\n\nfn axis_b\narity 0\ndeterministic true\n\nfn axis_c\narity 0\ndeterministic true\nend\n",
+    )]);
+    let errors = messages(&report, Severity::Error);
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(errors[1].starts_with("Unexpected line"), "{:?}", errors);
+    assert!(errors[2].contains("Missing 'end'"), "{:?}", errors);
+    // axis_c still parses after the unterminated axis_b
+    assert_eq!(report.functions, 1);
+}
+
+#[test]
+fn test_warnings_do_not_fail() {
+    let report = check(&[
+        ("ok.axreg", CLEAN),
+        ("warn.axreg", "fn axis_b\narity 0\ndeterministic true\nprofile network\nend\n\nfn str_len\narity 1\ndeterministic true\nend\n"),
+    ]);
+    assert_eq!(report.count(Severity::Error), 0);
+    assert_eq!(
+        messages(&report, Severity::Warning),
+        vec!["'axis_b' has unknown profile 'network'".to_string(), "'str_len' shadows a built-in primitive".to_string()]
+    );
+    assert_eq!(report.summary(), "checked 2 file(s), 3 function(s): 0 error(s), 2 warning(s)");
+}
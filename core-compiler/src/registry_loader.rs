@@ -1,17 +1,140 @@
 // Registry loading and parsing for CP-5
-// Implements the .axreg file format from axis-registry-0.1.md
+// Implements the .axreg file format from axis-registry-0.1.md, and format
+// version 2 from axis-registry-0.2.md (files starting with `axreg 2`).
+//
+// COMPATIBILITY: a file without a version line is version 1 and loads
+// as before: the v2 fields stay empty and unknown fields are ignored.
+// Version 2 files are checked strictly.
 
+use crate::operators;
+use crate::symbol_index::SymbolIndex;
 use std::collections::HashMap;
 use std::fs;
 
+// Newest .axreg format version this loader reads
+pub const REGISTRY_FORMAT_VERSION: u32 = 2;
+
+// registries/prelude.axreg: the pure and test primitives, built in and
+// loaded when no registry is given
+pub const PRELUDE_REGISTRY: &str = include_str!("../../registries/prelude.axreg");
+pub const PRELUDE_ORIGIN: &str = "<built-in prelude>";
+
 #[allow(dead_code)]
 // Registry entries are loaded eagerly but selectively consumed
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct RegistryEntry {
     pub name: String,
     pub arity: u32,
     pub deterministic: bool,
     pub profiles: Vec<String>,
+    // Version 2 only; None/empty for version 1 entries
+    pub params: Option<Vec<String>>,
+    pub returns: Option<String>,
+    pub module: Option<String>,
+    pub capabilities: Vec<Capability>,
+    pub deprecated: Option<String>,
+    // Where the entry is declared (`fn` line)
+    pub file: String,
+    pub line: usize,
+}
+
+// Registry problem at a line of a file (line 0: the file as a whole)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryError {
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+
+impl RegistryError {
+    pub fn new(file: &str, line: usize, message: String) -> Self {
+        RegistryError { file: file.to_string(), line, message }
+    }
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.file, self.message)
+        } else {
+            write!(f, "{}:{}: {}", self.file, self.line, self.message)
+        }
+    }
+}
+
+// What a foreign function may touch outside the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    Io,
+    Fs,
+    Proc,
+}
+
+impl Capability {
+    pub fn parse(name: &str) -> Option<Capability> {
+        match name {
+            "io" => Some(Capability::Io),
+            "fs" => Some(Capability::Fs),
+            "proc" => Some(Capability::Proc),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Io => "io",
+            Capability::Fs => "fs",
+            Capability::Proc => "proc",
+        }
+    }
+
+    // The io, fs and process class tags (profiles) imply a capability
+    fn from_profile(profile: &str) -> Option<Capability> {
+        match profile {
+            "io" => Some(Capability::Io),
+            "fs" => Some(Capability::Fs),
+            "process" => Some(Capability::Proc),
+            _ => None,
+        }
+    }
+
+    /// `io,fs` as given to --allow; `none` or an empty list grants nothing
+    pub fn parse_list(list: &str) -> Result<Vec<Capability>, String> {
+        let mut capabilities = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|name| !name.is_empty() && *name != "none") {
+            let capability = Capability::parse(name)
+                .ok_or_else(|| format!("unknown capability '{}' (expected io, fs, proc or none)", name))?;
+            capabilities.push(capability);
+        }
+        capabilities.sort();
+        capabilities.dedup();
+        Ok(capabilities)
+    }
+}
+
+impl RegistryEntry {
+    /// `name(Str, Int) -> Str`, or `name/2` when the types are not declared
+    pub fn signature(&self) -> String {
+        match &self.params {
+            Some(params) => format!(
+                "{}({}) -> {}",
+                self.name,
+                params.join(", "),
+                self.returns.as_deref().unwrap_or("?")
+            ),
+            None => format!("{}/{}", self.name, self.arity),
+        }
+    }
+
+    /// Capabilities a call exercises: those declared with `capability`
+    /// (version 2) and those implied by its class tags
+    pub fn required_capabilities(&self) -> Vec<Capability> {
+        let mut required = self.capabilities.clone();
+        required.extend(self.profiles.iter().filter_map(|profile| Capability::from_profile(profile)));
+        required.sort();
+        required.dedup();
+        required
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,86 +160,66 @@ impl Registry {
     }
 
     // Load and parse a single .axreg file
-    pub fn load_from_file(&mut self, file_path: &str) -> Result<(), String> {
+    pub fn load_from_file(&mut self, file_path: &str) -> Result<(), RegistryError> {
         let content = fs::read_to_string(file_path)
-            .map_err(|e| format!("Failed to read registry file {}: {}", file_path, e))?;
+            .map_err(|e| RegistryError::new(file_path, 0, format!("Failed to read registry file: {}", e)))?;
         
-        self.parse_registry_content(&content, file_path)
+        self.load_from_str(&content, file_path)
+    }
+
+    // Load registry text; `origin` names it in error messages
+    pub fn load_from_str(&mut self, content: &str, origin: &str) -> Result<(), RegistryError> {
+        let (entries, errors) = parse_registry(content, origin);
+        // FAIL-FAST: the first error stops loading
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+        for entry in entries {
+            // Check for duplicate names (required by spec)
+            if let Some(existing) = self.entries.get(&entry.name) {
+                return Err(RegistryError::new(&entry.file, entry.line, format!(
+                    "Duplicate function name '{}' in registry (first declared at {}:{})",
+                    entry.name, existing.file, existing.line
+                )));
+            }
+            self.entries.insert(entry.name.clone(), entry);
+        }
+        Ok(())
     }
 
     // Load multiple registry files
-    pub fn load_from_files(&mut self, file_paths: &[&str]) -> Result<(), String> {
+    pub fn load_from_files(&mut self, file_paths: &[&str]) -> Result<(), RegistryError> {
         for path in file_paths {
             self.load_from_file(path)?;
         }
         Ok(())
     }
 
-    // Parse .axreg file content according to axis-registry-0.1.md spec
-    fn parse_registry_content(&mut self, content: &str, file_path: &str) -> Result<(), String> {
-        let lines: Vec<&str> = content.lines().collect();
-        let mut i = 0;
-
-        while i < lines.len() {
-            let line = lines[i].trim();
-            
-            // Skip empty lines and comments (//)
-            if line.is_empty() || line.starts_with("//") {
-                i += 1;
-                continue;
-            }
-
-            // Parse function block
-            if line.starts_with("fn ") {
-                let name = line[3..].trim().to_string();
-                i += 1;
-
-                let mut arity = None;
-                let mut deterministic = None;
-                let mut profiles = Vec::new();
-
-                // Parse fields until "end"
-                while i < lines.len() {
-                    let field_line = lines[i].trim();
-                    i += 1;
-
-                    if field_line == "end" {
-                        break;
-                    }
-
-                    if field_line.starts_with("arity ") {
-                        let arity_str = field_line[6..].trim();
-                        arity = Some(arity_str.parse::<u32>()
-                            .map_err(|_| format!("Invalid arity in {}: {}", file_path, arity_str))?);
-                    } else if field_line.starts_with("deterministic ") {
-                        let det_str = field_line[14..].trim();
-                        deterministic = Some(det_str == "true");
-                    } else if field_line.starts_with("profile ") {
-                        profiles.push(field_line[8..].trim().to_string());
-                    }
-                }
-
-                // Validate required fields
-                let arity = arity.ok_or_else(|| format!("Missing 'arity' field for function '{}' in {}", name, file_path))?;
-                let deterministic = deterministic.ok_or_else(|| format!("Missing 'deterministic' field for function '{}' in {}", name, file_path))?;
-
-                // Check for duplicate names (required by spec)
-                if self.entries.contains_key(&name) {
-                    return Err(format!("Duplicate function name '{}' in registry", name));
-                }
+    // The built-in prelude (PRELUDE_REGISTRY)
+    pub fn prelude() -> Self {
+        let mut registry = Registry::new();
+        registry
+            .load_from_str(PRELUDE_REGISTRY, PRELUDE_ORIGIN)
+            .expect("the built-in prelude registry is well-formed");
+        registry
+    }
 
-                self.entries.insert(name.clone(), RegistryEntry {
-                    name,
-                    arity,
-                    deterministic,
-                    profiles,
-                });
-            } else {
-                return Err(format!("Unexpected line in {}: {}", file_path, line));
-            }
+    // The given registry files, or the prelude when there are none
+    pub fn load_or_prelude(file_paths: &[String]) -> Result<Self, RegistryError> {
+        if file_paths.is_empty() {
+            return Ok(Registry::prelude());
         }
+        let mut registry = Registry::new();
+        let paths: Vec<&str> = file_paths.iter().map(String::as_str).collect();
+        registry.load_from_files(&paths)?;
+        Ok(registry)
+    }
 
-        Ok(())
+    // The entries' names, for "did you mean" suggestions
+    pub fn symbol_index(&self) -> SymbolIndex {
+        let mut index = SymbolIndex::default();
+        index.extend(self.entries.keys().map(String::as_str));
+        index
     }
 
     // Single canonical classification function (CP-5 Deliverable A)
@@ -156,23 +259,199 @@ impl Registry {
 
 }
 
+/// Entries of one .axreg text, according to axis-registry-0.1.md (version 1)
+/// or axis-registry-0.2.md (version 2), with every error found. A block with
+/// an error is left out, so one mistake does not hide the next.
+/// Duplicate names are not checked here (see Registry::load_from_str).
+pub fn parse_registry(content: &str, file_path: &str) -> (Vec<RegistryEntry>, Vec<RegistryError>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    // Trailing `//` comments are whitespace (spec 3.2)
+    let lines: Vec<&str> = content
+        .lines()
+        .map(|line| line.split_once("//").map_or(line, |(code, _)| code).trim())
+        .collect();
+    let mut i = 0;
+
+    // Optional version line before the first block
+    let mut version = 1;
+    if let Some(first) = lines.iter().position(|line| !line.is_empty())
+        && let Some(number) = lines[first].strip_prefix("axreg ")
+    {
+        match number.trim().parse::<u32>() {
+            Ok(v) if (1..=REGISTRY_FORMAT_VERSION).contains(&v) => version = v,
+            _ => {
+                let message = format!(
+                    "Unsupported registry format version {} (this compiler reads 1 to {})",
+                    number.trim(), REGISTRY_FORMAT_VERSION
+                );
+                return (entries, vec![RegistryError::new(file_path, first + 1, message)]);
+            }
+        }
+        i = first + 1;
+    }
+
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        // Skip empty lines (comments are already stripped)
+        if line.is_empty() {
+            continue;
+        }
+        let Some(name) = line.strip_prefix("fn ") else {
+            errors.push(RegistryError::new(file_path, i, format!("Unexpected line: {}", line)));
+            continue;
+        };
+
+        // Parse function block
+        let mut entry = RegistryEntry {
+            name: name.trim().to_string(),
+            file: file_path.to_string(),
+            line: i,
+            ..Default::default()
+        };
+        let mut block_errors = Vec::new();
+        let mut arity = None;
+        let mut deterministic = None;
+        let mut closed = false;
+
+        // Parse fields until "end" (or the next block, if `end` is missing)
+        while i < lines.len() && !lines[i].starts_with("fn ") {
+            let field_line = lines[i];
+            i += 1;
+
+            if field_line == "end" {
+                closed = true;
+                break;
+            }
+            if field_line.is_empty() {
+                continue;
+            }
+            if let Err(message) = parse_field(&mut entry, field_line, version, &mut arity, &mut deterministic) {
+                block_errors.push(RegistryError::new(file_path, i, message));
+            }
+        }
+
+        // Validate required fields; a field that failed to parse is not
+        // also reported missing
+        let field_errors = !block_errors.is_empty();
+        let at = |message: String| RegistryError::new(file_path, entry.line, message);
+        if !closed {
+            block_errors.push(at(format!("Missing 'end' for function '{}'", entry.name)));
+        }
+        match arity {
+            Some(arity) => entry.arity = arity,
+            None if !field_errors => block_errors.push(at(format!("Missing 'arity' field for function '{}'", entry.name))),
+            None => {}
+        }
+        match deterministic {
+            Some(deterministic) => entry.deterministic = deterministic,
+            None if !field_errors => {
+                block_errors.push(at(format!("Missing 'deterministic' field for function '{}'", entry.name)))
+            }
+            None => {}
+        }
+        if let (Some(params), Some(arity)) = (&entry.params, arity)
+            && params.len() != arity as usize
+        {
+            block_errors.push(at(format!(
+                "Function '{}' declares {} params for arity {}",
+                entry.name, params.len(), arity
+            )));
+        }
+
+        if block_errors.is_empty() {
+            entries.push(entry);
+        } else {
+            errors.extend(block_errors);
+        }
+    }
+
+    (entries, errors)
+}
+
+// One `field value` line of a function block
+fn parse_field(
+    entry: &mut RegistryEntry,
+    field_line: &str,
+    version: u32,
+    arity: &mut Option<u32>,
+    deterministic: &mut Option<bool>,
+) -> Result<(), String> {
+    let name = entry.name.clone();
+    let (field, value) = field_line.split_once(' ').unwrap_or((field_line, ""));
+    let value = value.trim();
+    match field {
+        "arity" => {
+            *arity = Some(value.parse::<u32>().map_err(|_| format!("Invalid arity: {}", value))?);
+        }
+        "deterministic" => {
+            if version >= 2 && value != "true" && value != "false" {
+                return Err(format!("Invalid deterministic value for '{}': {}", name, value));
+            }
+            *deterministic = Some(value == "true");
+        }
+        "profile" => entry.profiles.push(value.to_string()),
+        // Version 1 ignores fields it does not know
+        _ if version < 2 => {}
+        "params" => {
+            let params: Vec<String> = value.split_whitespace().map(str::to_string).collect();
+            set_once(&mut entry.params, params, "params", &name)?;
+        }
+        "returns" if !value.is_empty() => {
+            set_once(&mut entry.returns, value.to_string(), "returns", &name)?;
+        }
+        "module" if is_module_path(value) => {
+            set_once(&mut entry.module, value.to_string(), "module", &name)?;
+        }
+        "capability" => {
+            let capability = Capability::parse(value).ok_or_else(|| format!(
+                "Unknown capability '{}' for function '{}' (expected io, fs or proc)",
+                value, name
+            ))?;
+            if !entry.capabilities.contains(&capability) {
+                entry.capabilities.push(capability);
+            }
+        }
+        "deprecated" => {
+            set_once(&mut entry.deprecated, value.to_string(), "deprecated", &name)?;
+        }
+        _ => return Err(format!("Invalid field for function '{}': {}", name, field_line)),
+    }
+    Ok(())
+}
+
+// Single-valued v2 fields may appear once per function
+fn set_once<T>(slot: &mut Option<T>, value: T, field: &str, name: &str) -> Result<(), String> {
+    if slot.is_some() {
+        return Err(format!("Duplicate '{}' field for function '{}'", field, name));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+// Dotted path of identifiers, e.g. axis.io
+fn is_module_path(value: &str) -> bool {
+    !value.is_empty()
+        && value.split('.').all(|part| {
+            part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+// Profile (class) tags used by the shipped registries
+pub const KNOWN_PROFILES: &[&str] = &["core", "compiler", "foreign", "fs", "io", "process", "test"];
+
 // Hardcoded builtin allowlist (as per CP-5 spec)
 fn is_builtin_function(name: &str) -> bool {
     match name {
-        // Core arithmetic
-        "+" | "-" | "*" | "/" | "%" => true,
-        "__add__" | "__sub__" | "__mul__" | "__div__" | "__mod__" => true,
-        
-        // Comparisons  
-        "==" | "!=" | "<" | "<=" | ">" | ">=" => true,
-        "__eq__" | "__lt__" | "__lte__" | "__gt__" | "__gte__" => true,
-        
-        // Logical
-        "&&" | "||" | "!" => true,
-        "__and__" | "__or__" | "__not__" => true,
+        // Infix operators (operators.rs) and their Core functions
+        _ if operators::is_operator(name) => true,
+        "!" | "__not__" => true,
         
         // Tuple operations
-        "tuple_field" => true,
+        "tuple_field" | "__tuple__" => true,
         
         // String operations
         "str_len" | "str_char" | "str_slice" | "str_concat" | "__concat__" => true,
@@ -0,0 +1,178 @@
+// Tests for .axreg loading (registry_loader.rs), both format versions, and
+// for the validator's use of version 2 signatures and of capabilities
+
+use crate::diagnostics::Severity;
+use crate::error_codes::ErrorCode;
+use crate::registry_loader::{Capability, Registry};
+use crate::{core_validator, surface_lower, surface_parser, surface_to_core};
+
+const V2: &str = "\
+axreg 2
+// string length
+fn axis_str_len
+  arity 1
+  deterministic true   // pure
+  profile core
+  module axis.str
+  params Str
+  returns Int
+end
+
+fn axis_fs_read
+  arity 1
+  deterministic false
+  profile core
+  module axis.fs
+  params Str
+  returns Str
+  capability fs
+  capability io
+  deprecated use axis_fs_read_text, which reports errors
+end
+";
+
+fn load(content: &str) -> Result<Registry, String> {
+    let mut registry = Registry::new();
+    registry.load_from_str(content, "test.axreg").map_err(|e| e.to_string())?;
+    Ok(registry)
+}
+
+#[test]
+fn test_load_version_2() {
+    let registry = load(V2).unwrap();
+
+    let len = &registry.entries["axis_str_len"];
+    assert!(len.deterministic);
+    assert_eq!(len.module.as_deref(), Some("axis.str"));
+    assert_eq!(len.signature(), "axis_str_len(Str) -> Int");
+    assert!(len.capabilities.is_empty());
+
+    let read = &registry.entries["axis_fs_read"];
+    assert_eq!(read.capabilities, vec![Capability::Fs, Capability::Io]);
+    assert_eq!(read.deprecated.as_deref(), Some("use axis_fs_read_text, which reports errors"));
+}
+
+#[test]
+fn test_version_1_loads_unchanged() {
+    // No version line: v2 fields are left empty, unknown fields ignored
+    let registry = load("fn axis_str_len\narity 1\ndeterministic true\nprofile core\nreturns Int\nend\n").unwrap();
+    let len = &registry.entries["axis_str_len"];
+    assert_eq!((len.arity, len.deterministic), (1, true));
+    assert_eq!(len.params, None);
+    assert_eq!(len.returns, None);
+    assert_eq!(len.signature(), "axis_str_len/1");
+
+    assert!(load("axreg 1\nfn f\narity 0\ndeterministic true\nend\n").is_ok());
+}
+
+#[test]
+fn test_version_2_is_strict() {
+    let cases = [
+        ("axreg 3\n", "Unsupported registry format version"),
+        ("axreg 2\nfn f\narity 2\ndeterministic true\nparams Int\nend\n", "declares 1 params for arity 2"),
+        ("axreg 2\nfn f\narity 0\ndeterministic true\ncapability net\nend\n", "Unknown capability 'net'"),
+        ("axreg 2\nfn f\narity 0\ndeterministic true\nowner me\nend\n", "Invalid field"),
+        ("axreg 2\nfn f\narity 0\ndeterministic yes\nend\n", "Invalid deterministic value"),
+        ("axreg 2\nfn f\narity 0\ndeterministic true\nmodule a..b\nend\n", "Invalid field"),
+        ("axreg 2\nfn f\narity 0\ndeterministic true\nreturns Int\nreturns Str\nend\n", "Duplicate 'returns'"),
+    ];
+    for (content, expected) in cases {
+        let err = load(content).expect_err(content);
+        assert!(err.contains(expected), "{}: {}", content, err);
+    }
+}
+
+fn validate(source: &str, registry: &Registry) -> Result<Vec<String>, String> {
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let report = core_validator::validate_core(&term, registry);
+    if let Some(error) = report.errors().next() {
+        return Err(error.message());
+    }
+    Ok(report.findings.iter().map(|warning| warning.message()).collect())
+}
+
+#[test]
+fn test_validator_uses_signatures() {
+    let registry = load(V2).unwrap();
+
+    assert_eq!(validate("fn main() { axis_str_len(\"abc\") }\n", &registry), Ok(vec![]));
+    assert_eq!(
+        validate("fn main() { axis_str_len(42) }\n", &registry),
+        Err("AX1003: argument 1 of `axis_str_len` is a Int literal, expected Str".to_string())
+    );
+    // Locals named like a registry function are not checked against it
+    assert_eq!(validate("fn axis_str_len(x) { x }\nfn main() { axis_str_len(42) }\n", &registry), Ok(vec![]));
+
+    assert_eq!(
+        validate("fn main() { axis_fs_read(\"a\") }\n", &registry),
+        Ok(vec!["AX1501: `axis_fs_read` is deprecated".to_string()])
+    );
+}
+
+#[test]
+fn test_deprecation_renders_as_warning() {
+    let registry = load(V2).unwrap();
+    let module = surface_parser::parse_module_with_file("fn main() { axis_fs_read(\"a\") }\n", "main.ax").unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let warnings = core_validator::deprecation_warnings(&term, &registry);
+
+    let diagnostic = warnings[0].to_diagnostic();
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    assert_eq!(diagnostic.code, Some(ErrorCode::Deprecated));
+}
+
+#[test]
+fn test_capabilities_from_class_tags() {
+    assert_eq!(Capability::parse_list("fs, io,fs"), Ok(vec![Capability::Io, Capability::Fs]));
+    assert_eq!(Capability::parse_list("none"), Ok(vec![]));
+    assert!(Capability::parse_list("io,net").unwrap_err().contains("unknown capability 'net'"));
+
+    let registry = load("fn axis_proc_exit\narity 1\ndeterministic false\nprofile process\nend\n").unwrap();
+    assert_eq!(registry.entries["axis_proc_exit"].required_capabilities(), vec![Capability::Proc]);
+    let registry = load(V2).unwrap();
+    assert_eq!(registry.entries["axis_fs_read"].required_capabilities(), vec![Capability::Io, Capability::Fs]);
+    assert!(registry.entries["axis_str_len"].required_capabilities().is_empty());
+}
+
+#[test]
+fn test_calls_outside_allow_fail_validation() {
+    let registry = load(V2).unwrap();
+    let module = surface_parser::parse_module_with_file(
+        "fn main() { axis_str_len(axis_fs_read(\"a\")) }\n",
+        "main.ax",
+    )
+    .unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+
+    assert!(core_validator::check_capabilities(&term, &registry, &[Capability::Io, Capability::Fs]).is_ok());
+    let error = core_validator::check_capabilities(&term, &registry, &[Capability::Io]).unwrap_err();
+    assert_eq!(error.message, "AX1008: `axis_fs_read` needs capability fs, which --allow does not grant");
+    assert_eq!(error.help, vec!["to permit these calls, compile with --allow io,fs".to_string()]);
+}
+
+#[test]
+fn test_prelude_entries_match_axis_registry() {
+    let prelude = Registry::prelude();
+    let full = load(include_str!("../../registries/axis.axreg")).unwrap();
+    assert!(prelude.entries.contains_key("axis_str_len") && prelude.entries.contains_key("axis_assert_eq"));
+    for (name, entry) in &prelude.entries {
+        let declared = full.entries.get(name).unwrap_or_else(|| panic!("{} is not in axis.axreg", name));
+        assert_eq!((entry.arity, entry.deterministic, &entry.profiles), (declared.arity, declared.deterministic, &declared.profiles), "{}", name);
+        assert!(entry.required_capabilities().is_empty(), "{} needs a capability", name);
+    }
+}
+
+#[test]
+fn test_pure_programs_validate_against_the_prelude() {
+    let pure = "fn main() -> Int {\n    axis_str_len(\"abc\") + 1\n}\n";
+    assert_eq!(validate(pure, &Registry::prelude()), Ok(vec![]));
+
+    // IO is only an error when it is used
+    let io = "fn main() -> Int {\n    axis_io_print(\"hi\");\n    0\n}\n";
+    assert_eq!(
+        validate(io, &Registry::prelude()),
+        Err("AX1002: `axis_io_print` does IO, but no loaded registry declares it".to_string())
+    );
+    assert!(validate(io, &load(include_str!("../../registries/axis.axreg")).unwrap()).is_ok());
+}
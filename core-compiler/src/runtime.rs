@@ -2,6 +2,10 @@
 // Experimental runtime evaluator (not active in compiler pipeline)
 
 // Minimal Axis Core evaluator for compiler
+use crate::symbol::Symbol;
+use axis_ir::term_kind::TermKind;
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -27,6 +31,7 @@ pub enum Value {
     Int(i64),
     Bool(bool),
     Str(i64), // String handle into string table
+    Bytes(Vec<u8>), // Raw binary data (not interned)
     Unit,
     Tuple(Vec<Value>),
     Closure(Env, String, Rc<CoreTerm>),
@@ -73,18 +78,20 @@ pub enum CoreTerm {
     BoolLit(bool, Option<Span>),
     UnitLit(Option<Span>),
     StrLit(String, Option<Span>), // Will be interned during evaluation
-    // Variables
-    Var(String, Option<Span>),
+    // Variables (names are interned, see symbol.rs)
+    Var(Symbol, Option<Span>),
     // Lambda
-    Lam(String, Rc<CoreTerm>, Option<Span>),
+    Lam(Symbol, Rc<CoreTerm>, Option<Span>),
     // Application
     App(Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
     // Tuple
     Tuple(Vec<CoreTerm>, Option<Span>),
-    // Projection (1-based index)
+    // Projection (0-based index, as surface `proj(expr, i)`)
     Proj(Rc<CoreTerm>, usize, Option<Span>),
+    // Constructor field access (0-based index)
+    CtorField(Rc<CoreTerm>, usize, Option<Span>),
     // Let binding
-    Let(String, Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
+    Let(Symbol, Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
     // If expression
     If(Rc<CoreTerm>, Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
     // Match expression
@@ -93,6 +100,32 @@ pub enum CoreTerm {
     Ctor(String, Vec<CoreTerm>, Option<Span>),
 }
 
+impl CoreTerm {
+    pub fn kind(&self) -> TermKind {
+        match self {
+            CoreTerm::IntLit(..) => TermKind::IntLit,
+            CoreTerm::BoolLit(..) => TermKind::BoolLit,
+            CoreTerm::UnitLit(..) => TermKind::UnitLit,
+            CoreTerm::StrLit(..) => TermKind::StrLit,
+            CoreTerm::Var(..) => TermKind::Var,
+            CoreTerm::Lam(..) => TermKind::Lam,
+            CoreTerm::App(..) => TermKind::App,
+            CoreTerm::Tuple(..) => TermKind::Tuple,
+            CoreTerm::Proj(..) => TermKind::Proj,
+            CoreTerm::CtorField(..) => TermKind::CtorField,
+            CoreTerm::Let(..) => TermKind::Let,
+            CoreTerm::If(..) => TermKind::If,
+            CoreTerm::Ctor(..) => TermKind::Ctor,
+            CoreTerm::Match(..) => TermKind::Match,
+        }
+    }
+
+    /// The node's kind, as the --view-core-ir graph and `stats` name it
+    pub fn kind_name(&self) -> &'static str {
+        self.kind().name()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Pattern {
     PInt(i64),
@@ -101,6 +134,18 @@ pub enum Pattern {
     PVar(String),
     PTuple(Vec<Pattern>),
     PEnum(String, Vec<Pattern>), // Cons, Nil, Ok, Err, etc.
+    // `name @ pattern`: binds the whole value as well
+    PBind(String, Box<Pattern>),
+}
+
+impl Pattern {
+    /// What the pattern tests: itself, or under any `name @` the pattern named
+    pub fn shape(&self) -> &Pattern {
+        match self {
+            Pattern::PBind(_, inner) => inner.shape(),
+            pattern => pattern,
+        }
+    }
 }
 
 pub fn eval(term: &CoreTerm, env: &Env) -> Result<Value, i64> {
@@ -113,8 +158,9 @@ pub fn eval(term: &CoreTerm, env: &Env) -> Result<Value, i64> {
             Ok(Value::Str(handle))
         }
         CoreTerm::Var(name, _) => {
-            env.get(name)
+            env.get(name.as_str())
                 .cloned()
+                .or_else(|| global(name.as_str()))
                 .ok_or_else(|| {
                     let err_msg = format!("Unbound variable: {}", name);
                     let handle = intern_str(err_msg);
@@ -122,9 +168,20 @@ pub fn eval(term: &CoreTerm, env: &Env) -> Result<Value, i64> {
                 })
         }
         CoreTerm::Lam(param, body, _) => {
-            Ok(Value::Closure(env.clone(), param.clone(), body.clone()))
+            Ok(Value::Closure(env.clone(), param.to_string(), body.clone()))
         }
         CoreTerm::App(func_term, arg_term, _) => {
+            // Surface tuples lower to __tuple__ applied to each element in
+            // turn; as in the emitters, the whole chain builds one tuple
+            if let Some(elems) = tuple_call_elements(term)
+                && !env.contains_key("__tuple__")
+            {
+                let mut values = Vec::with_capacity(elems.len());
+                for elem in elems {
+                    values.push(eval(elem, env)?);
+                }
+                return Ok(Value::Tuple(values));
+            }
             let func = eval(func_term, env)?;
             let arg = eval(arg_term, env)?;
             apply(func, arg)
@@ -140,10 +197,8 @@ pub fn eval(term: &CoreTerm, env: &Env) -> Result<Value, i64> {
             let val = eval(expr, env)?;
             match val {
                 Value::Tuple(elems) => {
-                    // Axis uses 1-based indexing
-                    let idx = index - 1;
-                    if idx < elems.len() {
-                        Ok(elems[idx].clone())
+                    if *index < elems.len() {
+                        Ok(elems[*index].clone())
                     } else {
                         let err_msg = format!("Tuple index {} out of bounds", index);
                         let handle = intern_str(err_msg);
@@ -157,10 +212,29 @@ pub fn eval(term: &CoreTerm, env: &Env) -> Result<Value, i64> {
                 }
             }
         }
+        CoreTerm::CtorField(expr, index, _) => {
+            let val = eval(expr, env)?;
+            match val {
+                Value::Enum(_, fields) => {
+                    if *index < fields.len() {
+                        Ok(fields[*index].clone())
+                    } else {
+                        let err_msg = format!("Constructor field {} out of bounds", index);
+                        let handle = intern_str(err_msg);
+                        Err(-handle)
+                    }
+                }
+                _ => {
+                    let err_msg = "Field access on non-constructor".to_string();
+                    let handle = intern_str(err_msg);
+                    Err(-handle)
+                }
+            }
+        }
         CoreTerm::Let(name, value_term, body_term, _) => {
             let value = eval(value_term, env)?;
             let mut new_env = (**env).clone();
-            new_env.insert(name.clone(), value);
+            new_env.insert(name.to_string(), value);
             eval(body_term, &Rc::new(new_env))
         }
         CoreTerm::If(cond_term, then_term, else_term, _) => {
@@ -196,6 +270,25 @@ pub fn eval(term: &CoreTerm, env: &Env) -> Result<Value, i64> {
     }
 }
 
+// The elements of `__tuple__ e0 ... en`, in order
+fn tuple_call_elements(term: &CoreTerm) -> Option<Vec<&CoreTerm>> {
+    let mut head = term;
+    while let CoreTerm::App(func, _, _) = head {
+        head = func;
+    }
+    if !matches!(head, CoreTerm::Var(name, _) if name.as_str() == "__tuple__") {
+        return None;
+    }
+    let mut elems = Vec::new();
+    let mut current = term;
+    while let CoreTerm::App(func, arg, _) = current {
+        elems.push(arg.as_ref());
+        current = func;
+    }
+    elems.reverse();
+    Some(elems)
+}
+
 fn apply(func: Value, arg: Value) -> Result<Value, i64> {
     match func {
         Value::Closure(env, param, body) => {
@@ -218,6 +311,11 @@ fn match_pattern(pattern: &Pattern, value: &Value) -> Option<Vec<(String, Value)
         (Pattern::PBool(b), Value::Bool(c)) if b == c => Some(vec![]),
         (Pattern::PUnit, Value::Unit) => Some(vec![]),
         (Pattern::PVar(name), val) => Some(vec![(name.clone(), val.clone())]),
+        (Pattern::PBind(name, inner), val) => {
+            let mut bindings = vec![(name.clone(), val.clone())];
+            bindings.extend(match_pattern(inner, val)?);
+            Some(bindings)
+        }
         (Pattern::PTuple(pat_elems), Value::Tuple(val_elems)) => {
             if pat_elems.len() != val_elems.len() {
                 return None;
@@ -251,3 +349,527 @@ fn match_pattern(pattern: &Pattern, value: &Value) -> Option<Vec<(String, Value)
 pub fn empty_env() -> Env {
     Rc::new(HashMap::new())
 }
+
+// Top-level definitions of the program being run (interpret.rs). A Var
+// bound neither in its environment nor here is unbound; looking globals up
+// at use, like the emitted functions, lets them call themselves and each
+// other.
+thread_local! {
+    static GLOBALS: RefCell<HashMap<String, Value>> = RefCell::new(HashMap::new());
+}
+
+pub fn define_global(name: &str, value: Value) {
+    GLOBALS.with(|globals| globals.borrow_mut().insert(name.to_string(), value));
+}
+
+/// Forget the definitions of a previous program
+pub fn clear_globals() {
+    GLOBALS.with(|globals| globals.borrow_mut().clear());
+}
+
+pub fn global(name: &str) -> Option<Value> {
+    GLOBALS.with(|globals| globals.borrow().get(name).cloned())
+}
+
+// ============================================================================
+// Primitives
+// ============================================================================
+//
+// Interpreter implementations of the pure primitives, mirroring the bridge
+// runtime (runtime/value.rs, shim.rs) including its fallbacks and errors
+// for ill-typed arguments. UNARY CONTRACT: an n-ary primitive is bound to a curried
+// closure that packs its arguments into one tuple, as the emitters do.
+// Arithmetic follows the bridge's default `--int-overflow wrap` mode.
+
+pub type Native = fn(Vec<Value>) -> Result<Value, i64>;
+
+const PRIMITIVES: &[(&str, usize, Native)] = &[
+    ("__add__", 2, |a| Ok(int_op(a, |x, y| Some(x.wrapping_add(y))))),
+    ("__sub__", 2, |a| Ok(int_op(a, |x, y| Some(x.wrapping_sub(y))))),
+    ("__mul__", 2, |a| Ok(int_op(a, |x, y| Some(x.wrapping_mul(y))))),
+    ("__div__", 2, |a| checked_int_op(a, "division", |x, y| if y == 0 { Some(0) } else { x.checked_div(y) })),
+    ("__mod__", 2, |a| checked_int_op(a, "remainder", |x, y| if y == 0 { Some(0) } else { x.checked_rem(y) })),
+    ("checked_add", 2, |a| checked_option(a, "checked_add", i64::checked_add)),
+    ("axis_checked_add", 2, |a| checked_option(a, "checked_add", i64::checked_add)),
+    ("checked_sub", 2, |a| checked_option(a, "checked_sub", i64::checked_sub)),
+    ("axis_checked_sub", 2, |a| checked_option(a, "checked_sub", i64::checked_sub)),
+    ("checked_mul", 2, |a| checked_option(a, "checked_mul", i64::checked_mul)),
+    ("axis_checked_mul", 2, |a| checked_option(a, "checked_mul", i64::checked_mul)),
+    ("checked_div", 2, |a| checked_option(a, "checked_div", i64::checked_div)),
+    ("axis_checked_div", 2, |a| checked_option(a, "checked_div", i64::checked_div)),
+    ("__eq__", 2, |a| Ok(pair(a).map_or(Value::Bool(false), |(x, y)| Value::Bool(values_equal(&x, &y))))),
+    ("__neq__", 2, |a| Ok(pair(a).map_or(Value::Bool(false), |(x, y)| Value::Bool(!values_equal(&x, &y))))),
+    ("__lt__", 2, |a| compare(a, Ordering::is_lt)),
+    ("__lte__", 2, |a| compare(a, Ordering::is_le)),
+    ("__gt__", 2, |a| compare(a, Ordering::is_gt)),
+    ("__gte__", 2, |a| compare(a, Ordering::is_ge)),
+    ("value_cmp", 2, value_cmp),
+    ("axis_value_cmp", 2, value_cmp),
+    ("list_sort_by", 2, list_sort_by),
+    ("axis_list_sort_by", 2, list_sort_by),
+    ("__and__", 2, |a| Ok(pair(a).map_or(Value::Bool(false), |(x, y)| Value::Bool(truthy(&x) && truthy(&y))))),
+    ("__or__", 2, |a| Ok(pair(a).map_or(Value::Bool(false), |(x, y)| Value::Bool(truthy(&x) || truthy(&y))))),
+    ("__not__", 1, |a| Ok(Value::Bool(!truthy(&single(a))))),
+    ("str_len", 1, str_len),
+    ("axis_str_len", 1, str_len),
+    ("str_concat", 2, str_concat),
+    ("axis_str_concat", 2, str_concat),
+    ("__concat__", 2, str_concat),
+    ("str_split", 2, str_split),
+    ("axis_str_split", 2, str_split),
+    ("str_trim", 1, str_trim),
+    ("axis_str_trim", 1, str_trim),
+    ("str_contains", 2, str_contains),
+    ("axis_str_contains", 2, str_contains),
+    ("str_starts_with", 2, str_starts_with),
+    ("axis_str_starts_with", 2, str_starts_with),
+    ("str_ends_with", 2, str_ends_with),
+    ("axis_str_ends_with", 2, str_ends_with),
+    ("str_replace", 3, str_replace),
+    ("axis_str_replace", 3, str_replace),
+    ("str_to_lower", 1, str_to_lower),
+    ("axis_str_to_lower", 1, str_to_lower),
+    ("str_to_upper", 1, str_to_upper),
+    ("axis_str_to_upper", 1, str_to_upper),
+    ("str_join", 2, str_join),
+    ("axis_str_join", 2, str_join),
+    ("char_is_alpha", 1, |a| classify_char(a, "char_is_alpha", char::is_alphabetic)),
+    ("axis_char_is_alpha", 1, |a| classify_char(a, "char_is_alpha", char::is_alphabetic)),
+    ("char_is_digit", 1, |a| classify_char(a, "char_is_digit", |c| c.is_ascii_digit())),
+    ("axis_char_is_digit", 1, |a| classify_char(a, "char_is_digit", |c| c.is_ascii_digit())),
+    ("char_is_alnum", 1, |a| classify_char(a, "char_is_alnum", |c| c.is_alphabetic() || c.is_ascii_digit())),
+    ("axis_char_is_alnum", 1, |a| classify_char(a, "char_is_alnum", |c| c.is_alphabetic() || c.is_ascii_digit())),
+    ("char_is_whitespace", 1, |a| classify_char(a, "char_is_whitespace", char::is_whitespace)),
+    ("axis_char_is_whitespace", 1, |a| classify_char(a, "char_is_whitespace", char::is_whitespace)),
+    ("char_is_ident_start", 1, |a| classify_char(a, "char_is_ident_start", |c| c.is_alphabetic() || c == '_')),
+    ("axis_char_is_ident_start", 1, |a| classify_char(a, "char_is_ident_start", |c| c.is_alphabetic() || c == '_')),
+    ("char_is_ident_char", 1, |a| classify_char(a, "char_is_ident_char", |c| c.is_alphanumeric() || c == '_')),
+    ("axis_char_is_ident_char", 1, |a| classify_char(a, "char_is_ident_char", |c| c.is_alphanumeric() || c == '_')),
+    ("char_to_digit", 1, char_to_digit),
+    ("axis_char_to_digit", 1, char_to_digit),
+    ("char_to_lower", 1, |a| map_char_case(a, "char_to_lower", |c| c.to_lowercase().collect())),
+    ("axis_char_to_lower", 1, |a| map_char_case(a, "char_to_lower", |c| c.to_lowercase().collect())),
+    ("char_to_upper", 1, |a| map_char_case(a, "char_to_upper", |c| c.to_uppercase().collect())),
+    ("axis_char_to_upper", 1, |a| map_char_case(a, "char_to_upper", |c| c.to_uppercase().collect())),
+    ("char_from_str", 1, char_from_str),
+    ("axis_char_from_str", 1, char_from_str),
+    ("char_to_str", 1, char_to_str),
+    ("axis_char_to_str", 1, char_to_str),
+    ("bytes_len", 1, bytes_len),
+    ("axis_bytes_len", 1, bytes_len),
+    ("bytes_get", 2, bytes_get),
+    ("axis_bytes_get", 2, bytes_get),
+    ("bytes_slice", 3, bytes_slice),
+    ("axis_bytes_slice", 3, bytes_slice),
+    ("int_to_str", 1, |a| match single(a) {
+        Value::Int(n) => Ok(Value::Str(intern_str(n.to_string()))),
+        _ => Ok(Value::Str(0)),
+    }),
+    ("str_to_int", 1, |a| match single(a) {
+        Value::Str(h) => Ok(Value::Int(get_str(h).parse().unwrap_or(0))),
+        _ => Ok(Value::Int(0)),
+    }),
+];
+
+/// Environment binding every primitive the interpreter implements
+pub fn primitive_env() -> Env {
+    let mut env = HashMap::new();
+    bind_primitives(&mut env, PRIMITIVES);
+    Rc::new(env)
+}
+
+/// Bind each (name, arity, implementation) in `env`
+pub fn bind_primitives(env: &mut HashMap<String, Value>, primitives: &[(&str, usize, Native)]) {
+    for (name, arity, native) in primitives {
+        let value = if *arity == 1 {
+            Value::NativeFn(*native)
+        } else {
+            // \#0. ... \#n-1. #native((#0, ..., #n-1)); `#` never starts a user name
+            let params: Vec<String> = (0..*arity).map(|i| format!("#{}", i)).collect();
+            let packed = CoreTerm::Tuple(params.iter().map(|p| CoreTerm::Var(p.into(), None)).collect(), None);
+            let mut body = CoreTerm::App(Rc::new(CoreTerm::Var("#native".into(), None)), Rc::new(packed), None);
+            for param in params[1..].iter().rev() {
+                body = CoreTerm::Lam(param.into(), Rc::new(body), None);
+            }
+            let mut native_env = HashMap::new();
+            native_env.insert("#native".to_string(), Value::NativeFn(*native));
+            Value::Closure(Rc::new(native_env), params[0].clone(), Rc::new(body))
+        };
+        env.insert(name.to_string(), value);
+    }
+}
+
+/// Structural equality of data values; functions are never equal
+pub fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => x == y,
+        (Value::Bool(x), Value::Bool(y)) => x == y,
+        (Value::Str(x), Value::Str(y)) => x == y,
+        (Value::Bytes(x), Value::Bytes(y)) => x == y,
+        (Value::Unit, Value::Unit) => true,
+        (Value::Tuple(xs), Value::Tuple(ys)) => {
+            xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| values_equal(x, y))
+        }
+        (Value::Enum(t1, xs), Value::Enum(t2, ys)) => {
+            t1 == t2 && xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| values_equal(x, y))
+        }
+        _ => false,
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Int(n) => *n != 0,
+        Value::Str(h) => *h != 0,
+        Value::Bytes(data) => !data.is_empty(),
+        Value::Tuple(elems) => !elems.is_empty(),
+        _ => false,
+    }
+}
+
+fn single(args: Vec<Value>) -> Value {
+    args.into_iter().next().unwrap_or(Value::Unit)
+}
+
+// The two fields of a packed (x, y) argument
+fn pair(args: Vec<Value>) -> Option<(Value, Value)> {
+    match single(args) {
+        Value::Tuple(elems) if elems.len() >= 2 => {
+            let mut elems = elems.into_iter();
+            Some((elems.next()?, elems.next()?))
+        }
+        _ => None,
+    }
+}
+
+fn int_pair(args: Vec<Value>) -> Option<(i64, i64)> {
+    match pair(args)? {
+        (Value::Int(x), Value::Int(y)) => Some((x, y)),
+        _ => None,
+    }
+}
+
+fn int_op(args: Vec<Value>, op: fn(i64, i64) -> Option<i64>) -> Value {
+    Value::Int(int_pair(args).and_then(|(x, y)| op(x, y)).unwrap_or(0))
+}
+
+// Like int_op, but an overflowing result (i64::MIN / -1) is a runtime error
+fn checked_int_op(args: Vec<Value>, what: &str, op: fn(i64, i64) -> Option<i64>) -> Result<Value, i64> {
+    match int_pair(args) {
+        Some((x, y)) => op(x, y)
+            .map(Value::Int)
+            .ok_or_else(|| -intern_str(format!("{} overflow: {} by {}", what, x, y))),
+        None => Ok(Value::Int(0)),
+    }
+}
+
+// Some(x op y), or None when it overflows or divides by zero
+fn checked_option(args: Vec<Value>, what: &str, op: fn(i64, i64) -> Option<i64>) -> Result<Value, i64> {
+    let (x, y) = int_pair(args).ok_or_else(|| -intern_str(format!("{} expects two Ints", what)))?;
+    Ok(match op(x, y) {
+        Some(n) => Value::Enum("Some".to_string(), vec![Value::Int(n)]),
+        None => Value::Enum("None".to_string(), vec![]),
+    })
+}
+
+// The order of two values, as the bridge's value_cmp has it
+// (rust-bridge/src/runtime/order.rs): by kind, then by contents; Nil
+// before any other constructor. The interpreter has no List or
+// StringBuilder values, so of the bridge's ranks only Unit (0) < Bool (1)
+// < Int (2) < Str (3) < Bytes (4) < Tuple (5) < Ctor (7) occur here.
+// Functions have no order.
+pub fn compare_values(a: &Value, b: &Value) -> Result<Ordering, i64> {
+    let rank = |value: &Value| match value {
+        Value::Unit => Some(0),
+        Value::Bool(_) => Some(1),
+        Value::Int(_) => Some(2),
+        Value::Str(_) => Some(3),
+        Value::Bytes(_) => Some(4),
+        Value::Tuple(_) => Some(5),
+        Value::Enum(..) => Some(7),
+        _ => None,
+    };
+    let all = |xs: &[Value], ys: &[Value]| -> Result<Ordering, i64> {
+        for (x, y) in xs.iter().zip(ys) {
+            let order = compare_values(x, y)?;
+            if order.is_ne() {
+                return Ok(order);
+            }
+        }
+        Ok(xs.len().cmp(&ys.len()))
+    };
+    match (a, b) {
+        (Value::Unit, Value::Unit) => Ok(Ordering::Equal),
+        (Value::Bool(x), Value::Bool(y)) => Ok(x.cmp(y)),
+        (Value::Int(x), Value::Int(y)) => Ok(x.cmp(y)),
+        (Value::Str(x), Value::Str(y)) => Ok(get_str(*x).cmp(&get_str(*y))),
+        (Value::Bytes(x), Value::Bytes(y)) => Ok(x.cmp(y)),
+        (Value::Tuple(xs), Value::Tuple(ys)) => all(xs, ys),
+        (Value::Enum(t1, xs), Value::Enum(t2, ys)) => {
+            let by_tag = (t1 != "Nil").cmp(&(t2 != "Nil")).then_with(|| t1.cmp(t2));
+            if by_tag.is_ne() {
+                return Ok(by_tag);
+            }
+            all(xs, ys)
+        }
+        _ => match (rank(a), rank(b)) {
+            (Some(x), Some(y)) => Ok(x.cmp(&y)),
+            _ => Err(-intern_str("cannot order a function".to_string())),
+        },
+    }
+}
+
+// a < b and the like; false unless given a pair
+fn compare(args: Vec<Value>, holds: fn(Ordering) -> bool) -> Result<Value, i64> {
+    match pair(args) {
+        Some((x, y)) => Ok(Value::Bool(holds(compare_values(&x, &y)?))),
+        None => Ok(Value::Bool(false)),
+    }
+}
+
+fn value_cmp(args: Vec<Value>) -> Result<Value, i64> {
+    let (x, y) = pair(args).ok_or_else(|| -intern_str("value_cmp expects (a, b)".to_string()))?;
+    Ok(Value::Int(compare_values(&x, &y)? as i64))
+}
+
+// list_sort_by(cmp, xs): xs sorted stably by cmp(a, b), an Int below,
+// equal to or above 0 as a goes before, with or after b
+fn list_sort_by(args: Vec<Value>) -> Result<Value, i64> {
+    let (cmp, list) = pair(args).ok_or_else(|| -intern_str("list_sort_by expects (cmp, xs)".to_string()))?;
+    let items = list_items(list, "list_sort_by")?;
+    let before = |a: &Value, b: &Value| match apply(apply(cmp.clone(), a.clone())?, b.clone())? {
+        Value::Int(order) => Ok(order < 0),
+        _ => Err(-intern_str("list_sort_by: the compare function must return an Int".to_string())),
+    };
+    Ok(cons_list(merge_sort(items, &before)?))
+}
+
+// The elements of a Nil/Cons list
+fn list_items(list: Value, what: &str) -> Result<Vec<Value>, i64> {
+    let mut items = Vec::new();
+    let mut rest = list;
+    while let Value::Enum(tag, mut fields) = rest {
+        match (tag.as_str(), fields.len()) {
+            ("Cons", 2) => {
+                rest = fields.pop().unwrap_or(Value::Unit);
+                items.extend(fields.pop());
+            }
+            ("Nil", 0) => return Ok(items),
+            _ => break,
+        }
+    }
+    Err(-intern_str(format!("{}: expected a list", what)))
+}
+
+fn cons_list(items: Vec<Value>) -> Value {
+    items.into_iter().rev().fold(Value::Enum("Nil".to_string(), Vec::new()), |tail, item| {
+        Value::Enum("Cons".to_string(), vec![item, tail])
+    })
+}
+
+// Stable merge sort, as the bridge's (order.rs): it never fails on a cmp
+// that is not a consistent order
+fn merge_sort(mut items: Vec<Value>, before: &dyn Fn(&Value, &Value) -> Result<bool, i64>) -> Result<Vec<Value>, i64> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let right = merge_sort(items.split_off(items.len() / 2), before)?;
+    let left = merge_sort(items, before)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(x), Some(y)) = (left.peek(), right.peek()) {
+        let next = if before(y, x)? { right.next() } else { left.next() };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+fn str_len(args: Vec<Value>) -> Result<Value, i64> {
+    match single(args) {
+        Value::Str(h) => Ok(Value::Int(get_str(h).chars().count() as i64)),
+        _ => Err(-intern_str("str_len: argument must be a string".to_string())),
+    }
+}
+
+fn str_concat(args: Vec<Value>) -> Result<Value, i64> {
+    match pair(args) {
+        Some((Value::Str(a), Value::Str(b))) => Ok(Value::Str(intern_str(format!("{}{}", get_str(a), get_str(b))))),
+        _ => Err(-intern_str("str_concat: arguments must be strings".to_string())),
+    }
+}
+
+// Byte buffers, as the bridge's (rust-bridge/src/runtime/value.rs),
+// including its fallbacks for other arguments
+
+fn bytes_len(args: Vec<Value>) -> Result<Value, i64> {
+    match single(args) {
+        Value::Bytes(data) => Ok(Value::Int(data.len() as i64)),
+        _ => Ok(Value::Int(0)),
+    }
+}
+
+// The byte at an index as an Int in 0..=255, or -1 out of range
+fn bytes_get(args: Vec<Value>) -> Result<Value, i64> {
+    match pair(args) {
+        Some((Value::Bytes(data), Value::Int(i))) if i >= 0 => {
+            Ok(Value::Int(data.get(i as usize).map_or(-1, |b| *b as i64)))
+        }
+        _ => Ok(Value::Int(-1)),
+    }
+}
+
+// Bytes start..end, the indices clamped to the buffer
+fn bytes_slice(args: Vec<Value>) -> Result<Value, i64> {
+    match single(args) {
+        Value::Tuple(fields) if fields.len() >= 3 => match (&fields[0], &fields[1], &fields[2]) {
+            (Value::Bytes(data), Value::Int(start), Value::Int(end)) => {
+                let len = data.len() as i64;
+                let (start, end) = ((*start).clamp(0, len) as usize, (*end).clamp(0, len) as usize);
+                Ok(Value::Bytes(if start < end { data[start..end].to_vec() } else { Vec::new() }))
+            }
+            _ => Ok(Value::Bytes(Vec::new())),
+        },
+        _ => Ok(Value::Bytes(Vec::new())),
+    }
+}
+
+// String utilities, as the bridge's (rust-bridge/src/runtime/strings.rs)
+
+// The texts of a packed (Str, ...) argument, n of them
+fn texts(args: Vec<Value>, n: usize, what: &str) -> Result<Vec<String>, i64> {
+    let fields = match single(args) {
+        Value::Tuple(fields) if n > 1 => fields,
+        value if n == 1 => vec![value],
+        _ => Vec::new(),
+    };
+    let texts: Vec<String> = fields.iter().take(n).filter_map(|field| match field {
+        Value::Str(h) => Some(get_str(*h)),
+        _ => None,
+    }).collect();
+    if texts.len() == n {
+        Ok(texts)
+    } else {
+        Err(-intern_str(format!("{}: arguments must be strings", what)))
+    }
+}
+
+fn str_value(text: &str) -> Value {
+    Value::Str(intern_str(text.to_string()))
+}
+
+fn str_split(args: Vec<Value>) -> Result<Value, i64> {
+    let t = texts(args, 2, "str_split")?;
+    let parts: Vec<Value> = if t[1].is_empty() {
+        t[0].chars().map(|c| str_value(&c.to_string())).collect()
+    } else {
+        t[0].split(t[1].as_str()).map(str_value).collect()
+    };
+    Ok(cons_list(parts))
+}
+
+fn str_trim(args: Vec<Value>) -> Result<Value, i64> {
+    Ok(str_value(texts(args, 1, "str_trim")?[0].trim()))
+}
+
+fn str_contains(args: Vec<Value>) -> Result<Value, i64> {
+    let t = texts(args, 2, "str_contains")?;
+    Ok(Value::Bool(t[0].contains(t[1].as_str())))
+}
+
+fn str_starts_with(args: Vec<Value>) -> Result<Value, i64> {
+    let t = texts(args, 2, "str_starts_with")?;
+    Ok(Value::Bool(t[0].starts_with(t[1].as_str())))
+}
+
+fn str_ends_with(args: Vec<Value>) -> Result<Value, i64> {
+    let t = texts(args, 2, "str_ends_with")?;
+    Ok(Value::Bool(t[0].ends_with(t[1].as_str())))
+}
+
+fn str_replace(args: Vec<Value>) -> Result<Value, i64> {
+    let t = texts(args, 3, "str_replace")?;
+    if t[1].is_empty() {
+        return Ok(str_value(&t[0]));
+    }
+    Ok(str_value(&t[0].replace(t[1].as_str(), &t[2])))
+}
+
+fn str_to_lower(args: Vec<Value>) -> Result<Value, i64> {
+    Ok(str_value(&texts(args, 1, "str_to_lower")?[0].to_lowercase()))
+}
+
+fn str_to_upper(args: Vec<Value>) -> Result<Value, i64> {
+    Ok(str_value(&texts(args, 1, "str_to_upper")?[0].to_uppercase()))
+}
+
+fn str_join(args: Vec<Value>) -> Result<Value, i64> {
+    let (parts, sep) = match pair(args) {
+        Some((parts, Value::Str(sep))) => (list_items(parts, "str_join")?, get_str(sep)),
+        _ => return Err(-intern_str("str_join expects (List[Str], Str)".to_string())),
+    };
+    let parts = parts.into_iter().map(|part| match part {
+        Value::Str(h) => Ok(get_str(h)),
+        _ => Err(-intern_str("str_join: parts must be strings".to_string())),
+    }).collect::<Result<Vec<String>, i64>>()?;
+    Ok(str_value(&parts.join(&sep)))
+}
+
+// The character of an Int code point or one-character Str argument, as
+// runtime/chars.rs; None for an Int that is no code point
+fn char_arg(args: Vec<Value>, what: &str) -> Result<(Value, Option<char>), i64> {
+    let value = single(args);
+    let c = match &value {
+        Value::Int(n) => u32::try_from(*n).ok().and_then(char::from_u32),
+        Value::Str(h) => {
+            let text = get_str(*h);
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => return Err(-intern_str(format!("{}: expected one character", what))),
+            }
+        }
+        _ => return Err(-intern_str(format!("{}: expected a character", what))),
+    };
+    Ok((value, c))
+}
+
+fn classify_char(args: Vec<Value>, what: &str, test: fn(char) -> bool) -> Result<Value, i64> {
+    Ok(Value::Bool(char_arg(args, what)?.1.is_some_and(test)))
+}
+
+fn char_to_digit(args: Vec<Value>) -> Result<Value, i64> {
+    let digit = char_arg(args, "char_to_digit")?.1.and_then(|c| c.to_digit(10));
+    Ok(Value::Int(digit.map_or(-1, i64::from)))
+}
+
+fn map_char_case(args: Vec<Value>, what: &str, map: fn(char) -> String) -> Result<Value, i64> {
+    let (value, c) = char_arg(args, what)?;
+    let Some(c) = c else {
+        return Ok(value);
+    };
+    let mapped = map(c);
+    let mut chars = mapped.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(one), None) => one,
+        _ => c,
+    };
+    Ok(Value::Int(code as i64))
+}
+
+fn char_from_str(args: Vec<Value>) -> Result<Value, i64> {
+    Ok(Value::Int(texts(args, 1, "char_from_str")?[0].chars().next().map_or(-1, |c| c as i64)))
+}
+
+// shim::char_to_str: the one-character string of a code point; a surrogate
+// is U+FFFD and anything else neither a code point nor a Str is ""
+fn char_to_str(args: Vec<Value>) -> Result<Value, i64> {
+    Ok(match single(args) {
+        value @ Value::Str(_) => value,
+        Value::Int(n @ 0..=0x10FFFF) => str_value(&char::from_u32(n as u32).unwrap_or('\u{FFFD}').to_string()),
+        _ => str_value(""),
+    })
+}
@@ -14,6 +14,7 @@ pub enum Value {
     Tuple(Vec<Value>),
     List(Vec<Value>),
     Ctor { tag: u32, fields: Vec<Value> }, // Constructor with tag and fields
+    Bytes(Vec<u8>), // Raw binary data (not interned)
 }
 
 impl Value {
@@ -52,6 +53,13 @@ impl Value {
         }
     }
 
+    pub fn as_bytes(&self) -> &Vec<u8> {
+        match self {
+            Value::Bytes(data) => data,
+            _ => panic!("Expected Bytes, got {:?}", self),
+        }
+    }
+
     pub fn as_ctor(&self) -> (u32, &Vec<Value>) {
         match self {
             Value::Ctor { tag, fields } => (*tag, fields),
@@ -180,5 +188,6 @@ pub fn truthy(v: &Value) -> bool {
         Value::Tuple(elems) => !elems.is_empty(),
         Value::List(elems) => !elems.is_empty(),
         Value::Ctor { .. } => true, // All constructors are truthy
+        Value::Bytes(data) => !data.is_empty(), // Empty buffer is falsy
     }
 }
\ No newline at end of file
@@ -0,0 +1,418 @@
+// Compiler sessions: the pipeline of `axis-compiler`, used by the CLI
+// (main.rs, `test` included) and by callers that compile more than once
+// (watch mode, the LSP)
+//
+// A Session keeps what does not change between compiles:
+//   - the parsed registries, by path list, reloaded when a file's
+//     modification time changes
+//   - the parsed prelude (prelude.rs)
+//   - the process string table (string_table.rs), so the string handles
+//     in one compile's Core stay valid in the next
+//
+// compile() runs every check from parsing to validation and returns the
+// Core with its warnings; optimizing and writing the bundle are left to
+// the caller. compile_with() also calls a CompileHooks between phases,
+// where the CLI writes its phase dumps and `test` swaps the entry point
+// for its harness. compile_core() checks Core that was not compiled from
+// sources (--from-core-text).
+
+use crate::constructors::{self, ConstructorTable};
+use crate::core_validator::{self, ValidationError, ValidationReport};
+use crate::diagnostics::{Diagnostic, SourceMap};
+use crate::prelude::Prelude;
+use crate::registry_loader::{Capability, Registry, RegistryError};
+use crate::runtime::{CoreTerm, Value};
+use crate::surface_parser::{self, Module, ParseError};
+use crate::trace;
+use crate::{binders, core_interface, imports, init_string_table, memo, surface_lower, surface_to_core};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+pub struct Session {
+    registries: HashMap<Vec<String>, LoadedRegistry>,
+    prelude: Prelude,
+}
+
+struct LoadedRegistry {
+    registry: Rc<Registry>,
+    // Modification time of each file, None when it could not be read
+    stamps: Vec<Option<SystemTime>>,
+}
+
+/// What the CLI takes from its flags
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    /// Registry files; none means the built-in prelude registry
+    pub registries: Vec<String>,
+    /// false for --no-prelude
+    pub prelude: bool,
+    /// --allow-duplicate-defs
+    pub allow_duplicates: bool,
+    /// --allow: the capabilities the program may use, or None for any
+    pub allowed: Option<Vec<Capability>>,
+    /// --separate: names the registry does not define are left for `link`
+    /// as imports, prelude functions included
+    pub separate: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        CompileOptions { registries: Vec::new(), prelude: true, allow_duplicates: false, allowed: None, separate: false }
+    }
+}
+
+/// A caller's look at the program between phases. Every method does
+/// nothing by default; a Break ends the compile with
+/// CompileError::Stopped, the reason left with the hooks.
+pub trait CompileHooks {
+    /// The sources, concatenated, before parsing
+    fn source(&mut self, _source: &str) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// The parsed module, before any check
+    fn parsed(&mut self, _module: &Module) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// The module with its imports resolved, before the prelude is added
+    /// and the entry point looked up
+    fn resolved(&mut self, _module: &mut Module) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// The lowered program, before it becomes a CoreTerm
+    fn lowered(&mut self, value: Value) -> ControlFlow<(), Value> {
+        ControlFlow::Continue(value)
+    }
+
+    /// The CoreTerm, before validation
+    fn core(&mut self, _core: &CoreTerm) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// No hooks: compile() as is
+pub struct NoHooks;
+
+impl CompileHooks for NoHooks {}
+
+/// A program that validated
+#[derive(Debug)]
+pub struct Compiled {
+    pub core: CoreTerm,
+    /// The function the program starts at, if it names one
+    pub entry: Option<String>,
+    /// Constructors of the program's `type` declarations, for the bundle
+    pub constructors: ConstructorTable,
+    /// Functions marked @inline, for the optimizer
+    pub inline: HashSet<String>,
+    /// Functions without `pub`, the entry point aside: private exports
+    pub private: HashSet<String>,
+    /// Names left for `link` (--separate)
+    pub imports: Vec<String>,
+    /// The warnings, located and sorted
+    pub warnings: ValidationReport,
+}
+
+#[derive(Debug)]
+pub enum CompileError {
+    Registry(RegistryError),
+    Parse(ParseError),
+    /// Every finding, located and sorted; at least one is an error
+    Validation(ValidationReport),
+    /// A hook ended the compile; the warnings found until then
+    Stopped(ValidationReport),
+}
+
+impl CompileError {
+    /// The diagnostics to render, in order
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            CompileError::Registry(error) => vec![Diagnostic::error(None, format!("Failed to load registries: {}", error))],
+            CompileError::Parse(error) => {
+                std::iter::once(error).chain(error.following()).map(Diagnostic::from).collect()
+            }
+            CompileError::Validation(report) | CompileError::Stopped(report) => {
+                report.findings.iter().map(|finding| finding.to_diagnostic()).collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Registry(error) => write!(f, "Failed to load registries: {}", error),
+            CompileError::Parse(error) => write!(f, "{}", error),
+            CompileError::Validation(report) => write!(f, "VALIDATION ERROR: {}", report),
+            CompileError::Stopped(_) => write!(f, "compile stopped"),
+        }
+    }
+}
+
+fn stamps(paths: &[String]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+        .collect()
+}
+
+impl Session {
+    pub fn new() -> Self {
+        init_string_table();
+        Session { registries: HashMap::new(), prelude: Prelude::parse() }
+    }
+
+    /// The registry of `paths`, loaded on first use and again after one of
+    /// the files changed
+    pub fn registry(&mut self, paths: &[String]) -> Result<Rc<Registry>, RegistryError> {
+        let current = stamps(paths);
+        if let Some(loaded) = self.registries.get(paths)
+            && loaded.stamps == current
+        {
+            return Ok(Rc::clone(&loaded.registry));
+        }
+        let registry = Rc::new(Registry::load_or_prelude(paths)?);
+        self.registries
+            .insert(paths.to_vec(), LoadedRegistry { registry: Rc::clone(&registry), stamps: current });
+        Ok(registry)
+    }
+
+    pub fn prelude(&self) -> &Prelude {
+        &self.prelude
+    }
+
+    /// Compile `sources` (file name, text) as one program, as the CLI
+    /// compiles the files it is given
+    pub fn compile(&mut self, sources: &[(String, String)], options: &CompileOptions) -> Result<Compiled, CompileError> {
+        self.compile_with(sources, options, &mut NoHooks)
+    }
+
+    /// compile(), calling `hooks` between phases
+    pub fn compile_with(
+        &mut self,
+        sources: &[(String, String)],
+        options: &CompileOptions,
+        hooks: &mut dyn CompileHooks,
+    ) -> Result<Compiled, CompileError> {
+        let reg = self.registry(&options.registries).map_err(CompileError::Registry)?;
+        let mut full_source = String::new();
+        for (_, text) in sources {
+            full_source.push_str(text);
+            full_source.push('\n');
+        }
+        let input_path = sources.first().map_or("<input>", |(file, _)| file.as_str());
+        let source_map = SourceMap::concatenated(sources);
+        let mut report = ValidationReport::default();
+        // A surface check that failed: its error ends the compile
+        let fail = |mut report: ValidationReport, mut error: ValidationError| {
+            error.locate_name(&full_source, input_path);
+            report.push(error);
+            report.sort_by_location();
+            CompileError::Validation(report)
+        };
+        let stopped = |mut report: ValidationReport| {
+            report.locate_names(&full_source, input_path);
+            report.sort_by_location();
+            CompileError::Stopped(report)
+        };
+
+        if hooks.source(&full_source).is_break() {
+            return Err(stopped(report));
+        }
+        let parse_span = trace::span_with("parse", &sources.iter().map(|(file, _)| file.as_str()).collect::<Vec<_>>().join(" "));
+        let mut module =
+            surface_parser::parse_module_with_file(&full_source, input_path).map_err(CompileError::Parse)?;
+        drop(parse_span);
+        if hooks.parsed(&module).is_break() {
+            return Err(stopped(report));
+        }
+        // A function defined twice, e.g. by two concatenated files
+        match surface_lower::check_duplicates(&mut module, &full_source, input_path, &source_map, options.allow_duplicates) {
+            Ok(warnings) => warnings.into_iter().for_each(|warning| report.push(warning)),
+            Err(error) => return Err(fail(report, error)),
+        }
+        let resolve_span = trace::span("resolve imports");
+        if let Err(error) = imports::resolve(&mut module, &reg) {
+            return Err(fail(report, error));
+        }
+        drop(resolve_span);
+        if hooks.resolved(&mut module).is_break() {
+            return Err(stopped(report));
+        }
+        // The program gets the prelude functions it reaches; a separate
+        // unit imports them and `link` adds them
+        if options.prelude {
+            let _span = trace::span("prelude");
+            for warning in self.prelude.shadowing_warnings(&module) {
+                report.push(warning);
+            }
+            if !options.separate {
+                self.prelude.add_reachable(&mut module);
+            }
+        }
+
+        let entry = module
+            .entry_function()
+            .map(|f| f.name.as_str())
+            .or_else(|| ["main", "axis_entry"].into_iter().find(|name| module.functions.iter().any(|f| f.name == *name)))
+            .map(str::to_string);
+        let inline = module.functions.iter().filter(|f| f.has_attribute("inline")).map(|f| f.name.clone()).collect();
+        let private = module
+            .functions
+            .iter()
+            .filter(|f| !f.public && f.name != "axis_entry" && Some(&f.name) != entry.as_ref())
+            .map(|f| f.name.clone())
+            .collect();
+        let prelude_functions: HashSet<String> =
+            module.functions.iter().filter(|f| f.has_attribute("prelude")).map(|f| f.name.clone()).collect();
+        let check_span = trace::span("check surface");
+        let constructors = match ConstructorTable::from_types(&module.types) {
+            Ok(table) => table,
+            Err(e) => return Err(fail(report, ValidationError::new(e))),
+        };
+        if let Err(error) = core_validator::check_let_patterns(&module, &constructors) {
+            return Err(fail(report, error));
+        }
+        if let Err(error) = memo::check(&module, &reg) {
+            return Err(fail(report, error));
+        }
+        drop(check_span);
+
+        let lower_span = trace::span("lower");
+        let lowered = surface_lower::lower_module(module);
+        drop(lower_span);
+        let lowered = match hooks.lowered(lowered) {
+            ControlFlow::Continue(lowered) => lowered,
+            ControlFlow::Break(()) => return Err(stopped(report)),
+        };
+        let core = {
+            let _span = trace::span("value to core");
+            surface_to_core::value_to_core(&lowered)
+        };
+        let program = Program {
+            constructors,
+            entry,
+            inline,
+            private,
+            prelude_functions,
+            surface: Some((&full_source, input_path)),
+            source_map: &source_map,
+        };
+        self.check(core, &reg, program, report, options, hooks)
+    }
+
+    /// Check Core that was not compiled from sources, as the CLI checks
+    /// --from-core-text: it has no types, prelude or source to locate
+    /// findings in
+    pub fn compile_core(
+        &mut self,
+        core: CoreTerm,
+        options: &CompileOptions,
+        hooks: &mut dyn CompileHooks,
+    ) -> Result<Compiled, CompileError> {
+        let reg = self.registry(&options.registries).map_err(CompileError::Registry)?;
+        let program = Program {
+            constructors: ConstructorTable::default(),
+            entry: None,
+            inline: HashSet::new(),
+            private: HashSet::new(),
+            prelude_functions: HashSet::new(),
+            surface: None,
+            source_map: &SourceMap::default(),
+        };
+        self.check(core, &reg, program, ValidationReport::default(), options, hooks)
+    }
+
+    // Validation of the Core: every finding is kept, sorted by location,
+    // and any error fails the compile
+    fn check(
+        &self,
+        core: CoreTerm,
+        reg: &Registry,
+        program: Program<'_>,
+        mut report: ValidationReport,
+        options: &CompileOptions,
+        hooks: &mut dyn CompileHooks,
+    ) -> Result<Compiled, CompileError> {
+        if hooks.core(&core).is_break() {
+            if let Some((source, input_path)) = program.surface {
+                report.locate_names(source, input_path);
+            }
+            report.sort_by_location();
+            return Err(CompileError::Stopped(report));
+        }
+        let _span = trace::span("validate");
+        // Separate units defer unresolved names to link time as imports
+        let imports = if options.separate { core_interface::unresolved_names(&core, reg) } else { Vec::new() };
+        for finding in core_validator::validate_core_with_imports(&core, reg, &imports).findings {
+            report.push(finding);
+        }
+        // Constructor references under their canonical names from here on
+        let core = if report.has_errors() {
+            core
+        } else {
+            match constructors::qualify(&core, &program.constructors) {
+                Ok(qualified) => {
+                    if let Err(error) = core_validator::check_constructors(&qualified, &program.constructors) {
+                        report.push(error);
+                    }
+                    qualified
+                }
+                Err(error) => {
+                    report.push(error);
+                    core
+                }
+            }
+        };
+        if let Some(allowed) = &options.allowed
+            && let Err(error) = core_validator::check_capabilities(&core, reg, allowed)
+        {
+            report.push(error);
+        }
+        let entry_name = program.entry.as_deref().unwrap_or("axis_entry");
+        for warning in
+            binders::binding_warnings(&core, &program.prelude_functions, entry_name, program.surface, program.source_map)
+        {
+            report.push(warning);
+        }
+        if let Some((source, input_path)) = program.surface {
+            report.locate_names(source, input_path);
+        }
+        report.sort_by_location();
+        if report.has_errors() {
+            return Err(CompileError::Validation(report));
+        }
+        Ok(Compiled {
+            core,
+            entry: program.entry,
+            constructors: program.constructors,
+            inline: program.inline,
+            private: program.private,
+            imports,
+            warnings: report,
+        })
+    }
+}
+
+// What the surface half of a compile knows about the program
+struct Program<'a> {
+    constructors: ConstructorTable,
+    entry: Option<String>,
+    inline: HashSet<String>,
+    private: HashSet<String>,
+    prelude_functions: HashSet<String>,
+    // The concatenated source and the first file's name
+    surface: Option<(&'a str, &'a str)>,
+    source_map: &'a SourceMap,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session::new()
+    }
+}
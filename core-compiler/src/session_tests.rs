@@ -0,0 +1,175 @@
+// Tests for compiler sessions (session.rs)
+
+use crate::diagnostics::Severity;
+use crate::error_codes::ErrorCode;
+use crate::registry_loader::PRELUDE_REGISTRY;
+use crate::runtime::Value;
+use crate::session::{CompileError, CompileHooks, CompileOptions, Session};
+use crate::surface_parser::Module;
+use std::fs;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+fn sources(text: &str) -> Vec<(String, String)> {
+    vec![("main.ax".to_string(), text.to_string())]
+}
+
+fn codes(error: CompileError) -> Vec<Option<ErrorCode>> {
+    error.diagnostics().into_iter().map(|diagnostic| diagnostic.code).collect()
+}
+
+#[test]
+fn test_registries_are_kept_until_a_file_changes() {
+    let path = std::env::temp_dir().join(format!("axis-session-{}.axreg", std::process::id()));
+    fs::write(&path, PRELUDE_REGISTRY).unwrap();
+    let paths = vec![path.to_string_lossy().into_owned()];
+
+    let mut session = Session::new();
+    let first = session.registry(&paths).unwrap();
+    assert!(Rc::ptr_eq(&first, &session.registry(&paths).unwrap()));
+
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+    let reloaded = session.registry(&paths).unwrap();
+    assert!(!Rc::ptr_eq(&first, &reloaded));
+    assert!(Rc::ptr_eq(&reloaded, &session.registry(&paths).unwrap()));
+
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(session.registry(&paths), Err(error) if error.file == paths[0]));
+}
+
+#[test]
+fn test_compiles_share_a_session() {
+    let mut session = Session::new();
+    let options = CompileOptions::default();
+    let source = "fn main(args: Unit) -> Int {\n    list_sum(list_range(0, 5))\n}\n";
+    let first = session.compile(&sources(source), &options).unwrap();
+    let second = session.compile(&sources(source), &options).unwrap();
+    assert_eq!(first.entry.as_deref(), Some("main"));
+    assert_eq!(format!("{:?}", first.core), format!("{:?}", second.core));
+    assert!(first.warnings.findings.is_empty());
+
+    // Without the prelude its functions are unbound
+    let options = CompileOptions { prelude: false, ..CompileOptions::default() };
+    let error = session.compile(&sources(source), &options).unwrap_err();
+    assert!(codes(error).contains(&Some(ErrorCode::UnboundVar)));
+}
+
+#[test]
+fn test_parse_errors_stop_the_compile() {
+    let error = Session::new().compile(&sources("fn main(args) {\n"), &CompileOptions::default()).unwrap_err();
+    assert!(matches!(error, CompileError::Parse(_)));
+    assert_eq!(error.diagnostics()[0].span.as_ref().map(|span| span.file.as_str()), Some("main.ax"));
+}
+
+#[test]
+fn test_every_validation_finding_is_returned() {
+    let source = "fn main(args) {\n    let unused = 1;\n    lst_cons(1, nil_list) + count(args)\n}\n";
+    let error = Session::new().compile(&sources(source), &CompileOptions::default()).unwrap_err();
+    let diagnostics = error.diagnostics();
+    let unbound: Vec<usize> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.code == Some(ErrorCode::UnboundVar))
+        .map(|diagnostic| diagnostic.span.as_ref().expect("located in the source").column)
+        .collect();
+    assert_eq!(unbound.len(), 3, "{}", error);
+    assert!(unbound.is_sorted());
+    assert!(diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Warning));
+}
+
+#[test]
+fn test_warnings_come_with_the_core() {
+    let source = "fn list_sum(xs) { xs }\nfn main(args) {\n    let unused = 1;\n    list_sum(args)\n}\n";
+    let compiled = Session::new().compile(&sources(source), &CompileOptions::default()).unwrap();
+    let warnings: Vec<_> = compiled.warnings.findings.iter().map(|finding| finding.code).collect();
+    assert_eq!(warnings, vec![Some(ErrorCode::ShadowsPrelude), Some(ErrorCode::UnusedBinding)]);
+}
+
+#[test]
+fn test_compiles_reuse_the_registry_and_prelude() {
+    let path = std::env::temp_dir().join(format!("axis-session-reuse-{}.axreg", std::process::id()));
+    fs::write(&path, PRELUDE_REGISTRY).unwrap();
+    let options = CompileOptions { registries: vec![path.to_string_lossy().into_owned()], ..CompileOptions::default() };
+    let source = "fn main(args: Unit) -> Int {\n    list_sum(list_range(0, 5))\n}\n";
+
+    let mut session = Session::new();
+    let registry = session.registry(&options.registries).unwrap();
+    let prelude = session.prelude().functions().as_ptr();
+    session.compile(&sources(source), &options).unwrap();
+    session.compile(&sources(source), &options).unwrap();
+    // Neither compile loaded the registry or parsed the prelude again
+    assert!(Rc::ptr_eq(&registry, &session.registry(&options.registries).unwrap()));
+    assert_eq!(session.prelude().functions().as_ptr(), prelude);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_separate_units_leave_unknown_names_as_imports() {
+    let source = "fn main(args) {\n    helper(list_sum(args))\n}\n";
+    let mut session = Session::new();
+    let error = session.compile(&sources(source), &CompileOptions::default()).unwrap_err();
+    assert!(codes(error).contains(&Some(ErrorCode::UnboundVar)));
+
+    let options = CompileOptions { separate: true, ..CompileOptions::default() };
+    let compiled = session.compile(&sources(source), &options).unwrap();
+    // The prelude is linked in later, not added to the unit
+    assert_eq!(compiled.imports, vec!["helper".to_string(), "list_sum".to_string()]);
+}
+
+// Records the phases it saw; stops at `stop_at` and swaps the entry
+// point for one returning 7 after lowering
+#[derive(Default)]
+struct Recorder {
+    phases: Vec<&'static str>,
+    stop_at: Option<&'static str>,
+}
+
+impl Recorder {
+    fn saw(&mut self, phase: &'static str) -> ControlFlow<()> {
+        self.phases.push(phase);
+        if self.stop_at == Some(phase) { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    }
+}
+
+impl CompileHooks for Recorder {
+    fn source(&mut self, _source: &str) -> ControlFlow<()> {
+        self.saw("source")
+    }
+
+    fn parsed(&mut self, _module: &Module) -> ControlFlow<()> {
+        self.saw("parsed")
+    }
+
+    fn resolved(&mut self, module: &mut Module) -> ControlFlow<()> {
+        module.functions.retain(|f| f.name != "axis_entry");
+        self.saw("resolved")
+    }
+
+    fn lowered(&mut self, value: Value) -> ControlFlow<(), Value> {
+        self.saw("lowered")?;
+        let entry = Value::Lam("args".to_string(), Box::new(Value::Int(7)));
+        ControlFlow::Continue(Value::Let("axis_entry".to_string(), Box::new(entry), Box::new(value)))
+    }
+
+    fn core(&mut self, _core: &crate::runtime::CoreTerm) -> ControlFlow<()> {
+        self.saw("core")
+    }
+}
+
+#[test]
+fn test_hooks_see_every_phase_and_can_stop_the_compile() {
+    let source = "fn axis_entry(args) {\n    let unused = 1;\n    0\n}\n";
+    let mut session = Session::new();
+    let mut hooks = Recorder::default();
+    let compiled = session.compile_with(&sources(source), &CompileOptions::default(), &mut hooks).unwrap();
+    assert_eq!(hooks.phases, vec!["source", "parsed", "resolved", "lowered", "core"]);
+    // The hook's entry point replaced the program's
+    assert!(compiled.warnings.findings.is_empty(), "{}", compiled.warnings);
+    assert_eq!(crate::interpret::run_program(&compiled.core, &[]), 7);
+
+    let mut hooks = Recorder { stop_at: Some("resolved"), ..Recorder::default() };
+    let error = session.compile_with(&sources(source), &CompileOptions::default(), &mut hooks).unwrap_err();
+    assert!(matches!(error, CompileError::Stopped(_)));
+    assert_eq!(hooks.phases, vec!["source", "parsed", "resolved"]);
+}
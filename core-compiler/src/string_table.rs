@@ -0,0 +1,60 @@
+// The string table: string literals interned to i64 handles for Value::Str
+//
+// One table per process, created by init_string_table() and kept for its
+// lifetime, so a handle stays valid across compiles (see session.rs).
+// Handle 0, like any unknown handle, reads as "".
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+static STRING_TABLE: Mutex<Option<StringTable>> = Mutex::new(None);
+
+struct StringTable {
+    strings: Vec<String>,
+    reverse: HashMap<String, i64>,
+}
+
+impl StringTable {
+    fn new() -> Self {
+        StringTable {
+            strings: vec![String::new()],
+            reverse: HashMap::new(),
+        }
+    }
+
+    fn intern(&mut self, s: String) -> i64 {
+        if let Some(&handle) = self.reverse.get(&s) {
+            return handle;
+        }
+        let handle = self.strings.len() as i64;
+        self.reverse.insert(s.clone(), handle);
+        self.strings.push(s);
+        handle
+    }
+
+    fn get(&self, handle: i64) -> &str {
+        if handle < 0 || handle >= self.strings.len() as i64 {
+            return "";
+        }
+        &self.strings[handle as usize]
+    }
+}
+
+pub fn init_string_table() {
+    let mut table = STRING_TABLE.lock().unwrap();
+    if table.is_none() {
+        *table = Some(StringTable::new());
+    }
+}
+
+pub fn intern_string(s: String) -> i64 {
+    let mut table = STRING_TABLE.lock().unwrap();
+    let table = table.as_mut().unwrap();
+    table.intern(s)
+}
+
+pub fn get_string(handle: i64) -> String {
+    let table = STRING_TABLE.lock().unwrap();
+    let table = table.as_ref().unwrap();
+    table.get(handle).to_string()
+}
@@ -1,13 +1,32 @@
 // Lower surface syntax to Core
-use crate::surface_parser::{SurfaceExpr, SurfaceStmt, FnDef, Module};
-use crate::runtime::Value;
+use crate::core_validator::ValidationError;
+use crate::diagnostics::SourceMap;
+use crate::error_codes::ErrorCode;
+use crate::operators;
+use crate::surface_parser::{self, SurfaceExpr, SurfaceStmt, FnDef, Module};
+use crate::runtime::{Span, Value};
+use std::collections::HashSet;
 
-// REGIME COMPLIANCE: No modules, no use statements, no main auto-calling
+// Module block functions are already named by their full path and
+// references to them resolved (imports.rs). No main auto-calling.
 pub fn lower_module(module: Module) -> Value {
     let mut core = Value::Unit;
+
+    // @entry: alias the marked function as axis_entry, the name the
+    // runtime calls. The alias is the last binding so it sees every function.
+    if let Some(entry) = module.entry_function()
+        && entry.name != "axis_entry"
+    {
+        core = Value::Let(
+            "axis_entry".to_string(),
+            Box::new(Value::Var(entry.name.clone())),
+            Box::new(core),
+        );
+    }
     
     // Lower all top-level functions (in reverse order for proper let-binding nesting)
     for func in module.functions.iter().rev() {
+        let _span = crate::trace::span_with("lower", &func.name);
         let lambda = lower_function(func, &[]);
         core = Value::Let(func.name.clone(), Box::new(lambda), Box::new(core));
     }
@@ -15,9 +34,76 @@ pub fn lower_module(module: Module) -> Value {
     core
 }
 
+// Functions defined more than once, each named with all its definition
+// sites: AX1018. The emitters would keep the first definition, so a later
+// one (typically from another concatenated file) would silently do nothing.
+// With `allow` (--allow-duplicate-defs) the first definition is kept and
+// each later one dropped with an AX1503 warning, as returned.
+pub fn check_duplicates(
+    module: &mut Module,
+    source: &str,
+    file: &str,
+    sources: &SourceMap,
+    allow: bool,
+) -> Result<Vec<ValidationError>, ValidationError> {
+    let mut seen = HashSet::new();
+    let mut duplicated = Vec::new();
+    for function in &module.functions {
+        if !seen.insert(function.name.as_str()) && !duplicated.contains(&function.name) {
+            duplicated.push(function.name.clone());
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for name in duplicated {
+        let count = module.functions.iter().filter(|function| function.name == name).count();
+        let sites = definition_sites(module, &name, source);
+        let listed: Vec<String> = sites.iter().map(|&(line, column)| sources.location(file, line, column)).collect();
+        let short = name.rsplit('.').next().unwrap_or(&name).to_string();
+        let (code, message) = if allow {
+            (ErrorCode::DuplicateFunctionIgnored, format!("`{}` is defined again; keeping the first definition", name))
+        } else {
+            (ErrorCode::DuplicateFunction, format!("`{}` is defined {} times", name, count))
+        };
+        let mut error = ValidationError::new(format!("{}: {}", code, message));
+        error.span = sites.get(1).map(|&(line, column)| Span { file: file.to_string(), line, column });
+        error.name = Some(short);
+        error.help.push(format!("defined at {}", listed.join(", ")));
+        if !allow {
+            error.help.push("rename or remove all but one, or pass --allow-duplicate-defs to keep the first".to_string());
+            return Err(error);
+        }
+        warnings.push(error);
+    }
+    if allow && !warnings.is_empty() {
+        let mut kept = HashSet::new();
+        module.functions.retain(|function| kept.insert(function.name.clone()));
+    }
+    Ok(warnings)
+}
+
+// Where each definition of `name` is in `source`: the parser keeps
+// definitions in source order, so the nth `fn` with the same last segment
+// is the nth function with it
+fn definition_sites(module: &Module, name: &str, source: &str) -> Vec<(usize, usize)> {
+    let short = |name: &str| name.rsplit('.').next().unwrap_or(name).to_string();
+    let target = short(name);
+    module
+        .functions
+        .iter()
+        .filter(|function| short(&function.name) == target)
+        .zip(surface_parser::function_definitions(source, &target))
+        .filter(|(function, _)| function.name == name)
+        .map(|(_, site)| site)
+        .collect()
+}
+
 // REGIME COMPLIANCE: No module paths, simplified function lowering
 fn lower_function(func: &FnDef, _module_path: &[String]) -> Value {
-    let body = lower_expr(&func.body);
+    let mut body = lower_expr(&func.body);
+    if func.has_attribute("memo") {
+        body = crate::memo::cached_body(func, body);
+    }
     build_lambda(&func.params, body)
 }
 
@@ -63,6 +149,30 @@ fn lower_expr(expr: &SurfaceExpr) -> Value {
                 return app;
             }
             
+            // `x |> f(a)` is `f(x, a)` and `x |> f` is `f(x)`; the parser
+            // allows nothing else on the right
+            if let (operators::PIPE, [value, target]) = (name.as_str(), args.as_slice()) {
+                let (function, rest) = match target {
+                    SurfaceExpr::Call(function, rest) => (function, rest.as_slice()),
+                    SurfaceExpr::Ident(function) => (function, &[][..]),
+                    _ => panic!("Invalid pipeline: expected a function or call after '|>'"),
+                };
+                let args = std::iter::once(value).chain(rest).cloned().collect();
+                return lower_expr(&SurfaceExpr::Call(function.clone(), args));
+            }
+
+            // `a && b` and `a || b` evaluate `b` only when `a` leaves the
+            // result open, so they are conditionals rather than calls of
+            // __and__ / __or__, which evaluate both arguments
+            if let ("__and__" | "__or__", [left, right]) = (name.as_str(), args.as_slice()) {
+                let (left, right) = (Box::new(lower_expr(left)), Box::new(lower_expr(right)));
+                return if name == "__and__" {
+                    Value::If(left, right, Box::new(Value::Bool(false)))
+                } else {
+                    Value::If(left, Box::new(Value::Bool(true)), right)
+                };
+            }
+
             let mut app = Value::Var(name.clone());
             let is_ctor = is_constructor_name(name);
 
@@ -142,30 +252,7 @@ fn lower_block(stmts: &[SurfaceStmt]) -> Value {
             let rest = lower_block(&stmts[1..]);
             Value::Let(name.clone(), Box::new(value), Box::new(rest))
         }
-        SurfaceStmt::LetPattern(ctor_name, field_vars, expr) => {
-            //  Pattern let - desugar to field extraction
-            // let Pair(x, y) = rhs  =>  let _tmp = rhs in let x = __ctor_field__(_tmp, 0) in let y = __ctor_field__(_tmp, 1) in <rest>
-            let rhs_value = lower_expr(expr);
-            let tmp_var = format!("_tmp_{}_{}", ctor_name, field_vars.len());
-            
-            // Build nested lets for each field variable
-            let rest = lower_block(&stmts[1..]);
-            let mut body = rest;
-            for (i, field_var) in field_vars.iter().enumerate().rev() {
-                // Build: __ctor_field__(_tmp, i)
-                let extract_call = Value::App(
-                    Box::new(Value::App(
-                        Box::new(Value::Var("__ctor_field__".to_string())),
-                        Box::new(Value::Var(tmp_var.clone())),
-                    )),
-                    Box::new(Value::Int(i as i64)),
-                );
-                body = Value::Let(field_var.clone(), Box::new(extract_call), Box::new(body));
-            }
-            
-            // Bind the temporary variable to the RHS value
-            Value::Let(tmp_var, Box::new(rhs_value), Box::new(body))
-        }
+        SurfaceStmt::LetPattern(pattern, expr, else_expr) => lower_let_pattern(pattern, expr, else_expr, || lower_block(&stmts[1..])),
         SurfaceStmt::Expr(expr) => {
             // Expression statement - evaluate for side effects, discard value
             let value = lower_expr(expr);
@@ -181,30 +268,29 @@ fn lower_stmt(stmt: &SurfaceStmt) -> Value {
         SurfaceStmt::Let(name, expr) => {
             Value::Let(name.clone(), Box::new(lower_expr(expr)), Box::new(Value::Unit))
         }
-        SurfaceStmt::LetPattern(ctor_name, field_vars, expr) => {
-            //  Pattern let in single-statement context
-            let rhs_value = lower_expr(expr);
-            let tmp_var = format!("_tmp_{}_{}", ctor_name, field_vars.len());
-            
-            // Build nested lets for each field variable, ending with Unit
-            let mut body = Value::Unit;
-            for (i, field_var) in field_vars.iter().enumerate().rev() {
-                let extract_call = Value::App(
-                    Box::new(Value::App(
-                        Box::new(Value::Var("__ctor_field__".to_string())),
-                        Box::new(Value::Var(tmp_var.clone())),
-                    )),
-                    Box::new(Value::Int(i as i64)),
-                );
-                body = Value::Let(field_var.clone(), Box::new(extract_call), Box::new(body));
-            }
-            
-            Value::Let(tmp_var, Box::new(rhs_value), Box::new(body))
-        }
+        SurfaceStmt::LetPattern(pattern, expr, else_expr) => lower_let_pattern(pattern, expr, else_expr, || Value::Unit),
         SurfaceStmt::Expr(expr) => lower_expr(expr),
     }
 }
 
+// let <pattern> = rhs [else <expr>]; <rest>
+//   =>  match rhs { <pattern> => <rest>, _ => <else> }
+// Without an else branch the pattern is irrefutable
+// (core_validator::check_let_patterns) and the match has one arm.
+fn lower_let_pattern(
+    pattern: &str,
+    expr: &SurfaceExpr,
+    else_expr: &Option<SurfaceExpr>,
+    rest: impl FnOnce() -> Value,
+) -> Value {
+    let scrutinee = lower_expr(expr);
+    let mut arms = vec![(pattern.to_string(), rest())];
+    if let Some(else_expr) = else_expr {
+        arms.push(("_".to_string(), lower_expr(else_expr)));
+    }
+    Value::Match(Box::new(scrutinee), arms)
+}
+
 fn is_constructor_name(name: &str) -> bool {
     let last = if let Some(idx) = name.rfind("::") {
         &name[idx + 2..]
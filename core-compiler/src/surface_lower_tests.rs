@@ -0,0 +1,90 @@
+// Tests for lowering (surface_lower.rs): duplicate function definitions
+// (check_duplicates), the operators lowered to conditionals, and pipelines
+
+use crate::core_text::print_core_text;
+use crate::diagnostics::SourceMap;
+use crate::error_codes::ErrorCode;
+use crate::surface_lower::{self, check_duplicates};
+use crate::surface_parser::{self, Module};
+use crate::surface_to_core;
+
+const UTIL: &str = "fn trim(s) { s }\nfn pad(s) { s ++ \" \" }\n";
+const MAIN: &str = "foreign fn trim(s)\nfn main() { trim(\"x\") }\nfn trim(s) { pad(s) }\n";
+
+// util.ax and main.ax concatenated, as the compiler reads them
+fn concatenated() -> (Module, String, SourceMap) {
+    crate::init_string_table();
+    let files = [("util.ax".to_string(), UTIL.to_string()), ("main.ax".to_string(), MAIN.to_string())];
+    let source = format!("{}\n{}\n", UTIL, MAIN);
+    let module = surface_parser::parse_module_with_file(&source, "util.ax").unwrap();
+    (module, source, SourceMap::concatenated(&files))
+}
+
+#[test]
+fn test_duplicate_definitions_are_reported_with_both_sites() {
+    let (mut module, source, sources) = concatenated();
+    let error = check_duplicates(&mut module, &source, "util.ax", &sources, false).expect_err("trim is defined twice");
+
+    assert_eq!(error.message, "AX1018: `trim` is defined 2 times");
+    // The second definition, in main.ax; the foreign declaration is not one
+    let span = error.span.clone().unwrap();
+    assert_eq!((span.line, span.column), (6, 4));
+    assert_eq!(error.help[0], "defined at util.ax:1:4, main.ax:3:4");
+    assert_eq!(error.to_diagnostic().code, Some(ErrorCode::DuplicateFunction));
+    assert_eq!(module.functions.len(), 4);
+}
+
+#[test]
+fn test_allowed_duplicates_keep_the_first_definition() {
+    let (mut module, source, sources) = concatenated();
+    let warnings = check_duplicates(&mut module, &source, "util.ax", &sources, true).unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, "AX1503: `trim` is defined again; keeping the first definition");
+    assert!(warnings[0].to_diagnostic().severity == crate::diagnostics::Severity::Warning);
+    let names: Vec<&str> = module.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["trim", "pad", "main"]);
+    assert_eq!(format!("{:?}", module.functions[0].body), "Block([Expr(Ident(\"s\"))])");
+}
+
+#[test]
+fn test_module_blocks_are_separate_namespaces() {
+    crate::init_string_table();
+    let source = "module a { fn f() { 1 } }\nmodule b { fn f() { 2 } }\nmodule a { fn f() { 3 } }\n";
+    let mut module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    let mut sources = SourceMap::default();
+    sources.add_file("main.ax", source);
+    let error = check_duplicates(&mut module, source, "main.ax", &sources, false).expect_err("a.f is defined twice");
+    assert_eq!(error.message, "AX1018: `a.f` is defined 2 times");
+    assert_eq!(error.help[0], "defined at main.ax:1:15, main.ax:3:15");
+}
+
+#[test]
+fn test_and_or_lower_to_conditionals() {
+    let module = surface_parser::parse_module_with_file("fn f(a, b) { a && b }\nfn g(a, b) { a || b }\n", "main.ax").unwrap();
+    let term = surface_to_core::value_to_core(&surface_lower::lower_module(module));
+    let text = print_core_text(&term);
+    assert!(text.contains("(if (var a) (var b) (bool false))"), "{}", text);
+    assert!(text.contains("(if (var a) (bool true) (var b))"), "{}", text);
+    assert!(!text.contains("__and__") && !text.contains("__or__"), "{}", text);
+}
+
+fn core_text(source: &str) -> String {
+    crate::init_string_table();
+    let module = surface_parser::parse_module_with_file(source, "main.ax").unwrap();
+    print_core_text(&surface_to_core::value_to_core(&surface_lower::lower_module(module)))
+}
+
+#[test]
+fn test_pipeline_passes_the_value_as_the_first_argument() {
+    for (piped, nested) in [
+        ("fn f(x) { x |> g }", "fn f(x) { g(x) }"),
+        ("fn f(x, a) { x |> g(a) }", "fn f(x, a) { g(x, a) }"),
+        ("fn f(x, a) { x |> g(a) |> h |> k(a, 1) }", "fn f(x, a) { k(h(g(x, a)), a, 1) }"),
+        // Looser than every other operator
+        ("fn f(x, a) { x + 1 |> g(a == 2) }", "fn f(x, a) { g(x + 1, a == 2) }"),
+        ("fn f(x) { x |> Some }", "fn f(x) { Some(x) }"),
+    ] {
+        assert_eq!(core_text(piped), core_text(nested), "{}", piped);
+    }
+}
@@ -2,6 +2,9 @@
 // Parses: let, blocks, calls, literals, identifiers
 // Does NOT parse: if, match, operators, lambdas, etc.
 
+use crate::diagnostics::{self, Diagnostic, SourceMap};
+use crate::lexer::{self, LexError, Token, TokenKind};
+use crate::operators::{self, Assoc};
 use crate::registry_loader::Registry;
 
 #[derive(Debug, Clone)]
@@ -9,42 +12,68 @@ pub struct ParseError {
     pub file: String,
     pub line: usize,
     pub column: usize,
-    pub found: String,
-    pub expected: String,
+    // Characters of the offending token (1 at end of input)
+    pub width: usize,
+    pub kind: ParseErrorKind,
     pub source_line: String,
 }
 
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(
-            f,
-            "Parse error in {}:{}:{}",
-            self.file, self.line, self.column
-        )?;
-        writeln!(f, "    {}", self.source_line)?;
-        writeln!(f, "    {}^", " ".repeat(self.column.saturating_sub(1)))?;
-        write!(f, "Expected '{}', got '{}'", self.expected, self.found)
-    }
+#[derive(Debug, Clone)]
+pub enum ParseErrorKind {
+    // AX0001
+    Unexpected { expected: String, found: String },
+    // AX0002: what is wrong ("unterminated string literal"), and the
+    // lexical errors on later lines. The lexer skips to the next line after
+    // each, so a file reports all of them at once. (A boxed slice keeps
+    // ParseError, the Err of every parser function, small.)
+    Lexical { message: String, following: Box<[ParseError]> },
+    // AX0003: a keyword where a name is required ("parameter name")
+    Keyword { keyword: String, expected: &'static str },
 }
 
-#[allow(dead_code)]
-// Byte offsets reserved for future diagnostics
-#[derive(Debug, Clone)]
-struct SourceLocation {
-    line: usize,
-    column: usize,
-    byte_offset: usize,
+impl ParseError {
+    fn lexical(error: &LexError, source: &str, file: &str) -> Self {
+        ParseError {
+            file: file.to_string(),
+            line: error.line,
+            column: error.column,
+            width: error.width,
+            kind: ParseErrorKind::Lexical { message: error.message.clone(), following: Box::default() },
+            source_line: source.lines().nth(error.line - 1).unwrap_or("").to_string(),
+        }
+    }
+
+    /// The further errors reported with this one
+    pub fn following(&self) -> &[ParseError] {
+        match &self.kind {
+            ParseErrorKind::Lexical { following, .. } => following,
+            ParseErrorKind::Unexpected { .. } | ParseErrorKind::Keyword { .. } => &[],
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-struct Token {
-    text: String,
-    location: SourceLocation,
+// Uncolored; the compiler renders parse errors itself, with color and the
+// real file name of concatenated sources
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sources = SourceMap::single_line(&self.file, self.line, &self.source_line);
+        let rendered = diagnostics::render(&Diagnostic::from(self), &sources, false);
+        write!(f, "{}", rendered.trim_end())?;
+        for error in self.following() {
+            write!(f, "\n{}", error)?;
+        }
+        Ok(())
+    }
 }
 
+// Maximum expression nesting accepted by the parser. Deeper input is
+// rejected with a ParseError instead of overflowing the stack.
+const MAX_NESTING_DEPTH: usize = 256;
+
 struct Parser {
     tokens: Vec<Token>,
     pos: usize,
+    depth: usize,
     source: String,
     file: String,
     #[allow(dead_code)]
@@ -52,19 +81,28 @@ struct Parser {
     registry: Registry,
 }
 
+// Calls the parser builds for syntax other than operators
+const SYNTAX_CALLS: &[&str] = &["__tuple__", "__struct_lit__", "__let_in__"];
+
+// A named function or a call of one; operators (the pipe included) and
+// tuples, struct literals and let-in are calls too, but not of a function
+// the program names
+fn is_pipe_target(expr: &SurfaceExpr) -> bool {
+    match expr {
+        SurfaceExpr::Ident(_) => true,
+        SurfaceExpr::Call(name, _) => {
+            !operators::is_operator(name) && name != operators::PIPE && !SYNTAX_CALLS.contains(&name.as_str())
+        }
+        _ => false,
+    }
+}
+
 // Unescape string literals: convert \n, \t, \\, \" etc. to actual characters
 fn unescape_string(s: &str) -> String {
     let mut result = String::new();
     let mut chars = s.chars();
-    let mut char_count = 0;
-    let mut last_logged = 0;
 
     while let Some(c) = chars.next() {
-        char_count += 1;
-        if char_count >= last_logged + 10000 {
-            eprintln!("[PROGRESS] phase=axis_compiler loop=unescape count={}", char_count);
-            last_logged = char_count;
-        }
         if c == '\\' {
             match chars.next() {
                 Some('n') => result.push('\n'),
@@ -115,15 +153,67 @@ pub struct MatchArm {
 #[derive(Debug, Clone)]
 pub enum SurfaceStmt {
     Let(String, SurfaceExpr),
-    LetPattern(String, Vec<String>, SurfaceExpr), // LetPattern(ctor_name, field_vars, rhs)
+    // let <pattern> = rhs [else <expr>];  the pattern is text, as in a MatchArm
+    LetPattern(String, SurfaceExpr, Option<SurfaceExpr>),
     Expr(SurfaceExpr),
 }
 
+// Function attribute `@name` or `@name(arg, ...)`.
+// Arguments are kept as raw token text for the consumer to interpret.
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+// Attributes understood by the compiler; anything else is a parse error
+//   @entry  - program entry point (aliased as axis_entry during lowering)
+//   @inline - optimizer hint, carried on the FnDef
+//   @memo   - results cached per argument value (memo.rs)
+//   @test   - test function, collected by Module::test_functions
+const KNOWN_ATTRIBUTES: [&str; 4] = ["entry", "inline", "memo", "test"];
+
 #[derive(Debug, Clone)]
 pub struct FnDef {
     pub name: String,
     pub params: Vec<String>,
     pub body: SurfaceExpr,
+    pub attributes: Vec<Attribute>,
+    // `pub fn`: usable outside its `module` block (imports.rs) and by other
+    // bundles at link time
+    pub public: bool,
+}
+
+impl FnDef {
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|attr| attr.name == name)
+    }
+
+    // The enclosing `module` block's path ("" at top level): functions in a
+    // module block are named by their full path, e.g. text.util.pad
+    pub fn module(&self) -> &str {
+        self.name.rsplit_once('.').map_or("", |(module, _)| module)
+    }
+}
+
+// `use a.b.c;` in the `module` block `module` ("" at top level): imports
+// the module a.b.c (its functions are then c.f) or the function c of a.b.
+// Resolved by imports.rs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub module: String,
+    pub path: Vec<String>,
+}
+
+impl Import {
+    pub fn target(&self) -> String {
+        self.path.join(".")
+    }
+
+    // The name the import binds in its scope
+    pub fn binds(&self) -> &str {
+        self.path.last().map_or("", String::as_str)
+    }
 }
 #[allow(dead_code)]
 // Foreign function metadata (validated later)
@@ -134,13 +224,144 @@ pub struct ForeignFnDef {
     // No body for foreign functions - they're implemented externally
 }
 
+// `type Name[T, ...] { Variant(FieldType, ...), ... }`, or a record
+// `type Name { field: Type, ... }`, which has one constructor named after
+// the type. Field types are kept as written; only their number is used.
+#[derive(Debug, Clone)]
+pub struct TypeDef {
+    pub name: String,
+    pub params: Vec<String>,
+    pub variants: Vec<VariantDef>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantDef {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
 #[allow(dead_code)]
 // Foreign function metadata (validated later)
-// REGIME COMPLIANCE: No modules, no use statements
 #[derive(Debug, Clone)]
 pub struct Module {
     pub functions: Vec<FnDef>,
     pub foreign_functions: Vec<ForeignFnDef>,
+    pub types: Vec<TypeDef>,
+    pub imports: Vec<Import>,
+}
+
+impl Module {
+    // The function marked @entry, if any (the parser admits at most one)
+    pub fn entry_function(&self) -> Option<&FnDef> {
+        self.functions.iter().find(|f| f.has_attribute("entry"))
+    }
+
+    pub fn test_functions(&self) -> impl Iterator<Item = &FnDef> {
+        self.functions.iter().filter(|f| f.has_attribute("test"))
+    }
+}
+
+// Token stream as one "LINE:COL TEXT" line per token (text quoted), for
+// --dump-phase tokens
+pub fn dump_tokens(source: &str) -> String {
+    let mut out = String::new();
+    for token in tokenize_with_location(source) {
+        out.push_str(&format!(
+            "{}:{} {:?}\n",
+            token.location.line, token.location.column, token.text
+        ));
+    }
+    out
+}
+
+// Whether `source` contains `fn NAME`; finds the file defining a function
+// among sources that are concatenated before parsing
+pub fn defines_function(source: &str, name: &str) -> bool {
+    tokenize_with_location(source)
+        .windows(2)
+        .any(|pair| pair[0].text == "fn" && pair[1].text == name)
+}
+
+// Line and column of the name in each `fn NAME` definition, in source
+// order; foreign declarations are not definitions
+pub fn function_definitions(source: &str, name: &str) -> Vec<(usize, usize)> {
+    let tokens = tokenize_with_location(source);
+    (1..tokens.len())
+        .filter(|&i| tokens[i - 1].text == "fn" && tokens[i].text == name)
+        .filter(|&i| i < 2 || tokens[i - 2].text != "foreign")
+        .map(|i| (tokens[i].location.line, tokens[i].location.column))
+        .collect()
+}
+
+// Line and column of each place the function defined at `site` (from
+// function_definitions) binds `name`, in source order: its parameters,
+// `let` names and patterns, and match arm patterns
+pub fn binding_sites(source: &str, site: (usize, usize), name: &str) -> Vec<(usize, usize)> {
+    let tokens = tokenize_with_location(source);
+    let Some(start) = tokens.iter().position(|token| (token.location.line, token.location.column) == site) else {
+        return Vec::new();
+    };
+    let mut sites = Vec::new();
+    let mut depth = 0;
+    let mut in_params = false;
+    let mut in_let = false;
+    // Open matches: the depth inside their `{`, and whether an arm
+    // pattern (rather than its expression) is being read
+    let mut matches: Vec<(usize, bool)> = Vec::new();
+    let mut match_pending = false;
+    let mut body_started = false;
+    for (i, token) in tokens.iter().enumerate().skip(start + 1) {
+        let binds = in_params || in_let || matches.last().is_some_and(|&(_, pattern)| pattern);
+        match token.text.as_str() {
+            "(" | "[" => {
+                in_params |= i == start + 1;
+                depth += 1;
+            }
+            ")" | "]" => {
+                depth -= 1;
+                in_params &= depth > 0;
+            }
+            "{" => {
+                depth += 1;
+                body_started = true;
+                if match_pending {
+                    matches.push((depth, true));
+                    match_pending = false;
+                }
+            }
+            "}" => {
+                if matches.last().is_some_and(|&(level, _)| level == depth) {
+                    matches.pop();
+                }
+                depth -= 1;
+                if body_started && depth == 0 {
+                    break;
+                }
+            }
+            "let" => in_let = true,
+            "=" => in_let = false,
+            "match" => match_pending = true,
+            "=>" | "," => {
+                if let Some((level, pattern)) = matches.last_mut()
+                    && *level == depth
+                {
+                    *pattern = token.text == ",";
+                }
+            }
+            text if binds && text == name => sites.push((token.location.line, token.location.column)),
+            _ => {}
+        }
+    }
+    sites
+}
+
+// Line and column of the first token spelled `name`; the surface AST has no
+// spans, so diagnostics about a name point at its first use
+pub fn find_identifier(source: &str, name: &str) -> Option<(usize, usize)> {
+    tokenize_with_location(source)
+        .into_iter()
+        .find(|token| token.text == name)
+        .map(|token| (token.location.line, token.location.column))
 }
 
 pub fn parse_module_with_file(source: &str, file: &str) -> Result<Module, ParseError> {
@@ -159,10 +380,17 @@ pub fn parse_module_with_file(source: &str, file: &str) -> Result<Module, ParseE
         }
     }
 
-    let tokens = tokenize_with_location(source);
+    let tokens = lexer::lex(source).map_err(|errors| {
+        let mut first = ParseError::lexical(&errors[0], source, file);
+        if let ParseErrorKind::Lexical { following, .. } = &mut first.kind {
+            *following = errors[1..].iter().map(|error| ParseError::lexical(error, source, file)).collect();
+        }
+        first
+    })?;
     let mut parser = Parser {
         tokens,
         pos: 0,
+        depth: 0,
         source: source.to_string(),
         file: file.to_string(),
         registry,
@@ -181,114 +409,259 @@ pub fn parse_module(source: &str) -> Result<Module, String> {
 
 impl Parser {
     fn parse_module(&mut self) -> Result<Module, ParseError> {
-        // REGIME COMPLIANCE: No module blocks, no use declarations
-        let mut functions = Vec::new();
+        let mut functions: Vec<FnDef> = Vec::new();
         let mut foreign_functions = Vec::new();
+        let mut types: Vec<TypeDef> = Vec::new();
+        let mut imports: Vec<Import> = Vec::new();
+        let mut entry_function: Option<String> = None;
+        let mut entry_pos = 0;
+        // Paths of the enclosing `module` blocks, innermost last
+        let mut modules: Vec<String> = Vec::new();
 
         while self.pos < self.tokens.len() {
             // Skip comments that became tokens
-            while self.pos < self.tokens.len() && self.tokens[self.pos].text.starts_with("//") {
+            while self.pos < self.tokens.len() && self.peek_text().starts_with("//") {
                 self.pos += 1;
             }
             if self.pos >= self.tokens.len() {
                 break;
             }
 
-            // REGIME COMPLIANCE: Skip use and module keywords (legacy compatibility)
-            if self.tokens[self.pos].text == "use" {
-                // Skip use declarations silently for backward compatibility
+            let module = modules.last().cloned().unwrap_or_default();
+            if self.peek_text() == "use" {
+                // The `;` is optional, as it always was
                 self.pos += 1;
-                let _ = self.parse_path()?;
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == ";" {
+                let path = self.parse_path()?;
+                if self.pos < self.tokens.len() && self.peek_text() == ";" {
                     self.pos += 1;
                 }
-                continue;
-            } else if self.tokens[self.pos].text == "module" {
-                // Skip module blocks entirely
-                self.skip_module_block()?;
-                continue;
-            } else if self.tokens[self.pos].text == "type" {
-                // Skip type declarations (ADTs)
-                self.skip_type_declaration()?;
-            } else if self.tokens[self.pos].text == "foreign" {
+                imports.push(Import { module, path });
+            } else if self.peek_text() == "module" {
+                // module a.b { ... }: nested blocks extend the path
+                self.pos += 1;
+                let path = self.parse_path()?.join(".");
+                self.expect_token("{")?;
+                modules.push(if module.is_empty() { path } else { format!("{}.{}", module, path) });
+            } else if self.peek_text() == "}" && !modules.is_empty() {
+                self.pos += 1;
+                modules.pop();
+            } else if self.peek_text() == "type" {
+                let type_pos = self.pos;
+                let mut type_def = self.parse_type_declaration()?;
+                if !module.is_empty() {
+                    type_def.name = format!("{}.{}", module, type_def.name);
+                }
+                if types.iter().any(|t| t.name == type_def.name) {
+                    self.pos = type_pos + 1;
+                    return self.error("each type declared once", &type_def.name);
+                }
+                types.push(type_def);
+            } else if self.peek_text() == "foreign" {
                 // Parse foreign function declaration
                 foreign_functions.push(self.parse_foreign_declaration()?);
-            } else if self.tokens[self.pos].text == "fn" {
-                functions.push(self.parse_function()?);
+            } else if self.peek_text() == "fn" || self.peek_text() == "@" || self.peek_text() == "pub" {
+                let attributes_pos = self.pos;
+                let attributes = self.parse_attributes()?;
+                // Visibility comes after the attributes: `@inline pub fn`
+                let public = self.pos < self.tokens.len() && self.peek_text() == "pub";
+                if public {
+                    self.pos += 1;
+                }
+                let mut function = self.parse_function()?;
+                function.attributes = attributes;
+                function.public = public;
+                if !module.is_empty() {
+                    function.name = format!("{}.{}", module, function.name);
+                }
+
+                if function.has_attribute("entry") {
+                    if let Some(previous) = entry_function.replace(function.name.clone()) {
+                        self.pos = attributes_pos;
+                        return self.error(
+                            &format!("at most one @entry function (already on '{}')", previous),
+                            "@entry",
+                        );
+                    }
+                    entry_pos = attributes_pos;
+                }
+                functions.push(function);
             } else {
                 return self.error(
-                    "function, type, or foreign declaration",
-                    &self.tokens[self.pos].text,
+                    "function, type, foreign, use or module declaration",
+                    self.peek_text(),
                 );
             }
         }
+        if !modules.is_empty() {
+            return self.error("}", "end of input in module block");
+        }
+
+        // @entry is lowered to an axis_entry alias, so it cannot coexist
+        // with a function that is itself named axis_entry
+        if let Some(entry) = &entry_function
+            && entry != "axis_entry"
+            && functions.iter().any(|f| f.name == "axis_entry")
+        {
+            self.pos = entry_pos;
+            return self.error("@entry or a function named axis_entry, not both", "@entry");
+        }
 
         Ok(Module {
             functions,
             foreign_functions,
+            types,
+            imports,
         })
     }
 
-    // REGIME COMPLIANCE: Skip module blocks (backward compatibility only)
-    fn skip_module_block(&mut self) -> Result<(), ParseError> {
-        self.expect_token("module")?;
-        let _ = self.parse_path()?;
-        self.expect_token("{")?;
-
-        let mut depth = 1;
-        while self.pos < self.tokens.len() && depth > 0 {
-            if self.tokens[self.pos].text == "{" {
-                depth += 1;
-            } else if self.tokens[self.pos].text == "}" {
-                depth -= 1;
+    // Parse zero or more `@name` / `@name(arg, ...)` attributes before `fn`
+    fn parse_attributes(&mut self) -> Result<Vec<Attribute>, ParseError> {
+        let mut attributes: Vec<Attribute> = Vec::new();
+        while self.pos < self.tokens.len() && self.peek_text() == "@" {
+            self.pos += 1; // consume '@'
+            if self.pos >= self.tokens.len() {
+                return self.error("attribute name", "EOF");
+            }
+            let name = self.peek_text().to_string();
+            if !KNOWN_ATTRIBUTES.contains(&name.as_str()) {
+                return self.error(
+                    &format!("attribute ({})", KNOWN_ATTRIBUTES.join(", ")),
+                    &format!("@{}", name),
+                );
+            }
+            if attributes.iter().any(|attr| attr.name == name) {
+                return self.error("each attribute at most once", &format!("@{}", name));
             }
             self.pos += 1;
-        }
 
-        if depth != 0 {
-            return self.error("}", "end of input in module block");
-        }
+            let mut args = Vec::new();
+            if self.pos < self.tokens.len() && self.peek_text() == "(" {
+                self.pos += 1; // consume '('
+                while self.pos < self.tokens.len() && self.peek_text() != ")" {
+                    args.push(self.consume_token()?.text.clone());
+                    if self.pos < self.tokens.len() && self.peek_text() == "," {
+                        self.pos += 1;
+                    }
+                }
+                self.expect_token(")")?;
+            }
+            // None of the current attributes take arguments
+            if !args.is_empty() {
+                return self.error(&format!("no arguments for @{}", name), &args.join(", "));
+            }
 
-        Ok(())
+            attributes.push(Attribute { name, args });
+        }
+        Ok(attributes)
     }
 
-    // Skip a type declaration (ADT)
-    // Example: type Foo { Bar(Int), Baz(Str, Bool) }
-    fn skip_type_declaration(&mut self) -> Result<(), ParseError> {
+    // Parse a type declaration (ADT or record)
+    // Example: type Foo[T] { Bar(Int), Baz(Str, T), Qux }
+    // Variants may be separated by `,` or `|`.
+    fn parse_type_declaration(&mut self) -> Result<TypeDef, ParseError> {
         self.expect_token("type")?;
-
-        // Consume type name
-        if self.pos < self.tokens.len() {
-            self.pos += 1;
+        let name = self.consume_token()?.text.clone();
+        if !name.starts_with(|c: char| c.is_uppercase()) {
+            self.pos -= 1;
+            return self.error("type name (capitalized)", &name);
         }
 
-        // Skip optional type parameters (e.g., type Foo[T] { ... })
-        if self.pos < self.tokens.len() && self.tokens[self.pos].text == "[" {
+        // Optional type parameters (e.g., type Foo[T] { ... })
+        let mut params = Vec::new();
+        if self.pos < self.tokens.len() && self.peek_text() == "[" {
             self.pos += 1; // consume '['
-            while self.pos < self.tokens.len() && self.tokens[self.pos].text != "]" {
-                self.pos += 1; // skip type parameter
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
-                    self.pos += 1;
-                }
+            while self.pos < self.tokens.len() && self.peek_text() != "]" {
+                params.push(self.consume_name("type parameter")?);
+                self.list_separator("]")?;
             }
             self.expect_token("]")?;
         }
 
-        // Expect opening brace
         self.expect_token("{")?;
+        let mut variants: Vec<VariantDef> = Vec::new();
+        let mut record_fields: Vec<String> = Vec::new();
+        loop {
+            while self.pos < self.tokens.len() && self.peek_text().starts_with("//") {
+                self.pos += 1;
+            }
+            if self.pos >= self.tokens.len() {
+                return self.error("}", "end of input in type declaration");
+            }
+            if self.peek_text() == "}" {
+                self.pos += 1;
+                break;
+            }
 
-        // Skip all variants until closing brace
-        let mut brace_depth = 1;
-        while self.pos < self.tokens.len() && brace_depth > 0 {
-            if self.tokens[self.pos].text == "{" {
-                brace_depth += 1;
-            } else if self.tokens[self.pos].text == "}" {
-                brace_depth -= 1;
+            let entry = self.consume_token()?.text.clone();
+            if self.peek_text() == ":" {
+                // Record field `name: Type`
+                if lexer::is_keyword(&entry) {
+                    self.pos -= 1;
+                    return self.keyword_error("field name");
+                }
+                self.pos += 1;
+                let field_type = self.parse_field_type("}")?;
+                record_fields.push(format!("{}: {}", entry, field_type));
+            } else if entry.starts_with(|c: char| c.is_uppercase()) {
+                if variants.iter().any(|v| v.name == entry) {
+                    self.pos -= 1;
+                    return self.error("each variant declared once", &entry);
+                }
+                let mut fields = Vec::new();
+                if self.peek_text() == "(" {
+                    self.pos += 1;
+                    while self.pos < self.tokens.len() && self.peek_text() != ")" {
+                        fields.push(self.parse_field_type(")")?);
+                        if self.peek_text() == "," {
+                            self.pos += 1;
+                        }
+                    }
+                    self.expect_token(")")?;
+                }
+                variants.push(VariantDef { name: entry, fields });
+            } else {
+                self.pos -= 1;
+                return self.error("variant (capitalized) or `field: Type`", &entry);
+            }
+
+            if self.peek_text() == "," || self.peek_text() == "|" {
+                self.pos += 1;
             }
-            self.pos += 1;
         }
 
-        Ok(())
+        if !record_fields.is_empty() {
+            if !variants.is_empty() {
+                return self.error(&format!("variants or record fields in type {}, not both", name), "both");
+            }
+            variants.push(VariantDef { name: name.clone(), fields: record_fields });
+        }
+        Ok(TypeDef { name, params, variants })
+    }
+
+    // Text of one field type, up to a `,` or `close` outside brackets
+    fn parse_field_type(&mut self, close: &str) -> Result<String, ParseError> {
+        let mut text = String::new();
+        let mut depth = 0usize;
+        while self.pos < self.tokens.len() {
+            let token = self.peek_text();
+            if depth == 0 && (token == "," || token == close) {
+                break;
+            }
+            match token {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if !text.is_empty() && !matches!(token, "[" | "]" | "," | ")" | ":") && !text.ends_with(['[', '(']) {
+                text.push(' ');
+            }
+            text.push_str(token);
+            self.pos += 1;
+        }
+        if text.is_empty() {
+            return self.error("field type", self.peek_text());
+        }
+        Ok(text)
     }
 
     // Parse a foreign function declaration (CP-5 requirement)
@@ -298,25 +671,23 @@ impl Parser {
         self.expect_token("fn")?;
 
         // Get function name (support dotted names like "axis.io.print")
-        let name = self.consume_qualified_name()?;
+        let name = self.consume_qualified_name("function name")?;
 
         // Parse parameter list
         self.expect_token("(")?;
         let mut params = Vec::new();
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text != ")" {
-            params.push(self.consume_token()?.text.clone());
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == ":" {
+        while self.pos < self.tokens.len() && self.peek_text() != ")" {
+            params.push(self.consume_name("parameter name")?);
+            if self.pos < self.tokens.len() && self.peek_text() == ":" {
                 self.pos += 1; // skip type annotation colon
                 self.skip_type()?; // skip the type expression
             }
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
-                self.pos += 1;
-            }
+            self.list_separator(")")?;
         }
         self.expect_token(")")?;
 
         // Skip optional return type
-        if self.pos < self.tokens.len() && self.tokens[self.pos].text == "->" {
+        if self.pos < self.tokens.len() && self.peek_text() == "->" {
             self.pos += 1;
             self.skip_type()?;
         }
@@ -326,30 +697,28 @@ impl Parser {
 
     fn parse_function(&mut self) -> Result<FnDef, ParseError> {
         self.expect_token("fn")?;
-        let name = self.consume_qualified_name()?;
+        let name = self.consume_qualified_name("function name")?;
         self.expect_token("(")?;
 
         let mut params = Vec::new();
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text != ")" {
-            params.push(self.consume_token()?.text.clone());
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == ":" {
+        while self.pos < self.tokens.len() && self.peek_text() != ")" {
+            params.push(self.consume_name("parameter name")?);
+            if self.pos < self.tokens.len() && self.peek_text() == ":" {
                 self.pos += 1; // skip type annotation colon
                 self.skip_type()?; // skip the type expression
             }
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
-                self.pos += 1;
-            }
+            self.list_separator(")")?;
         }
         self.expect_token(")")?;
 
         // Skip optional return type
-        if self.pos < self.tokens.len() && self.tokens[self.pos].text == "->" {
+        if self.pos < self.tokens.len() && self.peek_text() == "->" {
             self.pos += 1;
             self.skip_type()?; // skip the return type expression
         }
 
         let body = self.parse_block()?;
-        Ok(FnDef { name, params, body })
+        Ok(FnDef { name, params, body, attributes: Vec::new(), public: false })
     }
 
     // Skip over a type expression in the token stream
@@ -360,7 +729,7 @@ impl Parser {
         }
 
         // Handle tuple types (T1, T2, ...)
-        if self.tokens[self.pos].text == "(" {
+        if self.peek_text() == "(" {
             self.pos += 1; // consume '('
 
             // Skip tuple element types (can be comma-separated)
@@ -369,7 +738,7 @@ impl Parser {
                     return self.error(")", "EOF");
                 }
 
-                if self.tokens[self.pos].text == ")" {
+                if self.peek_text() == ")" {
                     self.pos += 1; // consume ')'
                     break;
                 }
@@ -378,7 +747,7 @@ impl Parser {
                 self.skip_type()?;
 
                 // Skip comma if present
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
+                if self.pos < self.tokens.len() && self.peek_text() == "," {
                     self.pos += 1;
                 }
             }
@@ -390,7 +759,7 @@ impl Parser {
         self.pos += 1;
 
         // Handle qualified types (e.g., parser.SurfaceAst)
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text == "." {
+        while self.pos < self.tokens.len() && self.peek_text() == "." {
             self.pos += 1; // consume '.'
             if self.pos < self.tokens.len() {
                 self.pos += 1; // consume type component
@@ -398,7 +767,7 @@ impl Parser {
         }
 
         // Handle generic types (e.g., List[Str], Result[Int])
-        if self.pos < self.tokens.len() && self.tokens[self.pos].text == "[" {
+        if self.pos < self.tokens.len() && self.peek_text() == "[" {
             self.pos += 1; // consume '['
 
             // Skip type arguments (can be comma-separated)
@@ -407,7 +776,7 @@ impl Parser {
                     return self.error("]", "EOF");
                 }
 
-                if self.tokens[self.pos].text == "]" {
+                if self.peek_text() == "]" {
                     self.pos += 1; // consume ']'
                     break;
                 }
@@ -416,7 +785,7 @@ impl Parser {
                 self.skip_type()?;
 
                 // Skip comma if present
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
+                if self.pos < self.tokens.len() && self.peek_text() == "," {
                     self.pos += 1;
                 }
             }
@@ -429,19 +798,19 @@ impl Parser {
         self.expect_token("{")?;
         let mut stmts = Vec::new();
 
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text != "}" {
-            if self.tokens[self.pos].text == "let" {
+        while self.pos < self.tokens.len() && self.peek_text() != "}" {
+            if self.peek_text() == "let" {
                 // Peek ahead to see if this is let-in or let-statement
                 // Save position
                 let saved_pos = self.pos;
 
                 self.pos += 1; // skip 'let'
-                if self.pos < self.tokens.len() {
-                    self.consume_token().ok(); // skip name
+                if self.pos < self.tokens.len() && self.peek_text() != "(" {
+                    self.consume_token().ok(); // skip name (a tuple pattern's '(' is counted below)
                 }
 
                 // Skip optional type annotation
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == ":" {
+                if self.pos < self.tokens.len() && self.peek_text() == ":" {
                     self.pos += 1;
                     // Try to skip type (might fail but that's ok for lookahead)
                     let _ = self.skip_type();
@@ -454,7 +823,7 @@ impl Parser {
                 let mut found_in = false;
                 let mut _found_semi = false;
                 while self.pos < self.tokens.len() {
-                    match self.tokens[self.pos].text.as_str() {
+                    match self.peek_text() {
                         "{" | "(" | "[" => depth += 1,
                         "}" | ")" | "]" => {
                             if depth == 0 {
@@ -485,63 +854,11 @@ impl Parser {
                     self.expect_token("}")?;
                     return Ok(expr);
                 } else {
-                    // This is a let statement with a pattern
-                    self.pos += 1; // consume 'let'
-
-                    // Collect pattern tokens until '='
-                    let mut pattern_tokens = Vec::new();
-                    while self.pos < self.tokens.len() && self.tokens[self.pos].text != "=" {
-                        pattern_tokens.push(self.consume_token()?.text.clone());
-                    }
-
-                    self.expect_token("=")?;
-                    let expr = self.parse_expr()?;
-                    self.expect_token(";")?;
-
-                    //  Detect pattern lets like "Pair(x, y)" or "Ok(val)" or "Token::TokEof(_, _)"
-                    // pattern_tokens could be:
-                    //   ["Pair", "(", "x", ",", "y", ")"]
-                    //   ["Token", "::", "TokEof", "(", "_", ",", "_", ")"]
-                    //   ["lexer", "_", "Token", "::", "TokEof", "(", "_", ",", "_", ")"]  (after type annotation)
-
-                    // Find the opening paren to detect pattern let
-                    let paren_pos = pattern_tokens.iter().position(|t| t == "(");
-
-                    if let Some(paren_idx) = paren_pos {
-                        // Extract constructor name (everything before the paren)
-                        // Skip type annotations (tokens ending with ":")
-                        let ctor_tokens: Vec<String> = pattern_tokens[..paren_idx]
-                            .iter()
-                            .filter(|t| !t.ends_with(':'))
-                            .cloned()
-                            .collect();
-
-                        let ctor_name = ctor_tokens.join("");
-                        let mut field_vars = Vec::new();
-
-                        // Extract field names between ( and )
-                        let mut i = paren_idx + 1;
-                        while i < pattern_tokens.len() && pattern_tokens[i] != ")" {
-                            if pattern_tokens[i] != "," {
-                                field_vars.push(pattern_tokens[i].clone());
-                            }
-                            i += 1;
-                        }
-
-                        stmts.push(SurfaceStmt::LetPattern(ctor_name, field_vars, expr));
-                    } else {
-                        // Simple let binding
-                        let name = if pattern_tokens.is_empty() {
-                            "_".to_string()
-                        } else {
-                            pattern_tokens[0].clone()
-                        };
-                        stmts.push(SurfaceStmt::Let(name, expr));
-                    }
+                    stmts.push(self.parse_let_stmt()?);
                 }
             } else {
                 let expr = self.parse_expr()?;
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == ";" {
+                if self.pos < self.tokens.len() && self.peek_text() == ";" {
                     self.pos += 1;
                     stmts.push(SurfaceStmt::Expr(expr));
                 } else {
@@ -556,56 +873,106 @@ impl Parser {
         Ok(SurfaceExpr::Block(stmts))
     }
 
+    // let <name> [: Type] = <expr>;
+    // let <pattern> = <expr> [else <expr>];
+    // The else branch is the block's value when a refutable pattern does
+    // not match (core_validator::check_let_patterns).
+    fn parse_let_stmt(&mut self) -> Result<SurfaceStmt, ParseError> {
+        self.expect_token("let")?;
+
+        // Collect pattern tokens until '='
+        let start = self.pos;
+        let mut pattern_tokens = Vec::new();
+        while self.pos < self.tokens.len() && self.peek_text() != "=" {
+            pattern_tokens.push(self.consume_token()?.text.clone());
+        }
+
+        // A tuple, constructor or named pattern: "(a, b)", "Pair(x, y)",
+        // "Cons(x, Cons(y, _))", "Token::TokEof(_, _)", "all @ (a, b)". A type annotation
+        // ("x: (Int, Int)") makes it a plain binding.
+        let is_pattern =
+            pattern_tokens.iter().any(|t| t == "(" || t == "@") && pattern_tokens.get(1).is_none_or(|t| t != ":");
+        if is_pattern {
+            self.check_pattern_names(start)?;
+        } else if lexer::is_keyword(pattern_tokens.first().map_or("", String::as_str)) {
+            self.pos = start;
+            return self.keyword_error("variable name");
+        }
+
+        self.expect_token("=")?;
+        let expr = self.parse_expr()?;
+
+        if !is_pattern {
+            self.expect_token(";")?;
+            let name = pattern_tokens.first().cloned().unwrap_or_else(|| "_".to_string());
+            return Ok(SurfaceStmt::Let(name, expr));
+        }
+        let else_expr = if self.peek_text() == "else" {
+            self.pos += 1;
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        self.expect_token(";")?;
+        Ok(SurfaceStmt::LetPattern(join_pattern_tokens(&pattern_tokens), expr, else_expr))
+    }
+
     fn parse_expr(&mut self) -> Result<SurfaceExpr, ParseError> {
+        self.parse_binary(0)
+    }
+
+    // Precedence climbing over operators::OPERATORS: operands joined by
+    // operators binding tighter than `min_precedence`
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<SurfaceExpr, ParseError> {
         let mut expr = self.parse_primary_expr()?;
 
-        // Handle binary operators (but not across certain delimiters)
         while self.pos < self.tokens.len() {
-            // Stop at delimiters that end expressions
-            let tok = &self.tokens[self.pos].text;
-            if tok == "," || tok == ";" || tok == ")" || tok == "}" || tok == "]" {
+            let Some(op) = operators::infix(self.peek_text()).filter(|op| op.precedence > min_precedence) else {
                 break;
-            }
-
-            let op = tok;
-            let op_name = match op.as_str() {
-                "++" => "__concat__",
-                "+" => "__add__",
-                "-" => "__sub__",
-                "*" => "__mul__",
-                "/" => "__div__",
-                "%" => "__mod__",
-                "==" => "__eq__",
-                "!=" => "__neq__",
-                ">=" => "__gte__",
-                "<=" => "__lte__",
-                ">" => "__gt__",
-                "<" => "__lt__",
-                "&&" => "__and__",
-                "||" => "__or__",
-                _ => break,
             };
-
             self.pos += 1; // consume operator
-            let right = self.parse_primary_expr()?;
-            expr = SurfaceExpr::Call(op_name.to_string(), vec![expr, right]);
+            let start = self.pos;
+            let right = match op.assoc {
+                Assoc::Left => self.parse_binary(op.precedence)?,
+                Assoc::Right => self.parse_binary(op.precedence - 1)?,
+            };
+            // `x |> f` and `x |> f(a)` only: surface_lower makes x the first argument
+            if op.function == operators::PIPE && !is_pipe_target(&right) {
+                self.pos = start;
+                let found = self.peek_text().to_string();
+                return self.error("function or call", &found);
+            }
+            expr = SurfaceExpr::Call(op.function.to_string(), vec![expr, right]);
         }
 
         Ok(expr)
     }
 
+    // Every recursive descent (blocks, parens, calls, match, if) passes
+    // through here, so this is the single nesting-depth checkpoint.
     fn parse_primary_expr(&mut self) -> Result<SurfaceExpr, ParseError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            let found = self.peek_text().to_string();
+            return self.error(&format!("at most {} levels of nesting", MAX_NESTING_DEPTH), &found);
+        }
+        self.depth += 1;
+        let result = self.parse_primary_expr_unguarded();
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_primary_expr_unguarded(&mut self) -> Result<SurfaceExpr, ParseError> {
         if self.pos >= self.tokens.len() {
             return self.error("expression", "EOF");
         }
 
-        if self.tokens[self.pos].text == "let" {
+        if self.peek_text() == "let" {
             // let-in expression: let x = expr1 in expr2
             self.pos += 1; // consume 'let'
-            let name = self.consume_token()?.text.clone();
+            let name = self.consume_name("variable name")?;
 
             // Optional type annotation
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == ":" {
+            if self.pos < self.tokens.len() && self.peek_text() == ":" {
                 self.pos += 1;
                 self.skip_type()?;
             }
@@ -622,32 +989,27 @@ impl Parser {
             ));
         }
 
-        if self.tokens[self.pos].text == "{" {
+        if self.peek_text() == "{" {
             return self.parse_block();
         }
 
-        if self.tokens[self.pos].text == "match" {
+        if self.peek_text() == "match" {
             return self.parse_match();
         }
 
-        if self.tokens[self.pos].text == "if" {
-            return self.parse_if();
-        }
-
-        // Reserved keyword: 'fn' is not valid in expression position.
-        // Treat as a parse error to prevent it being parsed as an identifier
-        // which would later lower to a `Var("fn")` and produce an illegal Core var.
-        if self.tokens[self.pos].text == "fn" {
-            return self.error("expression", "fn");
+        if self.peek_text() == "if" {
+            // A statement when it starts one: after a `{`, `;` or block
+            let statement = self.pos > 0 && matches!(self.tokens[self.pos - 1].text.as_str(), "{" | ";" | "}");
+            return self.parse_if(statement);
         }
 
         // Unit literal () or tuple expression
-        if self.tokens[self.pos].text == "(" {
+        if self.peek_text() == "(" {
             let _start_pos = self.pos;
             self.pos += 1; // consume '('
 
             // Check if this is unit literal ()
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == ")" {
+            if self.pos < self.tokens.len() && self.peek_text() == ")" {
                 self.pos += 1; // consume ')'
                 return Ok(SurfaceExpr::UnitLit);
             }
@@ -655,50 +1017,53 @@ impl Parser {
             // Otherwise, parse as tuple expression (expr1, expr2, ...)
             // For now, we'll parse it as a call to a special __tuple__ function
             let mut elements = Vec::new();
+            // Whether the last element was followed by a comma
+            let mut trailing_comma = false;
             loop {
                 if self.pos >= self.tokens.len() {
                     return self.error(")", "EOF");
                 }
 
-                if self.tokens[self.pos].text == ")" {
+                if self.peek_text() == ")" {
                     self.pos += 1; // consume ')'
                     break;
                 }
 
                 elements.push(self.parse_expr()?);
+                trailing_comma = self.peek_text() == ",";
+                self.list_separator(")")?;
+            }
 
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
-                    self.pos += 1;
-                } else if self.pos < self.tokens.len() && self.tokens[self.pos].text == ")" {
-                    // Allow trailing comma or no comma before )
-                    continue;
-                } else {
-                    return self.error(", or )", &self.tokens[self.pos].text);
-                }
+            // `(e)` only groups; `(e,)` is a one-element tuple
+            if elements.len() == 1 && !trailing_comma {
+                return Ok(elements.remove(0));
             }
 
             // Represent tuple as a special call
             return Ok(SurfaceExpr::Call("__tuple__".to_string(), elements));
         }
 
-        if self.tokens[self.pos].text.parse::<i64>().is_ok() {
+        if self.peek_text().parse::<i64>().is_ok() {
             let token_text = self.consume_token()?.text.clone();
             let n = token_text.parse().unwrap();
             return Ok(SurfaceExpr::IntLit(n));
         }
 
         // Boolean literals
-        if self.tokens[self.pos].text == "true" {
+        if self.peek_text() == "true" {
             self.pos += 1;
             return Ok(SurfaceExpr::BoolLit(true));
         }
-        if self.tokens[self.pos].text == "false" {
+        if self.peek_text() == "false" {
             self.pos += 1;
             return Ok(SurfaceExpr::BoolLit(false));
         }
 
         // String literals
-        if self.tokens[self.pos].text.starts_with('"') && self.tokens[self.pos].text.ends_with('"')
+        // (a lone `"` is an unterminated literal, not an empty string)
+        if self.peek_text().len() >= 2
+            && self.peek_text().starts_with('"')
+            && self.peek_text().ends_with('"')
         {
             let token_text = self.consume_token()?.text.clone();
             let content = token_text[1..token_text.len() - 1].to_string(); // Remove quotes
@@ -706,21 +1071,27 @@ impl Parser {
             return Ok(SurfaceExpr::StringLit(unescaped));
         }
 
+        // Any other keyword would be read as a variable (`Var("fn")`, an
+        // illegal Core var), except `proj` in a call
+        if lexer::is_keyword(self.peek_text()) && !(self.peek_text() == "proj" && self.peek_text_at(1) == "(") {
+            return self.keyword_error("expression");
+        }
+
         let mut name = self.consume_token()?.text.clone();
 
         // Handle qualified identifiers: both '.' and '::'
         // e.g., cli.parse_args or Result::Ok or SurfaceAst::SUnitLit
         loop {
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == "." {
+            if self.pos < self.tokens.len() && self.peek_text() == "." {
                 self.pos += 1;
                 if self.pos < self.tokens.len() {
-                    let next = self.consume_token()?.text.clone();
+                    let next = self.consume_name("name after '.'")?;
                     name = format!("{}.{}", name, next);
                 }
-            } else if self.pos < self.tokens.len() && self.tokens[self.pos].text == "::" {
+            } else if self.pos < self.tokens.len() && self.peek_text() == "::" {
                 self.pos += 1;
                 if self.pos < self.tokens.len() {
-                    let next = self.consume_token()?.text.clone();
+                    let next = self.consume_name("name after '::'")?;
                     name = format!("{}::{}", name, next);
                 }
             } else {
@@ -728,14 +1099,12 @@ impl Parser {
             }
         }
 
-        if self.pos < self.tokens.len() && self.tokens[self.pos].text == "(" {
+        if self.pos < self.tokens.len() && self.peek_text() == "(" {
             self.pos += 1;
             let mut args = Vec::new();
-            while self.pos < self.tokens.len() && self.tokens[self.pos].text != ")" {
+            while self.pos < self.tokens.len() && self.peek_text() != ")" {
                 args.push(self.parse_expr()?);
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
-                    self.pos += 1;
-                }
+                self.list_separator(")")?;
             }
             self.expect_token(")")?;
 
@@ -759,7 +1128,7 @@ impl Parser {
 
             Ok(SurfaceExpr::Call(name, args))
         } else if self.pos < self.tokens.len()
-            && self.tokens[self.pos].text == "{"
+            && self.peek_text() == "{"
             && name.chars().next().map_or(false, |c| c.is_uppercase())
         {
             // Struct/record literal: TypeName { field1: expr1, field2: expr2, ... }
@@ -767,9 +1136,9 @@ impl Parser {
             self.pos += 1; // consume '{'
             let mut fields = Vec::new();
 
-            while self.pos < self.tokens.len() && self.tokens[self.pos].text != "}" {
+            while self.pos < self.tokens.len() && self.peek_text() != "}" {
                 // Parse field_name: expr (per Surface 0.7 spec section 8.1)
-                let field_name = self.consume_token()?.text.clone();
+                let field_name = self.consume_name("field name")?;
                 self.expect_token(":")?;
                 let field_expr = self.parse_expr()?;
 
@@ -777,10 +1146,7 @@ impl Parser {
                 // treats it as a value (not a variable reference).
                 fields.push(SurfaceExpr::StringLit(field_name));
                 fields.push(field_expr);
-
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
-                    self.pos += 1;
-                }
+                self.list_separator("}")?;
             }
 
             self.expect_token("}")?;
@@ -795,10 +1161,10 @@ impl Parser {
     }
 
     fn parse_path(&mut self) -> Result<Vec<String>, ParseError> {
-        let mut path = vec![self.consume_token()?.text.clone()];
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text == "." {
+        let mut path = vec![self.consume_name("module name")?];
+        while self.pos < self.tokens.len() && self.peek_text() == "." {
             self.pos += 1;
-            path.push(self.consume_token()?.text.clone());
+            path.push(self.consume_name("module name")?);
         }
         Ok(path)
     }
@@ -809,53 +1175,30 @@ impl Parser {
         self.expect_token("{")?;
 
         let mut arms = Vec::new();
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text != "}" {
+        while self.pos < self.tokens.len() && self.peek_text() != "}" {
             // Parse pattern (simplified - just collect tokens until =>)
+            let start = self.pos;
             let mut pattern_tokens = Vec::new();
             while self.pos < self.tokens.len()
-                && self.tokens[self.pos].text != "=>"
-                && self.tokens[self.pos].text != "}"
+                && self.peek_text() != "=>"
+                && self.peek_text() != "}"
             {
                 pattern_tokens.push(self.consume_token()?.text.clone());
             }
+            self.check_pattern_names(start)?;
 
             if pattern_tokens.is_empty() {
                 // If we hit }, we're done with arms
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == "}" {
+                if self.pos < self.tokens.len() && self.peek_text() == "}" {
                     break;
                 }
                 return self.error("pattern", "=>");
             }
 
-            // Join pattern tokens, removing spaces around '.' and '::'
-            let mut pattern = String::new();
-            let mut i = 0;
-            while i < pattern_tokens.len() {
-                pattern.push_str(&pattern_tokens[i]);
-
-                // Don't add space before '.', '::', '(', ')', ',', or after '('
-                if i + 1 < pattern_tokens.len() {
-                    let current = &pattern_tokens[i];
-                    let next = &pattern_tokens[i + 1];
-
-                    if next != "."
-                        && next != "::"
-                        && next != "("
-                        && next != ")"
-                        && next != ","
-                        && current != "("
-                        && current != "."
-                        && current != "::"
-                    {
-                        pattern.push(' ');
-                    }
-                }
-
-                i += 1;
-            }
+            let pattern = join_pattern_tokens(&pattern_tokens);
 
             // Check if we have => or hit end
-            if self.pos >= self.tokens.len() || self.tokens[self.pos].text != "=>" {
+            if self.pos >= self.tokens.len() || self.peek_text() != "=>" {
                 // If we don't have =>, treat as end of match
                 break;
             }
@@ -868,37 +1211,34 @@ impl Parser {
             arms.push(MatchArm { pattern, expr });
 
             // Optional comma after the match arm
-            if self.pos < self.tokens.len() && self.tokens[self.pos].text == "," {
+            if self.pos < self.tokens.len() && self.peek_text() == "," {
                 self.pos += 1;
             }
         }
 
+        // Lowering needs an arm to build the match from
+        if arms.is_empty() {
+            return self.error("match arm", self.peek_text());
+        }
         self.expect_token("}")?;
         Ok(SurfaceExpr::Match(scrutinee, arms))
     }
 
-    fn parse_if(&mut self) -> Result<SurfaceExpr, ParseError> {
+    // `if c { ... }` without else is only a statement: its value is (), and
+    // a value is wanted where an `if` does not start a statement
+    fn parse_if(&mut self, statement: bool) -> Result<SurfaceExpr, ParseError> {
         self.expect_token("if")?;
         let cond = Box::new(self.parse_expr()?);
 
         // Parse then block
         self.expect_token("{")?;
         let mut then_stmts = Vec::new();
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text != "}" {
-            if self.tokens[self.pos].text == "let" {
-                self.pos += 1;
-                let name = self.consume_token()?.text.clone();
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == ":" {
-                    self.pos += 1; // skip ':'
-                    self.skip_type()?; // skip the type expression
-                }
-                self.expect_token("=")?;
-                let expr = self.parse_expr()?;
-                self.expect_token(";")?;
-                then_stmts.push(SurfaceStmt::Let(name, expr));
+        while self.pos < self.tokens.len() && self.peek_text() != "}" {
+            if self.peek_text() == "let" {
+                then_stmts.push(self.parse_let_stmt()?);
             } else {
                 let expr = self.parse_expr()?;
-                if self.pos < self.tokens.len() && self.tokens[self.pos].text == ";" {
+                if self.pos < self.tokens.len() && self.peek_text() == ";" {
                     self.pos += 1;
                     then_stmts.push(SurfaceStmt::Expr(expr));
                 } else {
@@ -909,31 +1249,29 @@ impl Parser {
         }
         self.expect_token("}")?;
 
+        if statement && self.peek_text() != "else" {
+            return Ok(SurfaceExpr::If {
+                cond,
+                then_branch: Box::new(SurfaceExpr::Block(then_stmts)),
+                else_branch: Box::new(SurfaceExpr::UnitLit),
+            });
+        }
         self.expect_token("else")?;
 
         // Parse else branch - can be either 'else if' or 'else { ... }'
-        let else_branch = if self.pos < self.tokens.len() && self.tokens[self.pos].text == "if" {
+        let else_branch = if self.pos < self.tokens.len() && self.peek_text() == "if" {
             // else if - recursively parse another if expression
-            Box::new(self.parse_if()?)
+            Box::new(self.parse_if(statement)?)
         } else {
             // else block
             self.expect_token("{")?;
             let mut else_stmts = Vec::new();
-            while self.pos < self.tokens.len() && self.tokens[self.pos].text != "}" {
-                if self.tokens[self.pos].text == "let" {
-                    self.pos += 1;
-                    let name = self.consume_token()?.text.clone();
-                    if self.pos < self.tokens.len() && self.tokens[self.pos].text == ":" {
-                        self.pos += 1; // skip ':'
-                        self.skip_type()?; // skip the type expression
-                    }
-                    self.expect_token("=")?;
-                    let expr = self.parse_expr()?;
-                    self.expect_token(";")?;
-                    else_stmts.push(SurfaceStmt::Let(name, expr));
+            while self.pos < self.tokens.len() && self.peek_text() != "}" {
+                if self.peek_text() == "let" {
+                    else_stmts.push(self.parse_let_stmt()?);
                 } else {
                     let expr = self.parse_expr()?;
-                    if self.pos < self.tokens.len() && self.tokens[self.pos].text == ";" {
+                    if self.pos < self.tokens.len() && self.peek_text() == ";" {
                         self.pos += 1;
                         else_stmts.push(SurfaceStmt::Expr(expr));
                     } else {
@@ -953,6 +1291,80 @@ impl Parser {
         })
     }
 
+    // Text of the current token, or "" at end of input.
+    // POLICY: all lookahead goes through here so truncated input yields a
+    // ParseError instead of an index-out-of-bounds panic.
+    fn peek_text(&self) -> &str {
+        self.tokens.get(self.pos).map(|t| t.text.as_str()).unwrap_or("")
+    }
+
+    // After an item of a comma-separated list closed by `close` (arguments,
+    // parameters, tuple elements, record fields): the comma, which may
+    // also follow the last item
+    fn list_separator(&mut self, close: &str) -> Result<(), ParseError> {
+        match self.peek_text() {
+            "," => {
+                self.pos += 1;
+                Ok(())
+            }
+            // The caller's expect_token reports a missing `close` at EOF
+            text if text == close || self.pos >= self.tokens.len() => Ok(()),
+            text => {
+                let found = text.to_string();
+                self.error(&format!(", or {}", close), &found)
+            }
+        }
+    }
+
+    fn peek_text_at(&self, offset: usize) -> &str {
+        self.tokens.get(self.pos + offset).map(|t| t.text.as_str()).unwrap_or("")
+    }
+
+    // The next token as a name: an identifier, and not a keyword
+    fn consume_name(&mut self, what: &'static str) -> Result<String, ParseError> {
+        match self.tokens.get(self.pos) {
+            None => self.error(what, "EOF"),
+            Some(token) if lexer::is_keyword(&token.text) => self.keyword_error(what),
+            Some(token) if token.kind != TokenKind::Identifier => self.error(what, &token.text),
+            Some(token) => {
+                let name = token.text.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+        }
+    }
+
+    // The names in the pattern tokens from `start` to here (let and match
+    // patterns are kept as text): no keyword, except the literals `true`
+    // and `false`, and a variable before each `@` of `name @ pattern`
+    fn check_pattern_names(&mut self, start: usize) -> Result<(), ParseError> {
+        let keyword = (start..self.pos).find(|&i| {
+            let text = self.tokens[i].text.as_str();
+            lexer::is_keyword(text) && text != "true" && text != "false"
+        });
+        if let Some(pos) = keyword {
+            self.pos = pos;
+            return self.keyword_error("pattern");
+        }
+        let text = |i: usize| self.tokens[i].text.as_str();
+        let unnamed = (start..self.pos).find(|&at| {
+            text(at) == "@"
+                && !(at > start
+                    && self.tokens[at - 1].kind == TokenKind::Identifier
+                    && !text(at - 1).starts_with(char::is_uppercase)
+                    && (at - 1 == start || matches!(text(at - 2), "(" | "," | "@"))
+                    && at + 1 < self.pos
+                    && !matches!(text(at + 1), ")" | ","))
+        });
+        match unnamed {
+            Some(at) => {
+                self.pos = at;
+                self.error("name @ pattern", "@")
+            }
+            None => Ok(()),
+        }
+    }
+
     fn expect_token(&mut self, expected: &str) -> Result<(), ParseError> {
         if self.pos >= self.tokens.len() {
             return self.error(expected, "EOF");
@@ -977,47 +1389,52 @@ impl Parser {
 
     // Consume a qualified name (dotted identifier like "axis.char.is_digit")
     // Returns the full name as a string
-    fn consume_qualified_name(&mut self) -> Result<String, ParseError> {
-        let mut parts = vec![self.consume_token()?.text.clone()];
+    fn consume_qualified_name(&mut self, what: &'static str) -> Result<String, ParseError> {
+        let mut parts = vec![self.consume_name(what)?];
         
         // Continue consuming "." + identifier pairs
-        while self.pos < self.tokens.len() && self.tokens[self.pos].text == "." {
+        while self.pos < self.tokens.len() && self.peek_text() == "." {
             self.pos += 1; // consume the dot
             if self.pos >= self.tokens.len() {
                 return self.error("identifier after '.'", "EOF");
             }
-            parts.push(self.consume_token()?.text.clone());
+            parts.push(self.consume_name(what)?);
         }
         
         Ok(parts.join("."))
     }
 
     fn error<T>(&self, expected: &str, found: &str) -> Result<T, ParseError> {
-        let (line, column, source_line) = if self.pos < self.tokens.len() {
+        Err(self.error_here(ParseErrorKind::Unexpected { expected: expected.to_string(), found: found.to_string() }))
+    }
+
+    // The keyword at the current token, where `expected` is required
+    fn keyword_error<T>(&self, expected: &'static str) -> Result<T, ParseError> {
+        Err(self.error_here(ParseErrorKind::Keyword { keyword: self.peek_text().to_string(), expected }))
+    }
+
+    // An error at the current token, or just past the last at end of input
+    fn error_here(&self, kind: ParseErrorKind) -> ParseError {
+        let (line, column, width, source_line) = if self.pos < self.tokens.len() {
             let token = &self.tokens[self.pos];
             (
                 token.location.line,
                 token.location.column,
+                token.text.chars().count().max(1),
                 self.get_source_line(token.location.line),
             )
         } else if let Some(last_token) = self.tokens.last() {
             (
                 last_token.location.line,
                 last_token.location.column + last_token.text.len(),
+                1,
                 self.get_source_line(last_token.location.line),
             )
         } else {
-            (1, 1, "<empty file>".to_string())
+            (1, 1, 1, "<empty file>".to_string())
         };
 
-        Err(ParseError {
-            file: self.file.clone(),
-            line,
-            column,
-            found: found.to_string(),
-            expected: expected.to_string(),
-            source_line,
-        })
+        ParseError { file: self.file.clone(), line, column, width, kind, source_line }
     }
 
     fn get_source_line(&self, line_num: usize) -> String {
@@ -1029,327 +1446,33 @@ impl Parser {
     }
 }
 
+// Tokens without the lexical errors, for dump_tokens and the helpers that
+// scan source; parse_module_with_file reports the errors
 fn tokenize_with_location(source: &str) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut chars = source.chars().peekable();
-    let mut line = 1;
-    let mut column = 1;
-    let mut byte_offset = 0;
-    let mut last_logged = 0;
-
-    while let Some(&ch) = chars.peek() {
-        if byte_offset >= last_logged + 10000 {
-            eprintln!("[PROGRESS] phase=axis_compiler loop=tokenize count={}", byte_offset);
-            last_logged = byte_offset;
-        }
-        if ch.is_whitespace() {
-            if ch == '\n' {
-                line += 1;
-                column = 1;
-            } else {
-                column += 1;
-            }
-            chars.next();
-            byte_offset += 1;
-        } else if ch == '/' && chars.clone().nth(1) == Some('/') {
-            // Skip line comment
-            while let Some(c) = chars.next() {
-                byte_offset += 1;
-                if c == '\n' {
-                    line += 1;
-                    column = 1;
-                    break;
-                }
-                column += 1;
-            }
-        } else if ch.is_alphabetic() || ch == '_' {
-            let start_column = column;
-            let start_offset = byte_offset;
-            let mut ident = String::new();
-            while let Some(&c) = chars.peek() {
-                if c.is_alphanumeric() || c == '_' {
-                    ident.push(chars.next().unwrap());
-                    column += 1;
-                    byte_offset += 1;
-                } else {
-                    break;
-                }
-            }
-            tokens.push(Token {
-                text: ident,
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch.is_numeric() {
-            let start_column = column;
-            let start_offset = byte_offset;
-            let mut num = String::new();
-            while let Some(&c) = chars.peek() {
-                if c.is_numeric() {
-                    num.push(chars.next().unwrap());
-                    column += 1;
-                    byte_offset += 1;
-                } else {
-                    break;
-                }
-            }
-            tokens.push(Token {
-                text: num,
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '-' && chars.clone().nth(1) == Some('>') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "->".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '-' && chars.clone().nth(1) == Some('>') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "->".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '+' && chars.clone().nth(1) == Some('+') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "++".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '=' && chars.clone().nth(1) == Some('>') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "=>".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '=' && chars.clone().nth(1) == Some('=') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "==".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '!' && chars.clone().nth(1) == Some('=') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "!=".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '>' && chars.clone().nth(1) == Some('=') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: ">=".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '<' && chars.clone().nth(1) == Some('=') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "<=".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '&' && chars.clone().nth(1) == Some('&') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "&&".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '|' && chars.clone().nth(1) == Some('|') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "||".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == ':' && chars.clone().nth(1) == Some(':') {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next();
-            chars.next();
-            column += 2;
-            byte_offset += 2;
-            tokens.push(Token {
-                text: "::".to_string(),
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '"' {
-            let start_column = column;
-            let start_offset = byte_offset;
-            chars.next(); // consume opening quote
-            column += 1;
-            byte_offset += 1;
-            let mut string_literal = String::new();
-            string_literal.push('"'); // include the opening quote in the token
-
-            while let Some(&c) = chars.peek() {
-                if c == '"' {
-                    string_literal.push(chars.next().unwrap());
-                    column += 1;
-                    byte_offset += 1;
-                    break;
-                } else if c == '\\' {
-                    // Handle escape sequences
-                    string_literal.push(chars.next().unwrap());
-                    column += 1;
-                    byte_offset += 1;
-                    if let Some(&_next_c) = chars.peek() {
-                        string_literal.push(chars.next().unwrap());
-                        column += 1;
-                        byte_offset += 1;
-                    }
-                } else {
-                    string_literal.push(chars.next().unwrap());
-                    if c == '\n' {
-                        line += 1;
-                        column = 1;
-                    } else {
-                        column += 1;
-                    }
-                    byte_offset += 1;
-                }
-            }
+    lexer::tokens(source)
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Error)
+        .collect()
+}
 
-            tokens.push(Token {
-                text: string_literal,
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if "(){}[],.;:<>+-*/%".contains(ch) {
-            let start_column = column;
-            let start_offset = byte_offset;
-            let tok = chars.next().unwrap().to_string();
-            column += 1;
-            byte_offset += 1;
-            tokens.push(Token {
-                text: tok,
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else if ch == '=' {
-            let start_column = column;
-            let start_offset = byte_offset;
-            let tok = chars.next().unwrap().to_string();
-            column += 1;
-            byte_offset += 1;
-            tokens.push(Token {
-                text: tok,
-                location: SourceLocation {
-                    line,
-                    column: start_column,
-                    byte_offset: start_offset,
-                },
-            });
-        } else {
-            chars.next(); // skip unknown char
-            column += 1;
-            byte_offset += 1;
+// Pattern text from its tokens (match arms and let patterns), without
+// spaces around '.', '::' and parentheses: `Token::TokEof(_, x)`
+fn join_pattern_tokens(tokens: &[String]) -> String {
+    let mut pattern = String::new();
+    for (i, current) in tokens.iter().enumerate() {
+        // A trailing comma, `Some(x,)`, says nothing
+        if current == "," && tokens.get(i + 1).is_some_and(|next| next == ")") {
+            continue;
+        }
+        pattern.push_str(current);
+        if let Some(next) = tokens.get(i + 1)
+            && !matches!(next.as_str(), "." | "::" | "(" | ")" | ",")
+            && !matches!(current.as_str(), "(" | "." | "::")
+        {
+            pattern.push(' ');
         }
     }
-
-    tokens
+    pattern
 }
 
 #[allow(dead_code)]
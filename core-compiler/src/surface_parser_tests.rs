@@ -0,0 +1,177 @@
+// Tests for the surface parser (surface_parser.rs): where names are
+// required, and the comma-separated lists
+
+use crate::ast_dump;
+use crate::diagnostics::Diagnostic;
+use crate::error_codes::ErrorCode;
+use crate::lexer::KEYWORDS;
+use crate::surface_parser::{self, ParseError, ParseErrorKind, SurfaceExpr, SurfaceStmt};
+
+fn parse_error(source: &str) -> ParseError {
+    match surface_parser::parse_module_with_file(source, "main.ax") {
+        Ok(_) => panic!("parsed: {}", source),
+        Err(error) => error,
+    }
+}
+
+// The keyword and what was expected in its place
+fn keyword_error(source: &str) -> (String, &'static str) {
+    let error = parse_error(source);
+    match error.kind {
+        ParseErrorKind::Keyword { keyword, expected } => (keyword, expected),
+        _ => panic!("{}: {}", source, error),
+    }
+}
+
+#[test]
+fn test_every_keyword_is_reserved_where_a_name_is_required() {
+    for &keyword in KEYWORDS {
+        let cases = [
+            (format!("fn {}() {{ 1 }}\n", keyword), "function name"),
+            (format!("foreign fn axis.{}(x: Int) -> Int\n", keyword), "function name"),
+            (format!("fn f(a, {}) {{ a }}\n", keyword), "parameter name"),
+            (format!("fn f() {{ let {} = 1; 2 }}\n", keyword), "variable name"),
+            (format!("fn f() {{ let {} = 1 in 2 }}\n", keyword), "variable name"),
+            (format!("type Point {{ x: Int, {}: Int }}\n", keyword), "field name"),
+            (format!("fn f() {{ Point {{ {}: 1 }} }}\n", keyword), "field name"),
+            (format!("module geo.{} {{ }}\n", keyword), "module name"),
+            (format!("use text.{};\n", keyword), "module name"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(keyword_error(&source), (keyword.to_string(), expected), "{}", source);
+        }
+    }
+}
+
+#[test]
+fn test_keywords_are_not_pattern_bindings() {
+    for &keyword in KEYWORDS.iter().filter(|&&k| k != "true" && k != "false") {
+        let arm = format!("fn f(x) {{ match x {{ Some({}) => 1, _ => 0 }} }}\n", keyword);
+        assert_eq!(keyword_error(&arm), (keyword.to_string(), "pattern"), "{}", arm);
+        let let_pattern = format!("fn f(p) {{ let (a, {}) = p; a }}\n", keyword);
+        assert_eq!(keyword_error(&let_pattern), (keyword.to_string(), "pattern"), "{}", let_pattern);
+    }
+    // `true` and `false` are literal patterns
+    let source = "fn f(b) { match b { true => 1, false => 0 } }\nfn g(p) { let (true, x) = p else 0; x }\n";
+    assert!(surface_parser::parse_module_with_file(source, "main.ax").is_ok());
+}
+
+#[test]
+fn test_as_patterns_name_a_pattern() {
+    let source = "fn f(x) { match x { all @ Some(y) => 1, Cons(h, t @ Cons(_, _)) => 2, _ => 0 } }\n\
+                  fn g(p) { let whole @ (a, b) = p; a }\n";
+    assert!(surface_parser::parse_module_with_file(source, "main.ax").is_ok());
+    for arm in ["@ Some(y)", "Some(y) @ x", "None @ x", "(a, b @)", "x @"] {
+        let source = format!("fn f(x) {{ match x {{ {} => 1, _ => 0 }} }}\n", arm);
+        let error = parse_error(&source);
+        assert_eq!(Diagnostic::from(&error).message, "expected 'name @ pattern', found '@'", "{}", source);
+    }
+}
+
+#[test]
+fn test_keywords_are_not_variables() {
+    for keyword in ["else", "in", "type", "use", "module", "foreign", "pub", "fn", "proj"] {
+        let source = format!("fn f(x) {{ g(x, {}) }}\n", keyword);
+        assert_eq!(keyword_error(&source), (keyword.to_string(), "expression"), "{}", source);
+    }
+    assert_eq!(keyword_error("fn f(x) { x.match }\n"), ("match".to_string(), "name after '.'"));
+    // `proj` is called, never named
+    assert!(surface_parser::parse_module_with_file("fn f(p) { proj(p, 0) }\n", "main.ax").is_ok());
+}
+
+#[test]
+fn test_keyword_diagnostic_points_at_the_keyword() {
+    let error = parse_error("fn first(pair, type) {\n    proj(pair, 0)\n}\n");
+    assert_eq!((error.line, error.column, error.width), (1, 16, 4));
+
+    let diagnostic = Diagnostic::from(&error);
+    assert_eq!(diagnostic.code, Some(ErrorCode::Keyword));
+    assert_eq!(diagnostic.message, "expected parameter name, found keyword `type`");
+    assert_eq!(diagnostic.help, ["keywords cannot be names; rename it, e.g. `type_`"]);
+}
+
+#[test]
+fn test_names_must_be_identifiers() {
+    let error = parse_error("fn f(1) { 1 }\n");
+    assert_eq!(Diagnostic::from(&error).message, "expected 'parameter name', found '1'");
+    let error = parse_error("fn \"main\"() { 1 }\n");
+    assert_eq!(Diagnostic::from(&error).message, "expected 'function name', found '\"main\"'");
+}
+
+fn ast(source: &str) -> String {
+    match surface_parser::parse_module_with_file(source, "main.ax") {
+        Ok(module) => ast_dump::dump_text(&module),
+        Err(error) => panic!("{}\n{}", source, error),
+    }
+}
+
+#[test]
+fn test_trailing_commas_are_accepted_in_every_list() {
+    let cases = [
+        ("fn f() { g(1, 2,) }", "fn f() { g(1, 2) }"),
+        ("fn f(a, b: Int,) { a }", "fn f(a, b: Int) { a }"),
+        ("foreign fn axis.g(a: Int, b: Int,) -> Int", "foreign fn axis.g(a: Int, b: Int) -> Int"),
+        ("fn f() { (1, 2,) }", "fn f() { (1, 2) }"),
+        ("fn f() { Point { x: 1, y: 2, } }", "fn f() { Point { x: 1, y: 2 } }"),
+        ("fn f(x) { match x { Pair(a, b,) => a, } }", "fn f(x) { match x { Pair(a, b) => a } }"),
+        ("fn f(p) { let (a, Some(b,),) = p else 0; a }", "fn f(p) { let (a, Some(b)) = p else 0; a }"),
+        ("type Pair[A, B,] { MkPair(A, B,), Empty, }", "type Pair[A, B] { MkPair(A, B), Empty }"),
+    ];
+    for (with_comma, without) in cases {
+        assert_eq!(ast(with_comma), ast(without), "{}", with_comma);
+    }
+}
+
+#[test]
+fn test_lists_may_span_lines() {
+    let source = "fn f(\n    a,\n    b,\n) {\n    g(\n        a,\n        b,\n    )\n}\n";
+    assert_eq!(ast(source), ast("fn f(a, b) { g(a, b) }"));
+}
+
+#[test]
+fn test_list_items_need_commas_between_them() {
+    for (source, expected) in [
+        ("fn f() { g(1 2) }", "expected ', or )', found '2'"),
+        ("fn f(a b) { a }", "expected ', or )', found 'b'"),
+        ("fn f() { (1 2) }", "expected ', or )', found '2'"),
+        ("fn f() { Point { x: 1 y: 2 } }", "expected ', or }', found 'y'"),
+        ("type Pair[A B] { Empty }", "expected ', or ]', found 'B'"),
+    ] {
+        assert_eq!(Diagnostic::from(&parse_error(source)).message, expected, "{}", source);
+    }
+}
+
+#[test]
+fn test_if_without_else_is_a_statement_with_the_value_unit() {
+    let module = surface_parser::parse_module_with_file("fn f(c) { if c { g(1) } 0 }", "main.ax").unwrap();
+    let SurfaceExpr::Block(stmts) = &module.functions[0].body else { panic!("{}", ast_dump::dump_text(&module)) };
+    let [SurfaceStmt::Expr(SurfaceExpr::If { else_branch, .. }), SurfaceStmt::Expr(SurfaceExpr::IntLit(0))] = stmts.as_slice()
+    else {
+        panic!("{}", ast_dump::dump_text(&module))
+    };
+    assert!(matches!(**else_branch, SurfaceExpr::UnitLit));
+    // Also after `;` and a block, and down an else-if chain
+    ast("fn f(c) { g(0); if c { g(1) }; if c { 2 } if c { 3 } else if c { 4 } }");
+}
+
+#[test]
+fn test_if_used_as_a_value_needs_an_else() {
+    for source in [
+        "fn f(c) { let x = if c { 1 }; x }",
+        "fn f(c) { g(if c { 1 }) }",
+        "fn f(c) { match c { true => if c { 1 }, false => 0 } }",
+        "fn f(c) { 1 + if c { 1 } }",
+        "fn f(c) { let x = if c { 1 } else if c { 2 }; x }",
+    ] {
+        let diagnostic = Diagnostic::from(&parse_error(source));
+        assert!(diagnostic.message.starts_with("expected 'else'"), "{}: {}", source, diagnostic.message);
+        assert_eq!(diagnostic.help, ["an `if` without `else` is a statement; where its value is used, it needs an `else`"]);
+    }
+}
+
+#[test]
+fn test_match_needs_an_arm() {
+    for (source, found) in [("fn f(x) { match x { } }", "}"), ("fn f(x) { match x { y } }", "}")] {
+        assert_eq!(Diagnostic::from(&parse_error(source)).message, format!("expected 'match arm', found '{}'", found));
+    }
+}
@@ -14,12 +14,22 @@ pub fn value_to_core(v: &Value) -> CoreTerm {
             };
             return CoreTerm::Proj(obj, index, None);
         }
+        // CCtorField - constructor field read by pattern lets
+        if ctor == "CCtorField" && fields.len() == 2 {
+            let obj = Rc::new(value_to_core(&fields[0]));
+            let index = if let Value::Int(n) = &fields[1] {
+                *n as usize
+            } else {
+                panic!("CCtorField index must be an Int");
+            };
+            return CoreTerm::CtorField(obj, index, None);
+        }
     }
     
     if let Some((ctor_name, ctor_fields)) = try_extract_ctor(v) {
         let lowered_fields = ctor_fields
             .into_iter()
-            .map(|field| value_to_core(field))
+            .map(value_to_core)
             .collect();
         return CoreTerm::Ctor(ctor_name, lowered_fields, None);
     }
@@ -33,9 +43,9 @@ pub fn value_to_core(v: &Value) -> CoreTerm {
             let string_content = crate::get_string(*handle);
             CoreTerm::StrLit(string_content, None)
         }
-        Value::Var(name) => CoreTerm::Var(name.clone(), None),
+        Value::Var(name) => CoreTerm::Var(name.into(), None),
         Value::Lam(param, body) => {
-            CoreTerm::Lam(param.clone(), Rc::new(value_to_core(body)), None)
+            CoreTerm::Lam(param.into(), Rc::new(value_to_core(body)), None)
         }
         Value::App(func, arg) => {
             CoreTerm::App(
@@ -46,7 +56,7 @@ pub fn value_to_core(v: &Value) -> CoreTerm {
         }
         Value::Let(name, val, body) => {
             CoreTerm::Let(
-                name.clone(),
+                name.into(),
                 Rc::new(value_to_core(val)),
                 Rc::new(value_to_core(body)),
                 None
@@ -73,7 +83,7 @@ pub fn value_to_core(v: &Value) -> CoreTerm {
         }
         Value::Tuple(elems) => {
             //  Convert tuple elements
-            let core_elems = elems.iter().map(|e| value_to_core(e)).collect();
+            let core_elems = elems.iter().map(value_to_core).collect();
             CoreTerm::Tuple(core_elems, None)
         }
         _ => panic!("Cannot convert {:?} to CoreTerm", v),
@@ -81,8 +91,16 @@ pub fn value_to_core(v: &Value) -> CoreTerm {
 }
 
 // compiler pattern parser - handles constructor patterns with nesting
-fn parse_pattern(s: &str) -> Pattern {
+pub fn parse_pattern(s: &str) -> Pattern {
     let trimmed = s.trim();
+
+    // `name @ pattern` (the parser checks there is a name before each `@`)
+    if let Some((name, inner)) = trimmed.split_once('@') {
+        let name = name.trim();
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Pattern::PBind(name.to_string(), Box::new(parse_pattern(inner)));
+        }
+    }
     
     // Try to parse as int literal
     if let Ok(n) = trimmed.parse::<i64>() {
@@ -123,29 +141,29 @@ fn parse_pattern(s: &str) -> Pattern {
         }
     }
     
-    // If we found a constructor pattern
-    if let Some(paren_pos) = last_top_level_paren {
-        if let Some(close_paren_pos) = trimmed.rfind(')') {
-            // Extract constructor name (everything before opening paren)
-            let ctor_part = trimmed[..paren_pos].trim();
-            // Remove spaces around . and :: for constructor name
-            let ctor_name = ctor_part.replace(" . ", ".").replace(" :: ", "::").replace(" ", "");
-            
-            // Extract field patterns (everything between parens)
-            let fields_str = trimmed[paren_pos + 1..close_paren_pos].trim();
-            
-            // Split by top-level commas (not inside nested parens)
-            let field_patterns: Vec<Pattern> = if fields_str.is_empty() {
-                vec![]
-            } else {
-                split_by_top_level_comma(fields_str)
-                    .iter()
-                    .map(|f| parse_pattern(f.trim()))
-                    .collect()
-            };
-            
-            return Pattern::PEnum(ctor_name, field_patterns);
-        }
+    // If we found a constructor pattern (a leading paren is a tuple)
+    if let Some(paren_pos) = last_top_level_paren.filter(|&pos| pos > 0)
+        && let Some(close_paren_pos) = trimmed.rfind(')')
+    {
+        // Extract constructor name (everything before opening paren)
+        let ctor_part = trimmed[..paren_pos].trim();
+        // Remove spaces around . and :: for constructor name
+        let ctor_name = ctor_part.replace(" . ", ".").replace(" :: ", "::").replace(" ", "");
+        
+        // Extract field patterns (everything between parens)
+        let fields_str = trimmed[paren_pos + 1..close_paren_pos].trim();
+        
+        // Split by top-level commas (not inside nested parens)
+        let field_patterns: Vec<Pattern> = if fields_str.is_empty() {
+            vec![]
+        } else {
+            split_by_top_level_comma(fields_str)
+                .iter()
+                .map(|f| parse_pattern(f.trim()))
+                .collect()
+        };
+        
+        return Pattern::PEnum(ctor_name, field_patterns);
     }
     
     // Tuple patterns: ( pat1 , pat2 , ... )
@@ -208,9 +226,7 @@ fn split_by_top_level_comma(s: &str) -> Vec<String> {
 
 fn try_extract_ctor<'a>(value: &'a Value) -> Option<(String, Vec<&'a Value>)> {
     match value {
-        Value::Var(name) if is_constructor_name(name) => {
-            return Some((name.clone(), Vec::new()));
-        }
+        Value::Var(name) if is_constructor_name(name) => Some((name.clone(), Vec::new())),
         Value::App(_, _) => {
             let mut args: Vec<&'a Value> = Vec::new();
             let mut current = value;
@@ -218,11 +234,11 @@ fn try_extract_ctor<'a>(value: &'a Value) -> Option<(String, Vec<&'a Value>)> {
                 args.push(arg.as_ref());
                 current = func.as_ref();
             }
-            if let Value::Var(name) = current {
-                if is_constructor_name(name) {
-                    args.reverse();
-                    return Some((name.clone(), args));
-                }
+            if let Value::Var(name) = current
+                && is_constructor_name(name)
+            {
+                args.reverse();
+                return Some((name.clone(), args));
             }
             None
         }
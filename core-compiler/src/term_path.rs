@@ -0,0 +1,219 @@
+// Term paths: the address of a subterm of a Core program, for tools
+//
+//   main/lam/let.body/if.then/app.arg
+//
+// A path starts with the name of a top-level function, standing for the
+// value of the first binding of that name along the program's Let chain,
+// or with `/` for the program root. Then comes one step per node, named
+// after the node's kind (CoreTerm::kind_name) and the child taken:
+//
+//   lam                       the body of a lambda
+//   app.func, app.arg         the function and the argument of an application
+//   let.value, let.body       the bound value and the body of a let
+//   if.cond, if.then, if.else
+//   tuple[i], ctor[i]         the ith element or constructor field, from 0
+//   proj, ctor_field          the expression projected from
+//   match.scrutinee, match[i] the scrutinee, the body of the ith arm
+//
+// Core IR built from source carries no spans, so this is how validation
+// findings name the term they are about, and how `--view-core-ir --focus`
+// picks the subtree to print.
+
+use crate::runtime::CoreTerm;
+use crate::symbol_index::SymbolIndex;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    Lam,
+    AppFunc,
+    AppArg,
+    LetValue,
+    LetBody,
+    IfCond,
+    IfThen,
+    IfElse,
+    Tuple(usize),
+    Ctor(usize),
+    Proj,
+    CtorField,
+    MatchScrutinee,
+    MatchArm(usize),
+}
+
+impl Step {
+    fn parse(text: &str) -> Option<Step> {
+        let step = match text {
+            "lam" => Step::Lam,
+            "app.func" => Step::AppFunc,
+            "app.arg" => Step::AppArg,
+            "let.value" => Step::LetValue,
+            "let.body" => Step::LetBody,
+            "if.cond" => Step::IfCond,
+            "if.then" => Step::IfThen,
+            "if.else" => Step::IfElse,
+            "proj" => Step::Proj,
+            "ctor_field" => Step::CtorField,
+            "match.scrutinee" => Step::MatchScrutinee,
+            _ => {
+                let (kind, index) = text.strip_suffix(']')?.split_once('[')?;
+                let index = index.parse().ok()?;
+                match kind {
+                    "tuple" => Step::Tuple(index),
+                    "ctor" => Step::Ctor(index),
+                    "match" => Step::MatchArm(index),
+                    _ => return None,
+                }
+            }
+        };
+        Some(step)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Lam => write!(f, "lam"),
+            Step::AppFunc => write!(f, "app.func"),
+            Step::AppArg => write!(f, "app.arg"),
+            Step::LetValue => write!(f, "let.value"),
+            Step::LetBody => write!(f, "let.body"),
+            Step::IfCond => write!(f, "if.cond"),
+            Step::IfThen => write!(f, "if.then"),
+            Step::IfElse => write!(f, "if.else"),
+            Step::Tuple(index) => write!(f, "tuple[{}]", index),
+            Step::Ctor(index) => write!(f, "ctor[{}]", index),
+            Step::Proj => write!(f, "proj"),
+            Step::CtorField => write!(f, "ctor_field"),
+            Step::MatchScrutinee => write!(f, "match.scrutinee"),
+            Step::MatchArm(index) => write!(f, "match[{}]", index),
+        }
+    }
+}
+
+// The node's children, each with the step to it, in source order
+fn children(term: &CoreTerm) -> Vec<(Step, &CoreTerm)> {
+    match term {
+        CoreTerm::IntLit(..) | CoreTerm::BoolLit(..) | CoreTerm::UnitLit(..) | CoreTerm::StrLit(..) | CoreTerm::Var(..) => {
+            Vec::new()
+        }
+        CoreTerm::Lam(_, body, _) => vec![(Step::Lam, body)],
+        CoreTerm::App(func, arg, _) => vec![(Step::AppFunc, func), (Step::AppArg, arg)],
+        CoreTerm::Let(_, value, body, _) => vec![(Step::LetValue, value), (Step::LetBody, body)],
+        CoreTerm::If(cond, then_branch, else_branch, _) => {
+            vec![(Step::IfCond, cond), (Step::IfThen, then_branch), (Step::IfElse, else_branch)]
+        }
+        CoreTerm::Tuple(elems, _) => elems.iter().enumerate().map(|(i, elem)| (Step::Tuple(i), elem)).collect(),
+        CoreTerm::Ctor(_, fields, _) => fields.iter().enumerate().map(|(i, field)| (Step::Ctor(i), field)).collect(),
+        CoreTerm::Proj(expr, _, _) => vec![(Step::Proj, expr)],
+        CoreTerm::CtorField(expr, _, _) => vec![(Step::CtorField, expr)],
+        CoreTerm::Match(scrutinee, arms, _) => {
+            let mut all = vec![(Step::MatchScrutinee, scrutinee.as_ref())];
+            all.extend(arms.iter().enumerate().map(|(i, (_, body))| (Step::MatchArm(i), body)));
+            all
+        }
+    }
+}
+
+// The top-level functions a path can start with: the first binding of each
+// name along the root's Let chain, with the value bound
+fn top_level(root: &CoreTerm) -> Vec<(&str, &CoreTerm)> {
+    let mut seen = HashSet::new();
+    let mut bindings = Vec::new();
+    let mut current = root;
+    while let CoreTerm::Let(name, value, body, _) = current {
+        if seen.insert(name.as_str()) {
+            bindings.push((name.as_str(), value.as_ref()));
+        }
+        current = body;
+    }
+    bindings
+}
+
+fn format(start: &str, steps: &[Step]) -> String {
+    let steps: Vec<String> = steps.iter().map(Step::to_string).collect();
+    match (start, steps.is_empty()) {
+        ("", _) => format!("/{}", steps.join("/")),
+        (name, true) => name.to_string(),
+        (name, false) => format!("{}/{}", name, steps.join("/")),
+    }
+}
+
+/// The subterm of `root` that `path` names
+pub fn resolve<'a>(root: &'a CoreTerm, path: &str) -> Result<&'a CoreTerm, String> {
+    let (start, rest) = match path.strip_prefix('/') {
+        Some(rest) => ("", rest),
+        None => path.split_once('/').unwrap_or((path, "")),
+    };
+    let mut term = root;
+    if !start.is_empty() {
+        let functions = top_level(root);
+        term = match functions.iter().find(|(name, _)| *name == start) {
+            Some((_, value)) => value,
+            None => {
+                let mut symbols = SymbolIndex::default();
+                symbols.extend(functions.iter().map(|(name, _)| *name));
+                let hint = symbols.did_you_mean(start).map_or(String::new(), |hint| format!("; {}", hint));
+                return Err(format!("{}: no top-level function `{}`{}", path, start, hint));
+            }
+        };
+    }
+    let mut steps = Vec::new();
+    for text in rest.split('/').filter(|text| !text.is_empty()) {
+        let step = Step::parse(text).ok_or_else(|| {
+            format!("{}: `{}` is not a step (lam, app.func, app.arg, let.value, let.body, if.cond, if.then, if.else, tuple[i], ctor[i], proj, ctor_field, match.scrutinee, match[i])", path, text)
+        })?;
+        term = match children(term).into_iter().find(|(candidate, _)| *candidate == step) {
+            Some((_, child)) => child,
+            None => {
+                return Err(format!(
+                    "{}: no `{}` under {}, which is a {}",
+                    path,
+                    step,
+                    format(start, &steps),
+                    term.kind_name()
+                ))
+            }
+        };
+        steps.push(step);
+    }
+    Ok(term)
+}
+
+/// The path from `root` to `target` (found by address, so a subterm of
+/// `root` itself), through its top-level function where there is one
+pub fn path_of(root: &CoreTerm, target: &CoreTerm) -> Option<String> {
+    // Depth-first with an explicit stack, so deep terms are safe:
+    // (term, top-level function or "" for the root, steps from there)
+    let mut stack: Vec<(&CoreTerm, &str, Vec<Step>)> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut chain = Vec::new();
+    let mut current = root;
+    while let CoreTerm::Let(name, value, body, _) = current {
+        if std::ptr::eq(current, target) {
+            return Some(format("", &chain));
+        }
+        if seen.insert(name.as_str()) {
+            stack.push((value, name.as_str(), Vec::new()));
+        } else {
+            let mut steps = chain.clone();
+            steps.push(Step::LetValue);
+            stack.push((value, "", steps));
+        }
+        chain.push(Step::LetBody);
+        current = body;
+    }
+    stack.push((current, "", chain));
+    while let Some((term, start, steps)) = stack.pop() {
+        if std::ptr::eq(term, target) {
+            return Some(format(start, &steps));
+        }
+        for (step, child) in children(term).into_iter().rev() {
+            let mut steps = steps.clone();
+            steps.push(step);
+            stack.push((child, start, steps));
+        }
+    }
+    None
+}
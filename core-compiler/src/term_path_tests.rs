@@ -0,0 +1,120 @@
+// Tests for term paths (term_path.rs)
+
+use crate::runtime::{CoreTerm, Pattern};
+use crate::term_path::{path_of, resolve};
+use std::rc::Rc;
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn int(n: i64) -> CoreTerm {
+    CoreTerm::IntLit(n, None)
+}
+
+fn let_in(name: &str, value: CoreTerm, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Let(name.into(), Rc::new(value), Rc::new(body), None)
+}
+
+fn lam(param: &str, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Lam(param.into(), Rc::new(body), None)
+}
+
+fn app(func: CoreTerm, arg: CoreTerm) -> CoreTerm {
+    CoreTerm::App(Rc::new(func), Rc::new(arg), None)
+}
+
+// helper(x) = x
+// main(n) = let t = (n, 2) in if n then helper(t.0) else match n { 0 => 1, _ => n }
+fn program() -> CoreTerm {
+    let arms = vec![(Pattern::PInt(0), int(1)), (Pattern::PVar("_".to_string()), var("n"))];
+    let branch = CoreTerm::If(
+        Rc::new(var("n")),
+        Rc::new(app(var("helper"), CoreTerm::Proj(Rc::new(var("t")), 0, None))),
+        Rc::new(CoreTerm::Match(Rc::new(var("n")), arms, None)),
+        None,
+    );
+    let body = let_in("t", CoreTerm::Tuple(vec![var("n"), int(2)], None), branch);
+    let_in("helper", lam("x", var("x")), let_in("main", lam("n", body), CoreTerm::UnitLit(None)))
+}
+
+// Every subterm, depth first
+fn subterms(term: &CoreTerm) -> Vec<&CoreTerm> {
+    let mut all = vec![term];
+    let mut i = 0;
+    while i < all.len() {
+        let children: Vec<&CoreTerm> = match all[i] {
+            CoreTerm::Lam(_, body, _) => vec![body],
+            CoreTerm::App(func, arg, _) => vec![func, arg],
+            CoreTerm::Let(_, value, body, _) => vec![value, body],
+            CoreTerm::If(cond, then_branch, else_branch, _) => vec![cond, then_branch, else_branch],
+            CoreTerm::Tuple(elems, _) | CoreTerm::Ctor(_, elems, _) => elems.iter().collect(),
+            CoreTerm::Proj(expr, _, _) | CoreTerm::CtorField(expr, _, _) => vec![expr],
+            CoreTerm::Match(scrutinee, arms, _) => {
+                std::iter::once(scrutinee.as_ref()).chain(arms.iter().map(|(_, body)| body)).collect()
+            }
+            _ => Vec::new(),
+        };
+        all.extend(children);
+        i += 1;
+    }
+    all
+}
+
+#[test]
+fn test_paths_start_at_the_top_level_function() {
+    let root = program();
+    let proj = resolve(&root, "main/lam/let.body/if.then/app.arg").unwrap();
+    assert!(matches!(proj, CoreTerm::Proj(_, 0, _)));
+    assert_eq!(path_of(&root, proj).as_deref(), Some("main/lam/let.body/if.then/app.arg"));
+
+    let arm = resolve(&root, "main/lam/let.body/if.else/match[1]").unwrap();
+    assert!(matches!(arm, CoreTerm::Var(name, _) if name.as_str() == "n"));
+    assert!(matches!(resolve(&root, "main/lam/let.value/tuple[1]").unwrap(), CoreTerm::IntLit(2, _)));
+    assert!(matches!(resolve(&root, "helper").unwrap(), CoreTerm::Lam(..)));
+}
+
+#[test]
+fn test_paths_from_the_root_start_with_a_slash() {
+    let root = program();
+    assert!(std::ptr::eq(resolve(&root, "/").unwrap(), &root));
+    assert!(matches!(resolve(&root, "/let.body/let.body").unwrap(), CoreTerm::UnitLit(_)));
+    assert_eq!(path_of(&root, &root).as_deref(), Some("/"));
+    let tail = resolve(&root, "/let.body/let.body").unwrap();
+    assert_eq!(path_of(&root, tail).as_deref(), Some("/let.body/let.body"));
+}
+
+#[test]
+fn test_every_subterm_resolves_back_to_itself() {
+    let root = program();
+    for term in subterms(&root) {
+        let path = path_of(&root, term).expect("a subterm has a path");
+        assert!(std::ptr::eq(resolve(&root, &path).unwrap(), term), "{}", path);
+    }
+    assert_eq!(path_of(&root, &int(2)), None);
+}
+
+#[test]
+fn test_a_redefinition_is_addressed_from_the_root() {
+    // The name stands for the first binding; the second is reached by steps
+    let root = let_in("f", int(1), let_in("f", int(2), int(0)));
+    let second = resolve(&root, "/let.body/let.value").unwrap();
+    assert!(matches!(second, CoreTerm::IntLit(2, _)));
+    assert_eq!(path_of(&root, second).as_deref(), Some("/let.body/let.value"));
+    assert!(matches!(resolve(&root, "f").unwrap(), CoreTerm::IntLit(1, _)));
+}
+
+#[test]
+fn test_bad_paths_say_what_is_there() {
+    let root = program();
+    assert_eq!(resolve(&root, "mains/lam").unwrap_err(), "mains/lam: no top-level function `mains`; did you mean `main`?");
+    assert_eq!(
+        resolve(&root, "main/lam/if.then").unwrap_err(),
+        "main/lam/if.then: no `if.then` under main/lam, which is a let"
+    );
+    assert_eq!(
+        resolve(&root, "main/lam/let.value/tuple[2]").unwrap_err(),
+        "main/lam/let.value/tuple[2]: no `tuple[2]` under main/lam/let.value, which is a tuple"
+    );
+    assert!(resolve(&root, "main/body").unwrap_err().starts_with("main/body: `body` is not a step ("));
+}
@@ -0,0 +1,183 @@
+// `axis-compiler test`: discover and run the tests of an Axis program
+//
+// A test is a zero-parameter function marked @test, or named test_*. The
+// program is compiled once with a generated harness as its axis_entry and
+// built with axis-rust-bridge; each test then runs in its own process
+// (the binary is invoked with the test's index) so a runtime error fails
+// only that test.
+//
+// RESULT CONTRACT (harness exit code):
+//   0      - test returned Unit, true or Ok(_): pass
+//   1      - test returned anything else (false, Err(_), ...): fail
+//   2      - no test with the given index (harness misuse)
+//   3      - an axis_assert_eq / axis_assert_true / axis_fail call failed
+//   other  - runtime error inside the test (70) or proc_exit: fail
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+use crate::runtime::Value;
+use crate::surface_parser::{FnDef, Module};
+
+// Name of the harness argument bound by `Cons(..)` in the dispatch match
+const SELECTOR: &str = "axis_test_selector";
+
+fn is_test(func: &FnDef) -> bool {
+    func.has_attribute("test") || (func.name.starts_with("test_") && func.params.is_empty())
+}
+
+// Tests in definition order. @test on a function with parameters is an
+// error; a test_* function with parameters is simply not a test. A name
+// defined twice is listed once (--allow-duplicate-defs keeps the first).
+pub fn discover_tests(module: &Module) -> Result<Vec<String>, String> {
+    let mut tests = Vec::new();
+    for func in &module.functions {
+        if !is_test(func) || tests.contains(&func.name) {
+            continue;
+        }
+        if !func.params.is_empty() {
+            return Err(format!(
+                "@test function '{}' must take no parameters (has {})",
+                func.name,
+                func.params.len()
+            ));
+        }
+        tests.push(func.name.clone());
+    }
+    Ok(tests)
+}
+
+// The harness replaces the program entry: drop @entry and any axis_entry
+pub fn strip_entry(module: &mut Module) {
+    module.functions.retain(|f| f.name != "axis_entry");
+    for func in &mut module.functions {
+        func.attributes.retain(|attr| attr.name != "entry");
+    }
+}
+
+// match test(()) { () => 0, true => 0, Ok(_) => 0, _ => 1 }
+fn run_one(test: &str) -> Value {
+    let call = Value::App(Box::new(Value::Var(test.to_string())), Box::new(Value::Unit));
+    Value::Match(
+        Box::new(call),
+        vec![
+            ("()".to_string(), Value::Int(0)),
+            ("true".to_string(), Value::Int(0)),
+            ("Ok(_)".to_string(), Value::Int(0)),
+            ("_".to_string(), Value::Int(1)),
+        ],
+    )
+}
+
+// axis_entry(args): run the test whose index is the first argument
+fn harness_entry(tests: &[String]) -> Value {
+    let mut dispatch = Value::Int(2);
+    for (index, test) in tests.iter().enumerate().rev() {
+        let index_str = Value::Str(crate::intern_string(index.to_string()));
+        let is_selected = Value::App(
+            Box::new(Value::App(
+                Box::new(Value::Var("__eq__".to_string())),
+                Box::new(Value::Var(SELECTOR.to_string())),
+            )),
+            Box::new(index_str),
+        );
+        dispatch = Value::If(Box::new(is_selected), Box::new(run_one(test)), Box::new(dispatch));
+    }
+
+    let body = Value::Match(
+        Box::new(Value::Var("args".to_string())),
+        vec![
+            (format!("Cons({}, _)", SELECTOR), dispatch),
+            ("_".to_string(), Value::Int(2)),
+        ],
+    );
+    Value::Lam("args".to_string(), Box::new(body))
+}
+
+// Append `axis_entry = harness` as the innermost binding of a lowered
+// module, so the harness sees every function
+pub fn with_harness(lowered: Value, tests: &[String]) -> Value {
+    match lowered {
+        Value::Let(name, value, body) => {
+            Value::Let(name, value, Box::new(with_harness(*body, tests)))
+        }
+        tail => Value::Let(
+            "axis_entry".to_string(),
+            Box::new(harness_entry(tests)),
+            Box::new(tail),
+        ),
+    }
+}
+
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: Vec<String>,
+}
+
+// Why a test whose harness exited with `code` failed, or None if it passed
+fn failure_reason(code: Option<i32>) -> Option<String> {
+    match code {
+        Some(0) => None,
+        Some(1) => Some("returned a failing value".to_string()),
+        Some(3) => Some("assertion failed".to_string()),
+        Some(70) => Some("runtime error".to_string()),
+        Some(n) => Some(format!("exit code {}", n)),
+        None => Some("terminated by signal".to_string()),
+    }
+}
+
+// Run every test in its own process and write a line per test, the
+// captured output of failing tests and a summary line to `out`
+pub fn run_tests(binary: &Path, tests: &[String], out: &mut impl Write) -> io::Result<TestSummary> {
+    writeln!(out, "running {} test{}", tests.len(), if tests.len() == 1 { "" } else { "s" })?;
+
+    let mut summary = TestSummary { passed: 0, failed: Vec::new() };
+    let mut failure_output = Vec::new();
+    for (index, test) in tests.iter().enumerate() {
+        let output = Command::new(binary)
+            .arg(index.to_string())
+            .output()
+            .map_err(|e| io::Error::new(e.kind(), format!("Failed to run {}: {}", binary.display(), e)))?;
+
+        match failure_reason(output.status.code()) {
+            None => {
+                writeln!(out, "test {} ... ok", test)?;
+                summary.passed += 1;
+            }
+            Some(reason) => {
+                writeln!(out, "test {} ... FAILED ({})", test, reason)?;
+                summary.failed.push(test.clone());
+                failure_output.push((
+                    test,
+                    String::from_utf8_lossy(&output.stdout).into_owned(),
+                    String::from_utf8_lossy(&output.stderr).into_owned(),
+                ));
+            }
+        }
+    }
+
+    if !failure_output.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "failures:")?;
+        for (test, stdout, stderr) in failure_output {
+            writeln!(out)?;
+            writeln!(out, "---- {} ----", test)?;
+            if !stdout.is_empty() {
+                writeln!(out, "--- stdout\n{}", stdout.trim_end())?;
+            }
+            if !stderr.is_empty() {
+                writeln!(out, "--- stderr\n{}", stderr.trim_end())?;
+            }
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "test result: {}. {} passed; {} failed",
+        if summary.failed.is_empty() { "ok" } else { "FAILED" },
+        summary.passed,
+        summary.failed.len()
+    )?;
+    Ok(summary)
+}
@@ -0,0 +1,118 @@
+// Tests for `axis-compiler test` (test_runner.rs)
+
+use crate::surface_parser::parse_module;
+use crate::test_runner::{discover_tests, run_tests, strip_entry};
+
+#[test]
+fn test_discovery_follows_the_name_and_signature_rules() {
+    let module = parse_module(
+        "fn test_first() { true }\n\
+         @test\nfn checks_sum() { () }\n\
+         fn test_with_args(x) { x }\n\
+         fn helper() { 1 }\n\
+         fn testing() { true }\n\
+         @test\nfn test_both() { true }\n",
+    )
+    .unwrap();
+    assert_eq!(discover_tests(&module).unwrap(), ["test_first", "checks_sum", "test_both"]);
+
+    let module = parse_module("@test\nfn needs_input(a, b) { a }\n").unwrap();
+    assert_eq!(
+        discover_tests(&module).unwrap_err(),
+        "@test function 'needs_input' must take no parameters (has 2)"
+    );
+    assert!(discover_tests(&parse_module("fn main() { 0 }\n").unwrap()).unwrap().is_empty());
+}
+
+#[test]
+fn test_a_test_defined_twice_is_listed_once() {
+    let module = parse_module("fn test_a() { true }\nfn test_b() { true }\n@test\nfn test_a() { false }\n").unwrap();
+    assert_eq!(discover_tests(&module).unwrap(), ["test_a", "test_b"]);
+}
+
+#[test]
+fn test_strip_entry_removes_the_program_entry() {
+    let names = |source: &str| {
+        let mut module = parse_module(source).unwrap();
+        strip_entry(&mut module);
+        module.functions.iter().map(|f| (f.name.clone(), f.has_attribute("entry"))).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names("fn axis_entry(args) { 0 }\n@test\nfn test_one() { true }\n"),
+        [("test_one".to_string(), false)]
+    );
+    // An @entry function stays, but is no longer the entry point
+    assert_eq!(
+        names("@entry\nfn start(args) { 1 }\nfn test_one() { true }\n"),
+        [("start".to_string(), false), ("test_one".to_string(), false)]
+    );
+}
+
+// A stand-in for the harness binary: exits with the code listed for the
+// test index it is given, after some output for the failures to show
+#[cfg(unix)]
+#[test]
+fn test_the_report_counts_passes_and_failures() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("axis-test-runner-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let binary = dir.join("harness");
+    std::fs::write(
+        &binary,
+        "#!/bin/sh\n\
+         case \"$1\" in\n\
+         0) exit 0 ;;\n\
+         1) echo 'left: 1'; echo 'right: 2' >&2; exit 3 ;;\n\
+         2) exit 70 ;;\n\
+         3) exit 1 ;;\n\
+         *) exit 9 ;;\n\
+         esac\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let tests: Vec<String> = ["test_ok", "test_assert", "test_crash", "test_false", "test_odd"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let mut out = Vec::new();
+    let summary = run_tests(&binary, &tests, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(summary.passed, 1);
+    assert_eq!(summary.failed, ["test_assert", "test_crash", "test_false", "test_odd"]);
+    assert_eq!(
+        out,
+        "running 5 tests\n\
+         test test_ok ... ok\n\
+         test test_assert ... FAILED (assertion failed)\n\
+         test test_crash ... FAILED (runtime error)\n\
+         test test_false ... FAILED (returned a failing value)\n\
+         test test_odd ... FAILED (exit code 9)\n\
+         \n\
+         failures:\n\
+         \n\
+         ---- test_assert ----\n\
+         --- stdout\n\
+         left: 1\n\
+         --- stderr\n\
+         right: 2\n\
+         \n\
+         ---- test_crash ----\n\
+         \n\
+         ---- test_false ----\n\
+         \n\
+         ---- test_odd ----\n\
+         \n\
+         test result: FAILED. 1 passed; 4 failed\n"
+    );
+
+    let mut out = Vec::new();
+    let summary = run_tests(&binary, &tests[..1], &mut out).unwrap();
+    assert!(summary.failed.is_empty());
+    assert_eq!(String::from_utf8(out).unwrap(), "running 1 test\ntest test_ok ... ok\n\ntest result: ok. 1 passed; 0 failed\n");
+
+    let error = run_tests(&dir.join("missing"), &tests, &mut Vec::new()).err().unwrap();
+    assert!(error.to_string().starts_with("Failed to run "), "{}", error);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
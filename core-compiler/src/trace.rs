@@ -1,7 +1,333 @@
-// AXIS_TRACE=1 gated debug tracing (stderr only)
+// AXIS_TRACE=1 gated debug tracing (stderr only), and phase spans
+//
+// A span times one phase of the compiler, from span() until its guard is
+// dropped; spans opened while another is open nest in it:
+//
+//   let _span = trace::span("parse");
+//   let _span = trace::span_with("lower", &function.name);
+//
+// Spans are recorded only after start_profiling() (--timings,
+// --profile-out); otherwise span() only notes the phase, which internal
+// errors name (axis_ir::internal_error). finish() returns the spans in the order
+// they were opened, for format_timings() (an indented tree) and
+// chrome_trace() (trace-event JSON for chrome://tracing, Perfetto or
+// speedscope).
+//
+// Each span also records memory: the process's peak RSS when it ends (Linux
+// only), and, in builds with the `alloc-stats` feature, the peak heap size
+// while it was open and the number of allocations it made. alloc-stats
+// installs a counting global allocator, which costs a few atomic operations
+// per allocation; it is off by default.
+
+use crate::diagnostics::json_string;
+use crate::internal_error;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
 
 pub fn trace(msg: &str) {
     if std::env::var("AXIS_TRACE").is_ok() {
         eprintln!("[TRACE] {}", msg);
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    pub name: &'static str,
+    // What the phase worked on: a file, a function
+    pub detail: Option<String>,
+    // Number of enclosing spans
+    pub depth: usize,
+    // From start_profiling()
+    pub start: Duration,
+    pub duration: Duration,
+    // Largest heap size while the span was open (alloc-stats builds)
+    pub peak_heap: Option<u64>,
+    // Allocations made while the span was open (alloc-stats builds)
+    pub allocations: Option<u64>,
+    // Process peak RSS in KiB when the span ended (Linux)
+    pub peak_rss_kb: Option<u64>,
+}
+
+struct Profile {
+    epoch: Instant,
+    // The open spans, innermost last
+    open: Vec<OpenSpan>,
+    spans: Vec<SpanRecord>,
+}
+
+struct OpenSpan {
+    index: usize,
+    heap: Option<heap::Mark>,
+}
+
+thread_local! {
+    static PROFILE: RefCell<Option<Profile>> = const { RefCell::new(None) };
+}
+
+/// Guard of an open span; the span ends when it is dropped
+pub struct Span {
+    // Index into Profile::spans, None when not profiling
+    index: Option<usize>,
+    // Names the phase in internal errors, profiling or not
+    _phase: internal_error::Phase,
+}
+
+pub fn start_profiling() {
+    PROFILE.with(|profile| {
+        *profile.borrow_mut() = Some(Profile { epoch: Instant::now(), open: Vec::new(), spans: Vec::new() });
+    });
+}
+
+pub fn span(name: &'static str) -> Span {
+    Span { index: open(name, None), _phase: internal_error::enter_phase(name) }
+}
+
+pub fn span_with(name: &'static str, detail: &str) -> Span {
+    let index = if profiling() { open(name, Some(detail.to_string())) } else { None };
+    Span { index, _phase: internal_error::enter_phase(name) }
+}
+
+fn profiling() -> bool {
+    PROFILE.with(|profile| profile.borrow().is_some())
+}
+
+fn open(name: &'static str, detail: Option<String>) -> Option<usize> {
+    PROFILE.with(|profile| {
+        let mut profile = profile.borrow_mut();
+        let profile = profile.as_mut()?;
+        profile.spans.push(SpanRecord {
+            name,
+            detail,
+            depth: profile.open.len(),
+            start: profile.epoch.elapsed(),
+            duration: Duration::ZERO,
+            peak_heap: None,
+            allocations: None,
+            peak_rss_kb: None,
+        });
+        let index = profile.spans.len() - 1;
+        profile.open.push(OpenSpan { index, heap: heap::mark() });
+        Some(index)
+    })
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(index) = self.index else {
+            return;
+        };
+        PROFILE.with(|profile| {
+            if let Some(profile) = profile.borrow_mut().as_mut()
+                && let Some(position) = profile.open.iter().position(|open| open.index == index)
+            {
+                let open = profile.open.remove(position);
+                close(profile, open);
+            }
+        });
+    }
+}
+
+/// Stop profiling and take the recorded spans; spans still open are cut off
+/// at this point
+pub fn finish() -> Vec<SpanRecord> {
+    PROFILE.with(|profile| {
+        let Some(mut profile) = profile.borrow_mut().take() else {
+            return Vec::new();
+        };
+        while let Some(open) = profile.open.pop() {
+            close(&mut profile, open);
+        }
+        profile.spans
+    })
+}
+
+fn close(profile: &mut Profile, open: OpenSpan) {
+    let now = profile.epoch.elapsed();
+    let span = &mut profile.spans[open.index];
+    span.duration = now - span.start;
+    if let Some(mark) = open.heap {
+        let (peak, allocations) = heap::release(mark);
+        span.peak_heap = Some(peak);
+        span.allocations = Some(allocations);
+    }
+    span.peak_rss_kb = peak_rss_kb();
+}
+
+// VmHWM of /proc/self/status: the largest resident set size so far
+fn peak_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let value = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    value.trim().trim_end_matches("kB").trim().parse().ok()
+}
+
+// Heap accounting for alloc-stats builds. A span's mark swaps the peak for
+// the current size, so the peak seen at release is the span's own; release
+// puts back the larger of the two for the enclosing span.
+#[cfg(feature = "alloc-stats")]
+mod heap {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static CURRENT: AtomicU64 = AtomicU64::new(0);
+    static PEAK: AtomicU64 = AtomicU64::new(0);
+    static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+    pub struct Mark {
+        outer_peak: u64,
+        allocations: u64,
+    }
+
+    pub fn mark() -> Option<Mark> {
+        let outer_peak = PEAK.swap(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+        Some(Mark { outer_peak, allocations: ALLOCATIONS.load(Ordering::Relaxed) })
+    }
+
+    // The span's peak heap size and allocation count
+    pub fn release(mark: Mark) -> (u64, u64) {
+        let peak = PEAK.fetch_max(mark.outer_peak, Ordering::Relaxed);
+        (peak, ALLOCATIONS.load(Ordering::Relaxed) - mark.allocations)
+    }
+
+    fn grow(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        CURRENT.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                grow(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc_zeroed(layout) };
+            if !ptr.is_null() {
+                grow(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            shrink(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+            if !new_ptr.is_null() {
+                shrink(layout.size());
+                grow(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+mod heap {
+    pub enum Mark {}
+
+    pub fn mark() -> Option<Mark> {
+        None
+    }
+
+    pub fn release(mark: Mark) -> (u64, u64) {
+        match mark {}
+    }
+}
+
+/// A header, then one line per span indented by nesting:
+/// `    1.25 ms   2.4 MiB      310    18.0 MiB      lower main`. The heap
+/// and RSS columns are left out when nothing was measured.
+pub fn format_timings(spans: &[SpanRecord]) -> String {
+    let heap = spans.iter().any(|span| span.peak_heap.is_some());
+    let rss = spans.iter().any(|span| span.peak_rss_kb.is_some());
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    let mut out = format!("{:>12}", "time");
+    if heap {
+        out.push_str(&format!(" {:>10} {:>8}", "peak heap", "allocs"));
+    }
+    if rss {
+        out.push_str(&format!(" {:>10}", "peak rss"));
+    }
+    out.push_str("  phase\n");
+    for span in spans {
+        let label = match &span.detail {
+            Some(detail) => format!("{} {}", span.name, detail),
+            None => span.name.to_string(),
+        };
+        out.push_str(&format!("{:>9.2} ms", span.duration.as_secs_f64() * 1000.0));
+        if heap {
+            out.push_str(&format!(
+                " {:>10} {:>8}",
+                optional(span.peak_heap.map(format_bytes)),
+                optional(span.allocations.map(|count| count.to_string()))
+            ));
+        }
+        if rss {
+            out.push_str(&format!(" {:>10}", optional(span.peak_rss_kb.map(|kb| format_bytes(kb * 1024)))));
+        }
+        out.push_str(&format!("  {}{}\n", "  ".repeat(span.depth), label));
+    }
+    out
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes < KIB {
+        format!("{} B", bytes)
+    } else if bytes < KIB * KIB {
+        format!("{:.1} KiB", bytes / KIB)
+    } else if bytes < KIB * KIB * KIB {
+        format!("{:.1} MiB", bytes / (KIB * KIB))
+    } else {
+        format!("{:.1} GiB", bytes / (KIB * KIB * KIB))
+    }
+}
+
+/// Chrome trace-event format: one complete ("X") event per span, times in
+/// microseconds
+pub fn chrome_trace(spans: &[SpanRecord]) -> String {
+    let events: Vec<String> = spans
+        .iter()
+        .map(|span| {
+            let mut args = Vec::new();
+            if let Some(detail) = &span.detail {
+                args.push(format!("\"detail\":{}", json_string(detail)));
+            }
+            if let Some(peak) = span.peak_heap {
+                args.push(format!("\"peak_heap_bytes\":{}", peak));
+            }
+            if let Some(allocations) = span.allocations {
+                args.push(format!("\"allocations\":{}", allocations));
+            }
+            if let Some(kb) = span.peak_rss_kb {
+                args.push(format!("\"peak_rss_kb\":{}", kb));
+            }
+            let args = if args.is_empty() { String::new() } else { format!(",\"args\":{{{}}}", args.join(",")) };
+            format!(
+                "{{\"name\":{},\"cat\":\"axis-compiler\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1{}}}",
+                json_string(span.name),
+                span.start.as_micros(),
+                span.duration.as_micros(),
+                args
+            )
+        })
+        .collect();
+    format!("{{\"traceEvents\":[\n{}\n],\"displayTimeUnit\":\"ms\"}}\n", events.join(",\n"))
+}
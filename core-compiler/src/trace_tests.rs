@@ -0,0 +1,98 @@
+// Tests for phase spans (trace.rs)
+
+use crate::internal_error;
+use crate::trace::{self, chrome_trace, format_timings};
+
+#[test]
+fn test_spans_nest_in_open_order() {
+    trace::start_profiling();
+    {
+        let _run = trace::span("axis-compiler");
+        {
+            let _parse = trace::span_with("parse", "main.ax");
+        }
+        let _lower = trace::span("lower");
+        let _function = trace::span_with("lower", "main");
+    }
+    let spans = trace::finish();
+
+    let tree: Vec<(usize, &str, Option<&str>)> =
+        spans.iter().map(|span| (span.depth, span.name, span.detail.as_deref())).collect();
+    assert_eq!(
+        tree,
+        [(0, "axis-compiler", None), (1, "parse", Some("main.ax")), (1, "lower", None), (2, "lower", Some("main"))]
+    );
+    // A span contains the spans nested in it
+    assert!(spans[0].duration >= spans[2].duration && spans[2].duration >= spans[3].duration);
+    assert!(spans[3].start >= spans[1].start + spans[1].duration);
+
+    let timings = format_timings(&spans);
+    assert!(timings.starts_with("        time"), "{}", timings);
+    assert!(timings.lines().next().unwrap().ends_with("  phase"), "{}", timings);
+    assert!(timings.lines().nth(4).unwrap().ends_with("      lower main"), "{}", timings);
+}
+
+#[test]
+fn test_spans_record_memory() {
+    trace::start_profiling();
+    let kept = {
+        let _span = trace::span("allocate");
+        vec![0u8; 1 << 20]
+    };
+    let spans = trace::finish();
+    assert_eq!(kept.len(), 1 << 20);
+
+    if std::path::Path::new("/proc/self/status").exists() {
+        assert!(spans[0].peak_rss_kb.is_some_and(|kb| kb > 0));
+        assert!(format_timings(&spans).contains("peak rss"));
+    }
+    if cfg!(feature = "alloc-stats") {
+        assert!(spans[0].peak_heap.is_some_and(|peak| peak >= 1 << 20), "{:?}", spans[0]);
+        assert!(spans[0].allocations.is_some_and(|count| count >= 1));
+        assert!(chrome_trace(&spans).contains("\"peak_heap_bytes\":"));
+    } else {
+        assert_eq!(spans[0].peak_heap, None);
+    }
+}
+
+#[test]
+fn test_nothing_is_recorded_without_profiling() {
+    {
+        let _span = trace::span("parse");
+    }
+    assert!(trace::finish().is_empty());
+}
+
+#[test]
+fn test_open_spans_end_at_finish() {
+    trace::start_profiling();
+    let _run = trace::span("axis-compiler");
+    let spans = trace::finish();
+    assert_eq!(spans.len(), 1);
+    // Dropping the guard after finish() is harmless
+    drop(_run);
+}
+
+#[test]
+fn test_chrome_trace_events() {
+    trace::start_profiling();
+    {
+        let _span = trace::span_with("read", "dir/\"a\".ax");
+    }
+    let json = chrome_trace(&trace::finish());
+    assert!(json.starts_with("{\"traceEvents\":[\n{\"name\":\"read\",\"cat\":\"axis-compiler\",\"ph\":\"X\",\"ts\":"), "{}", json);
+    assert!(json.contains(",\"pid\":1,\"tid\":1,\"args\":{\"detail\":\"dir/\\\"a\\\".ax\""), "{}", json);
+    assert!(json.ends_with("\"displayTimeUnit\":\"ms\"}\n"));
+}
+
+#[test]
+fn test_a_panic_names_the_innermost_span() {
+    let error = internal_error::guard(|| {
+        let _outer = trace::span("validate");
+        let _inner = trace::span_with("lower", "main");
+        panic!("no arm for {}", "x")
+    })
+    .unwrap_err();
+    assert_eq!((error.phase, error.message.as_str()), (Some("lower"), "no arm for x"));
+    assert_eq!(internal_error::current_phase(), None);
+}
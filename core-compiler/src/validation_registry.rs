@@ -2,6 +2,7 @@
 // Validation uses the CLI-loaded Registry as the sole authority.
 // No filesystem access is permitted here.
 
+use crate::operators;
 use crate::registry_loader::Registry;
 
 // Check if a function is known (builtin or in registry)
@@ -15,23 +16,24 @@ pub fn is_known_function(registry: &Registry, name: &str) -> bool {
     is_builtin_function(name)
 }
 
+// IO, filesystem and process primitives, by the prefixes of their
+// sections in registries/axis.axreg: recognized even when no loaded
+// registry (e.g. the built-in prelude) declares them
+pub fn is_io_primitive(name: &str) -> bool {
+    ["axis_io_", "axis_fs_", "axis_proc_", "axis_env_", "axis_args_"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
 // Builtin function check (same as in registry_loader.rs but duplicated to avoid circular deps)
-fn is_builtin_function(name: &str) -> bool {
+pub fn is_builtin_function(name: &str) -> bool {
     match name {
-        // Core arithmetic
-        "+" | "-" | "*" | "/" | "%" => true,
-        "__add__" | "__sub__" | "__mul__" | "__div__" | "__mod__" => true,
-        
-        // Comparisons  
-        "==" | "!=" | "<" | "<=" | ">" | ">=" => true,
-        "__eq__" | "__lt__" | "__lte__" | "__gt__" | "__gte__" => true,
-        
-        // Logical
-        "&&" | "||" | "!" => true,
-        "__and__" | "__or__" | "__not__" => true,
+        // Infix operators (operators.rs) and their Core functions
+        _ if operators::is_operator(name) => true,
+        "!" | "__not__" => true,
         
         // Tuple operations
-        "tuple_field" => true,
+        "tuple_field" | "__tuple__" => true,
         
         // String operations
         "str_len" | "str_char" | "str_slice" | "str_concat" | "__concat__" => true,
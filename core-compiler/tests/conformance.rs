@@ -0,0 +1,113 @@
+// Language conformance suite (tests/conformance/ at the repository root)
+//
+// One directory per language feature, each case a program <case>.ax with
+//   <case>.coretext  the Core IR after lowering (--dump-phase core-term)
+//   <case>.expected  the exit code and stdout of running it, in the format
+//                    of tests/e2e (`exit: N`, `--- stdout`, the output)
+// Cases are compiled with `axis-compiler --no-prelude` against
+// registries/axis.axreg, and run with `axis-compiler run`.
+//
+// AXIS_BLESS=1 rewrites the expectation files from the observed results.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The features the suite covers; each directory holds at least one case
+const FEATURES: [&str; 6] = ["let", "if", "match", "ctors", "strings", "tuples"];
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+}
+
+fn compiler() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_axis-compiler"))
+}
+
+// The .ax cases of a feature directory, sorted
+fn cases(feature: &str) -> Vec<PathBuf> {
+    let dir = repo_root().join("tests/conformance").join(feature);
+    let mut cases: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("{}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ax"))
+        .collect();
+    cases.sort();
+    cases
+}
+
+// Compare `actual` with the expectation file, or rewrite it under AXIS_BLESS
+fn check(expected_path: &Path, actual: &str, failures: &mut Vec<String>) {
+    if std::env::var("AXIS_BLESS").is_ok_and(|v| v == "1") {
+        fs::write(expected_path, actual).unwrap();
+        return;
+    }
+    match fs::read_to_string(expected_path) {
+        Ok(expected) if expected == actual => {}
+        Ok(expected) => failures.push(format!(
+            "{}: mismatch\n--- expected\n{}--- actual\n{}",
+            expected_path.display(),
+            expected,
+            actual
+        )),
+        Err(e) => failures.push(format!("{}: {} (AXIS_BLESS=1 creates it)", expected_path.display(), e)),
+    }
+}
+
+fn run_case(source: &Path, work_dir: &Path, failures: &mut Vec<String>) {
+    let feature = source.parent().unwrap().file_name().unwrap().to_string_lossy();
+    let stem = format!("{}-{}", feature, source.file_stem().unwrap().to_string_lossy());
+    let core_dump = work_dir.join(format!("{}.coretext", stem));
+    let bundle = work_dir.join(format!("{}.coreir", stem));
+
+    let compiled = compiler()
+        .arg("--sources")
+        .arg(source)
+        .arg("--registries")
+        .arg(repo_root().join("registries/axis.axreg"))
+        .arg("--no-prelude")
+        .arg("--dump-phase")
+        .arg(format!("core-term={}", core_dump.display()))
+        .arg("--out")
+        .arg(&bundle)
+        .arg("--force")
+        .output()
+        .unwrap();
+    if !compiled.status.success() {
+        failures.push(format!(
+            "{}: axis-compiler failed\n{}",
+            source.display(),
+            String::from_utf8_lossy(&compiled.stderr)
+        ));
+        return;
+    }
+    check(&source.with_extension("coretext"), &fs::read_to_string(&core_dump).unwrap(), failures);
+
+    let ran = compiler().arg("run").arg(&bundle).output().unwrap();
+    let Some(code) = ran.status.code() else {
+        failures.push(format!("{}: axis-compiler run was killed", source.display()));
+        return;
+    };
+    let actual = format!("exit: {}\n--- stdout\n{}", code, String::from_utf8_lossy(&ran.stdout));
+    check(&source.with_extension("expected"), &actual, failures);
+}
+
+#[test]
+fn test_every_feature_has_cases() {
+    for feature in FEATURES {
+        assert!(!cases(feature).is_empty(), "tests/conformance/{} has no cases", feature);
+    }
+}
+
+#[test]
+fn test_conformance() {
+    let work_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("conformance");
+    fs::create_dir_all(&work_dir).unwrap();
+    let mut failures = Vec::new();
+    for feature in FEATURES {
+        for source in cases(feature) {
+            run_case(&source, &work_dir, &mut failures);
+        }
+    }
+    assert!(failures.is_empty(), "{} conformance failure(s):\n\n{}", failures.len(), failures.join("\n"));
+}
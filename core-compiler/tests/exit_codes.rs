@@ -0,0 +1,79 @@
+// Exit codes of axis-compiler (axis_ir::exit_codes): each kind of failure
+// ends the process with its own code
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn compiler() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_axis-compiler"))
+}
+
+// A scratch file under the target directory
+fn scratch(name: &str, contents: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("exit_codes");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn exit_code(command: &mut Command) -> i32 {
+    let output = command.output().unwrap();
+    output.status.code().expect("killed by a signal")
+}
+
+// Compile `source` (written to `name`) and return the exit code
+fn compile(name: &str, source: &str) -> i32 {
+    let path = scratch(name, source);
+    let out = path.with_extension("coreir");
+    exit_code(compiler().arg("--sources").arg(&path).arg("--no-prelude").arg("--out").arg(&out).arg("--force"))
+}
+
+#[test]
+fn test_success_is_zero() {
+    assert_eq!(compile("ok.ax", "fn axis_entry(args) { 0 }\n"), 0);
+    assert_eq!(exit_code(compiler().arg("explain")), 0);
+}
+
+#[test]
+fn test_usage_errors_exit_2() {
+    assert_eq!(exit_code(compiler().arg("--no-such-flag")), 2);
+    assert_eq!(exit_code(compiler().arg("explain").arg("AX9999")), 2);
+    assert_eq!(exit_code(compiler().arg("--sources").arg("a.ax").arg("--allow").arg("network")), 2);
+    let bundle = scratch("link-input.coreir", "");
+    assert_eq!(exit_code(compiler().arg("link").arg(&bundle)), 2);
+}
+
+#[test]
+fn test_parse_errors_exit_3() {
+    assert_eq!(compile("parse.ax", "fn axis_entry(args) { 1 + }\n"), 3);
+    let registry = scratch("broken.axreg", "axreg 2\nnot a registry line\n");
+    let source = scratch("uses-registry.ax", "fn axis_entry(args) { 0 }\n");
+    assert_eq!(exit_code(compiler().arg("--sources").arg(&source).arg("--registries").arg(&registry)), 3);
+}
+
+#[test]
+fn test_validation_errors_exit_4() {
+    assert_eq!(compile("unbound.ax", "fn axis_entry(args) { missing }\n"), 4);
+    assert_eq!(compile("duplicate.ax", "fn f() { 1 }\nfn f() { 2 }\nfn axis_entry(args) { f() }\n"), 4);
+}
+
+#[test]
+fn test_io_errors_exit_5() {
+    assert_eq!(exit_code(compiler().arg("--sources").arg("no/such/file.ax")), 5);
+    assert_eq!(exit_code(compiler().arg("--view-core-ir").arg("no/such/file.coreir")), 5);
+    // An existing output is not replaced without --force
+    let source = scratch("exists.ax", "fn axis_entry(args) { 0 }\n");
+    let out = scratch("exists.coreir", "");
+    assert_eq!(exit_code(compiler().arg("--sources").arg(&source).arg("--out").arg(&out)), 5);
+}
+
+#[test]
+fn test_help_lists_the_exit_codes() {
+    let output = compiler().arg("--help").output().unwrap();
+    assert!(output.status.success());
+    let help = String::from_utf8(output.stdout).unwrap();
+    assert!(help.contains("Exit codes:"), "{}", help);
+    assert!(help.contains("4  validation error"), "{}", help);
+}
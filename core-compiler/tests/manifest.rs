@@ -0,0 +1,75 @@
+// Command-line arguments against an axis.toml manifest: --sources, --out
+// and --registries replace the manifest's values, and flags are on if set
+// in either place
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// A project directory under the target directory holding `files`
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("manifest").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn build(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_axis-compiler"))
+        .current_dir(dir)
+        .arg("build")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+const MAIN: &str = "fn axis_entry(args) {\n    list_sum(list_range(0, 5))\n}\n";
+
+#[test]
+fn test_manifest_values_are_used_without_arguments() {
+    let dir = project("defaults", &[
+        ("axis.toml", "[project]\nsources = [\"src/main.ax\"]\nout = \"build/main.coreir\"\n"),
+        ("src/main.ax", MAIN),
+    ]);
+    let output = build(&dir, &[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(dir.join("build/main.coreir").is_file());
+}
+
+#[test]
+fn test_arguments_replace_manifest_values() {
+    let dir = project("override", &[
+        ("axis.toml", "[project]\nsources = [\"broken.ax\"]\nregistries = [\"missing.axreg\"]\nout = \"manifest.coreir\"\n"),
+        ("broken.ax", "fn axis_entry(args) { 1 + }\n"),
+        ("main.ax", MAIN),
+    ]);
+    let registry = Path::new(env!("CARGO_MANIFEST_DIR")).join("../registries/prelude.axreg");
+    let args = ["--sources", "main.ax", "--out", "cli.coreir"];
+    // Without --registries the manifest's missing registry is read
+    assert_eq!(build(&dir, &args).status.code(), Some(5));
+
+    // With all three given, the manifest's broken source and missing
+    // registry are never read and its out is not written
+    let output = build(&dir, &[&args[..], &["--registries", registry.to_str().unwrap()]].concat());
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(dir.join("cli.coreir").is_file());
+    assert!(!dir.join("manifest.coreir").exists());
+}
+
+#[test]
+fn test_flags_are_on_if_set_in_either_place() {
+    let dir = project("flags", &[
+        ("axis.toml", "[project]\nsources = [\"main.ax\"]\nout = \"main.coreir\"\n\n[build]\nflags = [\"no-prelude\"]\n"),
+        ("main.ax", MAIN),
+    ]);
+    // The manifest turns the prelude off, so list_sum is unbound
+    assert_eq!(build(&dir, &[]).status.code(), Some(4));
+
+    fs::write(dir.join("axis.toml"), "[project]\nsources = [\"main.ax\"]\nout = \"main.coreir\"\n").unwrap();
+    assert!(build(&dir, &[]).status.success());
+    assert_eq!(build(&dir, &["--no-prelude"]).status.code(), Some(4));
+}
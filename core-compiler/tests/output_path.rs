@@ -0,0 +1,71 @@
+// Where axis-compiler writes its bundle: --out-dir is created when missing,
+// and an existing output is only replaced with --force
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// A directory under the target directory holding main.ax
+fn project(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("output_path").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("main.ax"), "fn axis_entry(args) { 0 }\n").unwrap();
+    dir
+}
+
+fn compile(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_axis-compiler"))
+        .current_dir(dir)
+        .args(["--sources", "main.ax", "--no-prelude"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_out_dir_is_created() {
+    let dir = project("create");
+    let output = compile(&dir, &["--out-dir", "build/nested"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let bundle = dir.join("build/nested/main.coreir");
+    assert!(bundle.is_file());
+    // The last line of stdout is the bundle's absolute path
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(Path::new(stdout.lines().last().unwrap()), bundle);
+}
+
+#[test]
+fn test_an_existing_output_is_refused_without_force() {
+    let dir = project("refuse");
+    for args in [&["--out-dir", "build"][..], &["--out", "build/main.coreir"][..]] {
+        fs::create_dir_all(dir.join("build")).unwrap();
+        fs::write(dir.join("build/main.coreir"), "keep me").unwrap();
+        let output = compile(&dir, args);
+        assert_eq!(output.status.code(), Some(5), "{}", stderr(&output));
+        assert!(stderr(&output).contains("build/main.coreir already exists (use --force to overwrite)"), "{}", stderr(&output));
+        assert_eq!(fs::read_to_string(dir.join("build/main.coreir")).unwrap(), "keep me");
+    }
+}
+
+#[test]
+fn test_force_replaces_an_existing_output() {
+    let dir = project("force");
+    fs::create_dir_all(dir.join("build")).unwrap();
+    fs::write(dir.join("build/main.coreir"), "stale").unwrap();
+    let output = compile(&dir, &["--out-dir", "build", "--force"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_ne!(fs::read(dir.join("build/main.coreir")).unwrap(), b"stale");
+}
+
+#[test]
+fn test_out_and_out_dir_conflict() {
+    let dir = project("conflict");
+    let output = compile(&dir, &["--out", "a.coreir", "--out-dir", "build"]);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+    assert!(!dir.join("a.coreir").exists() && !dir.join("build").exists());
+}
@@ -0,0 +1,65 @@
+// Golden dumps of every --dump-phase phase (tests/golden/ at the repository
+// root)
+//
+// tests/golden/phases.ax is compiled once with every phase dumped; each
+// dump must match tests/golden/phases.<phase>.dump. The program is
+// compiled with `--no-prelude` against registries/axis.axreg, as the
+// conformance suite does.
+//
+// AXIS_BLESS=1 rewrites the expectation files from the observed dumps.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Every phase --dump-phase accepts
+const PHASES: [&str; 5] = ["tokens", "ast", "core-value", "core-term", "validated"];
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join("tests/golden")
+}
+
+#[test]
+fn test_every_phase_matches_its_golden_dump() {
+    let work_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("phase_dumps");
+    fs::create_dir_all(&work_dir).unwrap();
+    let repo_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_axis-compiler"));
+    command
+        .arg("--sources")
+        .arg(golden_dir().join("phases.ax"))
+        .arg("--registries")
+        .arg(repo_root.join("registries/axis.axreg"))
+        .arg("--no-prelude")
+        .arg("--out")
+        .arg(work_dir.join("phases.coreir"))
+        .arg("--force");
+    for phase in PHASES {
+        command.arg("--dump-phase").arg(format!("{}={}", phase, work_dir.join(phase).display()));
+    }
+    let compiled = command.output().unwrap();
+    assert!(compiled.status.success(), "{}", String::from_utf8_lossy(&compiled.stderr));
+
+    let bless = std::env::var("AXIS_BLESS").is_ok_and(|v| v == "1");
+    let mut failures = Vec::new();
+    for phase in PHASES {
+        let actual = fs::read_to_string(work_dir.join(phase)).unwrap();
+        let expected_path = golden_dir().join(format!("phases.{}.dump", phase));
+        if bless {
+            fs::write(&expected_path, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&expected_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{}: mismatch\n--- expected\n{}--- actual\n{}",
+                expected_path.display(),
+                expected,
+                actual
+            )),
+            Err(e) => failures.push(format!("{}: {} (AXIS_BLESS=1 creates it)", expected_path.display(), e)),
+        }
+    }
+    assert!(failures.is_empty(), "{} golden dump(s) differ:\n\n{}", failures.len(), failures.join("\n"));
+}
@@ -0,0 +1,11 @@
+// cargo fuzz run fuzz_load_bundle
+// Decodes arbitrary bytes as a Core bundle under untrusted reader limits.
+// Seed the corpus with real bundles, e.g. cp ../coreir/*.coreir fuzz/corpus/fuzz_load_bundle/
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    axis_rust_bridge::core_ir::fuzz_load_bundle(data);
+});
@@ -0,0 +1,112 @@
+// Stable interface between generated programs and the runtime
+//
+// Generated code names nothing from this crate but what is here. The
+// emitted module and the main wrapper of the Rust backend (backend.rs)
+// start with `use axis_rust_bridge::abi::*;`, and primitives are called as
+// `shim::<name>`. The runtime behind this module (runtime::*) can be
+// refactored freely: a program generated by an earlier bridge keeps
+// building as long as this module does.
+//
+// COMPATIBILITY: this module is the crate's semver surface. Adding an item
+// is a minor change. Removing or renaming one, changing a signature, or
+// changing an existing Value variant needs a new major version. Generated
+// matches on Value always end in a wildcard arm, so a new variant is not a
+// breaking change for them.
+//
+// Every generated module is stamped with the ABI_VERSION it was emitted
+// for (AXIS_ABI_VERSION), and its main checks the stamp against the runtime
+// it is built with (init_runtime_for) before running anything.
+//
+// POLICY: every primitive the emitter maps (get_foreign_symbol_mapping) is
+// re-exported here (checked by abi_tests). A primitive behind a capability
+// feature is only here with that feature, as in the runtime, which is how
+// `build --allow` leaves it out.
+
+/// Version of this interface; bumped with every breaking change to it
+pub const ABI_VERSION: u32 = 1;
+
+// Values
+pub use crate::runtime::value::Value;
+
+// Interning: string and constructor tag tables
+pub use crate::runtime::value::{
+    get_str, get_tag_name, init_runtime, init_runtime_for, intern_str, intern_tag, register_tags, str_lit,
+};
+
+// Helpers the emitted code calls directly
+pub use crate::runtime::tuple::{match_field, tuple_field};
+pub use crate::runtime::value::truthy;
+
+// Runtime errors, assertion failures and the --debug call stack
+pub use crate::runtime::error::{
+    assertion_failures, axis_runtime_error, install_runtime_error_hook, CallFrame, RuntimeSpan,
+    AXIS_ASSERTION_FAILED_EXIT_CODE, AXIS_ENTRY_ERR_EXIT_CODE, AXIS_RUNTIME_ERROR_EXIT_CODE,
+};
+
+// Functions given to seq_map, seq_filter, seq_unfold and list_sort_by
+pub use crate::runtime::seq::SeqFn;
+
+// The main wrapper writes out buffered output (io_println) before it exits
+pub use crate::runtime::io::flush_stdout;
+
+// The main wrapper's arguments to axis_entry and exit code (`build
+// --entry-args-format`)
+pub use crate::runtime::entry::{entry_args, entry_exit_code, EntryArgsFormat};
+
+// `build --int-overflow`: the main wrapper sets the arithmetic mode
+pub use crate::runtime::overflow::{set_int_overflow, IntOverflow};
+
+// Primitives the emitter maps to their bare names (a program's own
+// definition of the name shadows them)
+pub use crate::runtime::list::{list_concat, list_contains_str, list_index_of_str};
+pub use crate::runtime::shim::{list_append, list_get};
+pub use crate::runtime::value::__neq__;
+
+// `build --runtime async`: the main wrapper runs the entry under the reactor
+#[cfg(feature = "async")]
+pub use crate::runtime::reactor::drive_reactor;
+
+/// The primitives, as the emitter calls them (`shim::<name>`)
+pub mod shim {
+    pub use crate::runtime::shim::{
+        assert_eq, assert_true, axis_io_make_error, axis_json_parse, char_to_str, fail, str_char, str_char_at,
+        str_char_code, str_concat, str_len,
+    };
+    pub use crate::runtime::value::{
+        __add__, __and__, __concat__, __div__, __eq__, __gt__, __gte__, __lt__, __lte__, __mod__, __mul__, __neq__,
+        __not__, __or__, __sub__, bytes_get, bytes_len, bytes_slice, get_str, get_tag_name, init_runtime, int_to_str,
+        intern_str, intern_tag, sb_build, sb_new, sb_push, sb_push_int, str_slice, str_to_int, truthy,
+    };
+    pub use crate::runtime::tuple::{ctor_field, tuple, tuple_field};
+    pub use crate::runtime::list::{list_concat, list_cons, list_contains_str, list_index_of_str, list_nil, list_reverse};
+    pub use crate::runtime::json::{json_encode, json_parse};
+    pub use crate::runtime::concurrency::{channel_new, channel_recv, channel_send, join, spawn};
+    pub use crate::runtime::io::debug_trace;
+    pub use crate::runtime::inspect::{value_diff, value_pretty};
+    pub use crate::runtime::memo::{memo_get, memo_put};
+    pub use crate::runtime::overflow::{checked_add, checked_div, checked_mul, checked_sub};
+    pub use crate::runtime::order::{list_sort_by, value_cmp};
+    pub use crate::runtime::strings::{
+        str_contains, str_ends_with, str_join, str_replace, str_split, str_starts_with, str_to_lower, str_to_upper,
+        str_trim,
+    };
+    pub use crate::runtime::chars::{
+        char_from_str, char_is_alnum, char_is_alpha, char_is_digit, char_is_ident_char, char_is_ident_start,
+        char_is_whitespace, char_to_digit, char_to_lower, char_to_upper,
+    };
+    pub use crate::runtime::seq::{seq_filter, seq_from_list, seq_map, seq_take, seq_to_list, seq_unfold};
+
+    #[cfg(feature = "io")]
+    pub use crate::runtime::shim::{
+        io_eprint, io_flush, io_print, io_print_buffered, io_println, io_read, io_read_bytes,
+    };
+
+    #[cfg(feature = "fs")]
+    pub use crate::runtime::shim::{
+        axis_emit_core_bundle_to_file, axis_load_core_bundle_from_file, fs_copy, fs_create_dir_all, fs_list_dir,
+        fs_read_bytes, fs_read_text, fs_remove_file, fs_write_bytes, fs_write_text, path_join, path_parent, path_stem,
+    };
+
+    #[cfg(feature = "proc")]
+    pub use crate::runtime::shim::{args_flag, args_has, args_positional, env_get, env_set, proc_exit, proc_run};
+}
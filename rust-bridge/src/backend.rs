@@ -0,0 +1,545 @@
+// Code generation backends
+//
+// A backend turns a loaded program (in arena form, core_arena.rs) into the
+// sources of a buildable project plus the command that builds it. The
+// bridge CLI writes the files into a scratch directory, runs the build
+// command there and copies the built program to --out, so a new target (C,
+// WASM, an interpreter launcher) is a CodegenBackend implementation listed
+// in `backend_by_name`; the CLI itself does not change.
+
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::BundleLoadOptions;
+use crate::emit_rust::{self, EmitOptions, StrictCodegenError};
+use crate::runtime::capability::{self, Capability, DeniedCall};
+use crate::runtime::emit_c;
+use crate::runtime::entry::EntryArgsFormat;
+use crate::runtime::overflow::IntOverflow;
+use crate::runtime::reactor::RuntimeProfile;
+use crate::runtime::standalone;
+
+/// Output of a backend: a project ready to build
+pub struct EmitArtifacts {
+    /// Files to write, relative to the build directory
+    pub files: Vec<(PathBuf, String)>,
+    /// Program and arguments run in the build directory
+    pub build_command: Vec<String>,
+    /// Built program, relative to the build directory
+    pub output: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum EmitError {
+    /// References nothing resolves; with --strict-codegen also those only
+    /// the name-drift workarounds resolve
+    UnresolvedReferences(Vec<StrictCodegenError>),
+    /// The program uses something this backend cannot express
+    Unsupported(String),
+    /// --allow: primitives outside the allowed capabilities
+    Denied(Vec<DeniedCall>),
+    /// --stream: the bundle could not be decoded or the source not written
+    Bundle(String),
+}
+
+impl fmt::Display for EmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmitError::UnresolvedReferences(errors) => {
+                for error in errors {
+                    writeln!(f, "{}", error)?;
+                }
+                write!(f, "{} unresolved reference(s)", errors.len())
+            }
+            EmitError::Unsupported(msg) => write!(f, "unsupported: {}", msg),
+            EmitError::Denied(calls) => {
+                for call in calls {
+                    writeln!(f, "{}", call)?;
+                }
+                write!(f, "capability sandbox: {} primitive(s) outside --allow", calls.len())
+            }
+            EmitError::Bundle(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+pub trait CodegenBackend {
+    /// Name accepted by `--backend`
+    fn name(&self) -> &'static str;
+    fn emit(&self, program: &ArenaProgram, opts: &EmitOptions) -> Result<EmitArtifacts, EmitError>;
+
+    /// `build --stream`: the generated source, relative to the build
+    /// directory, that emit_streaming writes; None if the backend needs
+    /// the loaded program
+    fn streamed_source(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Emit straight from an encoded bundle, writing streamed_source to
+    /// `out` without loading the program; returns the other files
+    fn emit_streaming(
+        &self,
+        _bundle: &[u8],
+        _load_options: &BundleLoadOptions,
+        _out: &mut dyn Write,
+        _opts: &EmitOptions,
+    ) -> Result<EmitArtifacts, EmitError> {
+        Err(EmitError::Unsupported(format!("the {} backend cannot stream", self.name())))
+    }
+
+    /// `build --debug`: add the program's Axis sources (name, text) to the
+    /// project. By default they are only copied next to it, under
+    /// axis_sources/; backends that can also embed them in the binary.
+    fn embed_sources(&self, artifacts: &mut EmitArtifacts, sources: &[(String, String)]) {
+        add_source_copies(artifacts, sources);
+    }
+}
+
+// The Axis sources next to the generated ones, for reading along in a
+// debugger (`directory axis_sources` in gdb)
+fn add_source_copies(artifacts: &mut EmitArtifacts, sources: &[(String, String)]) {
+    for (name, text) in sources {
+        let file_name = Path::new(name).file_name().map(PathBuf::from).unwrap_or_else(|| PathBuf::from(name));
+        artifacts.files.push((Path::new("axis_sources").join(file_name), text.clone()));
+    }
+}
+
+/// The Axis sources as embedded in a `--debug` binary: for each source
+/// `axis-source <name> <bytes>` on a line of its own, then its text.
+/// Extract with `objcopy -O binary --only-section=.axis_sources <binary> <out>`.
+pub fn embedded_sources(sources: &[(String, String)]) -> String {
+    let mut blob = String::new();
+    for (name, text) in sources {
+        blob.push_str(&format!("axis-source {} {}\n{}", name, text.len(), text));
+    }
+    blob
+}
+
+/// Names accepted by `backend_by_name`, default first
+pub const BACKENDS: &[&str] = &["rust", "c"];
+
+/// `bridge_dir` is the axis-rust-bridge crate, for backends that link a
+/// runtime from it
+pub fn backend_by_name(name: &str, bridge_dir: &Path) -> Result<Box<dyn CodegenBackend>, String> {
+    match name {
+        "rust" => Ok(Box::new(RustBackend { runtime_crate: bridge_dir.to_path_buf() })),
+        "c" => Ok(Box::new(CBackend { compiler: std::env::var("CC").unwrap_or_else(|_| "cc".to_string()) })),
+        other => Err(format!("unknown backend '{}' (expected one of: {})", other, BACKENDS.join(", "))),
+    }
+}
+
+// POLICY: a sandboxed program is refused before anything is emitted
+fn check_sandbox(program: &ArenaProgram, opts: &EmitOptions) -> Result<(), EmitError> {
+    let Some(allowed) = &opts.allow else {
+        return Ok(());
+    };
+    let denied = capability::denied_calls(program.root_term(), allowed);
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(EmitError::Denied(denied))
+    }
+}
+
+// Capabilities --allow leaves out, whose primitives the runtime omits
+fn omitted_capabilities(opts: &EmitOptions) -> Vec<Capability> {
+    match &opts.allow {
+        Some(allowed) => Capability::ALL.iter().copied().filter(|c| !allowed.contains(c)).collect(),
+        None => Vec::new(),
+    }
+}
+
+// ============================================================================
+// Bridge version (`--require-bridge-version`)
+// ============================================================================
+
+/// Version of this bridge, and of the runtime crate the Rust backend links
+pub const BRIDGE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Whether this bridge satisfies `requirement`, a cargo version requirement:
+/// comparators such as `1.2.3` or `^1.2` (compatible), `~1.2`, `=1.2.3`,
+/// `>=0.1, <0.3`, joined by commas
+pub fn check_bridge_version(requirement: &str) -> Result<(), String> {
+    if version_matches(BRIDGE_VERSION, requirement)? {
+        Ok(())
+    } else {
+        Err(format!("axis-rust-bridge {} does not satisfy the required version {}", BRIDGE_VERSION, requirement))
+    }
+}
+
+pub(crate) fn version_matches(version: &str, requirement: &str) -> Result<bool, String> {
+    let version = match parse_version(version)? {
+        (major, Some(minor), Some(patch)) => (major, minor, patch),
+        _ => return Err(format!("version '{}' needs major.minor.patch", version)),
+    };
+    let mut matches = true;
+    for comparator in requirement.split(',').map(str::trim) {
+        matches &= comparator_matches(version, comparator)?;
+    }
+    Ok(matches)
+}
+
+// `1`, `1.2` or `1.2.3`; missing parts are None
+fn parse_version(text: &str) -> Result<(u64, Option<u64>, Option<u64>), String> {
+    let mut parts = text.trim().split('.');
+    let mut number = |required: bool| -> Result<Option<u64>, String> {
+        match parts.next() {
+            Some(part) => part.parse().map(Some).map_err(|_| format!("invalid version '{}'", text)),
+            None if required => Err(format!("invalid version '{}'", text)),
+            None => Ok(None),
+        }
+    };
+    let major = number(true)?.unwrap_or(0);
+    let version = (major, number(false)?, number(false)?);
+    if parts.next().is_some() {
+        return Err(format!("invalid version '{}'", text));
+    }
+    Ok(version)
+}
+
+fn comparator_matches(version: (u64, u64, u64), comparator: &str) -> Result<bool, String> {
+    // Two-character operators first, so `>=` is not read as `>`
+    let (op, rest) = [">=", "<=", "^", "~", "=", ">", "<"]
+        .iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("^", comparator));
+    let (major, minor, patch) = parse_version(rest)?;
+    let lower = (major, minor.unwrap_or(0), patch.unwrap_or(0));
+    // The first version past every one the parts given match (`1.2` is 1.2.*)
+    let next = match (minor, patch) {
+        (Some(minor), Some(patch)) => (major, minor, patch + 1),
+        (Some(minor), None) => (major, minor + 1, 0),
+        _ => (major + 1, 0, 0),
+    };
+    let upper = match op {
+        // The first version that is not compatible: the leftmost non-zero part changes
+        "^" => match (major, minor, patch) {
+            (0, Some(0), Some(patch)) => (0, 0, patch + 1),
+            (0, Some(minor), _) => (0, minor + 1, 0),
+            _ => (major + 1, 0, 0),
+        },
+        "~" => match minor {
+            Some(minor) => (major, minor + 1, 0),
+            None => (major + 1, 0, 0),
+        },
+        _ => next,
+    };
+    Ok(match op {
+        ">=" => version >= lower,
+        ">" => version >= next,
+        "<=" => version < next,
+        "<" => version < lower,
+        _ => version >= lower && version < upper,
+    })
+}
+
+// ============================================================================
+// Rust
+// ============================================================================
+
+/// Cargo package depending on the axis-rust-bridge runtime; built with
+/// `cargo build --release` (`cargo build`, with full debug info, under
+/// --debug). With --standalone, one .rs file with the runtime inlined,
+/// built with `rustc` (runtime::standalone).
+pub struct RustBackend {
+    pub runtime_crate: PathBuf,
+}
+
+// Thin executable wrapper that initializes the runtime and calls the Axis
+// entry function `axis_entry` produced by the emitter. Like the generated
+// module it only uses the stable interface (abi.rs).
+const RUST_MAIN: &str = r#"mod axis_generated;
+use axis_rust_bridge::abi::*;
+
+fn main() {
+    // Fails fast if the generated code is for another runtime interface
+    init_runtime_for(axis_generated::AXIS_ABI_VERSION);
+    // Before any constructor is built: the generated code's tag literals
+    if let Err(message) = register_tags(axis_generated::AXIS_CONSTRUCTOR_TAGS) {
+        axis_runtime_error(&message, None);
+    }
+    // Report escaping panics as Axis runtime errors (exit code 70)
+    install_runtime_error_hook();
+    // Overflow and division by zero in the arithmetic primitives
+    set_int_overflow(INT_OVERFLOW);
+
+    let __phase_start = std::time::Instant::now();
+
+    // CLI arguments (skip program name), in the shape the entry takes
+    let cli_args: Vec<String> = std::env::args().skip(1).collect();
+    let axis_args = entry_args(EntryArgsFormat::ENTRY_ARGS_FORMAT, &cli_args);
+
+    // Call Axis entry with arguments
+    let result = ENTRY_CALL;
+
+    // Output io_println and io_print_buffered still hold, before anything
+    // the exit code reports on stderr
+    flush_stdout();
+
+    // Int, Unit, Ok or Err; failed assertions take precedence
+    let exit_code = entry_exit_code(&result);
+
+    let __phase_elapsed = __phase_start.elapsed().as_millis();
+
+    std::process::exit(exit_code);
+}
+"#;
+
+// ENTRY_CALL in RUST_MAIN, by runtime profile
+const RUST_ENTRY: &str = "axis_generated::axis_entry(axis_args)";
+const RUST_ASYNC_ENTRY: &str = "drive_reactor(move || axis_generated::axis_entry(axis_args))";
+
+const RUST_GENERATED: &str = "src/axis_generated.rs";
+
+// --standalone: the whole program
+const RUST_STANDALONE: &str = "axis_emitted.rs";
+
+impl RustBackend {
+    // The project around the generated module, which emit and
+    // emit_streaming add
+    fn project(&self, opts: &EmitOptions) -> EmitArtifacts {
+        // Sandboxed: the runtime is built with only the allowed capability
+        // features. The runtime profile adds its own.
+        let mut names: Vec<String> = Vec::new();
+        if let Some(allowed) = &opts.allow {
+            names.extend(allowed.iter().map(|c| format!("\"{}\"", c.name())));
+        }
+        names.extend(opts.runtime.feature().map(|feature| format!("\"{}\"", feature)));
+        let features = match (&opts.allow, names.is_empty()) {
+            (Some(_), _) => format!(", default-features = false, features = [{}]", names.join(", ")),
+            (None, false) => format!(", features = [{}]", names.join(", ")),
+            (None, true) => String::new(),
+        };
+        // --require-bridge-version: cargo holds the runtime crate to it too,
+        // should the project be built later (--emit-source)
+        let version = match &opts.require_bridge_version {
+            Some(requirement) => format!(", version = \"{}\"", requirement),
+            None => String::new(),
+        };
+        let cargo_toml = format!(r#"[package]
+name = "axis_emitted"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+axis-rust-bridge = {{ path = "{}"{}{} }}
+"#, self.runtime_crate.display(), version, features);
+
+        // --debug: the dev profile, so the generated code is stepped through
+        // as written; the runtime keeps its own optimizations
+        let (build_command, output) = if opts.debug_info {
+            (vec!["cargo", "build"], "target/debug/axis_emitted")
+        } else {
+            (vec!["cargo", "build", "--release"], "target/release/axis_emitted")
+        };
+
+        // Generated code goes in its own module; the wrapper provides the
+        // single Rust `main`, keeping the Axis entry separate
+        EmitArtifacts {
+            files: vec![
+                (PathBuf::from("Cargo.toml"), cargo_toml),
+                (PathBuf::from("src/main.rs"), main_wrapper(opts)),
+            ],
+            build_command: build_command.iter().map(|arg| arg.to_string()).collect(),
+            output: PathBuf::from(output),
+        }
+    }
+
+    // --standalone: the wrapper, the generated module and the runtime in
+    // one file, with the runtime features the project would have
+    fn standalone(&self, generated: &str, opts: &EmitOptions) -> Result<EmitArtifacts, EmitError> {
+        let mut features: Vec<&str> = match &opts.allow {
+            Some(allowed) => allowed.iter().map(|c| c.name()).collect(),
+            None => Capability::ALL.iter().map(|c| c.name()).collect(),
+        };
+        features.extend(opts.runtime.feature());
+        let program = standalone::standalone_program(&main_wrapper(opts), generated, &features)
+            .map_err(EmitError::Unsupported)?;
+
+        let optimize = if opts.debug_info { "-g" } else { "-O" };
+        let build_command = ["rustc", "--edition", "2021", optimize, "-o", "axis_emitted", RUST_STANDALONE];
+        Ok(EmitArtifacts {
+            files: vec![(PathBuf::from(RUST_STANDALONE), program)],
+            build_command: build_command.iter().map(|arg| arg.to_string()).collect(),
+            output: PathBuf::from("axis_emitted"),
+        })
+    }
+}
+
+// RUST_MAIN for the options
+fn main_wrapper(opts: &EmitOptions) -> String {
+    // The async profile's main runs the entry under the reactor
+    let entry = match opts.runtime {
+        RuntimeProfile::Blocking => RUST_ENTRY,
+        RuntimeProfile::Async => RUST_ASYNC_ENTRY,
+    };
+    RUST_MAIN
+        .replace("ENTRY_CALL", entry)
+        .replace("INT_OVERFLOW", &format!("IntOverflow::{:?}", opts.int_overflow))
+        .replace("ENTRY_ARGS_FORMAT", &format!("{:?}", opts.entry_args))
+}
+
+impl CodegenBackend for RustBackend {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn emit(&self, program: &ArenaProgram, opts: &EmitOptions) -> Result<EmitArtifacts, EmitError> {
+        // POLICY: strict codegen refuses to emit anything that relies on the
+        // name-drift workarounds; the lowering has to be fixed instead.
+        // References nothing resolves are refused either way.
+        emit_rust::check_references(program.root_term(), opts.strict_codegen).map_err(EmitError::UnresolvedReferences)?;
+        check_sandbox(program, opts)?;
+        let generated = emit_rust::emit_rust_from_core_with_options(program.root_term(), "<core>", "", opts);
+        if opts.standalone {
+            return self.standalone(&generated, opts);
+        }
+
+        let mut project = self.project(opts);
+        project.files.insert(1, (PathBuf::from(RUST_GENERATED), generated));
+        Ok(project)
+    }
+
+    fn streamed_source(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(RUST_GENERATED))
+    }
+
+    fn emit_streaming(
+        &self,
+        bundle: &[u8],
+        load_options: &BundleLoadOptions,
+        out: &mut dyn Write,
+        opts: &EmitOptions,
+    ) -> Result<EmitArtifacts, EmitError> {
+        // The standalone file wraps the whole generated module
+        if opts.standalone {
+            return Err(EmitError::Unsupported("--standalone cannot be combined with --stream".to_string()));
+        }
+        // Same checks as emit, made by emit_rust_streaming before it writes
+        emit_rust::emit_rust_streaming(bundle, load_options, out, opts)?;
+        Ok(self.project(opts))
+    }
+
+    // Embedded as a static in an .axis_sources section (__DATA,__axis_sources
+    // on Apple targets), kept by #[used] although nothing reads it
+    fn embed_sources(&self, artifacts: &mut EmitArtifacts, sources: &[(String, String)]) {
+        let blob = embedded_sources(sources);
+        let module = format!(
+            r#"// Axis sources of this program (axis-rust-bridge build --debug)
+#[used]
+#[cfg_attr(target_vendor = "apple", link_section = "__DATA,__axis_sources")]
+#[cfg_attr(not(target_vendor = "apple"), link_section = ".axis_sources")]
+static AXIS_SOURCES: [u8; {}] = *include_bytes!("../axis_sources.txt");
+"#,
+            blob.len()
+        );
+        for (path, contents) in artifacts.files.iter_mut() {
+            if path == Path::new("src/main.rs") {
+                contents.insert_str(0, "mod axis_sources;\n");
+            }
+        }
+        // --standalone: in the one file, which is next to the blob
+        match artifacts.files.iter_mut().find(|(path, _)| path == Path::new(RUST_STANDALONE)) {
+            Some((_, contents)) => contents.push_str(&module.replace("../axis_sources.txt", "axis_sources.txt")),
+            None => artifacts.files.push((PathBuf::from("src/axis_sources.rs"), module)),
+        }
+        artifacts.files.push((PathBuf::from("axis_sources.txt"), blob));
+        add_source_copies(artifacts, sources);
+    }
+}
+
+// ============================================================================
+// C
+// ============================================================================
+
+/// C99 sources plus the reference-counted runtime (runtime/c), built with
+/// `$CC` (default `cc`). For targets without rustc; see emit_c for what
+/// the emitter does not support.
+pub struct CBackend {
+    pub compiler: String,
+}
+
+impl CodegenBackend for CBackend {
+    fn name(&self) -> &'static str {
+        "c"
+    }
+
+    fn emit(&self, program: &ArenaProgram, opts: &EmitOptions) -> Result<EmitArtifacts, EmitError> {
+        // The C emitter has no name-drift workarounds, but --strict-codegen
+        // still reports the same references as for Rust
+        if opts.strict_codegen {
+            emit_rust::check_strict_codegen(program.root_term()).map_err(EmitError::UnresolvedReferences)?;
+        }
+        check_sandbox(program, opts)?;
+        // The C sources are already self-contained
+        if opts.standalone {
+            return Err(EmitError::Unsupported("--standalone needs the rust backend".to_string()));
+        }
+        // The C runtime only wraps
+        if opts.int_overflow != IntOverflow::Wrap {
+            return Err(EmitError::Unsupported(format!(
+                "--int-overflow {} needs the rust backend",
+                opts.int_overflow.name()
+            )));
+        }
+        if opts.runtime != RuntimeProfile::Blocking {
+            return Err(EmitError::Unsupported(format!(
+                "the {} runtime profile needs the rust backend",
+                opts.runtime.name()
+            )));
+        }
+        // ax_main always passes a list
+        if opts.entry_args != EntryArgsFormat::List {
+            return Err(EmitError::Unsupported(format!(
+                "--entry-args-format {} needs the rust backend",
+                opts.entry_args.name()
+            )));
+        }
+        let generated = emit_c::emit_c_from_core(program.root_term()).map_err(EmitError::Unsupported)?;
+
+        // --debug: debug info, no optimizations
+        let optimize: &[&str] = if opts.debug_info { &["-g", "-O0"] } else { &["-O2"] };
+        let mut build_command: Vec<String> = [self.compiler.as_str(), "-std=c99"]
+            .iter()
+            .chain(optimize)
+            .map(|arg| arg.to_string())
+            .collect();
+        build_command.extend(omitted_capabilities(opts).iter().map(|c| format!("-D{}", c.c_define())));
+        build_command.extend(["-o", "axis_emitted", "axis_generated.c", "axis_runtime.c"].iter().map(|arg| arg.to_string()));
+
+        Ok(EmitArtifacts {
+            files: vec![
+                (PathBuf::from("axis_generated.c"), generated),
+                (PathBuf::from("axis_runtime.h"), emit_c::RUNTIME_HEADER.to_string()),
+                (PathBuf::from("axis_runtime.c"), emit_c::RUNTIME_SOURCE.to_string()),
+            ],
+            build_command,
+            output: PathBuf::from("axis_emitted"),
+        })
+    }
+    // Embedded as a byte array in an .axis_sources section, like the Rust
+    // backend's
+    fn embed_sources(&self, artifacts: &mut EmitArtifacts, sources: &[(String, String)]) {
+        let blob = embedded_sources(sources);
+        let mut bytes = String::new();
+        for (i, byte) in blob.bytes().enumerate() {
+            bytes.push_str(if i % 16 == 0 { "\n    " } else { " " });
+            bytes.push_str(&format!("0x{:02x},", byte));
+        }
+        let source = format!(
+            "/* Axis sources of this program (axis-rust-bridge build --debug) */\n\
+             #ifdef __APPLE__\n\
+             __attribute__((used, section(\"__DATA,__axis_sources\")))\n\
+             #else\n\
+             __attribute__((used, section(\".axis_sources\")))\n\
+             #endif\n\
+             const unsigned char axis_sources[{}] = {{{}\n}};\n",
+            blob.len().max(1),
+            if bytes.is_empty() { "\n    0".to_string() } else { bytes }
+        );
+        artifacts.files.push((PathBuf::from("axis_sources.c"), source));
+        artifacts.build_command.push("axis_sources.c".to_string());
+        add_source_copies(artifacts, sources);
+    }
+}
@@ -0,0 +1,385 @@
+// Arena form of Core IR: every term of a program in one Vec, children
+// referenced by index
+//
+// The Rc tree (core_ir::CoreTerm) allocates each node on its own; a large
+// self-hosting bundle is millions of small allocations spread over the
+// heap. In a CoreArena a term is a 16-byte ArenaTerm, children are 4-byte
+// TermIds, and the rare parts (string literal text, spans, match arms,
+// tuple and constructor fields) live in side tables.
+//
+// The loader builds a program straight into an arena (core_ir::
+// load_core_bundle_arena). The emitters and the checks run before
+// emission only read terms, so they walk the arena through TermRef, whose
+// `view()` gives one level of a term in the same shape as CoreTerm.
+//
+// A child is always allocated before its parent (a term can only refer to
+// ids that exist), so children have smaller ids than their parents.
+//
+// COMPATIBILITY: code that builds or rewrites terms keeps using CoreTerm.
+// CoreProgram::from_arena builds the Rc tree of a loaded program and
+// ArenaProgram::from_program goes the other way.
+
+use crate::core_ir::{CoreProgram, CoreTerm, Pattern, Span};
+use crate::symbol::Symbol;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TermId(u32);
+
+impl TermId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Fields of a Tuple or Ctor, a range of CoreArena::lists
+#[derive(Clone, Copy, Debug)]
+pub struct TermList {
+    start: u32,
+    len: u32,
+}
+
+/// Arms of a Match, a range of CoreArena::arms
+#[derive(Clone, Copy, Debug)]
+pub struct ArmList {
+    start: u32,
+    len: u32,
+}
+
+// One term as stored; see TermView for the meaning of each variant
+#[derive(Clone, Debug)]
+pub enum ArenaTerm {
+    IntLit(i64),
+    BoolLit(bool),
+    UnitLit,
+    // Index into CoreArena::texts
+    StrLit(u32),
+    Var(Symbol),
+    Lam(Symbol, TermId),
+    App(TermId, TermId),
+    Tuple(TermList),
+    Proj(TermId, u32),
+    CtorField(TermId, u32),
+    Let(Symbol, TermId, TermId),
+    If(TermId, TermId, TermId),
+    Match(TermId, ArmList),
+    Ctor(Symbol, TermList),
+}
+
+#[derive(Default)]
+pub struct CoreArena {
+    terms: Vec<ArenaTerm>,
+    lists: Vec<TermId>,
+    arms: Vec<(Pattern, TermId)>,
+    texts: Vec<Box<str>>,
+    // The loader records no spans; only terms converted from CoreTerm
+    // can have one. Always Some, so views can lend &Option<Span>.
+    spans: HashMap<TermId, Option<Span>>,
+}
+
+static NO_SPAN: Option<Span> = None;
+
+fn index_u32(len: usize, what: &str) -> u32 {
+    u32::try_from(len).unwrap_or_else(|_| panic!("CoreArena: more than u32::MAX {}", what))
+}
+
+impl CoreArena {
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Drop every term, keeping the allocations for reuse
+    pub fn clear(&mut self) {
+        self.terms.clear();
+        self.lists.clear();
+        self.arms.clear();
+        self.texts.clear();
+        self.spans.clear();
+    }
+
+    pub fn alloc(&mut self, term: ArenaTerm) -> TermId {
+        let id = TermId(index_u32(self.terms.len(), "terms"));
+        self.terms.push(term);
+        id
+    }
+
+    /// Text of a string literal, for ArenaTerm::StrLit
+    pub fn alloc_text(&mut self, text: &str) -> u32 {
+        let index = index_u32(self.texts.len(), "string literals");
+        self.texts.push(text.into());
+        index
+    }
+
+    pub fn alloc_list(&mut self, ids: &[TermId]) -> TermList {
+        let start = index_u32(self.lists.len(), "fields");
+        self.lists.extend_from_slice(ids);
+        TermList { start, len: index_u32(ids.len(), "fields") }
+    }
+
+    pub fn alloc_arms(&mut self, arms: Vec<(Pattern, TermId)>) -> ArmList {
+        let start = index_u32(self.arms.len(), "match arms");
+        let len = index_u32(arms.len(), "match arms");
+        self.arms.extend(arms);
+        ArmList { start, len }
+    }
+
+    pub fn set_span(&mut self, id: TermId, span: Span) {
+        self.spans.insert(id, Some(span));
+    }
+
+    pub fn term(&self, id: TermId) -> TermRef<'_> {
+        TermRef { arena: self, id }
+    }
+
+    /// Copy an Rc tree into the arena
+    pub fn add_term(&mut self, term: &CoreTerm) -> TermId {
+        let (stored, span) = match term {
+            CoreTerm::IntLit(n, span) => (ArenaTerm::IntLit(*n), span),
+            CoreTerm::BoolLit(b, span) => (ArenaTerm::BoolLit(*b), span),
+            CoreTerm::UnitLit(span) => (ArenaTerm::UnitLit, span),
+            CoreTerm::StrLit(s, span) => (ArenaTerm::StrLit(self.alloc_text(s)), span),
+            CoreTerm::Var(name, span) => (ArenaTerm::Var(*name), span),
+            CoreTerm::Lam(param, body, span) => (ArenaTerm::Lam(*param, self.add_term(body)), span),
+            CoreTerm::App(func, arg, span) => {
+                let func = self.add_term(func);
+                (ArenaTerm::App(func, self.add_term(arg)), span)
+            }
+            CoreTerm::Tuple(elems, span) => {
+                let ids: Vec<TermId> = elems.iter().map(|elem| self.add_term(elem)).collect();
+                (ArenaTerm::Tuple(self.alloc_list(&ids)), span)
+            }
+            CoreTerm::Proj(expr, index, span) => {
+                (ArenaTerm::Proj(self.add_term(expr), index_u32(*index, "tuple fields")), span)
+            }
+            CoreTerm::CtorField(expr, index, span) => {
+                (ArenaTerm::CtorField(self.add_term(expr), index_u32(*index, "constructor fields")), span)
+            }
+            CoreTerm::Let(name, value, body, span) => {
+                let value = self.add_term(value);
+                (ArenaTerm::Let(*name, value, self.add_term(body)), span)
+            }
+            CoreTerm::If(cond, then_br, else_br, span) => {
+                let cond = self.add_term(cond);
+                let then_br = self.add_term(then_br);
+                (ArenaTerm::If(cond, then_br, self.add_term(else_br)), span)
+            }
+            CoreTerm::Match(scrutinee, arms, span) => {
+                let scrutinee = self.add_term(scrutinee);
+                let arms = arms.iter().map(|(pattern, body)| (pattern.clone(), self.add_term(body))).collect();
+                (ArenaTerm::Match(scrutinee, self.alloc_arms(arms)), span)
+            }
+            CoreTerm::Ctor(name, fields, span) => {
+                let ids: Vec<TermId> = fields.iter().map(|field| self.add_term(field)).collect();
+                (ArenaTerm::Ctor(Symbol::intern(name), self.alloc_list(&ids)), span)
+            }
+        };
+        let id = self.alloc(stored);
+        if let Some(span) = span {
+            self.set_span(id, span.clone());
+        }
+        id
+    }
+}
+
+/// A term of an arena; Copy, so walkers pass it by value
+#[derive(Clone, Copy)]
+pub struct TermRef<'a> {
+    arena: &'a CoreArena,
+    id: TermId,
+}
+
+/// One level of a term, shaped like CoreTerm with children as TermRefs
+pub enum TermView<'a> {
+    IntLit(&'a i64, &'a Option<Span>),
+    BoolLit(&'a bool, &'a Option<Span>),
+    UnitLit(&'a Option<Span>),
+    StrLit(&'a str, &'a Option<Span>),
+    Var(&'a Symbol, &'a Option<Span>),
+    Lam(&'a Symbol, TermRef<'a>, &'a Option<Span>),
+    App(TermRef<'a>, TermRef<'a>, &'a Option<Span>),
+    Tuple(Terms<'a>, &'a Option<Span>),
+    // 0-based, as CoreTerm::Proj
+    Proj(TermRef<'a>, usize, &'a Option<Span>),
+    CtorField(TermRef<'a>, usize, &'a Option<Span>),
+    Let(&'a Symbol, TermRef<'a>, TermRef<'a>, &'a Option<Span>),
+    If(TermRef<'a>, TermRef<'a>, TermRef<'a>, &'a Option<Span>),
+    Match(TermRef<'a>, Arms<'a>, &'a Option<Span>),
+    Ctor(&'a Symbol, Terms<'a>, &'a Option<Span>),
+}
+
+impl<'a> TermRef<'a> {
+    pub fn id(self) -> TermId {
+        self.id
+    }
+
+    pub fn span(self) -> &'a Option<Span> {
+        self.arena.spans.get(&self.id).unwrap_or(&NO_SPAN)
+    }
+
+    pub fn view(self) -> TermView<'a> {
+        let arena = self.arena;
+        let span = self.span();
+        let at = |id: TermId| TermRef { arena, id };
+        let terms = |list: &TermList| Terms {
+            arena,
+            ids: &arena.lists[list.start as usize..(list.start + list.len) as usize],
+        };
+        match &arena.terms[self.id.index()] {
+            ArenaTerm::IntLit(n) => TermView::IntLit(n, span),
+            ArenaTerm::BoolLit(b) => TermView::BoolLit(b, span),
+            ArenaTerm::UnitLit => TermView::UnitLit(span),
+            ArenaTerm::StrLit(index) => TermView::StrLit(&arena.texts[*index as usize], span),
+            ArenaTerm::Var(name) => TermView::Var(name, span),
+            ArenaTerm::Lam(param, body) => TermView::Lam(param, at(*body), span),
+            ArenaTerm::App(func, arg) => TermView::App(at(*func), at(*arg), span),
+            ArenaTerm::Tuple(elems) => TermView::Tuple(terms(elems), span),
+            ArenaTerm::Proj(expr, index) => TermView::Proj(at(*expr), *index as usize, span),
+            ArenaTerm::CtorField(expr, index) => TermView::CtorField(at(*expr), *index as usize, span),
+            ArenaTerm::Let(name, value, body) => TermView::Let(name, at(*value), at(*body), span),
+            ArenaTerm::If(cond, then_br, else_br) => TermView::If(at(*cond), at(*then_br), at(*else_br), span),
+            ArenaTerm::Match(scrutinee, arms) => TermView::Match(
+                at(*scrutinee),
+                Arms { arena, arms: &arena.arms[arms.start as usize..(arms.start + arms.len) as usize] },
+                span,
+            ),
+            ArenaTerm::Ctor(name, fields) => TermView::Ctor(name, terms(fields), span),
+        }
+    }
+}
+
+/// Fields of a Tuple or Ctor
+#[derive(Clone, Copy)]
+pub struct Terms<'a> {
+    arena: &'a CoreArena,
+    ids: &'a [TermId],
+}
+
+impl<'a> Terms<'a> {
+    pub fn len(self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn get(self, index: usize) -> Option<TermRef<'a>> {
+        self.ids.get(index).map(|&id| TermRef { arena: self.arena, id })
+    }
+
+    pub fn iter(self) -> impl DoubleEndedIterator<Item = TermRef<'a>> + ExactSizeIterator {
+        let arena = self.arena;
+        self.ids.iter().map(move |&id| TermRef { arena, id })
+    }
+}
+
+/// Arms of a Match
+#[derive(Clone, Copy)]
+pub struct Arms<'a> {
+    arena: &'a CoreArena,
+    arms: &'a [(Pattern, TermId)],
+}
+
+impl<'a> Arms<'a> {
+    pub fn len(self) -> usize {
+        self.arms.len()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.arms.is_empty()
+    }
+
+    pub fn iter(self) -> impl DoubleEndedIterator<Item = (&'a Pattern, TermRef<'a>)> + ExactSizeIterator {
+        let arena = self.arena;
+        self.arms.iter().map(move |(pattern, id)| (pattern, TermRef { arena, id: *id }))
+    }
+}
+
+/// A loaded program in arena form
+pub struct ArenaProgram {
+    pub strings: Vec<String>,
+    pub arena: CoreArena,
+    pub root: TermId,
+    pub entrypoint_id: usize,
+}
+
+impl ArenaProgram {
+    pub fn root_term(&self) -> TermRef<'_> {
+        self.arena.term(self.root)
+    }
+
+    pub fn from_program(program: &CoreProgram) -> ArenaProgram {
+        let mut arena = CoreArena::default();
+        let root = arena.add_term(&program.root_term);
+        ArenaProgram { strings: program.strings.clone(), arena, root, entrypoint_id: program.entrypoint_id }
+    }
+
+    /// A program that is just `term`, as tests build them
+    pub fn from_term(term: &CoreTerm) -> ArenaProgram {
+        let mut arena = CoreArena::default();
+        let root = arena.add_term(term);
+        ArenaProgram { strings: Vec::new(), arena, root, entrypoint_id: 0 }
+    }
+}
+
+impl CoreProgram {
+    /// COMPATIBILITY: the Rc tree of an arena program. Children precede
+    /// their parents, so one pass in id order builds every term without
+    /// recursing (bundles can be nested far deeper than the Rust stack).
+    pub fn from_arena(program: &ArenaProgram) -> CoreProgram {
+        let arena = &program.arena;
+        let mut built: Vec<Option<CoreTerm>> = Vec::with_capacity(program.root.index() + 1);
+        for index in 0..=program.root.index() {
+            let id = TermId(index as u32);
+            let span = arena.term(id).span().clone();
+            // Each term has one parent, which takes it
+            let mut take = |child: &TermId| {
+                built[child.index()].take().expect("CoreArena term shared by two parents")
+            };
+            let term = match &arena.terms[index] {
+                ArenaTerm::IntLit(n) => CoreTerm::IntLit(*n, span),
+                ArenaTerm::BoolLit(b) => CoreTerm::BoolLit(*b, span),
+                ArenaTerm::UnitLit => CoreTerm::UnitLit(span),
+                ArenaTerm::StrLit(text) => CoreTerm::StrLit(arena.texts[*text as usize].to_string(), span),
+                ArenaTerm::Var(name) => CoreTerm::Var(*name, span),
+                ArenaTerm::Lam(param, body) => CoreTerm::Lam(*param, Rc::new(take(body)), span),
+                ArenaTerm::App(func, arg) => CoreTerm::App(Rc::new(take(func)), Rc::new(take(arg)), span),
+                ArenaTerm::Tuple(elems) => {
+                    let elems = arena.lists[elems.start as usize..(elems.start + elems.len) as usize].iter().map(&mut take).collect();
+                    CoreTerm::Tuple(elems, span)
+                }
+                ArenaTerm::Proj(expr, i) => CoreTerm::Proj(Rc::new(take(expr)), *i as usize, span),
+                ArenaTerm::CtorField(expr, i) => CoreTerm::CtorField(Rc::new(take(expr)), *i as usize, span),
+                ArenaTerm::Let(name, value, body) => {
+                    let value = take(value);
+                    CoreTerm::Let(*name, Rc::new(value), Rc::new(take(body)), span)
+                }
+                ArenaTerm::If(cond, then_br, else_br) => {
+                    let cond = take(cond);
+                    let then_br = take(then_br);
+                    CoreTerm::If(Rc::new(cond), Rc::new(then_br), Rc::new(take(else_br)), span)
+                }
+                ArenaTerm::Match(scrutinee, arms) => {
+                    let scrutinee = take(scrutinee);
+                    let arms = arena.arms[arms.start as usize..(arms.start + arms.len) as usize]
+                        .iter()
+                        .map(|(pattern, body)| (pattern.clone(), take(body)))
+                        .collect();
+                    CoreTerm::Match(Rc::new(scrutinee), arms, span)
+                }
+                ArenaTerm::Ctor(name, fields) => {
+                    let fields = arena.lists[fields.start as usize..(fields.start + fields.len) as usize].iter().map(&mut take).collect();
+                    CoreTerm::Ctor(name.to_string(), fields, span)
+                }
+            };
+            built.push(Some(term));
+        }
+        let root_term = built[program.root.index()].take().expect("root term");
+        CoreProgram { strings: program.strings.clone(), root_term, entrypoint_id: program.entrypoint_id }
+    }
+}
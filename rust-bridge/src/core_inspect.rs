@@ -0,0 +1,185 @@
+// `inspect --deep`: what a Core bundle holds, for reading one without the
+// compiler at hand
+//
+// Node counts by kind, the top-level functions with their arities, the
+// string table, and the entry point as an indented tree cut off at a
+// given depth. Nodes are labelled as in the graph `axis-compiler
+// --view-core-ir` prints (kind, then the literal, name or index), with
+// children on the lines below instead of node ids. Counting is iterative,
+// so very deep bundles are safe; the tree only recurses as deep as it
+// prints.
+//
+// Kinds are named and nodes labelled by axis_ir::term_kind, which names
+// the compiler's CoreTerm kinds too.
+
+use crate::core_arena::{ArenaProgram, TermRef, TermView};
+use axis_ir::term_kind::TermKind;
+use std::collections::BTreeMap;
+
+fn kind(view: &TermView<'_>) -> TermKind {
+    match view {
+        TermView::IntLit(..) => TermKind::IntLit,
+        TermView::BoolLit(..) => TermKind::BoolLit,
+        TermView::UnitLit(..) => TermKind::UnitLit,
+        TermView::StrLit(..) => TermKind::StrLit,
+        TermView::Var(..) => TermKind::Var,
+        TermView::Lam(..) => TermKind::Lam,
+        TermView::App(..) => TermKind::App,
+        TermView::Tuple(..) => TermKind::Tuple,
+        TermView::Proj(..) => TermKind::Proj,
+        TermView::CtorField(..) => TermKind::CtorField,
+        TermView::Let(..) => TermKind::Let,
+        TermView::If(..) => TermKind::If,
+        TermView::Ctor(..) => TermKind::Ctor,
+        TermView::Match(..) => TermKind::Match,
+    }
+}
+
+// The node's line: its kind, then the literal, bound name or index
+fn label(view: &TermView<'_>) -> String {
+    let detail = match view {
+        TermView::IntLit(n, _) => Some(n.to_string()),
+        TermView::BoolLit(b, _) => Some(b.to_string()),
+        TermView::StrLit(s, _) => Some(s.to_string()),
+        TermView::Var(name, _) | TermView::Lam(name, _, _) | TermView::Let(name, _, _, _) | TermView::Ctor(name, _, _) => {
+            Some(name.to_string())
+        }
+        TermView::Proj(_, index, _) | TermView::CtorField(_, index, _) => Some(index.to_string()),
+        _ => None,
+    };
+    kind(view).label(detail.as_deref())
+}
+
+// Children in source order, each with the prefix its line gets: a match
+// arm's body is led by the arm's pattern
+fn children<'a>(view: &TermView<'a>) -> Vec<(Option<String>, TermRef<'a>)> {
+    match view {
+        TermView::IntLit(..) | TermView::BoolLit(..) | TermView::UnitLit(..) | TermView::StrLit(..) | TermView::Var(..) => {
+            Vec::new()
+        }
+        TermView::Lam(_, body, _) => vec![(None, *body)],
+        TermView::App(func, arg, _) => vec![(None, *func), (None, *arg)],
+        TermView::Tuple(elems, _) | TermView::Ctor(_, elems, _) => elems.iter().map(|elem| (None, elem)).collect(),
+        TermView::Proj(expr, _, _) | TermView::CtorField(expr, _, _) => vec![(None, *expr)],
+        TermView::Let(_, value, body, _) => vec![(None, *value), (None, *body)],
+        TermView::If(cond, then_br, else_br, _) => vec![(None, *cond), (None, *then_br), (None, *else_br)],
+        TermView::Match(scrutinee, arms, _) => {
+            let mut all = vec![(None, *scrutinee)];
+            all.extend(arms.iter().map(|(pattern, body)| (Some(format!("{:?} =>", pattern)), body)));
+            all
+        }
+    }
+}
+
+/// Nodes under and including `root`, in total and by kind
+pub fn count_nodes(root: TermRef<'_>) -> (usize, BTreeMap<&'static str, usize>) {
+    let mut total = 0;
+    let mut by_kind = BTreeMap::new();
+    let mut stack = vec![root];
+    while let Some(term) = stack.pop() {
+        let view = term.view();
+        total += 1;
+        *by_kind.entry(kind(&view).name()).or_insert(0) += 1;
+        stack.extend(children(&view).into_iter().map(|(_, child)| child));
+    }
+    (total, by_kind)
+}
+
+/// The top-level functions, in definition order: each binding of the
+/// program's Let chain whose value is a lambda, with its number of
+/// leading lambdas
+pub fn functions(program: &ArenaProgram) -> Vec<(String, usize)> {
+    let mut functions = Vec::new();
+    let mut current = program.root_term();
+    while let TermView::Let(name, value, body, _) = current.view() {
+        let mut arity = 0;
+        let mut inner = value;
+        while let TermView::Lam(_, lam_body, _) = inner.view() {
+            arity += 1;
+            inner = lam_body;
+        }
+        if arity > 0 {
+            functions.push((name.to_string(), arity));
+        }
+        current = body;
+    }
+    functions
+}
+
+// The entry point's name and definition: axis_entry, else main, through
+// an alias such as `axis_entry = main`
+fn entry_point(program: &ArenaProgram) -> Option<(String, TermRef<'_>)> {
+    let mut definitions: Vec<(String, TermRef<'_>)> = Vec::new();
+    let mut current = program.root_term();
+    while let TermView::Let(name, value, body, _) = current.view() {
+        definitions.push((name.to_string(), value));
+        current = body;
+    }
+    let find = |name: &str| definitions.iter().rev().find(|(defined, _)| defined == name).cloned();
+    let (name, value) = find("axis_entry").or_else(|| find("main"))?;
+    match value.view() {
+        TermView::Var(target, _) => find(target.as_str()).or(Some((name, value))),
+        _ => Some((name, value)),
+    }
+}
+
+/// `term` as an indented tree, two spaces per level; below `max_depth`
+/// levels a subtree is cut to a `... N nodes` line
+pub fn format_tree(term: TermRef<'_>, max_depth: usize) -> String {
+    let mut out = String::new();
+    write_tree(term, None, 0, max_depth, &mut out);
+    out
+}
+
+fn write_tree(term: TermRef<'_>, prefix: Option<String>, depth: usize, max_depth: usize, out: &mut String) {
+    let view = term.view();
+    let indent = "  ".repeat(depth);
+    match prefix {
+        Some(prefix) => out.push_str(&format!("{}{} {}\n", indent, prefix, label(&view))),
+        None => out.push_str(&format!("{}{}\n", indent, label(&view))),
+    }
+    let children = children(&view);
+    if children.is_empty() {
+        return;
+    }
+    if depth + 1 >= max_depth {
+        let below: usize = children.iter().map(|(_, child)| count_nodes(*child).0).sum();
+        out.push_str(&format!("{}  ... {} nodes\n", indent, below));
+        return;
+    }
+    for (prefix, child) in children {
+        write_tree(child, prefix, depth + 1, max_depth, out);
+    }
+}
+
+/// The report `inspect --deep` adds to the summary
+pub fn deep_report(program: &ArenaProgram, max_depth: usize) -> String {
+    let (total, by_kind) = count_nodes(program.root_term());
+    let mut out = format!("Nodes: {}\n", total);
+    for (kind, count) in &by_kind {
+        out.push_str(&format!("  {:<14} {:>8}\n", kind, count));
+    }
+
+    let functions = functions(program);
+    out.push_str(&format!("\nFunctions: {}\n", functions.len()));
+    for (name, arity) in &functions {
+        out.push_str(&format!("  {}/{}\n", name, arity));
+    }
+
+    out.push_str(&format!("\nString table: {} entries\n", program.strings.len()));
+    for (index, text) in program.strings.iter().enumerate() {
+        out.push_str(&format!("  {:>4}  {:?}\n", index, text));
+    }
+
+    out.push('\n');
+    match entry_point(program) {
+        Some((name, definition)) => {
+            out.push_str(&format!("Entry point {} (to depth {}):\n", name, max_depth));
+            for line in format_tree(definition, max_depth).lines() {
+                out.push_str(&format!("  {}\n", line));
+            }
+        }
+        None => out.push_str("Entry point: none (no axis_entry or main binding)\n"),
+    }
+    out
+}
@@ -1,5 +1,8 @@
 use std::fs;
 use std::rc::Rc;
+use crate::core_arena::{ArenaProgram, ArenaTerm, CoreArena, TermId, TermRef, TermView};
+use crate::progress::Progress;
+use crate::symbol::Symbol;
 
 /// Lightweight program container returned by the bridge loader
 pub struct CoreProgram {
@@ -8,6 +11,94 @@ pub struct CoreProgram {
     pub entrypoint_id: usize,
 }
 
+/// Resource limits applied while loading a Core bundle.
+/// Defaults are safe for untrusted input; `trusted()` lifts them for
+/// compiler-produced bundles (CLI: --trusted-input).
+#[derive(Clone, Debug)]
+pub struct BundleLoadOptions {
+    /// Maximum encoded bundle size in bytes
+    pub max_size: usize,
+    /// Maximum number of CoreTerm and Pattern nodes
+    pub max_nodes: usize,
+    /// Maximum term nesting depth
+    pub max_depth: usize,
+    /// Skip all limits (input produced by a trusted compiler)
+    pub trusted: bool,
+    /// Heartbeats while decoding a large bundle (stderr by default)
+    pub progress: Progress,
+}
+
+impl Default for BundleLoadOptions {
+    fn default() -> Self {
+        BundleLoadOptions {
+            max_size: 64 * 1024 * 1024,
+            max_nodes: 8_000_000,
+            max_depth: 4096,
+            trusted: false,
+            progress: Progress::default(),
+        }
+    }
+}
+
+impl BundleLoadOptions {
+    pub fn trusted() -> Self {
+        BundleLoadOptions { trusted: true, ..BundleLoadOptions::default() }
+    }
+}
+
+// Cap'n Proto reader limits derived from the load options
+fn bundle_reader_options(options: &BundleLoadOptions) -> capnp::message::ReaderOptions {
+    let mut opts = capnp::message::ReaderOptions::new();
+    if options.trusted {
+        // Allow very large compiler IRs (trusted input)
+        opts.traversal_limit_in_words = Some(1024 * 1024 * 1024); // ~8GB logical traversal
+        opts.nesting_limit = 1_000_000;                     // extremely deep trees
+    } else {
+        // Traversal proportional to the size cap; each term level is two
+        // pointer hops (CoreTerm union -> variant struct)
+        opts.traversal_limit_in_words = Some(options.max_size / 8);
+        opts.nesting_limit = i32::try_from(options.max_depth.saturating_mul(2).saturating_add(8))
+            .unwrap_or(i32::MAX);
+    }
+    opts
+}
+
+// Running node/depth budget for one bundle load
+struct LoadBudget {
+    nodes_left: usize,
+    max_depth: usize,
+    progress: Progress,
+}
+
+impl LoadBudget {
+    fn new(options: &BundleLoadOptions) -> Self {
+        let progress = options.progress.clone();
+        if options.trusted {
+            LoadBudget { nodes_left: usize::MAX, max_depth: usize::MAX, progress }
+        } else {
+            LoadBudget { nodes_left: options.max_nodes, max_depth: options.max_depth, progress }
+        }
+    }
+
+    fn take_node(&mut self) -> Result<(), String> {
+        if self.nodes_left == 0 {
+            return Err("Core bundle exceeds node limit (use --trusted-input to lift)".to_string());
+        }
+        self.nodes_left -= 1;
+        Ok(())
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<(), String> {
+        if depth > self.max_depth {
+            return Err(format!(
+                "Core bundle exceeds nesting depth limit of {} (use --trusted-input to lift)",
+                self.max_depth
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Span {
     pub file: String,
@@ -22,12 +113,16 @@ pub enum CoreTerm {
     BoolLit(bool, Option<Span>),
     UnitLit(Option<Span>),
     StrLit(String, Option<Span>),
-    Var(String, Option<Span>),
-    Lam(String, Rc<CoreTerm>, Option<Span>),
+    // Variable names are interned (symbol.rs)
+    Var(Symbol, Option<Span>),
+    Lam(Symbol, Rc<CoreTerm>, Option<Span>),
     App(Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
     Tuple(Vec<CoreTerm>, Option<Span>),
+    // Tuple field read; the index is 0-based, as surface `proj(expr, i)`
     Proj(Rc<CoreTerm>, usize, Option<Span>),
-    Let(String, Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
+    // Constructor field read; 0-based, as Proj
+    CtorField(Rc<CoreTerm>, usize, Option<Span>),
+    Let(Symbol, Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
     If(Rc<CoreTerm>, Rc<CoreTerm>, Rc<CoreTerm>, Option<Span>),
     Match(Rc<CoreTerm>, Vec<(Pattern, CoreTerm)>, Option<Span>),
     Ctor(String, Vec<CoreTerm>, Option<Span>),
@@ -41,20 +136,35 @@ pub enum Pattern {
     PVar(String),
     PTuple(Vec<Pattern>),
     PEnum(String, Vec<Pattern>),
+    // `name @ pattern`: binds the whole value as well
+    PBind(String, Box<Pattern>),
 }
 
-// Stack-based iterative deserialization to handle deeply nested Core IR
+impl Pattern {
+    /// What the pattern tests: itself, or under any `name @` the pattern named
+    pub fn shape(&self) -> &Pattern {
+        match self {
+            Pattern::PBind(_, inner) => inner.shape(),
+            pattern => pattern,
+        }
+    }
+}
+
+// Stack-based iterative deserialization to handle deeply nested Core IR.
+// Terms go straight into a CoreArena; the Rc tree is built from it only
+// when asked for (CoreProgram::from_arena).
 enum StackFrame<'a> {
     // Leaf nodes - ready to convert
     IntLit(i64),
     BoolLit(bool),
     UnitLit,
-    StrLit(String),
-    Var(String),
+    // Borrowed from the message; copied once, into the arena
+    StrLit(&'a str),
+    Var(Symbol),
     
     // Non-leaf nodes - waiting for children
     Lam {
-        param: String,
+        param: Symbol,
         body_reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>,
         body_done: bool,
     },
@@ -66,16 +176,21 @@ enum StackFrame<'a> {
     },
     Tuple {
         readers: Vec<crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>>,
-        children: Vec<CoreTerm>,
+        children: Vec<TermId>,
         next_idx: usize,
     },
     Proj {
         expr_reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>,
-        index: usize,
+        index: u32,
+        expr_done: bool,
+    },
+    CtorField {
+        expr_reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>,
+        index: u32,
         expr_done: bool,
     },
     Let {
-        name: String,
+        name: Symbol,
         value_reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>,
         body_reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>,
         value_done: bool,
@@ -90,48 +205,59 @@ enum StackFrame<'a> {
         else_done: bool,
     },
     Ctor {
-        name: String,
+        name: Symbol,
         readers: Vec<crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>>,
-        children: Vec<CoreTerm>,
+        children: Vec<TermId>,
         next_idx: usize,
     },
     Match {
         scrutinee_reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>,
         scrutinee_done: bool,
-        arms: Vec<(Pattern, CoreTerm)>,
+        arms: Vec<(Pattern, TermId)>,
     },
 }
 
-fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reader) -> Result<CoreTerm, String> {
+// `base_depth` is the nesting depth of `reader` itself (non-zero for
+// match-arm bodies, which are decoded by a nested call)
+fn deserialize_core_term(
+    reader: crate::axis_core_ir_0_1_capnp::core_term::Reader,
+    arena: &mut CoreArena,
+    budget: &mut LoadBudget,
+    base_depth: usize,
+) -> Result<TermId, String> {
     
     let mut work_stack: Vec<StackFrame> = Vec::new();
-    let mut result_stack: Vec<CoreTerm> = Vec::new();
+    let mut result_stack: Vec<TermId> = Vec::new();
     let mut loop_counter: usize = 0;
     
     // Push initial reader as work
-    work_stack.push(parse_reader_to_frame(reader)?);
+    work_stack.push(parse_reader_to_frame(reader, arena, budget, base_depth)?);
     
     while let Some(frame) = work_stack.pop() {
+        // Pending frames are exactly the ancestors of the node being decoded
+        let depth = base_depth + work_stack.len() + 1;
+        budget.check_depth(depth)?;
         loop_counter += 1;
         if loop_counter % 10000 == 0 {
-            eprintln!("[PROGRESS] phase=axis_rust_bridge loop=core_ir_deserialize count={}", loop_counter);
+            budget.progress.heartbeat("core_ir_deserialize", loop_counter as u64);
         }
         match frame {
             // Leaf nodes - directly push to result stack
             StackFrame::IntLit(v) => {
-                result_stack.push(CoreTerm::IntLit(v, None));
+                result_stack.push(arena.alloc(ArenaTerm::IntLit(v)));
             },
             StackFrame::BoolLit(v) => {
-                result_stack.push(CoreTerm::BoolLit(v, None));
+                result_stack.push(arena.alloc(ArenaTerm::BoolLit(v)));
             },
             StackFrame::UnitLit => {
-                result_stack.push(CoreTerm::UnitLit(None));
+                result_stack.push(arena.alloc(ArenaTerm::UnitLit));
             },
             StackFrame::StrLit(s) => {
-                result_stack.push(CoreTerm::StrLit(s, None));
+                let text = arena.alloc_text(s);
+                result_stack.push(arena.alloc(ArenaTerm::StrLit(text)));
             },
             StackFrame::Var(name) => {
-                result_stack.push(CoreTerm::Var(name, None));
+                result_stack.push(arena.alloc(ArenaTerm::Var(name)));
             },
             
             // Non-leaf nodes - process children
@@ -140,25 +266,25 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                     // Re-push this frame with body_done=true
                     work_stack.push(StackFrame::Lam { param, body_reader, body_done: true });
                     // Push body work
-                    work_stack.push(parse_reader_to_frame(body_reader)?);
+                    work_stack.push(parse_reader_to_frame(body_reader, arena, budget, depth)?);
                 } else {
                     // Body is on result stack
                     let body = result_stack.pop().ok_or("Stack underflow: Lam body")?;
-                    result_stack.push(CoreTerm::Lam(param, Rc::new(body), None));
+                    result_stack.push(arena.alloc(ArenaTerm::Lam(param, body)));
                 }
             },
             
             StackFrame::App { func_reader, arg_reader, func_done, arg_done } => {
                 if !func_done {
                     work_stack.push(StackFrame::App { func_reader, arg_reader, func_done: true, arg_done: false });
-                    work_stack.push(parse_reader_to_frame(func_reader)?);
+                    work_stack.push(parse_reader_to_frame(func_reader, arena, budget, depth)?);
                 } else if !arg_done {
                     work_stack.push(StackFrame::App { func_reader, arg_reader, func_done: true, arg_done: true });
-                    work_stack.push(parse_reader_to_frame(arg_reader)?);
+                    work_stack.push(parse_reader_to_frame(arg_reader, arena, budget, depth)?);
                 } else {
                     let arg = result_stack.pop().ok_or("Stack underflow: App arg")?;
                     let func = result_stack.pop().ok_or("Stack underflow: App func")?;
-                    result_stack.push(CoreTerm::App(Rc::new(func), Rc::new(arg), None));
+                    result_stack.push(arena.alloc(ArenaTerm::App(func, arg)));
                 }
             },
             
@@ -166,7 +292,7 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                 if next_idx < readers.len() {
                     let reader_to_process = readers[next_idx];
                     work_stack.push(StackFrame::Tuple { readers, children, next_idx: next_idx + 1 });
-                    work_stack.push(parse_reader_to_frame(reader_to_process)?);
+                    work_stack.push(parse_reader_to_frame(reader_to_process, arena, budget, depth)?);
                 } else {
                     // All children processed - collect from result stack
                     let count = readers.len();
@@ -174,49 +300,60 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                         children.push(result_stack.pop().ok_or("Stack underflow: Tuple")?);
                     }
                     children.reverse();
-                    result_stack.push(CoreTerm::Tuple(children, None));
+                    let elems = arena.alloc_list(&children);
+                    result_stack.push(arena.alloc(ArenaTerm::Tuple(elems)));
                 }
             },
             
             StackFrame::Proj { expr_reader, index, expr_done } => {
                 if !expr_done {
                     work_stack.push(StackFrame::Proj { expr_reader, index, expr_done: true });
-                    work_stack.push(parse_reader_to_frame(expr_reader)?);
+                    work_stack.push(parse_reader_to_frame(expr_reader, arena, budget, depth)?);
                 } else {
                     let expr = result_stack.pop().ok_or("Stack underflow: Proj expr")?;
-                    result_stack.push(CoreTerm::Proj(Rc::new(expr), index, None));
+                    result_stack.push(arena.alloc(ArenaTerm::Proj(expr, index)));
+                }
+            },
+            
+            StackFrame::CtorField { expr_reader, index, expr_done } => {
+                if !expr_done {
+                    work_stack.push(StackFrame::CtorField { expr_reader, index, expr_done: true });
+                    work_stack.push(parse_reader_to_frame(expr_reader, arena, budget, depth)?);
+                } else {
+                    let expr = result_stack.pop().ok_or("Stack underflow: CtorField expr")?;
+                    result_stack.push(arena.alloc(ArenaTerm::CtorField(expr, index)));
                 }
             },
             
             StackFrame::Let { name, value_reader, body_reader, value_done, body_done } => {
                 if !value_done {
                     work_stack.push(StackFrame::Let { name, value_reader, body_reader, value_done: true, body_done: false });
-                    work_stack.push(parse_reader_to_frame(value_reader)?);
+                    work_stack.push(parse_reader_to_frame(value_reader, arena, budget, depth)?);
                 } else if !body_done {
                     work_stack.push(StackFrame::Let { name, value_reader, body_reader, value_done: true, body_done: true });
-                    work_stack.push(parse_reader_to_frame(body_reader)?);
+                    work_stack.push(parse_reader_to_frame(body_reader, arena, budget, depth)?);
                 } else {
                     let body = result_stack.pop().ok_or("Stack underflow: Let body")?;
                     let value = result_stack.pop().ok_or("Stack underflow: Let value")?;
-                    result_stack.push(CoreTerm::Let(name, Rc::new(value), Rc::new(body), None));
+                    result_stack.push(arena.alloc(ArenaTerm::Let(name, value, body)));
                 }
             },
             
             StackFrame::If { cond_reader, then_reader, else_reader, cond_done, then_done, else_done } => {
                 if !cond_done {
                     work_stack.push(StackFrame::If { cond_reader, then_reader, else_reader, cond_done: true, then_done: false, else_done: false });
-                    work_stack.push(parse_reader_to_frame(cond_reader)?);
+                    work_stack.push(parse_reader_to_frame(cond_reader, arena, budget, depth)?);
                 } else if !then_done {
                     work_stack.push(StackFrame::If { cond_reader, then_reader, else_reader, cond_done: true, then_done: true, else_done: false });
-                    work_stack.push(parse_reader_to_frame(then_reader)?);
+                    work_stack.push(parse_reader_to_frame(then_reader, arena, budget, depth)?);
                 } else if !else_done {
                     work_stack.push(StackFrame::If { cond_reader, then_reader, else_reader, cond_done: true, then_done: true, else_done: true });
-                    work_stack.push(parse_reader_to_frame(else_reader)?);
+                    work_stack.push(parse_reader_to_frame(else_reader, arena, budget, depth)?);
                 } else {
                     let else_branch = result_stack.pop().ok_or("Stack underflow: If else")?;
                     let then_branch = result_stack.pop().ok_or("Stack underflow: If then")?;
                     let cond = result_stack.pop().ok_or("Stack underflow: If cond")?;
-                    result_stack.push(CoreTerm::If(Rc::new(cond), Rc::new(then_branch), Rc::new(else_branch), None));
+                    result_stack.push(arena.alloc(ArenaTerm::If(cond, then_branch, else_branch)));
                 }
             },
             
@@ -224,7 +361,7 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                 if next_idx < readers.len() {
                     let reader_to_process = readers[next_idx];
                     work_stack.push(StackFrame::Ctor { name, readers, children, next_idx: next_idx + 1 });
-                    work_stack.push(parse_reader_to_frame(reader_to_process)?);
+                    work_stack.push(parse_reader_to_frame(reader_to_process, arena, budget, depth)?);
                 } else {
                     // All children processed - collect from result stack
                     let count = readers.len();
@@ -232,17 +369,19 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
                         children.push(result_stack.pop().ok_or("Stack underflow: Ctor field")?);
                     }
                     children.reverse();
-                    result_stack.push(CoreTerm::Ctor(name, children, None));
+                    let fields = arena.alloc_list(&children);
+                    result_stack.push(arena.alloc(ArenaTerm::Ctor(name, fields)));
                 }
             },
             
             StackFrame::Match { scrutinee_reader, scrutinee_done, arms } => {
                 if !scrutinee_done {
                     work_stack.push(StackFrame::Match { scrutinee_reader, scrutinee_done: true, arms });
-                    work_stack.push(parse_reader_to_frame(scrutinee_reader)?);
+                    work_stack.push(parse_reader_to_frame(scrutinee_reader, arena, budget, depth)?);
                 } else {
                     let scrutinee = result_stack.pop().ok_or("Stack underflow: Match scrutinee")?;
-                    result_stack.push(CoreTerm::Match(Rc::new(scrutinee), arms, None));
+                    let arms = arena.alloc_arms(arms);
+                    result_stack.push(arena.alloc(ArenaTerm::Match(scrutinee, arms)));
                 }
             },
         }
@@ -251,9 +390,15 @@ fn deserialize_core_term(reader: crate::axis_core_ir_0_1_capnp::core_term::Reade
     result_stack.pop().ok_or_else(|| "Empty result stack after deserialization".to_string())
 }
 
-fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>) -> Result<StackFrame<'a>, String> {
+fn parse_reader_to_frame<'a>(
+    reader: crate::axis_core_ir_0_1_capnp::core_term::Reader<'a>,
+    arena: &mut CoreArena,
+    budget: &mut LoadBudget,
+    depth: usize,
+) -> Result<StackFrame<'a>, String> {
     use crate::axis_core_ir_0_1_capnp::core_term::Which;
     
+    budget.take_node()?;
     match reader.which() {
         Ok(Which::CIntLit(lit)) => {
             let lit = lit.map_err(|e| format!("Failed to read CIntLit: {}", e))?;
@@ -271,16 +416,16 @@ fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::R
             let value = lit.get_value()
                 .map_err(|e| format!("Failed to get string value: {}", e))?;
             Ok(StackFrame::StrLit(
-                value.to_str().map_err(|e| format!("Invalid UTF-8 in string: {}", e))?.to_string()
+                value.to_str().map_err(|e| format!("Invalid UTF-8 in string: {}", e))?
             ))
         },
         Ok(Which::CVar(var)) => {
             let var = var.map_err(|e| format!("Failed to read CVar: {}", e))?;
             let name = var.get_name()
                 .map_err(|e| format!("Failed to get var name: {}", e))?;
-            Ok(StackFrame::Var(
-                name.to_str().map_err(|e| format!("Invalid UTF-8 in var name: {}", e))?.to_string()
-            ))
+            Ok(StackFrame::Var(Symbol::intern(
+                name.to_str().map_err(|e| format!("Invalid UTF-8 in var name: {}", e))?
+            )))
         },
         Ok(Which::CLam(lam)) => {
             let lam = lam.map_err(|e| format!("Failed to read CLam: {}", e))?;
@@ -289,7 +434,7 @@ fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::R
             let body_reader = lam.get_body()
                 .map_err(|e| format!("Failed to get body: {}", e))?;
             Ok(StackFrame::Lam {
-                param: param.to_str().map_err(|e| format!("Invalid UTF-8 in param: {}", e))?.to_string(),
+                param: Symbol::intern(param.to_str().map_err(|e| format!("Invalid UTF-8 in param: {}", e))?),
                 body_reader,
                 body_done: false,
             })
@@ -325,13 +470,24 @@ fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::R
             let proj = proj.map_err(|e| format!("Failed to read CProj: {}", e))?;
             let expr_reader = proj.get_expr()
                 .map_err(|e| format!("Failed to get expr: {}", e))?;
-            let index = proj.get_index() as usize;
+            let index = proj.get_index();
             Ok(StackFrame::Proj {
                 expr_reader,
                 index,
                 expr_done: false,
             })
         },
+        Ok(Which::CCtorField(field)) => {
+            let field = field.map_err(|e| format!("Failed to read CCtorField: {}", e))?;
+            let expr_reader = field.get_expr()
+                .map_err(|e| format!("Failed to get expr: {}", e))?;
+            let index = field.get_index();
+            Ok(StackFrame::CtorField {
+                expr_reader,
+                index,
+                expr_done: false,
+            })
+        },
         Ok(Which::CLet(let_)) => {
             let let_ = let_.map_err(|e| format!("Failed to read CLet: {}", e))?;
             let name = let_.get_name()
@@ -341,7 +497,7 @@ fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::R
             let body_reader = let_.get_body()
                 .map_err(|e| format!("Failed to get body: {}", e))?;
             Ok(StackFrame::Let {
-                name: name.to_str().map_err(|e| format!("Invalid UTF-8 in let name: {}", e))?.to_string(),
+                name: Symbol::intern(name.to_str().map_err(|e| format!("Invalid UTF-8 in let name: {}", e))?),
                 value_reader,
                 body_reader,
                 value_done: false,
@@ -376,7 +532,7 @@ fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::R
                 readers.push(fields_reader.get(i));
             }
             Ok(StackFrame::Ctor {
-                name: name.to_str().map_err(|e| format!("Invalid UTF-8 in ctor name: {}", e))?.to_string(),
+                name: Symbol::intern(name.to_str().map_err(|e| format!("Invalid UTF-8 in ctor name: {}", e))?),
                 readers,
                 children: Vec::new(),
                 next_idx: 0,
@@ -394,11 +550,11 @@ fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::R
             for arm_reader in arms_reader.iter() {
                 let pattern_reader = arm_reader.get_pattern()
                     .map_err(|e| format!("Failed to get pattern: {}", e))?;
-                let pattern = deserialize_pattern(pattern_reader)?;
+                let pattern = deserialize_pattern(pattern_reader, budget, depth + 1)?;
                 
                 let body_reader = arm_reader.get_body()
                     .map_err(|e| format!("Failed to get body: {}", e))?;
-                let body = deserialize_core_term(body_reader)?;
+                let body = deserialize_core_term(body_reader, arena, budget, depth + 1)?;
                 
                 arms.push((pattern, body));
             }
@@ -414,9 +570,15 @@ fn parse_reader_to_frame<'a>(reader: crate::axis_core_ir_0_1_capnp::core_term::R
 }
 
 // Deserialize Pattern from Cap'n Proto
-fn deserialize_pattern(reader: crate::axis_core_ir_0_1_capnp::pattern::Reader) -> Result<Pattern, String> {
+fn deserialize_pattern(
+    reader: crate::axis_core_ir_0_1_capnp::pattern::Reader,
+    budget: &mut LoadBudget,
+    depth: usize,
+) -> Result<Pattern, String> {
     use crate::axis_core_ir_0_1_capnp::pattern::Which;
     
+    budget.take_node()?;
+    budget.check_depth(depth)?;
     match reader.which() {
         Ok(Which::PInt(p)) => {
             let p = p.map_err(|e| format!("Failed to read PInt: {}", e))?;
@@ -443,7 +605,7 @@ fn deserialize_pattern(reader: crate::axis_core_ir_0_1_capnp::pattern::Reader) -
                 .map_err(|e| format!("Failed to get tuple patterns: {}", e))?;
             let mut patterns = Vec::new();
             for pat_reader in patterns_reader.iter() {
-                patterns.push(deserialize_pattern(pat_reader)?);
+                patterns.push(deserialize_pattern(pat_reader, budget, depth + 1)?);
             }
             Ok(Pattern::PTuple(patterns))
         },
@@ -455,13 +617,24 @@ fn deserialize_pattern(reader: crate::axis_core_ir_0_1_capnp::pattern::Reader) -
                 .map_err(|e| format!("Failed to get enum patterns: {}", e))?;
             let mut patterns = Vec::new();
             for pat_reader in patterns_reader.iter() {
-                patterns.push(deserialize_pattern(pat_reader)?);
+                patterns.push(deserialize_pattern(pat_reader, budget, depth + 1)?);
             }
             Ok(Pattern::PEnum(
                 name.to_str().map_err(|e| format!("Invalid UTF-8 in enum name: {}", e))?.to_string(),
                 patterns
             ))
         },
+        Ok(Which::PBind(p)) => {
+            let p = p.map_err(|e| format!("Failed to read PBind: {}", e))?;
+            let name = p.get_name()
+                .map_err(|e| format!("Failed to get bind name: {}", e))?;
+            let inner = p.get_pattern()
+                .map_err(|e| format!("Failed to get bound pattern: {}", e))?;
+            Ok(Pattern::PBind(
+                name.to_str().map_err(|e| format!("Invalid UTF-8 in bind name: {}", e))?.to_string(),
+                Box::new(deserialize_pattern(inner, budget, depth + 1)?)
+            ))
+        },
         Err(e) => Err(format!("Unknown Pattern variant: {:?}", e)),
     }
 }
@@ -490,11 +663,11 @@ fn serialize_core_term(term: &CoreTerm, builder: crate::axis_core_ir_0_1_capnp::
         },
         CoreTerm::Var(name, _) => {
             let mut var = builder.init_c_var();
-            var.set_name(name);
+            var.set_name(name.as_str());
         },
         CoreTerm::Lam(param, body, _) => {
             let mut lam = builder.init_c_lam();
-            lam.set_param(param);
+            lam.set_param(param.as_str());
             let body_builder = lam.init_body();
             serialize_core_term(body, body_builder);
         },
@@ -519,9 +692,15 @@ fn serialize_core_term(term: &CoreTerm, builder: crate::axis_core_ir_0_1_capnp::
             let expr_builder = proj.init_expr();
             serialize_core_term(expr, expr_builder);
         },
+        CoreTerm::CtorField(expr, index, _) => {
+            let mut field = builder.init_c_ctor_field();
+            field.set_index(*index as u32);
+            let expr_builder = field.init_expr();
+            serialize_core_term(expr, expr_builder);
+        },
         CoreTerm::Let(name, value, body, _) => {
             let mut let_node = builder.init_c_let();
-            let_node.set_name(name);
+            let_node.set_name(name.as_str());
             let value_builder = let_node.reborrow().init_value();
             serialize_core_term(value, value_builder);
             let body_builder = let_node.init_body();
@@ -596,6 +775,11 @@ fn serialize_pattern(pattern: &Pattern, builder: crate::axis_core_ir_0_1_capnp::
                 serialize_pattern(pat, pat_builder);
             }
         },
+        Pattern::PBind(name, inner) => {
+            let mut p = builder.init_p_bind();
+            p.set_name(name);
+            serialize_pattern(inner, p.init_pattern());
+        },
     }
 }
 
@@ -629,53 +813,226 @@ pub fn write_core_bundle_to_file(term: &CoreTerm, entrypoint_name: &str, path: &
 }
 
 /// Inspect a Core bundle file and return a summary
-pub fn inspect_core_bundle(path: &str) -> Result<String, String> {
-    let program = load_core_bundle(path)?;
-    Ok(format!(
-        "Core bundle: {}\n  Version: 0.1\n  Entrypoint ID: {}\n  String table entries: {}\n  Root term: {:?}",
+pub fn inspect_core_bundle(path: &str, options: &BundleLoadOptions) -> Result<String, String> {
+    inspect(path, options, None)
+}
+
+/// `inspect --deep`: the summary, then node counts, functions, the string
+/// table and the entry point's tree to `depth` levels (core_inspect.rs)
+pub fn inspect_core_bundle_deep(path: &str, options: &BundleLoadOptions, depth: usize) -> Result<String, String> {
+    inspect(path, options, Some(depth))
+}
+
+fn inspect(path: &str, options: &BundleLoadOptions, deep: Option<usize>) -> Result<String, String> {
+    let bytes = read_core_bundle_file(path, options)?;
+    let program = load_core_bundle_arena_from_bytes(&bytes, options)?;
+    let metadata = read_bundle_metadata(&bytes, options)?;
+    let mut summary = format!(
+        "Core bundle: {}\n  Version: 0.1\n  Entrypoint ID: {}\n  String table entries: {}\n  Root term: {:?}{}",
         path,
         program.entrypoint_id,
         program.strings.len(),
-        core_term_summary(&program.root_term)
-    ))
+        core_term_summary(program.root_term()),
+        metadata.summary()
+    );
+    if let Some(depth) = deep {
+        summary.push_str("\n\n");
+        summary.push_str(crate::core_inspect::deep_report(&program, depth).trim_end());
+    }
+    Ok(summary)
+}
+
+/// Provenance the compiler records in a bundle (CoreBundle.metadata, see
+/// core-compiler/src/bundle_metadata.rs); empty for bundles that predate it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BundleMetadata {
+    pub compiler_version: String,
+    /// "" when unknown
+    pub compiler_commit: String,
+    /// (path, digest) of each source; a linked bundle's are its inputs
+    pub sources: Vec<(String, String)>,
+    /// (path, digest) of each registry
+    pub registries: Vec<(String, String)>,
+    pub flags: Vec<String>,
+}
+
+impl BundleMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == BundleMetadata::default()
+    }
+
+    /// The lines `inspect` adds to its summary, "" without metadata
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let mut out = format!("\n  Compiler: {}", self.compiler_version);
+        if !self.compiler_commit.is_empty() {
+            out.push_str(&format!(" ({})", self.compiler_commit));
+        }
+        for (label, files) in [("Source", &self.sources), ("Registry", &self.registries)] {
+            for (path, digest) in files {
+                out.push_str(&format!("\n  {}: {} {}", label, path, digest));
+            }
+        }
+        if !self.flags.is_empty() {
+            out.push_str(&format!("\n  Flags: {}", self.flags.join(" ")));
+        }
+        out
+    }
+}
+
+/// The metadata of an encoded bundle, without decoding its term
+pub fn read_bundle_metadata(bytes: &[u8], options: &BundleLoadOptions) -> Result<BundleMetadata, String> {
+    fn text(text: capnp::Result<capnp::text::Reader<'_>>, what: &str) -> Result<String, String> {
+        let text = text.map_err(|e| format!("Failed to get metadata {}: {}", what, e))?;
+        Ok(text.to_str().map_err(|e| format!("Invalid UTF-8 in metadata {}: {}", what, e))?.to_string())
+    }
+
+    let reader = capnp::serialize::read_message_from_flat_slice(
+        &mut &bytes[..],
+        bundle_reader_options(options)
+    ).map_err(|e| format!("Failed to read Cap'n Proto message: {}", e))?;
+    let bundle = reader.get_root::<crate::axis_core_ir_0_1_capnp::core_bundle::Reader>()
+        .map_err(|e| format!("Failed to get root: {}", e))?;
+    if !bundle.has_metadata() {
+        return Ok(BundleMetadata::default());
+    }
+    let metadata = bundle.get_metadata()
+        .map_err(|e| format!("Failed to get metadata: {}", e))?;
+
+    let mut result = BundleMetadata {
+        compiler_version: text(metadata.get_compiler_version(), "compiler version")?,
+        compiler_commit: text(metadata.get_compiler_commit(), "compiler commit")?,
+        ..BundleMetadata::default()
+    };
+    let sources = metadata.get_sources()
+        .map_err(|e| format!("Failed to get metadata sources: {}", e))?;
+    for file in sources.iter() {
+        result.sources.push((text(file.get_path(), "source")?, text(file.get_digest(), "source")?));
+    }
+    let registries = metadata.get_registries()
+        .map_err(|e| format!("Failed to get metadata registries: {}", e))?;
+    for file in registries.iter() {
+        result.registries.push((text(file.get_path(), "registry")?, text(file.get_digest(), "registry")?));
+    }
+    let flags = metadata.get_flags()
+        .map_err(|e| format!("Failed to get metadata flags: {}", e))?;
+    for i in 0..flags.len() {
+        result.flags.push(text(flags.get(i), "flag")?);
+    }
+    Ok(result)
+}
+
+/// Write `metadata` into a bundle being built
+pub(crate) fn serialize_metadata(
+    metadata: &BundleMetadata,
+    mut builder: crate::axis_core_ir_0_1_capnp::bundle_metadata::Builder<'_>,
+) {
+    builder.set_compiler_version(&metadata.compiler_version);
+    builder.set_compiler_commit(&metadata.compiler_commit);
+    let mut sources = builder.reborrow().init_sources(metadata.sources.len() as u32);
+    for (i, (path, digest)) in metadata.sources.iter().enumerate() {
+        let mut file = sources.reborrow().get(i as u32);
+        file.set_path(path);
+        file.set_digest(digest);
+    }
+    let mut registries = builder.reborrow().init_registries(metadata.registries.len() as u32);
+    for (i, (path, digest)) in metadata.registries.iter().enumerate() {
+        let mut file = registries.reborrow().get(i as u32);
+        file.set_path(path);
+        file.set_digest(digest);
+    }
+    let mut flags = builder.init_flags(metadata.flags.len() as u32);
+    for (i, flag) in metadata.flags.iter().enumerate() {
+        flags.set(i as u32, flag);
+    }
 }
 
 /// Generate a brief summary of a CoreTerm (for inspection)
-fn core_term_summary(term: &CoreTerm) -> String {
-    match term {
-        CoreTerm::IntLit(n, _) => format!("IntLit({})", n),
-        CoreTerm::BoolLit(b, _) => format!("BoolLit({})", b),
-        CoreTerm::UnitLit(_) => "UnitLit".to_string(),
-        CoreTerm::StrLit(s, _) => format!("StrLit({:?})", s),
-        CoreTerm::Var(name, _) => format!("Var({})", name),
-        CoreTerm::Lam(param, _, _) => format!("Lam({}, ...)", param),
-        CoreTerm::App(_, _, _) => "App(...)".to_string(),
-        CoreTerm::Tuple(elems, _) => format!("Tuple({} elems)", elems.len()),
-        CoreTerm::Proj(_, idx, _) => format!("Proj(..., {})", idx),
-        CoreTerm::Let(name, _, _, _) => format!("Let({}, ...)", name),
-        CoreTerm::If(_, _, _, _) => "If(...)".to_string(),
-        CoreTerm::Match(_, arms, _) => format!("Match({} arms)", arms.len()),
-        CoreTerm::Ctor(name, fields, _) => format!("Ctor({}, {} fields)", name, fields.len()),
+fn core_term_summary(term: TermRef<'_>) -> String {
+    match term.view() {
+        TermView::IntLit(n, _) => format!("IntLit({})", n),
+        TermView::BoolLit(b, _) => format!("BoolLit({})", b),
+        TermView::UnitLit(_) => "UnitLit".to_string(),
+        TermView::StrLit(s, _) => format!("StrLit({:?})", s),
+        TermView::Var(name, _) => format!("Var({})", name),
+        TermView::Lam(param, _, _) => format!("Lam({}, ...)", param),
+        TermView::App(_, _, _) => "App(...)".to_string(),
+        TermView::Tuple(elems, _) => format!("Tuple({} elems)", elems.len()),
+        TermView::Proj(_, idx, _) => format!("Proj(..., {})", idx),
+        TermView::CtorField(_, idx, _) => format!("CtorField(..., {})", idx),
+        TermView::Let(name, _, _, _) => format!("Let({}, ...)", name),
+        TermView::If(_, _, _, _) => "If(...)".to_string(),
+        TermView::Match(_, arms, _) => format!("Match({} arms)", arms.len()),
+        TermView::Ctor(name, fields, _) => format!("Ctor({}, {} fields)", name, fields.len()),
     }
 }
 
+/// Load a core bundle binary file produced by `axis-compiler` as an Rc tree
+///
+/// COMPATIBILITY: for callers that rewrite the program; the emitters use
+/// load_core_bundle_arena.
+pub fn load_core_bundle(path: &str, options: &BundleLoadOptions) -> Result<CoreProgram, String> {
+    load_core_bundle_arena(path, options).map(|program| CoreProgram::from_arena(&program))
+}
+
 /// Load a core bundle binary file produced by `axis-compiler`
-pub fn load_core_bundle(path: &str) -> Result<CoreProgram, String> {
-    use capnp::message::ReaderOptions;
-    use capnp::serialize;
-    
-    let bytes = fs::read(path)
-        .map_err(|e| format!("Failed to read Core bundle: {}", e))?;
+pub fn load_core_bundle_arena(path: &str, options: &BundleLoadOptions) -> Result<ArenaProgram, String> {
+    let bytes = read_core_bundle_file(path, options)?;
+    load_core_bundle_arena_from_bytes(&bytes, options)
+}
+
+/// Read a core bundle file without decoding it, enforcing the size limit
+pub fn read_core_bundle_file(path: &str, options: &BundleLoadOptions) -> Result<Vec<u8>, String> {
+    if !options.trusted {
+        // Reject oversized files before reading them into memory
+        let size = fs::metadata(path)
+            .map_err(|e| format!("Failed to read Core bundle: {}", e))?
+            .len();
+        if size > options.max_size as u64 {
+            return Err(format!(
+                "Core bundle is {} bytes, exceeding the {} byte limit (use --trusted-input to lift)",
+                size, options.max_size
+            ));
+        }
+    }
 
-    let mut opts = ReaderOptions::new();
+    fs::read(path).map_err(|e| format!("Failed to read Core bundle: {}", e))
+}
 
-    // Allow very large compiler IRs (trusted input)
-    opts.traversal_limit_in_words = Some(1024 * 1024 * 1024); // ~8GB logical traversal
-    opts.nesting_limit = 1_000_000;                     // extremely deep trees
+/// Fuzzing entry point: decode arbitrary bytes as a Core bundle under the
+/// default (untrusted) limits. Must return for every input, never panic or abort.
+pub fn fuzz_load_bundle(data: &[u8]) {
+    let _ = load_core_bundle_arena_from_bytes(data, &BundleLoadOptions::default());
+}
+
+/// Decode an in-memory core bundle into an Rc tree (COMPATIBILITY, see
+/// load_core_bundle)
+pub fn load_core_bundle_from_bytes(
+    bytes: &[u8],
+    options: &BundleLoadOptions,
+) -> Result<CoreProgram, String> {
+    load_core_bundle_arena_from_bytes(bytes, options).map(|program| CoreProgram::from_arena(&program))
+}
+
+/// Decode an in-memory core bundle, enforcing `options` limits
+pub fn load_core_bundle_arena_from_bytes(
+    bytes: &[u8],
+    options: &BundleLoadOptions,
+) -> Result<ArenaProgram, String> {
+    use capnp::serialize;
+
+    if !options.trusted && bytes.len() > options.max_size {
+        return Err(format!(
+            "Core bundle is {} bytes, exceeding the {} byte limit (use --trusted-input to lift)",
+            bytes.len(), options.max_size
+        ));
+    }
 
     let reader = serialize::read_message(
         &mut &bytes[..],
-        opts
+        bundle_reader_options(options)
     ).map_err(|e| format!("Failed to read Cap'n Proto message: {}", e))?;
     
     let bundle = reader.get_root::<crate::axis_core_ir_0_1_capnp::core_bundle::Reader>()
@@ -705,7 +1062,152 @@ pub fn load_core_bundle(path: &str) -> Result<CoreProgram, String> {
     let core_term_reader = bundle.get_core_term()
         .map_err(|e| format!("Failed to get core term: {}", e))?;
     
-    let root_term = deserialize_core_term(core_term_reader)?;
+    let mut budget = LoadBudget::new(options);
+    let mut arena = CoreArena::default();
+    let root = deserialize_core_term(core_term_reader, &mut arena, &mut budget, 0)?;
+
+    Ok(ArenaProgram { strings, arena, root, entrypoint_id })
+}
+
+// ============================================================================
+// Streaming (axis-rust-bridge build --stream)
+// ============================================================================
+
+/// A top-level definition as scan_definitions reads it, without decoding
+/// its value
+#[derive(Clone, Debug)]
+pub struct DefinitionHead {
+    pub name: Symbol,
+    /// Leading lambdas of the value: its arity, if it is a function
+    pub lambdas: usize,
+    /// The value is a variable, e.g. the entry binding `axis_entry = main`
+    pub alias_of: Option<Symbol>,
+}
+
+// Run `f` on the root term of an encoded bundle. The message is read in
+// place: its segments borrow `bytes` instead of being copied.
+fn with_bundle_root<T>(
+    bytes: &[u8],
+    options: &BundleLoadOptions,
+    f: impl FnOnce(crate::axis_core_ir_0_1_capnp::core_term::Reader<'_>, &mut LoadBudget) -> Result<T, String>,
+) -> Result<T, String> {
+    if !options.trusted && bytes.len() > options.max_size {
+        return Err(format!(
+            "Core bundle is {} bytes, exceeding the {} byte limit (use --trusted-input to lift)",
+            bytes.len(), options.max_size
+        ));
+    }
+
+    let reader = capnp::serialize::read_message_from_flat_slice(
+        &mut &bytes[..],
+        bundle_reader_options(options)
+    ).map_err(|e| format!("Failed to read Cap'n Proto message: {}", e))?;
+
+    let bundle = reader.get_root::<crate::axis_core_ir_0_1_capnp::core_bundle::Reader>()
+        .map_err(|e| format!("Failed to get root: {}", e))?;
+
+    let version = bundle.get_version()
+        .map_err(|e| format!("Failed to get version: {}", e))?;
+
+    if version.to_str().map_err(|e| format!("Invalid UTF-8 in version: {}", e))? != "0.1" {
+        return Err(format!("Unsupported Core bundle version: {:?}", version));
+    }
+
+    let core_term_reader = bundle.get_core_term()
+        .map_err(|e| format!("Failed to get core term: {}", e))?;
+
+    let mut budget = LoadBudget::new(options);
+    f(core_term_reader, &mut budget)
+}
+
+/// Names and shapes of the top-level definitions of an encoded bundle, in
+/// order. Only the chain of top-level Lets and the leading lambdas of each
+/// value are read.
+pub fn scan_definitions(bytes: &[u8], options: &BundleLoadOptions) -> Result<Vec<DefinitionHead>, String> {
+    use crate::axis_core_ir_0_1_capnp::core_term::Which;
+
+    with_bundle_root(bytes, options, |root, budget| {
+        let mut heads = Vec::new();
+        let mut current = root;
+        while let Ok(Which::CLet(let_)) = current.which() {
+            budget.take_node()?;
+            budget.check_depth(heads.len() + 1)?;
+            let let_ = let_.map_err(|e| format!("Failed to read CLet: {}", e))?;
+            let name = let_.get_name()
+                .map_err(|e| format!("Failed to get name: {}", e))?;
+            let mut value = let_.get_value()
+                .map_err(|e| format!("Failed to get value: {}", e))?;
+
+            let mut lambdas = 0;
+            while let Ok(Which::CLam(lam)) = value.which() {
+                let lam = lam.map_err(|e| format!("Failed to read CLam: {}", e))?;
+                lambdas += 1;
+                value = lam.get_body()
+                    .map_err(|e| format!("Failed to get body: {}", e))?;
+            }
+            let alias_of = match value.which() {
+                Ok(Which::CVar(var)) if lambdas == 0 => {
+                    let var = var.map_err(|e| format!("Failed to read CVar: {}", e))?;
+                    let target = var.get_name()
+                        .map_err(|e| format!("Failed to get var name: {}", e))?;
+                    Some(Symbol::intern(target.to_str().map_err(|e| format!("Invalid UTF-8 in var name: {}", e))?))
+                }
+                _ => None,
+            };
+
+            heads.push(DefinitionHead {
+                name: Symbol::intern(name.to_str().map_err(|e| format!("Invalid UTF-8 in let name: {}", e))?),
+                lambdas,
+                alias_of,
+            });
+            current = let_.get_body()
+                .map_err(|e| format!("Failed to get body: {}", e))?;
+        }
+        Ok(heads)
+    })
+}
+
+/// Decode the top-level definitions of an encoded bundle one at a time, in
+/// order, and hand each to `visit` as the one-definition program
+/// `let name = value in ()`; last comes the term the definitions scope over
+/// (normally `()`). Every definition is decoded into the same arena,
+/// cleared in between, so memory use is bounded by the largest definition
+/// rather than by the program.
+pub fn for_each_definition(
+    bytes: &[u8],
+    options: &BundleLoadOptions,
+    mut visit: impl FnMut(TermRef<'_>) -> Result<(), String>,
+) -> Result<(), String> {
+    use crate::axis_core_ir_0_1_capnp::core_term::Which;
+
+    with_bundle_root(bytes, options, |root, budget| {
+        let mut arena = CoreArena::default();
+        let mut current = root;
+        // Depth of `current`, as deserialize_core_term counts it
+        let mut depth = 1;
+        while let Ok(Which::CLet(let_)) = current.which() {
+            budget.take_node()?;
+            budget.check_depth(depth)?;
+            let let_ = let_.map_err(|e| format!("Failed to read CLet: {}", e))?;
+            let name = let_.get_name()
+                .map_err(|e| format!("Failed to get name: {}", e))?;
+            let name = Symbol::intern(name.to_str().map_err(|e| format!("Invalid UTF-8 in let name: {}", e))?);
+            let value_reader = let_.get_value()
+                .map_err(|e| format!("Failed to get value: {}", e))?;
+
+            arena.clear();
+            let value = deserialize_core_term(value_reader, &mut arena, budget, depth)?;
+            let unit = arena.alloc(ArenaTerm::UnitLit);
+            let definition = arena.alloc(ArenaTerm::Let(name, value, unit));
+            visit(arena.term(definition))?;
+
+            current = let_.get_body()
+                .map_err(|e| format!("Failed to get body: {}", e))?;
+            depth += 1;
+        }
 
-    Ok(CoreProgram { strings, root_term, entrypoint_id })
+        arena.clear();
+        let rest = deserialize_core_term(current, &mut arena, budget, depth - 1)?;
+        visit(arena.term(rest))
+    })
 }
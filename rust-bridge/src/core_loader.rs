@@ -3,6 +3,7 @@
 // use while code is transitioning. Use `core_ir::load_core_bundle` instead.
 
 use crate::core_ir::CoreTerm;
+use crate::symbol::Symbol;
 use std::rc::Rc;
 #[allow(dead_code)]
 // Transitional helpers retained for alternate emission paths
@@ -38,7 +39,7 @@ fn deserialize_core_term(value: &serde_json::Value) -> Result<CoreTerm, String>
             let name = obj.get("name")
                 .and_then(|v| v.as_str())
                 .ok_or("CVar missing name")?;
-            Ok(CoreTerm::Var(name.to_string(), None))
+            Ok(CoreTerm::Var(Symbol::intern(name), None))
         },
         "CLam" => {
             let param = obj.get("param")
@@ -47,7 +48,7 @@ fn deserialize_core_term(value: &serde_json::Value) -> Result<CoreTerm, String>
             let body_val = obj.get("body")
                 .ok_or("CLam missing body")?;
             let body = deserialize_core_term(body_val)?;
-            Ok(CoreTerm::Lam(param.to_string(), Rc::new(body), None))
+            Ok(CoreTerm::Lam(Symbol::intern(param), Rc::new(body), None))
         },
         "CApp" => {
             let func_val = obj.get("func")
@@ -77,6 +78,15 @@ fn deserialize_core_term(value: &serde_json::Value) -> Result<CoreTerm, String>
             let expr = deserialize_core_term(expr_val)?;
             Ok(CoreTerm::Proj(Rc::new(expr), index as usize, None))
         },
+        "CCtorField" => {
+            let expr_val = obj.get("expr")
+                .ok_or("CCtorField missing expr")?;
+            let index = obj.get("index")
+                .and_then(|v| v.as_i64())
+                .ok_or("CCtorField missing index")?;
+            let expr = deserialize_core_term(expr_val)?;
+            Ok(CoreTerm::CtorField(Rc::new(expr), index as usize, None))
+        },
         "CLet" => {
             let name = obj.get("name")
                 .and_then(|v| v.as_str())
@@ -87,7 +97,7 @@ fn deserialize_core_term(value: &serde_json::Value) -> Result<CoreTerm, String>
                 .ok_or("CLet missing body")?;
             let value = deserialize_core_term(value_val)?;
             let body = deserialize_core_term(body_val)?;
-            Ok(CoreTerm::Let(name.to_string(), Rc::new(value), Rc::new(body), None))
+            Ok(CoreTerm::Let(Symbol::intern(name), Rc::new(value), Rc::new(body), None))
         },
         "CIf" => {
             let cond_val = obj.get("cond")
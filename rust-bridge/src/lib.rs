@@ -5,7 +5,14 @@ pub mod axis_core_ir_0_1_capnp {
     include!(concat!(env!("OUT_DIR"), "/axis_core_ir_0_1_capnp.rs"));
 }
 
+pub mod abi;
+pub mod backend;
+pub mod core_arena;
+pub mod core_inspect;
 pub mod core_ir;
 pub mod core_loader;
+pub mod progress;
 pub mod runtime;
 pub use runtime::emit_rust;
+// Shared with axis-compiler (axis-ir), under the paths they had here
+pub use axis_ir::{depfile, exit_codes, internal_error, symbol};
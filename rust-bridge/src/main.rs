@@ -1,266 +1,832 @@
+use clap::builder::PossibleValuesParser;
+use clap::{value_parser, Arg, ArgAction, ArgGroup, ArgMatches, Command};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
-use std::time::Instant;
-use axis_rust_bridge::{core_ir, emit_rust};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use axis_rust_bridge::{backend, core_ir, depfile, emit_rust, exit_codes, internal_error, symbol};
+use axis_rust_bridge::progress::{self, Progress, ProgressEvent};
+use axis_rust_bridge::runtime::{capability, code_writer, entry, overflow, reactor};
 
 // Generated Cap'n Proto schema
 mod axis_core_ir_0_1_capnp {
     include!(concat!(env!("OUT_DIR"), "/axis_core_ir_0_1_capnp.rs"));
 }
 
-fn usage_and_exit() -> ! {
-    eprintln!("Usage:");
-    eprintln!("  axis-rust-bridge build <path-to.coreir> --out <binary>");
-    eprintln!("  axis-rust-bridge inspect <path-to.coreir>");
-    std::process::exit(1)
+// Names the bridge in internal errors
+const PROGRAM: &str = "axis-rust-bridge";
+
+// Build and staging directories under the temp directory; `clean` removes them
+const BUILD_DIR_PREFIX: &str = "axis_rust_bridge_build_";
+const STREAM_DIR_PREFIX: &str = "axis_rust_bridge_stream_";
+
+// The bundle every subcommand but `clean` reads
+fn bundle_arg() -> Arg {
+    Arg::new("bundle")
+        .help("Core IR bundle (.coreir)")
+        .value_name("BUNDLE")
+        .required(true)
+}
+
+fn trusted_input_arg() -> Arg {
+    Arg::new("trusted-input")
+        .long("trusted-input")
+        .help("The bundle comes from our own compiler: lift the size, node and depth limits")
+        .action(ArgAction::SetTrue)
+}
+
+// What `build` and `emit` share: the bundle and how to emit it
+fn emit_args() -> Vec<Arg> {
+    let one_of = |names: Vec<&'static str>| PossibleValuesParser::new(names);
+    vec![
+        bundle_arg(),
+        Arg::new("backend")
+            .long("backend")
+            .visible_alias("target")
+            .help("Code generator")
+            .value_name("NAME")
+            .value_parser(one_of(backend::BACKENDS.to_vec()))
+            .default_value(backend::BACKENDS[0]),
+        Arg::new("debug")
+            .long("debug")
+            .help("Shadow call stack for Axis-level backtraces on runtime errors, and a build to step through in a debugger")
+            .action(ArgAction::SetTrue),
+        Arg::new("axis-source")
+            .long("axis-source")
+            .help("An Axis source to embed in the binary; repeatable")
+            .value_name("FILE")
+            .value_parser(value_parser!(PathBuf))
+            .action(ArgAction::Append)
+            .requires("debug"),
+        trusted_input_arg(),
+        Arg::new("strict-codegen")
+            .long("strict-codegen")
+            .help("Report references the emitter would paper over with aliases")
+            .action(ArgAction::SetTrue),
+        Arg::new("linear-matches")
+            .long("linear-matches")
+            .help("The pre-decision-tree match lowering, for comparison")
+            .action(ArgAction::SetTrue),
+        Arg::new("native-args")
+            .long("native-args")
+            .help("Unpacked parameters for functions only ever called in full")
+            .action(ArgAction::SetTrue),
+        Arg::new("keep-clones")
+            .long("keep-clones")
+            .help("Every clone the emitter writes, for comparison")
+            .action(ArgAction::SetTrue),
+        Arg::new("stream")
+            .long("stream")
+            .help("Emit from the encoded bundle without loading the whole program")
+            .action(ArgAction::SetTrue),
+        Arg::new("standalone")
+            .long("standalone")
+            .help("One .rs file with the runtime inlined, built with rustc alone")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("stream"),
+        Arg::new("format-generated")
+            .long("format-generated")
+            .help("rustfmt the emitted Rust, for reading it; skipped if rustfmt is missing")
+            .action(ArgAction::SetTrue),
+        Arg::new("allow")
+            .long("allow")
+            .help("Capability sandbox: refuse other primitives and leave them out of the runtime")
+            .value_name("io,fs,proc|none")
+            .value_parser(capability::Capability::parse_list),
+        Arg::new("runtime")
+            .long("runtime")
+            .help("Runtime profile: async runs the IO and file primitives through a reactor")
+            .value_name("PROFILE")
+            .value_parser(one_of(reactor::RuntimeProfile::ALL.iter().map(|p| p.name()).collect())),
+        Arg::new("int-overflow")
+            .long("int-overflow")
+            .help("What the arithmetic primitives do on overflow and division by zero")
+            .value_name("MODE")
+            .value_parser(one_of(overflow::IntOverflow::ALL.iter().map(|m| m.name()).collect())),
+        Arg::new("entry-args-format")
+            .long("entry-args-format")
+            .help("Shape of axis_entry's argument: a List[Str] or (argc, argv)")
+            .value_name("FORMAT")
+            .value_parser(one_of(entry::EntryArgsFormat::ALL.iter().map(|f| f.name()).collect())),
+        Arg::new("require-bridge-version")
+            .long("require-bridge-version")
+            .help("Refuse to build with a bridge (and runtime) outside a version requirement, e.g. ^0.1")
+            .value_name("REQ")
+            .value_parser(|requirement: &str| backend::check_bridge_version(requirement).map(|()| requirement.to_string())),
+        Arg::new("emit-depfile")
+            .long("emit-depfile")
+            .help("Also write a Make-style dependency file: the output depends on the bundle and the --axis-source files")
+            .value_name("FILE"),
+    ]
+}
+
+fn cli() -> Command {
+    Command::new("axis-rust-bridge")
+        .about("Axis Rust bridge - builds programs from Core IR bundles")
+        .version(backend::BRIDGE_VERSION)
+        .after_help(exit_codes::BRIDGE_HELP)
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Report the backend, every file written, the build command run and every directory cleaned")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("timings")
+                .long("timings")
+                .help("Print how long each phase took, as a tree of nested phases")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .subcommand(
+            Command::new("build")
+                .about("Build a binary from a .coreir bundle (or only write its sources, with --emit-source)")
+                .arg(
+                    Arg::new("out")
+                        .short('o')
+                        .long("out")
+                        .help("Where to write the binary")
+                        .value_name("BINARY")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("emit-source")
+                        .long("emit-source")
+                        .help("Write the emitted project to DIR instead of building it (the `emit` subcommand)")
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .group(ArgGroup::new("destination").args(["out", "emit-source"]).required(true))
+                .arg(
+                    Arg::new("reproducible")
+                        .long("reproducible")
+                        .help("Same bundle, same binary: build in a directory named after the sources, not the process")
+                        .action(ArgAction::SetTrue),
+                )
+                .args(emit_args()),
+        )
+        .subcommand(
+            Command::new("emit")
+                .about("Write the project emitted for a .coreir bundle to a directory, without building it")
+                .arg(
+                    Arg::new("out")
+                        .short('o')
+                        .long("out")
+                        .help("Directory for the emitted sources")
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true),
+                )
+                .args(emit_args()),
+        )
+        .subcommand(
+            Command::new("inspect")
+                .about("Summarise a .coreir bundle: entry point, string table, root term and the compiler that wrote it")
+                .arg(bundle_arg())
+                .arg(trusted_input_arg())
+                .arg(
+                    Arg::new("deep")
+                        .long("deep")
+                        .help("Also print node counts by kind, the top-level functions and their arities, the string table and the entry point as a tree")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .help("How many levels of the entry point's tree --deep prints")
+                        .value_name("N")
+                        .value_parser(value_parser!(u32).range(1..))
+                        .default_value("8")
+                        .requires("deep"),
+                ),
+        )
+        .subcommand(
+            Command::new("clean")
+                .about("Remove the build directories earlier builds left in the temp directory"),
+        )
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        usage_and_exit();
+    // A panic is a bridge bug: reported as an internal error naming the
+    // phase, not as a backtrace
+    match internal_error::guard(|| run_command(&cli().get_matches())) {
+        Ok(code) => std::process::exit(code),
+        Err(error) => {
+            eprint!("{}", error.render(PROGRAM));
+            std::process::exit(exit_codes::INTERNAL);
+        }
     }
+}
 
-    let cmd = args.get(1).map(|s| s.as_str()).unwrap_or("");
-
-    match cmd {
-        "inspect" => {
-            // Inspect a Core IR file
-            if args.len() < 3 {
-                eprintln!("Usage: axis-rust-bridge inspect <path-to.coreir>");
-                std::process::exit(1);
-            }
-            let core_path = &args[2];
-            match core_ir::inspect_core_bundle(core_path) {
+fn run_command(matches: &ArgMatches) -> i32 {
+    let verbose = matches.get_flag("verbose");
+    let timings = matches.get_flag("timings");
+    match matches.subcommand() {
+        Some(("build", build)) => {
+            let out_path = build.get_one::<PathBuf>("out").cloned();
+            let emit_source = build.get_one::<PathBuf>("emit-source").cloned();
+            run_build(build, out_path, emit_source, verbose, timings)
+        }
+        Some(("emit", emit)) => {
+            let dir = emit.get_one::<PathBuf>("out").cloned();
+            run_build(emit, None, dir, verbose, timings)
+        }
+        Some(("inspect", inspect)) => {
+            let core_path = inspect.get_one::<String>("bundle").expect("required by clap");
+            let load_options = if inspect.get_flag("trusted-input") {
+                core_ir::BundleLoadOptions::trusted()
+            } else {
+                core_ir::BundleLoadOptions::default()
+            };
+            let summary = if inspect.get_flag("deep") {
+                let depth = *inspect.get_one::<u32>("depth").expect("has a default") as usize;
+                core_ir::inspect_core_bundle_deep(core_path, &load_options, depth)
+            } else {
+                core_ir::inspect_core_bundle(core_path, &load_options)
+            };
+            match summary {
                 Ok(summary) => {
                     println!("{}", summary);
-                    std::process::exit(0);
-                },
+                    exit_codes::SUCCESS
+                }
                 Err(e) => {
                     eprintln!("Failed to inspect Core IR: {}", e);
-                    std::process::exit(1);
+                    exit_codes::IO
                 }
             }
-        },
-        "build" => {
-            // Build a binary from Core IR
-            run_build(&args);
-        },
-        _ => {
-            usage_and_exit();
         }
+        Some(("clean", _)) => run_clean(verbose),
+        // subcommand_required
+        _ => exit_codes::USAGE,
     }
 }
 
-fn run_build(args: &[String]) {
-    let phase_start = Instant::now();
-    eprintln!("[PHASE] phase4_axis_rust_bridge_run=start");
-    
-    let exit_code = (|| {
-        // Expect: build <path-to.coreir> --out <binary>
-        if args.len() < 4 {
-            usage_and_exit();
+// `clean`: the build and --stream staging directories in the temp
+// directory. A build running meanwhile loses its directory, so clean
+// between builds.
+fn run_clean(verbose: bool) -> i32 {
+    let temp = env::temp_dir();
+    let entries = match fs::read_dir(&temp) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("read {}: {}", temp.display(), e);
+            return exit_codes::IO;
         }
-
-        let core_path = args[2].clone();
-        if core_path.starts_with("--") {
-            eprintln!("Expected path to .coreir file as first argument");
-            usage_and_exit();
+    };
+    let mut removed = 0;
+    let mut failed = false;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if !name.starts_with(BUILD_DIR_PREFIX) && !name.starts_with(STREAM_DIR_PREFIX) {
+            continue;
         }
-
-        let mut out_path: Option<String> = None;
-        let mut i = 3;
-        while i < args.len() {
-            match args[i].as_str() {
-                "--out" => {
-                    i += 1;
-                    if i >= args.len() {
-                        usage_and_exit();
-                    }
-                    out_path = Some(args[i].clone());
-                    i += 1;
-                }
-                _ => {
-                    eprintln!("Unknown arg: {}", args[i]);
-                    usage_and_exit();
+        let path = entry.path();
+        match fs::remove_dir_all(&path) {
+            Ok(()) => {
+                removed += 1;
+                if verbose {
+                    eprintln!("Removed {}", path.display());
                 }
             }
+            Err(e) => {
+                eprintln!("remove {}: {}", path.display(), e);
+                failed = true;
+            }
         }
+    }
+    eprintln!("Removed {} build directories from {}", removed, temp.display());
+    if failed { exit_codes::IO } else { exit_codes::SUCCESS }
+}
 
-        if out_path.is_none() {
-            usage_and_exit();
+fn write_artifacts(dir: &Path, artifacts: &backend::EmitArtifacts, verbose: bool) -> Result<(), String> {
+    for (rel_path, contents) in &artifacts.files {
+        let path = dir.join(rel_path);
+        create_parent_dir(&path)?;
+        fs::write(&path, contents).map_err(|e| format!("write {}: {}", path.display(), e))?;
+        if verbose {
+            eprintln!("Wrote {} ({} bytes)", path.display(), contents.len());
         }
+    }
+    Ok(())
+}
 
-        // Prepare temp build directory
-        let mut build_dir = env::temp_dir();
-        build_dir.push(format!("axis_rust_bridge_build_{}", std::process::id()));
-        let _ = fs::remove_dir_all(&build_dir);
-        fs::create_dir_all(build_dir.join("src")).expect("failed to create build dir");
+fn create_parent_dir(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent).map_err(|e| format!("create {}: {}", parent.display(), e)),
+        None => Ok(()),
+    }
+}
 
-        // 1) Use the provided Core IR file (do not invoke axis-compiler)
-        let core_bundle_path = PathBuf::from(core_path);
-        if !core_bundle_path.exists() {
-            eprintln!("Core IR file not found: {}", core_bundle_path.display());
-            return 1;
+// --format-generated: run rustfmt over the emitted Rust sources, in
+// place. Formatting is only for reading the output, so a missing rustfmt
+// or a source it rejects is a warning and the sources stay as emitted.
+fn format_rust_sources(artifacts: &mut backend::EmitArtifacts, streamed: Option<&mut StreamedSource>) -> Result<(), String> {
+    let format_one = |path: &Path, source: &str| -> Option<String> {
+        if path.extension().is_none_or(|ext| ext != "rs") {
+            return None;
         }
-
-        // 2) Load Core bundle using core_ir deserialization helper
-        let sub_start = Instant::now();
-        eprintln!("[PHASE] phase4_core_ir_load=start");
-        let core_program = match core_ir::load_core_bundle(core_bundle_path.to_str().unwrap()) {
-            Ok(p) => p,
+        match code_writer::rustfmt(source) {
+            Ok(formatted) => Some(formatted),
             Err(e) => {
-                eprintln!("Failed to load Core IR bundle: {}", e);
-                return 1;
-            }
-        };
-        eprintln!("[PHASE] phase4_core_ir_load=end ms={}", sub_start.elapsed().as_millis());
-
-    // 3) Emit Rust using existing emitter
-    let sub_start = Instant::now();
-    eprintln!("[PHASE] phase4_emit_rust=start");
-    let generated = emit_rust::emit_rust_from_core(&core_program.root_term, "<core>", "");
-    eprintln!("[PHASE] phase4_emit_rust=end ms={}", sub_start.elapsed().as_millis());
-
-    // 4) Write emitted Rust into temporary Cargo package
-    // Determine bridge path: if running from release/debug, go up to find axis-rust-bridge
-    let bridge_path = {
-        let exe_path = env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
-        let mut candidate = exe_path.parent().unwrap_or(std::path::Path::new(".")).to_path_buf();
-
-        // Walk up to find axis-rust-bridge directory
-        let mut found = false;
-        for _ in 0..5 {
-            let test_path = candidate.join("axis-rust-bridge");
-            if test_path.join("Cargo.toml").exists() {
-                candidate = test_path;
-                found = true;
-                break;
-            }
-            if let Some(parent) = candidate.parent() {
-                candidate = parent.to_path_buf();
-            } else {
-                break;
+                eprintln!("warning: --format-generated: {}: {}; left unformatted", path.display(), e);
+                None
             }
         }
-
-        // Verify we found a valid path
-        if !found || !candidate.join("Cargo.toml").exists() {
-            candidate = PathBuf::from("/home/chris/dev/axis-lang/axis-rust-bridge"); // Fallback
+    };
+    for (path, contents) in artifacts.files.iter_mut() {
+        if let Some(formatted) = format_one(path, contents) {
+            *contents = formatted;
+        }
+    }
+    if let Some(source) = streamed {
+        let text = fs::read_to_string(&source.written_to)
+            .map_err(|e| format!("read {}: {}", source.written_to.display(), e))?;
+        if let Some(formatted) = format_one(&source.path, &text) {
+            fs::write(&source.written_to, &formatted)
+                .map_err(|e| format!("write {}: {}", source.written_to.display(), e))?;
+            let mut hash = Fnv1a::new();
+            hash.update(formatted.as_bytes());
+            source.hash = hash.0;
         }
+    }
+    Ok(())
+}
 
-        candidate
-    };
+// --debug: axis_source_map.tsv, locating the Axis functions in each
+// generated Rust source (emit_rust::source_map)
+fn add_source_maps(artifacts: &mut backend::EmitArtifacts, streamed: Option<&StreamedSource>) -> Result<(), String> {
+    let mut map = String::new();
+    for (path, contents) in &artifacts.files {
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            map.push_str(&emit_rust::source_map(&path.display().to_string(), contents));
+        }
+    }
+    if let Some(source) = streamed {
+        let text = fs::read_to_string(&source.written_to)
+            .map_err(|e| format!("read {}: {}", source.written_to.display(), e))?;
+        map.push_str(&emit_rust::source_map(&source.path.display().to_string(), &text));
+    }
+    if !map.is_empty() {
+        map.insert_str(0, "# generated line\taxis function\taxis location\n");
+        artifacts.files.push((PathBuf::from("axis_source_map.tsv"), map));
+    }
+    Ok(())
+}
 
-    let emitted_cargo_toml = format!(r#"[package]
-name = "axis_emitted"
-version = "0.1.0"
-edition = "2021"
-
-[dependencies]
-axis-rust-bridge = {{ path = "{}" }}
-"#, bridge_path.display());
-    fs::write(build_dir.join("Cargo.toml"), emitted_cargo_toml).expect("write Cargo.toml");
-    // Write generated code into a dedicated module file to avoid emitting
-    // an executable `main` here. The bridge will provide the single Rust
-    // `main` wrapper that calls `axis_entry`.
-    fs::write(build_dir.join("src").join("axis_generated.rs"), generated).expect("write generated Rust");
-
-    // Create a thin executable wrapper that initializes runtime and calls
-    // the Axis entry function `axis_entry` produced by the emitter.
-    let wrapper = r#"mod axis_generated;
-use axis_rust_bridge::runtime::*;
+// FNV-1a; stable across Rust releases, unlike DefaultHasher
+struct Fnv1a(u64);
 
-fn main() {
-    init_runtime();
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
 
-    let __phase_start = std::time::Instant::now();
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
 
-    // Read CLI arguments (skip program name)
-    let cli_args: Vec<String> = std::env::args().skip(1).collect();
+// A source written by `build --stream` rather than held in the artifacts
+struct StreamedSource {
+    // Relative to the build directory
+    path: PathBuf,
+    written_to: PathBuf,
+    // Fnv1a of the contents
+    hash: u64,
+}
 
-    // Convert to Axis list representation using Nil/Cons constructors
-    let mut axis_args = Value::Ctor {
-        tag: intern_tag("Nil"),
-        fields: vec![]
-    };
+// Buffered writer for a streamed source, counting and hashing the bytes
+// that go through it (phase line, --reproducible)
+struct StreamWriter<W: Write> {
+    inner: W,
+    hash: Fnv1a,
+    bytes: usize,
+    lines: usize,
+}
 
-    // Build list in reverse order
-    for arg in cli_args.iter().rev() {
-        axis_args = Value::Ctor {
-            tag: intern_tag("Cons"),
-            fields: vec![
-                Value::Str(intern_str(arg)),
-                axis_args
-            ]
-        };
+impl<W: Write> StreamWriter<W> {
+    fn new(inner: W) -> Self {
+        StreamWriter { inner, hash: Fnv1a::new(), bytes: 0, lines: 0 }
     }
+}
 
-    // Call Axis entry with arguments
-    let result = axis_generated::axis_entry(axis_args);
+impl<W: Write> Write for StreamWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        let chunk = &buf[..written];
+        self.hash.update(chunk);
+        self.bytes += written;
+        self.lines += chunk.iter().filter(|&&byte| byte == b'\n').count();
+        Ok(written)
+    }
 
-    // Extract exit code from result
-    let exit_code = match result {
-        Value::Int(n) => n as i32,
-        _ => 0,
-    };
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
 
-    let __phase_elapsed = __phase_start.elapsed().as_millis();
+// Hash of the emitted file names and contents
+fn artifacts_hash(artifacts: &backend::EmitArtifacts, streamed: Option<&StreamedSource>) -> u64 {
+    let mut hash = Fnv1a::new();
+    for (path, contents) in &artifacts.files {
+        hash.update(path.to_string_lossy().as_bytes());
+        hash.update(&[0]);
+        hash.update(contents.as_bytes());
+        hash.update(&[0]);
+    }
+    if let Some(source) = streamed {
+        hash.update(source.path.to_string_lossy().as_bytes());
+        hash.update(&[0]);
+        hash.update(&source.hash.to_le_bytes());
+    }
+    hash.0
+}
 
-    std::process::exit(exit_code);
+// Peak resident set size (VmHWM) in KiB; Linux only, `-` elsewhere
+fn peak_rss_kb() -> String {
+    fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("VmHWM:"))
+                .map(|value| value.trim().trim_end_matches("kB").trim().to_string())
+        })
+        .unwrap_or_else(|| "-".to_string())
 }
-"#;
-    fs::write(build_dir.join("src").join("main.rs"), wrapper).expect("write wrapper main.rs");
 
-        // 5) Invoke cargo build --release in the temp dir
-        eprintln!("Building emitted Rust with cargo...");
-        let sub_start = Instant::now();
-        eprintln!("[PHASE] phase4_cargo_build=start");
-        let mut child = Command::new("cargo")
-            .arg("build")
-            .arg("--release")
-            .current_dir(&build_dir)
-            .spawn()
-            .expect("failed to spawn cargo build");
-        
-        // Heartbeat loop: emit progress every 1000ms while cargo runs
-        loop {
-            match child.try_wait() {
-                Ok(Some(_status)) => break,
-                Ok(None) => {
-                    let elapsed = sub_start.elapsed().as_millis();
-                    eprintln!("[PROGRESS] phase=axis_rust_bridge loop=cargo_build_wait count={}", elapsed);
-                    std::thread::sleep(std::time::Duration::from_millis(1000));
+// `build`, and `emit` (always with `emit_source`)
+fn run_build(matches: &ArgMatches, out_path: Option<PathBuf>, emit_source: Option<PathBuf>, verbose: bool, timings: bool) -> i32 {
+    let phase_start = Instant::now();
+    // [PHASE] lines and heartbeats on stderr; --timings also collects them
+    let recorded = Arc::new(progress::Timings::default());
+    let progress = if timings {
+        let recorded = Arc::clone(&recorded);
+        Progress::new(move |event: &ProgressEvent| {
+            eprintln!("{}", event);
+            recorded.record(event);
+        })
+    } else {
+        Progress::default()
+    };
+    progress.report(ProgressEvent::start("phase4_axis_rust_bridge_run"));
+    
+    let exit_code = (|| {
+        let core_path = matches.get_one::<String>("bundle").expect("required by clap").clone();
+        let backend_name = matches.get_one::<String>("backend").expect("has a default").clone();
+        let load_options = if matches.get_flag("trusted-input") {
+            core_ir::BundleLoadOptions::trusted()
+        } else {
+            core_ir::BundleLoadOptions::default()
+        };
+        let defaults = emit_rust::EmitOptions::default();
+        let emit_options = emit_rust::EmitOptions {
+            // --debug: shadow call-stack and debug info
+            debug_call_stack: matches.get_flag("debug"),
+            debug_info: matches.get_flag("debug"),
+            strict_codegen: matches.get_flag("strict-codegen"),
+            linear_matches: matches.get_flag("linear-matches"),
+            native_args: matches.get_flag("native-args"),
+            keep_clones: matches.get_flag("keep-clones"),
+            standalone: matches.get_flag("standalone"),
+            allow: matches.get_one::<Vec<capability::Capability>>("allow").cloned(),
+            runtime: matches
+                .get_one::<String>("runtime")
+                .and_then(|name| reactor::RuntimeProfile::parse(name))
+                .unwrap_or(defaults.runtime),
+            int_overflow: matches
+                .get_one::<String>("int-overflow")
+                .and_then(|name| overflow::IntOverflow::parse(name))
+                .unwrap_or(defaults.int_overflow),
+            entry_args: matches
+                .get_one::<String>("entry-args-format")
+                .and_then(|name| entry::EntryArgsFormat::parse(name))
+                .unwrap_or(defaults.entry_args),
+            require_bridge_version: matches.get_one::<String>("require-bridge-version").cloned(),
+        };
+        // `emit` has no --reproducible: it builds nothing
+        let reproducible = matches.try_get_one::<bool>("reproducible").ok().flatten().copied().unwrap_or(false);
+        let stream = matches.get_flag("stream");
+        let format_generated = matches.get_flag("format-generated");
+        let axis_sources: Vec<PathBuf> = matches.get_many::<PathBuf>("axis-source").into_iter().flatten().cloned().collect();
+        let depfile_path = matches.get_one::<String>("emit-depfile").cloned();
+
+        // 1) Use the provided Core IR file (do not invoke axis-compiler)
+        let core_bundle_path = PathBuf::from(&core_path);
+        if !core_bundle_path.exists() {
+            eprintln!("Core IR file not found: {}", core_bundle_path.display());
+            return exit_codes::IO;
+        }
+
+        // --emit-depfile: what the build reads
+        let mut inputs = vec![core_path.clone()];
+        inputs.extend(axis_sources.iter().map(|path| path.display().to_string()));
+        let write_depfile = |target: &Path| match &depfile_path {
+            Some(path) => match depfile::write(path, &target.display().to_string(), &inputs) {
+                Ok(()) => {
+                    eprintln!("Wrote dependency file -> {}", path);
+                    true
                 }
                 Err(e) => {
-                    eprintln!("Error checking cargo status: {}", e);
+                    eprintln!("{}", e);
+                    false
+                }
+            },
+            None => true,
+        };
+
+        // 2) Select the backend
+        // Determine bridge path: if running from release/debug, go up to find axis-rust-bridge
+        let bridge_path = {
+            let exe_path = env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
+            let mut candidate = exe_path.parent().unwrap_or(std::path::Path::new(".")).to_path_buf();
+
+            // Walk up to find axis-rust-bridge directory
+            let mut found = false;
+            for _ in 0..5 {
+                let test_path = candidate.join("axis-rust-bridge");
+                if test_path.join("Cargo.toml").exists() {
+                    candidate = test_path;
+                    found = true;
                     break;
                 }
+                if let Some(parent) = candidate.parent() {
+                    candidate = parent.to_path_buf();
+                } else {
+                    break;
+                }
+            }
+
+            // Verify we found a valid path
+            if !found || !candidate.join("Cargo.toml").exists() {
+                candidate = PathBuf::from("/home/chris/dev/axis-lang/axis-rust-bridge"); // Fallback
             }
+
+            candidate
+        };
+
+        let backend = match backend::backend_by_name(&backend_name, &bridge_path) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("{}", e);
+                return exit_codes::USAGE;
+            }
+        };
+        if verbose {
+            eprintln!("Using the {} backend (runtime crate {})", backend.name(), bridge_path.display());
         }
+
+        // 3) Load the Core bundle and emit the target project; with
+        // --stream, emit straight from the encoded bundle instead
+        let mut streamed: Option<StreamedSource> = None;
+        let emit_phase = internal_error::enter_phase("emit");
+        let mut artifacts = if stream {
+            let Some(source) = backend.streamed_source() else {
+                eprintln!("--stream: the {} backend cannot stream", backend.name());
+                return exit_codes::USAGE;
+            };
+            let bundle = match core_ir::read_core_bundle_file(&core_path, &load_options) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    eprintln!("Failed to load Core IR bundle: {}", e);
+                    return exit_codes::IO;
+                }
+            };
+
+            // Written into the --emit-source directory, or a staging
+            // directory until the build directory is known (--reproducible
+            // names it after the sources)
+            let stage_dir = emit_source.clone().unwrap_or_else(|| {
+                env::temp_dir().join(format!("{}{}", STREAM_DIR_PREFIX, std::process::id()))
+            });
+            let path = stage_dir.join(&source);
+            let file = match create_parent_dir(&path).and_then(|()| {
+                fs::File::create(&path).map_err(|e| format!("write {}: {}", path.display(), e))
+            }) {
+                Ok(file) => file,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return exit_codes::IO;
+                }
+            };
+            let mut out = StreamWriter::new(BufWriter::new(file));
+
+            let sub_start = Instant::now();
+            progress.report(ProgressEvent::start(format!("phase4_emit_{}", backend.name())).with("stream", 1));
+            let artifacts = match backend.emit_streaming(&bundle, &load_options, &mut out, &emit_options) {
+                Ok(a) => a,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return exit_codes::VALIDATION;
+                }
+            };
+            if let Err(e) = out.flush() {
+                eprintln!("write {}: {}", path.display(), e);
+                return exit_codes::IO;
+            }
+            progress.report(
+                ProgressEvent::end(format!("phase4_emit_{}", backend.name()), sub_start)
+                    .with("bytes", out.bytes + artifacts.files.iter().map(|(_, text)| text.len()).sum::<usize>())
+                    .with("lines", out.lines + artifacts.files.iter().map(|(_, text)| text.lines().count()).sum::<usize>())
+                    .with("peak_rss_kb", peak_rss_kb()),
+            );
+            streamed = Some(StreamedSource { path: source, written_to: path, hash: out.hash.0 });
+            artifacts
+        } else {
+            // Load Core bundle using core_ir deserialization helper
+            let sub_start = Instant::now();
+            progress.report(ProgressEvent::start("phase4_core_ir_load"));
+            let load_phase = internal_error::enter_phase("load");
+            let core_program = match core_ir::load_core_bundle_arena(&core_path, &load_options) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("Failed to load Core IR bundle: {}", e);
+                    return exit_codes::IO;
+                }
+            };
+            drop(load_phase);
+            // Memory is tracked alongside time: names are interned (symbol.rs), so
+            // symbols is the number of distinct variable names in the bundle;
+            // terms is the size of the program's arena (core_arena.rs)
+            progress.report(
+                ProgressEvent::end("phase4_core_ir_load", sub_start)
+                    .with("terms", core_program.arena.len())
+                    .with("symbols", symbol::symbol_count())
+                    .with("peak_rss_kb", peak_rss_kb()),
+            );
+
+            let sub_start = Instant::now();
+            progress.report(ProgressEvent::start(format!("phase4_emit_{}", backend.name())));
+            let artifacts = match backend.emit(&core_program, &emit_options) {
+                Ok(a) => a,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return exit_codes::VALIDATION;
+                }
+            };
+            // Generated size is tracked alongside time: the runtime is linked from
+            // this crate, so the emitted sources should only grow with the program
+            progress.report(
+                ProgressEvent::end(format!("phase4_emit_{}", backend.name()), sub_start)
+                    .with("bytes", artifacts.files.iter().map(|(_, text)| text.len()).sum::<usize>())
+                    .with("lines", artifacts.files.iter().map(|(_, text)| text.lines().count()).sum::<usize>())
+                    .with("peak_rss_kb", peak_rss_kb()),
+            );
+            artifacts
+        };
+
+        drop(emit_phase);
+
+        if format_generated {
+            let _phase = internal_error::enter_phase("format");
+            if let Err(e) = format_rust_sources(&mut artifacts, streamed.as_mut()) {
+                eprintln!("{}", e);
+                return exit_codes::IO;
+            }
+        }
+
+        // --debug: where the Axis functions are in the generated Rust (after
+        // formatting, which moves them), and the Axis sources to embed
+        if emit_options.debug_info {
+            if let Err(e) = add_source_maps(&mut artifacts, streamed.as_ref()) {
+                eprintln!("{}", e);
+                return exit_codes::IO;
+            }
+            let mut sources = Vec::new();
+            for path in &axis_sources {
+                match fs::read_to_string(path) {
+                    Ok(text) => sources.push((path.display().to_string(), text)),
+                    Err(e) => {
+                        eprintln!("--axis-source {}: {}", path.display(), e);
+                        return exit_codes::IO;
+                    }
+                }
+            }
+            if !sources.is_empty() {
+                backend.embed_sources(&mut artifacts, &sources);
+            }
+        }
+
+        let write_phase = internal_error::enter_phase("write");
+        if let Some(dir) = emit_source {
+            if let Err(e) = write_artifacts(&dir, &artifacts, verbose) {
+                eprintln!("{}", e);
+                return exit_codes::IO;
+            }
+            eprintln!("Wrote {} sources -> {}", backend.name(), dir.display());
+            return if write_depfile(&dir) { exit_codes::SUCCESS } else { exit_codes::IO };
+        }
+
+        // 4) Write the emitted project into a temp build directory
+        // POLICY: the toolchains record the build path in the binary (crate
+        // metadata hashes, panic locations), so --reproducible names the
+        // directory after the emitted sources instead of the process. The
+        // directory is reused, not cleared: it only ever holds these sources.
+        let mut build_dir = env::temp_dir();
+        if reproducible {
+            build_dir.push(format!("{}{:016x}", BUILD_DIR_PREFIX, artifacts_hash(&artifacts, streamed.as_ref())));
+        } else {
+            build_dir.push(format!("{}{}", BUILD_DIR_PREFIX, std::process::id()));
+            let _ = fs::remove_dir_all(&build_dir);
+        }
+        let written = fs::create_dir_all(&build_dir)
+            .map_err(|e| format!("create {}: {}", build_dir.display(), e))
+            .and_then(|()| write_artifacts(&build_dir, &artifacts, verbose));
+        if let Err(e) = written {
+            eprintln!("{}", e);
+            return exit_codes::IO;
+        }
+        if let Some(source) = &streamed {
+            let path = build_dir.join(&source.path);
+            let moved = create_parent_dir(&path).and_then(|()| {
+                fs::rename(&source.written_to, &path)
+                    .map_err(|e| format!("move {} to {}: {}", source.written_to.display(), path.display(), e))
+            });
+            if let Err(e) = moved {
+                eprintln!("{}", e);
+                return exit_codes::IO;
+            }
+            // The staging directory the source was written under
+            if let Some(stage_dir) = source.written_to.ancestors().nth(source.path.components().count()) {
+                let _ = fs::remove_dir_all(stage_dir);
+            }
+        }
+        drop(write_phase);
+
+        // 5) Run the backend's build command in the temp dir
+        let _build_phase = internal_error::enter_phase("build");
+        let Some((program, build_args)) = artifacts.build_command.split_first() else {
+            let error = internal_error::CompilerError::new("build", format!("the {} backend has no build command", backend.name()));
+            eprint!("{}", error.render(PROGRAM));
+            return exit_codes::INTERNAL;
+        };
+        eprintln!("Building emitted {} with {}...", backend.name(), program);
+        if verbose {
+            eprintln!("Running {} in {}", artifacts.build_command.join(" "), build_dir.display());
+        }
+        let sub_start = Instant::now();
+        progress.report(ProgressEvent::start(format!("phase4_{}_build", program)));
+        let mut child = match std::process::Command::new(program).args(build_args).current_dir(&build_dir).spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("failed to spawn {}: {}", program, e);
+                return exit_codes::IO;
+            }
+        };
         
-        let build_status = child.wait().expect("failed to wait for cargo build");
-        eprintln!("[PHASE] phase4_cargo_build=end ms={}", sub_start.elapsed().as_millis());
+        // Heartbeat every 1000ms while the build runs
+        let task = format!("{}_build_wait", program);
+        let build_status = match progress::wait_with_heartbeat(&mut child, &task, Duration::from_millis(1000), &progress)
+            .or_else(|e| {
+                eprintln!("Error checking {} status: {}", program, e);
+                child.wait()
+            }) {
+            Ok(status) => status,
+            Err(e) => {
+                eprintln!("failed to wait for {}: {}", program, e);
+                return exit_codes::IO;
+            }
+        };
+        progress.report(ProgressEvent::end(format!("phase4_{}_build", program), sub_start));
         if !build_status.success() {
-            eprintln!("cargo build failed");
-            return 1;
+            // The emitted project always builds: a failure is a bridge bug
+            eprintln!("{} build failed", program);
+            return exit_codes::INTERNAL;
         }
 
         // 6) Copy resulting binary to --out
-        let bin_name = "axis_emitted";
-        let built_bin = build_dir.join("target").join("release").join(bin_name);
-        let out_path = PathBuf::from(out_path.unwrap());
-        fs::copy(&built_bin, &out_path).expect("failed to copy binary to output");
+        let built_bin = build_dir.join(&artifacts.output);
+        let out_path = out_path.expect("--out or --emit-source, required by clap");
+        if let Err(e) = fs::copy(&built_bin, &out_path) {
+            eprintln!("copy {} to {}: {}", built_bin.display(), out_path.display(), e);
+            return exit_codes::IO;
+        }
 
         eprintln!("Wrote binary -> {}", out_path.display());
-        0
+        if !write_depfile(&out_path) {
+            return exit_codes::IO;
+        }
+        if emit_options.debug_info {
+            // The binary's debug info points into the project; see
+            // axis_source_map.tsv there for the Axis functions
+            eprintln!("Kept debug build project -> {}", build_dir.display());
+        }
+        exit_codes::SUCCESS
     })();
     
-    eprintln!(
-        "[PHASE] phase4_axis_rust_bridge_run=end ms={}",
-        phase_start.elapsed().as_millis()
-    );
-    std::process::exit(exit_code);
+    progress.report(ProgressEvent::end("phase4_axis_rust_bridge_run", phase_start).with("peak_rss_kb", peak_rss_kb()));
+    if timings {
+        eprint!("Timings:\n{}", recorded.format());
+    }
+    exit_code
 }
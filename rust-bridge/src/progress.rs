@@ -0,0 +1,187 @@
+//! Progress of a build, for whoever is watching
+//!
+//! `axis-rust-bridge build` prints `[PHASE]` lines as it enters and leaves
+//! each phase and `[PROGRESS]` heartbeats inside long ones. The same events
+//! go through a [`ProgressReporter`], so a GUI or language server driving
+//! the library can draw a progress bar instead of scraping stderr:
+//!
+//! ```ignore
+//! let (sender, events) = std::sync::mpsc::channel();
+//! let options = BundleLoadOptions { progress: Progress::new(sender), ..BundleLoadOptions::default() };
+//! ```
+//!
+//! A closure `Fn(&ProgressEvent)` is a reporter too. [`Progress::default`]
+//! prints to stderr, as the CLI does; [`Progress::silent`] drops events.
+//! [`Timings`] collects the phase times for `--timings`.
+
+use std::fmt;
+use std::io;
+use std::process::{Child, ExitStatus};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// One progress event; `Display` gives the CLI's stderr line
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgressEvent {
+    /// `[PHASE] <phase>=start`
+    PhaseStart { phase: String, details: Vec<(String, String)> },
+    /// `[PHASE] <phase>=end ms=<ms>`, then the phase's figures (bytes,
+    /// terms, peak_rss_kb, ...)
+    PhaseEnd { phase: String, ms: u128, details: Vec<(String, String)> },
+    /// `[PROGRESS] phase=axis_rust_bridge loop=<task> count=<count>`: still
+    /// working. `count` is the task's own unit (terms decoded, ms waited).
+    Heartbeat { task: String, count: u64 },
+}
+
+impl ProgressEvent {
+    pub fn start(phase: impl Into<String>) -> Self {
+        ProgressEvent::PhaseStart { phase: phase.into(), details: Vec::new() }
+    }
+
+    pub fn end(phase: impl Into<String>, started: Instant) -> Self {
+        ProgressEvent::PhaseEnd { phase: phase.into(), ms: started.elapsed().as_millis(), details: Vec::new() }
+    }
+
+    /// Add a `key=value` figure to a phase event
+    pub fn with(mut self, key: &str, value: impl ToString) -> Self {
+        if let ProgressEvent::PhaseStart { details, .. } | ProgressEvent::PhaseEnd { details, .. } = &mut self {
+            details.push((key.to_string(), value.to_string()));
+        }
+        self
+    }
+}
+
+impl fmt::Display for ProgressEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let details = |f: &mut fmt::Formatter<'_>, details: &[(String, String)]| {
+            details.iter().try_for_each(|(key, value)| write!(f, " {}={}", key, value))
+        };
+        match self {
+            ProgressEvent::PhaseStart { phase, details: figures } => {
+                write!(f, "[PHASE] {}=start", phase)?;
+                details(f, figures)
+            }
+            ProgressEvent::PhaseEnd { phase, ms, details: figures } => {
+                write!(f, "[PHASE] {}=end ms={}", phase, ms)?;
+                details(f, figures)
+            }
+            ProgressEvent::Heartbeat { task, count } => {
+                write!(f, "[PROGRESS] phase=axis_rust_bridge loop={} count={}", task, count)
+            }
+        }
+    }
+}
+
+/// Receives the events of a build as they happen
+pub trait ProgressReporter: Send + Sync {
+    fn report(&self, event: &ProgressEvent);
+}
+
+/// The CLI's reporter: each event as a line on stderr
+pub struct StderrProgress;
+
+impl ProgressReporter for StderrProgress {
+    fn report(&self, event: &ProgressEvent) {
+        eprintln!("{}", event);
+    }
+}
+
+impl<F: Fn(&ProgressEvent) + Send + Sync> ProgressReporter for F {
+    fn report(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+// A receiver that hung up no longer wants the events; the build goes on
+impl ProgressReporter for Sender<ProgressEvent> {
+    fn report(&self, event: &ProgressEvent) {
+        let _ = self.send(event.clone());
+    }
+}
+
+/// A shared reporter, cheap to clone into the options of each phase
+#[derive(Clone)]
+pub struct Progress(Arc<dyn ProgressReporter>);
+
+impl Progress {
+    pub fn new(reporter: impl ProgressReporter + 'static) -> Self {
+        Progress(Arc::new(reporter))
+    }
+
+    pub fn silent() -> Self {
+        Progress::new(|_: &ProgressEvent| {})
+    }
+
+    pub fn report(&self, event: ProgressEvent) {
+        self.0.report(&event);
+    }
+
+    pub fn heartbeat(&self, task: &str, count: u64) {
+        self.report(ProgressEvent::Heartbeat { task: task.to_string(), count });
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Progress::new(StderrProgress)
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Progress")
+    }
+}
+
+/// The phases of a build and how long each took, for `--timings`: record
+/// every event, then `format` once the build is done
+#[derive(Debug, Default)]
+pub struct Timings {
+    // (depth, phase, ms once it ended), in the order the phases started
+    phases: Mutex<Vec<(usize, String, Option<u128>)>>,
+}
+
+impl Timings {
+    pub fn record(&self, event: &ProgressEvent) {
+        let mut phases = self.phases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match event {
+            ProgressEvent::PhaseStart { phase, .. } => {
+                let depth = phases.iter().filter(|(_, _, ms)| ms.is_none()).count();
+                phases.push((depth, phase.clone(), None));
+            }
+            ProgressEvent::PhaseEnd { phase, ms, .. } => {
+                if let Some(open) = phases.iter_mut().rev().find(|(_, name, end)| name == phase && end.is_none()) {
+                    open.2 = Some(*ms);
+                }
+            }
+            ProgressEvent::Heartbeat { .. } => {}
+        }
+    }
+
+    /// A header, then one line per ended phase indented by nesting:
+    /// `      12 ms    phase4_core_ir_load`, like `axis-compiler --timings`
+    pub fn format(&self) -> String {
+        let phases = self.phases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut out = format!("{:>12}  phase\n", "time");
+        for (depth, phase, ms) in phases.iter() {
+            if let Some(ms) = ms {
+                out.push_str(&format!("{:>9} ms  {}{}\n", ms, "  ".repeat(*depth), phase));
+            }
+        }
+        out
+    }
+}
+
+/// Wait for a child process, with a heartbeat (milliseconds waited) every
+/// `interval` until it exits
+pub fn wait_with_heartbeat(child: &mut Child, task: &str, interval: Duration, progress: &Progress) -> io::Result<ExitStatus> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        progress.heartbeat(task, started.elapsed().as_millis() as u64);
+        std::thread::sleep(interval);
+    }
+}
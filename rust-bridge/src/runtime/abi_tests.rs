@@ -0,0 +1,98 @@
+// Tests for the stable interface generated code builds against (abi.rs)
+
+use crate::abi::{init_runtime_for, ABI_VERSION};
+use crate::backend::{backend_by_name, check_bridge_version, version_matches, BRIDGE_VERSION};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::emit_rust::{get_foreign_symbol_mapping, EmitOptions};
+use std::path::Path;
+use std::rc::Rc;
+use crate::runtime::panic_on_runtime_errors;
+
+// The names abi::shim re-exports, read from its source
+fn abi_shim_names() -> Vec<String> {
+    let source = include_str!("../abi.rs");
+    let shim = &source[source.find("pub mod shim {").expect("abi.rs declares mod shim")..];
+    shim.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_abi_exports_every_mapped_primitive() {
+    let names = abi_shim_names();
+    let mut missing: Vec<&str> = get_foreign_symbol_mapping()
+        .values()
+        .filter_map(|target| target.strip_prefix("shim::"))
+        .filter(|name| !names.iter().any(|n| n == name))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    assert!(missing.is_empty(), "mapped but not in abi::shim: {:?}", missing);
+}
+
+#[test]
+fn test_generated_code_imports_only_the_abi() {
+    // axis_entry(args) = io_print(str_concat(("a", "b")))
+    let call = |f: &str, arg: CoreTerm| CoreTerm::App(Rc::new(CoreTerm::Var(f.into(), None)), Rc::new(arg), None);
+    let pair = CoreTerm::Tuple(vec![CoreTerm::StrLit("a".to_string(), None), CoreTerm::StrLit("b".to_string(), None)], None);
+    let entry = CoreTerm::Lam("args".into(), Rc::new(call("io_print", call("str_concat", pair))), None);
+    let program =
+        ArenaProgram::from_term(&CoreTerm::Let("axis_entry".into(), Rc::new(entry), Rc::new(CoreTerm::UnitLit(None)), None));
+
+    let rust = backend_by_name("rust", Path::new("/opt/axis-rust-bridge")).unwrap();
+    let artifacts = rust.emit(&program, &EmitOptions::default()).unwrap();
+    for (path, contents) in &artifacts.files {
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            assert!(contents.contains("use axis_rust_bridge::abi::*;"), "{}:\n{}", path.display(), contents);
+            assert!(!contents.contains("runtime::"), "{}:\n{}", path.display(), contents);
+        }
+    }
+}
+
+#[test]
+fn test_generated_code_is_stamped_and_checked() {
+    let entry = CoreTerm::Lam("args".into(), Rc::new(CoreTerm::IntLit(0, None)), None);
+    let program =
+        ArenaProgram::from_term(&CoreTerm::Let("axis_entry".into(), Rc::new(entry), Rc::new(CoreTerm::UnitLit(None)), None));
+    let rust = backend_by_name("rust", Path::new("/opt/axis-rust-bridge")).unwrap();
+    let artifacts = rust.emit(&program, &EmitOptions::default()).unwrap();
+    let file = |name: &str| &artifacts.files.iter().find(|(path, _)| path == Path::new(name)).unwrap().1;
+    assert!(file("src/axis_generated.rs").contains(&format!("pub const AXIS_ABI_VERSION: u32 = {};", ABI_VERSION)));
+    assert!(file("src/main.rs").contains("init_runtime_for(axis_generated::AXIS_ABI_VERSION);"));
+    assert!(!file("Cargo.toml").contains("version = \"^"));
+
+    let pinned = EmitOptions { require_bridge_version: Some("^0.1".to_string()), ..EmitOptions::default() };
+    let artifacts = rust.emit(&program, &pinned).unwrap();
+    assert!(artifacts.files[0].1.contains("{ path = \"/opt/axis-rust-bridge\", version = \"^0.1\" }"), "{}", artifacts.files[0].1);
+}
+
+#[test]
+fn test_init_runtime_for_this_version() {
+    init_runtime_for(ABI_VERSION);
+}
+
+#[test]
+#[should_panic(expected = "but axis-rust-bridge")]
+fn test_init_runtime_refuses_other_versions() {
+    panic_on_runtime_errors();
+    init_runtime_for(ABI_VERSION + 1);
+}
+
+#[test]
+fn test_bridge_version_requirements() {
+    let matches = |requirement: &str| version_matches("0.3.2", requirement).unwrap();
+    for requirement in ["0.3", "^0.3.1", "0.3.2", "=0.3.2", "=0.3", "~0.3", ">=0.2, <0.4", ">0.3.1", "<=0.3", "0"] {
+        assert!(matches(requirement), "{}", requirement);
+    }
+    for requirement in ["0.2", "^0.3.3", "=0.3.1", "~0.4", ">=0.4", "<0.3.2", ">0.3", "1", "0.0"] {
+        assert!(!matches(requirement), "{}", requirement);
+    }
+    assert!(version_matches("1.4.0", "1.2").unwrap());
+    assert!(!version_matches("2.0.0", "1.2").unwrap());
+    assert!(version_matches("0.1.0", "x.1").is_err());
+    assert!(version_matches("0.1.0", "*").is_err());
+    assert!(check_bridge_version(BRIDGE_VERSION).is_ok());
+    assert!(check_bridge_version("=999").unwrap_err().contains("does not satisfy the required version =999"));
+}
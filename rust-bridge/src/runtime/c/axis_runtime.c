@@ -0,0 +1,1223 @@
+/* Axis C runtime; see axis_runtime.h for the ownership contract.
+ *
+ * Primitives mirror the Rust runtime (runtime/value.rs, tuple.rs, shim.rs)
+ * including its fallbacks: arithmetic on non-Int operands yields 0, a field
+ * read out of range yields Unit. Strings are length-prefixed byte buffers
+ * compared by content (the Rust runtime interns them, with the same effect).
+ */
+#include "axis_runtime.h"
+
+#include <inttypes.h>
+#include <stdarg.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define AX_RUNTIME_ERROR_EXIT_CODE 70
+#define AX_ASSERTION_FAILED_EXIT_CODE 3
+#define AX_ENTRY_ERR_EXIT_CODE 1
+
+static AxValue ax_unit_value = { -1, AX_UNIT, { 0 } };
+static AxValue ax_true_value = { -1, AX_BOOL, { 1 } };
+static AxValue ax_false_value = { -1, AX_BOOL, { 0 } };
+
+static long ax_assertion_failures = 0;
+
+/* ========================================================================
+ * Errors
+ * ======================================================================== */
+
+static void ax_report_location(const char *file, int line, int column) {
+    if (file != NULL) {
+        fprintf(stderr, "\n  --> %s:%d:%d", file, line, column);
+    }
+}
+
+void ax_runtime_error(const char *msg, const char *file, int line, int column) {
+    fflush(stdout);
+    fprintf(stderr, "error[runtime]: %s", msg);
+    ax_report_location(file, line, column);
+    fputc('\n', stderr);
+    exit(AX_RUNTIME_ERROR_EXIT_CODE);
+}
+
+static void *ax_alloc(size_t size) {
+    void *p = malloc(size == 0 ? 1 : size);
+    if (p == NULL) {
+        ax_runtime_error("out of memory", NULL, 0, 0);
+    }
+    return p;
+}
+
+/* ========================================================================
+ * Reference counting
+ * ======================================================================== */
+
+AxValue *ax_retain(AxValue *v) {
+    if (v->refs >= 0) {
+        v->refs++;
+    }
+    return v;
+}
+
+/* Iterative so that releasing a long list does not exhaust the C stack */
+void ax_release(AxValue *v) {
+    AxValue **pending = NULL;
+    size_t count = 0, cap = 0;
+    for (;;) {
+        if (v->refs >= 0 && --v->refs == 0) {
+            if (v->kind == AX_STR) {
+                free(v->as.str.data);
+            } else if (v->kind == AX_BUILDER) {
+                free(v->as.sb.data);
+            } else if (v->kind == AX_TUPLE || v->kind == AX_CTOR) {
+                size_t i;
+                for (i = 0; i < v->as.agg.len; i++) {
+                    if (count == cap) {
+                        cap = cap == 0 ? 16 : cap * 2;
+                        pending = realloc(pending, cap * sizeof(AxValue *));
+                        if (pending == NULL) {
+                            ax_runtime_error("out of memory", NULL, 0, 0);
+                        }
+                    }
+                    pending[count++] = v->as.agg.items[i];
+                }
+                free(v->as.agg.items);
+            }
+            free(v);
+        }
+        if (count == 0) {
+            break;
+        }
+        v = pending[--count];
+    }
+    free(pending);
+}
+
+/* ========================================================================
+ * Constructors
+ * ======================================================================== */
+
+static AxValue *ax_new(AxKind kind) {
+    AxValue *v = ax_alloc(sizeof(AxValue));
+    v->refs = 1;
+    v->kind = kind;
+    return v;
+}
+
+AxValue *ax_int(int64_t i) {
+    AxValue *v = ax_new(AX_INT);
+    v->as.i = i;
+    return v;
+}
+
+AxValue *ax_bool(int b) {
+    return b ? &ax_true_value : &ax_false_value;
+}
+
+AxValue *ax_unit(void) {
+    return &ax_unit_value;
+}
+
+AxValue *ax_str(const char *data, size_t len) {
+    AxValue *v = ax_new(AX_STR);
+    v->as.str.data = ax_alloc(len + 1);
+    memcpy(v->as.str.data, data, len);
+    v->as.str.data[len] = '\0';
+    v->as.str.len = len;
+    return v;
+}
+
+static AxValue *ax_agg(AxKind kind, const char *tag, size_t n, va_list ap) {
+    AxValue *v = ax_new(kind);
+    size_t i;
+    v->as.agg.tag = tag;
+    v->as.agg.len = n;
+    v->as.agg.items = ax_alloc(n * sizeof(AxValue *));
+    for (i = 0; i < n; i++) {
+        v->as.agg.items[i] = va_arg(ap, AxValue *);
+    }
+    return v;
+}
+
+AxValue *ax_tuple(size_t n, ...) {
+    AxValue *v;
+    va_list ap;
+    va_start(ap, n);
+    v = ax_agg(AX_TUPLE, NULL, n, ap);
+    va_end(ap);
+    return v;
+}
+
+AxValue *ax_ctor(const char *tag, size_t n, ...) {
+    AxValue *v;
+    va_list ap;
+    va_start(ap, n);
+    v = ax_agg(AX_CTOR, tag, n, ap);
+    va_end(ap);
+    return v;
+}
+
+/* ========================================================================
+ * Access and tests
+ * ======================================================================== */
+
+int ax_test(AxValue *v) {
+    int result;
+    switch (v->kind) {
+    case AX_BOOL: result = v->as.b; break;
+    case AX_INT: result = v->as.i != 0; break;
+    case AX_STR: result = v->as.str.len != 0; break;
+    case AX_UNIT: result = 0; break;
+    case AX_TUPLE: result = v->as.agg.len != 0; break;
+    case AX_BUILDER: result = v->as.sb.len != 0; break;
+    default: result = 1; break;
+    }
+    ax_release(v);
+    return result;
+}
+
+const AxValue *ax_item(const AxValue *v, size_t index) {
+    if (v == NULL || (v->kind != AX_TUPLE && v->kind != AX_CTOR) || index >= v->as.agg.len) {
+        return NULL;
+    }
+    return v->as.agg.items[index];
+}
+
+static AxValue *ax_field(AxValue *v, AxKind kind, size_t index) {
+    AxValue *field = &ax_unit_value;
+    if (v->kind == kind && index < v->as.agg.len) {
+        field = ax_retain(v->as.agg.items[index]);
+    }
+    ax_release(v);
+    return field;
+}
+
+AxValue *ax_tuple_field(AxValue *v, size_t index) {
+    return ax_field(v, AX_TUPLE, index);
+}
+
+AxValue *ax_ctor_field(AxValue *v, size_t index) {
+    return ax_field(v, AX_CTOR, index);
+}
+
+AxValue *ax_arg(const AxValue *args, size_t index) {
+    if (args->kind == AX_TUPLE && index < args->as.agg.len) {
+        return ax_retain(args->as.agg.items[index]);
+    }
+    return &ax_unit_value;
+}
+
+int ax_is_int(const AxValue *v, int64_t i) {
+    return v != NULL && v->kind == AX_INT && v->as.i == i;
+}
+
+int ax_is_bool(const AxValue *v, int b) {
+    return v != NULL && v->kind == AX_BOOL && v->as.b == b;
+}
+
+int ax_is_unit(const AxValue *v) {
+    return v != NULL && v->kind == AX_UNIT;
+}
+
+int ax_is_tuple(const AxValue *v, size_t n) {
+    return v != NULL && v->kind == AX_TUPLE && v->as.agg.len == n;
+}
+
+int ax_is_ctor(const AxValue *v, const char *tag, size_t n) {
+    return v != NULL && v->kind == AX_CTOR && v->as.agg.len == n && strcmp(v->as.agg.tag, tag) == 0;
+}
+
+static int ax_equal(const AxValue *a, const AxValue *b) {
+    size_t i;
+    if (a == b) {
+        return 1;
+    }
+    if (a->kind != b->kind) {
+        return 0;
+    }
+    switch (a->kind) {
+    case AX_INT: return a->as.i == b->as.i;
+    case AX_BOOL: return a->as.b == b->as.b;
+    case AX_UNIT: return 1;
+    case AX_STR:
+        return a->as.str.len == b->as.str.len && memcmp(a->as.str.data, b->as.str.data, a->as.str.len) == 0;
+    case AX_BUILDER:
+        return a->as.sb.len == b->as.sb.len && (a->as.sb.len == 0 || memcmp(a->as.sb.data, b->as.sb.data, a->as.sb.len) == 0);
+    default:
+        if (a->as.agg.len != b->as.agg.len) {
+            return 0;
+        }
+        if (a->kind == AX_CTOR && strcmp(a->as.agg.tag, b->as.agg.tag) != 0) {
+            return 0;
+        }
+        for (i = 0; i < a->as.agg.len; i++) {
+            if (!ax_equal(a->as.agg.items[i], b->as.agg.items[i])) {
+                return 0;
+            }
+        }
+        return 1;
+    }
+}
+
+/* Ordering of values, as runtime/order.rs: by kind, then by contents */
+static int ax_kind_rank(AxKind kind) {
+    switch (kind) {
+    case AX_UNIT: return 0;
+    case AX_BOOL: return 1;
+    case AX_INT: return 2;
+    case AX_STR: return 3;
+    case AX_TUPLE: return 5;
+    case AX_CTOR: return 7;
+    default: return 8; /* AX_BUILDER */
+    }
+}
+
+static int ax_compare_bytes(const char *a, size_t alen, const char *b, size_t blen) {
+    size_t n = alen < blen ? alen : blen;
+    int r = n == 0 ? 0 : memcmp(a, b, n);
+    if (r != 0) {
+        return r < 0 ? -1 : 1;
+    }
+    return (alen > blen) - (alen < blen);
+}
+
+static int ax_compare(const AxValue *a, const AxValue *b) {
+    size_t i, n;
+    int r;
+    if (a == b) {
+        return 0;
+    }
+    if (a->kind != b->kind) {
+        return ax_kind_rank(a->kind) < ax_kind_rank(b->kind) ? -1 : 1;
+    }
+    switch (a->kind) {
+    case AX_INT: return (a->as.i > b->as.i) - (a->as.i < b->as.i);
+    case AX_BOOL: return (a->as.b != 0) - (b->as.b != 0);
+    case AX_UNIT: return 0;
+    case AX_STR: return ax_compare_bytes(a->as.str.data, a->as.str.len, b->as.str.data, b->as.str.len);
+    case AX_BUILDER: return ax_compare_bytes(a->as.sb.data, a->as.sb.len, b->as.sb.data, b->as.sb.len);
+    default:
+        if (a->kind == AX_CTOR) {
+            /* Nil first, so Nil/Cons lists compare lexicographically */
+            int a_nil = strcmp(a->as.agg.tag, "Nil") == 0, b_nil = strcmp(b->as.agg.tag, "Nil") == 0;
+            if (a_nil != b_nil) {
+                return a_nil ? -1 : 1;
+            }
+            r = strcmp(a->as.agg.tag, b->as.agg.tag);
+            if (r != 0) {
+                return r < 0 ? -1 : 1;
+            }
+        }
+        n = a->as.agg.len < b->as.agg.len ? a->as.agg.len : b->as.agg.len;
+        for (i = 0; i < n; i++) {
+            r = ax_compare(a->as.agg.items[i], b->as.agg.items[i]);
+            if (r != 0) {
+                return r;
+            }
+        }
+        return (a->as.agg.len > b->as.agg.len) - (a->as.agg.len < b->as.agg.len);
+    }
+}
+
+/* ========================================================================
+ * Formatting (Display, as in runtime/value.rs)
+ * ======================================================================== */
+
+typedef struct {
+    char *data;
+    size_t len, cap;
+} AxBuf;
+
+static void ax_buf_put(AxBuf *buf, const char *data, size_t len) {
+    if (buf->len + len + 1 > buf->cap) {
+        buf->cap = (buf->len + len + 1) * 2;
+        buf->data = realloc(buf->data, buf->cap);
+        if (buf->data == NULL) {
+            ax_runtime_error("out of memory", NULL, 0, 0);
+        }
+    }
+    memcpy(buf->data + buf->len, data, len);
+    buf->len += len;
+    buf->data[buf->len] = '\0';
+}
+
+static void ax_buf_str(AxBuf *buf, const char *s) {
+    ax_buf_put(buf, s, strlen(s));
+}
+
+/* The program's constructors (ax_register_tags) */
+static const char *const *ax_program_tags = NULL;
+
+void ax_register_tags(const char *const *names) {
+    ax_program_tags = names;
+}
+
+static const char *ax_short_tag(const char *tag) {
+    const char *dot = strrchr(tag, '.');
+    return dot != NULL ? dot + 1 : tag;
+}
+
+/* The runtime's own constructors, runtime/value.rs WELL_KNOWN_TAGS */
+static const char *const ax_runtime_tags[] = {"Nil", "Cons", "None", "Some", "Ok", "Err", "ProcResult", NULL};
+
+/* Another of `tags` has the short name `short_name` */
+static int ax_shares_short_tag(const char *const *tags, const char *tag, const char *short_name) {
+    size_t i;
+    for (i = 0; tags != NULL && tags[i] != NULL; i++) {
+        if (strcmp(tags[i], tag) != 0 && strcmp(ax_short_tag(tags[i]), short_name) == 0) {
+            return 1;
+        }
+    }
+    return 0;
+}
+
+/* The name a constructor prints as: `Circle` for `geo.Shape.Circle`, unless
+ * another constructor of the program or the runtime is also `Circle` (see
+ * runtime/value.rs tag_display_name) */
+static const char *ax_tag_display(const char *tag) {
+    const char *short_name = ax_short_tag(tag);
+    if (ax_shares_short_tag(ax_program_tags, tag, short_name) || ax_shares_short_tag(ax_runtime_tags, tag, short_name)) {
+        return tag;
+    }
+    return short_name;
+}
+
+static void ax_format(AxBuf *buf, const AxValue *v) {
+    char num[32];
+    size_t i;
+    switch (v->kind) {
+    case AX_INT:
+        sprintf(num, "%" PRId64, v->as.i);
+        ax_buf_str(buf, num);
+        break;
+    case AX_BOOL: ax_buf_str(buf, v->as.b ? "true" : "false"); break;
+    case AX_UNIT: ax_buf_str(buf, "()"); break;
+    case AX_STR: ax_buf_put(buf, v->as.str.data, v->as.str.len); break;
+    case AX_BUILDER:
+        sprintf(num, "<builder:%zu>", v->as.sb.len);
+        ax_buf_str(buf, num);
+        break;
+    default:
+        if (v->kind == AX_CTOR) {
+            ax_buf_str(buf, ax_tag_display(v->as.agg.tag));
+        }
+        ax_buf_str(buf, "(");
+        for (i = 0; i < v->as.agg.len; i++) {
+            if (i > 0) {
+                ax_buf_str(buf, ", ");
+            }
+            ax_format(buf, v->as.agg.items[i]);
+        }
+        ax_buf_str(buf, ")");
+        break;
+    }
+}
+
+static void ax_write(FILE *out, const AxValue *v) {
+    AxBuf buf = { NULL, 0, 0 };
+    ax_format(&buf, v);
+    if (buf.len > 0) {
+        fwrite(buf.data, 1, buf.len, out);
+    }
+    free(buf.data);
+}
+
+/* ========================================================================
+ * Primitives
+ * ======================================================================== */
+
+/* The two operands of a binary primitive, borrowed from args; NULL when
+ * args is not a tuple of at least two */
+static int ax_operands(const AxValue *args, const AxValue **a, const AxValue **b) {
+    *a = ax_item(args, 0);
+    *b = ax_item(args, 1);
+    return args->kind == AX_TUPLE && *a != NULL && *b != NULL;
+}
+
+static int ax_int_operands(const AxValue *args, int64_t *x, int64_t *y) {
+    const AxValue *a, *b;
+    if (!ax_operands(args, &a, &b) || a->kind != AX_INT || b->kind != AX_INT) {
+        return 0;
+    }
+    *x = a->as.i;
+    *y = b->as.i;
+    return 1;
+}
+
+/* Wrapping arithmetic, as Rust's wrapping_add/sub/mul */
+#define AX_WRAP(x, op, y) ((int64_t)((uint64_t)(x) op (uint64_t)(y)))
+
+AxValue *ax_prim_add(AxValue *args) {
+    int64_t x, y, r = 0;
+    if (ax_int_operands(args, &x, &y)) r = AX_WRAP(x, +, y);
+    ax_release(args);
+    return ax_int(r);
+}
+
+AxValue *ax_prim_sub(AxValue *args) {
+    int64_t x, y, r = 0;
+    if (ax_int_operands(args, &x, &y)) r = AX_WRAP(x, -, y);
+    ax_release(args);
+    return ax_int(r);
+}
+
+AxValue *ax_prim_mul(AxValue *args) {
+    int64_t x, y, r = 0;
+    if (ax_int_operands(args, &x, &y)) r = AX_WRAP(x, *, y);
+    ax_release(args);
+    return ax_int(r);
+}
+
+AxValue *ax_prim_div(AxValue *args) {
+    int64_t x, y, r = 0;
+    if (ax_int_operands(args, &x, &y) && y != 0) {
+        if (x == INT64_MIN && y == -1) {
+            ax_runtime_error("attempt to divide with overflow", NULL, 0, 0);
+        }
+        r = x / y;
+    }
+    ax_release(args);
+    return ax_int(r);
+}
+
+AxValue *ax_prim_mod(AxValue *args) {
+    int64_t x, y, r = 0;
+    if (ax_int_operands(args, &x, &y) && y != 0) {
+        if (x == INT64_MIN && y == -1) {
+            ax_runtime_error("attempt to calculate the remainder with overflow", NULL, 0, 0);
+        }
+        r = x % y;
+    }
+    ax_release(args);
+    return ax_int(r);
+}
+
+AxValue *ax_prim_eq(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && ax_equal(a, b);
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_neq(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && !ax_equal(a, b);
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_lt(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && ax_compare(a, b) < 0;
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_lte(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && ax_compare(a, b) <= 0;
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_gt(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && ax_compare(a, b) > 0;
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_gte(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && ax_compare(a, b) >= 0;
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_value_cmp(AxValue *args) {
+    const AxValue *a, *b;
+    int r;
+    if (!ax_operands(args, &a, &b)) {
+        ax_runtime_error("value_cmp expects (a, b)", NULL, 0, 0);
+    }
+    r = ax_compare(a, b);
+    ax_release(args);
+    return ax_int(r);
+}
+
+static int ax_truthy(const AxValue *v) {
+    return ax_test(ax_retain((AxValue *)v));
+}
+
+AxValue *ax_prim_and(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && ax_truthy(a) && ax_truthy(b);
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_or(AxValue *args) {
+    const AxValue *a, *b;
+    int r = ax_operands(args, &a, &b) && (ax_truthy(a) || ax_truthy(b));
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_not(AxValue *v) {
+    return ax_bool(!ax_test(v));
+}
+
+AxValue *ax_prim_str_concat(AxValue *args) {
+    const AxValue *a, *b;
+    AxValue *r;
+    if (!ax_operands(args, &a, &b) || a->kind != AX_STR || b->kind != AX_STR) {
+        ax_runtime_error("str_concat: arguments must be strings", NULL, 0, 0);
+    }
+    r = ax_new(AX_STR);
+    r->as.str.len = a->as.str.len + b->as.str.len;
+    r->as.str.data = ax_alloc(r->as.str.len + 1);
+    memcpy(r->as.str.data, a->as.str.data, a->as.str.len);
+    memcpy(r->as.str.data + a->as.str.len, b->as.str.data, b->as.str.len);
+    r->as.str.data[r->as.str.len] = '\0';
+    ax_release(args);
+    return r;
+}
+
+/* Length in characters (UTF-8 code points), as shim::str_len */
+AxValue *ax_prim_str_len(AxValue *v) {
+    int64_t count = 0;
+    size_t i;
+    if (v->kind != AX_STR) {
+        ax_runtime_error("str_len: argument must be a string", NULL, 0, 0);
+    }
+    for (i = 0; i < v->as.str.len; i++) {
+        if (((unsigned char)v->as.str.data[i] & 0xC0) != 0x80) {
+            count++;
+        }
+    }
+    ax_release(v);
+    return ax_int(count);
+}
+
+/* Byte range [start, end), clamped to the string */
+AxValue *ax_prim_str_slice(AxValue *args) {
+    const AxValue *s = ax_item(args, 0), *from = ax_item(args, 1), *to = ax_item(args, 2);
+    AxValue *r;
+    int64_t len, start, end;
+    if (args->kind != AX_TUPLE || s == NULL || from == NULL || to == NULL
+        || s->kind != AX_STR || from->kind != AX_INT || to->kind != AX_INT) {
+        ax_release(args);
+        return ax_str("", 0);
+    }
+    len = (int64_t)s->as.str.len;
+    start = from->as.i < len ? from->as.i : len;
+    end = to->as.i < len ? to->as.i : len;
+    if (start < 0 || end < start) {
+        ax_runtime_error("str_slice: invalid range", NULL, 0, 0);
+    }
+    r = ax_str(s->as.str.data + start, (size_t)(end - start));
+    ax_release(args);
+    return r;
+}
+
+AxValue *ax_prim_int_to_str(AxValue *v) {
+    char num[32];
+    if (v->kind != AX_INT) {
+        ax_release(v);
+        return ax_str("<not-an-int>", 12);
+    }
+    sprintf(num, "%" PRId64, v->as.i);
+    ax_release(v);
+    return ax_str(num, strlen(num));
+}
+
+/* Strict decimal parse (optional sign, digits only); anything else is 0 */
+AxValue *ax_prim_str_to_int(AxValue *v) {
+    uint64_t magnitude = 0;
+    uint64_t limit;
+    size_t i = 0;
+    int negative = 0, ok;
+    if (v->kind != AX_STR) {
+        ax_release(v);
+        return ax_int(0);
+    }
+    if (i < v->as.str.len && (v->as.str.data[i] == '-' || v->as.str.data[i] == '+')) {
+        negative = v->as.str.data[i] == '-';
+        i++;
+    }
+    limit = negative ? (uint64_t)INT64_MAX + 1 : (uint64_t)INT64_MAX;
+    ok = i < v->as.str.len;
+    for (; ok && i < v->as.str.len; i++) {
+        char c = v->as.str.data[i];
+        if (c < '0' || c > '9' || magnitude > (limit - (uint64_t)(c - '0')) / 10) {
+            ok = 0;
+        } else {
+            magnitude = magnitude * 10 + (uint64_t)(c - '0');
+        }
+    }
+    ax_release(v);
+    if (!ok) {
+        return ax_int(0);
+    }
+    return ax_int(negative ? (int64_t)(0 - magnitude) : (int64_t)magnitude);
+}
+
+/* String utilities (runtime/strings.rs). Case mapping and trimming only
+ * know ASCII letters and whitespace. */
+
+/* The text of operand `index` of a string primitive */
+static const AxValue *ax_str_operand(const AxValue *args, size_t index, const char *what) {
+    const AxValue *s = args->kind == AX_TUPLE ? ax_item(args, index) : NULL;
+    if (s == NULL || s->kind != AX_STR) {
+        ax_runtime_error(what, NULL, 0, 0);
+    }
+    return s;
+}
+
+/* Offset of the first needle in hay at or after from; hlen when none */
+static size_t ax_find(const AxValue *hay, size_t from, const AxValue *needle) {
+    size_t i;
+    for (i = from; i + needle->as.str.len <= hay->as.str.len; i++) {
+        if (memcmp(hay->as.str.data + i, needle->as.str.data, needle->as.str.len) == 0) {
+            return i;
+        }
+    }
+    return hay->as.str.len;
+}
+
+/* The Nil/Cons list of parts[0..n), which it takes ownership of */
+static AxValue *ax_list_of(AxValue **parts, size_t n) {
+    AxValue *list = ax_ctor("Nil", 0);
+    while (n > 0) {
+        n--;
+        list = ax_ctor("Cons", 2, parts[n], list);
+    }
+    return list;
+}
+
+AxValue *ax_prim_str_split(AxValue *args) {
+    const AxValue *s = ax_str_operand(args, 0, "str_split expects (Str, Str)");
+    const AxValue *sep = ax_str_operand(args, 1, "str_split expects (Str, Str)");
+    AxValue **parts = ax_alloc((s->as.str.len + 1) * sizeof(AxValue *));
+    AxValue *list;
+    size_t n = 0, start = 0, end;
+    if (sep->as.str.len == 0) {
+        /* Each character: a lead byte and its continuation bytes */
+        for (end = 1; start < s->as.str.len; end++) {
+            if (end == s->as.str.len || ((unsigned char)s->as.str.data[end] & 0xC0) != 0x80) {
+                parts[n++] = ax_str(s->as.str.data + start, end - start);
+                start = end;
+            }
+        }
+    } else {
+        for (;;) {
+            end = ax_find(s, start, sep);
+            parts[n++] = ax_str(s->as.str.data + start, end - start);
+            if (end == s->as.str.len) {
+                break;
+            }
+            start = end + sep->as.str.len;
+        }
+    }
+    list = ax_list_of(parts, n);
+    free(parts);
+    ax_release(args);
+    return list;
+}
+
+static int ax_is_space(char c) {
+    return c == ' ' || c == '\t' || c == '\n' || c == '\v' || c == '\f' || c == '\r';
+}
+
+AxValue *ax_prim_str_trim(AxValue *v) {
+    size_t start = 0, end;
+    AxValue *r;
+    if (v->kind != AX_STR) {
+        ax_runtime_error("str_trim: argument must be a string", NULL, 0, 0);
+    }
+    end = v->as.str.len;
+    while (start < end && ax_is_space(v->as.str.data[start])) {
+        start++;
+    }
+    while (end > start && ax_is_space(v->as.str.data[end - 1])) {
+        end--;
+    }
+    r = ax_str(v->as.str.data + start, end - start);
+    ax_release(v);
+    return r;
+}
+
+AxValue *ax_prim_str_contains(AxValue *args) {
+    const AxValue *s = ax_str_operand(args, 0, "str_contains expects (Str, Str)");
+    const AxValue *needle = ax_str_operand(args, 1, "str_contains expects (Str, Str)");
+    int r = needle->as.str.len <= s->as.str.len && (needle->as.str.len == 0 || ax_find(s, 0, needle) < s->as.str.len);
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_str_starts_with(AxValue *args) {
+    const AxValue *s = ax_str_operand(args, 0, "str_starts_with expects (Str, Str)");
+    const AxValue *prefix = ax_str_operand(args, 1, "str_starts_with expects (Str, Str)");
+    int r = prefix->as.str.len <= s->as.str.len
+        && memcmp(s->as.str.data, prefix->as.str.data, prefix->as.str.len) == 0;
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_str_ends_with(AxValue *args) {
+    const AxValue *s = ax_str_operand(args, 0, "str_ends_with expects (Str, Str)");
+    const AxValue *suffix = ax_str_operand(args, 1, "str_ends_with expects (Str, Str)");
+    int r = suffix->as.str.len <= s->as.str.len
+        && memcmp(s->as.str.data + s->as.str.len - suffix->as.str.len, suffix->as.str.data, suffix->as.str.len) == 0;
+    ax_release(args);
+    return ax_bool(r);
+}
+
+AxValue *ax_prim_str_replace(AxValue *args) {
+    const AxValue *s = ax_str_operand(args, 0, "str_replace expects (Str, Str, Str)");
+    const AxValue *from = ax_str_operand(args, 1, "str_replace expects (Str, Str, Str)");
+    const AxValue *to = ax_str_operand(args, 2, "str_replace expects (Str, Str, Str)");
+    AxBuf buf = { NULL, 0, 0 };
+    AxValue *r;
+    size_t start = 0, end;
+    if (from->as.str.len == 0) {
+        r = ax_str(s->as.str.data, s->as.str.len);
+        ax_release(args);
+        return r;
+    }
+    ax_buf_put(&buf, "", 0);
+    for (;;) {
+        end = ax_find(s, start, from);
+        ax_buf_put(&buf, s->as.str.data + start, end - start);
+        if (end == s->as.str.len) {
+            break;
+        }
+        ax_buf_put(&buf, to->as.str.data, to->as.str.len);
+        start = end + from->as.str.len;
+    }
+    r = ax_str(buf.data, buf.len);
+    free(buf.data);
+    ax_release(args);
+    return r;
+}
+
+static AxValue *ax_map_case(AxValue *v, int upper, const char *what) {
+    AxValue *r;
+    size_t i;
+    if (v->kind != AX_STR) {
+        ax_runtime_error(what, NULL, 0, 0);
+    }
+    r = ax_str(v->as.str.data, v->as.str.len);
+    for (i = 0; i < r->as.str.len; i++) {
+        char c = r->as.str.data[i];
+        if (upper && c >= 'a' && c <= 'z') {
+            r->as.str.data[i] = (char)(c - 'a' + 'A');
+        } else if (!upper && c >= 'A' && c <= 'Z') {
+            r->as.str.data[i] = (char)(c - 'A' + 'a');
+        }
+    }
+    ax_release(v);
+    return r;
+}
+
+AxValue *ax_prim_str_to_lower(AxValue *v) {
+    return ax_map_case(v, 0, "str_to_lower: argument must be a string");
+}
+
+AxValue *ax_prim_str_to_upper(AxValue *v) {
+    return ax_map_case(v, 1, "str_to_upper: argument must be a string");
+}
+
+AxValue *ax_prim_str_join(AxValue *args) {
+    const AxValue *list = args->kind == AX_TUPLE ? ax_item(args, 0) : NULL;
+    const AxValue *sep = ax_str_operand(args, 1, "str_join expects (List[Str], Str)");
+    const AxValue *part;
+    AxBuf buf = { NULL, 0, 0 };
+    AxValue *r;
+    int first = 1;
+    ax_buf_put(&buf, "", 0);
+    while (ax_is_ctor(list, "Cons", 2)) {
+        part = ax_item(list, 0);
+        if (part->kind != AX_STR) {
+            ax_runtime_error("str_join: parts must be strings", NULL, 0, 0);
+        }
+        if (!first) {
+            ax_buf_put(&buf, sep->as.str.data, sep->as.str.len);
+        }
+        ax_buf_put(&buf, part->as.str.data, part->as.str.len);
+        first = 0;
+        list = ax_item(list, 1);
+    }
+    r = ax_str(buf.data, buf.len);
+    free(buf.data);
+    ax_release(args);
+    return r;
+}
+
+/* Character primitives (runtime/chars.rs): a character is an Int code
+ * point or a one-character Str. Classification and case mapping only
+ * know ASCII. */
+
+/* The code point at data[0..len) and its byte length (1 for bad UTF-8) */
+static size_t ax_utf8_decode(const char *data, size_t len, int64_t *cp) {
+    unsigned char lead = (unsigned char)data[0];
+    size_t n = lead < 0x80 ? 1 : lead >= 0xF0 ? 4 : lead >= 0xE0 ? 3 : lead >= 0xC0 ? 2 : 1;
+    size_t i;
+    if (n > len) {
+        n = 1;
+    }
+    *cp = n == 1 ? lead : n == 2 ? (lead & 0x1F) : n == 3 ? (lead & 0x0F) : (lead & 0x07);
+    for (i = 1; i < n; i++) {
+        *cp = (*cp << 6) | ((unsigned char)data[i] & 0x3F);
+    }
+    return n;
+}
+
+/* The code point of a character argument, which it releases */
+static int64_t ax_char_operand(AxValue *v, const char *what) {
+    int64_t cp = -1;
+    if (v->kind == AX_INT) {
+        cp = v->as.i;
+    } else if (v->kind != AX_STR || v->as.str.len == 0
+        || ax_utf8_decode(v->as.str.data, v->as.str.len, &cp) != v->as.str.len) {
+        ax_runtime_error(what, NULL, 0, 0);
+    }
+    ax_release(v);
+    return cp;
+}
+
+AxValue *ax_prim_char_is_alpha(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_is_alpha: argument must be a character");
+    return ax_bool((c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z'));
+}
+
+AxValue *ax_prim_char_is_digit(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_is_digit: argument must be a character");
+    return ax_bool(c >= '0' && c <= '9');
+}
+
+AxValue *ax_prim_char_is_alnum(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_is_alnum: argument must be a character");
+    return ax_bool((c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9'));
+}
+
+AxValue *ax_prim_char_is_whitespace(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_is_whitespace: argument must be a character");
+    return ax_bool(c >= 0 && c < 0x80 && ax_is_space((char)c));
+}
+
+AxValue *ax_prim_char_is_ident_start(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_is_ident_start: argument must be a character");
+    return ax_bool((c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_');
+}
+
+AxValue *ax_prim_char_is_ident_char(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_is_ident_char: argument must be a character");
+    return ax_bool((c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9') || c == '_');
+}
+
+AxValue *ax_prim_char_to_digit(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_to_digit: argument must be a character");
+    return ax_int(c >= '0' && c <= '9' ? c - '0' : -1);
+}
+
+AxValue *ax_prim_char_to_lower(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_to_lower: argument must be a character");
+    return ax_int(c >= 'A' && c <= 'Z' ? c - 'A' + 'a' : c);
+}
+
+AxValue *ax_prim_char_to_upper(AxValue *v) {
+    int64_t c = ax_char_operand(v, "char_to_upper: argument must be a character");
+    return ax_int(c >= 'a' && c <= 'z' ? c - 'a' + 'A' : c);
+}
+
+AxValue *ax_prim_char_from_str(AxValue *v) {
+    int64_t cp = -1;
+    if (v->kind != AX_STR) {
+        ax_runtime_error("char_from_str: argument must be a string", NULL, 0, 0);
+    }
+    if (v->as.str.len > 0) {
+        ax_utf8_decode(v->as.str.data, v->as.str.len, &cp);
+    }
+    ax_release(v);
+    return ax_int(cp);
+}
+
+/* shim::char_to_str: a Str is itself, a surrogate U+FFFD, and anything
+ * else that is no code point "" */
+AxValue *ax_prim_char_to_str(AxValue *v) {
+    unsigned char out[4];
+    size_t n;
+    int64_t cp;
+    if (v->kind == AX_STR) {
+        return v;
+    }
+    cp = v->kind == AX_INT ? v->as.i : -1;
+    ax_release(v);
+    if (cp >= 0xD800 && cp <= 0xDFFF) {
+        cp = 0xFFFD;
+    }
+    if (cp < 0 || cp > 0x10FFFF) {
+        return ax_str("", 0);
+    }
+    if (cp < 0x80) {
+        out[0] = (unsigned char)cp;
+        n = 1;
+    } else if (cp < 0x800) {
+        out[0] = (unsigned char)(0xC0 | (cp >> 6));
+        out[1] = (unsigned char)(0x80 | (cp & 0x3F));
+        n = 2;
+    } else if (cp < 0x10000) {
+        out[0] = (unsigned char)(0xE0 | (cp >> 12));
+        out[1] = (unsigned char)(0x80 | ((cp >> 6) & 0x3F));
+        out[2] = (unsigned char)(0x80 | (cp & 0x3F));
+        n = 3;
+    } else {
+        out[0] = (unsigned char)(0xF0 | (cp >> 18));
+        out[1] = (unsigned char)(0x80 | ((cp >> 12) & 0x3F));
+        out[2] = (unsigned char)(0x80 | ((cp >> 6) & 0x3F));
+        out[3] = (unsigned char)(0x80 | (cp & 0x3F));
+        n = 4;
+    }
+    return ax_str((const char *)out, n);
+}
+
+/* String builder (shim::sb_*): copies share one buffer, so a builder is
+ * used linearly; sb_push returns the builder it was given */
+AxValue *ax_prim_sb_new(AxValue *unit) {
+    AxValue *v = ax_new(AX_BUILDER);
+    v->as.sb.len = 0;
+    v->as.sb.cap = 0;
+    v->as.sb.data = NULL;
+    ax_release(unit);
+    return v;
+}
+
+static AxValue *ax_sb_append(AxValue *args, const char *data, size_t len) {
+    AxValue *b = ax_arg(args, 0);
+    if (b->kind != AX_BUILDER) {
+        ax_runtime_error("sb_push: first argument must be a builder", NULL, 0, 0);
+    }
+    if (b->as.sb.len + len > b->as.sb.cap) {
+        b->as.sb.cap = (b->as.sb.len + len) * 2;
+        b->as.sb.data = realloc(b->as.sb.data, b->as.sb.cap);
+        if (b->as.sb.data == NULL) {
+            ax_runtime_error("out of memory", NULL, 0, 0);
+        }
+    }
+    memcpy(b->as.sb.data + b->as.sb.len, data, len);
+    b->as.sb.len += len;
+    return b;
+}
+
+AxValue *ax_prim_sb_push(AxValue *args) {
+    const AxValue *s = ax_item(args, 1);
+    AxValue *b;
+    if (s == NULL || s->kind != AX_STR) {
+        ax_runtime_error("sb_push expects (builder, Str)", NULL, 0, 0);
+    }
+    b = ax_sb_append(args, s->as.str.data, s->as.str.len);
+    ax_release(args);
+    return b;
+}
+
+AxValue *ax_prim_sb_push_int(AxValue *args) {
+    const AxValue *n = ax_item(args, 1);
+    char num[32];
+    AxValue *b;
+    if (n == NULL || n->kind != AX_INT) {
+        ax_runtime_error("sb_push_int expects (builder, Int)", NULL, 0, 0);
+    }
+    sprintf(num, "%" PRId64, n->as.i);
+    b = ax_sb_append(args, num, strlen(num));
+    ax_release(args);
+    return b;
+}
+
+AxValue *ax_prim_sb_build(AxValue *v) {
+    AxValue *r;
+    if (v->kind != AX_BUILDER) {
+        ax_runtime_error("sb_build: argument must be a builder", NULL, 0, 0);
+    }
+    r = ax_str(v->as.sb.len == 0 ? "" : v->as.sb.data, v->as.sb.len);
+    ax_release(v);
+    return r;
+}
+
+AxValue *ax_prim_tuple(AxValue *args) {
+    if (args->kind == AX_TUPLE) {
+        return args;
+    }
+    return ax_tuple(1, args);
+}
+
+static AxValue *ax_prim_field(AxValue *args, AxKind kind) {
+    const AxValue *data = ax_item(args, 0), *index = ax_item(args, 1);
+    AxValue *r = &ax_unit_value;
+    if (args->kind == AX_TUPLE && data != NULL && index != NULL && index->kind == AX_INT && index->as.i >= 0) {
+        const AxValue *field = data->kind == kind ? ax_item(data, (size_t)index->as.i) : NULL;
+        if (field != NULL) {
+            r = ax_retain((AxValue *)field);
+        }
+    }
+    ax_release(args);
+    return r;
+}
+
+AxValue *ax_prim_tuple_field(AxValue *args) {
+    return ax_prim_field(args, AX_TUPLE);
+}
+
+AxValue *ax_prim_ctor_field(AxValue *args) {
+    return ax_prim_field(args, AX_CTOR);
+}
+
+#ifndef AX_NO_IO
+AxValue *ax_prim_io_print(AxValue *v) {
+    ax_write(stdout, v);
+    fflush(stdout);
+    ax_release(v);
+    return &ax_unit_value;
+}
+
+/* stdio holds these until io_flush, the next io_print, a full buffer or
+ * exit */
+AxValue *ax_prim_io_print_buffered(AxValue *v) {
+    ax_write(stdout, v);
+    ax_release(v);
+    return &ax_unit_value;
+}
+
+AxValue *ax_prim_io_println(AxValue *v) {
+    ax_write(stdout, v);
+    fputc('\n', stdout);
+    ax_release(v);
+    return &ax_unit_value;
+}
+
+AxValue *ax_prim_io_flush(AxValue *unit) {
+    fflush(stdout);
+    ax_release(unit);
+    return &ax_unit_value;
+}
+
+AxValue *ax_prim_io_eprint(AxValue *v) {
+    ax_write(stderr, v);
+    fflush(stderr);
+    ax_release(v);
+    return &ax_unit_value;
+}
+#endif
+
+AxValue *ax_prim_debug_trace(AxValue *v) {
+    const char *trace = getenv("AXIS_TRACE");
+    if (trace != NULL && strcmp(trace, "1") == 0) {
+        ax_write(stderr, v);
+        fputc('\n', stderr);
+    }
+    ax_release(v);
+    return &ax_unit_value;
+}
+
+#ifndef AX_NO_PROC
+AxValue *ax_prim_proc_exit(AxValue *v) {
+    int code = v->kind == AX_INT ? (int)v->as.i : 1;
+    fflush(stdout);
+    exit(code);
+}
+#endif
+
+/* ========================================================================
+ * Assertions (recorded, not fatal; see runtime/error.rs)
+ * ======================================================================== */
+
+static void ax_assertion_failure(const char *msg, const char **labels, const AxValue **values, size_t n,
+                                 const char *file, int line, int column) {
+    size_t i, width = 0;
+    ax_assertion_failures++;
+    fflush(stdout);
+    fprintf(stderr, "error[assertion]: %s", msg);
+    ax_report_location(file, line, column);
+    for (i = 0; i < n; i++) {
+        if (strlen(labels[i]) > width) {
+            width = strlen(labels[i]);
+        }
+    }
+    for (i = 0; i < n; i++) {
+        fprintf(stderr, "\n  %*s: ", (int)width, labels[i]);
+        ax_write(stderr, values[i]);
+    }
+    fputc('\n', stderr);
+}
+
+AxValue *ax_prim_assert_eq(AxValue *args, const char *file, int line, int column) {
+    const char *labels[2] = { "left", "right" };
+    const AxValue *values[2];
+    if (!ax_is_tuple(args, 2)) {
+        ax_runtime_error("assert_eq expects (left, right)", file, line, column);
+    }
+    values[0] = ax_item(args, 0);
+    values[1] = ax_item(args, 1);
+    if (!ax_equal(values[0], values[1])) {
+        ax_assertion_failure("assertion failed: left == right", labels, values, 2, file, line, column);
+    }
+    ax_release(args);
+    return &ax_unit_value;
+}
+
+AxValue *ax_prim_assert_true(AxValue *v, const char *file, int line, int column) {
+    const char *labels[1] = { "got" };
+    const AxValue *values[1];
+    values[0] = v;
+    if (!ax_is_bool(v, 1)) {
+        ax_assertion_failure("assertion failed: expected true", labels, values, 1, file, line, column);
+    }
+    ax_release(v);
+    return &ax_unit_value;
+}
+
+AxValue *ax_prim_fail(AxValue *msg, const char *file, int line, int column) {
+    AxBuf buf = { NULL, 0, 0 };
+    ax_buf_str(&buf, "failed: ");
+    ax_format(&buf, msg);
+    ax_assertion_failure(buf.data, NULL, NULL, 0, file, line, column);
+    free(buf.data);
+    ax_release(msg);
+    return &ax_unit_value;
+}
+
+/* ========================================================================
+ * Program entry
+ * ======================================================================== */
+
+int ax_main(int argc, char **argv, AxEntry entry) {
+    AxValue *args = ax_ctor("Nil", 0);
+    AxValue *result;
+    int i, code = 0;
+    for (i = argc - 1; i >= 1; i--) {
+        args = ax_ctor("Cons", 2, ax_str(argv[i], strlen(argv[i])), args);
+    }
+    result = entry(args);
+    fflush(stdout);
+    /* The exit code of the result, as runtime/entry.rs */
+    if (ax_assertion_failures > 0) {
+        code = AX_ASSERTION_FAILED_EXIT_CODE;
+    } else if (result->kind == AX_INT) {
+        code = (int)result->as.i;
+    } else if (ax_is_ctor(result, "Err", 1)) {
+        fputs("error: ", stderr);
+        ax_write(stderr, ax_item(result, 0));
+        fputc('\n', stderr);
+        code = AX_ENTRY_ERR_EXIT_CODE;
+    } else if (result->kind == AX_CTOR && !ax_is_ctor(result, "Ok", 1)) {
+        fprintf(stderr, "warning: axis_entry returned a constructor %s, not an Int, Unit or Result; exiting with 0\n",
+            ax_tag_display(result->as.agg.tag));
+    } else if (result->kind != AX_UNIT && result->kind != AX_CTOR) {
+        fprintf(stderr, "warning: axis_entry returned a %s, not an Int, Unit or Result; exiting with 0\n",
+            result->kind == AX_BOOL ? "Bool" : result->kind == AX_STR ? "Str"
+            : result->kind == AX_TUPLE ? "Tuple" : "StringBuilder");
+    }
+    ax_release(result);
+    return code;
+}
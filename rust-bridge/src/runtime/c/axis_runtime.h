@@ -0,0 +1,144 @@
+/* Axis C runtime: reference-counted values for the bridge's C backend
+ *
+ * OWNERSHIP CONTRACT:
+ *   - Every function returning AxValue * returns a new reference.
+ *   - Every AxValue * argument is consumed, except where marked "borrowed".
+ *   - Unit and the two Bools are immortal; retain/release ignore them.
+ *
+ * EXIT CODE CONTRACT: same as the Rust runtime (runtime/error.rs):
+ *   n = axis_entry returned Int(n), 3 = an assertion failed,
+ *   70 = runtime error, other = explicit proc_exit.
+ */
+#ifndef AXIS_RUNTIME_H
+#define AXIS_RUNTIME_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#if defined(__GNUC__)
+#define AX_NORETURN __attribute__((noreturn))
+#else
+#define AX_NORETURN
+#endif
+
+typedef enum { AX_INT, AX_BOOL, AX_UNIT, AX_STR, AX_TUPLE, AX_CTOR, AX_BUILDER } AxKind;
+
+typedef struct AxValue {
+    long refs; /* < 0: immortal */
+    AxKind kind;
+    union {
+        int64_t i;
+        int b;
+        struct { size_t len; char *data; } str;
+        /* Tuple (tag == NULL) or constructor */
+        struct { const char *tag; size_t len; struct AxValue **items; } agg;
+        /* String builder: appended in place, shared by every reference */
+        struct { size_t len, cap; char *data; } sb;
+    } as;
+} AxValue;
+
+/* Entry function of a generated program */
+typedef AxValue *(*AxEntry)(AxValue *args);
+
+AxValue *ax_retain(AxValue *v);
+void ax_release(AxValue *v);
+
+AxValue *ax_int(int64_t i);
+AxValue *ax_bool(int b);
+AxValue *ax_unit(void);
+AxValue *ax_str(const char *data, size_t len);
+AxValue *ax_tuple(size_t n, ...);
+AxValue *ax_ctor(const char *tag, size_t n, ...);
+
+/* Consumes v; nonzero when v is truthy (see runtime/value.rs truthy) */
+int ax_test(AxValue *v);
+/* Field reads; out of range or wrong kind yields Unit */
+AxValue *ax_tuple_field(AxValue *v, size_t index);
+AxValue *ax_ctor_field(AxValue *v, size_t index);
+/* Parameter i of a packed argument tuple (borrowed args) */
+AxValue *ax_arg(const AxValue *args, size_t index);
+
+/* Pattern tests; all borrowed, NULL never matches */
+const AxValue *ax_item(const AxValue *v, size_t index);
+int ax_is_int(const AxValue *v, int64_t i);
+int ax_is_bool(const AxValue *v, int b);
+int ax_is_unit(const AxValue *v);
+int ax_is_tuple(const AxValue *v, size_t n);
+int ax_is_ctor(const AxValue *v, const char *tag, size_t n);
+
+/* Primitives (UNARY CONTRACT: several arguments arrive as one tuple) */
+AxValue *ax_prim_add(AxValue *args);
+AxValue *ax_prim_sub(AxValue *args);
+AxValue *ax_prim_mul(AxValue *args);
+AxValue *ax_prim_div(AxValue *args);
+AxValue *ax_prim_mod(AxValue *args);
+AxValue *ax_prim_eq(AxValue *args);
+AxValue *ax_prim_neq(AxValue *args);
+AxValue *ax_prim_lt(AxValue *args);
+AxValue *ax_prim_lte(AxValue *args);
+AxValue *ax_prim_gt(AxValue *args);
+AxValue *ax_prim_gte(AxValue *args);
+AxValue *ax_prim_value_cmp(AxValue *args);
+AxValue *ax_prim_and(AxValue *args);
+AxValue *ax_prim_or(AxValue *args);
+AxValue *ax_prim_not(AxValue *v);
+AxValue *ax_prim_str_concat(AxValue *args);
+AxValue *ax_prim_str_len(AxValue *v);
+AxValue *ax_prim_str_slice(AxValue *args);
+AxValue *ax_prim_int_to_str(AxValue *v);
+AxValue *ax_prim_str_to_int(AxValue *v);
+AxValue *ax_prim_str_split(AxValue *args);
+AxValue *ax_prim_str_trim(AxValue *v);
+AxValue *ax_prim_str_contains(AxValue *args);
+AxValue *ax_prim_str_starts_with(AxValue *args);
+AxValue *ax_prim_str_ends_with(AxValue *args);
+AxValue *ax_prim_str_replace(AxValue *args);
+AxValue *ax_prim_str_to_lower(AxValue *v);
+AxValue *ax_prim_str_to_upper(AxValue *v);
+AxValue *ax_prim_str_join(AxValue *args);
+AxValue *ax_prim_char_is_alpha(AxValue *v);
+AxValue *ax_prim_char_is_digit(AxValue *v);
+AxValue *ax_prim_char_is_alnum(AxValue *v);
+AxValue *ax_prim_char_is_whitespace(AxValue *v);
+AxValue *ax_prim_char_is_ident_start(AxValue *v);
+AxValue *ax_prim_char_is_ident_char(AxValue *v);
+AxValue *ax_prim_char_to_digit(AxValue *v);
+AxValue *ax_prim_char_to_lower(AxValue *v);
+AxValue *ax_prim_char_to_upper(AxValue *v);
+AxValue *ax_prim_char_from_str(AxValue *v);
+AxValue *ax_prim_char_to_str(AxValue *v);
+AxValue *ax_prim_sb_new(AxValue *unit);
+AxValue *ax_prim_sb_push(AxValue *args);
+AxValue *ax_prim_sb_push_int(AxValue *args);
+AxValue *ax_prim_sb_build(AxValue *v);
+AxValue *ax_prim_tuple(AxValue *args);
+AxValue *ax_prim_tuple_field(AxValue *args);
+AxValue *ax_prim_ctor_field(AxValue *args);
+/* build --allow compiles primitives out by capability: AX_NO_IO, AX_NO_PROC */
+#ifndef AX_NO_IO
+AxValue *ax_prim_io_print(AxValue *v);
+AxValue *ax_prim_io_print_buffered(AxValue *v);
+AxValue *ax_prim_io_println(AxValue *v);
+AxValue *ax_prim_io_flush(AxValue *unit);
+AxValue *ax_prim_io_eprint(AxValue *v);
+#endif
+AxValue *ax_prim_debug_trace(AxValue *v);
+#ifndef AX_NO_PROC
+AxValue *ax_prim_proc_exit(AxValue *v);
+#endif
+
+/* Assertions take the call's span; file == NULL when the bundle has none */
+AxValue *ax_prim_assert_eq(AxValue *args, const char *file, int line, int column);
+AxValue *ax_prim_assert_true(AxValue *v, const char *file, int line, int column);
+AxValue *ax_prim_fail(AxValue *msg, const char *file, int line, int column);
+
+AX_NORETURN void ax_runtime_error(const char *msg, const char *file, int line, int column);
+
+/* The program's constructor names, NULL-terminated, which decide how a
+ * constructor prints (ax_tag_display); called by the generated main */
+void ax_register_tags(const char *const *names);
+
+/* Run entry with the command line as a Cons/Nil list; returns the exit code */
+int ax_main(int argc, char **argv, AxEntry entry);
+
+#endif
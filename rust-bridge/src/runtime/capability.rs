@@ -0,0 +1,209 @@
+// Capability sandboxing of foreign primitives (`build --allow io,fs`)
+//
+// Primitives that reach outside the program are grouped by the capability
+// they exercise, following the class tags the registries give them
+// (profile io, fs and process). With --allow the bridge
+//   - refuses a program that calls a primitive outside the allowed set,
+//     before emitting anything, and
+//   - builds a runtime without those primitives: the Rust backend turns
+//     off the matching cargo features of this crate, the C backend defines
+//     AX_NO_<CAPABILITY> for the C runtime.
+//
+// POLICY: the bundle is checked here even when the compiler was given the
+// same --allow; a bundle may come from anywhere.
+//
+// NOTE: core-compiler's registry_loader::Capability is the compiler's side
+// (it reads the class tags from the registries); keep the names in step.
+
+use crate::core_arena::{TermRef, TermView};
+use crate::core_ir::{Pattern, Span};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    Io,
+    Fs,
+    Proc,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 3] = [Capability::Io, Capability::Fs, Capability::Proc];
+
+    pub fn parse(name: &str) -> Option<Capability> {
+        match name {
+            "io" => Some(Capability::Io),
+            "fs" => Some(Capability::Fs),
+            "proc" => Some(Capability::Proc),
+            _ => None,
+        }
+    }
+
+    /// Also the cargo feature that compiles the primitives in
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Io => "io",
+            Capability::Fs => "fs",
+            Capability::Proc => "proc",
+        }
+    }
+
+    /// Preprocessor symbol that compiles the primitives out of the C runtime
+    pub fn c_define(self) -> &'static str {
+        match self {
+            Capability::Io => "AX_NO_IO",
+            Capability::Fs => "AX_NO_FS",
+            Capability::Proc => "AX_NO_PROC",
+        }
+    }
+
+    /// `io,fs` as given to --allow; `none` or an empty list grants nothing
+    pub fn parse_list(list: &str) -> Result<Vec<Capability>, String> {
+        let mut capabilities = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|name| !name.is_empty() && *name != "none") {
+            let capability = Capability::parse(name)
+                .ok_or_else(|| format!("unknown capability '{}' (expected io, fs, proc or none)", name))?;
+            capabilities.push(capability);
+        }
+        capabilities.sort();
+        capabilities.dedup();
+        Ok(capabilities)
+    }
+}
+
+/// Capability a foreign symbol (namespaces stripped) exercises, if any
+pub fn capability_of(symbol: &str) -> Option<Capability> {
+    match symbol {
+        "io_print" | "axis_io_print" | "io_eprint" | "axis_io_eprint" | "io_read" | "io_read_bytes"
+        | "axis_io_read_bytes" | "io_print_buffered" | "axis_io_print_buffered" | "io_println" | "axis_io_println"
+        | "io_flush" | "axis_io_flush" => Some(Capability::Io),
+        "fs_read_text" | "axis_fs_read_text" | "fs_read_to_string" | "fs_write_text" | "axis_fs_write_text"
+        | "fs_read_bytes" | "axis_fs_read_bytes" | "fs_write_bytes" | "axis_fs_write_bytes" | "fs_list_dir"
+        | "axis_fs_list_dir" | "fs_create_dir_all" | "axis_fs_create_dir_all" | "fs_remove_file"
+        | "axis_fs_remove_file" | "fs_copy" | "axis_fs_copy" | "path_join" | "axis_path_join" | "path_stem"
+        | "axis_path_stem" | "path_parent" | "axis_path_parent"
+        | "axis_emit_core_bundle_to_file" | "axis_load_core_bundle_from_file" => Some(Capability::Fs),
+        "env_get" | "axis_env_get" | "env_set" | "axis_env_set" | "proc_run" | "axis_proc_run" | "exit"
+        | "axis_proc_exit" | "axis_proc_args" | "args_flag" | "axis_args_flag" | "args_has" | "axis_args_has"
+        | "args_positional" | "axis_args_positional" => Some(Capability::Proc),
+        _ => None,
+    }
+}
+
+/// A primitive the program references outside --allow
+#[derive(Clone, Debug)]
+pub struct DeniedCall {
+    pub symbol: String,
+    pub capability: Capability,
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for DeniedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` needs capability {}", self.symbol, self.capability.name())?;
+        if let Some(span) = &self.span {
+            write!(f, " ({}:{}:{})", span.file, span.line, span.column)?;
+        }
+        Ok(())
+    }
+}
+
+/// References to primitives outside `allowed`, first use of each symbol in
+/// program order. Top-level definitions and locals of the same name shadow
+/// the primitive, as in the emitters.
+pub fn denied_calls(root: TermRef<'_>, allowed: &[Capability]) -> Vec<DeniedCall> {
+    // Top-level names are in scope everywhere (mutual recursion)
+    let mut scope = Vec::new();
+    let mut current = root;
+    while let TermView::Let(name, _, body, _) = current.view() {
+        scope.push(name.to_string());
+        current = body;
+    }
+    let mut denied = Vec::new();
+    denied_calls_in(root, &scope, allowed, &mut denied);
+    denied
+}
+
+/// denied_calls for part of a program (`build --stream` checks one
+/// top-level definition at a time) whose top-level names are `top_level`;
+/// adds symbols not already in `denied`
+pub fn denied_calls_in(term: TermRef<'_>, top_level: &[String], allowed: &[Capability], denied: &mut Vec<DeniedCall>) {
+    let mut walker = Walker { allowed, scope: top_level.to_vec(), denied };
+    walker.walk(term);
+}
+
+struct Walker<'a> {
+    allowed: &'a [Capability],
+    scope: Vec<String>,
+    denied: &'a mut Vec<DeniedCall>,
+}
+
+impl Walker<'_> {
+    fn reference(&mut self, name: &str, span: &Option<Span>) {
+        if self.scope.iter().any(|bound| bound == name) {
+            return;
+        }
+        let symbol = name.rsplit("::").next().unwrap_or(name);
+        let Some(capability) = capability_of(symbol) else {
+            return;
+        };
+        if !self.allowed.contains(&capability) && !self.denied.iter().any(|d| d.symbol == symbol) {
+            self.denied.push(DeniedCall { symbol: symbol.to_string(), capability, span: span.clone() });
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Pattern) -> usize {
+        match pattern {
+            Pattern::PVar(name) => {
+                self.scope.push(name.clone());
+                1
+            }
+            Pattern::PTuple(pats) | Pattern::PEnum(_, pats) => pats.iter().map(|p| self.bind_pattern(p)).sum(),
+            Pattern::PBind(name, inner) => {
+                self.scope.push(name.clone());
+                1 + self.bind_pattern(inner)
+            }
+            _ => 0,
+        }
+    }
+
+    fn walk(&mut self, term: TermRef<'_>) {
+        match term.view() {
+            TermView::IntLit(..) | TermView::BoolLit(..) | TermView::UnitLit(..) | TermView::StrLit(..) => {}
+            TermView::Var(name, span) => self.reference(name, span),
+            TermView::Lam(param, body, _) => {
+                self.scope.push(param.to_string());
+                self.walk(body);
+                self.scope.pop();
+            }
+            TermView::App(func, arg, _) => {
+                self.walk(func);
+                self.walk(arg);
+            }
+            TermView::Tuple(elems, _) | TermView::Ctor(_, elems, _) => {
+                for elem in elems.iter() {
+                    self.walk(elem);
+                }
+            }
+            TermView::Proj(expr, _, _) | TermView::CtorField(expr, _, _) => self.walk(expr),
+            TermView::Let(name, value, body, _) => {
+                self.walk(value);
+                self.scope.push(name.to_string());
+                self.walk(body);
+                self.scope.pop();
+            }
+            TermView::If(cond, then_br, else_br, _) => {
+                self.walk(cond);
+                self.walk(then_br);
+                self.walk(else_br);
+            }
+            TermView::Match(scrutinee, arms, _) => {
+                self.walk(scrutinee);
+                for (pattern, body) in arms.iter() {
+                    let bound = self.bind_pattern(pattern);
+                    self.walk(body);
+                    self.scope.truncate(self.scope.len() - bound);
+                }
+            }
+        }
+    }
+}
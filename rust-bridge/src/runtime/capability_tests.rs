@@ -0,0 +1,96 @@
+// Tests for the capability sandbox (capability.rs) and its use by the
+// backends (`build --allow`)
+
+use crate::backend::{backend_by_name, EmitError};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::emit_rust::EmitOptions;
+use crate::runtime::capability::{denied_calls, Capability};
+use std::path::Path;
+use std::rc::Rc;
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn call(f: &str, arg: CoreTerm) -> CoreTerm {
+    CoreTerm::App(Rc::new(var(f)), Rc::new(arg), None)
+}
+
+fn lam(param: &str, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Lam(param.into(), Rc::new(body), None)
+}
+
+fn let_(name: &str, value: CoreTerm, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Let(name.into(), Rc::new(value), Rc::new(body), None)
+}
+
+fn str_lit(s: &str) -> CoreTerm {
+    CoreTerm::StrLit(s.to_string(), None)
+}
+
+// axis_entry(args) = io_print(fs_read_text("in.txt")); exit(0)
+fn io_program() -> ArenaProgram {
+    let entry = lam(
+        "args",
+        let_(
+            "_",
+            call("io_print", call("axis::fs::fs_read_text", str_lit("in.txt"))),
+            call("exit", CoreTerm::IntLit(0, None)),
+        ),
+    );
+    ArenaProgram::from_term(&let_("axis_entry", entry, CoreTerm::UnitLit(None)))
+}
+
+#[test]
+fn test_parse_allow_list() {
+    assert_eq!(Capability::parse_list("fs,io"), Ok(vec![Capability::Io, Capability::Fs]));
+    assert_eq!(Capability::parse_list("none"), Ok(vec![]));
+    assert!(Capability::parse_list("net").is_err());
+}
+
+#[test]
+fn test_denied_calls() {
+    let program = io_program();
+    let denied: Vec<String> = denied_calls(program.root_term(), &[Capability::Io])
+        .iter()
+        .map(|call| call.to_string())
+        .collect();
+    assert_eq!(denied, ["`fs_read_text` needs capability fs", "`exit` needs capability proc"]);
+    assert!(denied_calls(program.root_term(), &Capability::ALL).is_empty());
+
+    // A program's own `exit` is not the primitive
+    let own_exit = let_("exit", lam("code", var("code")), lam("args", call("exit", CoreTerm::IntLit(1, None))));
+    assert!(denied_calls(ArenaProgram::from_term(&own_exit).root_term(), &[]).is_empty());
+}
+
+#[test]
+fn test_backends_refuse_and_omit() {
+    let sandboxed = |caps: &[Capability]| EmitOptions { allow: Some(caps.to_vec()), ..EmitOptions::default() };
+    let program = io_program();
+
+    for name in crate::backend::BACKENDS {
+        let backend = backend_by_name(name, Path::new("/opt/axis-rust-bridge")).unwrap();
+        let err = backend.emit(&program, &sandboxed(&[Capability::Io])).err().expect("fs and proc are not allowed");
+        assert!(matches!(err, EmitError::Denied(ref calls) if calls.len() == 2), "{}", err);
+        assert!(err.to_string().ends_with("capability sandbox: 2 primitive(s) outside --allow"), "{}", err);
+    }
+
+    let rust = backend_by_name("rust", Path::new("/opt/axis-rust-bridge")).unwrap();
+    let artifacts = rust.emit(&program, &sandboxed(&Capability::ALL)).unwrap();
+    assert!(
+        artifacts.files[0].1.contains("default-features = false, features = [\"io\", \"fs\", \"proc\"]"),
+        "{}",
+        artifacts.files[0].1
+    );
+    let unrestricted = rust.emit(&program, &EmitOptions::default()).unwrap();
+    assert!(!unrestricted.files[0].1.contains("default-features"));
+
+    // Only str_len: everything else is compiled out of the C runtime
+    let pure = ArenaProgram::from_term(&let_("axis_entry", lam("args", call("str_len", str_lit("abc"))), CoreTerm::UnitLit(None)));
+    let c = backend_by_name("c", Path::new(".")).unwrap();
+    let artifacts = c.emit(&pure, &sandboxed(&[Capability::Fs])).unwrap();
+    assert!(artifacts.build_command.contains(&"-DAX_NO_IO".to_string()));
+    assert!(artifacts.build_command.contains(&"-DAX_NO_PROC".to_string()));
+    assert!(!artifacts.build_command.contains(&"-DAX_NO_FS".to_string()));
+}
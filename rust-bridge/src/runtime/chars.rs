@@ -0,0 +1,103 @@
+// Character primitives: classification and conversion
+//
+// A character is an Int holding a Unicode code point, as str_char_at
+// returns it. A one-character Str is taken as its character too, so code
+// holding the Str that str_char returns needs no conversion. Anything else
+// is a runtime error; an Int that is not a code point (negative, above
+// 0x10FFFF or a surrogate) is no letter, digit or whitespace.
+//
+//   char_is_alpha          alphabetic (Unicode)
+//   char_is_digit          0-9 only
+//   char_is_alnum          alphabetic or 0-9
+//   char_is_whitespace     whitespace (Unicode)
+//   char_is_ident_start    alphabetic or _, as the surface lexer starts a name
+//   char_is_ident_char     alphanumeric or _, as the surface lexer continues one
+//   char_to_digit          0-9 for '0'-'9', else -1
+//   char_to_lower/upper    the code point in that case, when it is a single
+//                          character; otherwise unchanged
+//   char_from_str          the first character of a string, -1 for ""
+//
+// char_to_str (shim.rs) goes the other way. The C runtime (runtime/c)
+// classifies and maps ASCII only; the compiler's interpreter as here.
+
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::value::Value;
+
+// The character of a primitive's argument; None for an Int that is no
+// code point
+fn char_arg(value: &Value, primitive: &str) -> Option<char> {
+    match value {
+        Value::Int(n) => u32::try_from(*n).ok().and_then(char::from_u32),
+        Value::Str(s) => {
+            let mut chars = s.text().chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => axis_runtime_error(&format!("{}: expected one character, got {:?}", primitive, s.text()), None),
+            }
+        }
+        other => axis_runtime_error(&format!("{}: expected a character, got {}", primitive, other.kind_name()), None),
+    }
+}
+
+fn classify(value: Value, primitive: &str, test: fn(char) -> bool) -> Value {
+    Value::Bool(char_arg(&value, primitive).is_some_and(test))
+}
+
+pub fn char_is_alpha(c: Value) -> Value {
+    classify(c, "char_is_alpha", char::is_alphabetic)
+}
+
+pub fn char_is_digit(c: Value) -> Value {
+    classify(c, "char_is_digit", |c| c.is_ascii_digit())
+}
+
+pub fn char_is_alnum(c: Value) -> Value {
+    classify(c, "char_is_alnum", |c| c.is_alphabetic() || c.is_ascii_digit())
+}
+
+pub fn char_is_whitespace(c: Value) -> Value {
+    classify(c, "char_is_whitespace", char::is_whitespace)
+}
+
+pub fn char_is_ident_start(c: Value) -> Value {
+    classify(c, "char_is_ident_start", |c| c.is_alphabetic() || c == '_')
+}
+
+pub fn char_is_ident_char(c: Value) -> Value {
+    classify(c, "char_is_ident_char", |c| c.is_alphanumeric() || c == '_')
+}
+
+pub fn char_to_digit(c: Value) -> Value {
+    let digit = char_arg(&c, "char_to_digit").and_then(|c| c.to_digit(10));
+    Value::Int(digit.map_or(-1, i64::from))
+}
+
+// A mapping to more than one character (ß to SS) leaves it unchanged, as
+// does an Int that is no code point
+fn map_case(c: Value, primitive: &str, map: fn(char) -> String) -> Value {
+    let Some(ch) = char_arg(&c, primitive) else {
+        return c;
+    };
+    let mapped = map(ch);
+    let mut chars = mapped.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(one), None) => one,
+        _ => ch,
+    };
+    Value::Int(code as i64)
+}
+
+pub fn char_to_lower(c: Value) -> Value {
+    map_case(c, "char_to_lower", |c| c.to_lowercase().collect())
+}
+
+pub fn char_to_upper(c: Value) -> Value {
+    map_case(c, "char_to_upper", |c| c.to_uppercase().collect())
+}
+
+pub fn char_from_str(s: Value) -> Value {
+    match s {
+        Value::Str(s) => Value::Int(s.text().chars().next().map_or(-1, |c| c as i64)),
+        other => axis_runtime_error(&format!("char_from_str: expected a string, got {}", other.kind_name()), None),
+    }
+}
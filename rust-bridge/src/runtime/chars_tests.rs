@@ -0,0 +1,75 @@
+// Tests for the character primitives (chars.rs)
+
+use crate::runtime::chars::{
+    char_from_str, char_is_alnum, char_is_alpha, char_is_digit, char_is_ident_char, char_is_ident_start,
+    char_is_whitespace, char_to_digit, char_to_lower, char_to_upper,
+};
+use crate::runtime::shim::{char_to_str, str_char_code};
+use crate::runtime::value::{init_runtime, Value};
+use crate::runtime::panic_on_runtime_errors;
+
+fn code(c: char) -> Value {
+    Value::Int(c as i64)
+}
+
+#[test]
+fn test_classification_of_code_points() {
+    init_runtime();
+    assert_eq!(char_is_alpha(code('q')), Value::Bool(true));
+    assert_eq!(char_is_alpha(code('é')), Value::Bool(true));
+    assert_eq!(char_is_alpha(code('7')), Value::Bool(false));
+    assert_eq!(char_is_digit(code('7')), Value::Bool(true));
+    assert_eq!(char_is_digit(code('٣')), Value::Bool(false));
+    assert_eq!(char_is_alnum(code('Z')), Value::Bool(true));
+    assert_eq!(char_is_alnum(code('-')), Value::Bool(false));
+    assert_eq!(char_is_whitespace(code('\t')), Value::Bool(true));
+    assert_eq!(char_is_whitespace(code('x')), Value::Bool(false));
+    assert_eq!(char_is_ident_start(code('_')), Value::Bool(true));
+    assert_eq!(char_is_ident_start(code('1')), Value::Bool(false));
+    assert_eq!(char_is_ident_char(code('1')), Value::Bool(true));
+    assert_eq!(char_is_ident_char(code('.')), Value::Bool(false));
+}
+
+#[test]
+fn test_non_code_points_are_nothing() {
+    init_runtime();
+    for n in [-1, 0xD800, 0x110000] {
+        assert_eq!(char_is_alpha(Value::Int(n)), Value::Bool(false));
+        assert_eq!(char_is_whitespace(Value::Int(n)), Value::Bool(false));
+        assert_eq!(char_to_digit(Value::Int(n)), Value::Int(-1));
+        assert_eq!(char_to_upper(Value::Int(n)), Value::Int(n));
+    }
+}
+
+#[test]
+fn test_one_character_strings_are_characters() {
+    init_runtime();
+    assert_eq!(char_is_alpha(Value::string("a")), Value::Bool(true));
+    assert_eq!(char_to_digit(Value::string("4")), Value::Int(4));
+    assert_eq!(char_to_upper(Value::string("é")), code('É'));
+}
+
+#[test]
+fn test_conversions() {
+    init_runtime();
+    assert_eq!(char_to_digit(code('0')), Value::Int(0));
+    assert_eq!(char_to_digit(code('9')), Value::Int(9));
+    assert_eq!(char_to_digit(code('a')), Value::Int(-1));
+    assert_eq!(char_to_lower(code('Q')), code('q'));
+    assert_eq!(char_to_upper(code('q')), code('Q'));
+    assert_eq!(char_to_upper(code('ß')), code('ß'));
+    assert_eq!(char_from_str(Value::string("éa")), code('é'));
+    assert_eq!(char_from_str(Value::string("")), Value::Int(-1));
+    // str_char_at and char_to_str round-trip through code points
+    let c = str_char_code(Value::Tuple(vec![Value::string("xyz"), Value::Int(1)]));
+    assert_eq!(c, code('y'));
+    assert_eq!(char_to_str(char_to_upper(c)), Value::string("Y"));
+}
+
+#[test]
+#[should_panic(expected = "char_is_digit: expected one character, got \"12\"")]
+fn test_longer_strings_are_rejected() {
+    panic_on_runtime_errors();
+    init_runtime();
+    char_is_digit(Value::string("12"));
+}
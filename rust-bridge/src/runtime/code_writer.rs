@@ -0,0 +1,160 @@
+// Indentation-aware writer for generated code
+//
+// The emitters build source from nested pieces: a function body holds a
+// `match`, whose arms hold `let` blocks, and so on. Each piece is written
+// at indentation zero, and whoever nests it writes it with `lines`, which
+// indents every line to the writer's current level. Only the writer
+// indents, so a piece never needs to know how deep it ends up.
+//
+// `--format-generated` additionally runs rustfmt over the Rust sources
+// (rustfmt below). The writer's output is already readable without it,
+// and a build must not depend on rustfmt being installed.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const INDENT: &str = "    ";
+
+#[derive(Default)]
+pub struct CodeWriter {
+    out: String,
+    level: usize,
+}
+
+impl CodeWriter {
+    pub fn new() -> Self {
+        CodeWriter::default()
+    }
+
+    /// One line at the current level; an empty line is left unindented
+    pub fn line(&mut self, text: &str) {
+        if !text.is_empty() {
+            for _ in 0..self.level {
+                self.out.push_str(INDENT);
+            }
+            self.out.push_str(text);
+        }
+        self.out.push('\n');
+    }
+
+    /// Every line of a piece written at level zero, at the current level
+    pub fn lines(&mut self, text: &str) {
+        for line in text.lines() {
+            self.line(line);
+        }
+    }
+
+    /// A piece used as an expression, between `prefix` and `suffix` (e.g.
+    /// `let x = ` and `;`): its first line goes after the prefix and the
+    /// suffix after its last line
+    pub fn expr(&mut self, prefix: &str, code: &str, suffix: &str) {
+        let mut lines = code.lines().peekable();
+        let first = lines.next().unwrap_or("");
+        if lines.peek().is_none() {
+            self.line(&format!("{}{}{}", prefix, first, suffix));
+            return;
+        }
+        self.line(&format!("{}{}", prefix, first));
+        while let Some(line) = lines.next() {
+            if lines.peek().is_none() {
+                self.line(&format!("{}{}", line, suffix));
+            } else {
+                self.line(line);
+            }
+        }
+    }
+
+    pub fn blank(&mut self) {
+        self.out.push('\n');
+    }
+
+    pub fn indent(&mut self) {
+        self.level += 1;
+    }
+
+    /// `header` (e.g. `fn f() -> Value {`), then indent what follows
+    pub fn open(&mut self, header: &str) {
+        self.line(header);
+        self.indent();
+    }
+
+    /// Back out one level, then `footer` (e.g. `}`)
+    pub fn close(&mut self, footer: &str) {
+        self.level = self.level.checked_sub(1).expect("CodeWriter::close without open");
+        self.line(footer);
+    }
+
+    /// `header`, the lines written by `body` one level in, then `footer`
+    pub fn block(&mut self, header: &str, footer: &str, body: impl FnOnce(&mut CodeWriter)) {
+        self.open(header);
+        body(self);
+        self.close(footer);
+    }
+
+    /// The code written so far, without the final newline (for a piece
+    /// that is used as an expression)
+    pub fn finish_expr(self) -> String {
+        let mut out = self.out;
+        if out.ends_with('\n') {
+            out.pop();
+        }
+        out
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// Why rustfmt left the source unformatted
+#[derive(Debug)]
+pub enum FormatError {
+    // rustfmt is not installed (or not on PATH)
+    Unavailable(String),
+    // rustfmt rejected the source: usually a miscompile, which the
+    // unformatted source is the one to debug
+    Rejected(String),
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Unavailable(detail) => write!(f, "rustfmt is not available ({})", detail),
+            FormatError::Rejected(detail) => write!(f, "rustfmt rejected the generated source: {}", detail),
+        }
+    }
+}
+
+/// Run rustfmt (`$RUSTFMT`, else `rustfmt` on PATH) over one Rust source
+pub fn rustfmt(source: &str) -> Result<String, FormatError> {
+    let rustfmt = std::env::var("RUSTFMT").unwrap_or_else(|_| "rustfmt".to_string());
+    rustfmt_with(&rustfmt, source)
+}
+
+/// Run the given rustfmt over one Rust source. The generated crate's
+/// edition is passed explicitly: rustfmt reading stdin cannot find its
+/// Cargo.toml.
+pub fn rustfmt_with(rustfmt: &str, source: &str) -> Result<String, FormatError> {
+    let mut child = Command::new(rustfmt)
+        .args(["--edition", "2021", "--emit", "stdout", "--quiet"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| FormatError::Unavailable(format!("{}: {}", rustfmt, e)))?;
+
+    // Write from a thread: rustfmt may fill its stdout pipe before it has
+    // read all of a large source
+    let mut stdin = child.stdin.take().expect("rustfmt stdin");
+    let input = source.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output().map_err(|e| FormatError::Unavailable(e.to_string()))?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let first = stderr.lines().take(5).collect::<Vec<_>>().join("\n");
+        return Err(FormatError::Rejected(first));
+    }
+    String::from_utf8(output.stdout).map_err(|e| FormatError::Rejected(e.to_string()))
+}
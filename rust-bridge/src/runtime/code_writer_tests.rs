@@ -0,0 +1,76 @@
+// Tests for the generated-code writer (code_writer.rs)
+
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::{CoreTerm, Pattern};
+use crate::runtime::code_writer::{rustfmt_with, CodeWriter, FormatError};
+use crate::runtime::emit_rust::emit_rust_from_core;
+use std::rc::Rc;
+
+#[test]
+fn test_nested_pieces_are_reindented() {
+    let mut inner = CodeWriter::new();
+    inner.block("{", "}", |w| w.line("let y = 1;"));
+    let piece = inner.finish_expr();
+
+    let mut outer = CodeWriter::new();
+    outer.open("fn f() -> Value {");
+    outer.expr("let x = ", &piece, ";");
+    outer.expr("g(", "x", ")");
+    outer.blank();
+    outer.close("}");
+    assert_eq!(outer.finish(), "fn f() -> Value {\n    let x = {\n        let y = 1;\n    };\n    g(x)\n\n}\n");
+}
+
+#[test]
+fn test_emitted_blocks_are_indented_by_depth() {
+    // show = \x -> match x { Some(y) => let z = int_to_str(y) in io_print(z)
+    //                        _ => () }
+    let var = |name: &str| Rc::new(CoreTerm::Var(name.into(), None));
+    let app = |f: Rc<CoreTerm>, x: Rc<CoreTerm>| Rc::new(CoreTerm::App(f, x, None));
+    let body = CoreTerm::Match(
+        var("x"),
+        vec![
+            (
+                Pattern::PEnum("Some".to_string(), vec![Pattern::PVar("y".to_string())]),
+                CoreTerm::Let("z".into(), app(var("int_to_str"), var("y")), app(var("io_print"), var("z")), None),
+            ),
+            (Pattern::PVar("_".to_string()), CoreTerm::UnitLit(None)),
+        ],
+        None,
+    );
+    let term = CoreTerm::Let("show".into(), Rc::new(CoreTerm::Lam("x".into(), Rc::new(body), None)), Rc::new(CoreTerm::UnitLit(None)), None);
+    let code = emit_rust_from_core(ArenaProgram::from_term(&term).root_term(), "t.ax", "show");
+
+    let expected = [
+        "fn show(x: Value) -> Value {",
+        "    {",
+        "        let scr = x;",
+        "        let __arm = match &scr {",
+        "            Value::Ctor { tag: 3 /* Some */, fields } if fields.len() == 1 => Some(0),",
+        "            _ => Some(1),",
+        "        };",
+        "        match __arm {",
+        "            Some(0) => {",
+        "                let y = match_field(&scr, 0).clone();",
+        "                {",
+        "                    let z = shim::int_to_str(y);",
+        "                    shim::io_print(z)",
+        "                }",
+        "            },",
+        "            Some(1) => {",
+        "                Value::Unit",
+        "            },",
+        "            _ => { Value::Unit },",
+        "        }",
+        "    }",
+        "}",
+    ]
+    .join("\n");
+    assert!(code.contains(&expected), "unexpected layout:\n{}", code);
+}
+
+#[test]
+fn test_missing_rustfmt_is_reported_not_fatal() {
+    let result = rustfmt_with("/nonexistent/rustfmt", "fn main() {}\n");
+    assert!(matches!(result, Err(FormatError::Unavailable(_))), "{:?}", result);
+}
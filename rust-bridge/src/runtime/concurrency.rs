@@ -0,0 +1,147 @@
+// Concurrency primitives: threads (spawn/join) and channels
+//
+// spawn(f, x) runs the top-level function f on x in a new OS thread and
+// returns a Task; join(task) waits for it and returns f's result. A channel
+// carries values between threads: channel_send appends, channel_recv takes
+// the oldest value, waiting until there is one. Channels and tasks are
+// values themselves and can be sent to other threads.
+//
+// The validator only accepts a top-level function as spawn's target
+// (E_SPAWN_TARGET), so a thread gets nothing from its parent but the
+// argument it is given. Values cross threads whole; the only kinds that
+// cannot are a StringBuilder, whose buffer is shared by its copies, and a
+// lazy Seq, whose items are (FAIL-FAST: spawn, channel_send and the end of
+// a thread reject either, however deeply nested).
+//
+// The registry marks these primitives non-deterministic: the order in which
+// threads run and send is not.
+
+use crate::runtime::error::{assertion_failures, axis_runtime_error, record_assertion_failures};
+use crate::runtime::value::Value;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+// A value on its way to another thread
+#[derive(Debug)]
+pub struct Sendable(Value);
+
+// SAFETY: Value is not Send only because of the Rcs in Value::Builder and
+// Value::Seq, and Sendable::new refuses a value containing either.
+// Everything else a Value holds (Arc<str>, vectors, Arc<Channel>,
+// Arc<Task>) is Send.
+unsafe impl Send for Sendable {}
+
+impl Sendable {
+    // `what` names the primitive in the runtime error
+    fn new(value: Value, what: &str) -> Sendable {
+        if let Some(kind) = thread_local_part(&value) {
+            axis_runtime_error(&format!("{}: a {} cannot be sent to another thread", what, kind), None);
+        }
+        Sendable(value)
+    }
+
+    fn into_value(self) -> Value {
+        self.0
+    }
+}
+
+// The kind of the first part of `value` that must stay on its thread
+fn thread_local_part(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Builder(_) | Value::Seq(_) => Some(value.kind_name()),
+        Value::Tuple(elems) | Value::List(elems) | Value::Ctor { fields: elems, .. } => {
+            elems.iter().find_map(thread_local_part)
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+pub struct Channel {
+    sender: Sender<Sendable>,
+    // One receiver for every copy of the channel, on any thread
+    receiver: Mutex<Receiver<Sendable>>,
+}
+
+// Channels are compared by identity
+impl PartialEq for Channel {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+#[derive(Debug)]
+pub struct Task {
+    // The thread's result and the assertion failures it recorded; taken
+    // by the first join
+    handle: Mutex<Option<JoinHandle<(Sendable, usize)>>>,
+}
+
+impl PartialEq for Task {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+// Emitted for `spawn(f, x)` with f a top-level function (emit_rust passes
+// the Rust function itself)
+pub fn spawn(function: fn(Value) -> Value, arg: Value) -> Value {
+    let arg = Sendable::new(arg, "spawn");
+    let handle = std::thread::spawn(move || {
+        let result = function(arg.into_value());
+        (Sendable::new(result, "spawn: the thread's result"), assertion_failures())
+    });
+    Value::Task(Arc::new(Task { handle: Mutex::new(Some(handle)) }))
+}
+
+// Waits for the task's thread and returns its result. Assertion failures
+// recorded on that thread count as the joining thread's.
+pub fn join(task: Value) -> Value {
+    let Value::Task(task) = task else {
+        axis_runtime_error(&format!("join: expected Task, got {}", task.kind_name()), None);
+    };
+    let Some(handle) = task.handle.lock().unwrap().take() else {
+        axis_runtime_error("join: the task was already joined", None);
+    };
+    match handle.join() {
+        Ok((result, failures)) => {
+            record_assertion_failures(failures);
+            result.into_value()
+        }
+        Err(_) => axis_runtime_error("join: the task's thread panicked", None),
+    }
+}
+
+// Takes a (unit) argument, as sb_new
+pub fn channel_new(_unit: Value) -> Value {
+    let (sender, receiver) = mpsc::channel();
+    Value::Channel(Arc::new(Channel { sender, receiver: Mutex::new(receiver) }))
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [channel, value]
+pub fn channel_send(args: Value) -> Value {
+    let elems = args.as_tuple();
+    match (elems.first(), elems.get(1)) {
+        (Some(Value::Channel(channel)), Some(value)) => {
+            let value = Sendable::new(value.clone(), "channel_send");
+            // The channel holds its own receiver, so it is never disconnected
+            let _ = channel.sender.send(value);
+            Value::Unit
+        }
+        _ => axis_runtime_error("channel_send expects (Channel, value)", None),
+    }
+}
+
+// The oldest value sent to the channel; waits for one if there is none.
+// POLICY: receiving from a channel nothing will send to again waits forever.
+pub fn channel_recv(channel: Value) -> Value {
+    let Value::Channel(channel) = channel else {
+        axis_runtime_error(&format!("channel_recv: expected Channel, got {}", channel.kind_name()), None);
+    };
+    let receiver = channel.receiver.lock().unwrap();
+    match receiver.recv() {
+        Ok(value) => value.into_value(),
+        Err(_) => axis_runtime_error("channel_recv: the channel is closed", None),
+    }
+}
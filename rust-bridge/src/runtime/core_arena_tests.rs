@@ -0,0 +1,79 @@
+// Tests for the arena form of Core IR (core_arena.rs)
+
+use crate::core_arena::{ArenaProgram, ArenaTerm, TermView};
+use crate::core_ir::{CoreProgram, CoreTerm, Pattern, Span};
+use std::rc::Rc;
+
+fn span(line: usize) -> Option<Span> {
+    Some(Span { file: "t.ax".to_string(), line, column: 1 })
+}
+
+// One of every term kind:
+// let f = \x -> match (x, "s").1 { Some(y) => if true then Some(y) else ()
+//                                  _ => x.0 } in f 7
+fn every_kind() -> CoreTerm {
+    let x = || Rc::new(CoreTerm::Var("x".into(), None));
+    let pair = CoreTerm::Tuple(vec![CoreTerm::Var("x".into(), None), CoreTerm::StrLit("s".to_string(), None)], None);
+    let branch = CoreTerm::If(
+        Rc::new(CoreTerm::BoolLit(true, None)),
+        Rc::new(CoreTerm::Ctor("Some".to_string(), vec![CoreTerm::Var("y".into(), None)], None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None,
+    );
+    let body = CoreTerm::Match(
+        Rc::new(CoreTerm::Proj(Rc::new(pair), 1, None)),
+        vec![
+            (Pattern::PEnum("Some".to_string(), vec![Pattern::PVar("y".to_string())]), branch),
+            (Pattern::PVar("_".to_string()), CoreTerm::CtorField(x(), 0, span(3))),
+        ],
+        span(2),
+    );
+    CoreTerm::Let(
+        "f".into(),
+        Rc::new(CoreTerm::Lam("x".into(), Rc::new(body), None)),
+        Rc::new(CoreTerm::App(Rc::new(CoreTerm::Var("f".into(), None)), Rc::new(CoreTerm::IntLit(7, None)), span(1))),
+        None,
+    )
+}
+
+#[test]
+fn test_arena_term_is_compact() {
+    assert_eq!(std::mem::size_of::<ArenaTerm>(), 16);
+}
+
+#[test]
+fn test_round_trip_through_arena() {
+    let term = every_kind();
+    let program = CoreProgram { strings: vec!["s".to_string()], root_term: term.clone(), entrypoint_id: 0 };
+    let arena = ArenaProgram::from_program(&program);
+    let back = CoreProgram::from_arena(&arena);
+    assert_eq!(format!("{:?}", back.root_term), format!("{:?}", term));
+    assert_eq!(back.strings, program.strings);
+}
+
+#[test]
+fn test_view_matches_term() {
+    let program = ArenaProgram::from_term(&every_kind());
+    let TermView::Let(name, value, body, None) = program.root_term().view() else {
+        panic!("root is a Let");
+    };
+    assert_eq!(*name, "f");
+    // Children are allocated before their parents
+    assert!(value.id().index() < program.root.index() && body.id().index() < program.root.index());
+    assert_eq!(body.span().as_ref().map(|sp| sp.line), Some(1));
+
+    let TermView::Lam(_, lam_body, _) = value.view() else { panic!("value is a Lam") };
+    let TermView::Match(scrutinee, arms, _) = lam_body.view() else { panic!("body is a Match") };
+    assert_eq!(arms.len(), 2);
+    let TermView::Proj(pair, 1, _) = scrutinee.view() else { panic!("scrutinee is a Proj") };
+    let TermView::Tuple(elems, _) = pair.view() else { panic!("Proj of a Tuple") };
+    let texts: Vec<String> = elems
+        .iter()
+        .map(|elem| match elem.view() {
+            TermView::Var(name, _) => name.to_string(),
+            TermView::StrLit(s, _) => format!("{:?}", s),
+            _ => panic!("unexpected tuple element"),
+        })
+        .collect();
+    assert_eq!(texts, ["x", "\"s\""]);
+}
@@ -1,9 +1,11 @@
 // Core IR emission runtime — linked from generated Axis code
-// This module provides `axis_emit_core_bundle_to_file` as a runtime function.
+// This module provides `axis_emit_core_bundle_to_file` and its inverse
+// `axis_load_core_bundle_from_file` as runtime functions.
 
-use crate::core_ir::{CoreTerm, Pattern};
+use crate::core_ir::{BundleMetadata, CoreTerm, Pattern};
 use crate::runtime::value::Value;
-use crate::runtime::value::{get_str, get_tag_name};
+use crate::runtime::value::{get_tag_name, intern_tag};
+use crate::symbol::Symbol;
 use std::fs;
 use std::rc::Rc;
 
@@ -18,8 +20,8 @@ pub fn axis_emit_core_bundle_to_file(args: Value) -> Value {
         },
         _ => {
             let err_tag = crate::runtime::value::intern_tag("Err");
-            let msg = crate::runtime::value::intern_str("axis_emit_core_bundle_to_file: expected tuple with 2 elements");
-            return Value::Ctor { tag: err_tag, fields: vec![Value::Str(msg)] };
+            let msg = Value::string("axis_emit_core_bundle_to_file: expected tuple with 2 elements");
+            return Value::Ctor { tag: err_tag, fields: vec![msg] };
         }
     };
     match emit_core_bundle_impl(bundle, path) {
@@ -34,10 +36,9 @@ pub fn axis_emit_core_bundle_to_file(args: Value) -> Value {
         Err(e) => {
             // Err(msg) constructor
             let err_tag = crate::runtime::value::intern_tag("Err");
-            let msg_str = crate::runtime::value::intern_str(&e);
             Value::Ctor {
                 tag: err_tag,
-                fields: vec![Value::Str(msg_str)],
+                fields: vec![Value::string(&e)],
             }
         }
     }
@@ -46,7 +47,7 @@ pub fn axis_emit_core_bundle_to_file(args: Value) -> Value {
 fn emit_core_bundle_impl(bundle: Value, path: Value) -> Result<(), String> {
     // Extract path string
     let path_str = match path {
-        Value::Str(handle) => get_str(handle).to_string(),
+        Value::Str(s) => s.text().to_string(),
         _ => return Err(format!("Expected Str for path, got {:?}", path)),
     };
 
@@ -66,7 +67,7 @@ fn emit_core_bundle_impl(bundle: Value, path: Value) -> Result<(), String> {
                     let mut result = Vec::new();
                     for s_val in strs {
                         match s_val {
-                            Value::Str(handle) => result.push(get_str(*handle).to_string()),
+                            Value::Str(s) => result.push(s.text().to_string()),
                             _ => return Err(format!("Expected Str in string table, got {:?}", s_val)),
                         }
                     }
@@ -111,6 +112,117 @@ fn emit_core_bundle_impl(bundle: Value, path: Value) -> Result<(), String> {
     Ok(())
 }
 
+/// Runtime entry point called from generated Axis code
+/// Signature: axis_load_core_bundle_from_file(path: Str) -> Result[CoreBundle]
+/// The returned bundle uses the same Value encoding accepted by
+/// `axis_emit_core_bundle_to_file`, so load -> transform -> emit round-trips.
+pub fn axis_load_core_bundle_from_file(path: Value) -> Value {
+    match load_core_bundle_impl(path) {
+        Ok(bundle) => Value::Ctor {
+            tag: intern_tag("Ok"),
+            fields: vec![bundle],
+        },
+        Err(e) => Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::string(&e)],
+        },
+    }
+}
+
+fn load_core_bundle_impl(path: Value) -> Result<Value, String> {
+    let path_str = match path {
+        Value::Str(s) => s.text().to_string(),
+        _ => return Err(format!("Expected Str for path, got {:?}", path)),
+    };
+
+    // Bundles read by Axis programs are untrusted: default resource limits apply
+    let program = crate::core_ir::load_core_bundle(&path_str, &crate::core_ir::BundleLoadOptions::default())?;
+
+    // Encode StringTable(List[Str], Int)
+    let string_count = program.strings.len() as i64;
+    let strings: Vec<Value> = program.strings.iter()
+        .map(|s| Value::string(s))
+        .collect();
+    let string_table = Value::Ctor {
+        tag: intern_tag("StringTable"),
+        fields: vec![Value::List(strings), Value::Int(string_count)],
+    };
+
+    // A bundle holds a single root term; expose it as a one-element List[CoreTerm]
+    let terms = Value::List(vec![core_term_to_value(&program.root_term)]);
+
+    Ok(Value::Ctor {
+        tag: intern_tag("CoreBundle"),
+        fields: vec![string_table, terms],
+    })
+}
+
+/// Convert Rust CoreTerm to its runtime Value representation (inverse of value_to_core_term)
+fn core_term_to_value(term: &CoreTerm) -> Value {
+    let ctor = |name: &str, fields: Vec<Value>| Value::Ctor { tag: intern_tag(name), fields };
+    match term {
+        CoreTerm::IntLit(n, _) => ctor("CIntLit", vec![Value::Int(*n)]),
+        CoreTerm::BoolLit(b, _) => ctor("CBoolLit", vec![Value::Bool(*b)]),
+        CoreTerm::UnitLit(_) => ctor("CUnitLit", vec![]),
+        CoreTerm::StrLit(s, _) => ctor("CStrLit", vec![Value::string(s)]),
+        CoreTerm::Var(name, _) => ctor("CVar", vec![Value::string(name)]),
+        CoreTerm::Lam(param, body, _) => {
+            ctor("CLam", vec![Value::string(param), core_term_to_value(body)])
+        }
+        CoreTerm::App(func, arg, _) => {
+            ctor("CApp", vec![core_term_to_value(func), core_term_to_value(arg)])
+        }
+        CoreTerm::Tuple(elems, _) => {
+            ctor("CTuple", vec![Value::List(elems.iter().map(core_term_to_value).collect())])
+        }
+        CoreTerm::Proj(expr, idx, _) => {
+            ctor("CProj", vec![core_term_to_value(expr), Value::Int(*idx as i64)])
+        }
+        CoreTerm::CtorField(expr, idx, _) => {
+            ctor("CCtorField", vec![core_term_to_value(expr), Value::Int(*idx as i64)])
+        }
+        CoreTerm::Let(name, value, body, _) => ctor("CLet", vec![
+            Value::string(name),
+            core_term_to_value(value),
+            core_term_to_value(body),
+        ]),
+        CoreTerm::If(cond, then_br, else_br, _) => ctor("CIf", vec![
+            core_term_to_value(cond),
+            core_term_to_value(then_br),
+            core_term_to_value(else_br),
+        ]),
+        CoreTerm::Ctor(name, fields, _) => ctor("CCtor", vec![
+            Value::string(name),
+            Value::List(fields.iter().map(core_term_to_value).collect()),
+        ]),
+        CoreTerm::Match(scrutinee, arms, _) => {
+            let arm_values = arms.iter()
+                .map(|(pattern, body)| ctor("MatchCase", vec![pattern_to_value(pattern), core_term_to_value(body)]))
+                .collect();
+            ctor("CMatch", vec![core_term_to_value(scrutinee), Value::List(arm_values)])
+        }
+    }
+}
+
+/// Convert Rust Pattern to its runtime Value representation (inverse of value_to_pattern)
+fn pattern_to_value(pattern: &Pattern) -> Value {
+    let ctor = |name: &str, fields: Vec<Value>| Value::Ctor { tag: intern_tag(name), fields };
+    match pattern {
+        Pattern::PInt(n) => ctor("PInt", vec![Value::Int(*n)]),
+        Pattern::PBool(b) => ctor("PBool", vec![Value::Bool(*b)]),
+        Pattern::PUnit => ctor("PUnit", vec![]),
+        Pattern::PVar(name) => ctor("PVar", vec![Value::string(name)]),
+        Pattern::PTuple(pats) => {
+            ctor("PTuple", vec![Value::List(pats.iter().map(pattern_to_value).collect())])
+        }
+        Pattern::PEnum(name, pats) => ctor("PEnum", vec![
+            Value::string(name),
+            Value::List(pats.iter().map(pattern_to_value).collect()),
+        ]),
+        Pattern::PBind(name, inner) => ctor("PBind", vec![Value::string(name), pattern_to_value(inner)]),
+    }
+}
+
 /// Convert runtime Value representation of CoreTerm to Rust CoreTerm
 fn value_to_core_term(val: &Value) -> Result<CoreTerm, String> {
     match val {
@@ -129,19 +241,19 @@ fn value_to_core_term(val: &Value) -> Result<CoreTerm, String> {
                 }
                 "CStrLit" if fields.len() == 1 => {
                     match &fields[0] {
-                        Value::Str(handle) => Ok(CoreTerm::StrLit(get_str(*handle).to_string(), None)),
+                        Value::Str(s) => Ok(CoreTerm::StrLit(s.text().to_string(), None)),
                         _ => Err(format!("Expected Str in CStrLit, got {:?}", fields[0])),
                     }
                 }
                 "CVar" if fields.len() == 1 => {
                     match &fields[0] {
-                        Value::Str(handle) => Ok(CoreTerm::Var(get_str(*handle).to_string(), None)),
+                        Value::Str(s) => Ok(CoreTerm::Var(Symbol::intern(s.text()), None)),
                         _ => Err(format!("Expected Str in CVar, got {:?}", fields[0])),
                     }
                 }
                 "CLam" if fields.len() == 2 => {
                     let param = match &fields[0] {
-                        Value::Str(handle) => get_str(*handle).to_string(),
+                        Value::Str(s) => Symbol::intern(s.text()),
                         _ => return Err(format!("Expected Str param in CLam, got {:?}", fields[0])),
                     };
                     let body = value_to_core_term(&fields[1])?;
@@ -169,9 +281,14 @@ fn value_to_core_term(val: &Value) -> Result<CoreTerm, String> {
                     let idx = fields[1].as_int() as usize;
                     Ok(CoreTerm::Proj(Rc::new(expr), idx, None))
                 }
+                "CCtorField" if fields.len() == 2 => {
+                    let expr = value_to_core_term(&fields[0])?;
+                    let idx = fields[1].as_int() as usize;
+                    Ok(CoreTerm::CtorField(Rc::new(expr), idx, None))
+                }
                 "CLet" if fields.len() == 3 => {
                     let name = match &fields[0] {
-                        Value::Str(handle) => get_str(*handle).to_string(),
+                        Value::Str(s) => Symbol::intern(s.text()),
                         _ => return Err(format!("Expected Str name in CLet, got {:?}", fields[0])),
                     };
                     let value = value_to_core_term(&fields[1])?;
@@ -186,7 +303,7 @@ fn value_to_core_term(val: &Value) -> Result<CoreTerm, String> {
                 }
                 "CCtor" if fields.len() == 2 => {
                     let name = match &fields[0] {
-                        Value::Str(handle) => get_str(*handle).to_string(),
+                        Value::Str(s) => s.text().to_string(),
                         _ => return Err(format!("Expected Str name in CCtor, got {:?}", fields[0])),
                     };
                     let field_list = match &fields[1] {
@@ -249,7 +366,7 @@ fn value_to_pattern(val: &Value) -> Result<Pattern, String> {
                 }
                 "PVar" if fields.len() == 1 => {
                     match &fields[0] {
-                        Value::Str(handle) => Ok(Pattern::PVar(get_str(*handle).to_string())),
+                        Value::Str(s) => Ok(Pattern::PVar(s.text().to_string())),
                         _ => Err(format!("Expected Str in PVar, got {:?}", fields[0])),
                     }
                 }
@@ -267,7 +384,7 @@ fn value_to_pattern(val: &Value) -> Result<Pattern, String> {
                 }
                 "PEnum" if fields.len() == 2 => {
                     let name = match &fields[0] {
-                        Value::Str(handle) => get_str(*handle).to_string(),
+                        Value::Str(s) => s.text().to_string(),
                         _ => return Err(format!("Expected Str name in PEnum, got {:?}", fields[0])),
                     };
                     let pats = match &fields[1] {
@@ -282,6 +399,13 @@ fn value_to_pattern(val: &Value) -> Result<Pattern, String> {
                     };
                     Ok(Pattern::PEnum(name, pats))
                 }
+                "PBind" if fields.len() == 2 => {
+                    let name = match &fields[0] {
+                        Value::Str(s) => s.text().to_string(),
+                        _ => return Err(format!("Expected Str name in PBind, got {:?}", fields[0])),
+                    };
+                    Ok(Pattern::PBind(name, Box::new(value_to_pattern(&fields[1])?)))
+                }
                 _ => Err(format!("Unknown Pattern constructor: {}", tag_name)),
             }
         }
@@ -307,6 +431,14 @@ fn create_core_bundle(term: &CoreTerm, entrypoint_name: &str, strings: &[String]
         for (i, s) in strings.iter().enumerate() {
             string_table_builder.set(i as u32, s);
         }
+
+        // The self-hosted compiler knows no more about itself than the
+        // runtime it was built with
+        let metadata = BundleMetadata {
+            compiler_version: format!("axis self-hosted (axis-rust-bridge {})", env!("CARGO_PKG_VERSION")),
+            ..BundleMetadata::default()
+        };
+        crate::core_ir::serialize_metadata(&metadata, bundle.reborrow().init_metadata());
         
         let core_term_builder = bundle.init_core_term();
         serialize_core_term(term, core_term_builder);
@@ -370,6 +502,12 @@ fn serialize_core_term<'a>(
             let expr_builder = proj_builder.init_expr();
             serialize_core_term(expr, expr_builder);
         },
+        CoreTerm::CtorField(expr, index, _) => {
+            let mut field_builder = builder.init_c_ctor_field();
+            field_builder.set_index(*index as u32);
+            let expr_builder = field_builder.init_expr();
+            serialize_core_term(expr, expr_builder);
+        },
         CoreTerm::Let(name, value, body, _) => {
             let mut let_builder = builder.init_c_let();
             let_builder.set_name(name);
@@ -451,5 +589,10 @@ fn serialize_pattern<'a>(
                 serialize_pattern(pat, pat_builder);
             }
         },
+        Pattern::PBind(name, inner) => {
+            let mut p_builder = builder.init_p_bind();
+            p_builder.set_name(name);
+            serialize_pattern(inner, p_builder.init_pattern());
+        },
     }
 }
@@ -0,0 +1,165 @@
+//! Round-trip tests for the Core bundle runtime primitives
+//!
+//! A bundle emitted with `axis_emit_core_bundle_to_file` must load back
+//! through `axis_load_core_bundle_from_file` as an identical Value, and
+//! carry the metadata `inspect` prints.
+
+use crate::runtime::core_emit::{axis_emit_core_bundle_to_file, axis_load_core_bundle_from_file};
+use crate::runtime::value::{Value, get_tag_name, init_runtime, intern_tag, str_lit};
+
+fn ctor(name: &str, fields: Vec<Value>) -> Value {
+    Value::Ctor { tag: intern_tag(name), fields }
+}
+
+fn s(text: &str) -> Value {
+    str_lit(text)
+}
+
+fn expect_ok(result: Value) -> Value {
+    match result {
+        Value::Ctor { tag, mut fields } if get_tag_name(tag) == "Ok" && fields.len() == 1 => fields.remove(0),
+        other => panic!("Expected Ok(..), got {:?}", other),
+    }
+}
+
+#[test]
+fn test_core_bundle_round_trip() {
+    init_runtime();
+
+    // main = λx. match (x, 1) { (a, PInt 1) => Some(a.1); _ => if true then "s" else () }
+    let body = ctor("CMatch", vec![
+        ctor("CTuple", vec![Value::List(vec![
+            ctor("CVar", vec![s("x")]),
+            ctor("CIntLit", vec![Value::Int(1)]),
+        ])]),
+        Value::List(vec![
+            ctor("MatchCase", vec![
+                ctor("PTuple", vec![Value::List(vec![
+                    ctor("PVar", vec![s("a")]),
+                    ctor("PInt", vec![Value::Int(1)]),
+                ])]),
+                ctor("CCtor", vec![s("Some"), Value::List(vec![
+                    ctor("CProj", vec![ctor("CVar", vec![s("a")]), Value::Int(1)]),
+                ])]),
+            ]),
+            ctor("MatchCase", vec![
+                ctor("PEnum", vec![s("Pair"), Value::List(vec![
+                    ctor("PBool", vec![Value::Bool(true)]),
+                    ctor("PUnit", vec![]),
+                ])]),
+                ctor("CIf", vec![
+                    ctor("CBoolLit", vec![Value::Bool(true)]),
+                    ctor("CStrLit", vec![s("s")]),
+                    ctor("CUnitLit", vec![]),
+                ]),
+            ]),
+        ]),
+    ]);
+    let root = ctor("CLet", vec![
+        s("main"),
+        ctor("CLam", vec![s("x"), body]),
+        ctor("CApp", vec![ctor("CVar", vec![s("main")]), ctor("CUnitLit", vec![])]),
+    ]);
+    let bundle = ctor("CoreBundle", vec![
+        ctor("StringTable", vec![Value::List(vec![s("alpha"), s("beta")]), Value::Int(2)]),
+        Value::List(vec![root]),
+    ]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = s(dir.path().join("round_trip.coreir").to_str().unwrap());
+
+    expect_ok(axis_emit_core_bundle_to_file(Value::Tuple(vec![bundle.clone(), path.clone()])));
+    let loaded = expect_ok(axis_load_core_bundle_from_file(path));
+
+    assert_eq!(loaded, bundle);
+}
+
+#[test]
+fn test_load_core_bundle_missing_file_is_err() {
+    init_runtime();
+
+    let result = axis_load_core_bundle_from_file(s("/nonexistent/bundle.coreir"));
+    match result {
+        Value::Ctor { tag, .. } => assert_eq!(get_tag_name(tag), "Err"),
+        other => panic!("Expected Err(..), got {:?}", other),
+    }
+}
+
+#[test]
+fn test_load_core_bundle_enforces_limits() {
+    use crate::core_ir::{BundleLoadOptions, load_core_bundle};
+
+    init_runtime();
+
+    // λx0. λx1. ... λx49. x0  (51 nested terms)
+    let mut term = ctor("CVar", vec![s("x0")]);
+    for i in (0..50).rev() {
+        term = ctor("CLam", vec![s(&format!("x{}", i)), term]);
+    }
+    let bundle = ctor("CoreBundle", vec![
+        ctor("StringTable", vec![Value::List(vec![]), Value::Int(0)]),
+        Value::List(vec![term]),
+    ]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("nested.coreir");
+    let path = file.to_str().unwrap();
+    expect_ok(axis_emit_core_bundle_to_file(Value::Tuple(vec![bundle, s(path)])));
+
+    let shallow = BundleLoadOptions { max_depth: 10, ..BundleLoadOptions::default() };
+    let err = load_core_bundle(path, &shallow).err().expect("limit not enforced");
+    assert!(err.contains("nesting depth limit"), "unexpected error: {}", err);
+
+    let few_nodes = BundleLoadOptions { max_nodes: 20, ..BundleLoadOptions::default() };
+    let err = load_core_bundle(path, &few_nodes).err().expect("limit not enforced");
+    assert!(err.contains("node limit"), "unexpected error: {}", err);
+
+    let tiny = BundleLoadOptions { max_size: 16, ..BundleLoadOptions::default() };
+    let err = load_core_bundle(path, &tiny).err().expect("limit not enforced");
+    assert!(err.contains("byte limit"), "unexpected error: {}", err);
+
+    // Defaults admit the bundle; trusted ignores the per-field limits
+    assert!(load_core_bundle(path, &BundleLoadOptions::default()).is_ok());
+    let trusted = BundleLoadOptions { max_depth: 1, max_nodes: 1, max_size: 1, trusted: true, ..BundleLoadOptions::default() };
+    assert!(load_core_bundle(path, &trusted).is_ok());
+}
+
+#[test]
+fn test_emitted_bundle_names_the_self_hosted_compiler() {
+    use crate::core_ir::{BundleLoadOptions, inspect_core_bundle, read_bundle_metadata};
+
+    init_runtime();
+
+    let bundle = ctor("CoreBundle", vec![
+        ctor("StringTable", vec![Value::List(vec![]), Value::Int(0)]),
+        Value::List(vec![ctor("CUnitLit", vec![])]),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("stamped.coreir");
+    let path = file.to_str().unwrap();
+    expect_ok(axis_emit_core_bundle_to_file(Value::Tuple(vec![bundle, s(path)])));
+
+    let metadata = read_bundle_metadata(&std::fs::read(path).unwrap(), &BundleLoadOptions::default()).unwrap();
+    assert_eq!(metadata.compiler_version, format!("axis self-hosted (axis-rust-bridge {})", env!("CARGO_PKG_VERSION")));
+    assert!(metadata.sources.is_empty());
+    let summary = inspect_core_bundle(path, &BundleLoadOptions::default()).unwrap();
+    assert!(summary.ends_with(&format!("\n  Compiler: {}", metadata.compiler_version)), "{}", summary);
+}
+
+#[test]
+fn test_metadata_summary_lists_inputs_and_flags() {
+    use crate::core_ir::BundleMetadata;
+
+    assert_eq!(BundleMetadata::default().summary(), "");
+    let metadata = BundleMetadata {
+        compiler_version: "axis-compiler 0.1.0".to_string(),
+        compiler_commit: "8e2c80a1b2c3".to_string(),
+        sources: vec![("main.ax".to_string(), "fnv1a:af63dc4c8601ec8c".to_string())],
+        registries: vec![("axis.axreg".to_string(), "fnv1a:cbf29ce484222325".to_string())],
+        flags: vec!["--optimize".to_string()],
+    };
+    assert_eq!(
+        metadata.summary(),
+        "\n  Compiler: axis-compiler 0.1.0 (8e2c80a1b2c3)\n  Source: main.ax fnv1a:af63dc4c8601ec8c\n  Registry: axis.axreg fnv1a:cbf29ce484222325\n  Flags: --optimize"
+    );
+}
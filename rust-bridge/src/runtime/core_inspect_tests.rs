@@ -0,0 +1,115 @@
+// Tests for `inspect --deep` (core_inspect.rs)
+
+use crate::core_arena::ArenaProgram;
+use crate::core_inspect::{count_nodes, deep_report, format_tree, functions};
+use crate::core_ir::{CoreProgram, CoreTerm, Pattern};
+use std::rc::Rc;
+
+fn var(name: &str) -> Rc<CoreTerm> {
+    Rc::new(CoreTerm::Var(name.into(), None))
+}
+
+fn app(func: Rc<CoreTerm>, arg: CoreTerm) -> Rc<CoreTerm> {
+    Rc::new(CoreTerm::App(func, Rc::new(arg), None))
+}
+
+// let add = \a -> \b -> a + b in
+// let main = \args -> match add(1)(2) { 3 => "three"; n => print(n) } in
+// let axis_entry = main in axis_entry
+fn program() -> ArenaProgram {
+    let add = CoreTerm::Lam(
+        "a".into(),
+        Rc::new(CoreTerm::Lam("b".into(), app(app(var("int_add"), CoreTerm::Var("a".into(), None)), CoreTerm::Var("b".into(), None)), None)),
+        None,
+    );
+    let scrutinee = app(app(var("add"), CoreTerm::IntLit(1, None)), CoreTerm::IntLit(2, None));
+    let body = CoreTerm::Match(
+        scrutinee,
+        vec![
+            (Pattern::PInt(3), CoreTerm::StrLit("three".to_string(), None)),
+            (Pattern::PVar("n".to_string()), CoreTerm::App(var("print"), var("n"), None)),
+        ],
+        None,
+    );
+    let main = CoreTerm::Lam("args".into(), Rc::new(body), None);
+    let root = CoreTerm::Let(
+        "add".into(),
+        Rc::new(add),
+        Rc::new(CoreTerm::Let(
+            "main".into(),
+            Rc::new(main),
+            Rc::new(CoreTerm::Let("axis_entry".into(), var("main"), var("axis_entry"), None)),
+            None,
+        )),
+        None,
+    );
+    ArenaProgram::from_program(&CoreProgram { strings: vec!["three".to_string()], root_term: root, entrypoint_id: 0 })
+}
+
+#[test]
+fn test_counts_nodes_by_kind() {
+    let program = program();
+    let (total, by_kind) = count_nodes(program.root_term());
+    assert_eq!(total, 23);
+    assert_eq!(by_kind["let"], 3);
+    assert_eq!(by_kind["lam"], 3);
+    assert_eq!(by_kind["app"], 5);
+    assert_eq!(by_kind["var"], 8);
+    assert_eq!(by_kind["literal.int"], 2);
+    assert_eq!(by_kind["literal.str"], 1);
+    assert_eq!(by_kind["match"], 1);
+    assert_eq!(by_kind.get("if"), None);
+}
+
+#[test]
+fn test_lists_functions_with_arities() {
+    // The alias `axis_entry = main` is not a function of its own
+    assert_eq!(functions(&program()), vec![("add".to_string(), 2), ("main".to_string(), 1)]);
+}
+
+#[test]
+fn test_tree_is_cut_at_the_depth() {
+    let program = program();
+    let entry = program.root_term();
+    assert_eq!(format_tree(entry, 1), "let add\n  ... 22 nodes\n");
+    assert_eq!(
+        format_tree(entry, 3),
+        concat!(
+            "let add\n",
+            "  lam a\n",
+            "    lam b\n",
+            "      ... 5 nodes\n",
+            "  let main\n",
+            "    lam args\n",
+            "      ... 10 nodes\n",
+            "    let axis_entry\n",
+            "      ... 2 nodes\n",
+        )
+    );
+}
+
+#[test]
+fn test_report_prints_the_entry_point_through_its_alias() {
+    let report = deep_report(&program(), 8);
+    assert!(report.starts_with("Nodes: 23\n  app                   5\n"), "{}", report);
+    assert!(report.contains("\nFunctions: 2\n  add/2\n  main/1\n"), "{}", report);
+    assert!(report.contains("\nString table: 1 entries\n     0  \"three\"\n"), "{}", report);
+    assert!(
+        report.ends_with(concat!(
+            "Entry point main (to depth 8):\n",
+            "  lam args\n",
+            "    match\n",
+            "      app\n",
+            "        app\n",
+            "          var add\n",
+            "          literal.int 1\n",
+            "        literal.int 2\n",
+            "      PInt(3) => literal.str \"three\"\n",
+            "      PVar(\"n\") => app\n",
+            "        var print\n",
+            "        var n\n",
+        )),
+        "{}",
+        report
+    );
+}
@@ -0,0 +1,665 @@
+// Emit portable C99 from Core IR (backend: `--backend c`)
+//
+// Same calling convention as emit_rust: every top-level definition becomes
+// a unary function over AxValue *, several parameters arriving packed in
+// one tuple. Terms are emitted as statements that store a new reference in
+// a destination variable, so ownership follows the contract in
+// c/axis_runtime.h: a local is released when its scope ends, and a tail
+// self-call releases everything in scope before jumping back to the top of
+// the function (constant stack for tail recursion).
+//
+// The generated program links c/axis_runtime.c, which the backend ships
+// next to it. Anything the Rust emitter would turn into a closure or a
+// shim the C runtime lacks is reported instead of emitted: lambdas outside
+// a top-level parameter list, functions used as values, calls through
+// local variables, and foreign symbols missing from c_primitive.
+
+use crate::core_arena::{TermRef, TermView};
+use crate::core_ir::{Pattern, Span};
+use crate::runtime::emit_rust::constructor_name;
+use crate::runtime::operators::{operator_function, OPERATOR_FUNCTIONS};
+use crate::runtime::symbol_index::SymbolIndex;
+use std::collections::HashMap;
+
+pub const RUNTIME_HEADER: &str = include_str!("c/axis_runtime.h");
+pub const RUNTIME_SOURCE: &str = include_str!("c/axis_runtime.c");
+
+/// C implementations of foreign symbols: (symbol, function, takes the
+/// call's span). The operators' functions are in runtime/operators.rs.
+const C_PRIMITIVES: &[(&str, &str, bool)] = &[
+    ("axis_int_add", "ax_prim_add", false),
+    ("value_cmp", "ax_prim_value_cmp", false),
+    ("axis_value_cmp", "ax_prim_value_cmp", false),
+    ("__not__", "ax_prim_not", false),
+    ("str_concat", "ax_prim_str_concat", false),
+    ("axis_str_concat", "ax_prim_str_concat", false),
+    ("str_len", "ax_prim_str_len", false),
+    ("axis_str_len", "ax_prim_str_len", false),
+    ("str_slice", "ax_prim_str_slice", false),
+    ("axis_str_slice", "ax_prim_str_slice", false),
+    ("int_to_str", "ax_prim_int_to_str", false),
+    ("str_to_int", "ax_prim_str_to_int", false),
+    ("str_split", "ax_prim_str_split", false),
+    ("axis_str_split", "ax_prim_str_split", false),
+    ("str_trim", "ax_prim_str_trim", false),
+    ("axis_str_trim", "ax_prim_str_trim", false),
+    ("str_contains", "ax_prim_str_contains", false),
+    ("axis_str_contains", "ax_prim_str_contains", false),
+    ("str_starts_with", "ax_prim_str_starts_with", false),
+    ("axis_str_starts_with", "ax_prim_str_starts_with", false),
+    ("str_ends_with", "ax_prim_str_ends_with", false),
+    ("axis_str_ends_with", "ax_prim_str_ends_with", false),
+    ("str_replace", "ax_prim_str_replace", false),
+    ("axis_str_replace", "ax_prim_str_replace", false),
+    ("str_to_lower", "ax_prim_str_to_lower", false),
+    ("axis_str_to_lower", "ax_prim_str_to_lower", false),
+    ("str_to_upper", "ax_prim_str_to_upper", false),
+    ("axis_str_to_upper", "ax_prim_str_to_upper", false),
+    ("str_join", "ax_prim_str_join", false),
+    ("axis_str_join", "ax_prim_str_join", false),
+    ("char_is_alpha", "ax_prim_char_is_alpha", false),
+    ("axis_char_is_alpha", "ax_prim_char_is_alpha", false),
+    ("char_is_digit", "ax_prim_char_is_digit", false),
+    ("axis_char_is_digit", "ax_prim_char_is_digit", false),
+    ("char_is_alnum", "ax_prim_char_is_alnum", false),
+    ("axis_char_is_alnum", "ax_prim_char_is_alnum", false),
+    ("char_is_whitespace", "ax_prim_char_is_whitespace", false),
+    ("axis_char_is_whitespace", "ax_prim_char_is_whitespace", false),
+    ("char_is_ident_start", "ax_prim_char_is_ident_start", false),
+    ("axis_char_is_ident_start", "ax_prim_char_is_ident_start", false),
+    ("char_is_ident_char", "ax_prim_char_is_ident_char", false),
+    ("axis_char_is_ident_char", "ax_prim_char_is_ident_char", false),
+    ("char_to_digit", "ax_prim_char_to_digit", false),
+    ("axis_char_to_digit", "ax_prim_char_to_digit", false),
+    ("char_to_lower", "ax_prim_char_to_lower", false),
+    ("axis_char_to_lower", "ax_prim_char_to_lower", false),
+    ("char_to_upper", "ax_prim_char_to_upper", false),
+    ("axis_char_to_upper", "ax_prim_char_to_upper", false),
+    ("char_from_str", "ax_prim_char_from_str", false),
+    ("axis_char_from_str", "ax_prim_char_from_str", false),
+    ("char_to_str", "ax_prim_char_to_str", false),
+    ("axis_char_to_str", "ax_prim_char_to_str", false),
+    ("sb_new", "ax_prim_sb_new", false),
+    ("axis_sb_new", "ax_prim_sb_new", false),
+    ("sb_push", "ax_prim_sb_push", false),
+    ("axis_sb_push", "ax_prim_sb_push", false),
+    ("sb_push_int", "ax_prim_sb_push_int", false),
+    ("axis_sb_push_int", "ax_prim_sb_push_int", false),
+    ("sb_build", "ax_prim_sb_build", false),
+    ("axis_sb_build", "ax_prim_sb_build", false),
+    ("__tuple__", "ax_prim_tuple", false),
+    ("tuple_field", "ax_prim_tuple_field", false),
+    ("proj", "ax_prim_tuple_field", false),
+    ("ctor_field", "ax_prim_ctor_field", false),
+    ("__ctor_field__", "ax_prim_ctor_field", false),
+    ("io_print", "ax_prim_io_print", false),
+    ("axis_io_print", "ax_prim_io_print", false),
+    ("io_eprint", "ax_prim_io_eprint", false),
+    ("io_print_buffered", "ax_prim_io_print_buffered", false),
+    ("axis_io_print_buffered", "ax_prim_io_print_buffered", false),
+    ("io_println", "ax_prim_io_println", false),
+    ("axis_io_println", "ax_prim_io_println", false),
+    ("io_flush", "ax_prim_io_flush", false),
+    ("axis_io_flush", "ax_prim_io_flush", false),
+    ("debug_trace", "ax_prim_debug_trace", false),
+    ("exit", "ax_prim_proc_exit", false),
+    ("axis_proc_exit", "ax_prim_proc_exit", false),
+    ("assert_eq", "ax_prim_assert_eq", true),
+    ("axis_assert_eq", "ax_prim_assert_eq", true),
+    ("assert_true", "ax_prim_assert_true", true),
+    ("axis_assert_true", "ax_prim_assert_true", true),
+    ("fail", "ax_prim_fail", true),
+    ("axis_fail", "ax_prim_fail", true),
+];
+
+fn c_primitive(symbol: &str) -> Option<(&'static str, bool)> {
+    if let Some(op) = operator_function(symbol) {
+        return Some((op.c, false));
+    }
+    C_PRIMITIVES
+        .iter()
+        .find(|(name, _, _)| *name == symbol)
+        .map(|&(_, function, takes_span)| (function, takes_span))
+}
+
+struct Function {
+    c_name: String,
+    arity: usize,
+}
+
+pub fn emit_c_from_core(core: TermRef<'_>) -> Result<String, String> {
+    // Pass 1: every top-level name, so calls resolve regardless of order
+    let mut functions: HashMap<String, Function> = HashMap::new();
+    let mut definitions: Vec<(&str, TermRef<'_>)> = Vec::new();
+    let mut current = core;
+    while let TermView::Let(name, value, body, _) = current.view() {
+        let arity = match alias_target(value, &functions) {
+            Some(target) => functions[target].arity,
+            None => lambda_arity(value),
+        };
+        let entry = if name == "main" { "axis_entry" } else { name.as_str() };
+        let c_name = format!("ax_fn_{}", c_ident(entry));
+        // First definition of a C name wins, as in emit_rust
+        if !functions.values().any(|f| f.c_name == c_name) {
+            definitions.push((name.as_str(), value));
+        }
+        functions.entry(name.to_string()).or_insert(Function { c_name, arity });
+        current = body;
+    }
+    if !functions.values().any(|f| f.c_name == "ax_fn_axis_entry") {
+        return Err("program has no entry point (main or axis_entry)".to_string());
+    }
+
+    let mut output = String::new();
+    output.push_str("/* Generated by axis-rust-bridge (C backend) */\n");
+    output.push_str("#include \"axis_runtime.h\"\n\n");
+    for (name, _) in &definitions {
+        output.push_str(&format!("static AxValue *{}(AxValue *args);\n", functions[*name].c_name));
+    }
+    output.push('\n');
+
+    // Pass 2: function bodies
+    for &(name, value) in &definitions {
+        let function = &functions[name];
+        if let Some(target) = alias_target(value, &functions) {
+            output.push_str(&format!(
+                "static AxValue *{}(AxValue *args) {{\n    return {}(args);\n}}\n\n",
+                function.c_name, functions[target].c_name
+            ));
+            continue;
+        }
+        let mut emitter = CEmitter {
+            functions: &functions,
+            current: name,
+            scope: Vec::new(),
+            owned: Vec::new(),
+            next_id: 0,
+            uses_top: false,
+            out: String::new(),
+        };
+        emitter.emit_function(value).map_err(|e| format!("in function '{}': {}", name, e))?;
+        output.push_str(&format!("static AxValue *{}(AxValue *args) {{\n", function.c_name));
+        output.push_str(&emitter.out);
+        output.push_str("}\n\n");
+    }
+
+    // The program's constructors, so a value prints by its short name where
+    // that is unambiguous (ax_tag_display)
+    let mut constructors = Vec::new();
+    constructor_names(core, &mut constructors);
+    output.push_str("static const char *const ax_program_tags[] = {");
+    for constructor in &constructors {
+        output.push_str(&format!("{}, ", c_string(constructor)));
+    }
+    output.push_str("NULL};\n\n");
+    output.push_str(
+        "int main(int argc, char **argv) {\n    ax_register_tags(ax_program_tags);\n    return ax_main(argc, argv, ax_fn_axis_entry);\n}\n",
+    );
+    Ok(output)
+}
+
+// Every constructor `term` builds or matches, first use first
+fn constructor_names<'a>(term: TermRef<'a>, names: &mut Vec<&'a str>) {
+    fn add<'a>(name: &'a str, names: &mut Vec<&'a str>) {
+        let name = constructor_name(name);
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    fn in_pattern<'a>(pattern: &'a Pattern, names: &mut Vec<&'a str>) {
+        match pattern {
+            Pattern::PEnum(name, fields) => {
+                add(name, names);
+                fields.iter().for_each(|field| in_pattern(field, names));
+            }
+            Pattern::PTuple(elems) => elems.iter().for_each(|elem| in_pattern(elem, names)),
+            Pattern::PBind(_, inner) => in_pattern(inner, names),
+            Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit | Pattern::PVar(_) => {}
+        }
+    }
+    match term.view() {
+        TermView::Ctor(name, fields, _) => {
+            add(name.as_str(), names);
+            fields.iter().for_each(|field| constructor_names(field, names));
+        }
+        TermView::Match(scrutinee, arms, _) => {
+            constructor_names(scrutinee, names);
+            for (pattern, body) in arms.iter() {
+                in_pattern(pattern, names);
+                constructor_names(body, names);
+            }
+        }
+        TermView::Lam(_, body, _) | TermView::Proj(body, _, _) | TermView::CtorField(body, _, _) => constructor_names(body, names),
+        TermView::App(first, second, _) | TermView::Let(_, first, second, _) => {
+            constructor_names(first, names);
+            constructor_names(second, names);
+        }
+        TermView::If(cond, then_branch, else_branch, _) => {
+            constructor_names(cond, names);
+            constructor_names(then_branch, names);
+            constructor_names(else_branch, names);
+        }
+        TermView::Tuple(elems, _) => elems.iter().for_each(|elem| constructor_names(elem, names)),
+        TermView::IntLit(..) | TermView::BoolLit(..) | TermView::UnitLit(_) | TermView::StrLit(..) | TermView::Var(..) => {}
+    }
+}
+
+// Number of leading lambdas
+fn lambda_arity(term: TermRef<'_>) -> usize {
+    let mut arity = 0;
+    let mut current = term;
+    while let TermView::Lam(_, body, _) = current.view() {
+        arity += 1;
+        current = body;
+    }
+    arity
+}
+
+// `axis_entry = main`: a top-level value that is another function
+fn alias_target<'a>(value: TermRef<'a>, functions: &HashMap<String, Function>) -> Option<&'a str> {
+    match value.view() {
+        TermView::Var(name, _) if functions.get(name.as_str()).is_some_and(|f| f.arity > 0) => Some(name.as_str()),
+        _ => None,
+    }
+}
+
+fn strip_namespaces(name: &str) -> &str {
+    match name.rfind("::") {
+        Some(idx) => &name[idx + 2..],
+        None => name,
+    }
+}
+
+// Axis names may contain `.`, `-` and `::`; locals and functions carry a
+// prefix, so C keywords cannot collide
+fn c_ident(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
+
+// C string literal; non-printable and non-ASCII bytes as octal escapes,
+// `?` escaped against trigraphs
+fn c_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for byte in s.bytes() {
+        match byte {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'?' => out.push_str("\\?"),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            0x20..=0x7e => out.push(byte as char),
+            _ => out.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn c_int(n: i64) -> String {
+    if n == i64::MIN {
+        "(-INT64_C(9223372036854775807) - 1)".to_string()
+    } else {
+        format!("INT64_C({})", n)
+    }
+}
+
+// Span arguments of the assertion primitives
+fn c_span(span: &Option<Span>) -> String {
+    match span {
+        Some(sp) => format!("{}, {}, {}", c_string(&sp.file), sp.line, sp.column),
+        None => "NULL, 0, 0".to_string(),
+    }
+}
+
+// App(App(f, a), b) -> (f, [a, b])
+fn collect_app_args(term: TermRef<'_>) -> (TermRef<'_>, Vec<TermRef<'_>>) {
+    let mut args = Vec::new();
+    let mut current = term;
+    while let TermView::App(func, arg, _) = current.view() {
+        args.push(arg);
+        current = func;
+    }
+    args.reverse();
+    (current, args)
+}
+
+struct CEmitter<'a> {
+    functions: &'a HashMap<String, Function>,
+    // Axis name of the function being emitted (tail self-calls)
+    current: &'a str,
+    // Axis name -> C local, innermost last
+    scope: Vec<(String, String)>,
+    // Owned C locals in scope, released before a tail self-call
+    owned: Vec<String>,
+    next_id: usize,
+    // A tail self-call jumps to the `ax_top` label
+    uses_top: bool,
+    out: String,
+}
+
+impl<'a> CEmitter<'a> {
+    fn line(&mut self, indent: usize, text: &str) {
+        self.out.push_str(&"    ".repeat(indent));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn fresh(&mut self, prefix: &str, name: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}_{}", prefix, c_ident(name), self.next_id)
+    }
+
+    // " (did you mean ...)" for an unknown name, from the top-level
+    // functions and `others`; empty when nothing is close
+    fn did_you_mean<'n>(&self, name: &str, others: impl IntoIterator<Item = &'n str>) -> String {
+        let mut symbols = SymbolIndex::default();
+        symbols.extend(self.functions.keys().map(String::as_str));
+        symbols.extend(others);
+        symbols.did_you_mean(strip_namespaces(name)).map(|hint| format!(" ({})", hint)).unwrap_or_default()
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.scope.iter().any(|(axis, _)| axis == name)
+    }
+
+    fn emit_function(&mut self, value: TermRef<'a>) -> Result<(), String> {
+        let mut params = Vec::new();
+        let mut body = value;
+        while let TermView::Lam(param, inner, _) = body.view() {
+            params.push(param.as_str());
+            body = inner;
+        }
+        let locals: Vec<String> = params.iter().map(|p| self.fresh("v_", p)).collect();
+
+        let mut declarations = String::from("    AxValue *result;\n");
+        for local in &locals {
+            declarations.push_str(&format!("    AxValue *{};\n", local));
+        }
+
+        // Parameters are owned: a single one takes over `args`, several are
+        // copied out of the packed tuple
+        match locals.as_slice() {
+            [] => self.line(1, "ax_release(args);"),
+            [only] => self.line(1, &format!("{} = args;", only)),
+            _ => {
+                for (i, local) in locals.iter().enumerate() {
+                    self.line(1, &format!("{} = ax_arg(args, {});", local, i));
+                }
+                self.line(1, "ax_release(args);");
+            }
+        }
+        for (param, local) in params.iter().zip(&locals) {
+            self.scope.push((param.to_string(), local.clone()));
+            self.owned.push(local.clone());
+        }
+        self.emit(body, "result", 1, true)?;
+        for local in &locals {
+            self.line(1, &format!("ax_release({});", local));
+        }
+        self.line(1, "return result;");
+
+        let label = if self.uses_top { "ax_top:\n" } else { "" };
+        self.out = format!("{}{}{}", declarations, label, self.out);
+        Ok(())
+    }
+
+    // Evaluate `term` into a new temporary holding an owned reference
+    fn emit_temp(&mut self, term: TermRef<'a>, indent: usize) -> Result<String, String> {
+        let temp = self.fresh("t", "");
+        self.line(indent, &format!("AxValue *{};", temp));
+        self.emit(term, &temp, indent, false)?;
+        Ok(temp)
+    }
+
+    // UNARY INVARIANT: several arguments are packed into one tuple
+    fn emit_args(&mut self, args: &[TermRef<'a>], indent: usize) -> Result<String, String> {
+        if let [only] = args {
+            return self.emit_temp(*only, indent);
+        }
+        let mut temps = Vec::new();
+        for &arg in args {
+            temps.push(self.emit_temp(arg, indent)?);
+        }
+        let packed = self.fresh("t", "");
+        self.line(indent, &format!("AxValue *{} = ax_tuple({}, {});", packed, temps.len(), temps.join(", ")));
+        Ok(packed)
+    }
+
+    fn emit(&mut self, term: TermRef<'a>, dest: &str, indent: usize, tail: bool) -> Result<(), String> {
+        match term.view() {
+            TermView::IntLit(n, _) => self.line(indent, &format!("{} = ax_int({});", dest, c_int(*n))),
+            TermView::BoolLit(b, _) => self.line(indent, &format!("{} = ax_bool({});", dest, *b as i32)),
+            TermView::UnitLit(_) => self.line(indent, &format!("{} = ax_unit();", dest)),
+            TermView::StrLit(s, _) => {
+                self.line(indent, &format!("{} = ax_str({}, {});", dest, c_string(s), s.len()))
+            }
+
+            TermView::Var(name, _) => {
+                let value = self.var_value(name)?;
+                self.line(indent, &format!("{} = {};", dest, value));
+            }
+
+            TermView::Lam(param, _, _) => {
+                return Err(format!("lambda (\\{}) outside a top-level definition", param));
+            }
+
+            TermView::App(_, _, span) => {
+                let (base, args) = collect_app_args(term);
+                let TermView::Var(name, _) = base.view() else {
+                    return Err("call of a computed function".to_string());
+                };
+                if self.is_local(name) {
+                    return Err(format!("call through local variable '{}'", name));
+                }
+                self.line(indent, "{");
+                let arg = self.emit_args(&args, indent + 1)?;
+                if tail && name == self.current {
+                    // TAIL-CALL OPTIMIZATION: release the frame, rebind, restart
+                    let owned: Vec<String> = self.owned.iter().rev().cloned().collect();
+                    for local in owned {
+                        self.line(indent + 1, &format!("ax_release({});", local));
+                    }
+                    self.line(indent + 1, &format!("args = {};", arg));
+                    self.line(indent + 1, "goto ax_top;");
+                    self.uses_top = true;
+                } else if let Some(function) = self.functions.get(name.as_str()) {
+                    let call = format!("{} = {}({});", dest, function.c_name, arg);
+                    self.line(indent + 1, &call);
+                } else if let Some((primitive, takes_span)) = c_primitive(strip_namespaces(name)) {
+                    let call = if takes_span {
+                        format!("{} = {}({}, {});", dest, primitive, arg, c_span(span))
+                    } else {
+                        format!("{} = {}({});", dest, primitive, arg)
+                    };
+                    self.line(indent + 1, &call);
+                } else {
+                    let known = C_PRIMITIVES.iter().map(|(symbol, _, _)| *symbol);
+                    let hint = self.did_you_mean(name, known.chain(OPERATOR_FUNCTIONS.iter().map(|op| op.function)));
+                    return Err(format!("foreign function '{}' has no C implementation{}", name, hint));
+                }
+                self.line(indent, "}");
+            }
+
+            TermView::Tuple(elems, _) => {
+                self.line(indent, "{");
+                let mut temps = Vec::new();
+                for elem in elems.iter() {
+                    temps.push(self.emit_temp(elem, indent + 1)?);
+                }
+                let items: String = temps.iter().map(|t| format!(", {}", t)).collect();
+                self.line(indent + 1, &format!("{} = ax_tuple({}{});", dest, temps.len(), items));
+                self.line(indent, "}");
+            }
+
+            TermView::Ctor(name, fields, _) => {
+                self.line(indent, "{");
+                let mut temps = Vec::new();
+                for field in fields.iter() {
+                    temps.push(self.emit_temp(field, indent + 1)?);
+                }
+                let items: String = temps.iter().map(|t| format!(", {}", t)).collect();
+                let tag = c_string(constructor_name(name));
+                self.line(indent + 1, &format!("{} = ax_ctor({}, {}{});", dest, tag, temps.len(), items));
+                self.line(indent, "}");
+            }
+
+            TermView::Proj(tuple, index, _) => {
+                self.line(indent, "{");
+                let temp = self.emit_temp(tuple, indent + 1)?;
+                // 0-based, as CoreTerm::Proj
+                self.line(indent + 1, &format!("{} = ax_tuple_field({}, {});", dest, temp, index));
+                self.line(indent, "}");
+            }
+
+            TermView::CtorField(ctor, index, _) => {
+                self.line(indent, "{");
+                let temp = self.emit_temp(ctor, indent + 1)?;
+                self.line(indent + 1, &format!("{} = ax_ctor_field({}, {});", dest, temp, index));
+                self.line(indent, "}");
+            }
+
+            TermView::Let(name, value, body, _) => {
+                let local = self.fresh("v_", name);
+                self.line(indent, "{");
+                self.line(indent + 1, &format!("AxValue *{};", local));
+                self.emit(value, &local, indent + 1, false)?;
+                self.scope.push((name.to_string(), local.clone()));
+                self.owned.push(local.clone());
+                self.emit(body, dest, indent + 1, tail)?;
+                self.owned.pop();
+                self.scope.pop();
+                self.line(indent + 1, &format!("ax_release({});", local));
+                self.line(indent, "}");
+            }
+
+            TermView::If(cond, then_branch, else_branch, _) => {
+                self.line(indent, "{");
+                let temp = self.emit_temp(cond, indent + 1)?;
+                self.line(indent + 1, &format!("if (ax_test({})) {{", temp));
+                self.emit(then_branch, dest, indent + 2, tail)?;
+                self.line(indent + 1, "} else {");
+                self.emit(else_branch, dest, indent + 2, tail)?;
+                self.line(indent + 1, "}");
+                self.line(indent, "}");
+            }
+
+            TermView::Match(scrutinee, arms, _) => {
+                self.line(indent, "{");
+                let temp = self.emit_temp(scrutinee, indent + 1)?;
+                self.owned.push(temp.clone());
+                let mut exhaustive = false;
+                for (i, (pattern, body)) in arms.iter().enumerate() {
+                    let mut conds = Vec::new();
+                    let mut binds = Vec::new();
+                    lower_pattern(pattern, &temp, &mut conds, &mut binds);
+                    exhaustive = conds.is_empty();
+                    let opener = match (i, exhaustive) {
+                        (0, false) => format!("if ({}) {{", conds.join(" && ")),
+                        (0, true) => "{".to_string(),
+                        (_, false) => format!("}} else if ({}) {{", conds.join(" && ")),
+                        (_, true) => "} else {".to_string(),
+                    };
+                    self.line(indent + 1, &opener);
+                    let mut locals = Vec::new();
+                    for (name, path) in binds {
+                        let local = self.fresh("v_", &name);
+                        self.line(indent + 2, &format!("AxValue *{} = ax_retain((AxValue *){});", local, path));
+                        self.scope.push((name, local.clone()));
+                        self.owned.push(local.clone());
+                        locals.push(local);
+                    }
+                    self.emit(body, dest, indent + 2, tail)?;
+                    for local in &locals {
+                        self.owned.pop();
+                        self.scope.pop();
+                        self.line(indent + 2, &format!("ax_release({});", local));
+                    }
+                    if exhaustive {
+                        break;
+                    }
+                }
+                // No arm matched: Unit, as in emit_rust
+                if !exhaustive {
+                    let fallback = format!("{} = ax_unit();", dest);
+                    if arms.is_empty() {
+                        self.line(indent + 1, &fallback);
+                    } else {
+                        self.line(indent + 1, "} else {");
+                        self.line(indent + 2, &fallback);
+                        self.line(indent + 1, "}");
+                    }
+                } else {
+                    self.line(indent + 1, "}");
+                }
+                self.owned.pop();
+                self.line(indent + 1, &format!("ax_release({});", temp));
+                self.line(indent, "}");
+            }
+        }
+        Ok(())
+    }
+
+    // C expression for a variable reference (a new reference)
+    fn var_value(&self, name: &str) -> Result<String, String> {
+        if name == "_" {
+            return Ok("ax_unit()".to_string());
+        }
+        if let Some((_, local)) = self.scope.iter().rev().find(|(axis, _)| axis == name) {
+            return Ok(format!("ax_retain({})", local));
+        }
+        if let Some(function) = self.functions.get(name) {
+            if function.arity > 0 {
+                return Err(format!("function '{}' used as a value", name));
+            }
+            // Zero-parameter definition: evaluate it
+            return Ok(format!("{}(ax_unit())", function.c_name));
+        }
+        let stripped = strip_namespaces(name);
+        match stripped {
+            "true" => Ok("ax_bool(1)".to_string()),
+            "false" => Ok("ax_bool(0)".to_string()),
+            // Capitalized: zero-field constructor
+            _ if stripped.starts_with(|c: char| c.is_uppercase()) => {
+                Ok(format!("ax_ctor({}, 0)", c_string(stripped)))
+            }
+            _ => {
+                let hint = self.did_you_mean(name, self.scope.iter().map(|(axis, _)| axis.as_str()));
+                Err(format!("'{}' is not bound (foreign functions can only be called){}", name, hint))
+            }
+        }
+    }
+}
+
+// Tests and bindings of a pattern against the borrowed value at `path`.
+// Tests are joined with && so each ax_item path is only read once its
+// parent has been checked.
+fn lower_pattern(pattern: &Pattern, path: &str, conds: &mut Vec<String>, binds: &mut Vec<(String, String)>) {
+    match pattern {
+        Pattern::PInt(n) => conds.push(format!("ax_is_int({}, {})", path, c_int(*n))),
+        Pattern::PBool(b) => conds.push(format!("ax_is_bool({}, {})", path, *b as i32)),
+        Pattern::PUnit => conds.push(format!("ax_is_unit({})", path)),
+        Pattern::PVar(name) if name == "_" => {}
+        // Capitalized: zero-field constructor, not a binding
+        Pattern::PVar(name) if name.starts_with(|c: char| c.is_uppercase()) => {
+            conds.push(format!("ax_is_ctor({}, {}, 0)", path, c_string(strip_namespaces(name))));
+        }
+        Pattern::PVar(name) => binds.push((name.clone(), path.to_string())),
+        Pattern::PBind(name, inner) => {
+            binds.push((name.clone(), path.to_string()));
+            lower_pattern(inner, path, conds, binds);
+        }
+        Pattern::PTuple(elems) => {
+            conds.push(format!("ax_is_tuple({}, {})", path, elems.len()));
+            for (i, elem) in elems.iter().enumerate() {
+                lower_pattern(elem, &format!("ax_item({}, {})", path, i), conds, binds);
+            }
+        }
+        Pattern::PEnum(tag, fields) => {
+            conds.push(format!("ax_is_ctor({}, {}, {})", path, c_string(constructor_name(tag)), fields.len()));
+            for (i, field) in fields.iter().enumerate() {
+                lower_pattern(field, &format!("ax_item({}, {})", path, i), conds, binds);
+            }
+        }
+    }
+}
@@ -0,0 +1,344 @@
+// Tests for the C backend emitter (emit_c.rs)
+// The end-to-end test compiles the output with `cc` and is skipped when no
+// C compiler is installed.
+
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::{CoreTerm, Pattern};
+use crate::runtime::emit_c::{emit_c_from_core, RUNTIME_HEADER, RUNTIME_SOURCE};
+use std::process::Command;
+use std::rc::Rc;
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn int(n: i64) -> CoreTerm {
+    CoreTerm::IntLit(n, None)
+}
+
+fn str_lit(s: &str) -> CoreTerm {
+    CoreTerm::StrLit(s.to_string(), None)
+}
+
+// Curried application f(a)(b)..., as lowered from f(a, b)
+fn call(f: &str, args: Vec<CoreTerm>) -> CoreTerm {
+    args.into_iter().fold(var(f), |func, arg| CoreTerm::App(Rc::new(func), Rc::new(arg), None))
+}
+
+fn lam(params: &[&str], body: CoreTerm) -> CoreTerm {
+    params.iter().rev().fold(body, |body, p| CoreTerm::Lam((*p).into(), Rc::new(body), None))
+}
+
+fn let_(name: &str, value: CoreTerm, body: CoreTerm) -> CoreTerm {
+    CoreTerm::Let(name.into(), Rc::new(value), Rc::new(body), None)
+}
+
+fn program(definitions: Vec<(&str, CoreTerm)>) -> CoreTerm {
+    definitions
+        .into_iter()
+        .rev()
+        .fold(CoreTerm::UnitLit(None), |rest, (name, value)| let_(name, value, rest))
+}
+
+// count(n, acc) = if n == 0 { acc } else { count(n - 1, acc + n) }
+fn count_fn() -> CoreTerm {
+    lam(
+        &["n", "acc"],
+        CoreTerm::If(
+            Rc::new(call("__eq__", vec![var("n"), int(0)])),
+            Rc::new(var("acc")),
+            Rc::new(call("count", vec![
+                call("__sub__", vec![var("n"), int(1)]),
+                call("__add__", vec![var("acc"), var("n")]),
+            ])),
+            None,
+        ),
+    )
+}
+
+#[test]
+fn test_emit_c_tail_call_is_a_jump() {
+    let core = program(vec![
+        ("count", count_fn()),
+        ("main", lam(&["args"], call("count", vec![int(10), int(0)]))),
+    ]);
+
+    let c_code = emit_c_from_core(ArenaProgram::from_term(&core).root_term()).unwrap();
+    assert!(c_code.contains("static AxValue *ax_fn_count(AxValue *args);"), "{}", c_code);
+    assert!(c_code.contains("goto ax_top;"), "tail self-call must jump:\n{}", c_code);
+    assert!(c_code.contains("ax_fn_axis_entry"), "main must become the entry:\n{}", c_code);
+    assert!(c_code.contains("return ax_main(argc, argv, ax_fn_axis_entry);"));
+}
+
+#[test]
+fn test_emit_c_reports_unsupported_terms() {
+    // Closure in argument position
+    let closure = program(vec![("main", lam(&["args"], call("io_print", vec![lam(&["x"], var("x"))])))]);
+    let err = emit_c_from_core(ArenaProgram::from_term(&closure).root_term()).unwrap_err();
+    assert!(err.contains("lambda"), "{}", err);
+
+    // Foreign symbol without a C implementation
+    let foreign = program(vec![("main", lam(&["args"], call("json_parse", vec![str_lit("{}")])))]);
+    let err = emit_c_from_core(ArenaProgram::from_term(&foreign).root_term()).unwrap_err();
+    assert!(err.contains("'json_parse' has no C implementation"), "{}", err);
+
+    // Misspelled primitive: the closest names are suggested
+    let typo = program(vec![("main", lam(&["args"], call("io_prnt", vec![str_lit("hi")])))]);
+    let err = emit_c_from_core(ArenaProgram::from_term(&typo).root_term()).unwrap_err();
+    assert!(err.ends_with("no C implementation (did you mean one of `io_print`, `io_eprint`?)"), "{}", err);
+
+    // No entry point
+    let no_entry = program(vec![("helper", lam(&["x"], var("x")))]);
+    assert!(emit_c_from_core(ArenaProgram::from_term(&no_entry).root_term()).is_err());
+}
+
+#[test]
+fn test_emit_c_projection_is_zero_based() {
+    // main(args) = proj((10, 20), 1)
+    let pair = CoreTerm::Tuple(vec![int(10), int(20)], None);
+    let core = program(vec![("main", lam(&["args"], CoreTerm::Proj(Rc::new(pair), 1, None)))]);
+
+    let c_code = emit_c_from_core(ArenaProgram::from_term(&core).root_term()).unwrap();
+    let read = c_code.lines().find(|line| line.contains("ax_tuple_field(")).expect("a tuple field read");
+    assert!(read.trim_end().ends_with(", 1);"), "proj index 1 is the second field:\n{}", c_code);
+}
+
+// Emit `core`, compile it with the runtime and return the directory
+// holding the `prog` executable
+fn build_c(core: &CoreTerm) -> tempfile::TempDir {
+    let c_code = emit_c_from_core(ArenaProgram::from_term(core).root_term()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("axis_generated.c"), &c_code).unwrap();
+    std::fs::write(dir.path().join("axis_runtime.h"), RUNTIME_HEADER).unwrap();
+    std::fs::write(dir.path().join("axis_runtime.c"), RUNTIME_SOURCE).unwrap();
+    let build = Command::new("cc")
+        .args(["-std=c99", "-Wall", "-Werror", "-o", "prog", "axis_generated.c", "axis_runtime.c"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(build.status.success(), "cc failed:\n{}\n{}", String::from_utf8_lossy(&build.stderr), c_code);
+    dir
+}
+
+#[test]
+fn test_emit_c_program_runs() {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler (cc) on PATH");
+        return;
+    }
+
+    // describe(x) = match x { Some(v) => "some " ++ int_to_str(v), None => "none" }
+    let describe = lam(
+        &["x"],
+        CoreTerm::Match(
+            Rc::new(var("x")),
+            vec![
+                (
+                    Pattern::PEnum("Some".to_string(), vec![Pattern::PVar("v".to_string())]),
+                    call("str_concat", vec![str_lit("some \"q\"? "), call("int_to_str", vec![var("v")])]),
+                ),
+                (Pattern::PVar("None".to_string()), str_lit("none")),
+            ],
+            None,
+        ),
+    );
+    // main(args) = print(describe(Some(count(1000000, 0)))); print(describe(None));
+    //              match args { Cons(a, _) => str_len(a), _ => 7 }
+    let main = lam(
+        &["args"],
+        let_(
+            "_",
+            call("io_print", vec![call("describe", vec![CoreTerm::Ctor(
+                "Option::Some".to_string(),
+                vec![call("count", vec![int(1_000_000), int(0)])],
+                None,
+            )])]),
+            let_(
+                "_",
+                call("io_print", vec![call("describe", vec![var("None")])]),
+                CoreTerm::Match(
+                    Rc::new(var("args")),
+                    vec![
+                        (
+                            Pattern::PEnum("Cons".to_string(), vec![Pattern::PVar("a".to_string()), Pattern::PVar("_".to_string())]),
+                            call("str_len", vec![var("a")]),
+                        ),
+                        (Pattern::PVar("_".to_string()), int(7)),
+                    ],
+                    None,
+                ),
+            ),
+        ),
+    );
+    let core = program(vec![("count", count_fn()), ("describe", describe), ("main", main)]);
+    let dir = build_c(&core);
+
+    let run = Command::new(dir.path().join("prog")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "some \"q\"? 500000500000none");
+    assert_eq!(run.status.code(), Some(7));
+
+    let run = Command::new(dir.path().join("prog")).arg("héllo").output().unwrap();
+    assert_eq!(run.status.code(), Some(5));
+}
+
+#[test]
+fn test_emit_c_string_builder() {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler (cc) on PATH");
+        return;
+    }
+
+    // fill(sb, n) = if n == 0 { sb } else { fill(sb_push(sb_push_int(sb, n), ","), n - 1) }
+    let fill = lam(
+        &["sb", "n"],
+        CoreTerm::If(
+            Rc::new(call("__eq__", vec![var("n"), int(0)])),
+            Rc::new(var("sb")),
+            Rc::new(call("fill", vec![
+                call("axis_sb_push", vec![call("axis_sb_push_int", vec![var("sb"), var("n")]), str_lit(",")]),
+                call("__sub__", vec![var("n"), int(1)]),
+            ])),
+            None,
+        ),
+    );
+    // main(args) = print(sb_build(fill(sb_new(), 3))); str_len(sb_build(fill(sb_new(), 30)))
+    let built = |n| call("axis_sb_build", vec![call("fill", vec![call("axis_sb_new", vec![CoreTerm::UnitLit(None)]), int(n)])]);
+    let main = lam(
+        &["args"],
+        let_("_", call("io_print", vec![built(3)]), call("str_len", vec![built(30)])),
+    );
+    let dir = build_c(&program(vec![("fill", fill), ("main", main)]));
+
+    let run = Command::new(dir.path().join("prog")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "3,2,1,");
+    // 9 one-digit and 21 two-digit numbers, each followed by a comma
+    assert_eq!(run.status.code(), Some(9 * 2 + 21 * 3));
+}
+
+#[test]
+fn test_emit_c_string_utilities() {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler (cc) on PATH");
+        return;
+    }
+
+    // main(args) = print(str_join(str_split(to_upper(trim(" a-b-c ")), "-"), "+"));
+    //              print(str_replace("one two", "o", "0"));
+    //              if str_contains("haystack", "st") && str_ends_with("haystack", "ck") { 3 } else { 4 }
+    let trimmed = call("axis_str_to_upper", vec![call("axis_str_trim", vec![str_lit(" a-b-c ")])]);
+    let joined = call("axis_str_join", vec![call("axis_str_split", vec![trimmed, str_lit("-")]), str_lit("+")]);
+    let found = call("__and__", vec![
+        call("axis_str_contains", vec![str_lit("haystack"), str_lit("st")]),
+        call("axis_str_ends_with", vec![str_lit("haystack"), str_lit("ck")]),
+    ]);
+    let main = lam(
+        &["args"],
+        let_(
+            "_",
+            call("io_print", vec![joined]),
+            let_(
+                "_",
+                call("io_print", vec![call("axis_str_replace", vec![str_lit("one two"), str_lit("o"), str_lit("0")])]),
+                CoreTerm::If(Rc::new(found), Rc::new(int(3)), Rc::new(int(4)), None),
+            ),
+        ),
+    );
+    let dir = build_c(&program(vec![("main", main)]));
+
+    let run = Command::new(dir.path().join("prog")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "A+B+C0ne tw0");
+    assert_eq!(run.status.code(), Some(3));
+}
+
+#[test]
+fn test_emit_c_qualified_constructors() {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler (cc) on PATH");
+        return;
+    }
+
+    // main(args) = let ok = http.Status.Ok;
+    //              println(match ok { db.Reply.Ok => 1, http.Status.Ok => 2 });
+    //              println(ok); println(geo.Shape.Circle(3)); 0
+    let ctor = |name: &str, fields| CoreTerm::Ctor(name.to_string(), fields, None);
+    let matched = CoreTerm::Match(
+        Rc::new(var("ok")),
+        vec![
+            (Pattern::PEnum("db.Reply.Ok".to_string(), vec![]), int(1)),
+            (Pattern::PEnum("http.Status.Ok".to_string(), vec![]), int(2)),
+        ],
+        None,
+    );
+    let body = let_(
+        "ok",
+        ctor("http.Status.Ok", vec![]),
+        let_(
+            "_",
+            call("io_println", vec![matched]),
+            let_(
+                "_",
+                call("io_println", vec![var("ok")]),
+                let_("_", call("io_println", vec![ctor("geo.Shape.Circle", vec![int(3)])]), int(0)),
+            ),
+        ),
+    );
+    let dir = build_c(&program(vec![("main", lam(&["args"], body))]));
+
+    let run = Command::new(dir.path().join("prog")).output().unwrap();
+    // The runtime's Ok and db.Reply.Ok share the short name; Circle is alone
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "2\nhttp.Status.Ok()\nCircle(3)\n");
+}
+
+#[test]
+fn test_emit_c_as_patterns_bind_the_whole_value() {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler (cc) on PATH");
+        return;
+    }
+
+    // main(args) = match (Some(4), 3) { pair @ (some @ Some(n), m) => { println(some); n + m + proj(pair, 1) } }
+    let bind = |name: &str, pattern| Pattern::PBind(name.to_string(), Box::new(pattern));
+    let pattern = bind(
+        "pair",
+        Pattern::PTuple(vec![
+            bind("some", Pattern::PEnum("Some".to_string(), vec![Pattern::PVar("n".to_string())])),
+            Pattern::PVar("m".to_string()),
+        ]),
+    );
+    let sum = call("__add__", vec![
+        call("__add__", vec![var("n"), var("m")]),
+        CoreTerm::Proj(Rc::new(var("pair")), 1, None),
+    ]);
+    let value = CoreTerm::Tuple(vec![CoreTerm::Ctor("Some".to_string(), vec![int(4)], None), int(3)], None);
+    let body = CoreTerm::Match(Rc::new(value), vec![(pattern, let_("_", call("io_println", vec![var("some")]), sum))], None);
+    let dir = build_c(&program(vec![("main", lam(&["args"], body))]));
+
+    let run = Command::new(dir.path().join("prog")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "Some(4)\n");
+    assert_eq!(run.status.code(), Some(10));
+}
+
+#[test]
+fn test_emit_c_buffered_output_keeps_its_order() {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler (cc) on PATH");
+        return;
+    }
+
+    // main(args) = println("a"); print_buffered(1); print("|"); print_buffered("b"); flush(); println(true); 0
+    let unit = || CoreTerm::UnitLit(None);
+    let steps = [
+        call("axis_io_println", vec![str_lit("a")]),
+        call("axis_io_print_buffered", vec![int(1)]),
+        call("io_print", vec![str_lit("|")]),
+        call("io_print_buffered", vec![str_lit("b")]),
+        call("axis_io_flush", vec![unit()]),
+        call("io_println", vec![CoreTerm::BoolLit(true, None)]),
+    ];
+    let body = steps.into_iter().rev().fold(int(0), |rest, step| let_("_", step, rest));
+    let dir = build_c(&program(vec![("main", lam(&["args"], body))]));
+
+    let run = Command::new(dir.path().join("prog")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "a\n1|btrue\n");
+    assert_eq!(run.status.code(), Some(0));
+}
@@ -5,35 +5,52 @@
 
 // Emit Rust code from Core IR - ANDL Loop 6: Value-based codegen
 
-use crate::core_ir::{CoreTerm, Pattern};
-use std::collections::{HashSet, HashMap};
+use crate::backend::EmitError;
+use crate::core_arena::{Arms, TermRef, TermView};
+use crate::core_ir::{self, BundleLoadOptions, Pattern};
+use crate::runtime::capability::{self, Capability};
+use crate::runtime::last_use;
+use crate::runtime::code_writer::CodeWriter;
+use crate::runtime::match_tree::{self, Decision, Head};
+use crate::runtime::entry::EntryArgsFormat;
+use crate::runtime::native_args::CallSites;
+use crate::runtime::operators::OPERATOR_FUNCTIONS;
+use crate::runtime::overflow::IntOverflow;
+use crate::runtime::reactor::RuntimeProfile;
+use crate::runtime::symbol_index::SymbolIndex;
+use crate::runtime::value::WELL_KNOWN_TAGS;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet, HashMap};
+use std::io::Write;
 
 /// Mapping from foreign Core IR symbols to their Rust implementation paths
 /// This ensures type-safe, explicit mapping with no string-based heuristics
-fn get_foreign_symbol_mapping() -> HashMap<&'static str, &'static str> {
+/// COMPATIBILITY: every `shim::` target must be re-exported by abi::shim
+pub(crate) fn get_foreign_symbol_mapping() -> HashMap<&'static str, &'static str> {
     let mut map = HashMap::new();
+
+    // Infix operators (runtime/operators.rs)
+    for op in OPERATOR_FUNCTIONS {
+        map.insert(op.function, op.rust);
+    }
     
     // Arithmetic operations
-    map.insert("__add__", "shim::__add__");
-    map.insert("__sub__", "shim::__sub__");
-    map.insert("__mul__", "shim::__mul__");
-    map.insert("__div__", "shim::__div__");
-    map.insert("__mod__", "shim::__mod__");
     map.insert("axis_int_add", "shim::__add__");
+    map.insert("checked_add", "shim::checked_add");
+    map.insert("axis_checked_add", "shim::checked_add");
+    map.insert("checked_sub", "shim::checked_sub");
+    map.insert("axis_checked_sub", "shim::checked_sub");
+    map.insert("checked_mul", "shim::checked_mul");
+    map.insert("axis_checked_mul", "shim::checked_mul");
+    map.insert("checked_div", "shim::checked_div");
+    map.insert("axis_checked_div", "shim::checked_div");
     
     // Comparison operations
-    map.insert("__eq__", "shim::__eq__");
-    map.insert("__neq__", "shim::__neq__");
-    map.insert("__lt__", "shim::__lt__");
-    map.insert("__lte__", "shim::__lte__");
-    map.insert("__gt__", "shim::__gt__");
-    map.insert("__gte__", "shim::__gte__");
+    map.insert("value_cmp", "shim::value_cmp");
+    map.insert("axis_value_cmp", "shim::value_cmp");
     
     // Logical operations
-    map.insert("__and__", "shim::__and__");
-    map.insert("__or__", "shim::__or__");
     map.insert("__not__", "shim::__not__");
-    map.insert("__concat__", "shim::__concat__");
     
     // String operations
     map.insert("str_len", "shim::str_len");
@@ -48,6 +65,47 @@ fn get_foreign_symbol_mapping() -> HashMap<&'static str, &'static str> {
     map.insert("int_to_str", "shim::int_to_str");
     map.insert("str_concat", "shim::str_concat");
     map.insert("axis_str_concat", "shim::str_concat");
+    map.insert("str_split", "shim::str_split");
+    map.insert("axis_str_split", "shim::str_split");
+    map.insert("str_trim", "shim::str_trim");
+    map.insert("axis_str_trim", "shim::str_trim");
+    map.insert("str_contains", "shim::str_contains");
+    map.insert("axis_str_contains", "shim::str_contains");
+    map.insert("str_starts_with", "shim::str_starts_with");
+    map.insert("axis_str_starts_with", "shim::str_starts_with");
+    map.insert("str_ends_with", "shim::str_ends_with");
+    map.insert("axis_str_ends_with", "shim::str_ends_with");
+    map.insert("str_replace", "shim::str_replace");
+    map.insert("axis_str_replace", "shim::str_replace");
+    map.insert("str_to_lower", "shim::str_to_lower");
+    map.insert("axis_str_to_lower", "shim::str_to_lower");
+    map.insert("str_to_upper", "shim::str_to_upper");
+    map.insert("axis_str_to_upper", "shim::str_to_upper");
+    map.insert("str_join", "shim::str_join");
+    map.insert("axis_str_join", "shim::str_join");
+    
+    // Character operations (a character is an Int code point)
+    map.insert("char_to_str", "shim::char_to_str");
+    map.insert("char_is_alpha", "shim::char_is_alpha");
+    map.insert("axis_char_is_alpha", "shim::char_is_alpha");
+    map.insert("char_is_digit", "shim::char_is_digit");
+    map.insert("axis_char_is_digit", "shim::char_is_digit");
+    map.insert("char_is_alnum", "shim::char_is_alnum");
+    map.insert("axis_char_is_alnum", "shim::char_is_alnum");
+    map.insert("char_is_whitespace", "shim::char_is_whitespace");
+    map.insert("axis_char_is_whitespace", "shim::char_is_whitespace");
+    map.insert("char_is_ident_start", "shim::char_is_ident_start");
+    map.insert("axis_char_is_ident_start", "shim::char_is_ident_start");
+    map.insert("char_is_ident_char", "shim::char_is_ident_char");
+    map.insert("axis_char_is_ident_char", "shim::char_is_ident_char");
+    map.insert("char_to_digit", "shim::char_to_digit");
+    map.insert("axis_char_to_digit", "shim::char_to_digit");
+    map.insert("char_to_lower", "shim::char_to_lower");
+    map.insert("axis_char_to_lower", "shim::char_to_lower");
+    map.insert("char_to_upper", "shim::char_to_upper");
+    map.insert("axis_char_to_upper", "shim::char_to_upper");
+    map.insert("char_from_str", "shim::char_from_str");
+    map.insert("axis_char_from_str", "shim::char_from_str");
     
     // List operations
     map.insert("list_nil", "shim::list_nil");
@@ -56,6 +114,16 @@ fn get_foreign_symbol_mapping() -> HashMap<&'static str, &'static str> {
     map.insert("list_concat", "shim::list_concat");
     map.insert("list_contains_str", "shim::list_contains_str");
     map.insert("list_index_of_str", "shim::list_index_of_str");
+    map.insert("list_sort_by", "shim::list_sort_by");
+    map.insert("axis_list_sort_by", "shim::list_sort_by");
+    
+    // CLI argument helpers
+    map.insert("args_flag", "shim::args_flag");
+    map.insert("axis_args_flag", "shim::args_flag");
+    map.insert("args_has", "shim::args_has");
+    map.insert("axis_args_has", "shim::args_has");
+    map.insert("args_positional", "shim::args_positional");
+    map.insert("axis_args_positional", "shim::args_positional");
     
     // Tuple/constructor access
     map.insert("tuple_field", "shim::tuple_field");
@@ -69,19 +137,121 @@ fn get_foreign_symbol_mapping() -> HashMap<&'static str, &'static str> {
     map.insert("io_print", "shim::io_print");
     map.insert("axis_io_print", "shim::io_print");
     map.insert("io_eprint", "shim::io_eprint");
+    map.insert("io_print_buffered", "shim::io_print_buffered");
+    map.insert("axis_io_print_buffered", "shim::io_print_buffered");
+    map.insert("io_println", "shim::io_println");
+    map.insert("axis_io_println", "shim::io_println");
+    map.insert("io_flush", "shim::io_flush");
+    map.insert("axis_io_flush", "shim::io_flush");
     map.insert("io_read", "shim::io_read");
     map.insert("axis_io_make_error", "shim::axis_io_make_error");
+
+    // Assertions (shims also take the call's span; see takes_call_span)
+    map.insert("assert_eq", "shim::assert_eq");
+    map.insert("axis_assert_eq", "shim::assert_eq");
+    map.insert("assert_true", "shim::assert_true");
+    map.insert("axis_assert_true", "shim::assert_true");
+    map.insert("fail", "shim::fail");
+    map.insert("axis_fail", "shim::fail");
     
     // JSON operations (minimal compiler implementation)
     map.insert("axis.json.parse", "shim::axis_json_parse");
     
+    // JSON operations (full JNull/JBool/JNum/JStr/JArr/JObj tree)
+    map.insert("json_parse", "shim::json_parse");
+    map.insert("axis_json_parse", "shim::json_parse");
+    map.insert("json_encode", "shim::json_encode");
+    map.insert("axis_json_encode", "shim::json_encode");
+    
     // File operations
     map.insert("fs_read_text", "shim::fs_read_text");
     map.insert("fs_read_to_string", "shim::fs_read_text");  // Alias for compatibility
     map.insert("fs_write_text", "shim::fs_write_text");
+    map.insert("fs_read_bytes", "shim::fs_read_bytes");
+    map.insert("axis_fs_read_bytes", "shim::fs_read_bytes");
+    map.insert("fs_write_bytes", "shim::fs_write_bytes");
+    map.insert("axis_fs_write_bytes", "shim::fs_write_bytes");
+    map.insert("fs_list_dir", "shim::fs_list_dir");
+    map.insert("axis_fs_list_dir", "shim::fs_list_dir");
+    map.insert("fs_create_dir_all", "shim::fs_create_dir_all");
+    map.insert("axis_fs_create_dir_all", "shim::fs_create_dir_all");
+    map.insert("fs_remove_file", "shim::fs_remove_file");
+    map.insert("axis_fs_remove_file", "shim::fs_remove_file");
+    map.insert("fs_copy", "shim::fs_copy");
+    map.insert("axis_fs_copy", "shim::fs_copy");
+    map.insert("path_join", "shim::path_join");
+    map.insert("axis_path_join", "shim::path_join");
+    map.insert("path_stem", "shim::path_stem");
+    map.insert("axis_path_stem", "shim::path_stem");
+    map.insert("path_parent", "shim::path_parent");
+    map.insert("axis_path_parent", "shim::path_parent");
+    map.insert("io_read_bytes", "shim::io_read_bytes");
+    map.insert("axis_io_read_bytes", "shim::io_read_bytes");
+    
+    // Byte buffer operations
+    map.insert("bytes_len", "shim::bytes_len");
+    map.insert("axis_bytes_len", "shim::bytes_len");
+    map.insert("bytes_get", "shim::bytes_get");
+    map.insert("axis_bytes_get", "shim::bytes_get");
+    map.insert("bytes_slice", "shim::bytes_slice");
+    map.insert("axis_bytes_slice", "shim::bytes_slice");
+
+    // String builder operations
+    map.insert("sb_new", "shim::sb_new");
+    map.insert("axis_sb_new", "shim::sb_new");
+    map.insert("sb_push", "shim::sb_push");
+    map.insert("axis_sb_push", "shim::sb_push");
+    map.insert("sb_push_int", "shim::sb_push_int");
+    map.insert("axis_sb_push_int", "shim::sb_push_int");
+    map.insert("sb_build", "shim::sb_build");
+    map.insert("axis_sb_build", "shim::sb_build");
+
+    // Concurrency (concurrency.rs)
+    map.insert("spawn", "shim::spawn");
+    map.insert("axis_spawn", "shim::spawn");
+    map.insert("join", "shim::join");
+    map.insert("axis_join", "shim::join");
+    map.insert("channel_new", "shim::channel_new");
+    map.insert("axis_channel_new", "shim::channel_new");
+    map.insert("channel_send", "shim::channel_send");
+    map.insert("axis_channel_send", "shim::channel_send");
+    map.insert("channel_recv", "shim::channel_recv");
+    map.insert("axis_channel_recv", "shim::channel_recv");
+
+    // Lazy sequences (seq.rs)
+    map.insert("seq_from_list", "shim::seq_from_list");
+    map.insert("axis_seq_from_list", "shim::seq_from_list");
+    map.insert("seq_map", "shim::seq_map");
+    map.insert("axis_seq_map", "shim::seq_map");
+    map.insert("seq_filter", "shim::seq_filter");
+    map.insert("axis_seq_filter", "shim::seq_filter");
+    map.insert("seq_unfold", "shim::seq_unfold");
+    map.insert("axis_seq_unfold", "shim::seq_unfold");
+    map.insert("seq_take", "shim::seq_take");
+    map.insert("axis_seq_take", "shim::seq_take");
+    map.insert("seq_to_list", "shim::seq_to_list");
+    map.insert("axis_seq_to_list", "shim::seq_to_list");
+    
+    // Environment and process operations
+    map.insert("env_get", "shim::env_get");
+    map.insert("axis_env_get", "shim::env_get");
+    map.insert("env_set", "shim::env_set");
+    map.insert("axis_env_set", "shim::env_set");
+    map.insert("proc_run", "shim::proc_run");
+    map.insert("axis_proc_run", "shim::proc_run");
+    map.insert("exit", "shim::proc_exit");
+    map.insert("axis_proc_exit", "shim::proc_exit");
     
     // Debug
     map.insert("debug_trace", "shim::debug_trace");
+    map.insert("value_pretty", "shim::value_pretty");
+    map.insert("axis_value_pretty", "shim::value_pretty");
+    map.insert("value_diff", "shim::value_diff");
+    map.insert("axis_value_diff", "shim::value_diff");
+
+    // Result cache of @memo functions
+    map.insert("axis_memo_get", "shim::memo_get");
+    map.insert("axis_memo_put", "shim::memo_put");
     
     // String/tag interning
     map.insert("intern_str", "shim::intern_str");
@@ -94,8 +264,10 @@ fn get_foreign_symbol_mapping() -> HashMap<&'static str, &'static str> {
     
     // Special compiler operations
     map.insert("axis_emit_core_bundle_to_file", "shim::axis_emit_core_bundle_to_file");
+    map.insert("axis_load_core_bundle_from_file", "shim::axis_load_core_bundle_from_file");
     
-    // Core IR constructor field access
+    // Core IR constructor field access, as lowered before CoreTerm::CtorField
+    // existed; kept so older bundles still build
     map.insert("__ctor_field__", "shim::ctor_field");
     
     // Compiler helper functions
@@ -296,72 +468,713 @@ fn get_foreign_symbol_mapping() -> HashMap<&'static str, &'static str> {
     // Tuple constructor
     map.insert("__tuple__", "shim::tuple");
     
-    // Inequality operator - identity mapped (implemented in compiler)
-    map.insert("__neq__", "__neq__");
-    
     map
 }
 
 /// HARDENED: Canonical foreign symbol normalization
 /// Normalizes foreign symbols to their canonical identity exactly once
 /// This is the single source of truth for foreign symbol identity
+/// Foreign primitives whose shim takes the call site's Option<RuntimeSpan>
+/// as a second Rust argument, so failures can point at the Axis source
+fn takes_call_span(canonical_symbol: &str) -> bool {
+    matches!(
+        canonical_symbol,
+        "assert_eq" | "axis_assert_eq" | "assert_true" | "axis_assert_true" | "fail" | "axis_fail"
+    )
+}
+
+// spawn(f, x): the shim takes f as a Rust function and x as a second
+// Rust argument
+fn takes_function_arg(canonical_symbol: &str) -> bool {
+    matches!(canonical_symbol, "spawn" | "axis_spawn")
+}
+
+// seq_map(f, s), seq_filter(f, s), seq_unfold(f, state), list_sort_by(f,
+// xs): as for spawn, but f is a SeqFn, which can also be a closure (seq.rs)
+fn takes_closure_arg(canonical_symbol: &str) -> bool {
+    matches!(
+        canonical_symbol,
+        "seq_map"
+            | "axis_seq_map"
+            | "seq_filter"
+            | "axis_seq_filter"
+            | "seq_unfold"
+            | "axis_seq_unfold"
+            | "list_sort_by"
+            | "axis_list_sort_by"
+    )
+}
+
+// The Rust function for a function passed to spawn. The validator only
+// lets a top-level function through (E_SPAWN_TARGET); passed as a value,
+// it keeps the packed calling convention (native_args).
+fn function_arg(term: TermRef<'_>, defined_functions: &HashMap<String, usize>) -> String {
+    match term.view() {
+        TermView::Var(name, _) if defined_functions.contains_key(name.as_str()) => {
+            sanitize_ident(&strip_namespaces(name))
+        }
+        _ => panic!(
+            "EMIT RUST: spawn needs a top-level function as its first argument, got {}",
+            describe_term(term, 3)
+        ),
+    }
+}
+
+// The SeqFn for a function passed to a sequence primitive: a top-level
+// function, or a lambda as a closure that owns copies of the locals it
+// uses, since it runs after the call returns. The validator lets nothing
+// else through (E_FUNCTION_ARG). A lambda of several parameters takes
+// them packed in one tuple, as a top-level function does.
+fn closure_arg(
+    term: TermRef<'_>,
+    module_path: &str,
+    foreign_calls: &mut BTreeSet<String>,
+    defined_functions: &HashMap<String, usize>,
+    options: &EmitOptions,
+) -> String {
+    match term.view() {
+        TermView::Var(name, _) if defined_functions.contains_key(name.as_str()) => {
+            format!("SeqFn::new({})", sanitize_ident(&strip_namespaces(name)))
+        }
+        TermView::Lam(..) => {
+            let mut params = Vec::new();
+            let mut body = term;
+            while let TermView::Lam(param, inner, _) = body.view() {
+                params.push(param.to_string());
+                body = inner;
+            }
+            let mut captured = BTreeSet::new();
+            let bound: HashSet<String> = params.iter().cloned().collect();
+            free_locals(body, &bound, defined_functions, &get_foreign_symbol_mapping(), &mut captured);
+            let body_code = emit_term_with_module(body, module_path, foreign_calls, None, defined_functions, options);
+            let mut closure = CodeWriter::new();
+            closure.open("{");
+            for name in &captured {
+                closure.line(&format!("let {} = {}.clone();", name, name));
+            }
+            if let [param] = params.as_slice() {
+                closure.open(&format!("SeqFn::new(move |{}: Value| -> Value {{", sanitize_ident(param)));
+            } else {
+                closure.open("SeqFn::new(move |__args: Value| -> Value {");
+                for (i, param) in params.iter().enumerate() {
+                    closure.line(&format!(
+                        "let {} = tuple_field(Value::Tuple(vec![__args.clone(), Value::Int({})]));",
+                        sanitize_ident(param),
+                        i
+                    ));
+                }
+            }
+            closure.lines(&body_code);
+            closure.close("})");
+            closure.close("}");
+            closure.finish_expr()
+        }
+        _ => panic!(
+            "EMIT RUST: a sequence primitive needs a top-level function or a lambda as its first argument, got {}",
+            describe_term(term, 3)
+        ),
+    }
+}
+
+// The (mangled) locals `term` uses that are not bound in it or by `bound`
+fn free_locals(
+    term: TermRef<'_>,
+    bound: &HashSet<String>,
+    defined_functions: &HashMap<String, usize>,
+    foreign_mapping: &HashMap<&'static str, &'static str>,
+    out: &mut BTreeSet<String>,
+) {
+    let mut under = |term: TermRef<'_>, names: &[&str]| {
+        let mut bound = bound.clone();
+        bound.extend(names.iter().map(|name| name.to_string()));
+        free_locals(term, &bound, defined_functions, foreign_mapping, out);
+    };
+    match term.view() {
+        TermView::Var(name, _) => {
+            let stripped = strip_namespaces(name);
+            let global = defined_functions.contains_key(name.as_str())
+                || foreign_mapping.contains_key(stripped.as_str())
+                || stripped.chars().next().is_some_and(char::is_uppercase)
+                || matches!(stripped.as_str(), "true" | "false" | "_");
+            if !global && !bound.contains(name.as_str()) {
+                out.insert(sanitize_ident(&stripped));
+            }
+        }
+        TermView::IntLit(..) | TermView::BoolLit(..) | TermView::UnitLit(..) | TermView::StrLit(..) => {}
+        TermView::Lam(param, body, _) => under(body, &[param.as_str()]),
+        TermView::Let(name, value, body, _) => {
+            under(value, &[]);
+            under(body, &[name.as_str()]);
+        }
+        TermView::Match(scrutinee, arms, _) => {
+            under(scrutinee, &[]);
+            for (pattern, body) in arms.iter() {
+                let mut names = Vec::new();
+                pattern_vars(pattern, &mut names);
+                under(body, &names);
+            }
+        }
+        TermView::App(func, arg, _) => {
+            under(func, &[]);
+            under(arg, &[]);
+        }
+        TermView::Tuple(items, _) | TermView::Ctor(_, items, _) => items.iter().for_each(|item| under(item, &[])),
+        TermView::Proj(expr, _, _) | TermView::CtorField(expr, _, _) => under(expr, &[]),
+        TermView::If(cond, then_branch, else_branch, _) => {
+            under(cond, &[]);
+            under(then_branch, &[]);
+            under(else_branch, &[]);
+        }
+    }
+}
+
+fn pattern_vars<'a>(pattern: &'a Pattern, out: &mut Vec<&'a str>) {
+    match pattern {
+        Pattern::PVar(name) => out.push(name),
+        Pattern::PTuple(patterns) | Pattern::PEnum(_, patterns) => patterns.iter().for_each(|p| pattern_vars(p, out)),
+        Pattern::PBind(name, inner) => {
+            out.push(name);
+            pattern_vars(inner, out);
+        }
+        Pattern::PInt(_) | Pattern::PBool(_) | Pattern::PUnit => {}
+    }
+}
+
 fn normalize_foreign_symbol(symbol: &str) -> String {
     // Strip namespaces to get canonical base symbol
     // This is the ONLY place where symbol normalization happens
     strip_namespaces(symbol)
 }
 
+// Emitter switches selected by the bridge CLI
+#[derive(Clone, Debug, Default)]
+pub struct EmitOptions {
+    // --debug: instrument every function prologue with a shadow call-stack
+    // frame so runtime errors print an Axis-level backtrace
+    pub debug_call_stack: bool,
+    // --debug: build for a debugger. Each generated function is preceded
+    // by a `// axis:` comment naming its Axis function (source_map), and
+    // the backends build without optimizations.
+    pub debug_info: bool,
+    // --strict-codegen: disable the name-drift workarounds (`x_term` aliased
+    // as `x`, `x_body` and `x_0..x_9`). Run check_strict_codegen first:
+    // without the workarounds an unresolved reference would otherwise only
+    // surface as a rustc error.
+    pub strict_codegen: bool,
+    // --allow: capabilities the program's primitives may use; None leaves
+    // the runtime unrestricted. Checked by the backends (capability.rs).
+    pub allow: Option<Vec<Capability>>,
+    // --linear-matches: emit each match as one guarded Rust arm per Axis
+    // arm, comparing constructor tags arm by arm, instead of a decision
+    // tree (match_tree). Kept to compare the two (tests/bench); nested
+    // literal patterns are not checked in this form.
+    pub linear_matches: bool,
+    // --native-args: a function of several parameters whose every call
+    // site is known takes them as separate Rust parameters instead of one
+    // packed Value::Tuple (native_args)
+    pub native_args: bool,
+    // --keep-clones: clone every argument and every last use of a local,
+    // as the emitter did before last_use. Kept to compare the two
+    // (tests/bench).
+    pub keep_clones: bool,
+    // --int-overflow: what the arithmetic primitives do on overflow and
+    // division by zero (overflow.rs). Set by the main wrapper; the
+    // generated code is the same under every mode.
+    pub int_overflow: IntOverflow,
+    // --runtime: the runtime profile the program is built against
+    // (reactor.rs). Only the backend's project changes; the generated
+    // code is the same under every profile.
+    pub runtime: RuntimeProfile,
+    // --entry-args-format: the shape of axis_entry's argument (entry.rs).
+    // Set by the main wrapper, like int_overflow.
+    pub entry_args: EntryArgsFormat,
+    // --require-bridge-version: a cargo version requirement on this crate,
+    // checked by the CLI and written into the Rust project's dependency
+    pub require_bridge_version: Option<String>,
+    // --standalone: the Rust backend writes one .rs file with the runtime
+    // inlined (standalone.rs) instead of a project depending on this crate
+    pub standalone: bool,
+}
+
 // REGIME COMPLIANCE: No filename-based special-casing
 // TEMPORARY: entry_fn parameter for entry point selection (will be removed)
-pub fn emit_rust_from_core(core: &CoreTerm, _input_path: &str, _entry_fn: &str) -> String {
+pub fn emit_rust_from_core(core: TermRef<'_>, input_path: &str, entry_fn: &str) -> String {
+    emit_rust_from_core_with_options(core, input_path, entry_fn, &EmitOptions::default())
+}
+
+pub fn emit_rust_from_core_with_options(
+    core: TermRef<'_>,
+    _input_path: &str,
+    _entry_fn: &str,
+    options: &EmitOptions,
+) -> String {
     let mut output = String::new();
-    let mut foreign_calls: HashSet<String> = HashSet::new();
+    // Ordered so diagnostics about foreign symbols do not depend on hash seeds
+    let mut foreign_calls: BTreeSet<String> = BTreeSet::new();
+    reset_constructor_tags();
 
-    // Import runtime from axis-rust-bridge library
-    output.push_str("use axis_rust_bridge::runtime::*;\n");
-    // output.push_str("use std::rc::Rc;\n");
-    output.push_str("\n// Generated function definitions\n");
+    output.push_str(&generated_header());
 
     // CRITICAL: Pre-collect all function names from Core IR BEFORE emitting any function
-    // bodies. This creates a "defined_functions" set that tracks which symbols are Axis-defined
-    // (vs foreign). This is used during emission to determine if a Var reference should be
-    // treated as an Axis function call or a foreign runtime function.
-    let mut defined_functions = HashSet::new();
+    // bodies. This creates a "defined_functions" map (name -> arity) that tracks which
+    // symbols are Axis-defined (vs foreign). This is used during emission to determine if
+    // a Var reference should be treated as an Axis function call or a foreign runtime function.
+    let mut defined_functions = HashMap::new();
     collect_function_names(core, &mut defined_functions);
-    
-    eprintln!("DEBUG: Collected {} function names from Core IR", defined_functions.len());
-    eprintln!("DEBUG: Function names: {:?}", defined_functions.iter().take(10).collect::<Vec<_>>());
-    
+
+    let mut call_sites = CallSites::default();
+    if options.native_args {
+        call_sites.scan(core, &defined_functions);
+    }
+    set_native_arg_functions(call_sites.native(&defined_functions));
+
     // emitted_functions tracks which functions we've already emitted (to skip duplicates)
     let mut emitted_functions = HashSet::new();
     
-    emit_top_level_lets(core, &mut output, "", &mut emitted_functions, &mut foreign_calls, &defined_functions);
+    emit_top_level_lets(core, &mut output, "", &mut emitted_functions, &mut foreign_calls, &defined_functions, options);
     
-    eprintln!("DEBUG: Generated output length: {} bytes", output.len());
+    output.push_str(&constructor_tag_table());
+
+    verify_foreign_calls(&foreign_calls);
+
+    // NOTE: Do not emit an executable `main` here; bridge will provide
+    // the Rust `main` wrapper. This keeps the Axis entry separate.
+
+    output
+}
+
+// Generated code imports only the stable interface of axis-rust-bridge
+// (abi.rs), never the runtime's internals, and is stamped with the
+// version of that interface; main passes the stamp to init_runtime_for
+fn generated_header() -> String {
+    format!(
+        "use axis_rust_bridge::abi::*;\n\n\
+         // Runtime interface version this module was generated for\n\
+         pub const AXIS_ABI_VERSION: u32 = {};\n\n\
+         // Generated function definitions\n",
+        crate::abi::ABI_VERSION
+    )
+}
 
-    // HARDENED: Verify all foreign symbols are mapped with canonical identity
+// HARDENED: Verify all foreign symbols are mapped with canonical identity
+// Backstop for check_references, which reports unmapped symbols first
+fn verify_foreign_calls(foreign_calls: &BTreeSet<String>) {
     let foreign_mapping = get_foreign_symbol_mapping();
-    for foreign_symbol in &foreign_calls {
+    for foreign_symbol in foreign_calls {
         // Use canonical symbol identity - strip namespaces exactly once
         let canonical_symbol = normalize_foreign_symbol(foreign_symbol);
         if !foreign_mapping.contains_key(canonical_symbol.as_str()) {
             panic!("Unmapped foreign symbol: {} — generating stubs is forbidden", foreign_symbol);
         }
     }
+}
 
-    // NOTE: Do not emit an executable `main` here; bridge will provide
-    // the Rust `main` wrapper. This keeps the Axis entry separate.
+/// Streaming form of emit_rust_from_core_with_options (`build --stream`).
+/// The encoded bundle is read in place one top-level definition at a time
+/// (core_ir::for_each_definition) and each function is written to `out`
+/// as soon as it is emitted, so neither the program nor the generated
+/// source is ever held whole. The output is the same.
+pub fn emit_rust_streaming(
+    bundle: &[u8],
+    load_options: &BundleLoadOptions,
+    out: &mut dyn Write,
+    options: &EmitOptions,
+) -> Result<(), EmitError> {
+    // Pass 1: names and arities, as collect_function_names reads them
+    let heads = core_ir::scan_definitions(bundle, load_options).map_err(EmitError::Bundle)?;
+    let mut defined_functions: HashMap<String, usize> = HashMap::new();
+    for head in &heads {
+        let alias = head.alias_of
+            .map(|target| top_level_name(&target))
+            .filter(|target| defined_functions.get(target).is_some_and(|&arity| arity > 0));
+        let arity = match alias {
+            Some(target) => defined_functions[&target],
+            None => head.lambdas,
+        };
+        defined_functions.insert(top_level_name(&head.name), arity);
+    }
 
-    output
+    // POLICY: unresolved references (check_references) and the sandbox
+    // refuse before anything is written, and native argument lists need
+    // every call site before the first function is emitted, so they take a
+    // pass of their own
+    let mut call_sites = CallSites::default();
+    let foreign_mapping = get_foreign_symbol_mapping();
+    let top_level: Vec<String> = heads.iter().map(|head| head.name.to_string()).collect();
+    let mut unresolved = Vec::new();
+    let mut denied = Vec::new();
+    core_ir::for_each_definition(bundle, load_options, |definition| {
+        if options.native_args {
+            call_sites.scan(definition, &defined_functions);
+        }
+        check_strict_definitions(definition, &defined_functions, &foreign_mapping, &mut unresolved);
+        if let Some(allowed) = &options.allow {
+            capability::denied_calls_in(definition, &top_level, allowed, &mut denied);
+        }
+        Ok(())
+    }).map_err(EmitError::Bundle)?;
+    unmasked(unresolved, options.strict_codegen).map_err(EmitError::UnresolvedReferences)?;
+    if !denied.is_empty() {
+        return Err(EmitError::Denied(denied));
+    }
+
+    // Pass 2: emit each function and write it out
+    let write_error = |e: std::io::Error| format!("Failed to write generated Rust: {}", e);
+    out.write_all(generated_header().as_bytes()).map_err(|e| EmitError::Bundle(write_error(e)))?;
+    let mut foreign_calls: BTreeSet<String> = BTreeSet::new();
+    let mut emitted_functions = HashSet::new();
+    let mut chunk = String::new();
+    reset_constructor_tags();
+    set_native_arg_functions(call_sites.native(&defined_functions));
+    core_ir::for_each_definition(bundle, load_options, |definition| {
+        chunk.clear();
+        emit_top_level_lets(definition, &mut chunk, "", &mut emitted_functions, &mut foreign_calls, &defined_functions, options);
+        out.write_all(chunk.as_bytes()).map_err(write_error)
+    }).map_err(EmitError::Bundle)?;
+    // Every tag is known only once every function has been emitted
+    out.write_all(constructor_tag_table().as_bytes()).map_err(|e| EmitError::Bundle(write_error(e)))?;
+
+    verify_foreign_calls(&foreign_calls);
+    Ok(())
+}
+
+// ============================================================================
+// Constructor tags
+// ============================================================================
+
+// Generated code builds and matches constructors on integer tags fixed
+// here, so a match arm is an integer comparison rather than a lookup of
+// the tag's name. Tags 0.. are the runtime's WELL_KNOWN_TAGS; every other
+// constructor gets the next tag the first time the emitter meets it. The
+// table is written after the functions as AXIS_CONSTRUCTOR_TAGS, which the
+// generated `main` registers (runtime::register_tags) before running
+// anything, so intern_tag and get_tag_name agree with the literals.
+//
+// NOTE: constructors are identified by constructor_name, which keeps the
+// type a declared constructor is qualified by.
+thread_local! {
+    static CONSTRUCTOR_TAGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn reset_constructor_tags() {
+    CONSTRUCTOR_TAGS.with(|tags| tags.borrow_mut().clear());
+}
+
+// The table so far, starting from WELL_KNOWN_TAGS
+fn with_constructor_tags<R>(f: impl FnOnce(&mut Vec<String>) -> R) -> R {
+    CONSTRUCTOR_TAGS.with(|tags| {
+        let mut tags = tags.borrow_mut();
+        if tags.is_empty() {
+            tags.extend(WELL_KNOWN_TAGS.iter().map(|name| name.to_string()));
+        }
+        f(&mut tags)
+    })
+}
+
+fn constructor_tag(name: &str) -> u32 {
+    with_constructor_tags(|tags| match tags.iter().position(|tag| tag == name) {
+        Some(tag) => tag as u32,
+        None => {
+            tags.push(name.to_string());
+            (tags.len() - 1) as u32
+        }
+    })
+}
+
+fn constructor_tag_table() -> String {
+    let names = with_constructor_tags(|tags| tags.clone());
+    let mut writer = CodeWriter::new();
+    writer.line("// Constructor tags used above, registered by main (register_tags)");
+    writer.block("pub static AXIS_CONSTRUCTOR_TAGS: &[&str] = &[", "];", |entries| {
+        for (tag, name) in names.iter().enumerate() {
+            entries.line(&format!("\"{}\", // {}", name.escape_default(), tag));
+        }
+    });
+    writer.finish()
+}
+
+// ============================================================================
+// Native argument lists (`--native-args`)
+// ============================================================================
+
+// The functions emitted with one Rust parameter per Axis parameter, from
+// CallSites::native; empty unless the option is set. Read by the header of
+// each function, its calls and its tail self-calls.
+thread_local! {
+    static NATIVE_ARG_FUNCTIONS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+fn set_native_arg_functions(functions: HashSet<String>) {
+    NATIVE_ARG_FUNCTIONS.with(|native| *native.borrow_mut() = functions);
+}
+
+fn takes_native_args(mangled: &str) -> bool {
+    NATIVE_ARG_FUNCTIONS.with(|native| native.borrow().contains(mangled))
+}
+
+// ============================================================================
+// Source map (`build --debug`)
+// ============================================================================
+
+// Precedes each generated function under --debug; parsed back by source_map
+const SOURCE_MAP_MARKER: &str = "// axis: ";
+
+fn source_map_comment(name: &str, span: &Option<crate::core_ir::Span>) -> String {
+    match span {
+        Some(sp) => format!("{}{} {}:{}:{}", SOURCE_MAP_MARKER, name, sp.file, sp.line, sp.column),
+        None => format!("{}{}", SOURCE_MAP_MARKER, name),
+    }
+}
+
+/// Where each Axis function starts in a source emitted with `debug_info`:
+/// one `<path>:<line>\t<function>\t<Axis location or ->` row per function,
+/// for setting breakpoints on generated lines. Read from the `// axis:`
+/// comments, so it also holds after rustfmt.
+pub fn source_map(path: &str, generated: &str) -> String {
+    let mut map = String::new();
+    for (index, line) in generated.lines().enumerate() {
+        let Some(entry) = line.trim_start().strip_prefix(SOURCE_MAP_MARKER) else {
+            continue;
+        };
+        let (function, location) = entry.split_once(' ').unwrap_or((entry, "-"));
+        // The function itself is on the line after its comment
+        map.push_str(&format!("{}:{}\t{}\t{}\n", path, index + 2, function, location));
+    }
+    map
+}
+
+// ============================================================================
+// Strict codegen: unresolved references
+// ============================================================================
+
+/// A Var that no binder, top-level function or foreign mapping provides.
+/// Without --strict-codegen some of these are silently satisfied by the
+/// emitter's name-drift workarounds; `masked_by` names the workaround.
+#[derive(Clone, Debug)]
+pub struct StrictCodegenError {
+    pub name: String,
+    // Enclosing top-level definition
+    pub function: String,
+    pub span: Option<crate::core_ir::Span>,
+    // Compact rendering of the term containing the reference
+    pub term: String,
+    pub masked_by: Option<String>,
+}
+
+// AX2001 in the compiler's catalog (core-compiler/src/error_codes.rs)
+impl std::fmt::Display for StrictCodegenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AX2001: '{}' in function '{}'", self.name, self.function)?;
+        if let Some(sp) = &self.span {
+            write!(f, " at {}:{}:{}", sp.file, sp.line, sp.column)?;
+        }
+        write!(f, "\n  in term: {}", self.term)?;
+        if let Some(workaround) = &self.masked_by {
+            write!(f, "\n  note: masked without --strict-codegen by {}", workaround)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find every reference the emitter could only resolve through a
+/// workaround (or not at all). Empty result: strict emission is safe.
+pub fn check_strict_codegen(core: TermRef<'_>) -> Result<(), Vec<StrictCodegenError>> {
+    let mut globals = HashMap::new();
+    collect_function_names(core, &mut globals);
+    let foreign_mapping = get_foreign_symbol_mapping();
+
+    let mut errors = Vec::new();
+    check_strict_definitions(core, &globals, &foreign_mapping, &mut errors);
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+/// The references the emitter cannot resolve: with `strict` every one
+/// check_strict_codegen finds, otherwise those no workaround masks either.
+/// Checked before emitting, so an unknown function or foreign symbol is
+/// reported with its call site instead of failing inside the emitter.
+pub fn check_references(core: TermRef<'_>, strict: bool) -> Result<(), Vec<StrictCodegenError>> {
+    check_strict_codegen(core).or_else(|errors| unmasked(errors, strict))
+}
+
+// Without --strict-codegen the workarounds resolve the masked references
+fn unmasked(errors: Vec<StrictCodegenError>, strict: bool) -> Result<(), Vec<StrictCodegenError>> {
+    let errors: Vec<_> = errors.into_iter().filter(|error| strict || error.masked_by.is_none()).collect();
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+// Check the top-level definitions of `core`, part of a program whose
+// functions are `globals`
+fn check_strict_definitions(
+    core: TermRef<'_>,
+    globals: &HashMap<String, usize>,
+    foreign_mapping: &HashMap<&'static str, &'static str>,
+    errors: &mut Vec<StrictCodegenError>,
+) {
+    let mut current = core;
+    while let TermView::Let(name, value, body, _) = current.view() {
+        let mut checker = StrictChecker {
+            function: name,
+            globals,
+            foreign_mapping,
+            scope: Vec::new(),
+            errors,
+        };
+        checker.check(value, value);
+        current = body;
+    }
+}
+
+struct StrictChecker<'a> {
+    function: &'a str,
+    globals: &'a HashMap<String, usize>,
+    foreign_mapping: &'a HashMap<&'static str, &'static str>,
+    // Local binders in scope, sanitized as the emitter binds them
+    scope: Vec<String>,
+    errors: &'a mut Vec<StrictCodegenError>,
+}
+
+impl StrictChecker<'_> {
+    fn in_scope(&self, mangled: &str) -> bool {
+        self.scope.iter().any(|b| b == mangled) || self.globals.contains_key(mangled)
+    }
+
+    fn resolves(&self, name: &str) -> bool {
+        let stripped = strip_namespaces(name);
+        let mangled = sanitize_ident(&stripped);
+        stripped == "true"
+            || stripped == "false"
+            || mangled == "_"
+            // Constructors are emitted as calls, not references
+            || mangled.chars().next().is_some_and(|c| c.is_uppercase())
+            || self.in_scope(&mangled)
+            || self.foreign_mapping.contains_key(stripped.as_str())
+    }
+
+    // Which non-strict workaround would have produced a binding for `name`
+    fn masked_by(&self, name: &str) -> Option<String> {
+        let mangled = sanitize_ident(&strip_namespaces(name));
+        if self.in_scope(&format!("{}_term", mangled)) {
+            return Some(format!("the `{}_term` -> `{}` alias", mangled, mangled));
+        }
+        if let Some(base) = mangled.strip_suffix("_body") {
+            if self.in_scope(&format!("{}_term", base)) {
+                return Some(format!("the `{}_term` -> `{}` alias", base, mangled));
+            }
+        }
+        if let Some((base, index)) = mangled.rsplit_once('_') {
+            if index.len() == 1 && index.chars().all(|c| c.is_ascii_digit()) && self.in_scope(&format!("{}_term", base)) {
+                return Some(format!("the `{}_term` projection alias `{}`", base, mangled));
+            }
+        }
+        None
+    }
+
+    fn bind_pattern(&mut self, pattern: &Pattern) -> usize {
+        match pattern {
+            Pattern::PVar(name) if name != "_" && !name.starts_with(|c: char| c.is_uppercase()) => {
+                self.scope.push(sanitize_ident(name));
+                1
+            }
+            Pattern::PTuple(pats) | Pattern::PEnum(_, pats) => pats.iter().map(|p| self.bind_pattern(p)).sum(),
+            Pattern::PBind(name, inner) => {
+                self.scope.push(sanitize_ident(name));
+                1 + self.bind_pattern(inner)
+            }
+            _ => 0,
+        }
+    }
+
+    // `parent` is the nearest enclosing non-Var term, rendered in errors
+    fn check(&mut self, term: TermRef<'_>, parent: TermRef<'_>) {
+        match term.view() {
+            TermView::IntLit(..) | TermView::BoolLit(..) | TermView::UnitLit(..) | TermView::StrLit(..) => {}
+            TermView::Var(name, span) => {
+                if !self.resolves(name) {
+                    self.errors.push(StrictCodegenError {
+                        name: name.to_string(),
+                        function: self.function.to_string(),
+                        span: span.clone(),
+                        term: describe_term(parent, 3),
+                        masked_by: self.masked_by(name),
+                    });
+                }
+            }
+            TermView::Lam(param, body, _) => {
+                self.scope.push(sanitize_ident(param));
+                self.check(body, term);
+                self.scope.pop();
+            }
+            TermView::App(func, arg, _) => {
+                self.check(func, term);
+                self.check(arg, term);
+            }
+            TermView::Tuple(elems, _) | TermView::Ctor(_, elems, _) => {
+                for elem in elems.iter() {
+                    self.check(elem, term);
+                }
+            }
+            TermView::Proj(expr, _, _) | TermView::CtorField(expr, _, _) => self.check(expr, term),
+            TermView::Let(name, value, body, _) => {
+                self.check(value, term);
+                self.scope.push(sanitize_ident(name));
+                self.check(body, term);
+                self.scope.pop();
+            }
+            TermView::If(cond, then_br, else_br, _) => {
+                self.check(cond, term);
+                self.check(then_br, term);
+                self.check(else_br, term);
+            }
+            TermView::Match(scrutinee, arms, _) => {
+                self.check(scrutinee, term);
+                for (pattern, body) in arms.iter() {
+                    let bound = self.bind_pattern(pattern);
+                    self.check(body, term);
+                    self.scope.truncate(self.scope.len() - bound);
+                }
+            }
+        }
+    }
+}
+
+// S-expression rendering cut off below `depth` levels, for error messages
+fn describe_term(term: TermRef<'_>, depth: usize) -> String {
+    if depth == 0 {
+        return "...".to_string();
+    }
+    let sub = |t: TermRef<'_>| describe_term(t, depth - 1);
+    match term.view() {
+        TermView::IntLit(n, _) => format!("(int {})", n),
+        TermView::BoolLit(b, _) => format!("(bool {})", b),
+        TermView::UnitLit(_) => "(unit)".to_string(),
+        TermView::StrLit(s, _) => format!("(str {:?})", s),
+        TermView::Var(name, _) => format!("(var {})", name),
+        TermView::Lam(param, body, _) => format!("(lam {} {})", param, sub(body)),
+        TermView::App(func, arg, _) => format!("(app {} {})", sub(func), sub(arg)),
+        TermView::Tuple(elems, _) => {
+            let parts: Vec<String> = elems.iter().map(sub).collect();
+            format!("(tuple {})", parts.join(" "))
+        }
+        TermView::Proj(expr, index, _) => format!("(proj {} {})", sub(expr), index),
+        TermView::CtorField(expr, index, _) => format!("(field {} {})", sub(expr), index),
+        TermView::Let(name, value, body, _) => format!("(let {} {} {})", name, sub(value), sub(body)),
+        TermView::If(cond, then_br, else_br, _) => format!("(if {} {} {})", sub(cond), sub(then_br), sub(else_br)),
+        TermView::Match(scrutinee, arms, _) => format!("(match {} <{} arms>)", sub(scrutinee), arms.len()),
+        TermView::Ctor(name, fields, _) => {
+            let parts: Vec<String> = fields.iter().map(sub).collect();
+            format!("(ctor {} {})", name, parts.join(" "))
+        }
+    }
 }
 
 #[allow(dead_code)]
 // Transitional helpers retained for alternate emission paths
-fn emit_term(term: &CoreTerm, indent: usize) -> String {
+fn emit_term(term: TermRef<'_>) -> String {
     // Convenience wrapper for ad-hoc calls; does not record foreign calls.
-    let mut tmp_set = HashSet::new();
-    let defined_functions = HashSet::new();
-    emit_term_with_module(term, indent, "", &mut tmp_set, None, &defined_functions)
+    let mut tmp_set = BTreeSet::new();
+    let defined_functions = HashMap::new();
+    emit_term_with_module(term, "", &mut tmp_set, None, &defined_functions, &EmitOptions::default())
 }
 
 #[allow(dead_code)]
@@ -383,21 +1196,21 @@ fn extract_module_path(input_path: &str) -> String {
 
 // TAIL-CALL OPTIMIZATION: Helper to detect if a term contains a self-recursive tail call
 // Returns true if term is a tail-positioned call to the given function name
-fn contains_tail_self_call(term: &CoreTerm, fn_name: &str) -> bool {
+fn contains_tail_self_call(term: TermRef<'_>, fn_name: &str) -> bool {
     contains_tail_self_call_impl(term, fn_name, 0)
 }
 
-fn contains_tail_self_call_impl(term: &CoreTerm, fn_name: &str, depth: usize) -> bool {
-    match term {
-        CoreTerm::App(func, _, _) => {
+fn contains_tail_self_call_impl(term: TermRef<'_>, fn_name: &str, depth: usize) -> bool {
+    match term.view() {
+        TermView::App(func, _, _) => {
             // Check if this is a call to fn_name
-            if let CoreTerm::Var(name, _) = func.as_ref() {
+            if let TermView::Var(name, _) = func.view() {
                 let mangled = sanitize_ident(&strip_namespaces(name));
                 mangled == fn_name
             } else {
                 // TAIL-CALL OPTIMIZATION: Handle curried calls - App(App(...), arg)
                 // Multi-argument function calls in Core IR are nested App nodes
-                if let CoreTerm::App(_, _, _) = func.as_ref() {
+                if let TermView::App(_, _, _) = func.view() {
                     // Recursively check the innermost function
                     contains_tail_self_call_impl(func, fn_name, depth)
                 } else {
@@ -405,15 +1218,15 @@ fn contains_tail_self_call_impl(term: &CoreTerm, fn_name: &str, depth: usize) ->
                 }
             }
         }
-        CoreTerm::If(_, then_branch, else_branch, _) => {
+        TermView::If(_, then_branch, else_branch, _) => {
             // Both branches are in tail position
             contains_tail_self_call_impl(then_branch, fn_name, depth + 1) || contains_tail_self_call_impl(else_branch, fn_name, depth + 1)
         }
-        CoreTerm::Let(_, _, body, _) => {
+        TermView::Let(_, _, body, _) => {
             // Only the body is in tail position (not the value)
             contains_tail_self_call_impl(body, fn_name, depth + 1)
         }
-        CoreTerm::Match(_, arms, _) => {
+        TermView::Match(_, arms, _) => {
             // All match arms are in tail position
             arms.iter().any(|(_, arm_term)| contains_tail_self_call_impl(arm_term, fn_name, depth + 1))
         }
@@ -421,69 +1234,118 @@ fn contains_tail_self_call_impl(term: &CoreTerm, fn_name: &str, depth: usize) ->
     }
 }
 
-/// Collect all top-level function names from Core IR
+/// Collect all top-level function names from Core IR, with their arity
 /// This populates defined_functions BEFORE emitting any function bodies,
 /// ensuring all Axis-defined functions are recognized during emission.
-fn collect_function_names(core: &CoreTerm, function_names: &mut HashSet<String>) {
+/// Arity is read from the IR: the number of leading lambdas, or for an
+/// alias of an earlier definition (`axis_entry = main`) that definition's.
+fn collect_function_names(core: TermRef<'_>, function_names: &mut HashMap<String, usize>) {
     let mut current = core;
     
     loop {
-        match current {
-            CoreTerm::Let(name, _value, body, _) => {
+        match current.view() {
+            TermView::Let(name, value, body, _) => {
                 // Add this function name (with same mangling as emit_top_level_lets)
-                let mut mangled = sanitize_ident(name);
-                if mangled == "main" {
-                    mangled = "axis_entry".to_string();
-                }
-                function_names.insert(mangled);
+                let arity = match alias_target(value, function_names) {
+                    Some(target) => function_names[&target],
+                    None => lambda_arity(value),
+                };
+                function_names.insert(top_level_name(name), arity);
                 
                 // Continue to next function
-                current = body.as_ref();
+                current = body;
             }
             _ => break,
         }
     }
 }
 
+// Rust name of a top-level definition
+pub(crate) fn top_level_name(name: &str) -> String {
+    let mangled = sanitize_ident(name);
+    // Ensure Axis-level program entry is not emitted as Rust `main`.
+    if mangled == "main" {
+        "axis_entry".to_string()
+    } else {
+        mangled
+    }
+}
+
+// Number of leading lambdas
+fn lambda_arity(term: TermRef<'_>) -> usize {
+    let mut arity = 0;
+    let mut current = term;
+    while let TermView::Lam(_, body, _) = current.view() {
+        arity += 1;
+        current = body;
+    }
+    arity
+}
+
+// A top-level value that is just another function, e.g. the @entry binding
+// `axis_entry = main`; returns the target's Rust name
+// Fail-fast message for a call that is neither a Core IR function nor a
+// mapped foreign symbol, naming the closest of both
+fn unmapped_symbol_message(
+    func_name: &str,
+    defined_functions: &HashMap<String, usize>,
+    foreign_mapping: &HashMap<&str, &str>,
+) -> String {
+    let mut message = format!(
+        "EMIT RUST: Foreign symbol '{}' is not mapped in shim. Add it to get_foreign_symbol_mapping() or define it in Core IR.",
+        func_name
+    );
+    let mut symbols = SymbolIndex::default();
+    symbols.extend(defined_functions.keys().map(String::as_str));
+    symbols.extend(foreign_mapping.keys().copied());
+    if let Some(hint) = symbols.did_you_mean(func_name) {
+        message.push_str(&format!(" ({})", hint));
+    }
+    message
+}
+
+fn alias_target(value: TermRef<'_>, defined_functions: &HashMap<String, usize>) -> Option<String> {
+    match value.view() {
+        TermView::Var(name, _) => {
+            let target = top_level_name(name);
+            match defined_functions.get(&target) {
+                Some(&arity) if arity > 0 => Some(target),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 // REGIME COMPLIANCE: Simplified function emission (no module paths)
 fn emit_top_level_lets(
-    core: &CoreTerm,
+    core: TermRef<'_>,
     output: &mut String,
     _module_path: &str,
     emitted_functions: &mut HashSet<String>,
-    foreign_calls: &mut HashSet<String>,
-    defined_functions: &HashSet<String>,
+    foreign_calls: &mut BTreeSet<String>,
+    defined_functions: &HashMap<String, usize>,
+    options: &EmitOptions,
 ) {
     // Walk nested top-level Let bindings and emit a Rust function for each
     let mut current = core;
-    
-    eprintln!("DEBUG emit_top_level_lets: Starting with term type: {:?}", std::mem::discriminant(core));
 
     loop {
-        match current {
-            CoreTerm::Let(name, value_rc, body_rc, _) => {
-                eprintln!("DEBUG emit_top_level_lets: Processing function: {}", name);
-                let value = value_rc.as_ref();
-
+        match current.view() {
+            TermView::Let(name, value, body, let_span) => {
                 // Collect parameters by descending through nested Lambdas
                 let mut params: Vec<String> = Vec::new();
                 let mut inner = value;
-                while let CoreTerm::Lam(param, inner_body, _) = inner {
-                    params.push(param.clone());
-                    inner = inner_body.as_ref();
+                while let TermView::Lam(param, inner_body, _) = inner.view() {
+                    params.push(param.to_string());
+                    inner = inner_body;
                 }
 
                     // REGIME COMPLIANCE: Simple name mangling (no module paths)
-                    let mut mangled = sanitize_ident(name);
-                    // Ensure Axis-level program entry is not emitted as Rust `main`.
-                    if mangled == "main" {
-                        mangled = "axis_entry".to_string();
-                    }
+                    let mangled = top_level_name(name);
                 if emitted_functions.contains(&mangled) {
                     // skip duplicates
-                    eprintln!("DEBUG emit_top_level_lets: SKIPPING duplicate function: {}", mangled);
                 } else {
-                    eprintln!("DEBUG emit_top_level_lets: EMITTING function: {}", mangled);
                     emitted_functions.insert(mangled.clone());
 
                     // TAIL-CALL OPTIMIZATION: Detect if this function is tail-recursive
@@ -492,34 +1354,61 @@ fn emit_top_level_lets(
                     // UNARY INVARIANT: All functions are unary
                     let pub_prefix = if mangled == "axis_entry" { "pub " } else { "" };
 
-                    if params.is_empty() {
-                        output.push_str(&format!("{}fn {}() -> Value {{\n", pub_prefix, mangled));
-                    } else if params.len() == 1 {
-                        let param_name = sanitize_ident(&params[0]);
-                        if is_tail_recursive {
-                            // Emit with mutable parameter for tail-call optimization
-                            output.push_str(&format!("{}fn {}(mut {}: Value) -> Value {{\n", pub_prefix, mangled, param_name));
-                            output.push_str("    loop {\n");
-                        } else {
-                            output.push_str(&format!("{}fn {}({}: Value) -> Value {{\n", pub_prefix, mangled, param_name));
+                    // DEBUG BUILDS: shadow call-stack frame pushed in the prologue and
+                    // popped on drop. Emitted before any TCO `loop` so a tail-recursive
+                    // function keeps a single frame, mirroring the erased Rust frames.
+                    let frame_line = options.debug_call_stack.then(|| {
+                        format!("let _axis_frame = CallFrame::enter(\"{}\", {});", name.escape_default(), emit_runtime_span(let_span))
+                    });
+                    let mut function = CodeWriter::new();
+                    if options.debug_info {
+                        function.line(&source_map_comment(name, let_span));
+                    }
+
+                    // Alias of another function: forward the single argument
+                    // Value, which already has the shape the target expects
+                    if let Some(target) = alias_target(value, defined_functions) {
+                        function.open(&format!("{}fn {}(args: Value) -> Value {{", pub_prefix, mangled));
+                        if let Some(frame) = &frame_line {
+                            function.line(frame);
                         }
+                        function.line(&format!("{}(args)", target));
+                        function.close("}");
+                        function.blank();
+                        output.push_str(&function.finish());
+                        current = body;
+                        continue;
+                    }
+
+                    let native_args = takes_native_args(&mangled);
+                    let header = if params.is_empty() {
+                        format!("{}fn {}() -> Value {{", pub_prefix, mangled)
+                    } else if native_args {
+                        // --native-args: one Value parameter per Axis parameter
+                        let mutability = if is_tail_recursive { "mut " } else { "" };
+                        let params: Vec<String> = params.iter().map(|p| format!("{}{}: Value", mutability, sanitize_ident(p))).collect();
+                        format!("{}fn {}({}) -> Value {{", pub_prefix, mangled, params.join(", "))
+                    } else if params.len() == 1 {
+                        // Emit with mutable parameter for tail-call optimization
+                        let mutability = if is_tail_recursive { "mut " } else { "" };
+                        format!("{}fn {}({}{}: Value) -> Value {{", pub_prefix, mangled, mutability, sanitize_ident(&params[0]))
                     } else {
                         // N-arity function (N > 1) - use tuple destructuring
-                        if is_tail_recursive {
-                            output.push_str(&format!("{}fn {}(mut args: Value) -> Value {{\n", pub_prefix, mangled));
-                            output.push_str("    loop {\n");
-                            for (i, param) in params.iter().enumerate() {
-                                let param_name = sanitize_ident(param);
-                                output.push_str(&format!("        let mut {} = tuple_field(Value::Tuple(vec![args.clone(), Value::Int({})]));
-", param_name, i));
-                            }
-                        } else {
-                            output.push_str(&format!("{}fn {}(args: Value) -> Value {{\n", pub_prefix, mangled));
-                            for (i, param) in params.iter().enumerate() {
-                                let param_name = sanitize_ident(param);
-                                output.push_str(&format!("    let {} = tuple_field(Value::Tuple(vec![args.clone(), Value::Int({})]));
-", param_name, i));
-                            }
+                        let mutability = if is_tail_recursive { "mut " } else { "" };
+                        format!("{}fn {}({}args: Value) -> Value {{", pub_prefix, mangled, mutability)
+                    };
+                    function.open(&header);
+                    if let Some(frame) = &frame_line {
+                        function.line(frame);
+                    }
+                    if is_tail_recursive {
+                        function.open("loop {");
+                    }
+                    if params.len() > 1 && !native_args {
+                        let binding = if is_tail_recursive { "let mut" } else { "let" };
+                        for (i, param) in params.iter().enumerate() {
+                            let param_name = sanitize_ident(param);
+                            function.line(&format!("{} {} = tuple_field(Value::Tuple(vec![args.clone(), Value::Int({})]));", binding, param_name, i));
                         }
                     }
 
@@ -532,40 +1421,50 @@ fn emit_top_level_lets(
                     };
 
                     // Emit body
-                    let base_indent = if is_tail_recursive { 2 } else { 1 };
-                    let body_code = emit_term_with_module(inner, base_indent, "", foreign_calls, tail_ctx, defined_functions);
-
-                    let indent_str = if is_tail_recursive { "        " } else { "    " };
-                    for line in body_code.lines() {
-                        output.push_str(indent_str);
-                        output.push_str(line);
-                        output.push_str("\n");
-                    }
+                    let body_code = emit_term_with_module(inner, "", foreign_calls, tail_ctx, defined_functions, options);
+                    function.lines(&body_code);
 
                     if is_tail_recursive {
-                        output.push_str("    }\n");
+                        function.close("}");
+                    }
+                    function.close("}");
+                    function.blank();
+                    if options.keep_clones {
+                        output.push_str(&function.finish());
+                    } else {
+                        output.push_str(&last_use::move_last_uses(&function.finish()));
                     }
-                    output.push_str("}\n\n");
                 }
 
                 // Continue with the body (remaining top-level lets)
-                current = body_rc.as_ref();
+                current = body;
             }
             _ => break,
         }
     }
 }
 
+// The unary calling convention makes a mismatched call compile (or fail in
+// rustc far from the cause); report it against the Axis name instead
+fn warn_arity_mismatch(func_name: &str, arity: usize, args: usize) {
+    if arity != args {
+        eprintln!(
+            "[WARN] emit_rust: '{}' takes {} argument(s) but is called with {}",
+            func_name, arity, args
+        );
+    }
+}
+
 // Collect args from nested App nodes for uncurrying
 // e.g., App(App(Var(f), a), b) -> (f, [a, b])
-fn collect_app_args(term: &CoreTerm) -> (&CoreTerm, Vec<&CoreTerm>) {
+pub(crate) fn collect_app_args(term: TermRef<'_>) -> (TermRef<'_>, Vec<TermRef<'_>>) {
     let mut args = Vec::new();
     let mut current = term;
 
     // Traverse nested App nodes to collect all arguments
-    while let CoreTerm::App(func, arg, _) = current {
-        args.push(arg.as_ref());
-        current = func.as_ref();
+    while let TermView::App(func, arg, _) = current.view() {
+        args.push(arg);
+        current = func;
     }
 
     // Reverse args since we collected them right-to-left
@@ -574,27 +1473,27 @@ fn collect_app_args(term: &CoreTerm) -> (&CoreTerm, Vec<&CoreTerm>) {
 }
 
 fn emit_term_with_module(
-    term: &CoreTerm,
-    indent: usize,
+    term: TermRef<'_>,
     module_path: &str,
-    foreign_calls: &mut std::collections::HashSet<String>,
+    foreign_calls: &mut BTreeSet<String>,
     // TAIL-CALL OPTIMIZATION: Optional function context for detecting tail self-calls
     // Format: Some((fn_name, param_names)) or None
     tail_ctx: Option<(&str, &[String])>,
     // Set of functions defined in this Core IR module
-    defined_functions: &HashSet<String>,
+    defined_functions: &HashMap<String, usize>,
+    options: &EmitOptions,
 ) -> String {
-    match term {
-        CoreTerm::IntLit(n, _) => format!("Value::Int({})", n),
-        CoreTerm::BoolLit(true, _) => "Value::Bool(true)".to_string(),
-        CoreTerm::BoolLit(false, _) => "Value::Bool(false)".to_string(),
-        CoreTerm::UnitLit(_) => "Value::Unit".to_string(),  // Unit as Value::Unit
-        CoreTerm::StrLit(s, _) => {
-            // Use proper string interning for Value::Str
-            format!("Value::Str(intern_str(\"{}\"))", s.escape_default())
+    match term.view() {
+        TermView::IntLit(n, _) => format!("Value::Int({})", n),
+        TermView::BoolLit(&true, _) => "Value::Bool(true)".to_string(),
+        TermView::BoolLit(&false, _) => "Value::Bool(false)".to_string(),
+        TermView::UnitLit(_) => "Value::Unit".to_string(),  // Unit as Value::Unit
+        TermView::StrLit(s, _) => {
+            // Literals are interned (see value.rs, "String values")
+            format!("str_lit(\"{}\")", s.escape_default())
         }
 
-        CoreTerm::Var(name, _) => {
+        TermView::Var(name, span) => {
             // Handle boolean literals (no mangling)
             let stripped_name = strip_namespaces(name);
             if stripped_name == "true" {
@@ -604,29 +1503,6 @@ fn emit_term_with_module(
                 return "Value::Bool(false)".to_string();
             }
 
-            // FIELD ACCESS CONVENTION: c_pattern means "field pattern of c"
-            // Known field names for MatchCase: pattern (0), body (1)
-            // ONLY apply this for single-letter base names (e.g., c_pattern, not core_body)
-            let field_map: &[(&str, usize)] = &[
-                ("pattern", 0),
-                ("body", 1),
-            ];
-            for (field_name, field_idx) in field_map {
-                let suffix = format!("_{}", field_name);
-                if stripped_name.ends_with(&suffix) {
-                    let base = &stripped_name[..stripped_name.len() - suffix.len()];
-                    // Only apply for single-letter base names (typical in patterns like Cons(c, rest))
-                    if base.len() == 1 && base.chars().next().map(|c| c.is_lowercase()).unwrap_or(false) {
-                        // Emit field projection: match &base { Value::Ctor { fields, .. } => fields[idx].clone(), _ => panic!(...) }
-                        let base_mangled = sanitize_ident(base);
-                        return format!(
-                            "match &{} {{ Value::Ctor {{ fields, .. }} => fields[{}].clone(), _ => panic!(\"Field access on non-ctor\") }}",
-                            base_mangled, field_idx
-                        );
-                    }
-                }
-            }
-
             // Task 3: Strip namespaces and sanitize identifier
             // If this looks like a constructor (capitalized final segment), emit as a zero-arg call: `Ctor()`
             let mangled = sanitize_ident(&stripped_name);
@@ -637,6 +1513,7 @@ fn emit_term_with_module(
             } else {
                 //  POLICY: Clone all variable references to avoid E0382 borrow errors
                 // The emitted code may reuse variables in multiple contexts (match arms, if branches, etc.)
+                // last_use turns the clone of a local's only read into a move
 
                 // MECHANICAL FIX: Handle wildcard pattern variables
                 // When the variable name is "_", we cannot use "_.clone()" in Rust
@@ -649,11 +1526,11 @@ fn emit_term_with_module(
                 }
             }
         }
-        CoreTerm::Ctor(name, fields, _) => {
-            let tag_name = strip_namespaces(name);
+        TermView::Ctor(name, fields, _) => {
+            let tag_name = constructor_name(name);
             let mut field_exprs = Vec::new();
-            for field in fields {
-                field_exprs.push(emit_term_with_module(field, indent, module_path, foreign_calls, None, defined_functions));
+            for field in fields.iter() {
+                field_exprs.push(emit_term_with_module(field, module_path, foreign_calls, None, defined_functions, options));
             }
             let fields_code = if field_exprs.is_empty() {
                 "vec![]".to_string()
@@ -661,102 +1538,81 @@ fn emit_term_with_module(
                 format!("vec![{}]", field_exprs.join(", "))
             };
             format!(
-                "Value::Ctor {{ tag: intern_tag(\"{}\"), fields: {} }}",
+                "Value::Ctor {{ tag: {} /* {} */, fields: {} }}",
+                constructor_tag(tag_name),
                 tag_name,
                 fields_code
             )
         }
 
-        CoreTerm::Lam(param, body, _) => {
+        TermView::Lam(param, body, _) => {
             // Emit lambda as a closure with a mangled Value parameter so Var references resolve
             let param_name = sanitize_ident(param);
-            let body_code = emit_term_with_module(body, indent + 1, module_path, foreign_calls, tail_ctx, defined_functions);
+            let body_code = emit_term_with_module(body, module_path, foreign_calls, tail_ctx, defined_functions, options);
             format!("Box::new(move |{}: Value| -> Value {{ {} }}) as Box<dyn Fn(Value) -> Value>", param_name, body_code)
         }
 
-        CoreTerm::App(func, arg, _) => {
+        TermView::App(func, arg, app_span) => {
             // UNCURRYING: Check if this is a nested application that should be flattened
             let (base_func, all_args) = collect_app_args(term);
 
             // TAIL-CALL OPTIMIZATION: Check if this is a tail self-call
             if let Some((fn_name, param_names)) = tail_ctx {
-                if let CoreTerm::Var(func_name, _) = base_func {
+                if let TermView::Var(func_name, _) = base_func.view() {
                     let mangled_func = sanitize_ident(&strip_namespaces(func_name));
                     if mangled_func == fn_name {
                         // This is a tail self-call - emit as parameter reassignments + continue
-                        let mut reassignments = String::new();
-                        let indent_str = "    ".repeat(indent);
+                        let mut reassignments = CodeWriter::new();
 
-                        if all_args.len() > 1 {
+                        if all_args.len() > 1 && takes_native_args(fn_name) {
+                            // Native parameters: assign them all at once, so each
+                            // argument sees the parameters of this iteration
+                            let arg_codes: Vec<String> = all_args.iter()
+                                .map(|&a| {
+                                    let code = emit_term_with_module(a, module_path, foreign_calls, None, defined_functions, options);
+                                    if needs_clone(a, options) { format!("{}.clone()", code) } else { code }
+                                })
+                                .collect();
+                            reassignments.expr(&format!("({}) = (", param_names.join(", ")), &arg_codes.join(", "), ");");
+                        } else if all_args.len() > 1 {
                             // Multi-param function: reconstruct args tuple and reassign
                             let arg_codes: Vec<String> = all_args.iter()
-                                .map(|a| {
-                                    let code = emit_term_with_module(a, indent, module_path, foreign_calls, None, defined_functions);
-                                    if needs_clone(a) { format!("{}.clone()", code) } else { code }
+                                .map(|&a| {
+                                    let code = emit_term_with_module(a, module_path, foreign_calls, None, defined_functions, options);
+                                    if needs_clone(a, options) { format!("{}.clone()", code) } else { code }
                                 })
                                 .collect();
-                            reassignments.push_str(&format!("{}args = Value::Tuple(vec![{}]);\n", indent_str, arg_codes.join(", ")));
+                            reassignments.expr("args = Value::Tuple(vec![", &arg_codes.join(", "), "]);");
                             // Also reassign individual params for consistency
                             for (i, param_name) in param_names.iter().enumerate() {
-                                reassignments.push_str(&format!("{}{} = tuple_field(Value::Tuple(vec![args.clone(), Value::Int({})]));\n", indent_str, param_name, i));
+                                reassignments.line(&format!("{} = tuple_field(Value::Tuple(vec![args.clone(), Value::Int({})]));", param_name, i));
                             }
                         } else if all_args.len() == 1 {
                             // Single-param function: direct assignment
-                            let arg_code = emit_term_with_module(all_args[0], indent, module_path, foreign_calls, None, defined_functions);
-                            let arg_final = if needs_clone(all_args[0]) { format!("{}.clone()", arg_code) } else { arg_code };
-                            reassignments.push_str(&format!("{}{} = {};\n", indent_str, param_names[0], arg_final));
+                            let arg_code = emit_term_with_module(all_args[0], module_path, foreign_calls, None, defined_functions, options);
+                            let arg_final = if needs_clone(all_args[0], options) { format!("{}.clone()", arg_code) } else { arg_code };
+                            reassignments.expr(&format!("{} = ", param_names[0]), &arg_final, ";");
                         }
                         // Emit continue to restart loop
-                        reassignments.push_str(&format!("{}continue", indent_str));
-                        return reassignments;
-                    }
-                }
-            }
-
-            // Special-case: __ctor_field__ used as tuple projection (two-arg call)
-            // We only rewrite when we're confident this is a projection (not
-            // pattern-destructuring). Pattern-destructuring in the lowering phase
-            // emits calls like __ctor_field__(_tmp_X, i) where the first arg is a
-            // temporary variable beginning with "_tmp_". We avoid rewriting in
-            // that case to preserve constructor field semantics.
-            if let CoreTerm::Var(func_name, _) = base_func {
-                let stripped = strip_namespaces(func_name);
-                if stripped == "__ctor_field__" && all_args.len() == 2 {
-                    // Check second arg is an int literal
-                    if let CoreTerm::IntLit(idx, _) = all_args[1] {
-                        // If first arg is a tmp var created by lowering, do NOT rewrite
-                        let first_arg = all_args[0];
-                        let is_tmp_var = match first_arg {
-                            CoreTerm::Var(vn, _) => vn.starts_with("_tmp_"),
-                            _ => false,
-                        };
-
-                        if !is_tmp_var {
-                            // Emit as direct call to shim tuple_field function
-                            foreign_calls.insert("tuple_field".to_string());
-                            let tuple_code = emit_term_with_module(first_arg, indent, module_path, foreign_calls, None, defined_functions);
-                            let tuple_final = if needs_clone(first_arg) { format!("{}.clone()", tuple_code) } else { tuple_code };
-                            // Index must be a literal Int (0-based)
-                            // UNARY INVARIANT: Pack both arguments into a single tuple
-                            return format!("shim::tuple_field(Value::Tuple(vec![{}, Value::Int({})]))", tuple_final, idx);
-                        }
+                        reassignments.line("continue");
+                        return reassignments.finish_expr();
                     }
                 }
             }
 
             if all_args.len() > 1 {
                 // Multiple arguments: pack into single tuple (UNARY INVARIANT)
-                match base_func {
-                    CoreTerm::Var(func_name, _) => {
+                match base_func.view() {
+                    TermView::Var(func_name, _) => {
                         // NEW: Proper call resolution - classify the symbol
                         let canonical_func = normalize_foreign_symbol(func_name);
                         let foreign_mapping = get_foreign_symbol_mapping();
                         
                         let arg_codes: Vec<String> = all_args.iter()
-                            .map(|a| {
-                                let code = emit_term_with_module(a, indent, module_path, foreign_calls, None, defined_functions);
+                            .map(|&a| {
+                                let code = emit_term_with_module(a, module_path, foreign_calls, None, defined_functions, options);
                                 //  POLICY: clone all function arguments
-                                if needs_clone(a) { format!("{}.clone()", code) } else { code }
+                                if needs_clone(a, options) { format!("{}.clone()", code) } else { code }
                             })
                             .collect();
 
@@ -765,164 +1621,179 @@ fn emit_term_with_module(
                         // 2. Core IR defined functions -> generate fn call
                         // 3. Foreign functions -> require mapping or panic
                         
-                        if defined_functions.contains(func_name) {
+                        if let Some(&arity) = defined_functions.get(func_name.as_str()) {
                             // This is a Core IR defined function - emit direct fn call
+                            warn_arity_mismatch(func_name, arity, all_args.len());
                             let mangled_name = sanitize_ident(&canonical_func);
-                            // UNARY INVARIANT: Pack multiple arguments into tuple
-                            format!("{}(Value::Tuple(vec![{}]))", mangled_name, arg_codes.join(", "))
+                            if takes_native_args(&mangled_name) {
+                                // --native-args: every call site passes the arguments as they are
+                                format!("{}({})", mangled_name, arg_codes.join(", "))
+                            } else {
+                                // UNARY INVARIANT: Pack multiple arguments into tuple
+                                format!("{}(Value::Tuple(vec![{}]))", mangled_name, arg_codes.join(", "))
+                            }
                         } else if let Some(&shim_path) = foreign_mapping.get(canonical_func.as_str()) {
                             // This is a mapped foreign function - emit direct shim call
                             foreign_calls.insert(canonical_func.clone());
                             
                             // UNARY INVARIANT: ALL runtime primitives accept exactly one Value argument
                             // Pack all arguments into a single Value::Tuple for shim
-                            format!("{}(Value::Tuple(vec![{}]))", shim_path, arg_codes.join(", "))
+                            if takes_closure_arg(&canonical_func) && all_args.len() == 2 {
+                                let function = closure_arg(all_args[0], module_path, foreign_calls, defined_functions, options);
+                                format!("{}({}, {})", shim_path, function, arg_codes[1])
+                            } else if takes_function_arg(&canonical_func) && all_args.len() == 2 {
+                                format!("{}({}, {})", shim_path, function_arg(all_args[0], defined_functions), arg_codes[1])
+                            } else if takes_call_span(&canonical_func) {
+                                format!("{}(Value::Tuple(vec![{}]), {})", shim_path, arg_codes.join(", "), emit_runtime_span(app_span))
+                            } else {
+                                format!("{}(Value::Tuple(vec![{}]))", shim_path, arg_codes.join(", "))
+                            }
                         } else {
-                            // FAIL-FAST: Unmapped foreign symbol - panic with clear error
-                            panic!("EMIT RUST: Foreign symbol '{}' is not mapped in shim. Add it to get_foreign_symbol_mapping() or define it in Core IR.", func_name);
+                            // check_references reports these with their call site before
+                            // emission starts; reaching here is an emitter bug
+                            panic!("{}", unmapped_symbol_message(func_name, defined_functions, &foreign_mapping));
                         }
                     }
                     _ => {
                         // Non-variable function: emit curried
-                        let func_code = emit_term_with_module(func, indent, module_path, foreign_calls, None, defined_functions);
-                        let arg_code = emit_term_with_module(arg, indent, module_path, foreign_calls, None, defined_functions);
+                        let func_code = emit_term_with_module(func, module_path, foreign_calls, None, defined_functions, options);
+                        let arg_code = emit_term_with_module(arg, module_path, foreign_calls, None, defined_functions, options);
                         format!("({})({})", func_code, arg_code)
                     }
                 }
             } else {
                 // Single argument: emit normally but with foreign mapping check
-                match func.as_ref() {
-                    CoreTerm::Var(func_name, _) => {
+                match func.view() {
+                    TermView::Var(func_name, _) => {
                         // NEW: Proper call resolution for single argument case
                         let canonical_func = normalize_foreign_symbol(func_name);
                         let foreign_mapping = get_foreign_symbol_mapping();
                         
-                        let arg_code = emit_term_with_module(arg, indent, module_path, foreign_calls, None, defined_functions);
-                        let arg_final = if needs_clone(arg) { format!("{}.clone()", arg_code) } else { arg_code };
+                        let arg_code = emit_term_with_module(arg, module_path, foreign_calls, None, defined_functions, options);
+                        let arg_final = if needs_clone(arg, options) { format!("{}.clone()", arg_code) } else { arg_code };
 
                         // Classification priority:
                         // 1. Local bindings (lambda params, let bindings) -> handled by Var case, not here
                         // 2. Core IR defined functions -> generate fn call
                         // 3. Foreign functions -> require mapping or panic
                         
-                        if defined_functions.contains(func_name) {
+                        if let Some(&arity) = defined_functions.get(func_name.as_str()) {
                             // This is a Core IR defined function - emit direct fn call
+                            // (one argument may also be a packed tuple for an N-ary function)
+                            if arity == 0 {
+                                warn_arity_mismatch(func_name, arity, 1);
+                            }
                             let mangled_name = sanitize_ident(&canonical_func);
                             format!("{}({})", mangled_name, arg_final)
                         } else if let Some(&shim_path) = foreign_mapping.get(canonical_func.as_str()) {
                             // This is a mapped foreign function - emit direct shim call
                             foreign_calls.insert(canonical_func.clone());
-                            format!("{}({})", shim_path, arg_final)
+                            if takes_call_span(&canonical_func) {
+                                format!("{}({}, {})", shim_path, arg_final, emit_runtime_span(app_span))
+                            } else {
+                                format!("{}({})", shim_path, arg_final)
+                            }
                         } else {
-                            // FAIL-FAST: Unmapped foreign symbol - panic with clear error
-                            panic!("EMIT RUST: Foreign symbol '{}' is not mapped in shim. Add it to get_foreign_symbol_mapping() or define it in Core IR.", func_name);
+                            // check_references reports these with their call site before
+                            // emission starts; reaching here is an emitter bug
+                            panic!("{}", unmapped_symbol_message(func_name, defined_functions, &foreign_mapping));
                         }
                     }
                     _ => {
-                        let func_code = emit_term_with_module(func, indent, module_path, foreign_calls, None, defined_functions);
-                        let arg_code = emit_term_with_module(arg, indent, module_path, foreign_calls, None, defined_functions);
+                        let func_code = emit_term_with_module(func, module_path, foreign_calls, None, defined_functions, options);
+                        let arg_code = emit_term_with_module(arg, module_path, foreign_calls, None, defined_functions, options);
                         //  POLICY: clone function arguments
-                        let arg_final = if needs_clone(arg) { format!("{}.clone()", arg_code) } else { arg_code };
+                        let arg_final = if needs_clone(arg, options) { format!("{}.clone()", arg_code) } else { arg_code };
                         format!("({})({})", func_code, arg_final)
                     }
                 }
             }
         }
 
-        CoreTerm::Let(name, value, body, _) => {
+        TermView::Let(name, value, body, _) => {
             // Mangle the binder so locals and Var references align with mangling
             let var_name = sanitize_ident(name);
 
             // Emit value and body recursively
-            let value_code = emit_term_with_module(value, indent + 1, module_path, foreign_calls, None, defined_functions);
-            let body_code = emit_term_with_module(body, indent + 1, module_path, foreign_calls, tail_ctx, defined_functions);
+            let value_code = emit_term_with_module(value, module_path, foreign_calls, None, defined_functions, options);
+            let body_code = emit_term_with_module(body, module_path, foreign_calls, tail_ctx, defined_functions, options);
 
             // TASK 3: UNCONDITIONAL tuple projection workaround
             // ALWAYS emit tuple projections for every let-binding
             // This handles Core IR bugs where tuple type annotations become patterns
             // Extra bindings are harmless; missing bindings are fatal
 
-            // Preserve indentation: produce a block with an indented `let` and body
-            let indent_str = "    ".repeat(indent);
-            let inner_indent = "    ".repeat(indent + 1);
-
-            // Build the full block
-            let mut block = String::new();
-            block.push_str("{\n");
-            block.push_str(&format!("{}let {} = {};\n", inner_indent, var_name, value_code));
-
-            eprintln!("[lower-let] {} projections=10", var_name);
+            let mut block = CodeWriter::new();
+            block.open("{");
+            block.expr(&format!("let {} = ", var_name), &value_code, ";");
 
             //  WORKAROUND: Emit tuple projections for let-bound variables
             // This handles Core IR bugs where references don't match bindings
             // Also create common aliases to handle name drift (e.g., json_term -> json_body)
             // for i in 0..10 {
             //     let proj_var = format!("{}_{}", var_name, i);
-            //     block.push_str(&format!("{}let {} = tuple_field({}.clone(), {});\n",
-            //         inner_indent, proj_var, var_name, i));
+            //     block.line(&format!("let {} = tuple_field({}.clone(), {});", proj_var, var_name, i));
             // }
 
             // ADDITIONAL workaround: Create name-drift aliases for common patterns
+            // (not under --strict-codegen, where check_strict_codegen reports them)
+            let drift_aliases = !options.strict_codegen && var_name.ends_with("_term");
             // Pattern 1: var_term -> var (strip "_term" suffix)
-            if var_name.ends_with("_term") {
+            if drift_aliases {
                 let base = &var_name[..var_name.len() - 5]; // Remove "_term"
-                block.push_str(&format!("{}let {} = {}.clone();\n",
-                    inner_indent, base, var_name));
+                block.line(&format!("let {} = {}.clone();", base, var_name));
                 // Also create projections for the alias
                 for i in 0..10 {
                     let proj_var = format!("{}_{}", base, i);
-                    block.push_str(&format!("{}let {} = tuple_field(Value::Tuple(vec![{}.clone(), Value::Int({})]));
-",
-                        inner_indent, proj_var, base, i));
+                    block.line(&format!("let {} = tuple_field(Value::Tuple(vec![{}.clone(), Value::Int({})]));", proj_var, base, i));
                 }
             }
             // Pattern 2: Check if this looks like it should have a "_body" alias
             // json_term -> json_body
-            if var_name.ends_with("_term") {
+            if drift_aliases {
                 let base = &var_name[..var_name.len() - 5];
                 let body_alias = format!("{}_body", base);
-                block.push_str(&format!("{}let {} = {}.clone();\n",
-                    inner_indent, body_alias, var_name));
+                block.line(&format!("let {} = {}.clone();", body_alias, var_name));
             }
 
-            // Indent body lines to match inner indentation
-            let indented_body = body_code
-                .lines()
-                .map(|l| format!("{}{}", inner_indent, l))
-                .collect::<Vec<_>>()
-                .join("\n");
-
-            block.push_str(&format!("{}\n", indented_body));
-            block.push_str(&format!("{}}}", indent_str));
-            block
+            block.lines(&body_code);
+            block.close("}");
+            block.finish_expr()
         }
 
-        CoreTerm::Tuple(elems, _) => {
+        TermView::Tuple(elems, _) => {
             let elem_codes: Vec<String> = elems.iter()
                 .map(|e| {
-                    let code = emit_term_with_module(e, indent, module_path, foreign_calls, None, defined_functions);
+                    let code = emit_term_with_module(e, module_path, foreign_calls, None, defined_functions, options);
                     //  POLICY: clone tuple elements
-                    if needs_clone(e) { format!("{}.clone()", code) } else { code }
+                    if needs_clone(e, options) { format!("{}.clone()", code) } else { code }
                 })
                 .collect();
             format!("Value::Tuple(vec![{}])", elem_codes.join(", "))
         }
 
-        CoreTerm::Proj(tuple, idx, _) => {
-            let tuple_code = emit_term_with_module(tuple, indent, module_path, foreign_calls, None, defined_functions);
+        TermView::Proj(tuple, idx, _) => {
+            let tuple_code = emit_term_with_module(tuple, module_path, foreign_calls, None, defined_functions, options);
             //  POLICY: clone projected values
-            let tuple_final = if needs_clone(tuple) { format!("{}.clone()", tuple_code) } else { tuple_code };
-            // CoreTerm::Proj uses 1-based indexing; convert to 0-based
-            let zero_based = idx.saturating_sub(1);
+            let tuple_final = if needs_clone(tuple, options) { format!("{}.clone()", tuple_code) } else { tuple_code };
             // UNARY INVARIANT: tuple_field accepts Value::Tuple containing [data, index]
+            // (0-based, as CoreTerm::Proj)
             foreign_calls.insert("tuple_field".to_string());
-            format!("shim::tuple_field(Value::Tuple(vec![{}, Value::Int({})]))", tuple_final, zero_based)
+            format!("shim::tuple_field(Value::Tuple(vec![{}, Value::Int({})]))", tuple_final, idx)
+        }
+
+        TermView::CtorField(ctor, idx, _) => {
+            let ctor_code = emit_term_with_module(ctor, module_path, foreign_calls, None, defined_functions, options);
+            let ctor_final = if needs_clone(ctor, options) { format!("{}.clone()", ctor_code) } else { ctor_code };
+            // UNARY INVARIANT: ctor_field accepts Value::Tuple containing [ctor, index]
+            foreign_calls.insert("ctor_field".to_string());
+            format!("shim::ctor_field(Value::Tuple(vec![{}, Value::Int({})]))", ctor_final, idx)
         }
 
-        CoreTerm::If(cond, then_branch, else_branch, _) => {
-            let cond_code = emit_term_with_module(cond, indent, module_path, foreign_calls, None, defined_functions);
-            let then_code = emit_term_with_module(then_branch, indent, module_path, foreign_calls, tail_ctx, defined_functions);
-            let else_code = emit_term_with_module(else_branch, indent, module_path, foreign_calls, tail_ctx, defined_functions);
+        TermView::If(cond, then_branch, else_branch, _) => {
+            let cond_code = emit_term_with_module(cond, module_path, foreign_calls, None, defined_functions, options);
+            let then_code = emit_term_with_module(then_branch, module_path, foreign_calls, tail_ctx, defined_functions, options);
+            let else_code = emit_term_with_module(else_branch, module_path, foreign_calls, tail_ctx, defined_functions, options);
 
             // TAIL-CALL OPTIMIZATION: Wrap branches with 'return' when in tail context
             // unless they contain 'continue'
@@ -940,13 +1811,31 @@ fn emit_term_with_module(
             };
 
             // No additional cloning needed here - truthy takes a reference
-            format!("if truthy(&({})) {{ {} }} else {{ {} }}",
-                cond_code, then_final, else_final)
+            // Single-line branches stay on one line; a block gets a line
+            // of its own so it can be read
+            if !then_final.contains('\n') && !else_final.contains('\n') {
+                format!("if truthy(&({})) {{ {} }} else {{ {} }}", cond_code, then_final, else_final)
+            } else {
+                let mut code = CodeWriter::new();
+                code.expr("if truthy(&(", &cond_code, ")) {");
+                code.indent();
+                code.lines(&then_final);
+                code.close("} else {");
+                code.indent();
+                code.lines(&else_final);
+                code.close("}");
+                code.finish_expr()
+            }
         }
 
-        CoreTerm::Match(scrutinee, arms, _) => {
-            // Emit a real Rust `match` on the evaluated scrutinee with recursive pattern lowering
-            let scr_code = emit_term_with_module(scrutinee, indent + 1, module_path, foreign_calls, None, defined_functions);
+        TermView::Match(scrutinee, arms, span) => {
+            if !options.linear_matches {
+                return emit_match_tree(scrutinee, arms, module_path, foreign_calls, tail_ctx, defined_functions, options);
+            }
+
+            // --linear-matches: one guarded Rust arm per Axis arm, with recursive pattern lowering
+            let span_code = emit_runtime_span(span);
+            let scr_code = emit_term_with_module(scrutinee, module_path, foreign_calls, None, defined_functions, options);
             let scr_var = sanitize_ident("scr");
 
             let mut arm_strs: Vec<String> = Vec::new();
@@ -955,7 +1844,7 @@ fn emit_term_with_module(
 
             for (pat, arm_term) in arms.iter() {
                 // Check if this is a catch-all pattern (wildcard _)
-                if matches!(pat, Pattern::PVar(name) if name == "_") {
+                if matches!(pat.shape(), Pattern::PVar(name) if name == "_") {
                     has_catch_all = true;
                 }
 
@@ -964,10 +1853,11 @@ fn emit_term_with_module(
                     pat,
                     &scr_var,
                     module_path,
+                    &span_code,
                     &mut temp_counter
                 );
 
-                let arm_body = emit_term_with_module(arm_term, indent + 2, module_path, foreign_calls, tail_ctx, defined_functions);
+                let arm_body = emit_term_with_module(arm_term, module_path, foreign_calls, tail_ctx, defined_functions, options);
 
                 // TAIL-CALL OPTIMIZATION: Wrap arm body with 'return' when in tail context
                 // unless it contains 'continue' (which handles tail calls)
@@ -978,15 +1868,14 @@ fn emit_term_with_module(
                     arm_body
                 };
 
-                let mut arm_block = String::new();
-                arm_block.push_str(&format!("{} => {{\n", arm_pat));
+                let mut arm_block = CodeWriter::new();
+                arm_block.open(&format!("{} => {{", arm_pat));
                 for binding in bindings.iter() {
-                    arm_block.push_str(&format!("    {}\n", binding));
+                    arm_block.lines(binding);
                 }
-                let indented = arm_body_final.lines().map(|l| format!("    {}", l)).collect::<Vec<_>>().join("\n");
-                arm_block.push_str(&format!("{}\n", indented));
-                arm_block.push_str("}");
-                arm_strs.push(arm_block);
+                arm_block.lines(&arm_body_final);
+                arm_block.close("},");
+                arm_strs.push(arm_block.finish_expr());
             }
 
             // Only add fallback if there's no explicit catch-all arm
@@ -997,49 +1886,142 @@ fn emit_term_with_module(
                 } else {
                     "Value::Unit"
                 };
-                arm_strs.push(format!("_ => {{ {} }}", default_value));
+                arm_strs.push(format!("_ => {{ {} }},", default_value));
             }
 
-            let mut full = String::new();
-            full.push_str("{\n");
-            full.push_str(&format!("let {} = {};\n", scr_var, scr_code));
-            full.push_str(&format!("match {} {{\n", scr_var));
-            for a in arm_strs.iter() {
-                full.push_str(&format!("    {},\n", a));
+            let mut full = CodeWriter::new();
+            full.open("{");
+            full.expr(&format!("let {} = ", scr_var), &scr_code, ";");
+            full.block(&format!("match {} {{", scr_var), "}", |arms_code| {
+                for a in arm_strs.iter() {
+                    arms_code.lines(a);
+                }
+            });
+            full.close("}");
+            full.finish_expr()
+        }
+    }
+}
+
+// A match compiled to a decision tree (match_tree): nested Rust `match`es
+// on tags and literals pick the arm, then the arm binds its variables and
+// runs:
+//
+//     let scr = <scrutinee>;
+//     let __arm = match &scr { Value::Ctor { tag: 3 /* Some */, fields } if ... => Some(0), _ => None };
+//     match __arm { Some(0) => { let x = match_field(&scr, 0).clone(); ... }, _ => Value::Unit }
+fn emit_match_tree(
+    scrutinee: TermRef<'_>,
+    arms: Arms<'_>,
+    module_path: &str,
+    foreign_calls: &mut BTreeSet<String>,
+    tail_ctx: Option<(&str, &[String])>,
+    defined_functions: &HashMap<String, usize>,
+    options: &EmitOptions,
+) -> String {
+    let scr_code = emit_term_with_module(scrutinee, module_path, foreign_calls, None, defined_functions, options);
+    let scr_var = sanitize_ident("scr");
+    let decision = match_tree::compile(arms.iter().map(|(pattern, _)| pattern));
+
+    let mut full = CodeWriter::new();
+    full.open("{");
+    full.expr(&format!("let {} = ", scr_var), &scr_code, ";");
+    full.expr("let __arm = ", &emit_decision(&decision, &scr_var), ";");
+    full.block("match __arm {", "}", |arms_code| {
+        for (index, (pattern, arm_term)) in arms.iter().enumerate() {
+            let arm_body = emit_term_with_module(arm_term, module_path, foreign_calls, tail_ctx, defined_functions, options);
+            // TAIL-CALL OPTIMIZATION: as for --linear-matches
+            let arm_body = if tail_ctx.is_some() && !arm_body.contains("continue") {
+                format!("return {}", arm_body)
+            } else {
+                arm_body
+            };
+            arms_code.open(&format!("Some({}) => {{", index));
+            for (name, path) in match_tree::bindings(pattern) {
+                arms_code.line(&format!("let {} = {}.clone();", sanitize_ident(&name), match_path(&scr_var, &path)));
             }
-            full.push_str("}\n}");
-            full
+            arms_code.lines(&arm_body);
+            arms_code.close("},");
+        }
+        // No arm matched
+        let default_value = if tail_ctx.is_some() { "return Value::Unit" } else { "Value::Unit" };
+        arms_code.line(&format!("_ => {{ {} }},", default_value));
+    });
+    full.close("}");
+    full.finish_expr()
+}
+
+// `Option<usize>` expression for the arm a decision tree picks
+fn emit_decision(decision: &Decision, scr_var: &str) -> String {
+    match decision {
+        Decision::Arm(index) => format!("Some({})", index),
+        Decision::Fail => "None".to_string(),
+        Decision::Switch { path, cases, default } => {
+            let mut code = CodeWriter::new();
+            code.block(&format!("match {} {{", match_path(scr_var, path)), "}", |cases_code| {
+                for (head, subtree) in cases {
+                    let case = match head {
+                        Head::Ctor(name, 0) => format!(
+                            "Value::Ctor {{ tag: {} /* {} */, fields }} if fields.is_empty() => ",
+                            constructor_tag(name), name
+                        ),
+                        Head::Ctor(name, arity) => format!(
+                            "Value::Ctor {{ tag: {} /* {} */, fields }} if fields.len() == {} => ",
+                            constructor_tag(name), name, arity
+                        ),
+                        Head::Int(n) => format!("Value::Int({}) => ", n),
+                        Head::Bool(b) => format!("Value::Bool({}) => ", b),
+                        Head::Unit => "Value::Unit => ".to_string(),
+                        Head::Tuple(arity) => format!("Value::Tuple(fields) if fields.len() == {} => ", arity),
+                    };
+                    cases_code.expr(&case, &emit_decision(subtree, scr_var), ",");
+                }
+                cases_code.expr("_ => ", &emit_decision(default, scr_var), ",");
+            });
+            code.finish_expr()
         }
     }
 }
 
+// `&Value` expression for a position in the scrutinee
+fn match_path(scr_var: &str, path: &[usize]) -> String {
+    path.iter().fold(format!("&{}", scr_var), |parent, index| format!("match_field({}, {})", parent, index))
+}
+
+/// Render a Core span as a Rust `Option<RuntimeSpan>` expression for
+/// axis_runtime_error and assertion calls in generated code
+fn emit_runtime_span(span: &Option<crate::core_ir::Span>) -> String {
+    match span {
+        Some(sp) => format!("Some((\"{}\", {}, {}))", sp.file.escape_default(), sp.line, sp.column),
+        None => "None".to_string(),
+    }
+}
+
 /// Recursively lower a pattern into a match arm pattern and a list of bindings
 /// Returns: (rust_pattern_string, vec_of_binding_statements)
 fn lower_pattern_recursive(
     pattern: &crate::core_ir::Pattern,
     scrutinee_expr: &str,
     module_path: &str,
+    // Rust expression for the enclosing match's Option<RuntimeSpan>
+    span_code: &str,
     temp_counter: &mut usize,
 ) -> (String, Vec<String>) {
     use crate::core_ir::Pattern;
 
     match pattern {
         Pattern::PInt(n) => {
-            eprintln!("[lower-pattern] PInt({})", n);
             (format!("Value::Int(x) if *x == {}", n), vec![])
         }
         Pattern::PBool(b) => {
-            eprintln!("[lower-pattern] PBool({})", b);
             (format!("Value::Bool(x) if *x == {}", b), vec![])
         }
         Pattern::PUnit => {
-            eprintln!("[lower-pattern] PUnit");
             ("Value::Unit".to_string(), vec![])
         }
         Pattern::PVar(name) => {
             // Discard pattern: emit no bindings
             if name == "_" {
-                eprintln!("[lower-pattern] PVar(_) -> discard (no bindings)");
                 return ("_".to_string(), vec![]);
             }
 
@@ -1048,20 +2030,16 @@ fn lower_pattern_recursive(
             //  FIX: Capitalized names are 0-arity constructors, not variables
             // Don't emit bindings for them - they're just pattern guards
             if bname.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
-                eprintln!("[lower-pattern] PVar({}) -> 0-arity ctor (no bindings)", name);
                 return ("_".to_string(), vec![]);
             }
 
-            eprintln!("[lower-pattern] PVar({}) -> bind {}", name, bname);
             let bindings = vec![format!("let {} = {}.clone();", bname, scrutinee_expr)];
 
-            eprintln!("[lower-pattern] PVar total bindings: {}", bindings.len());
             ("_".to_string(), bindings)
         }
         Pattern::PTuple(elements) => {
             let vec_ident = format!("__tuple_fields_{}", *temp_counter);
             *temp_counter += 1;
-            eprintln!("[lower-pattern] PTuple({} elems) -> {}", elements.len(), vec_ident);
 
             let mut bindings = Vec::new();
             for (i, sub_pat) in elements.iter().enumerate() {
@@ -1070,6 +2048,7 @@ fn lower_pattern_recursive(
                     sub_pat,
                     &field_expr,
                     module_path,
+                    span_code,
                     temp_counter
                 );
                 bindings.extend(sub_bindings);
@@ -1081,10 +2060,21 @@ fn lower_pattern_recursive(
             );
             (pattern_str, bindings)
         }
+        Pattern::PBind(name, inner) => {
+            // The whole value first, then what the inner pattern binds
+            let (pattern_str, mut bindings) = lower_pattern_recursive(
+                inner,
+                scrutinee_expr,
+                module_path,
+                span_code,
+                temp_counter
+            );
+            bindings.insert(0, format!("let {} = {}.clone();", sanitize_ident(name), scrutinee_expr));
+            (pattern_str, bindings)
+        }
         Pattern::PEnum(tag_name, fields) => {
             let fields_ident = "__ctor_fields".to_string();
             *temp_counter += 1;
-            eprintln!("[lower-pattern] PEnum({}, {} fields) -> {}", tag_name, fields.len(), fields_ident);
 
             let mut bindings = Vec::new();
             let mut literal_guards: Vec<String> = Vec::new();
@@ -1092,17 +2082,24 @@ fn lower_pattern_recursive(
             // Special case: 0-arity constructors (e.g., Nil, True, False)
             // Must NOT generate `let X::Y = ...` syntax
             if fields.is_empty() {
-                let stripped_tag = strip_namespaces(tag_name);
+                let name = constructor_name(tag_name);
                 let pattern_str = format!(
-                    "Value::Ctor {{ tag, fields: {} }} if get_tag_name(tag) == \"{}\" && {}.is_empty()",
-                    fields_ident, stripped_tag, fields_ident
+                    "Value::Ctor {{ tag: {} /* {} */, fields: {} }} if {}.is_empty()",
+                    constructor_tag(name), name, fields_ident, fields_ident
                 );
                 return (pattern_str, bindings);
             }
 
             // Recursively lower each field pattern
             for (i, sub_pat) in fields.iter().enumerate() {
+                // `name @ pattern` binds the whole field, then the pattern is lowered as usual
+                let mut sub_pat = sub_pat;
+                while let Pattern::PBind(name, inner) = sub_pat {
+                    bindings.push(format!("let {} = {}[{}].clone();", sanitize_ident(name), fields_ident, i));
+                    sub_pat = inner;
+                }
                 match sub_pat {
+                    Pattern::PBind(..) => {}
                     Pattern::PVar(vname) => {
                         // Skip discard patterns
                         if vname == "_" {
@@ -1111,14 +2108,12 @@ fn lower_pattern_recursive(
 
                         // Direct variable binding from field
                         let bname = sanitize_ident(vname);
-                        eprintln!("[lower-pattern] bind {} <- field({}[{}])", bname, fields_ident, i);
                         bindings.push(format!("let {} = {}[{}].clone();", bname, fields_ident, i));
                     }
                     Pattern::PEnum(sub_tag, sub_fields) => {
                         // Nested constructor: extract field to temp, then manually extract its fields
                         let temp_name = format!("__tmp_{}", *temp_counter);
                         *temp_counter += 1;
-                        eprintln!("[lower-pattern] nested PEnum({}) -> {}", sub_tag, temp_name);
                         bindings.push(format!("let {} = {}[{}].clone();", temp_name, fields_ident, i));
 
                         // Now extract fields from this nested constructor
@@ -1126,8 +2121,8 @@ fn lower_pattern_recursive(
                         let nested_fields_ident = format!("__ctor_fields_{}", *temp_counter);
                         *temp_counter += 1;
                         bindings.push(format!(
-                            "let {} = match &{} {{ Value::Ctor {{ fields, .. }} => fields, _ => panic!(\"Pattern mismatch\") }};",
-                            nested_fields_ident, temp_name
+                            "let {} = match &{} {{ Value::Ctor {{ fields, .. }} => fields, _ => axis_runtime_error(\"Pattern mismatch: expected constructor\", {}) }};",
+                            nested_fields_ident, temp_name, span_code
                         ));
 
                         // Recursively process the sub-fields
@@ -1137,6 +2132,7 @@ fn lower_pattern_recursive(
                                 subsub_pat,
                                 &field_expr,
                                 module_path,
+                                span_code,
                                 temp_counter
                             );
                             bindings.extend(subsub_bindings);
@@ -1151,15 +2147,14 @@ fn lower_pattern_recursive(
                         // Nested tuple: extract field to temp, then extract tuple elements
                         let temp_name = format!("__tmp_{}", *temp_counter);
                         *temp_counter += 1;
-                        eprintln!("[lower-pattern] nested PTuple({} elems) -> {}", tuple_elems.len(), temp_name);
                         bindings.push(format!("let {} = {}[{}].clone();", temp_name, fields_ident, i));
 
                         // Extract tuple fields
                         let tuple_fields_ident = format!("__tuple_fields_{}", *temp_counter);
                         *temp_counter += 1;
                         bindings.push(format!(
-                            "let {} = match &{} {{ Value::Tuple(elems) => elems, _ => panic!(\"Pattern mismatch\") }};",
-                            tuple_fields_ident, temp_name
+                            "let {} = match &{} {{ Value::Tuple(elems) => elems, _ => axis_runtime_error(\"Pattern mismatch: expected tuple\", {}) }};",
+                            tuple_fields_ident, temp_name, span_code
                         ));
 
                         // Recursively process tuple elements
@@ -1169,6 +2164,7 @@ fn lower_pattern_recursive(
                                 elem_pat,
                                 &elem_expr,
                                 module_path,
+                                span_code,
                                 temp_counter
                             );
                             bindings.extend(elem_bindings);
@@ -1189,16 +2185,15 @@ fn lower_pattern_recursive(
                 }
             }
 
-            // Strip namespace from tag_name for Loop-6 semantics
-            let stripped_tag = strip_namespaces(tag_name);
+            let name = constructor_name(tag_name);
             let mut pattern_str = format!(
-                "Value::Ctor {{ tag, fields: {} }} if get_tag_name(tag) == \"{}\"",
-                fields_ident, stripped_tag
+                "Value::Ctor {{ tag: {} /* {} */, fields: {} }}",
+                constructor_tag(name), name, fields_ident
             );
 
             // Add additional guards for literal field checks
-            for guard in &literal_guards {
-                pattern_str.push_str(&format!(" && {}", guard));
+            if !literal_guards.is_empty() {
+                pattern_str.push_str(&format!(" if {}", literal_guards.join(" && ")));
             }
 
             (pattern_str, bindings)
@@ -1207,7 +2202,7 @@ fn lower_pattern_recursive(
 }
 
 
-fn sanitize_ident(name: &str) -> String {
+pub(crate) fn sanitize_ident(name: &str) -> String {
     // Replace dots and dashes with underscores to make valid Rust identifiers
     let base = name.replace('.', "_").replace('-', "_");
 
@@ -1253,15 +2248,16 @@ fn sanitize_ident(name: &str) -> String {
 }
 
 ///  POLICY: Determine if a term needs .clone() when used
-/// Clone everything except literals to avoid borrow errors
-fn needs_clone(term: &CoreTerm) -> bool {
-    use crate::core_ir::CoreTerm;
-    match term {
-        CoreTerm::IntLit(_, _) => false,
-        CoreTerm::BoolLit(_, _) => false,
-        CoreTerm::UnitLit(_) => false,
-        CoreTerm::StrLit(_, _) => false,
-        _ => true,  // Clone: Var, App, Let, Lam, Tuple, Proj, If, Match
+/// The code of every term is already an owned Value (a variable is read as
+/// `x.clone()`), so cloning it again only copies a temporary; --keep-clones
+/// keeps the old policy of cloning everything except literals
+fn needs_clone(term: TermRef<'_>, options: &EmitOptions) -> bool {
+    options.keep_clones && match term.view() {
+        TermView::IntLit(_, _) => false,
+        TermView::BoolLit(_, _) => false,
+        TermView::UnitLit(_) => false,
+        TermView::StrLit(_, _) => false,
+        _ => true,  // Clone: Var, App, Let, Lam, Tuple, Proj, CtorField, If, Match
     }
 }
 
@@ -1270,7 +2266,7 @@ fn needs_clone(term: &CoreTerm) -> bool {
 /// compiler_main___Token::TokIdent -> compiler_main___TokIdent
 /// compiler_main___List::Nil -> compiler_main___Nil
 /// SurfaceAst::SStrLit -> SStrLit
-fn strip_namespaces(name: &str) -> String {
+pub(crate) fn strip_namespaces(name: &str) -> String {
     if let Some(colon_idx) = name.rfind("::") {
         // Always strip everything before the last ::
         name[colon_idx + 2..].to_string()
@@ -1279,3 +2275,16 @@ fn strip_namespaces(name: &str) -> String {
     }
 }
 
+/// The name a constructor's tag is interned under. The compiler names a
+/// declared constructor by its type's path (`geo.Shape.Circle`, see
+/// core-compiler constructors.rs), which is kept whole, so constructors of
+/// two types never share a tag. A `::` qualifier is Core naming a type
+/// nothing declares (`Option::Some`, the self-hosted compiler's
+/// `Token::TokIdent`) and is dropped, as it always was.
+pub(crate) fn constructor_name(name: &str) -> &str {
+    match name.rfind("::") {
+        Some(idx) => &name[idx + 2..],
+        None => name,
+    }
+}
+
@@ -4,8 +4,9 @@
 // 2. Foreign calls generate `shim::foreign_func` calls
 // 3. NO foreign stub functions are generated
 
-use crate::runtime::emit_rust::emit_rust_from_core;
-use crate::core_ir::CoreTerm;
+use crate::runtime::emit_rust::{EmitOptions, emit_rust_from_core, emit_rust_from_core_with_options};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::{CoreTerm, Span};
 use std::rc::Rc;
 
 #[test]
@@ -16,13 +17,13 @@ fn test_emit_rust_no_foreign_stubs() {
     
     // foo = λx. str_char_at(x, 0) 
     let core_ir = CoreTerm::Let(
-        "foo".to_string(),
+        "foo".into(),
         Rc::new(CoreTerm::Lam(
-            "x".to_string(),
+            "x".into(),
             Rc::new(CoreTerm::App(
                 Rc::new(CoreTerm::App(
-                    Rc::new(CoreTerm::Var("str_char_at".to_string(), None)),
-                    Rc::new(CoreTerm::Var("x".to_string(), None)),
+                    Rc::new(CoreTerm::Var("str_char_at".into(), None)),
+                    Rc::new(CoreTerm::Var("x".into(), None)),
                     None
                 )),
                 Rc::new(CoreTerm::IntLit(0, None)),
@@ -34,7 +35,7 @@ fn test_emit_rust_no_foreign_stubs() {
         None
     );
     
-    let generated_rust = emit_rust_from_core(&core_ir, "test.ax", "foo");
+    let generated_rust = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "test.ax", "foo");
     
     println!("Generated Rust code:");
     println!("{}", generated_rust);
@@ -62,12 +63,12 @@ fn test_emit_rust_no_foreign_stubs() {
 fn test_unmapped_foreign_symbol_fails_fast() {
     // Create Core IR that calls an unmapped foreign function
     let core_ir = CoreTerm::Let(
-        "test_func".to_string(),
+        "test_func".into(),
         Rc::new(CoreTerm::Lam(
-            "x".to_string(),
+            "x".into(),
             Rc::new(CoreTerm::App(
-                Rc::new(CoreTerm::Var("unmapped_foreign_function".to_string(), None)),
-                Rc::new(CoreTerm::Var("x".to_string(), None)), 
+                Rc::new(CoreTerm::Var("unmapped_foreign_function".into(), None)),
+                Rc::new(CoreTerm::Var("x".into(), None)), 
                 None
             )),
             None
@@ -78,7 +79,7 @@ fn test_unmapped_foreign_symbol_fails_fast() {
     
     // Should panic with the specific error message
     let result = std::panic::catch_unwind(|| {
-        emit_rust_from_core(&core_ir, "test.ax", "test_func")
+        emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "test.ax", "test_func")
     });
     
     assert!(result.is_err(), "Should panic on unmapped foreign symbol");
@@ -95,4 +96,593 @@ fn test_unmapped_foreign_symbol_fails_fast() {
     }
     
     println!("✅ Fail-fast test passed: unmapped foreign symbols cause proper error");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_unmapped_symbol_suggests_close_names() {
+    // helper = λx. x; main = λx. helpr(x)
+    let core_ir = CoreTerm::Let(
+        "helper".into(),
+        Rc::new(CoreTerm::Lam("x".into(), Rc::new(CoreTerm::Var("x".into(), None)), None)),
+        Rc::new(CoreTerm::Let(
+            "main".into(),
+            Rc::new(CoreTerm::Lam(
+                "x".into(),
+                Rc::new(CoreTerm::App(
+                    Rc::new(CoreTerm::Var("helpr".into(), None)),
+                    Rc::new(CoreTerm::Var("x".into(), None)),
+                    None
+                )),
+                None
+            )),
+            Rc::new(CoreTerm::UnitLit(None)),
+            None
+        )),
+        None
+    );
+
+    let panic_info = std::panic::catch_unwind(|| emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "test.ax", "main"))
+        .expect_err("helpr is not defined");
+    let msg = panic_info.downcast_ref::<String>().expect("formatted panic message");
+    assert!(msg.ends_with("(did you mean `helper`?)"), "{}", msg);
+}
+
+#[test]
+fn test_debug_build_emits_call_frames() {
+    // foo = λx. x   (defined at lib.ax:4:1)
+    let core_ir = CoreTerm::Let(
+        "foo".into(),
+        Rc::new(CoreTerm::Lam(
+            "x".into(),
+            Rc::new(CoreTerm::Var("x".into(), None)),
+            None
+        )),
+        Rc::new(CoreTerm::UnitLit(None)),
+        Some(Span { file: "lib.ax".to_string(), line: 4, column: 1 })
+    );
+
+    let plain = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "lib.ax", "foo");
+    assert!(!plain.contains("CallFrame::enter"),
+        "Non-debug builds must not instrument function prologues");
+
+    let options = EmitOptions { debug_call_stack: true, ..EmitOptions::default() };
+    let debug = emit_rust_from_core_with_options(ArenaProgram::from_term(&core_ir).root_term(), "lib.ax", "foo", &options);
+    assert!(debug.contains("CallFrame::enter(\"foo\", Some((\"lib.ax\", 4, 1)))"),
+        "Debug builds must push a shadow call-stack frame with the function span");
+}
+
+#[test]
+fn test_debug_info_maps_generated_lines_to_axis_functions() {
+    use crate::runtime::emit_rust::source_map;
+
+    // foo = λx. x (lib.ax:4:1); bar = λy. foo(y) (no span)
+    let core_ir = CoreTerm::Let(
+        "foo".into(),
+        Rc::new(CoreTerm::Lam("x".into(), Rc::new(CoreTerm::Var("x".into(), None)), None)),
+        Rc::new(CoreTerm::Let(
+            "bar".into(),
+            Rc::new(CoreTerm::Lam("y".into(), Rc::new(CoreTerm::App(
+                Rc::new(CoreTerm::Var("foo".into(), None)),
+                Rc::new(CoreTerm::Var("y".into(), None)),
+                None,
+            )), None)),
+            Rc::new(CoreTerm::UnitLit(None)),
+            None,
+        )),
+        Some(Span { file: "lib.ax".to_string(), line: 4, column: 1 }),
+    );
+    let program = ArenaProgram::from_term(&core_ir);
+
+    let plain = emit_rust_from_core(program.root_term(), "lib.ax", "foo");
+    assert_eq!(source_map("src/axis_generated.rs", &plain), "");
+
+    let options = EmitOptions { debug_info: true, ..EmitOptions::default() };
+    let debug = emit_rust_from_core_with_options(program.root_term(), "lib.ax", "foo", &options);
+    let lines: Vec<&str> = debug.lines().collect();
+    let map = source_map("src/axis_generated.rs", &debug);
+    let rows: Vec<&str> = map.lines().collect();
+    assert_eq!(rows.len(), 2, "{}", map);
+    assert!(rows[0].ends_with("\tfoo\tlib.ax:4:1") && rows[1].ends_with("\tbar\t-"), "{}", map);
+    // Each row points at its function's `fn` line
+    for (row, name) in rows.iter().zip(["fn foo(", "fn bar("]) {
+        let line: usize = row.split('\t').next().unwrap().rsplit(':').next().unwrap().parse().unwrap();
+        assert!(lines[line - 1].starts_with(name), "line {} is {:?}", line, lines[line - 1]);
+    }
+}
+
+#[test]
+fn test_debug_project_embeds_axis_sources() {
+    use crate::backend::{backend_by_name, embedded_sources};
+    use std::path::{Path, PathBuf};
+
+    let core_ir = CoreTerm::Let(
+        "axis_entry".into(),
+        Rc::new(CoreTerm::Lam("args".into(), Rc::new(CoreTerm::IntLit(0, None)), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None,
+    );
+    let program = ArenaProgram::from_term(&core_ir);
+    let sources = vec![("examples/main.ax".to_string(), "fn main() = 0\n".to_string())];
+    let blob = embedded_sources(&sources);
+    assert_eq!(blob, "axis-source examples/main.ax 14\nfn main() = 0\n");
+
+    let options = EmitOptions { debug_info: true, ..EmitOptions::default() };
+    let rust = backend_by_name("rust", Path::new("/opt/axis-rust-bridge")).unwrap();
+    let release = rust.emit(&program, &EmitOptions::default()).unwrap();
+    assert_eq!(release.output, PathBuf::from("target/release/axis_emitted"));
+    let mut debug = rust.emit(&program, &options).unwrap();
+    assert_eq!(debug.build_command, ["cargo", "build"]);
+    assert_eq!(debug.output, PathBuf::from("target/debug/axis_emitted"));
+    rust.embed_sources(&mut debug, &sources);
+    let file = |artifacts: &crate::backend::EmitArtifacts, path: &str| {
+        artifacts.files.iter().find(|(p, _)| p == Path::new(path)).map(|(_, text)| text.clone())
+    };
+    assert!(file(&debug, "src/main.rs").unwrap().starts_with("mod axis_sources;\n"));
+    assert!(file(&debug, "src/axis_sources.rs").unwrap().contains(&format!("[u8; {}]", blob.len())));
+    assert_eq!(file(&debug, "axis_sources.txt").unwrap(), blob);
+    assert_eq!(file(&debug, "axis_sources/main.ax").unwrap(), sources[0].1);
+
+    let c = backend_by_name("c", Path::new(".")).unwrap();
+    let mut debug = c.emit(&program, &options).unwrap();
+    assert!(debug.build_command.iter().any(|arg| arg == "-g") && !debug.build_command.iter().any(|arg| arg == "-O2"));
+    c.embed_sources(&mut debug, &sources);
+    assert!(debug.build_command.last().is_some_and(|arg| arg == "axis_sources.c"));
+    assert!(file(&debug, "axis_sources.c").unwrap().contains(&format!("axis_sources[{}]", blob.len())));
+}
+
+#[test]
+fn test_assertion_calls_pass_call_span() {
+    // check = λx. axis_assert_eq(x, 1)   (call at t.ax:2:5)
+    let core_ir = CoreTerm::Let(
+        "check".into(),
+        Rc::new(CoreTerm::Lam(
+            "x".into(),
+            Rc::new(CoreTerm::App(
+                Rc::new(CoreTerm::App(
+                    Rc::new(CoreTerm::Var("axis_assert_eq".into(), None)),
+                    Rc::new(CoreTerm::Var("x".into(), None)),
+                    None
+                )),
+                Rc::new(CoreTerm::IntLit(1, None)),
+                Some(Span { file: "t.ax".to_string(), line: 2, column: 5 })
+            )),
+            None
+        )),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+
+    let generated_rust = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "t.ax", "check");
+    assert!(generated_rust.contains("shim::assert_eq(Value::Tuple(vec!["),
+        "Expected axis_assert_eq to call shim::assert_eq with a packed tuple");
+    assert!(generated_rust.contains(", Some((\"t.ax\", 2, 5)))"),
+        "Expected the call's span as the second shim argument");
+}
+
+// lower = λparse_term. app(json_body, 0): json_body only exists through the
+// `_term` -> `_body` name-drift alias
+fn name_drift_program() -> CoreTerm {
+    CoreTerm::Let(
+        "lower".into(),
+        Rc::new(CoreTerm::Lam(
+            "json_term".into(),
+            Rc::new(CoreTerm::App(
+                Rc::new(CoreTerm::Var("int_to_str".into(), None)),
+                Rc::new(CoreTerm::Var(
+                    "json_body".into(),
+                    Some(Span { file: "lower.ax".to_string(), line: 7, column: 12 })
+                )),
+                None
+            )),
+            None
+        )),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    )
+}
+
+#[test]
+fn test_strict_codegen_reports_name_drift() {
+    use crate::runtime::emit_rust::check_strict_codegen;
+
+    let errors = check_strict_codegen(ArenaProgram::from_term(&name_drift_program()).root_term()).expect_err("json_body is unresolved");
+    assert_eq!(errors.len(), 1);
+    let message = errors[0].to_string();
+    assert!(message.starts_with("AX2001: 'json_body' in function 'lower' at lower.ax:7:12"),
+        "unexpected message: {}", message);
+    assert!(message.contains("in term: (app (var int_to_str) (var json_body))"),
+        "error must show the originating term: {}", message);
+    assert!(errors[0].masked_by.as_deref().is_some_and(|m| m.contains("json_term")),
+        "error must name the workaround that hid it: {}", message);
+}
+
+#[test]
+fn test_strict_codegen_disables_workarounds() {
+    use crate::runtime::emit_rust::check_strict_codegen;
+
+    // λc_term. c_pattern: c_body is provided only by the aliases, and
+    // c_pattern (formerly read as a field of `c`) by nothing
+    let core_ir = CoreTerm::Let(
+        "f".into(),
+        Rc::new(CoreTerm::Lam(
+            "x".into(),
+            Rc::new(CoreTerm::Let(
+                "c_term".into(),
+                Rc::new(CoreTerm::Var("x".into(), None)),
+                Rc::new(CoreTerm::Var("c_pattern".into(), None)),
+                None
+            )),
+            None
+        )),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+
+    let lenient = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "f.ax", "f");
+    assert!(lenient.contains("let c_body = c_term.clone();"));
+
+    let options = EmitOptions { strict_codegen: true, ..EmitOptions::default() };
+    let strict = emit_rust_from_core_with_options(ArenaProgram::from_term(&core_ir).root_term(), "f.ax", "f", &options);
+    assert!(!strict.contains("let c_body") && !strict.contains("let c = "),
+        "strict codegen must not emit name-drift aliases:\n{}", strict);
+
+    let errors = check_strict_codegen(ArenaProgram::from_term(&core_ir).root_term()).expect_err("c_pattern is unresolved");
+    assert!(errors[0].masked_by.is_none(), "no workaround covers c_pattern: {}", errors[0]);
+}
+
+#[test]
+fn test_ctor_field_emits_field_read() {
+    // f(x) = let p = x in field(p, 1)
+    let core_ir = CoreTerm::Let(
+        "f".into(),
+        Rc::new(CoreTerm::Lam(
+            "x".into(),
+            Rc::new(CoreTerm::Let(
+                "p".into(),
+                Rc::new(CoreTerm::Var("x".into(), None)),
+                Rc::new(CoreTerm::CtorField(Rc::new(CoreTerm::Var("p".into(), None)), 1, None)),
+                None
+            )),
+            None
+        )),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+
+    let rust_code = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "f.ax", "f");
+    assert!(rust_code.contains("shim::ctor_field(Value::Tuple(vec![p, ") && rust_code.contains("Value::Int(1)]))"),
+        "CtorField must read the field directly:\n{}", rust_code);
+    assert!(crate::runtime::emit_rust::check_strict_codegen(ArenaProgram::from_term(&core_ir).root_term()).is_ok());
+}
+
+#[test]
+fn test_projection_is_zero_based() {
+    // f(x) = proj((x, 2), 0)
+    let pair = CoreTerm::Tuple(vec![CoreTerm::Var("x".into(), None), CoreTerm::IntLit(2, None)], None);
+    let core_ir = CoreTerm::Let(
+        "f".into(),
+        Rc::new(CoreTerm::Lam("x".into(), Rc::new(CoreTerm::Proj(Rc::new(pair), 0, None)), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+
+    let rust_code = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "f.ax", "f");
+    assert!(rust_code.contains("shim::tuple_field(Value::Tuple(vec![") && rust_code.contains(", Value::Int(0)]))"),
+        "proj index 0 is the first field:\n{}", rust_code);
+}
+
+#[test]
+fn test_constructor_tags_are_fixed_at_compile_time() {
+    use crate::core_ir::Pattern;
+    use crate::runtime::value::WELL_KNOWN_TAGS;
+
+    // area(s) = match s { Shape::Rect(w, 1) => w, Some(n) => n, Empty => Circle(0) }
+    let arms = vec![
+        (Pattern::PEnum("Shape::Rect".to_string(), vec![Pattern::PVar("w".to_string()), Pattern::PInt(1)]), CoreTerm::Var("w".into(), None)),
+        (Pattern::PEnum("Some".to_string(), vec![Pattern::PVar("n".to_string())]), CoreTerm::Var("n".into(), None)),
+        (Pattern::PEnum("Empty".to_string(), vec![]), CoreTerm::Ctor("Circle".into(), vec![CoreTerm::IntLit(0, None)], None)),
+    ];
+    let body = CoreTerm::Match(Rc::new(CoreTerm::Var("s".into(), None)), arms, None);
+    let core_ir = CoreTerm::Let(
+        "area".into(),
+        Rc::new(CoreTerm::Lam("s".into(), Rc::new(body), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+
+    let rust_code = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "t.ax", "area");
+    let rect = WELL_KNOWN_TAGS.len();
+    let expected_arms = [
+        format!("Value::Ctor {{ tag: {} /* Rect */, fields }} if fields.len() == 2 => match match_field(&scr, 1) {{", rect),
+        "Value::Ctor { tag: 3 /* Some */, fields } if fields.len() == 1 => Some(1),".to_string(),
+        format!("Value::Ctor {{ tag: {} /* Empty */, fields }} if fields.is_empty() => Some(2),", rect + 1),
+        format!("Value::Ctor {{ tag: {} /* Circle */, fields: vec![Value::Int(0)] }}", rect + 2),
+    ];
+    for expected in &expected_arms {
+        assert!(rust_code.contains(expected.as_str()), "missing `{}`:\n{}", expected, rust_code);
+    }
+    assert!(!rust_code.contains("get_tag_name") && !rust_code.contains("intern_tag"), "{}", rust_code);
+
+    // The table the generated main registers, well-known tags first
+    let table = rust_code.split("pub static AXIS_CONSTRUCTOR_TAGS: &[&str] = &[").nth(1).expect("tag table");
+    let names: Vec<&str> = table.lines().skip(1).take_while(|line| !line.starts_with("];")).map(|line| line.trim().split('"').nth(1).unwrap()).collect();
+    let mut expected_names = WELL_KNOWN_TAGS.to_vec();
+    expected_names.extend(["Rect", "Empty", "Circle"]);
+    assert_eq!(names, expected_names);
+}
+
+#[test]
+fn test_qualified_constructors_keep_their_type() {
+    use crate::core_ir::Pattern;
+    use crate::runtime::value::WELL_KNOWN_TAGS;
+
+    // ok(r) = match r { http.Status.Ok => db.Reply.Ok, _ => Option::Some(1) }
+    let arms = vec![
+        (Pattern::PEnum("http.Status.Ok".to_string(), vec![]), CoreTerm::Ctor("db.Reply.Ok".into(), vec![], None)),
+        (Pattern::PVar("_".to_string()), CoreTerm::Ctor("Option::Some".into(), vec![CoreTerm::IntLit(1, None)], None)),
+    ];
+    let body = CoreTerm::Match(Rc::new(CoreTerm::Var("r".into(), None)), arms, None);
+    let core_ir = CoreTerm::Let(
+        "ok".into(),
+        Rc::new(CoreTerm::Lam("r".into(), Rc::new(body), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+
+    let rust_code = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "t.ax", "ok");
+    let first = WELL_KNOWN_TAGS.len();
+    // Neither is the runtime's Ok (tag 4), nor the other
+    assert!(rust_code.contains(&format!("tag: {} /* http.Status.Ok */", first)), "{}", rust_code);
+    assert!(rust_code.contains(&format!("tag: {} /* db.Reply.Ok */", first + 1)), "{}", rust_code);
+    // A type nothing declares does not qualify its constructor
+    assert!(rust_code.contains("tag: 3 /* Some */"), "{}", rust_code);
+}
+
+#[test]
+fn test_nested_patterns_are_tested_by_the_decision_tree() {
+    use crate::core_ir::Pattern;
+
+    // second(l) = match l { Cons(_, Cons(7, _)) => 1, _ => 0 }
+    let seven = Pattern::PEnum("Cons".to_string(), vec![Pattern::PInt(7), Pattern::PVar("_".to_string())]);
+    let arms = vec![
+        (Pattern::PEnum("Cons".to_string(), vec![Pattern::PVar("_".to_string()), seven]), CoreTerm::IntLit(1, None)),
+        (Pattern::PVar("_".to_string()), CoreTerm::IntLit(0, None)),
+    ];
+    let core_ir = CoreTerm::Let(
+        "second".into(),
+        Rc::new(CoreTerm::Lam("l".into(), Rc::new(CoreTerm::Match(Rc::new(CoreTerm::Var("l".into(), None)), arms, None)), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+    let program = ArenaProgram::from_term(&core_ir);
+
+    let tree = emit_rust_from_core(program.root_term(), "t.ax", "second");
+    assert!(tree.contains("match match_field(match_field(&scr, 1), 0) {") && tree.contains("Value::Int(7) => Some(0),"),
+        "the literal inside the nested constructor must be tested:\n{}", tree);
+
+    let options = EmitOptions { linear_matches: true, ..EmitOptions::default() };
+    let linear = emit_rust_from_core_with_options(program.root_term(), "t.ax", "second", &options);
+    assert!(linear.contains("Value::Ctor { tag: 1 /* Cons */, fields: __ctor_fields } => {") && !linear.contains("__arm"), "{}", linear);
+}
+
+#[test]
+fn test_last_uses_move_and_fresh_values_are_not_cloned() {
+    // twice(s) = let n = str_len(s) in (n, int_to_str(n))
+    let var = |name: &str| Rc::new(CoreTerm::Var(name.into(), None));
+    let app = |f: Rc<CoreTerm>, x: Rc<CoreTerm>| Rc::new(CoreTerm::App(f, x, None));
+    let pair = CoreTerm::Tuple(vec![CoreTerm::Var("n".into(), None), (*app(var("int_to_str"), var("n"))).clone()], None);
+    let body = CoreTerm::Let("n".into(), app(var("str_len"), var("s")), Rc::new(pair), None);
+    let core_ir = CoreTerm::Let(
+        "twice".into(),
+        Rc::new(CoreTerm::Lam("s".into(), Rc::new(body), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None
+    );
+    let program = ArenaProgram::from_term(&core_ir);
+
+    let moved = emit_rust_from_core(program.root_term(), "t.ax", "twice");
+    assert!(moved.contains("let n = shim::str_len(s);"), "{}", moved);
+    assert!(moved.contains("Value::Tuple(vec![n.clone(), shim::int_to_str(n.clone())])"), "n is read twice:\n{}", moved);
+
+    let options = EmitOptions { keep_clones: true, ..EmitOptions::default() };
+    let cloned = emit_rust_from_core_with_options(program.root_term(), "t.ax", "twice", &options);
+    assert!(cloned.contains("let n = shim::str_len(s.clone().clone());"), "{}", cloned);
+    assert!(cloned.contains("shim::int_to_str(n.clone().clone()).clone()"), "{}", cloned);
+}
+
+#[test]
+fn test_entry_alias_forwards_to_target() {
+    // start(args) = 0; axis_entry = start  (as lowered for `@entry fn start`)
+    let core_ir = CoreTerm::Let(
+        "start".into(),
+        Rc::new(CoreTerm::Lam(
+            "args".into(),
+            Rc::new(CoreTerm::IntLit(0, None)),
+            None
+        )),
+        Rc::new(CoreTerm::Let(
+            "axis_entry".into(),
+            Rc::new(CoreTerm::Var("start".into(), None)),
+            Rc::new(CoreTerm::UnitLit(None)),
+            None
+        )),
+        None
+    );
+
+    let rust_code = emit_rust_from_core(ArenaProgram::from_term(&core_ir).root_term(), "start.ax", "start");
+    assert!(rust_code.contains("pub fn axis_entry(args: Value) -> Value {\n    start(args)\n}"),
+        "alias must take the target's argument:\n{}", rust_code);
+}
+
+#[test]
+fn test_rust_backend_artifacts() {
+    use crate::backend::{backend_by_name, EmitError};
+    use crate::core_ir::CoreProgram;
+    use std::path::{Path, PathBuf};
+
+    // axis_entry(param) = body
+    let entry = |param: &str, body: &str| ArenaProgram::from_program(&CoreProgram {
+        strings: vec![],
+        root_term: CoreTerm::Let(
+            "axis_entry".into(),
+            Rc::new(CoreTerm::Lam(
+                param.into(),
+                Rc::new(CoreTerm::Var(body.into(), None)),
+                None
+            )),
+            Rc::new(CoreTerm::UnitLit(None)),
+            None
+        ),
+        entrypoint_id: 0,
+    });
+    // `args` only resolves through the `args_term` -> `args` workaround
+    let program = entry("args_term", "args");
+
+    let backend = backend_by_name("rust", Path::new("/opt/axis-rust-bridge")).unwrap();
+    let artifacts = backend.emit(&program, &EmitOptions::default()).unwrap();
+    let files: Vec<&PathBuf> = artifacts.files.iter().map(|(path, _)| path).collect();
+    assert_eq!(files, [Path::new("Cargo.toml"), Path::new("src/axis_generated.rs"), Path::new("src/main.rs")]);
+    assert!(artifacts.files[0].1.contains("path = \"/opt/axis-rust-bridge\""));
+    assert!(artifacts.files[1].1.contains("pub fn axis_entry"));
+    assert_eq!(artifacts.output, Path::new("target/release/axis_emitted"));
+
+    let strict = EmitOptions { strict_codegen: true, ..EmitOptions::default() };
+    assert!(matches!(backend.emit(&program, &strict), Err(EmitError::UnresolvedReferences(ref errors)) if errors.len() == 1));
+    // Nothing resolves an unbound `y`, with or without --strict-codegen
+    let unbound = entry("args", "y");
+    assert!(matches!(backend.emit(&unbound, &EmitOptions::default()),
+        Err(EmitError::UnresolvedReferences(ref errors)) if errors.len() == 1 && errors[0].masked_by.is_none()));
+    assert!(backend_by_name("wasm", Path::new(".")).is_err());
+}
+
+#[test]
+fn test_emission_is_deterministic() {
+    use crate::backend::backend_by_name;
+    use crate::core_ir::CoreProgram;
+    use std::path::Path;
+
+    let var = |name: &str| Rc::new(CoreTerm::Var(name.into(), None));
+    let app = |f: Rc<CoreTerm>, x: Rc<CoreTerm>| Rc::new(CoreTerm::App(f, x, None));
+    let lam = |param: &str, body: Rc<CoreTerm>| Rc::new(CoreTerm::Lam(param.into(), body, None));
+    let let_ = |name: &str, value: Rc<CoreTerm>, body: Rc<CoreTerm>| CoreTerm::Let(name.into(), value, body, None);
+
+    // shout(x) = str_concat(int_to_str(x), "!"); twice(x) = x + x
+    // axis_entry(args) = io_print(shout(twice(str_len("abc"))))
+    let shout = lam("x", app(
+        app(var("str_concat"), app(var("int_to_str"), var("x"))),
+        Rc::new(CoreTerm::StrLit("!".to_string(), None)),
+    ));
+    let twice = lam("x", app(app(var("__add__"), var("x")), var("x")));
+    let entry = lam("args", app(var("io_print"), app(var("shout"), app(var("twice"),
+        app(var("str_len"), Rc::new(CoreTerm::StrLit("abc".to_string(), None)))))));
+    let program = ArenaProgram::from_program(&CoreProgram {
+        strings: vec![],
+        root_term: let_("shout", shout, Rc::new(let_("twice", twice, Rc::new(let_("axis_entry", entry, Rc::new(CoreTerm::UnitLit(None))))))),
+        entrypoint_id: 0,
+    });
+
+    // Every emission builds fresh hash maps with fresh seeds
+    for name in crate::backend::BACKENDS {
+        let backend = backend_by_name(name, Path::new("/opt/axis-rust-bridge")).unwrap();
+        let first = backend.emit(&program, &EmitOptions::default()).unwrap();
+        for _ in 0..4 {
+            let again = backend.emit(&program, &EmitOptions::default()).unwrap();
+            assert_eq!(first.files, again.files, "{} backend output differs between runs", name);
+        }
+    }
+}
+
+#[test]
+fn test_streaming_emission_matches_in_memory() {
+    use crate::core_ir::{create_core_bundle, BundleLoadOptions};
+    use crate::runtime::emit_rust::emit_rust_streaming;
+
+    let var = |name: &str| Rc::new(CoreTerm::Var(name.into(), None));
+    let app = |f: Rc<CoreTerm>, x: Rc<CoreTerm>| Rc::new(CoreTerm::App(f, x, None));
+    let lam = |param: &str, body: Rc<CoreTerm>| Rc::new(CoreTerm::Lam(param.into(), body, None));
+    let let_ = |name: &str, value: Rc<CoreTerm>, body: Rc<CoreTerm>| CoreTerm::Let(name.into(), value, body, None);
+
+    // twice(x) = x + x; double = twice (an alias keeps its target's arity)
+    // axis_entry(args) = io_print(int_to_str(double(21)))
+    let twice = lam("x", app(app(var("__add__"), var("x")), var("x")));
+    let entry = lam("args", app(var("io_print"), app(var("int_to_str"),
+        app(var("double"), Rc::new(CoreTerm::IntLit(21, None))))));
+    let term = let_("twice", twice, Rc::new(let_("double", var("twice"),
+        Rc::new(let_("axis_entry", entry, Rc::new(CoreTerm::UnitLit(None)))))));
+
+    let expected = emit_rust_from_core_with_options(ArenaProgram::from_term(&term).root_term(), "t.ax", "axis_entry", &EmitOptions::default());
+    let bundle = create_core_bundle(&term, "axis_entry");
+    let mut streamed = Vec::new();
+    emit_rust_streaming(&bundle, &BundleLoadOptions::default(), &mut streamed, &EmitOptions::default()).unwrap();
+    assert_eq!(String::from_utf8(streamed).unwrap(), expected);
+}
+
+#[test]
+fn test_native_args_pass_parameters_unpacked() {
+    let var = |name: &str| Rc::new(CoreTerm::Var(name.into(), None));
+    let int = |n: i64| Rc::new(CoreTerm::IntLit(n, None));
+    let call = |f: &str, x: Rc<CoreTerm>, y: Rc<CoreTerm>| {
+        Rc::new(CoreTerm::App(Rc::new(CoreTerm::App(var(f), x, None)), y, None))
+    };
+    let lam = |param: &str, body: Rc<CoreTerm>| Rc::new(CoreTerm::Lam(param.into(), body, None));
+
+    // sum(n, acc) = if n == 0 { acc } else { sum(n - 1, acc + n) }
+    // axis_entry(args) = sum(10, 0)
+    let body = CoreTerm::If(
+        call("__eq__", var("n"), int(0)),
+        var("acc"),
+        call("sum", call("__sub__", var("n"), int(1)), call("__add__", var("acc"), var("n"))),
+        None
+    );
+    let sum = lam("n", lam("acc", Rc::new(body)));
+    let entry = lam("args", call("sum", int(10), int(0)));
+    let core_ir = CoreTerm::Let(
+        "sum".into(),
+        sum,
+        Rc::new(CoreTerm::Let("axis_entry".into(), entry, Rc::new(CoreTerm::UnitLit(None)), None)),
+        None
+    );
+    let program = ArenaProgram::from_term(&core_ir);
+
+    let options = EmitOptions { native_args: true, ..EmitOptions::default() };
+    let native = emit_rust_from_core_with_options(program.root_term(), "t.ax", "axis_entry", &options);
+    assert!(native.contains("fn sum(mut n: Value, mut acc: Value) -> Value {"), "{}", native);
+    assert!(native.contains("(n, acc) = (shim::__sub__(") && native.contains("continue"), "{}", native);
+    assert!(native.contains("sum(Value::Int(10), Value::Int(0))") && !native.contains("tuple_field"), "{}", native);
+    // The entry point keeps its packed argument
+    assert!(native.contains("pub fn axis_entry(args: Value) -> Value {"), "{}", native);
+
+    let packed = emit_rust_from_core(program.root_term(), "t.ax", "axis_entry");
+    assert!(packed.contains("fn sum(mut args: Value) -> Value {") && packed.contains("sum(Value::Tuple(vec![Value::Int(10), Value::Int(0)]))"),
+        "{}", packed);
+}
+
+#[test]
+fn test_spawn_passes_the_rust_function() {
+    let var = |name: &str| Rc::new(CoreTerm::Var(name.into(), None));
+    let call = |f: &str, x: Rc<CoreTerm>, y: Rc<CoreTerm>| {
+        Rc::new(CoreTerm::App(Rc::new(CoreTerm::App(var(f), x, None)), y, None))
+    };
+    let lam = |param: &str, body: Rc<CoreTerm>| Rc::new(CoreTerm::Lam(param.into(), body, None));
+
+    // work(a, b) = a + b
+    // axis_entry(args) = axis_join(axis_spawn(work, (1, 2)))
+    let work = lam("a", lam("b", call("__add__", var("a"), var("b"))));
+    let pair = Rc::new(CoreTerm::Tuple(vec![CoreTerm::IntLit(1, None), CoreTerm::IntLit(2, None)], None));
+    let spawned = call("axis_spawn", var("work"), pair);
+    let entry = lam("args", Rc::new(CoreTerm::App(var("axis_join"), spawned, None)));
+    let core_ir = CoreTerm::Let(
+        "work".into(),
+        work,
+        Rc::new(CoreTerm::Let("axis_entry".into(), entry, Rc::new(CoreTerm::UnitLit(None)), None)),
+        None
+    );
+    let program = ArenaProgram::from_term(&core_ir);
+
+    // Passed as a value, work keeps the packed argument even with --native-args
+    let options = EmitOptions { native_args: true, ..EmitOptions::default() };
+    let generated_rust = emit_rust_from_core_with_options(program.root_term(), "t.ax", "axis_entry", &options);
+    assert!(generated_rust.contains("fn work(args: Value) -> Value {"), "{}", generated_rust);
+    assert!(generated_rust.contains("shim::join(shim::spawn(work, Value::Tuple(vec![Value::Int(1), Value::Int(2)])"),
+        "{}", generated_rust);
+}
@@ -0,0 +1,79 @@
+// The generated main wrapper's side of running axis_entry (backend.rs):
+// the arguments it passes in and the exit code it makes of the result
+//
+// `build --entry-args-format` picks the argument's shape; the program name
+// is left out of both:
+//   list  the arguments as a List[Str] (Nil/Cons), the default
+//   raw   (argc, argv): the number of arguments and a tuple of them, for
+//         entry functions that index their arguments
+//
+// The result follows the EXIT CODE CONTRACT in error.rs: Int(n) exits n,
+// Unit and Ok(..) exit 0, Err(e) prints e to stderr and exits 1. Any other
+// result exits 0 after a warning on stderr, since it is most likely an
+// entry function that returns the wrong thing.
+
+use crate::runtime::error::{assertion_failures, AXIS_ASSERTION_FAILED_EXIT_CODE, AXIS_ENTRY_ERR_EXIT_CODE};
+use crate::runtime::inspect::pretty_text;
+use crate::runtime::list::cons_list;
+use crate::runtime::value::{get_tag_name, tag_display_name, Value};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EntryArgsFormat {
+    #[default]
+    List,
+    Raw,
+}
+
+impl EntryArgsFormat {
+    pub const ALL: [EntryArgsFormat; 2] = [EntryArgsFormat::List, EntryArgsFormat::Raw];
+
+    pub fn parse(name: &str) -> Option<EntryArgsFormat> {
+        EntryArgsFormat::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EntryArgsFormat::List => "list",
+            EntryArgsFormat::Raw => "raw",
+        }
+    }
+}
+
+/// axis_entry's argument for the command-line arguments `args` (without
+/// the program name)
+pub fn entry_args(format: EntryArgsFormat, args: &[String]) -> Value {
+    let strings: Vec<Value> = args.iter().map(|arg| Value::string(arg)).collect();
+    match format {
+        EntryArgsFormat::List => cons_list(strings),
+        EntryArgsFormat::Raw => Value::Tuple(vec![Value::Int(strings.len() as i64), Value::Tuple(strings)]),
+    }
+}
+
+/// The process exit code for axis_entry's `result`; failed assertions take
+/// precedence over it
+pub fn entry_exit_code(result: &Value) -> i32 {
+    if assertion_failures() > 0 {
+        return AXIS_ASSERTION_FAILED_EXIT_CODE;
+    }
+    match result {
+        Value::Int(n) => *n as i32,
+        Value::Unit => 0,
+        Value::Ctor { tag, fields } if fields.len() == 1 && get_tag_name(*tag) == "Ok" => 0,
+        Value::Ctor { tag, fields } if fields.len() == 1 && get_tag_name(*tag) == "Err" => {
+            let message = match &fields[0] {
+                Value::Str(s) => s.text().to_string(),
+                other => pretty_text(other),
+            };
+            eprintln!("error: {}", message);
+            AXIS_ENTRY_ERR_EXIT_CODE
+        }
+        other => {
+            let kind = match other {
+                Value::Ctor { tag, .. } => format!("constructor {}", tag_display_name(*tag)),
+                _ => other.kind_name().to_string(),
+            };
+            eprintln!("warning: axis_entry returned a {}, not an Int, Unit or Result; exiting with 0", kind);
+            0
+        }
+    }
+}
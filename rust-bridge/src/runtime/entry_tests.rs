@@ -0,0 +1,82 @@
+// Tests for the main wrapper's arguments and exit codes (entry.rs) and
+// `build --entry-args-format`
+
+use crate::backend::{backend_by_name, EmitError};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::emit_rust::EmitOptions;
+use crate::runtime::entry::{entry_args, entry_exit_code, EntryArgsFormat};
+use crate::runtime::error::{record_assertion_failures, AXIS_ASSERTION_FAILED_EXIT_CODE};
+use crate::runtime::list::list_elements;
+use crate::runtime::value::{init_runtime, intern_tag, Value};
+use std::path::Path;
+use std::rc::Rc;
+
+fn ctor(tag: &str, fields: Vec<Value>) -> Value {
+    Value::Ctor { tag: intern_tag(tag), fields }
+}
+
+#[test]
+fn test_parse_format() {
+    for format in EntryArgsFormat::ALL {
+        assert_eq!(EntryArgsFormat::parse(format.name()), Some(format));
+    }
+    assert_eq!(EntryArgsFormat::default(), EntryArgsFormat::List);
+    assert_eq!(EntryArgsFormat::parse("argv"), None);
+}
+
+#[test]
+fn test_entry_args_in_each_format() {
+    init_runtime();
+    let args = vec!["-v".to_string(), "in.ax".to_string()];
+    let list = entry_args(EntryArgsFormat::List, &args);
+    assert_eq!(list_elements(&list), [Value::string("-v"), Value::string("in.ax")]);
+    assert_eq!(
+        entry_args(EntryArgsFormat::Raw, &args),
+        Value::Tuple(vec![Value::Int(2), Value::Tuple(vec![Value::string("-v"), Value::string("in.ax")])])
+    );
+    assert_eq!(entry_args(EntryArgsFormat::Raw, &[]), Value::Tuple(vec![Value::Int(0), Value::Tuple(vec![])]));
+}
+
+#[test]
+fn test_exit_codes_of_results() {
+    init_runtime();
+    assert_eq!(entry_exit_code(&Value::Int(4)), 4);
+    assert_eq!(entry_exit_code(&Value::Unit), 0);
+    assert_eq!(entry_exit_code(&ctor("Ok", vec![Value::Int(9)])), 0);
+    assert_eq!(entry_exit_code(&ctor("Err", vec![Value::string("no input")])), 1);
+    assert_eq!(entry_exit_code(&ctor("Err", vec![Value::Int(2)])), 1);
+    // Anything else warns and exits 0
+    assert_eq!(entry_exit_code(&Value::Bool(true)), 0);
+    assert_eq!(entry_exit_code(&ctor("Some", vec![Value::Int(1)])), 0);
+}
+
+#[test]
+fn test_assertion_failures_take_precedence() {
+    init_runtime();
+    record_assertion_failures(1);
+    assert_eq!(entry_exit_code(&ctor("Err", vec![Value::string("ignored")])), AXIS_ASSERTION_FAILED_EXIT_CODE);
+}
+
+#[test]
+fn test_wrapper_takes_the_format() {
+    // axis_entry(args) = ()
+    let entry = CoreTerm::Lam("args".into(), Rc::new(CoreTerm::UnitLit(None)), None);
+    let program =
+        ArenaProgram::from_term(&CoreTerm::Let("axis_entry".into(), Rc::new(entry), Rc::new(CoreTerm::UnitLit(None)), None));
+    let main = |opts: &EmitOptions| {
+        let rust = backend_by_name("rust", Path::new("/opt/axis-rust-bridge")).unwrap();
+        let artifacts = rust.emit(&program, opts).unwrap();
+        artifacts.files.into_iter().find(|(path, _)| path == Path::new("src/main.rs")).unwrap().1
+    };
+    assert!(main(&EmitOptions::default()).contains("entry_args(EntryArgsFormat::List, &cli_args)"));
+    let raw = EmitOptions { entry_args: EntryArgsFormat::Raw, ..EmitOptions::default() };
+    assert!(main(&raw).contains("entry_args(EntryArgsFormat::Raw, &cli_args)"));
+
+    // The C runtime's main always passes a list
+    let c = backend_by_name("c", Path::new("/opt/axis-rust-bridge")).unwrap();
+    match c.emit(&program, &raw) {
+        Err(EmitError::Unsupported(msg)) => assert_eq!(msg, "--entry-args-format raw needs the rust backend"),
+        other => panic!("expected Unsupported, got {:?}", other.map(|_| ())),
+    }
+}
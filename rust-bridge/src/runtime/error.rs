@@ -0,0 +1,195 @@
+// Runtime error channel for generated Axis programs
+//
+// EXIT CODE CONTRACT (generated binaries):
+//   n         - axis_entry returned Int(n)
+//   0         - axis_entry returned Unit or Ok(..); any other result but
+//               Err(..) also exits 0, after a warning (entry.rs)
+//   1         - axis_entry returned Err(e), printed to stderr
+//               (AXIS_ENTRY_ERR_EXIT_CODE)
+//   3         - an assertion primitive failed (AXIS_ASSERTION_FAILED_EXIT_CODE);
+//               takes precedence over the result of axis_entry
+//   70        - Axis runtime error (AXIS_RUNTIME_ERROR_EXIT_CODE, EX_SOFTWARE)
+//   other     - explicit proc_exit(code) from the program
+//
+// POLICY: primitives that can fail on well-typed input return structured
+// Err(..) / None values. Type confusion and invariant violations (e.g. as_int
+// on a Str, field access on a non-ctor) abort through axis_runtime_error,
+// never through a raw Rust panic. Any panic that still escapes is caught by
+// the hook installed with install_runtime_error_hook and reported the same way.
+//
+// A host that runs Axis code in its own process (an embedding, the bridge's
+// tests) can take runtime errors over with set_runtime_error_handler instead
+// of having the process exit.
+
+use crate::runtime::io::flush_stdout;
+use std::cell::RefCell;
+use std::sync::{PoisonError, RwLock};
+
+pub const AXIS_RUNTIME_ERROR_EXIT_CODE: i32 = 70;
+pub const AXIS_ASSERTION_FAILED_EXIT_CODE: i32 = 3;
+pub const AXIS_ENTRY_ERR_EXIT_CODE: i32 = 1;
+
+// Source position attached to a runtime error: (file, line, column)
+pub type RuntimeSpan<'a> = (&'a str, usize, usize);
+
+// Frames printed before the backtrace is elided in the middle
+const MAX_BACKTRACE_FRAMES: usize = 32;
+
+// Called by axis_runtime_error before it reports and exits; a handler that
+// panics (or otherwise does not return) keeps the process alive
+pub type RuntimeErrorHandler = fn(&str, Option<RuntimeSpan>);
+
+static RUNTIME_ERROR_HANDLER: RwLock<Option<RuntimeErrorHandler>> = RwLock::new(None);
+
+thread_local! {
+    // Failed assert_eq / assert_true / fail calls so far; checked by the
+    // generated `main` wrapper once axis_entry returns
+    static ASSERTION_FAILURES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+
+    // Shadow call-stack of Axis functions; only populated by binaries
+    // built with `axis-rust-bridge build --debug`
+    static CALL_STACK: RefCell<Vec<(&'static str, Option<RuntimeSpan<'static>>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+// Shadow call-stack guard emitted in debug-build function prologues.
+// Pushes a frame on enter and pops it when the Rust frame is dropped.
+pub struct CallFrame {
+    _private: (),
+}
+
+impl CallFrame {
+    pub fn enter(name: &'static str, span: Option<RuntimeSpan<'static>>) -> CallFrame {
+        CALL_STACK.with(|stack| stack.borrow_mut().push((name, span)));
+        CallFrame { _private: () }
+    }
+}
+
+impl Drop for CallFrame {
+    fn drop(&mut self) {
+        CALL_STACK.with(|stack| {
+            stack.borrow_mut().pop();
+        });
+    }
+}
+
+// Render the shadow call-stack, innermost frame first.
+// Returns an empty string when no frames are recorded (non-debug builds).
+pub fn format_axis_backtrace() -> String {
+    CALL_STACK.with(|stack| {
+        let stack = stack.borrow();
+        if stack.is_empty() {
+            return String::new();
+        }
+        let mut out = String::from("Axis backtrace (most recent call first):");
+        let total = stack.len();
+        for (depth, (name, span)) in stack.iter().rev().enumerate() {
+            // Keep the innermost and outermost frames of very deep recursion
+            if total > MAX_BACKTRACE_FRAMES
+                && depth >= MAX_BACKTRACE_FRAMES / 2
+                && depth < total - MAX_BACKTRACE_FRAMES / 2
+            {
+                if depth == MAX_BACKTRACE_FRAMES / 2 {
+                    out.push_str(&format!("\n  ... {} frames elided ...", total - MAX_BACKTRACE_FRAMES));
+                }
+                continue;
+            }
+            out.push_str(&format!("\n  #{} {}", depth, name));
+            if let Some((file, line, column)) = span {
+                out.push_str(&format!(" at {}:{}:{}", file, line, column));
+            }
+        }
+        out
+    })
+}
+
+fn report(msg: &str, span: Option<RuntimeSpan>) {
+    flush_stdout();
+    eprintln!("{}", format_runtime_error(msg, span));
+    let backtrace = format_axis_backtrace();
+    if !backtrace.is_empty() {
+        eprintln!("{}", backtrace);
+    }
+}
+
+// Render a runtime error in Axis diagnostic style
+pub fn format_runtime_error(msg: &str, span: Option<RuntimeSpan>) -> String {
+    match span {
+        Some((file, line, column)) => {
+            format!("error[runtime]: {}\n  --> {}:{}:{}", msg, file, line, column)
+        }
+        None => format!("error[runtime]: {}", msg),
+    }
+}
+
+// Install the handler for every later runtime error in the process
+pub fn set_runtime_error_handler(handler: RuntimeErrorHandler) {
+    *RUNTIME_ERROR_HANDLER.write().unwrap_or_else(PoisonError::into_inner) = Some(handler);
+}
+
+// Report a runtime error and terminate with AXIS_RUNTIME_ERROR_EXIT_CODE,
+// unless the handler installed with set_runtime_error_handler takes it over
+pub fn axis_runtime_error(msg: &str, span: Option<RuntimeSpan>) -> ! {
+    let handler = *RUNTIME_ERROR_HANDLER.read().unwrap_or_else(PoisonError::into_inner);
+    if let Some(handler) = handler {
+        handler(msg, span);
+    }
+    report(msg, span);
+    std::process::exit(AXIS_RUNTIME_ERROR_EXIT_CODE)
+}
+
+// Report a failed assertion and record it, without terminating: the
+// program (and the test that called it) keeps running, and the generated
+// `main` wrapper exits with AXIS_ASSERTION_FAILED_EXIT_CODE at the end.
+pub fn axis_assertion_failure(msg: &str, details: &[(&str, String)], span: Option<RuntimeSpan>) {
+    ASSERTION_FAILURES.with(|count| count.set(count.get() + 1));
+    flush_stdout();
+    eprintln!("{}", format_assertion_failure(msg, details, span));
+    let backtrace = format_axis_backtrace();
+    if !backtrace.is_empty() {
+        eprintln!("{}", backtrace);
+    }
+}
+
+// Render a failed assertion: message, location, then labelled values
+pub fn format_assertion_failure(msg: &str, details: &[(&str, String)], span: Option<RuntimeSpan>) -> String {
+    let mut out = format!("error[assertion]: {}", msg);
+    if let Some((file, line, column)) = span {
+        out.push_str(&format!("\n  --> {}:{}:{}", file, line, column));
+    }
+    let width = details.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    for (label, value) in details {
+        // A value over several lines continues under its first line
+        let value = value.replace('\n', &format!("\n  {:width$}  ", "", width = width));
+        out.push_str(&format!("\n  {:>width$}: {}", label, value, width = width));
+    }
+    out
+}
+
+// Number of assertion failures recorded on this thread
+pub fn assertion_failures() -> usize {
+    ASSERTION_FAILURES.with(|count| count.get())
+}
+
+// Count failures recorded on another thread (concurrency::join) as this one's
+pub fn record_assertion_failures(failures: usize) {
+    ASSERTION_FAILURES.with(|count| count.set(count.get() + failures));
+}
+
+// Route any remaining Rust panic (index out of bounds in generated code,
+// arithmetic overflow, ...) through the runtime error channel.
+// Called once by the generated `main` wrapper after init_runtime().
+pub fn install_runtime_error_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let msg = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "unknown runtime fault".to_string()
+        };
+        report(&format!("internal runtime fault: {}", msg), None);
+        std::process::exit(AXIS_RUNTIME_ERROR_EXIT_CODE);
+    }));
+}
@@ -0,0 +1,120 @@
+// Directory and path primitives (feature `fs`, see capability.rs)
+//
+// What build tooling written in Axis needs besides reading and writing
+// files (io.rs): listing, creating and removing, copying, and taking paths
+// apart. The calls that touch the filesystem return Ok(..) or Err(message)
+// and go through the reactor like the fs_* primitives of io.rs.
+//
+//   fs_list_dir(path)           Ok(names), sorted, without "." and ".."
+//   fs_create_dir_all(path)     Ok(()); the missing parents are created too
+//   fs_remove_file(path)        Ok(()); a directory is an error
+//   fs_copy(from, to)           Ok(bytes copied)
+//
+// The path primitives only look at the string: path_join("a", "/b") is
+// "/b" as in std::path, and path_stem and path_parent return "" when the
+// path has none (path_parent("a.txt") is "").
+
+use super::error::axis_runtime_error;
+use super::list::cons_list;
+use super::reactor::offload;
+use super::value::{intern_tag, Value};
+use std::path::Path;
+
+fn ok(value: Value) -> Value {
+    Value::Ctor { tag: intern_tag("Ok"), fields: vec![value] }
+}
+
+fn err(message: &str) -> Value {
+    Value::Ctor { tag: intern_tag("Err"), fields: vec![Value::string(message)] }
+}
+
+// The text of a path argument; the filesystem calls return Err for a
+// non-string, the path primitives stop the program
+fn path_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Str(s) => Some(s.text().to_string()),
+        _ => None,
+    }
+}
+
+// The two paths of a packed (Str, Str) argument
+fn path_pair(args: &Value) -> Option<(String, String)> {
+    match args {
+        Value::Tuple(elems) if elems.len() >= 2 => Some((path_text(&elems[0])?, path_text(&elems[1])?)),
+        _ => None,
+    }
+}
+
+pub fn fs_list_dir(path: Value) -> Value {
+    let Some(path) = path_text(&path) else {
+        return err("Invalid path");
+    };
+    let listing = offload(move || -> std::io::Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            names.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        Ok(names)
+    });
+    match listing {
+        Ok(names) => ok(cons_list(names.iter().map(|name| Value::string(name)).collect())),
+        Err(e) => err(&e.to_string()),
+    }
+}
+
+pub fn fs_create_dir_all(path: Value) -> Value {
+    let Some(path) = path_text(&path) else {
+        return err("Invalid path");
+    };
+    match offload(move || std::fs::create_dir_all(path)) {
+        Ok(()) => ok(Value::Unit),
+        Err(e) => err(&e.to_string()),
+    }
+}
+
+pub fn fs_remove_file(path: Value) -> Value {
+    let Some(path) = path_text(&path) else {
+        return err("Invalid path");
+    };
+    match offload(move || std::fs::remove_file(path)) {
+        Ok(()) => ok(Value::Unit),
+        Err(e) => err(&e.to_string()),
+    }
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [from, to]
+pub fn fs_copy(args: Value) -> Value {
+    let Some((from, to)) = path_pair(&args) else {
+        return err("Invalid arguments");
+    };
+    match offload(move || std::fs::copy(from, to)) {
+        Ok(bytes) => ok(Value::Int(bytes as i64)),
+        Err(e) => err(&e.to_string()),
+    }
+}
+
+fn path_arg(value: &Value, primitive: &str) -> String {
+    path_text(value).unwrap_or_else(|| {
+        axis_runtime_error(&format!("{}: expected a path string, got {}", primitive, value.kind_name()), None)
+    })
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [base, path]
+pub fn path_join(args: Value) -> Value {
+    match path_pair(&args) {
+        Some((base, path)) => Value::string(&Path::new(&base).join(path).to_string_lossy()),
+        None => axis_runtime_error("path_join: expected a tuple of two path strings", None),
+    }
+}
+
+// The file name without its last extension
+pub fn path_stem(path: Value) -> Value {
+    let path = path_arg(&path, "path_stem");
+    Value::string(&Path::new(&path).file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default())
+}
+
+pub fn path_parent(path: Value) -> Value {
+    let path = path_arg(&path, "path_parent");
+    Value::string(&Path::new(&path).parent().map(|parent| parent.to_string_lossy()).unwrap_or_default())
+}
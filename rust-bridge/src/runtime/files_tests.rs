@@ -0,0 +1,69 @@
+// Tests for the directory and path primitives (files.rs)
+
+use crate::runtime::files::{
+    fs_copy, fs_create_dir_all, fs_list_dir, fs_remove_file, path_join, path_parent, path_stem,
+};
+use crate::runtime::list::list_elements;
+use crate::runtime::value::{get_tag_name, init_runtime, Value};
+
+fn s(text: &str) -> Value {
+    Value::string(text)
+}
+
+fn path(dir: &tempfile::TempDir, name: &str) -> Value {
+    s(&dir.path().join(name).to_string_lossy())
+}
+
+// The field of an Ok(..), panicking on Err
+fn unwrap_ok(result: Value) -> Value {
+    match result {
+        Value::Ctor { tag, mut fields } if get_tag_name(tag) == "Ok" => fields.remove(0),
+        other => panic!("expected Ok(..), got {:?}", other),
+    }
+}
+
+fn is_err(result: &Value) -> bool {
+    matches!(result, Value::Ctor { tag, .. } if get_tag_name(*tag) == "Err")
+}
+
+#[test]
+fn test_create_list_copy_and_remove() {
+    init_runtime();
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(unwrap_ok(fs_create_dir_all(path(&dir, "out/nested"))), Value::Unit);
+    std::fs::write(dir.path().join("b.txt"), "hello").unwrap();
+
+    let copied = fs_copy(Value::Tuple(vec![path(&dir, "b.txt"), path(&dir, "out/a.txt")]));
+    assert_eq!(unwrap_ok(copied), Value::Int(5));
+    assert_eq!(std::fs::read_to_string(dir.path().join("out/a.txt")).unwrap(), "hello");
+
+    let names = unwrap_ok(fs_list_dir(path(&dir, "out")));
+    assert_eq!(list_elements(&names), [s("a.txt"), s("nested")]);
+
+    assert_eq!(unwrap_ok(fs_remove_file(path(&dir, "out/a.txt"))), Value::Unit);
+    assert!(!dir.path().join("out/a.txt").exists());
+}
+
+#[test]
+fn test_failures_are_err_values() {
+    init_runtime();
+    let dir = tempfile::tempdir().unwrap();
+    assert!(is_err(&fs_list_dir(path(&dir, "missing"))));
+    assert!(is_err(&fs_remove_file(path(&dir, "missing"))));
+    assert!(is_err(&fs_remove_file(s(&dir.path().to_string_lossy()))));
+    assert!(is_err(&fs_copy(Value::Tuple(vec![path(&dir, "missing"), path(&dir, "to")]))));
+    assert!(is_err(&fs_create_dir_all(Value::Int(3))));
+}
+
+#[test]
+fn test_paths() {
+    init_runtime();
+    assert_eq!(path_join(Value::Tuple(vec![s("build"), s("out.rs")])), s("build/out.rs"));
+    assert_eq!(path_join(Value::Tuple(vec![s("build"), s("/abs")])), s("/abs"));
+    assert_eq!(path_stem(s("src/main.tar.gz")), s("main.tar"));
+    assert_eq!(path_stem(s("src/")), s("src"));
+    assert_eq!(path_stem(s("")), s(""));
+    assert_eq!(path_parent(s("src/lib/mod.ax")), s("src/lib"));
+    assert_eq!(path_parent(s("mod.ax")), s(""));
+    assert_eq!(path_parent(s("/")), s(""));
+}
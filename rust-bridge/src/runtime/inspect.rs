@@ -0,0 +1,114 @@
+// Debugging views of values: value_pretty and value_diff
+//
+// Both show strings quoted (with escapes), so `1` and `"1"` do not look
+// alike, and constructors without fields as a bare name; otherwise they
+// match Display. value_pretty keeps an aggregate on one line when it fits
+// in PRETTY_WIDTH columns and otherwise puts one item per line, indented
+// by two spaces per level. value_diff names the first place two values
+// differ by its path from the top: `.N` for a tuple item or constructor
+// field, `[N]` for a list item. assert_eq reports the same path when it
+// fails (shim.rs).
+
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::value::{tag_display_name, Value};
+
+pub const PRETTY_WIDTH: usize = 60;
+
+/// The value on one line, strings quoted
+pub fn debug_text(value: &Value) -> String {
+    match value {
+        Value::Str(s) => format!("{:?}", s.text()),
+        Value::Tuple(items) => format!("({})", joined(items)),
+        Value::List(items) => format!("[{}]", joined(items)),
+        Value::Ctor { tag, fields } if fields.is_empty() => tag_display_name(*tag),
+        Value::Ctor { tag, fields } => format!("{}({})", tag_display_name(*tag), joined(fields)),
+        other => other.to_string(),
+    }
+}
+
+fn joined(items: &[Value]) -> String {
+    items.iter().map(debug_text).collect::<Vec<_>>().join(", ")
+}
+
+/// The value indented over several lines where it does not fit on one
+pub fn pretty_text(value: &Value) -> String {
+    let mut out = String::new();
+    write_pretty(value, 0, &mut out);
+    out
+}
+
+fn write_pretty(value: &Value, indent: usize, out: &mut String) {
+    let flat = debug_text(value);
+    let (open, items, close) = match value {
+        Value::Tuple(items) => (String::from("("), items, ")"),
+        Value::List(items) => (String::from("["), items, "]"),
+        Value::Ctor { tag, fields } => (format!("{}(", tag_display_name(*tag)), fields, ")"),
+        _ => {
+            out.push_str(&flat);
+            return;
+        }
+    };
+    if items.is_empty() || indent + flat.len() <= PRETTY_WIDTH {
+        out.push_str(&flat);
+        return;
+    }
+    out.push_str(&open);
+    for item in items {
+        out.push('\n');
+        out.push_str(&" ".repeat(indent + 2));
+        write_pretty(item, indent + 2, out);
+        out.push(',');
+    }
+    out.push('\n');
+    out.push_str(&" ".repeat(indent));
+    out.push_str(close);
+}
+
+/// Where `left` and `right` first differ: the path (empty at the top) and
+/// what each side has there; None if they are equal
+pub fn first_difference(left: &Value, right: &Value) -> Option<(String, String)> {
+    if left == right {
+        return None;
+    }
+    let (left_items, right_items, list) = match (left, right) {
+        (Value::Tuple(l), Value::Tuple(r)) => (l, r, false),
+        (Value::List(l), Value::List(r)) => (l, r, true),
+        (Value::Ctor { tag: l_tag, fields: l }, Value::Ctor { tag: r_tag, fields: r }) if l_tag == r_tag => (l, r, false),
+        _ => return Some((String::new(), format!("left {}, right {}", debug_text(left), debug_text(right)))),
+    };
+    for (i, (l, r)) in left_items.iter().zip(right_items).enumerate() {
+        if let Some((path, report)) = first_difference(l, r) {
+            let step = if list { format!("[{}]", i) } else { format!(".{}", i) };
+            return Some((step + &path, report));
+        }
+    }
+    // Equal as far as the shorter goes
+    let unit = if list { "item" } else { "field" };
+    let count = |n: usize| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    Some((String::new(), format!("left has {}, right has {}", count(left_items.len()), count(right_items.len()))))
+}
+
+/// `value.1[0]: left 3, right 4` for the first difference; empty if the
+/// values are equal
+pub fn diff_text(left: &Value, right: &Value) -> String {
+    match first_difference(left, right) {
+        Some((path, report)) => format!("value{}: {}", path, report),
+        None => String::new(),
+    }
+}
+
+// Axis primitives
+
+// Indented form of a value, for printing while debugging
+pub fn value_pretty(value: Value) -> Value {
+    Value::string(&pretty_text(&value))
+}
+
+// Report of the first difference between two values ("" if equal)
+// UNARY CONTRACT: Accepts Value::Tuple containing [left, right]
+pub fn value_diff(args: Value) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() == 2 => Value::string(&diff_text(&elems[0], &elems[1])),
+        _ => axis_runtime_error("value_diff expects (left, right)", None),
+    }
+}
@@ -4,7 +4,7 @@
 //! the runtime shim library to preserve semantic distinctions.
 
 use crate::runtime::shim::*;
-use crate::runtime::value::{Value, intern_str};
+use crate::runtime::value::Value;
 
 /// Example: How the semantic compiler should generate calls for string operations
 /// 
@@ -19,7 +19,7 @@ pub fn example_generated_string_code() {
     // Initialize runtime before any operations
     crate::runtime::value::init_runtime();
     
-    let hello_str = Value::Str(intern_str("hello"));
+    let hello_str = Value::string("hello");
     let index_2 = Value::Int(2);
     let index_10 = Value::Int(10);
     
@@ -1,24 +1,100 @@
 // IO primitives
 // Extracted from emit_rust.rs generate_value_runtime()
 
-use super::value::{Value, intern_str, get_str, intern_tag};
-use std::io::{BufRead, Write};
+// io primitives need the `io` feature, file primitives `fs`; see capability.rs
+// Reads and file calls go through the reactor under `build --runtime async`;
+// see reactor.rs
 
-pub fn io_print(val: Value) -> Value {
+use super::value::Value;
+#[cfg(feature = "fs")]
+use super::reactor::offload;
+#[cfg(any(feature = "io", feature = "fs"))]
+use super::value::intern_tag;
+#[cfg(feature = "io")]
+use std::io::Write;
+#[cfg(feature = "io")]
+use std::sync::Mutex;
+#[cfg(all(feature = "io", not(feature = "async")))]
+use std::io::{BufRead, Read};
+
+// Output of io_print_buffered and io_println, held until io_flush, the
+// next io_print, STDOUT_BUFFER_LIMIT bytes, or the end of the program
+// (flush_stdout, which the main wrapper and every exit path call)
+#[cfg(feature = "io")]
+static STDOUT_BUFFER: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "io")]
+const STDOUT_BUFFER_LIMIT: usize = 64 * 1024;
+
+// What io_print and its buffered forms write for a value
+#[cfg(feature = "io")]
+fn display_text(val: &Value) -> String {
     match val {
-        Value::Str(handle) => print!("{}", get_str(handle)),
-        Value::Int(n) => print!("{}", n),
-        Value::Bool(b) => print!("{}", b),
-        Value::Unit => print!("()"),
-        _ => print!("{:?}", val),
+        Value::Str(s) => s.text().to_string(),
+        Value::Int(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Unit => "()".to_string(),
+        _ => format!("{:?}", val),
     }
-    std::io::stdout().flush().unwrap();
+}
+
+#[cfg(feature = "io")]
+fn write_buffered(text: &str) {
+    let mut buffer = STDOUT_BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+    buffer.extend_from_slice(text.as_bytes());
+    if buffer.len() >= STDOUT_BUFFER_LIMIT {
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(&buffer);
+        buffer.clear();
+    }
+}
+
+/// Writes out what io_print_buffered and io_println hold and flushes
+/// stdout; without the `io` feature there is nothing to write
+pub fn flush_stdout() {
+    #[cfg(feature = "io")]
+    {
+        let mut buffer = STDOUT_BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(&buffer);
+        let _ = stdout.flush();
+        buffer.clear();
+    }
+}
+
+// Written and flushed at once, after anything buffered
+#[cfg(feature = "io")]
+pub fn io_print(val: Value) -> Value {
+    write_buffered(&display_text(&val));
+    flush_stdout();
     Value::Unit
 }
 
+#[cfg(feature = "io")]
+pub fn io_print_buffered(val: Value) -> Value {
+    write_buffered(&display_text(&val));
+    Value::Unit
+}
+
+// io_print_buffered of the value and a newline
+#[cfg(feature = "io")]
+pub fn io_println(val: Value) -> Value {
+    let mut text = display_text(&val);
+    text.push('\n');
+    write_buffered(&text);
+    Value::Unit
+}
+
+#[cfg(feature = "io")]
+pub fn io_flush(_unit: Value) -> Value {
+    flush_stdout();
+    Value::Unit
+}
+
+#[cfg(feature = "io")]
 pub fn io_eprint(val: Value) -> Value {
     match val {
-        Value::Str(handle) => eprint!("{}", get_str(handle)),
+        Value::Str(s) => eprint!("{}", s.text()),
         Value::Int(n) => eprint!("{}", n),
         Value::Bool(b) => eprint!("{}", b),
         Value::Unit => eprint!("()"),
@@ -33,8 +109,8 @@ pub fn io_eprint(val: Value) -> Value {
 pub fn debug_trace(val: Value) -> Value {
     if std::env::var("AXIS_TRACE").ok().as_deref() == Some("1") {
         match val {
-            Value::Str(handle) => {
-                eprintln!("{}", get_str(handle));
+            Value::Str(s) => {
+                eprintln!("{}", s.text());
             },
             Value::Int(n) => eprintln!("{}", n),
             Value::Bool(b) => eprintln!("{}", b),
@@ -45,55 +121,162 @@ pub fn debug_trace(val: Value) -> Value {
     Value::Unit
 }
 
+#[cfg(all(feature = "io", not(feature = "async")))]
 pub fn io_read() -> Value {
     let stdin = std::io::stdin();
     let mut line = String::new();
     stdin.lock().read_line(&mut line).unwrap_or(0);
-    Value::Str(intern_str(&line))
+    Value::string(&line)
+}
+
+// Async profile: the line the reader thread read ahead
+#[cfg(all(feature = "io", feature = "async"))]
+pub fn io_read() -> Value {
+    let line = super::reactor::stdin_line().unwrap_or_default();
+    Value::string(&String::from_utf8_lossy(&line))
+}
+
+// Reads stdin to EOF as raw bytes. Takes a (unit) argument so generated
+// zero-arg calls `io_read_bytes(())` line up with the UNARY CONTRACT.
+#[cfg(all(feature = "io", not(feature = "async")))]
+pub fn io_read_bytes(_unit: Value) -> Value {
+    let mut buf = Vec::new();
+    match std::io::stdin().lock().read_to_end(&mut buf) {
+        Ok(_) => Value::Ctor {
+            tag: intern_tag("Ok"),
+            fields: vec![Value::Bytes(buf)],
+        },
+        Err(e) => Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::string(&e.to_string())],
+        },
+    }
+}
+
+// Async profile: the rest of what the reader thread reads ahead
+#[cfg(all(feature = "io", feature = "async"))]
+pub fn io_read_bytes(_unit: Value) -> Value {
+    let mut buf = Vec::new();
+    loop {
+        match super::reactor::stdin_line() {
+            Ok(line) if line.is_empty() => {
+                return Value::Ctor {
+                    tag: intern_tag("Ok"),
+                    fields: vec![Value::Bytes(buf)],
+                }
+            }
+            Ok(line) => buf.extend_from_slice(&line),
+            Err(e) => {
+                return Value::Ctor {
+                    tag: intern_tag("Err"),
+                    fields: vec![Value::string(&e.to_string())],
+                }
+            }
+        }
+    }
 }
 
 // File IO primitives
+#[cfg(feature = "fs")]
 pub fn fs_read_text(path: Value) -> Value {
     match path {
-        Value::Str(handle) => {
-            let path_str = get_str(handle);
-            match std::fs::read_to_string(&path_str) {
+        Value::Str(s) => {
+            let path_str = s.text().to_string();
+            match offload(move || std::fs::read_to_string(path_str)) {
                 Ok(content) => Value::Ctor {
                     tag: intern_tag("Ok"),
-                    fields: vec![Value::Str(intern_str(&content))],
+                    fields: vec![Value::string(&content)],
                 },
                 Err(e) => Value::Ctor {
                     tag: intern_tag("Err"),
-                    fields: vec![Value::Str(intern_str(&e.to_string()))],
+                    fields: vec![Value::string(&e.to_string())],
                 },
             }
         },
         _ => Value::Ctor {
             tag: intern_tag("Err"),
-            fields: vec![Value::Str(intern_str("Invalid path"))],
+            fields: vec![Value::string("Invalid path")],
         },
     }
 }
 
+#[cfg(feature = "fs")]
 pub fn fs_write_text(path: Value, content: Value) -> Value {
     match (path, content) {
-        (Value::Str(path_handle), Value::Str(content_handle)) => {
-            let path_str = get_str(path_handle);
-            let content_str = get_str(content_handle);
-            match std::fs::write(&path_str, &content_str) {
+        (Value::Str(path), Value::Str(content)) => {
+            let path_str = path.text().to_string();
+            let content_str = content.text().to_string();
+            match offload(move || std::fs::write(path_str, content_str)) {
                 Ok(_) => Value::Ctor {
                     tag: intern_tag("Ok"),
                     fields: vec![Value::Unit],
                 },
                 Err(e) => Value::Ctor {
                     tag: intern_tag("Err"),
-                    fields: vec![Value::Str(intern_str(&e.to_string()))],
+                    fields: vec![Value::string(&e.to_string())],
+                },
+            }
+        },
+        _ => Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::string("Invalid arguments")],
+        },
+    }
+}
+
+#[cfg(feature = "fs")]
+pub fn fs_read_bytes(path: Value) -> Value {
+    match path {
+        Value::Str(s) => {
+            let path_str = s.text().to_string();
+            match offload(move || std::fs::read(path_str)) {
+                Ok(content) => Value::Ctor {
+                    tag: intern_tag("Ok"),
+                    fields: vec![Value::Bytes(content)],
+                },
+                Err(e) => Value::Ctor {
+                    tag: intern_tag("Err"),
+                    fields: vec![Value::string(&e.to_string())],
+                },
+            }
+        },
+        _ => Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::string("Invalid path")],
+        },
+    }
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [path, bytes]
+#[cfg(feature = "fs")]
+pub fn fs_write_bytes(args: Value) -> Value {
+    match args {
+        Value::Tuple(mut elems) if elems.len() >= 2 => {
+            // The bytes are moved to the call, not copied
+            let data = std::mem::replace(&mut elems[1], Value::Unit);
+            match (&elems[0], data) {
+                (Value::Str(path), Value::Bytes(data)) => {
+                    let path_str = path.text().to_string();
+                    match offload(move || std::fs::write(path_str, data)) {
+                        Ok(_) => Value::Ctor {
+                            tag: intern_tag("Ok"),
+                            fields: vec![Value::Unit],
+                        },
+                        Err(e) => Value::Ctor {
+                            tag: intern_tag("Err"),
+                            fields: vec![Value::string(&e.to_string())],
+                        },
+                    }
+                },
+                _ => Value::Ctor {
+                    tag: intern_tag("Err"),
+                    fields: vec![Value::string("Invalid arguments")],
                 },
             }
         },
         _ => Value::Ctor {
             tag: intern_tag("Err"),
-            fields: vec![Value::Str(intern_str("Invalid arguments"))],
+            fields: vec![Value::string("Invalid arguments")],
         },
     }
 }
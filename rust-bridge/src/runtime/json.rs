@@ -0,0 +1,389 @@
+// JSON primitives
+// Full JSON decode/encode over a tagged Value tree:
+//   JNull | JBool(Bool) | JNum(Int) | JStr(Str) | JArr(List[J]) | JObj(List[(Str, J)])
+// Object members keep source order; duplicate keys are preserved as written.
+// Value has no float type, so numbers with a fraction or exponent are
+// rejected with a positioned error rather than silently truncated.
+//
+// Errors are returned as Err(JsonError(msg, line, column)) with 1-based
+// positions, mirroring the compiler's ParseError location conventions.
+//
+// NOTE: shim::axis_json_parse (flat {"k": "v"} pairs) is kept for the
+// self-hosted compiler's invocation parsing; this module is the general API.
+
+use super::value::{Value, intern_tag, get_tag_name};
+
+// Guard against stack exhaustion on adversarial input like [[[[...
+const MAX_DEPTH: usize = 512;
+
+struct JsonError {
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Parser { chars: text.chars().peekable(), line: 1, column: 1 }
+    }
+
+    fn error(&self, message: impl Into<String>) -> JsonError {
+        JsonError { message: message.into(), line: self.line, column: self.column }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(format!("expected '{}', found end of input", expected))),
+        }
+    }
+
+    fn expect_keyword(&mut self, word: &str) -> Result<(), JsonError> {
+        for expected in word.chars() {
+            match self.peek() {
+                Some(c) if c == expected => {
+                    self.bump();
+                }
+                _ => return Err(self.error(format!("invalid literal, expected '{}'", word))),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Value, JsonError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(format!("nesting deeper than {} levels", MAX_DEPTH)));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => {
+                self.expect_keyword("null")?;
+                Ok(Value::Ctor { tag: intern_tag("JNull"), fields: vec![] })
+            }
+            Some('t') => {
+                self.expect_keyword("true")?;
+                Ok(Value::Ctor { tag: intern_tag("JBool"), fields: vec![Value::Bool(true)] })
+            }
+            Some('f') => {
+                self.expect_keyword("false")?;
+                Ok(Value::Ctor { tag: intern_tag("JBool"), fields: vec![Value::Bool(false)] })
+            }
+            Some('"') => {
+                let s = self.parse_string()?;
+                Ok(Value::Ctor { tag: intern_tag("JStr"), fields: vec![Value::string(&s)] })
+            }
+            Some('[') => self.parse_array(depth),
+            Some('{') => self.parse_object(depth),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => Err(self.error(format!("unexpected character '{}'", c))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<Value, JsonError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.bump();
+        } else {
+            loop {
+                items.push(self.parse_value(depth + 1)?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => { self.bump(); }
+                    Some(']') => { self.bump(); break; }
+                    Some(c) => return Err(self.error(format!("expected ',' or ']', found '{}'", c))),
+                    None => return Err(self.error("unterminated array")),
+                }
+            }
+        }
+        Ok(Value::Ctor { tag: intern_tag("JArr"), fields: vec![Value::List(items)] })
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<Value, JsonError> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.bump();
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.peek() != Some('"') {
+                    return Err(self.error("expected string key"));
+                }
+                let key = self.parse_string()?;
+                self.skip_whitespace();
+                self.expect(':')?;
+                let value = self.parse_value(depth + 1)?;
+                members.push(Value::Tuple(vec![Value::string(&key), value]));
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => { self.bump(); }
+                    Some('}') => { self.bump(); break; }
+                    Some(c) => return Err(self.error(format!("expected ',' or '}}', found '{}'", c))),
+                    None => return Err(self.error("unterminated object")),
+                }
+            }
+        }
+        Ok(Value::Ctor { tag: intern_tag("JObj"), fields: vec![Value::List(members)] })
+    }
+
+    fn parse_number(&mut self) -> Result<Value, JsonError> {
+        let (line, column) = (self.line, self.column);
+        let mut text = String::new();
+        if self.peek() == Some('-') {
+            text.push('-');
+            self.bump();
+        }
+        match self.peek() {
+            Some('0') => {
+                text.push('0');
+                self.bump();
+                if matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                    return Err(self.error("leading zeros are not allowed"));
+                }
+            }
+            Some(c) if c.is_ascii_digit() => {
+                while let Some(c) = self.peek().filter(|c| c.is_ascii_digit()) {
+                    text.push(c);
+                    self.bump();
+                }
+            }
+            _ => return Err(self.error("expected digit")),
+        }
+        if let Some('.' | 'e' | 'E') = self.peek() {
+            return Err(self.error("fractional and exponent numbers are not supported (Axis has no float type)"));
+        }
+        match text.parse::<i64>() {
+            Ok(n) => Ok(Value::Ctor { tag: intern_tag("JNum"), fields: vec![Value::Int(n)] }),
+            Err(_) => Err(JsonError {
+                message: format!("number {} does not fit in a 64-bit integer", text),
+                line,
+                column,
+            }),
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0u32;
+        for _ in 0..4 {
+            let digit = self.peek().and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid \\u escape"))?;
+            self.bump();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => {
+                    self.bump();
+                    return Ok(out);
+                }
+                Some('\\') => {
+                    self.bump();
+                    let escaped = match self.peek() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{0008}',
+                        Some('f') => '\u{000C}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            self.bump();
+                            let high = self.parse_hex4()?;
+                            let code = if (0xD800..0xDC00).contains(&high) {
+                                // Surrogate pair: must be followed by \uDC00..\uDFFF
+                                if self.peek() != Some('\\') {
+                                    return Err(self.error("unpaired surrogate in \\u escape"));
+                                }
+                                self.bump();
+                                if self.peek() != Some('u') {
+                                    return Err(self.error("unpaired surrogate in \\u escape"));
+                                }
+                                self.bump();
+                                let low = self.parse_hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.error("invalid low surrogate in \\u escape"));
+                                }
+                                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                            } else {
+                                high
+                            };
+                            let c = char::from_u32(code)
+                                .ok_or_else(|| self.error("invalid code point in \\u escape"))?;
+                            out.push(c);
+                            continue;
+                        }
+                        Some(c) => return Err(self.error(format!("invalid escape '\\{}'", c))),
+                        None => return Err(self.error("unterminated string")),
+                    };
+                    self.bump();
+                    out.push(escaped);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"));
+                }
+                Some(c) => {
+                    self.bump();
+                    out.push(c);
+                }
+            }
+        }
+    }
+}
+
+fn parse_document(text: &str) -> Result<Value, JsonError> {
+    let mut parser = Parser::new(text);
+    let value = parser.parse_value(0)?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(value),
+        Some(c) => Err(parser.error(format!("trailing character '{}' after JSON value", c))),
+    }
+}
+
+/// Parse JSON text into a JNull/JBool/JNum/JStr/JArr/JObj tree
+/// Returns Ok(tree) or Err(JsonError(msg, line, column))
+pub fn json_parse(text: Value) -> Value {
+    let text = match text {
+        Value::Str(s) => s.text().to_string(),
+        other => return Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::Ctor {
+                tag: intern_tag("JsonError"),
+                fields: vec![
+                    Value::string(&format!("json_parse: expected Str, got {}", other)),
+                    Value::Int(0),
+                    Value::Int(0),
+                ],
+            }],
+        },
+    };
+    match parse_document(&text) {
+        Ok(tree) => Value::Ctor { tag: intern_tag("Ok"), fields: vec![tree] },
+        Err(e) => Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::Ctor {
+                tag: intern_tag("JsonError"),
+                fields: vec![
+                    Value::string(&e.message),
+                    Value::Int(e.line as i64),
+                    Value::Int(e.column as i64),
+                ],
+            }],
+        },
+    }
+}
+
+fn encode_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{0008}' => out.push_str("\\b"),
+            '\u{000C}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn encode_value(value: &Value, out: &mut String, depth: usize) -> Result<(), String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("json_encode: nesting deeper than {} levels", MAX_DEPTH));
+    }
+    let (tag, fields) = match value {
+        Value::Ctor { tag, fields } => (get_tag_name(*tag), fields),
+        other => return Err(format!("json_encode: expected JSON constructor, got {}", other)),
+    };
+    match (tag.as_str(), fields.as_slice()) {
+        ("JNull", []) => out.push_str("null"),
+        ("JBool", [Value::Bool(b)]) => out.push_str(if *b { "true" } else { "false" }),
+        ("JNum", [Value::Int(n)]) => out.push_str(&n.to_string()),
+        ("JStr", [Value::Str(s)]) => encode_string(s.text(), out),
+        ("JArr", [Value::List(items)]) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 { out.push(','); }
+                encode_value(item, out, depth + 1)?;
+            }
+            out.push(']');
+        }
+        ("JObj", [Value::List(members)]) => {
+            out.push('{');
+            for (i, member) in members.iter().enumerate() {
+                if i > 0 { out.push(','); }
+                match member {
+                    Value::Tuple(pair) if pair.len() == 2 => {
+                        match &pair[0] {
+                            Value::Str(key) => encode_string(key.text(), out),
+                            other => return Err(format!("json_encode: object key must be Str, got {}", other)),
+                        }
+                        out.push(':');
+                        encode_value(&pair[1], out, depth + 1)?;
+                    }
+                    other => return Err(format!("json_encode: object member must be (Str, J), got {}", other)),
+                }
+            }
+            out.push('}');
+        }
+        _ => return Err(format!("json_encode: malformed JSON value {}", value)),
+    }
+    Ok(())
+}
+
+/// Encode a JNull/JBool/JNum/JStr/JArr/JObj tree as compact JSON text
+/// Returns Ok(Str) or Err(msg) if the tree is malformed
+pub fn json_encode(tree: Value) -> Value {
+    let mut out = String::new();
+    match encode_value(&tree, &mut out, 0) {
+        Ok(()) => Value::Ctor { tag: intern_tag("Ok"), fields: vec![Value::string(&out)] },
+        Err(e) => Value::Ctor { tag: intern_tag("Err"), fields: vec![Value::string(&e)] },
+    }
+}
@@ -0,0 +1,220 @@
+// Moves instead of clones at a local's only use (emit_rust)
+//
+// The emitter clones every variable it reads (`x.clone()`), since the
+// same local may be read again by a later statement, another arm or the
+// next iteration of a tail-call loop. Most locals are read once, and the
+// clone of a list or a string they hold is then pure overhead: rustc does
+// not remove it. This pass runs over each generated function and drops
+// `.clone()` from a read that is the only one of its binding:
+//
+//     let n = shim::str_len(s.clone());          let n = shim::str_len(s);
+//     shim::int_to_str(n.clone())           ==>  shim::int_to_str(n)
+//
+// It works on the Rust text rather than on Core, so it sees every read,
+// including those the emitter adds itself (tuple_field unpacking, the
+// name-drift aliases).
+//
+// POLICY: conservative. A binding (`let x`, `let mut x`, or a `x: Value`
+// parameter) qualifies when its name occurs exactly once in its scope
+// after it, as `x.clone()`, and that read is not inside a loop or a
+// closure the binding is outside of, where it could run more than once.
+// A name that is reassigned, shadowed or used as a struct field counts
+// as occurring more than once. `--keep-clones` leaves the code as the
+// emitter wrote it.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Ident,
+    Punct,
+    Other,
+}
+
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+    end: usize,
+}
+
+const KEYWORDS: [&str; 14] =
+    ["let", "mut", "fn", "pub", "return", "continue", "if", "else", "match", "loop", "while", "for", "move", "in"];
+
+/// `function`, with the `.clone()` of every read that is the last use of
+/// its binding removed
+pub fn move_last_uses(function: &str) -> String {
+    let tokens = tokenize(function);
+    let blocks = Blocks::of(&tokens);
+    let mut references: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind == Kind::Ident && is_reference(&tokens, i) {
+            references.entry(token.text).or_default().push(i);
+        }
+    }
+
+    let mut removals: Vec<(usize, usize)> = Vec::new();
+    for index in 0..tokens.len() {
+        let Some(scope_start) = binding_scope_start(&tokens, index) else {
+            continue;
+        };
+        let block = blocks.enclosing[index];
+        let scope_end = block.map_or(tokens.len(), |open| blocks.end[&open]);
+        let occurrences = &references[tokens[index].text];
+        let from = occurrences.partition_point(|&i| i < scope_start);
+        let to = occurrences.partition_point(|&i| i < scope_end);
+        if let [read] = occurrences[from..to] {
+            if blocks.repeats(blocks.enclosing[read]) == blocks.repeats(block) && is_clone_call(&tokens, read) {
+                removals.push((tokens[read].end, tokens[read + 4].end));
+            }
+        }
+    }
+
+    removals.sort();
+    let mut out = String::with_capacity(function.len());
+    let mut copied = 0;
+    for (start, end) in removals {
+        out.push_str(&function[copied..start]);
+        copied = end;
+    }
+    out.push_str(&function[copied..]);
+    out
+}
+
+// The `{ }` blocks of a function, by the index of their opening brace
+struct Blocks {
+    // The innermost block each token is in (a brace belongs to the code
+    // around its block)
+    enclosing: Vec<Option<usize>>,
+    // The index of the closing brace
+    end: HashMap<usize, usize>,
+    // How many of the blocks from the outermost to this one are loop or
+    // closure bodies, whose code may run more than once
+    repeated: HashMap<usize, usize>,
+}
+
+impl Blocks {
+    fn of(tokens: &[Token<'_>]) -> Blocks {
+        let mut blocks = Blocks { enclosing: Vec::with_capacity(tokens.len()), end: HashMap::new(), repeated: HashMap::new() };
+        let mut stack: Vec<usize> = Vec::new();
+        let mut repeated_next = false;
+        for (i, token) in tokens.iter().enumerate() {
+            if token.text == "}" {
+                if let Some(open) = stack.pop() {
+                    blocks.end.insert(open, i);
+                }
+            }
+            blocks.enclosing.push(stack.last().copied());
+            match token.text {
+                "loop" | "while" | "for" | "|" | "||" => repeated_next = true,
+                "{" => {
+                    let outer = blocks.repeats(stack.last().copied());
+                    blocks.repeated.insert(i, outer + usize::from(repeated_next));
+                    repeated_next = false;
+                    stack.push(i);
+                }
+                _ => {}
+            }
+        }
+        for open in stack {
+            blocks.end.insert(open, tokens.len());
+        }
+        blocks
+    }
+
+    fn repeats(&self, block: Option<usize>) -> usize {
+        block.map_or(0, |open| self.repeated[&open])
+    }
+}
+
+// The index of the first token in the scope of the binding at `index`, if
+// there is one: after the `let` statement, or after the parameter
+fn binding_scope_start(tokens: &[Token<'_>], index: usize) -> Option<usize> {
+    let token = &tokens[index];
+    if token.kind != Kind::Ident || token.text == "_" || KEYWORDS.contains(&token.text) {
+        return None;
+    }
+    let before = |back: usize| index.checked_sub(back).map(|i| tokens[i].text);
+    let after = |ahead: usize| tokens.get(index + ahead).map(|t| t.text);
+    let let_binding = before(1) == Some("let") || (before(1) == Some("mut") && before(2) == Some("let"));
+    if let_binding && after(1) == Some("=") {
+        return statement_end(tokens, index).map(|end| end + 1);
+    }
+    let opener = if before(1) == Some("mut") { before(2) } else { before(1) };
+    let parameter = matches!(opener, Some("(" | "," | "|")) && after(1) == Some(":") && after(2) == Some("Value");
+    parameter.then_some(index + 1)
+}
+
+// The `;` ending the statement that starts before `index`
+fn statement_end(tokens: &[Token<'_>], index: usize) -> Option<usize> {
+    let mut nesting = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(index) {
+        match token.text {
+            "(" | "[" | "{" => nesting += 1,
+            ")" | "]" | "}" => nesting = nesting.checked_sub(1)?,
+            ";" if nesting == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// A use of a local, not a field, method or path segment of the same name
+fn is_reference(tokens: &[Token<'_>], index: usize) -> bool {
+    let before = index.checked_sub(1).map(|i| tokens[i].text);
+    let after = tokens.get(index + 1).map(|t| t.text);
+    !matches!(before, Some("." | "::")) && after != Some("::")
+}
+
+fn is_clone_call(tokens: &[Token<'_>], index: usize) -> bool {
+    let texts: Vec<&str> = tokens[index + 1..].iter().take(4).map(|t| t.text).collect();
+    texts == [".", "clone", "(", ")"]
+}
+
+// Identifiers and punctuation, skipping whitespace, comments, string and
+// character literals
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i];
+        let kind = if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if source[i..].starts_with("//") {
+            i = source[i..].find('\n').map_or(bytes.len(), |n| i + n);
+            continue;
+        } else if source[i..].starts_with("/*") {
+            i = source[i + 2..].find("*/").map_or(bytes.len(), |n| i + 2 + n + 2);
+            continue;
+        } else if c == b'"' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i += 1;
+            Kind::Other
+        } else if c == b'\'' && (bytes.get(i + 2) == Some(&b'\'') || bytes.get(i + 1) == Some(&b'\\')) {
+            i = source[i + 2..].find('\'').map_or(bytes.len(), |n| i + 2 + n + 1);
+            Kind::Other
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            Kind::Ident
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && bytes[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            Kind::Other
+        } else {
+            let two = source.get(i..i + 2);
+            i += if matches!(two, Some("::" | "=>" | "->" | "==" | "!=" | "&&" | "||")) { 2 } else { source[i..].chars().next().map_or(1, char::len_utf8) };
+            Kind::Punct
+        };
+        let end = i.min(bytes.len());
+        tokens.push(Token { kind, text: &source[start..end], end });
+    }
+    tokens
+}
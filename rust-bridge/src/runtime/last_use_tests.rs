@@ -0,0 +1,54 @@
+// Tests for moving locals at their only use (last_use.rs)
+
+use crate::runtime::last_use::move_last_uses;
+
+#[test]
+fn test_only_reads_are_moved() {
+    let function = "fn f(s: Value) -> Value {\n    \
+                    let n = shim::str_len(s.clone());\n    \
+                    let m = shim::__add__(Value::Tuple(vec![n.clone(), n.clone()]));\n    \
+                    shim::int_to_str(m.clone())\n}\n";
+    let expected = "fn f(s: Value) -> Value {\n    \
+                    let n = shim::str_len(s);\n    \
+                    let m = shim::__add__(Value::Tuple(vec![n.clone(), n.clone()]));\n    \
+                    shim::int_to_str(m)\n}\n";
+    assert_eq!(move_last_uses(function), expected);
+}
+
+#[test]
+fn test_reads_in_loops_and_closures_keep_their_clone() {
+    // k is read once in the text, but every time the closure runs
+    let closure = "fn f(k: Value) -> Value {\n    \
+                   shim::seq_map(SeqFn::new(move |x: Value| -> Value { shim::__add__(Value::Tuple(vec![x.clone(), k.clone()])) }), k2())\n}\n";
+    assert_eq!(move_last_uses(closure), closure);
+
+    // A parameter read once per iteration, and reassigned
+    let tail = "fn f(mut n: Value) -> Value {\n    loop {\n        \
+                let next = shim::__sub__(Value::Tuple(vec![n.clone(), Value::Int(1)]));\n        \
+                n = next.clone();\n        continue\n    }\n}\n";
+    let expected = tail.replace("n = next.clone();", "n = next;");
+    assert_eq!(move_last_uses(tail), expected);
+}
+
+#[test]
+fn test_scopes_shadowing_and_branches() {
+    // Each arm binds its own x; the outer y is read in two branches
+    let function = "fn f(y: Value) -> Value {\n    \
+                    if truthy(&(y.clone())) { let x = g(Value::Unit); x.clone() } else { let x = h(y.clone()); x.clone() }\n}\n";
+    let expected = "fn f(y: Value) -> Value {\n    \
+                    if truthy(&(y.clone())) { let x = g(Value::Unit); x } else { let x = h(y.clone()); x }\n}\n";
+    assert_eq!(move_last_uses(function), expected);
+
+    // The inner binding reads the outer one, which is read again after it
+    let shadowed = "fn f(k: Value) -> Value {\n    { let k = k.clone(); g(k.clone()) };\n    k.clone()\n}\n";
+    assert_eq!(move_last_uses(shadowed), "fn f(k: Value) -> Value {\n    { let k = k.clone(); g(k) };\n    k.clone()\n}\n");
+}
+
+#[test]
+fn test_strings_comments_and_fields_are_not_reads() {
+    let function = "// axis: f (main.ax:1)\nfn f(tag: Value, s: Value) -> Value {\n    \
+                    let t = str_lit(\"s.clone() \\\" tag\");\n    \
+                    Value::Ctor { tag: 3 /* Some */, fields: vec![t.clone(), s.clone(), tag.clone()] }\n}\n";
+    let expected = function.replace("vec![t.clone(), s.clone(),", "vec![t, s,");
+    assert_eq!(move_last_uses(function), expected);
+}
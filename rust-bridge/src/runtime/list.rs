@@ -55,15 +55,15 @@ pub fn list_contains_str(args: Value) -> Value {
     match args {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
             let needle_str = match &elems[1] {
-                Value::Str(handle) => super::value::get_str(*handle),
+                Value::Str(s) => s.text().to_string(),
                 _ => return Value::Bool(false),
             };
             
             match &elems[0] {
                 Value::List(list_elems) => {
                     for elem in list_elems {
-                        if let Value::Str(handle) = elem {
-                            if super::value::get_str(*handle) == needle_str {
+                        if let Value::Str(s) = elem {
+                            if s.text() == needle_str {
                                 return Value::Bool(true);
                             }
                         }
@@ -81,15 +81,15 @@ pub fn list_index_of_str(args: Value) -> Value {
     match args {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
             let needle_str = match &elems[1] {
-                Value::Str(handle) => super::value::get_str(*handle),
+                Value::Str(s) => s.text().to_string(),
                 _ => return Value::Int(-1),
             };
             
             match &elems[0] {
                 Value::List(list_elems) => {
                     for (i, elem) in list_elems.iter().enumerate() {
-                        if let Value::Str(handle) = elem {
-                            if super::value::get_str(*handle) == needle_str {
+                        if let Value::Str(s) = elem {
+                            if s.text() == needle_str {
                                 return Value::Int(i as i64);
                             }
                         }
@@ -102,3 +102,155 @@ pub fn list_index_of_str(args: Value) -> Value {
         _ => Value::Int(-1),
     }
 }
+
+// Flatten an Axis list into its elements.
+// Accepts both the runtime Value::List and the Nil/Cons constructor chain
+// produced by the bridge wrapper for CLI arguments. Anything else is empty.
+pub fn list_elements(list: &Value) -> Vec<Value> {
+    match list {
+        Value::List(elems) => elems.clone(),
+        Value::Ctor { .. } => {
+            let mut result = Vec::new();
+            let mut current = list;
+            while let Value::Ctor { tag, fields } = current {
+                if super::value::get_tag_name(*tag) != "Cons" || fields.len() != 2 {
+                    break;
+                }
+                result.push(fields[0].clone());
+                current = &fields[1];
+            }
+            result
+        },
+        _ => vec![],
+    }
+}
+
+// Rebuild a list in the same representation as `like` (Value::List or Nil/Cons)
+pub(crate) fn list_from_elements(elems: Vec<Value>, like: &Value) -> Value {
+    match like {
+        Value::Ctor { .. } => cons_list(elems),
+        _ => Value::List(elems),
+    }
+}
+
+// The Nil/Cons list of elems, as Axis code matches on it
+pub(crate) fn cons_list(elems: Vec<Value>) -> Value {
+    let mut result = Value::Ctor { tag: super::value::intern_tag("Nil"), fields: vec![] };
+    for elem in elems.into_iter().rev() {
+        result = Value::Ctor { tag: super::value::intern_tag("Cons"), fields: vec![elem, result] };
+    }
+    result
+}
+
+// CLI argument helpers (`proc` feature, like the registries' process tag)
+// Operate on the argv list handed to the Axis entry point (a Nil/Cons chain
+// of Str), or on a plain Value::List. A flag's value is either the next
+// argument (`--out bin`) or attached with `=` (`--out=bin`). Everything
+// after a bare `--` is positional.
+
+#[cfg(feature = "proc")]
+fn is_flag_arg(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-')
+}
+
+#[cfg(feature = "proc")]
+fn args_strings(args: &Value) -> Vec<String> {
+    list_elements(args).into_iter()
+        .filter_map(|v| match v {
+            Value::Str(s) => Some(s.text().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [args, flag_name]
+// Returns Some(value) for the first occurrence of the flag, None otherwise.
+// A bare flag followed by another flag (or nothing) yields None.
+#[cfg(feature = "proc")]
+pub fn args_flag(args: Value) -> Value {
+    let none = Value::Ctor { tag: super::value::intern_tag("None"), fields: vec![] };
+    let (argv, name) = match args {
+        Value::Tuple(ref elems) if elems.len() >= 2 => {
+            match &elems[1] {
+                Value::Str(s) => (args_strings(&elems[0]), s.text().to_string()),
+                _ => return none,
+            }
+        },
+        _ => return none,
+    };
+
+    let prefix = format!("{}=", name);
+    for (i, arg) in argv.iter().enumerate() {
+        if arg == "--" {
+            break;
+        }
+        let value = if *arg == name {
+            match argv.get(i + 1) {
+                Some(next) if !is_flag_arg(next) => next.clone(),
+                _ => return none,
+            }
+        } else if let Some(inline) = arg.strip_prefix(&prefix) {
+            inline.to_string()
+        } else {
+            continue;
+        };
+        return Value::Ctor {
+            tag: super::value::intern_tag("Some"),
+            fields: vec![Value::string(&value)],
+        };
+    }
+    none
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [args, flag_name]
+// True if the flag appears bare or in `name=value` form before any `--`.
+#[cfg(feature = "proc")]
+pub fn args_has(args: Value) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() >= 2 => {
+            let name = match &elems[1] {
+                Value::Str(s) => s.text().to_string(),
+                _ => return Value::Bool(false),
+            };
+            let prefix = format!("{}=", name);
+            let found = args_strings(&elems[0]).iter()
+                .take_while(|arg| arg.as_str() != "--")
+                .any(|arg| *arg == name || arg.starts_with(&prefix));
+            Value::Bool(found)
+        },
+        _ => Value::Bool(false),
+    }
+}
+
+// Arguments that are neither flags nor flag values, in order.
+// A bare flag consumes the following argument as its value (unless that
+// argument is itself a flag), so boolean switches should be written last
+// or in `--flag=true` form. A lone `-` is positional (conventionally stdin).
+// Returns a list in the same representation as the input.
+#[cfg(feature = "proc")]
+pub fn args_positional(args: Value) -> Value {
+    let argv = args_strings(&args);
+
+    let mut positional = Vec::new();
+    let mut i = 0;
+    while i < argv.len() {
+        let arg = &argv[i];
+        if arg == "--" {
+            positional.extend(argv[i + 1..].iter().cloned());
+            break;
+        }
+        if is_flag_arg(arg) {
+            let has_inline_value = arg.contains('=');
+            let next_is_value = argv.get(i + 1).map(|next| !is_flag_arg(next)).unwrap_or(false);
+            i += if !has_inline_value && next_is_value { 2 } else { 1 };
+            continue;
+        }
+        positional.push(arg.clone());
+        i += 1;
+    }
+
+    let elems = positional.iter()
+        .map(|s| Value::string(s))
+        .collect();
+    list_from_elements(elems, &args)
+}
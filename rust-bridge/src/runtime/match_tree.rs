@@ -0,0 +1,188 @@
+// Decision trees for `match` (emit_rust)
+//
+// The arms of a match are compiled into a tree that tests one position of
+// the scrutinee at a time (column by column, after Maranget). The tree
+// switches on a position's constructor tag or literal, each position is
+// tested at most once on any path, and rows keep their order, so the
+// first arm whose pattern matches is the one chosen. Nested patterns are
+// tested like top-level ones.
+//
+// Leaves only name the arm. An arm's variables are bound to fixed
+// positions in the scrutinee (bindings), whichever path led to it, so the
+// emitter writes each arm body once. `name @ pattern` is tested as its
+// pattern and binds `name` like a variable at the same position.
+//
+// NOTE: a capitalized PVar is not a variable. It is matched as a wildcard,
+// as the linear lowering always did (nullary constructors are PEnum).
+
+use crate::core_ir::Pattern;
+use crate::runtime::emit_rust::constructor_name;
+
+/// Position in the scrutinee: field indices (constructor fields or tuple
+/// elements) from the root
+pub type Path = Vec<usize>;
+
+/// What a switch case tests at a position
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Head {
+    // Namespace-stripped name and number of fields
+    Ctor(String, usize),
+    Int(i64),
+    Bool(bool),
+    Unit,
+    Tuple(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// The arm (index into the match's arms) that matches
+    Arm(usize),
+    /// No arm matches
+    Fail,
+    /// Test `path`: the first case whose head matches, else `default`
+    Switch { path: Path, cases: Vec<(Head, Decision)>, default: Box<Decision> },
+}
+
+// The refutable tests left in one arm, left to right
+#[derive(Clone)]
+struct Row<'a> {
+    tests: Vec<(Path, &'a Pattern)>,
+    arm: usize,
+}
+
+pub fn compile<'a>(patterns: impl IntoIterator<Item = &'a Pattern>) -> Decision {
+    let rows = patterns
+        .into_iter()
+        .enumerate()
+        .map(|(arm, pattern)| {
+            let mut tests = Vec::new();
+            push_tests(Vec::new(), pattern, &mut tests);
+            Row { tests, arm }
+        })
+        .collect();
+    compile_rows(rows)
+}
+
+/// The variables an arm binds and the positions they are bound to
+pub fn bindings(pattern: &Pattern) -> Vec<(String, Path)> {
+    let mut out = Vec::new();
+    collect_bindings(pattern, Vec::new(), &mut out);
+    out
+}
+
+fn collect_bindings(pattern: &Pattern, path: Path, out: &mut Vec<(String, Path)>) {
+    match pattern {
+        Pattern::PVar(name) if !is_wildcard(pattern) => out.push((name.clone(), path)),
+        Pattern::PBind(name, inner) => {
+            out.push((name.clone(), path.clone()));
+            collect_bindings(inner, path, out);
+        }
+        Pattern::PTuple(fields) | Pattern::PEnum(_, fields) => {
+            for (i, field) in fields.iter().enumerate() {
+                let mut child = path.clone();
+                child.push(i);
+                collect_bindings(field, child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_wildcard(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::PVar(name) => name == "_" || name.starts_with(|c: char| c.is_uppercase()),
+        _ => false,
+    }
+}
+
+fn push_tests<'a>(path: Path, pattern: &'a Pattern, tests: &mut Vec<(Path, &'a Pattern)>) {
+    let pattern = pattern.shape();
+    if !matches!(pattern, Pattern::PVar(_)) {
+        tests.push((path, pattern));
+    }
+}
+
+fn head(pattern: &Pattern) -> Head {
+    match pattern {
+        Pattern::PInt(n) => Head::Int(*n),
+        Pattern::PBool(b) => Head::Bool(*b),
+        Pattern::PUnit => Head::Unit,
+        Pattern::PTuple(fields) => Head::Tuple(fields.len()),
+        Pattern::PEnum(name, fields) => Head::Ctor(constructor_name(name).to_string(), fields.len()),
+        Pattern::PVar(_) | Pattern::PBind(..) => unreachable!("variables are not tested, `name @` patterns by their shape"),
+    }
+}
+
+fn fields(pattern: &Pattern) -> &[Pattern] {
+    match pattern {
+        Pattern::PTuple(fields) | Pattern::PEnum(_, fields) => fields,
+        _ => &[],
+    }
+}
+
+// Whether the heads cover every value of a well-typed position, so the
+// default is unreachable
+fn is_complete(heads: &[Head]) -> bool {
+    match heads {
+        [Head::Unit] | [Head::Tuple(_)] => true,
+        [Head::Bool(a), Head::Bool(b)] => a != b,
+        _ => false,
+    }
+}
+
+fn compile_rows(rows: Vec<Row<'_>>) -> Decision {
+    let Some(first) = rows.first() else {
+        return Decision::Fail;
+    };
+    // The first arm has nothing left to test
+    let Some((path, _)) = first.tests.first() else {
+        return Decision::Arm(first.arm);
+    };
+    let path = path.clone();
+
+    let test_at = |row: &Row<'_>| row.tests.iter().position(|(p, _)| *p == path);
+    let mut heads: Vec<Head> = Vec::new();
+    for row in &rows {
+        if let Some(i) = test_at(row) {
+            let h = head(row.tests[i].1);
+            if !heads.contains(&h) {
+                heads.push(h);
+            }
+        }
+    }
+
+    let cases = heads
+        .iter()
+        .map(|h| {
+            // Rows that agree with `h` here, with its fields to test in its place
+            let specialized = rows
+                .iter()
+                .filter_map(|row| {
+                    let Some(i) = test_at(row) else {
+                        return Some(row.clone());
+                    };
+                    let (_, pattern) = row.tests[i];
+                    if head(pattern) != *h {
+                        return None;
+                    }
+                    let mut tests = row.tests[..i].to_vec();
+                    for (j, field) in fields(pattern).iter().enumerate() {
+                        let mut child = path.clone();
+                        child.push(j);
+                        push_tests(child, field, &mut tests);
+                    }
+                    tests.extend_from_slice(&row.tests[i + 1..]);
+                    Some(Row { tests, arm: row.arm })
+                })
+                .collect();
+            (h.clone(), compile_rows(specialized))
+        })
+        .collect();
+
+    let default = if is_complete(&heads) {
+        Decision::Fail
+    } else {
+        compile_rows(rows.iter().filter(|row| test_at(row).is_none()).cloned().collect())
+    };
+    Decision::Switch { path, cases, default: Box::new(default) }
+}
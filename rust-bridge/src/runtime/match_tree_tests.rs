@@ -0,0 +1,94 @@
+// Tests for match compilation to decision trees (match_tree.rs)
+
+use crate::core_ir::Pattern;
+use crate::runtime::match_tree::{bindings, compile, Decision, Head};
+
+fn var(name: &str) -> Pattern {
+    Pattern::PVar(name.to_string())
+}
+
+fn ctor(name: &str, fields: Vec<Pattern>) -> Pattern {
+    Pattern::PEnum(name.to_string(), fields)
+}
+
+fn switch(path: &[usize], cases: Vec<(Head, Decision)>, default: Decision) -> Decision {
+    Decision::Switch { path: path.to_vec(), cases, default: Box::new(default) }
+}
+
+fn cons() -> Head {
+    Head::Ctor("Cons".to_string(), 2)
+}
+
+#[test]
+fn test_each_position_is_tested_once() {
+    // Cons(x, Nil) => 0, Cons(_, Cons(y, _)) => 1, Nil => 2
+    let arms = [
+        ctor("Cons", vec![var("x"), ctor("Nil", vec![])]),
+        ctor("Cons", vec![var("_"), ctor("Cons", vec![var("y"), var("_")])]),
+        ctor("Nil", vec![]),
+    ];
+    let tail = switch(
+        &[1],
+        vec![(Head::Ctor("Nil".to_string(), 0), Decision::Arm(0)), (cons(), Decision::Arm(1))],
+        Decision::Fail,
+    );
+    let expected = switch(&[], vec![(cons(), tail), (Head::Ctor("Nil".to_string(), 0), Decision::Arm(2))], Decision::Fail);
+    assert_eq!(compile(&arms), expected);
+}
+
+#[test]
+fn test_earlier_arms_win_and_wildcards_reach_every_case() {
+    // (1, _) => 0, (_, true) => 1, _ => 2
+    let arms = [
+        Pattern::PTuple(vec![Pattern::PInt(1), var("_")]),
+        Pattern::PTuple(vec![var("_"), Pattern::PBool(true)]),
+        var("_"),
+    ];
+    let second = switch(&[1], vec![(Head::Bool(true), Decision::Arm(1))], Decision::Arm(2));
+    let first = switch(&[0], vec![(Head::Int(1), Decision::Arm(0))], second);
+    // A tuple pattern covers every tuple: the wildcard arm is only reached through it
+    let expected = switch(&[], vec![(Head::Tuple(2), first)], Decision::Fail);
+    assert_eq!(compile(&arms), expected);
+}
+
+#[test]
+fn test_nested_literals_are_tested() {
+    // Some(0) => 0, Some(n) => 1
+    let arms = [ctor("Some", vec![Pattern::PInt(0)]), ctor("Some", vec![var("n")])];
+    let some = switch(&[0], vec![(Head::Int(0), Decision::Arm(0))], Decision::Arm(1));
+    let expected = switch(&[], vec![(Head::Ctor("Some".to_string(), 1), some)], Decision::Fail);
+    assert_eq!(compile(&arms), expected);
+}
+
+#[test]
+fn test_qualified_constructors_share_a_case() {
+    let arms = [ctor("Option::Some", vec![var("x")]), ctor("Some", vec![var("y")]), var("z")];
+    let expected = switch(&[], vec![(Head::Ctor("Some".to_string(), 1), Decision::Arm(0))], Decision::Arm(2));
+    assert_eq!(compile(&arms), expected);
+}
+
+#[test]
+fn test_bindings_are_positions_in_the_scrutinee() {
+    let pattern = ctor("Pair", vec![Pattern::PTuple(vec![var("a"), var("_")]), ctor("Just", vec![var("b")]), var("Nil")]);
+    assert_eq!(bindings(&pattern), vec![("a".to_string(), vec![0, 0]), ("b".to_string(), vec![1, 0])]);
+    assert!(bindings(&var("_")).is_empty());
+}
+
+#[test]
+fn test_as_patterns_are_tested_as_their_pattern_and_bound_whole() {
+    // all @ Cons(_, Nil) => 0, Cons(x, rest @ Cons(_, _)) => 1, _ => 2
+    let bind = |name: &str, pattern: Pattern| Pattern::PBind(name.to_string(), Box::new(pattern));
+    let arms = [
+        bind("all", ctor("Cons", vec![var("_"), ctor("Nil", vec![])])),
+        ctor("Cons", vec![var("x"), bind("rest", ctor("Cons", vec![var("_"), var("_")]))]),
+        var("_"),
+    ];
+    let tail = switch(
+        &[1],
+        vec![(Head::Ctor("Nil".to_string(), 0), Decision::Arm(0)), (cons(), Decision::Arm(1))],
+        Decision::Arm(2),
+    );
+    assert_eq!(compile(&arms), switch(&[], vec![(cons(), tail)], Decision::Arm(2)));
+    assert_eq!(bindings(&arms[0]), vec![("all".to_string(), vec![])]);
+    assert_eq!(bindings(&arms[1]), vec![("x".to_string(), vec![0]), ("rest".to_string(), vec![1])]);
+}
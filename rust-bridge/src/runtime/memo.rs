@@ -0,0 +1,98 @@
+// Result cache of @memo functions
+//
+// The compiler rewrites the body of `@memo fn f(...)` to call
+// memo_get(("f", key)) first and, on a miss, memo_put(("f", key, result))
+// (core-compiler/src/memo.rs), having checked that f only reaches
+// deterministic primitives. Keys are compared structurally, as `==` does.
+//
+// A key holding a StringBuilder, channel, task or sequence is never
+// cached: the first can change under the same value and the others are
+// only equal to themselves. Each thread has its own cache (spawned
+// threads start empty), kept until the thread ends.
+
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::value::{intern_tag, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+// An argument value as a cache key
+struct Key(Value);
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_value(&self.0, state);
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Key) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Key {}
+
+fn hash_value<H: Hasher>(value: &Value, state: &mut H) {
+    std::mem::discriminant(value).hash(state);
+    match value {
+        Value::Int(n) => n.hash(state),
+        Value::Bool(b) => b.hash(state),
+        Value::Str(s) => s.text().hash(state),
+        Value::Bytes(bytes) => bytes.hash(state),
+        Value::Tuple(items) | Value::List(items) => items.iter().for_each(|item| hash_value(item, state)),
+        Value::Ctor { tag, fields } => {
+            tag.hash(state);
+            fields.iter().for_each(|field| hash_value(field, state));
+        }
+        _ => {}
+    }
+}
+
+fn cacheable(value: &Value) -> bool {
+    match value {
+        Value::Builder(_) | Value::Channel(_) | Value::Task(_) | Value::Seq(_) => false,
+        Value::Tuple(items) | Value::List(items) | Value::Ctor { fields: items, .. } => items.iter().all(cacheable),
+        _ => true,
+    }
+}
+
+thread_local! {
+    // Function name -> argument -> result
+    static MEMO: RefCell<HashMap<String, HashMap<Key, Value>>> = RefCell::new(HashMap::new());
+}
+
+// Some(result) of an earlier call of the function on this argument, or None
+// UNARY CONTRACT: Accepts Value::Tuple containing [name, key]
+pub fn memo_get(args: Value) -> Value {
+    let Value::Tuple(mut elems) = args else {
+        axis_runtime_error("memo_get expects (name, key)", None);
+    };
+    let (Some(key), Some(Value::Str(name)), None) = (elems.pop(), elems.pop(), elems.pop()) else {
+        axis_runtime_error("memo_get expects (name, key)", None);
+    };
+    let key = Key(key);
+    let cached = MEMO.with(|memo| memo.borrow().get(name.text()).and_then(|results| results.get(&key)).cloned());
+    match cached {
+        Some(result) => Value::Ctor { tag: intern_tag("Some"), fields: vec![result] },
+        None => Value::Ctor { tag: intern_tag("None"), fields: Vec::new() },
+    }
+}
+
+// Record the result of the function on this argument; returns the result
+// UNARY CONTRACT: Accepts Value::Tuple containing [name, key, result]
+pub fn memo_put(args: Value) -> Value {
+    let Value::Tuple(mut elems) = args else {
+        axis_runtime_error("memo_put expects (name, key, result)", None);
+    };
+    let (Some(result), Some(key), Some(Value::Str(name)), None) = (elems.pop(), elems.pop(), elems.pop(), elems.pop())
+    else {
+        axis_runtime_error("memo_put expects (name, key, result)", None);
+    };
+    if cacheable(&key) {
+        MEMO.with(|memo| {
+            memo.borrow_mut().entry(name.text().to_string()).or_default().insert(Key(key), result.clone());
+        });
+    }
+    result
+}
@@ -0,0 +1,53 @@
+// Tests for the @memo result cache (memo.rs)
+
+use crate::runtime::memo::{memo_get, memo_put};
+use crate::runtime::value::{get_tag_name, init_runtime, str_lit, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::runtime::panic_on_runtime_errors;
+
+fn get(name: &str, key: Value) -> Option<Value> {
+    match memo_get(Value::Tuple(vec![str_lit(name), key])) {
+        Value::Ctor { tag, mut fields } if get_tag_name(tag) == "Some" => fields.pop(),
+        Value::Ctor { tag, fields } if get_tag_name(tag) == "None" && fields.is_empty() => None,
+        other => panic!("expected Some(_) or None, got {:?}", other),
+    }
+}
+
+fn put(name: &str, key: Value, result: Value) -> Value {
+    memo_put(Value::Tuple(vec![str_lit(name), key, result]))
+}
+
+#[test]
+fn test_memo_caches_per_function_and_argument() {
+    init_runtime();
+    let key = || Value::Tuple(vec![Value::Int(3), Value::List(vec![Value::string("a")])]);
+    assert_eq!(get("fib", key()), None);
+    assert_eq!(put("fib", key(), Value::Int(5)), Value::Int(5));
+    assert_eq!(get("fib", key()), Some(Value::Int(5)));
+
+    // Another function, or an argument that only prints the same, misses
+    assert_eq!(get("len", key()), None);
+    assert_eq!(get("fib", Value::Tuple(vec![Value::Int(3), Value::List(vec![Value::Int(0)])])), None);
+    assert_eq!(get("fib", Value::Tuple(vec![Value::Int(3), Value::List(vec![Value::string("a"), Value::Unit])])), None);
+
+    // Strings match by text, whether interned or owned
+    put("len", str_lit("abc"), Value::Int(3));
+    assert_eq!(get("len", Value::string("abc")), Some(Value::Int(3)));
+}
+
+#[test]
+fn test_memo_does_not_cache_builders() {
+    init_runtime();
+    let builder = Value::Builder(Rc::new(RefCell::new(String::from("x"))));
+    assert_eq!(put("build", builder.clone(), Value::Int(1)), Value::Int(1));
+    assert_eq!(get("build", builder), None);
+}
+
+#[test]
+#[should_panic(expected = "memo_put expects (name, key, result)")]
+fn test_memo_put_rejects_other_arguments() {
+    panic_on_runtime_errors();
+    init_runtime();
+    memo_put(Value::Tuple(vec![str_lit("f"), Value::Int(1)]));
+}
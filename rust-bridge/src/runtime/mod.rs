@@ -2,25 +2,145 @@
 // This module contains all runtime helpers needed for generated Axis code
 
 pub mod value;
+pub mod error;
 pub mod tuple;
 pub mod list;
 pub mod io;
+#[cfg(feature = "fs")]
+pub mod files;
+// Primitives behind a capability feature are left out by `build --allow`
+#[cfg(feature = "proc")]
+pub mod process;
+pub mod json;
+pub mod inspect;
+pub mod memo;
+pub mod entry;
+pub mod overflow;
+pub mod order;
+pub mod strings;
+pub mod chars;
+pub mod seq;
+pub mod concurrency;
+pub mod reactor;
 pub mod emit_rust;
+pub mod match_tree;
+pub mod native_args;
+pub mod last_use;
+pub mod operators;
+pub mod emit_c;
+#[cfg(feature = "fs")]
 pub mod core_emit;
 pub mod shim;
+pub use axis_ir::symbol_index;
+pub mod capability;
+pub mod code_writer;
 pub mod integration_guide;
+pub mod standalone;
+pub mod rust_items;
+
+// Runtime errors panic with their message in the crate's tests, so that
+// #[should_panic] can check them without the test harness exiting
+#[cfg(test)]
+pub(crate) fn panic_on_runtime_errors() {
+    error::set_runtime_error_handler(|msg, _| panic!("{}", msg));
+}
 
 #[cfg(test)]
 pub mod emit_rust_regression_tests;
 
+#[cfg(test)]
+mod match_tree_tests;
+
+#[cfg(test)]
+mod native_args_tests;
+
+#[cfg(test)]
+mod last_use_tests;
+
+#[cfg(test)]
+mod emit_c_tests;
+
 #[cfg(test)]
 mod shim_tests;
 
+#[cfg(all(test, feature = "fs"))]
+mod core_emit_tests;
+
+#[cfg(all(test, feature = "fs"))]
+mod files_tests;
+
+#[cfg(test)]
+mod capability_tests;
+
+#[cfg(test)]
+mod core_arena_tests;
+
+#[cfg(test)]
+mod core_inspect_tests;
+
+#[cfg(test)]
+mod progress_tests;
+
+#[cfg(test)]
+mod code_writer_tests;
+
+#[cfg(test)]
+mod reactor_tests;
+
+#[cfg(test)]
+mod abi_tests;
+
+#[cfg(test)]
+mod memo_tests;
+
+#[cfg(test)]
+mod entry_tests;
+
+#[cfg(test)]
+mod seq_tests;
+
+#[cfg(test)]
+mod overflow_tests;
+
+#[cfg(test)]
+mod order_tests;
+
+#[cfg(test)]
+mod strings_tests;
+
+#[cfg(test)]
+mod chars_tests;
+
+#[cfg(test)]
+mod standalone_tests;
+
+#[cfg(test)]
+mod rust_items_tests;
+
+#[cfg(test)]
+mod operators_tests;
+
 // Re-export all runtime items for convenient use
 pub use value::*;
+pub use error::*;
 pub use tuple::*;
 pub use list::*;
 pub use io::*;
+#[cfg(feature = "fs")]
+pub use files::*;
+#[cfg(feature = "proc")]
+pub use process::*;
+pub use json::*;
+pub use inspect::*;
+pub use memo::*;
+pub use overflow::*;
+pub use order::*;
+pub use strings::*;
+pub use chars::*;
+pub use seq::*;
+pub use concurrency::*;
+pub use reactor::*;
+#[cfg(feature = "fs")]
 pub use core_emit::*;
 
 // Re-export shim functions with explicit naming to avoid conflicts
@@ -0,0 +1,122 @@
+// Native argument lists for local functions (emit_rust, --native-args)
+//
+// Under the unary calling convention every generated function takes one
+// Value; an N-ary function receives its arguments packed into a
+// Value::Tuple and unpacks them again with tuple_field, one runtime index
+// lookup per parameter. When every reference to a top-level function is a
+// direct call with all of its arguments, the packed tuple is never seen
+// outside the generated code, so the function can take N Value parameters
+// and its calls pass the arguments as they are.
+//
+// POLICY: conservative. A function keeps the convention as soon as one
+// reference is anything but a full call: used as a value, partially or
+// over-applied, the target of an alias, defined twice, or its name bound
+// by a local anywhere in the program. The entry point always keeps it,
+// since the generated main calls it with the packed arguments.
+
+use crate::core_arena::{TermRef, TermView};
+use crate::core_ir::Pattern;
+use crate::runtime::emit_rust::{collect_app_args, sanitize_ident, strip_namespaces, top_level_name};
+use std::collections::{HashMap, HashSet};
+
+/// What the scanned definitions do with each top-level function
+#[derive(Debug, Default)]
+pub struct CallSites {
+    // Top-level name -> number of definitions
+    definitions: HashMap<String, usize>,
+    // Names referenced other than by a full call, or bound locally
+    escaped: HashSet<String>,
+}
+
+impl CallSites {
+    /// Record the top-level definitions of `core`: a whole program, or one
+    /// definition at a time when streaming. `defined_functions` are the
+    /// program's functions and arities (emit_rust::collect_function_names).
+    pub fn scan(&mut self, core: TermRef<'_>, defined_functions: &HashMap<String, usize>) {
+        let mut current = core;
+        while let TermView::Let(name, value, body, _) = current.view() {
+            *self.definitions.entry(top_level_name(name)).or_default() += 1;
+            // An alias forwards its one packed argument to the target
+            if let TermView::Var(..) = value.view() {
+                self.escape(name);
+            }
+            self.visit(value, defined_functions);
+            current = body;
+        }
+    }
+
+    /// The functions that can take native argument lists
+    pub fn native(&self, defined_functions: &HashMap<String, usize>) -> HashSet<String> {
+        self.definitions
+            .iter()
+            .filter(|&(name, &count)| {
+                count == 1
+                    && name != "axis_entry"
+                    && !self.escaped.contains(name)
+                    && defined_functions.get(name).is_some_and(|&arity| arity > 1)
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    fn escape(&mut self, name: &str) {
+        self.escaped.insert(sanitize_ident(&strip_namespaces(name)));
+    }
+
+    fn escape_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::PVar(name) => self.escape(name),
+            Pattern::PTuple(fields) | Pattern::PEnum(_, fields) => fields.iter().for_each(|field| self.escape_pattern(field)),
+            Pattern::PBind(name, inner) => {
+                self.escape(name);
+                self.escape_pattern(inner);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit(&mut self, term: TermRef<'_>, defined_functions: &HashMap<String, usize>) {
+        match term.view() {
+            TermView::IntLit(..) | TermView::BoolLit(..) | TermView::UnitLit(..) | TermView::StrLit(..) => {}
+            TermView::Var(name, _) => self.escape(name),
+            TermView::App(..) => {
+                // Resolved as emit_term_with_module resolves calls
+                let (func, args) = collect_app_args(term);
+                match func.view() {
+                    TermView::Var(name, _) if defined_functions.get(name.as_str()) == Some(&args.len()) => {}
+                    _ => self.visit(func, defined_functions),
+                }
+                for arg in args {
+                    self.visit(arg, defined_functions);
+                }
+            }
+            TermView::Lam(param, body, _) => {
+                self.escape(param);
+                self.visit(body, defined_functions);
+            }
+            TermView::Let(name, value, body, _) => {
+                self.escape(name);
+                self.visit(value, defined_functions);
+                self.visit(body, defined_functions);
+            }
+            TermView::Tuple(elems, _) | TermView::Ctor(_, elems, _) => {
+                for elem in elems.iter() {
+                    self.visit(elem, defined_functions);
+                }
+            }
+            TermView::Proj(expr, _, _) | TermView::CtorField(expr, _, _) => self.visit(expr, defined_functions),
+            TermView::If(cond, then_br, else_br, _) => {
+                self.visit(cond, defined_functions);
+                self.visit(then_br, defined_functions);
+                self.visit(else_br, defined_functions);
+            }
+            TermView::Match(scrutinee, arms, _) => {
+                self.visit(scrutinee, defined_functions);
+                for (pattern, body) in arms.iter() {
+                    self.escape_pattern(pattern);
+                    self.visit(body, defined_functions);
+                }
+            }
+        }
+    }
+}
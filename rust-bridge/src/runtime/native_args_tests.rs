@@ -0,0 +1,85 @@
+// Tests for choosing the functions emitted with native argument lists
+// (native_args.rs)
+
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::runtime::native_args::CallSites;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn app(f: CoreTerm, args: Vec<CoreTerm>) -> CoreTerm {
+    args.into_iter().fold(f, |f, arg| CoreTerm::App(Rc::new(f), Rc::new(arg), None))
+}
+
+fn call(f: &str, args: Vec<CoreTerm>) -> CoreTerm {
+    app(var(f), args)
+}
+
+fn lams(params: &[&str], body: CoreTerm) -> CoreTerm {
+    params.iter().rev().fold(body, |body, param| CoreTerm::Lam((*param).into(), Rc::new(body), None))
+}
+
+// Top-level definitions in order, then unit
+fn program(definitions: Vec<(&str, CoreTerm)>) -> ArenaProgram {
+    let term = definitions
+        .into_iter()
+        .rev()
+        .fold(CoreTerm::UnitLit(None), |body, (name, value)| CoreTerm::Let(name.into(), Rc::new(value), Rc::new(body), None));
+    ArenaProgram::from_term(&term)
+}
+
+fn native(program: &ArenaProgram, arities: &[(&str, usize)]) -> HashSet<String> {
+    let defined: HashMap<String, usize> = arities.iter().map(|(name, arity)| (name.to_string(), *arity)).collect();
+    let mut sites = CallSites::default();
+    sites.scan(program.root_term(), &defined);
+    sites.native(&defined)
+}
+
+fn names(names: &[&str]) -> HashSet<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_functions_only_called_in_full_take_native_args() {
+    // add(a, b) = a + b; axis_entry(args) = add(1, add(2, 3))
+    let add = lams(&["a", "b"], call("__add__", vec![var("a"), var("b")]));
+    let one = CoreTerm::IntLit(1, None);
+    let inner = call("add", vec![CoreTerm::IntLit(2, None), CoreTerm::IntLit(3, None)]);
+    let entry = lams(&["args"], call("add", vec![one, inner]));
+    let p = program(vec![("add", add), ("axis_entry", entry)]);
+    assert_eq!(native(&p, &[("add", 2), ("axis_entry", 1)]), names(&["add"]));
+}
+
+#[test]
+fn test_other_references_keep_the_convention() {
+    let pair = || lams(&["a", "b"], var("a"));
+    let cases = [
+        // Passed as a value
+        lams(&["args"], call("list_map", vec![var("pair"), var("args")])),
+        // Partially applied
+        lams(&["args"], call("pair", vec![var("args")])),
+        // Shadowed by a local
+        lams(&["pair"], call("pair", vec![var("pair"), var("pair")])),
+    ];
+    for entry in cases {
+        let p = program(vec![("pair", pair()), ("axis_entry", entry)]);
+        assert!(native(&p, &[("pair", 2), ("axis_entry", 1)]).is_empty());
+    }
+
+    // An alias forwards its packed argument, and the entry point is called
+    // by the generated main
+    let entry = lams(&["args"], CoreTerm::IntLit(0, None));
+    let p = program(vec![("start", lams(&["a", "b"], var("a"))), ("other", var("start")), ("axis_entry", entry)]);
+    assert!(native(&p, &[("start", 2), ("other", 2), ("axis_entry", 2)]).is_empty());
+}
+
+#[test]
+fn test_unary_functions_keep_the_convention() {
+    let entry = lams(&["args"], call("id", vec![var("args")]));
+    let p = program(vec![("id", lams(&["x"], var("x"))), ("axis_entry", entry)]);
+    assert!(native(&p, &[("id", 1), ("axis_entry", 1)]).is_empty());
+}
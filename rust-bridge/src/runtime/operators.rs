@@ -0,0 +1,45 @@
+// The infix operators' Core functions and their implementations
+//
+// The compiler lowers `a + b` to `(app (app (var __add__) a) b)`; both
+// emitters look the function up here, emit_rust for the shim path and
+// emit_c for the C runtime function.
+//
+// The operators themselves (symbols, precedence, Core functions) are the
+// table in axis-ir (axis_ir::operators), shared with the compiler; this
+// only says where each function is implemented, and operators_tests
+// checks that every function of that table is.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperatorFunction {
+    pub function: &'static str,
+    // Rust backend: re-exported by abi::shim (`__neq__` also at the top of
+    // abi, where generated code has always found it)
+    pub rust: &'static str,
+    // C backend: in c/axis_runtime.c
+    pub c: &'static str,
+}
+
+const fn op(function: &'static str, rust: &'static str, c: &'static str) -> OperatorFunction {
+    OperatorFunction { function, rust, c }
+}
+
+pub const OPERATOR_FUNCTIONS: &[OperatorFunction] = &[
+    op("__or__", "shim::__or__", "ax_prim_or"),
+    op("__and__", "shim::__and__", "ax_prim_and"),
+    op("__eq__", "shim::__eq__", "ax_prim_eq"),
+    op("__neq__", "__neq__", "ax_prim_neq"),
+    op("__lt__", "shim::__lt__", "ax_prim_lt"),
+    op("__lte__", "shim::__lte__", "ax_prim_lte"),
+    op("__gt__", "shim::__gt__", "ax_prim_gt"),
+    op("__gte__", "shim::__gte__", "ax_prim_gte"),
+    op("__concat__", "shim::__concat__", "ax_prim_str_concat"),
+    op("__add__", "shim::__add__", "ax_prim_add"),
+    op("__sub__", "shim::__sub__", "ax_prim_sub"),
+    op("__mul__", "shim::__mul__", "ax_prim_mul"),
+    op("__div__", "shim::__div__", "ax_prim_div"),
+    op("__mod__", "shim::__mod__", "ax_prim_mod"),
+];
+
+pub fn operator_function(function: &str) -> Option<&'static OperatorFunction> {
+    OPERATOR_FUNCTIONS.iter().find(|op| op.function == function)
+}
@@ -0,0 +1,27 @@
+// Tests for the operators' implementations (operators.rs)
+
+use crate::emit_rust::get_foreign_symbol_mapping;
+use crate::runtime::emit_c::RUNTIME_SOURCE;
+use crate::runtime::operators::{operator_function, OPERATOR_FUNCTIONS};
+use axis_ir::operators::{OPERATORS, PIPE};
+
+#[test]
+fn test_every_operator_is_implemented() {
+    let mut bridge: Vec<&str> = OPERATOR_FUNCTIONS.iter().map(|op| op.function).collect();
+    // `|>` applies PIPE, which the compiler lowers away
+    let mut shared: Vec<&str> = OPERATORS.iter().map(|op| op.function).filter(|function| *function != PIPE).collect();
+    bridge.sort();
+    shared.sort();
+    assert_eq!(bridge, shared);
+}
+
+#[test]
+fn test_both_backends_have_each_function() {
+    let mapping = get_foreign_symbol_mapping();
+    for op in OPERATOR_FUNCTIONS {
+        assert_eq!(mapping.get(op.function), Some(&op.rust), "{}", op.function);
+        assert!(RUNTIME_SOURCE.contains(&format!("AxValue *{}(", op.c)), "{} not in axis_runtime.c", op.c);
+        assert_eq!(operator_function(op.function), Some(op));
+    }
+    assert!(operator_function("__not__").is_none());
+}
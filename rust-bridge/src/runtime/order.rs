@@ -0,0 +1,128 @@
+// Ordering of values: value_cmp, the comparison primitives, list_sort_by
+//
+// Every two values are ordered, so `<` on strings or tuples and sorting a
+// list of them mean what they look like. Values of different kinds are
+// ordered by kind:
+//
+//   Unit < Bool < Int < Str < Bytes < Tuple < List < Ctor < StringBuilder
+//
+// and values of the same kind by their contents:
+//
+//   Bool               false < true
+//   Int                numerically
+//   Str, Bytes         lexicographically, by byte (for Str, by code point)
+//   Tuple, List        lexicographically, by element; a prefix comes first
+//   Ctor               by constructor name, then by fields as a tuple, except
+//                      that Nil comes before every other constructor, so a
+//                      Nil/Cons list compares as a List does
+//   StringBuilder      by its text so far
+//
+// Two values are ordered Equal exactly when `==` holds. Channels, tasks
+// and sequences are only equal to themselves and have no order: comparing
+// two different ones is a runtime error. The C runtime (runtime/c) and the
+// compiler's interpreter order values the same way.
+
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::list::{list_elements, list_from_elements};
+use crate::runtime::seq::SeqFn;
+use crate::runtime::value::{get_tag_name, Value};
+use std::cmp::Ordering;
+
+fn kind_rank(value: &Value) -> u8 {
+    match value {
+        Value::Unit => 0,
+        Value::Bool(_) => 1,
+        Value::Int(_) => 2,
+        Value::Str(_) => 3,
+        Value::Bytes(_) => 4,
+        Value::Tuple(_) => 5,
+        Value::List(_) => 6,
+        Value::Ctor { .. } => 7,
+        Value::Builder(_) => 8,
+        Value::Channel(_) => 9,
+        Value::Task(_) => 10,
+        Value::Seq(_) => 11,
+    }
+}
+
+fn compare_all(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| compare_values(x, y))
+        .find(|order| order.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn compare_tags(a: u32, b: u32) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+    let (a, b) = (get_tag_name(a), get_tag_name(b));
+    (a != "Nil").cmp(&(b != "Nil")).then_with(|| a.cmp(&b))
+}
+
+/// The order of two values (see above)
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Unit, Value::Unit) => Ordering::Equal,
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Int(x), Value::Int(y)) => x.cmp(y),
+        (Value::Str(x), Value::Str(y)) => x.text().cmp(y.text()),
+        (Value::Bytes(x), Value::Bytes(y)) => x.cmp(y),
+        (Value::Tuple(x), Value::Tuple(y)) | (Value::List(x), Value::List(y)) => compare_all(x, y),
+        (Value::Ctor { tag: x, fields: xs }, Value::Ctor { tag: y, fields: ys }) => {
+            compare_tags(*x, *y).then_with(|| compare_all(xs, ys))
+        }
+        (Value::Builder(x), Value::Builder(y)) => x.borrow().as_str().cmp(y.borrow().as_str()),
+        _ if kind_rank(a) != kind_rank(b) => kind_rank(a).cmp(&kind_rank(b)),
+        _ if a == b => Ordering::Equal,
+        _ => axis_runtime_error(&format!("cannot order two different {} values", a.kind_name()), None),
+    }
+}
+
+// -1, 0 or 1 as a is before, equal to or after b
+// UNARY CONTRACT: Accepts Value::Tuple containing [a, b]
+pub fn value_cmp(args: Value) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() == 2 => Value::Int(compare_values(&elems[0], &elems[1]) as i64),
+        _ => axis_runtime_error("value_cmp expects (a, b)", None),
+    }
+}
+
+// The list sorted by cmp(a, b), which returns an Int below, equal to or
+// above 0 as a goes before, with or after b. The sort is stable and the
+// list keeps its representation (Value::List or Nil/Cons). A cmp that is
+// not a consistent order gives the elements in some unspecified order,
+// never an error.
+pub fn list_sort_by(cmp: SeqFn, list: Value) -> Value {
+    let before = |a: &Value, b: &Value| match cmp.call(Value::Tuple(vec![a.clone(), b.clone()])) {
+        Value::Int(order) => order < 0,
+        other => axis_runtime_error(
+            &format!("list_sort_by: the compare function must return an Int, got {}", other.kind_name()),
+            None,
+        ),
+    };
+    list_from_elements(merge_sort(list_elements(&list), &before), &list)
+}
+
+// Stable merge sort by `before`. Unlike slice::sort_by, which may panic
+// when the order is not total, it only ever asks `before` and moves
+// elements, so any cmp of the program is safe.
+fn merge_sort(mut items: Vec<Value>, before: &dyn Fn(&Value, &Value) -> bool) -> Vec<Value> {
+    if items.len() <= 1 {
+        return items;
+    }
+    let right = merge_sort(items.split_off(items.len() / 2), before);
+    let left = merge_sort(items, before);
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(x), Some(y)) = (left.peek(), right.peek()) {
+        // Equal elements keep their order: the left one goes first
+        let next = if before(y, x) { right.next() } else { left.next() };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
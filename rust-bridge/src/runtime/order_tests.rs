@@ -0,0 +1,181 @@
+// Tests for the ordering of values (order.rs) and list_sort_by
+
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::runtime::concurrency::channel_new;
+use crate::runtime::emit_rust::emit_rust_from_core;
+use crate::runtime::order::{compare_values, list_sort_by, value_cmp};
+use crate::runtime::seq::SeqFn;
+use crate::runtime::value::{__lt__, init_runtime, intern_tag, Value};
+use std::cmp::Ordering;
+use std::rc::Rc;
+use crate::runtime::panic_on_runtime_errors;
+
+fn ctor(name: &str, fields: Vec<Value>) -> Value {
+    Value::Ctor { tag: intern_tag(name), fields }
+}
+
+fn ints(values: &[i64]) -> Value {
+    Value::List(values.iter().map(|n| Value::Int(*n)).collect())
+}
+
+fn cons_list(values: &[i64]) -> Value {
+    values.iter().rev().fold(ctor("Nil", Vec::new()), |tail, n| ctor("Cons", vec![Value::Int(*n), tail]))
+}
+
+fn pair(a: Value, b: Value) -> Value {
+    Value::Tuple(vec![a, b])
+}
+
+#[test]
+fn test_kinds_are_ordered() {
+    init_runtime();
+    let ascending = [
+        Value::Unit,
+        Value::Bool(true),
+        Value::Int(-5),
+        Value::string(""),
+        Value::Bytes(vec![0]),
+        Value::Tuple(Vec::new()),
+        ints(&[]),
+        ctor("None", Vec::new()),
+    ];
+    for (i, a) in ascending.iter().enumerate() {
+        for (j, b) in ascending.iter().enumerate() {
+            assert_eq!(compare_values(a, b), i.cmp(&j), "{:?} and {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_values_of_a_kind_are_ordered_by_contents() {
+    init_runtime();
+    let ascending = |values: &[Value]| {
+        for window in values.windows(2) {
+            assert_eq!(compare_values(&window[0], &window[1]), Ordering::Less, "{:?} < {:?}", window[0], window[1]);
+            assert_eq!(compare_values(&window[1], &window[0]), Ordering::Greater);
+        }
+    };
+    ascending(&[Value::Bool(false), Value::Bool(true)]);
+    ascending(&[Value::string(""), Value::string("a"), Value::string("ab"), Value::string("b"), Value::string("é")]);
+    ascending(&[
+        Value::Tuple(vec![Value::Int(1)]),
+        pair(Value::Int(1), Value::string("a")),
+        pair(Value::Int(1), Value::string("b")),
+        pair(Value::Int(2), Value::string("a")),
+    ]);
+    ascending(&[ints(&[]), ints(&[1]), ints(&[1, 2]), ints(&[2])]);
+    // Nil first, then the other constructors by name
+    ascending(&[
+        ctor("Nil", Vec::new()),
+        ctor("Cons", vec![Value::Int(9), ctor("Nil", Vec::new())]),
+        ctor("None", Vec::new()),
+        ctor("Some", vec![Value::Int(1)]),
+        ctor("Some", vec![Value::Int(2)]),
+    ]);
+    ascending(&[cons_list(&[]), cons_list(&[1]), cons_list(&[1, 2]), cons_list(&[2])]);
+}
+
+#[test]
+fn test_equal_exactly_when_eq() {
+    init_runtime();
+    let values = [
+        Value::Int(3),
+        Value::string("3"),
+        pair(Value::Int(1), ints(&[2, 3])),
+        cons_list(&[1, 2]),
+        ctor("Some", vec![Value::string("x")]),
+    ];
+    for a in &values {
+        for b in &values {
+            assert_eq!(compare_values(a, b) == Ordering::Equal, a == b, "{:?} and {:?}", a, b);
+        }
+    }
+    let channel = channel_new(Value::Unit);
+    assert_eq!(compare_values(&channel, &channel.clone()), Ordering::Equal);
+}
+
+#[test]
+#[should_panic(expected = "cannot order two different Channel values")]
+fn test_different_channels_have_no_order() {
+    panic_on_runtime_errors();
+    init_runtime();
+    compare_values(&channel_new(Value::Unit), &channel_new(Value::Unit));
+}
+
+#[test]
+fn test_comparison_primitives() {
+    init_runtime();
+    assert_eq!(__lt__(pair(Value::string("apple"), Value::string("banana"))), Value::Bool(true));
+    assert_eq!(__lt__(pair(Value::Int(2), Value::Int(1))), Value::Bool(false));
+    assert_eq!(value_cmp(pair(ints(&[1, 2]), ints(&[1, 3]))), Value::Int(-1));
+    assert_eq!(value_cmp(pair(Value::string("b"), Value::string("b"))), Value::Int(0));
+    assert_eq!(value_cmp(pair(Value::Int(1), Value::Bool(true))), Value::Int(1));
+}
+
+#[test]
+fn test_sort_by_is_stable_and_keeps_the_representation() {
+    init_runtime();
+    // By the first field only, descending
+    let by_first_descending = || {
+        SeqFn::new(|args: Value| match args {
+            Value::Tuple(elems) => match (&elems[0], &elems[1]) {
+                (Value::Tuple(a), Value::Tuple(b)) => value_cmp(pair(b[0].clone(), a[0].clone())),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        })
+    };
+    let entries = vec![
+        pair(Value::Int(1), Value::string("a")),
+        pair(Value::Int(2), Value::string("b")),
+        pair(Value::Int(1), Value::string("c")),
+        pair(Value::Int(2), Value::string("d")),
+    ];
+    let sorted = list_sort_by(by_first_descending(), Value::List(entries.clone()));
+    let expected = vec![entries[1].clone(), entries[3].clone(), entries[0].clone(), entries[2].clone()];
+    assert_eq!(sorted, Value::List(expected));
+
+    let cmp = || SeqFn::new(value_cmp);
+    assert_eq!(list_sort_by(cmp(), cons_list(&[3, 1, 2])), cons_list(&[1, 2, 3]));
+    assert_eq!(list_sort_by(cmp(), ints(&[])), ints(&[]));
+}
+
+#[test]
+#[should_panic(expected = "list_sort_by: the compare function must return an Int, got Bool")]
+fn test_sort_by_rejects_other_results() {
+    panic_on_runtime_errors();
+    init_runtime();
+    list_sort_by(SeqFn::new(|_| Value::Bool(true)), ints(&[2, 1]));
+}
+
+#[test]
+fn test_sort_by_survives_an_inconsistent_compare() {
+    init_runtime();
+    // Always "before": no total order, but every element is still there once
+    let sorted = list_sort_by(SeqFn::new(|_| Value::Int(-1)), ints(&[5, 3, 8, 1, 9, 2, 7, 4, 6, 0]));
+    let mut elems = match sorted {
+        Value::List(elems) => elems,
+        other => panic!("expected a list, got {:?}", other),
+    };
+    elems.sort_by(compare_values);
+    assert_eq!(Value::List(elems), ints(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]));
+}
+
+#[test]
+fn test_lambdas_of_two_parameters_take_a_tuple() {
+    // axis_entry = λxs. axis_list_sort_by(λa. λb. axis_value_cmp(b, a), xs)
+    let var = |name: &str| CoreTerm::Var(name.into(), None);
+    let app = |f: CoreTerm, args: Vec<CoreTerm>| {
+        args.into_iter().fold(f, |f, arg| CoreTerm::App(Rc::new(f), Rc::new(arg), None))
+    };
+    let lam = |param: &str, body: CoreTerm| CoreTerm::Lam(param.into(), Rc::new(body), None);
+    let descending = lam("a", lam("b", app(var("axis_value_cmp"), vec![var("b"), var("a")])));
+    let body = app(var("axis_list_sort_by"), vec![descending, var("xs")]);
+    let program = CoreTerm::Let("axis_entry".into(), Rc::new(lam("xs", body)), Rc::new(CoreTerm::UnitLit(None)), None);
+
+    let generated = emit_rust_from_core(ArenaProgram::from_term(&program).root_term(), "test.ax", "axis_entry");
+    assert!(generated.contains("shim::list_sort_by("), "{}", generated);
+    assert!(generated.contains("SeqFn::new(move |__args: Value| -> Value"), "{}", generated);
+    assert!(generated.contains("let b = tuple_field(Value::Tuple(vec![__args.clone(), Value::Int(1)]));"), "{}", generated);
+}
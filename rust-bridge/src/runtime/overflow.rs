@@ -0,0 +1,162 @@
+// Integer overflow semantics of the arithmetic primitives (--int-overflow)
+//
+// __add__, __sub__, __mul__, __div__ and __mod__ work on i64. What they do
+// when the result does not fit, or the divisor is 0, is chosen when the
+// program is built (`axis-rust-bridge build --int-overflow <mode>`) and
+// set by the generated `main` before anything runs:
+//
+//   wrap      two's complement wrap-around; x / 0 and x % 0 are 0. The
+//             default, and what the C backend and `axis-compiler run` do.
+//   checked   an overflowing result or a zero divisor is a runtime error
+//   saturate  an overflowing result is clamped to i64::MIN or i64::MAX; a
+//             zero divisor is a runtime error, there being no nearest value
+//
+// i64::MIN / -1 and i64::MIN % -1 are runtime errors under wrap too, as
+// they are in the C runtime.
+//
+// The mode is process-wide: spawned threads compute as the main one does.
+// The checked_* primitives return Some(result) or None whatever the mode,
+// for code that handles overflow itself.
+
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::value::{intern_tag, Value};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IntOverflow {
+    #[default]
+    Wrap,
+    Checked,
+    Saturate,
+}
+
+impl IntOverflow {
+    pub const ALL: [IntOverflow; 3] = [IntOverflow::Wrap, IntOverflow::Checked, IntOverflow::Saturate];
+
+    pub fn parse(name: &str) -> Option<IntOverflow> {
+        IntOverflow::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            IntOverflow::Wrap => "wrap",
+            IntOverflow::Checked => "checked",
+            IntOverflow::Saturate => "saturate",
+        }
+    }
+}
+
+static INT_OVERFLOW: AtomicU8 = AtomicU8::new(IntOverflow::Wrap as u8);
+
+// Called once by the generated `main` wrapper, before axis_entry
+pub fn set_int_overflow(mode: IntOverflow) {
+    INT_OVERFLOW.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn int_overflow() -> IntOverflow {
+    IntOverflow::ALL[INT_OVERFLOW.load(Ordering::Relaxed) as usize]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl IntOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            IntOp::Add => "+",
+            IntOp::Sub => "-",
+            IntOp::Mul => "*",
+            IntOp::Div => "/",
+            IntOp::Rem => "%",
+        }
+    }
+
+    // The exact result, or None when it does not fit or y is 0
+    fn checked(self, x: i64, y: i64) -> Option<i64> {
+        match self {
+            IntOp::Add => x.checked_add(y),
+            IntOp::Sub => x.checked_sub(y),
+            IntOp::Mul => x.checked_mul(y),
+            IntOp::Div => x.checked_div(y),
+            IntOp::Rem => x.checked_rem(y),
+        }
+    }
+}
+
+/// `x op y` under `mode`, or the message of the runtime error it raises
+pub fn int_arith(op: IntOp, x: i64, y: i64, mode: IntOverflow) -> Result<i64, String> {
+    if let Some(result) = op.checked(x, y) {
+        return Ok(result);
+    }
+    let by_zero = matches!(op, IntOp::Div | IntOp::Rem) && y == 0;
+    match (mode, op) {
+        (IntOverflow::Wrap, _) if by_zero => Ok(0),
+        _ if by_zero => Err(format!("division by zero: {} {} 0", x, op.symbol())),
+        (IntOverflow::Checked, _) => Err(format!("integer overflow: {} {} {}", x, op.symbol(), y)),
+        (IntOverflow::Wrap, IntOp::Add) => Ok(x.wrapping_add(y)),
+        (IntOverflow::Wrap, IntOp::Sub) => Ok(x.wrapping_sub(y)),
+        (IntOverflow::Wrap, IntOp::Mul) => Ok(x.wrapping_mul(y)),
+        (IntOverflow::Wrap, IntOp::Div) => Err("attempt to divide with overflow".to_string()),
+        (IntOverflow::Wrap, IntOp::Rem) => Err("attempt to calculate the remainder with overflow".to_string()),
+        (IntOverflow::Saturate, IntOp::Add) => Ok(x.saturating_add(y)),
+        (IntOverflow::Saturate, IntOp::Sub) => Ok(x.saturating_sub(y)),
+        (IntOverflow::Saturate, IntOp::Mul) => Ok(x.saturating_mul(y)),
+        (IntOverflow::Saturate, IntOp::Div) => Ok(x.saturating_div(y)),
+        // i64::MIN % -1 is 0, which fits
+        (IntOverflow::Saturate, IntOp::Rem) => Ok(0),
+    }
+}
+
+// An arithmetic primitive under the program's mode; arguments other than
+// two Ints give 0, as they always have
+pub(crate) fn arith_primitive(args: Value, op: IntOp) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() >= 2 => match (&elems[0], &elems[1]) {
+            (Value::Int(x), Value::Int(y)) => match int_arith(op, *x, *y, int_overflow()) {
+                Ok(result) => Value::Int(result),
+                Err(message) => axis_runtime_error(&message, None),
+            },
+            _ => Value::Int(0),
+        },
+        _ => Value::Int(0),
+    }
+}
+
+fn checked_primitive(args: Value, op: IntOp, name: &str) -> Value {
+    let (x, y) = match args {
+        Value::Tuple(ref elems) if elems.len() == 2 => match (&elems[0], &elems[1]) {
+            (Value::Int(x), Value::Int(y)) => (*x, *y),
+            _ => axis_runtime_error(&format!("{} expects two Ints", name), None),
+        },
+        _ => axis_runtime_error(&format!("{} expects (x, y)", name), None),
+    };
+    match op.checked(x, y) {
+        Some(result) => Value::Ctor { tag: intern_tag("Some"), fields: vec![Value::Int(result)] },
+        None => Value::Ctor { tag: intern_tag("None"), fields: Vec::new() },
+    }
+}
+
+// Axis primitives: Some(result), or None on overflow (or division by zero)
+// UNARY CONTRACT: Accepts Value::Tuple containing [x, y]
+
+pub fn checked_add(args: Value) -> Value {
+    checked_primitive(args, IntOp::Add, "checked_add")
+}
+
+pub fn checked_sub(args: Value) -> Value {
+    checked_primitive(args, IntOp::Sub, "checked_sub")
+}
+
+pub fn checked_mul(args: Value) -> Value {
+    checked_primitive(args, IntOp::Mul, "checked_mul")
+}
+
+pub fn checked_div(args: Value) -> Value {
+    checked_primitive(args, IntOp::Div, "checked_div")
+}
@@ -0,0 +1,130 @@
+// Tests for the integer overflow modes (overflow.rs) and `build --int-overflow`
+
+use crate::backend::{backend_by_name, EmitError};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::emit_rust::EmitOptions;
+use crate::runtime::overflow::{checked_add, checked_div, checked_mul, checked_sub, int_arith, IntOp, IntOverflow};
+use crate::runtime::value::{__add__, __div__, __mod__, get_tag_name, init_runtime, Value};
+use std::path::Path;
+use std::rc::Rc;
+use crate::runtime::panic_on_runtime_errors;
+
+const MAX: i64 = i64::MAX;
+const MIN: i64 = i64::MIN;
+
+// op, x, y and the result under wrap, checked and saturate
+type Case = (IntOp, i64, i64, [Result<i64, &'static str>; 3]);
+
+fn pair(x: i64, y: i64) -> Value {
+    Value::Tuple(vec![Value::Int(x), Value::Int(y)])
+}
+
+fn option(value: Value) -> Option<i64> {
+    match value {
+        Value::Ctor { tag, fields } if get_tag_name(tag) == "Some" => Some(fields[0].as_int()),
+        Value::Ctor { tag, fields } if get_tag_name(tag) == "None" && fields.is_empty() => None,
+        other => panic!("expected Some(_) or None, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_mode() {
+    for mode in IntOverflow::ALL {
+        assert_eq!(IntOverflow::parse(mode.name()), Some(mode));
+    }
+    assert_eq!(IntOverflow::default(), IntOverflow::Wrap);
+    assert_eq!(IntOverflow::parse("trap"), None);
+}
+
+#[test]
+fn test_results_by_mode() {
+    use IntOverflow::*;
+    let cases: [Case; 8] = [
+        (IntOp::Add, 2, 3, [Ok(5), Ok(5), Ok(5)]),
+        (IntOp::Add, MAX, 1, [Ok(MIN), Err("integer overflow: 9223372036854775807 + 1"), Ok(MAX)]),
+        (IntOp::Sub, MIN, 1, [Ok(MAX), Err("integer overflow: -9223372036854775808 - 1"), Ok(MIN)]),
+        (IntOp::Mul, MIN, -1, [Ok(MIN), Err("integer overflow: -9223372036854775808 * -1"), Ok(MAX)]),
+        (IntOp::Div, 7, 0, [Ok(0), Err("division by zero: 7 / 0"), Err("division by zero: 7 / 0")]),
+        (IntOp::Rem, 7, 0, [Ok(0), Err("division by zero: 7 % 0"), Err("division by zero: 7 % 0")]),
+        (
+            IntOp::Div,
+            MIN,
+            -1,
+            [Err("attempt to divide with overflow"), Err("integer overflow: -9223372036854775808 / -1"), Ok(MAX)],
+        ),
+        (IntOp::Rem, -7, 2, [Ok(-1), Ok(-1), Ok(-1)]),
+    ];
+    for (op, x, y, expected) in cases {
+        for (mode, expected) in [Wrap, Checked, Saturate].into_iter().zip(expected) {
+            let expected = expected.map_err(str::to_string);
+            assert_eq!(int_arith(op, x, y, mode), expected, "{:?} {} {} under {:?}", op, x, y, mode);
+        }
+    }
+}
+
+#[test]
+fn test_primitives_wrap_by_default() {
+    init_runtime();
+    assert_eq!(__add__(pair(MAX, 1)), Value::Int(MIN));
+    assert_eq!(__div__(pair(7, 0)), Value::Int(0));
+    assert_eq!(__mod__(pair(7, 0)), Value::Int(0));
+    // Arguments that are not two Ints still give 0
+    assert_eq!(__add__(Value::Tuple(vec![Value::Int(1), Value::Bool(true)])), Value::Int(0));
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide with overflow")]
+fn test_min_divided_by_minus_one_is_a_runtime_error() {
+    panic_on_runtime_errors();
+    init_runtime();
+    __div__(pair(MIN, -1));
+}
+
+#[test]
+fn test_checked_primitives() {
+    init_runtime();
+    assert_eq!(option(checked_add(pair(2, 3))), Some(5));
+    assert_eq!(option(checked_add(pair(MAX, 1))), None);
+    assert_eq!(option(checked_sub(pair(MIN, 1))), None);
+    assert_eq!(option(checked_mul(pair(-4, 5))), Some(-20));
+    assert_eq!(option(checked_mul(pair(MAX, 2))), None);
+    assert_eq!(option(checked_div(pair(7, 2))), Some(3));
+    assert_eq!(option(checked_div(pair(7, 0))), None);
+    assert_eq!(option(checked_div(pair(MIN, -1))), None);
+}
+
+#[test]
+#[should_panic(expected = "checked_add expects two Ints")]
+fn test_checked_primitives_reject_other_arguments() {
+    panic_on_runtime_errors();
+    init_runtime();
+    checked_add(Value::Tuple(vec![Value::Int(1), Value::string("2")]));
+}
+
+#[test]
+fn test_main_sets_the_mode() {
+    // axis_entry(args) = checked_div(7, 0)
+    let app = |f: CoreTerm, arg: CoreTerm| CoreTerm::App(Rc::new(f), Rc::new(arg), None);
+    let call = app(app(CoreTerm::Var("axis_checked_div".into(), None), CoreTerm::IntLit(7, None)), CoreTerm::IntLit(0, None));
+    let entry = CoreTerm::Lam("args".into(), Rc::new(call), None);
+    let program =
+        ArenaProgram::from_term(&CoreTerm::Let("axis_entry".into(), Rc::new(entry), Rc::new(CoreTerm::UnitLit(None)), None));
+
+    let rust = backend_by_name("rust", Path::new(".")).unwrap();
+    let main = |mode: IntOverflow| {
+        let opts = EmitOptions { int_overflow: mode, ..EmitOptions::default() };
+        let artifacts = rust.emit(&program, &opts).unwrap();
+        let generated = &artifacts.files.iter().find(|(path, _)| path == Path::new("src/axis_generated.rs")).unwrap().1;
+        assert!(generated.contains("shim::checked_div("), "{}", generated);
+        artifacts.files.into_iter().find(|(path, _)| path == Path::new("src/main.rs")).unwrap().1
+    };
+    assert!(main(IntOverflow::Wrap).contains("set_int_overflow(IntOverflow::Wrap);"));
+    assert!(main(IntOverflow::Checked).contains("set_int_overflow(IntOverflow::Checked);"));
+
+    // The C runtime only wraps
+    let c = backend_by_name("c", Path::new(".")).unwrap();
+    let opts = EmitOptions { int_overflow: IntOverflow::Saturate, ..EmitOptions::default() };
+    let err = c.emit(&program, &opts).err().expect("the C runtime has no overflow modes");
+    assert!(matches!(err, EmitError::Unsupported(_)), "{}", err);
+}
@@ -0,0 +1,120 @@
+// Environment and process primitives
+// Lets generated programs (notably the self-hosted compiler) read configuration
+// from the environment and orchestrate external tools such as cargo.
+
+use super::value::{Value, intern_tag};
+use super::list::list_elements;
+use std::process::Command;
+
+// Returns Some(value) when the variable is set to valid UTF-8, None otherwise
+pub fn env_get(name: Value) -> Value {
+    match name {
+        Value::Str(s) => match std::env::var(s.text()) {
+            Ok(value) => Value::Ctor {
+                tag: intern_tag("Some"),
+                fields: vec![Value::string(&value)],
+            },
+            Err(_) => Value::Ctor { tag: intern_tag("None"), fields: vec![] },
+        },
+        _ => Value::Ctor { tag: intern_tag("None"), fields: vec![] },
+    }
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [name, value]
+pub fn env_set(args: Value) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() >= 2 => {
+            match (&elems[0], &elems[1]) {
+                (Value::Str(name), Value::Str(value)) => {
+                    let name_str = name.text().to_string();
+                    // std::env::set_var panics on these; surface them as Err instead
+                    if name_str.is_empty() || name_str.contains('=') || name_str.contains('\0') {
+                        return Value::Ctor {
+                            tag: intern_tag("Err"),
+                            fields: vec![Value::string(&format!("Invalid environment variable name: {:?}", name_str))],
+                        };
+                    }
+                    if value.text().contains('\0') {
+                        return Value::Ctor {
+                            tag: intern_tag("Err"),
+                            fields: vec![Value::string(&format!("Invalid environment variable value for {}: contains NUL", name_str))],
+                        };
+                    }
+                    std::env::set_var(&name_str, value.text());
+                    Value::Ctor { tag: intern_tag("Ok"), fields: vec![Value::Unit] }
+                },
+                _ => Value::Ctor {
+                    tag: intern_tag("Err"),
+                    fields: vec![Value::string("Invalid arguments")],
+                },
+            }
+        },
+        _ => Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::string("Invalid arguments")],
+        },
+    }
+}
+
+// Run a program to completion, capturing its output.
+// UNARY CONTRACT: Accepts Value::Tuple containing [program, args]
+// where args is a List[Str] (Value::List or Nil/Cons chain).
+// Returns Ok(ProcResult(exit_code, stdout, stderr)) or Err(msg) if the
+// process could not be spawned. A process killed by a signal reports -1.
+pub fn proc_run(args: Value) -> Value {
+    let (program, argv) = match args {
+        Value::Tuple(ref elems) if elems.len() >= 2 => {
+            let program = match &elems[0] {
+                Value::Str(s) => s.text().to_string(),
+                _ => return Value::Ctor {
+                    tag: intern_tag("Err"),
+                    fields: vec![Value::string("proc_run: program must be a string")],
+                },
+            };
+            let mut argv = Vec::new();
+            for arg in list_elements(&elems[1]) {
+                match arg {
+                    Value::Str(s) => argv.push(s.text().to_string()),
+                    other => return Value::Ctor {
+                        tag: intern_tag("Err"),
+                        fields: vec![Value::string(&format!("proc_run: argument must be a string, got {}", other))],
+                    },
+                }
+            }
+            (program, argv)
+        },
+        _ => return Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::string("Invalid arguments")],
+        },
+    };
+
+    match Command::new(&program).args(&argv).output() {
+        Ok(output) => Value::Ctor {
+            tag: intern_tag("Ok"),
+            fields: vec![Value::Ctor {
+                tag: intern_tag("ProcResult"),
+                fields: vec![
+                    Value::Int(output.status.code().map(|c| c as i64).unwrap_or(-1)),
+                    Value::string(&String::from_utf8_lossy(&output.stdout)),
+                    Value::string(&String::from_utf8_lossy(&output.stderr)),
+                ],
+            }],
+        },
+        Err(e) => Value::Ctor {
+            tag: intern_tag("Err"),
+            fields: vec![Value::string(&format!("Failed to run {}: {}", program, e))],
+        },
+    }
+}
+
+// Terminate the process immediately with the given exit code.
+// Stdout is flushed first so buffered output (io_println) is not lost.
+pub fn proc_exit(code: Value) -> Value {
+    crate::runtime::io::flush_stdout();
+    let code = match code {
+        Value::Int(n) => n as i32,
+        _ => 1,
+    };
+    std::process::exit(code)
+}
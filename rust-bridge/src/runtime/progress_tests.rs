@@ -0,0 +1,102 @@
+// Tests for build progress reporting (progress.rs)
+
+use crate::progress::{wait_with_heartbeat, Progress, ProgressEvent, Timings};
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_events_print_as_the_cli_lines() {
+    let start = ProgressEvent::start("phase4_emit_rust").with("stream", 1);
+    assert_eq!(start.to_string(), "[PHASE] phase4_emit_rust=start stream=1");
+    let end = ProgressEvent::PhaseEnd {
+        phase: "phase4_core_ir_load".to_string(),
+        ms: 12,
+        details: vec![("terms".to_string(), "40".to_string()), ("symbols".to_string(), "7".to_string())],
+    };
+    assert_eq!(end.to_string(), "[PHASE] phase4_core_ir_load=end ms=12 terms=40 symbols=7");
+    let heartbeat = ProgressEvent::Heartbeat { task: "cargo_build_wait".to_string(), count: 3000 };
+    assert_eq!(heartbeat.to_string(), "[PROGRESS] phase=axis_rust_bridge loop=cargo_build_wait count=3000");
+}
+
+#[test]
+fn test_end_records_the_elapsed_time() {
+    let started = Instant::now() - Duration::from_millis(50);
+    match ProgressEvent::end("phase4_cargo_build", started) {
+        ProgressEvent::PhaseEnd { phase, ms, details } => {
+            assert_eq!(phase, "phase4_cargo_build");
+            assert!(ms >= 50, "{}", ms);
+            assert!(details.is_empty());
+        }
+        other => panic!("expected PhaseEnd, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_channel_receives_the_events() {
+    let (sender, events) = channel();
+    let progress = Progress::new(sender);
+    progress.report(ProgressEvent::start("phase4_core_ir_load"));
+    progress.heartbeat("core_ir_deserialize", 10000);
+    drop(progress);
+    let received: Vec<ProgressEvent> = events.iter().collect();
+    assert_eq!(
+        received,
+        vec![
+            ProgressEvent::start("phase4_core_ir_load"),
+            ProgressEvent::Heartbeat { task: "core_ir_deserialize".to_string(), count: 10000 },
+        ]
+    );
+}
+
+#[test]
+fn test_hung_up_receiver_is_ignored() {
+    let (sender, events) = channel();
+    drop(events);
+    Progress::new(sender).heartbeat("core_ir_deserialize", 1);
+}
+
+#[test]
+fn test_closure_is_a_reporter() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&seen);
+    let progress = Progress::new(move |event: &ProgressEvent| sink.lock().unwrap().push(event.to_string()));
+    progress.clone().report(ProgressEvent::start("phase4_axis_rust_bridge_run"));
+    assert_eq!(*seen.lock().unwrap(), vec!["[PHASE] phase4_axis_rust_bridge_run=start"]);
+}
+
+#[test]
+fn test_timings_nest_the_phases_in_start_order() {
+    let timings = Timings::default();
+    let end = |phase: &str, ms| ProgressEvent::PhaseEnd { phase: phase.to_string(), ms, details: Vec::new() };
+    timings.record(&ProgressEvent::start("phase4_axis_rust_bridge_run"));
+    timings.record(&ProgressEvent::start("phase4_core_ir_load"));
+    timings.record(&ProgressEvent::Heartbeat { task: "core_ir_deserialize".to_string(), count: 10 });
+    timings.record(&end("phase4_core_ir_load", 12));
+    timings.record(&ProgressEvent::start("phase4_emit_rust"));
+    timings.record(&end("phase4_emit_rust", 3));
+    // Never ended: the build failed in it
+    timings.record(&ProgressEvent::start("phase4_cargo_build"));
+    timings.record(&end("phase4_axis_rust_bridge_run", 20));
+    assert_eq!(
+        timings.format(),
+        concat!(
+            "        time  phase\n",
+            "       20 ms  phase4_axis_rust_bridge_run\n",
+            "       12 ms    phase4_core_ir_load\n",
+            "        3 ms    phase4_emit_rust\n",
+        )
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_wait_with_heartbeat_beats_until_the_child_exits() {
+    let (sender, events) = channel();
+    let mut child = std::process::Command::new("sleep").arg("0.2").spawn().unwrap();
+    let status = wait_with_heartbeat(&mut child, "sleep_wait", Duration::from_millis(20), &Progress::new(sender)).unwrap();
+    assert!(status.success());
+    let beats: Vec<ProgressEvent> = events.try_iter().collect();
+    assert!(beats.len() >= 2, "{:?}", beats);
+    assert!(beats.iter().all(|event| matches!(event, ProgressEvent::Heartbeat { task, .. } if task == "sleep_wait")));
+}
@@ -0,0 +1,236 @@
+// Runtime profiles (`build --runtime blocking|async`)
+//
+// Under the default blocking profile the IO and file primitives call std
+// directly on the thread that runs them. The async profile is for IO-bound
+// programs (a language server, say): the runtime is built with the `async`
+// feature and the generated main runs the Axis entry under drive_reactor.
+//   - The reactor's worker threads carry out the blocking calls (offload).
+//     Each call is submitted as a job whose completion is a Future
+//     (IoFuture); the calling thread waits for that future parked
+//     (block_on) instead of inside the system call.
+//   - stdin is read ahead, a line at a time, by a reader thread of its own,
+//     so input that arrives while the program computes is already
+//     buffered when io_read asks for it.
+// Axis has no async constructs: a primitive still returns its result, and
+// a program means the same under both profiles. Threads made with spawn
+// share the one reactor.
+//
+// NOTE: std has no non-blocking file IO, so the reactor is a pool of
+// worker threads rather than an event loop over readiness; IoFuture is the
+// boundary at which one would go.
+//
+// POLICY: io_print and io_eprint stay on the calling thread under both
+// profiles; output keeps the order the program wrote it in.
+
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::sync::mpsc::{self, Receiver, Sender};
+#[cfg(feature = "async")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Wake, Waker};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuntimeProfile {
+    #[default]
+    Blocking,
+    Async,
+}
+
+impl RuntimeProfile {
+    pub const ALL: [RuntimeProfile; 2] = [RuntimeProfile::Blocking, RuntimeProfile::Async];
+
+    pub fn parse(name: &str) -> Option<RuntimeProfile> {
+        match name {
+            "blocking" => Some(RuntimeProfile::Blocking),
+            "async" => Some(RuntimeProfile::Async),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RuntimeProfile::Blocking => "blocking",
+            RuntimeProfile::Async => "async",
+        }
+    }
+
+    /// The cargo feature of this crate that compiles the profile in, if any
+    pub fn feature(self) -> Option<&'static str> {
+        match self {
+            RuntimeProfile::Blocking => None,
+            RuntimeProfile::Async => Some("async"),
+        }
+    }
+}
+
+// Blocking profile: the call is made here
+#[cfg(not(feature = "async"))]
+pub fn offload<T: Send + 'static>(call: impl FnOnce() -> T + Send + 'static) -> T {
+    call()
+}
+
+// ============================================================================
+// Reactor (feature `async`)
+// ============================================================================
+
+#[cfg(feature = "async")]
+type Job = Box<dyn FnOnce() + Send>;
+
+// Where offload submits jobs; None outside drive_reactor
+#[cfg(feature = "async")]
+static REACTOR: Mutex<Option<Sender<Job>>> = Mutex::new(None);
+
+#[cfg(feature = "async")]
+const REACTOR_WORKERS: usize = 4;
+
+/// Runs `entry` (the generated main's call of axis_entry) with the reactor
+/// running, then lets the workers finish the jobs still queued
+#[cfg(feature = "async")]
+pub fn drive_reactor<T>(entry: impl FnOnce() -> T) -> T {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    let workers: Vec<_> = (0..REACTOR_WORKERS)
+        .map(|_| {
+            let receiver = Arc::clone(&receiver);
+            std::thread::spawn(move || loop {
+                let job = receiver.lock().unwrap().recv();
+                match job {
+                    Ok(job) => job(),
+                    // Every sender is gone: the reactor is shut down
+                    Err(_) => break,
+                }
+            })
+        })
+        .collect();
+    *REACTOR.lock().unwrap() = Some(sender);
+
+    let result = entry();
+
+    REACTOR.lock().unwrap().take();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    result
+}
+
+/// Runs a blocking IO call on the reactor and waits for it; outside
+/// drive_reactor (a library caller, the tests) the call is made here
+#[cfg(feature = "async")]
+pub fn offload<T: Send + 'static>(call: impl FnOnce() -> T + Send + 'static) -> T {
+    let sender = REACTOR.lock().unwrap().clone();
+    match sender {
+        Some(sender) => block_on(submit(&sender, call)),
+        None => call(),
+    }
+}
+
+#[cfg(feature = "async")]
+struct Completion<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// The result of a job on the reactor
+#[cfg(feature = "async")]
+pub struct IoFuture<T> {
+    state: Arc<Mutex<Completion<T>>>,
+}
+
+#[cfg(feature = "async")]
+impl<T> Future for IoFuture<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match state.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+fn submit<T: Send + 'static>(reactor: &Sender<Job>, call: impl FnOnce() -> T + Send + 'static) -> IoFuture<T> {
+    let state = Arc::new(Mutex::new(Completion { value: None, waker: None }));
+    let completion = Arc::clone(&state);
+    let job: Job = Box::new(move || {
+        let value = call();
+        let mut completion = completion.lock().unwrap();
+        completion.value = Some(value);
+        if let Some(waker) = completion.waker.take() {
+            waker.wake();
+        }
+    });
+    // FAIL-FAST: the workers only stop once every sender is gone
+    reactor.send(job).unwrap_or_else(|_| panic!("reactor: the worker threads are gone"));
+    IoFuture { state }
+}
+
+// Wakes a thread parked in block_on
+#[cfg(feature = "async")]
+struct ThreadWaker(std::thread::Thread);
+
+#[cfg(feature = "async")]
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` on this thread, parking it until the future is woken
+#[cfg(feature = "async")]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+            return value;
+        }
+        // A wake between the poll and here leaves the token set; park
+        // then returns at once
+        std::thread::park();
+    }
+}
+
+// Lines of stdin read ahead, started by the first read; the reader stops
+// after the end of input or an error, which it sends as the last item
+#[cfg(all(feature = "async", feature = "io"))]
+static STDIN_LINES: std::sync::OnceLock<Mutex<Receiver<std::io::Result<Vec<u8>>>>> = std::sync::OnceLock::new();
+
+/// The next line of stdin with its newline; Ok(empty) at the end of input
+#[cfg(all(feature = "async", feature = "io"))]
+pub fn stdin_line() -> std::io::Result<Vec<u8>> {
+    let lines = STDIN_LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            use std::io::BufRead;
+            let mut stdin = std::io::stdin().lock();
+            loop {
+                let mut line = Vec::new();
+                match stdin.read_until(b'\n', &mut line) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        if sender.send(Ok(line)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = sender.send(Err(e));
+                        break;
+                    }
+                }
+            }
+        });
+        Mutex::new(receiver)
+    });
+    // A closed channel is the end of input
+    lines.lock().unwrap().recv().unwrap_or_else(|_| Ok(Vec::new()))
+}
@@ -0,0 +1,85 @@
+// Tests for the runtime profiles (reactor.rs) and their use by the
+// backends (`build --runtime`)
+
+use crate::backend::{backend_by_name, EmitError};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::emit_rust::EmitOptions;
+use crate::runtime::capability::Capability;
+use crate::runtime::reactor::{offload, RuntimeProfile};
+use std::path::Path;
+use std::rc::Rc;
+
+// axis_entry(args) = str_len("abc")
+fn program() -> ArenaProgram {
+    let entry = CoreTerm::Lam(
+        "args".into(),
+        Rc::new(CoreTerm::App(
+            Rc::new(CoreTerm::Var("str_len".into(), None)),
+            Rc::new(CoreTerm::StrLit("abc".to_string(), None)),
+            None,
+        )),
+        None,
+    );
+    ArenaProgram::from_term(&CoreTerm::Let("axis_entry".into(), Rc::new(entry), Rc::new(CoreTerm::UnitLit(None)), None))
+}
+
+fn file<'a>(files: &'a [(std::path::PathBuf, String)], name: &str) -> &'a str {
+    &files.iter().find(|(path, _)| path == Path::new(name)).unwrap().1
+}
+
+#[test]
+fn test_parse_profile() {
+    for profile in RuntimeProfile::ALL {
+        assert_eq!(RuntimeProfile::parse(profile.name()), Some(profile));
+    }
+    assert_eq!(RuntimeProfile::default(), RuntimeProfile::Blocking);
+    assert_eq!(RuntimeProfile::parse("tokio"), None);
+}
+
+#[test]
+fn test_async_profile_drives_the_reactor() {
+    let rust = backend_by_name("rust", Path::new("/opt/axis-rust-bridge")).unwrap();
+    let blocking = rust.emit(&program(), &EmitOptions::default()).unwrap();
+    assert!(!file(&blocking.files, "Cargo.toml").contains("features"));
+    assert!(file(&blocking.files, "src/main.rs").contains("let result = axis_generated::axis_entry(axis_args);"));
+
+    let async_opts = EmitOptions { runtime: RuntimeProfile::Async, ..EmitOptions::default() };
+    let artifacts = rust.emit(&program(), &async_opts).unwrap();
+    let cargo_toml = file(&artifacts.files, "Cargo.toml");
+    assert!(cargo_toml.contains("path = \"/opt/axis-rust-bridge\", features = [\"async\"] }"), "{}", cargo_toml);
+    assert!(file(&artifacts.files, "src/main.rs")
+        .contains("let result = drive_reactor(move || axis_generated::axis_entry(axis_args));"));
+    // The generated code does not change
+    assert_eq!(file(&artifacts.files, "src/axis_generated.rs"), file(&blocking.files, "src/axis_generated.rs"));
+
+    // With --allow the profile's feature joins the allowed capabilities
+    let sandboxed = EmitOptions { allow: Some(vec![Capability::Io]), ..async_opts.clone() };
+    let artifacts = rust.emit(&program(), &sandboxed).unwrap();
+    assert!(file(&artifacts.files, "Cargo.toml").contains("default-features = false, features = [\"io\", \"async\"]"));
+
+    let c = backend_by_name("c", Path::new(".")).unwrap();
+    let err = c.emit(&program(), &async_opts).err().expect("the C runtime has no reactor");
+    assert!(matches!(err, EmitError::Unsupported(_)), "{}", err);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_blocking_offload_calls_here() {
+    let caller = std::thread::current().id();
+    assert!(offload(move || std::thread::current().id() == caller));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_offload_runs_on_the_reactor() {
+    use crate::runtime::reactor::drive_reactor;
+    let caller = std::thread::current().id();
+    let (same_thread, sum) = drive_reactor(|| {
+        let same_thread = offload(move || std::thread::current().id() == caller);
+        let sum: u64 = (0..20u64).map(|n| offload(move || n * 2)).sum();
+        (same_thread, sum)
+    });
+    assert!(!same_thread);
+    assert_eq!(sum, 380);
+}
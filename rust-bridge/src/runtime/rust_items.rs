@@ -0,0 +1,577 @@
+// Pruning Rust sources to the items a program reaches (`build
+// --standalone`, standalone.rs)
+//
+// The standalone runtime is this crate's runtime modules, but a program
+// only reaches part of them: hello-world needs io_println and what it
+// calls, not JSON or the reactor. prune keeps the items reachable from the
+// names the root sources use (the main wrapper and the generated module,
+// whose calls into the runtime are the primitives emit_rust records as
+// foreign calls) and drops the rest:
+//   - an item is kept when a kept item or a root names something it
+//     defines; names are matched without their module path, so items of
+//     the same name in two modules are kept together
+//   - an `impl` is kept with its type, and with its trait when that is a
+//     runtime trait implemented for a type from elsewhere
+//   - leaves of `use` lists naming dropped items are removed, and a `use`
+//     left empty goes with them
+//   - inline `mod` blocks are kept, their items pruned like the top level
+//   - items under `#[cfg(any())]` (a feature resolved off) are dropped
+//
+// This is a scanner, not a parser: it knows tokens, nesting and the item
+// keywords, which is all the runtime's sources need. Items defined by
+// macros other than thread_local! are not seen.
+
+use std::collections::HashSet;
+
+// Item keywords, as the first of them in an item's head
+const KINDS: &[&str] = &[
+    "fn", "impl", "struct", "enum", "mod", "trait", "union", "type", "static", "const", "use", "thread_local",
+    "macro_rules",
+];
+
+// Items that end at their closing brace rather than at a `;`
+// (`struct S(u8);` ends at its `;` before either)
+const BRACED: &[&str] = &["fn", "impl", "struct", "enum", "mod", "trait", "union", "thread_local", "macro_rules"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TokenKind {
+    Ident,
+    Punct,
+    Literal,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+// Identifiers, punctuation (`::` as one token) and literals; comments and
+// whitespace are skipped, lifetimes dropped
+fn tokenize(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if source[i..].starts_with("//") {
+            i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+        } else if source[i..].starts_with("/*") {
+            // Block comments nest
+            let mut depth = 0;
+            while i < bytes.len() {
+                if source[i..].starts_with("/*") {
+                    depth += 1;
+                    i += 2;
+                } else if source[i..].starts_with("*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if let Some(end) = raw_string_end(source, i) {
+            i = end;
+            tokens.push(Token { kind: TokenKind::Literal, start, end });
+        } else if c == b'"' || (c == b'b' && bytes.get(i + 1) == Some(&b'"')) {
+            i += if c == b'b' { 2 } else { 1 };
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i += 1;
+            tokens.push(Token { kind: TokenKind::Literal, start, end: i.min(bytes.len()) });
+        } else if c == b'\'' || (c == b'b' && bytes.get(i + 1) == Some(&b'\'')) {
+            i += if c == b'b' { 2 } else { 1 };
+            if bytes.get(i) == Some(&b'\\') {
+                // An escaped char: up to the closing quote
+                i += 2;
+                while i < bytes.len() && bytes[i] != b'\'' {
+                    i += 1;
+                }
+                i += 1;
+                tokens.push(Token { kind: TokenKind::Literal, start, end: i.min(bytes.len()) });
+            } else {
+                let width = source[i..].chars().next().map_or(1, char::len_utf8);
+                if bytes.get(i + width) == Some(&b'\'') {
+                    i += width + 1;
+                    tokens.push(Token { kind: TokenKind::Literal, start, end: i });
+                } else {
+                    // A lifetime
+                    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                        i += 1;
+                    }
+                }
+            }
+        } else if c.is_ascii_alphabetic() || c == b'_' || c >= 0x80 {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] >= 0x80) {
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Ident, start, end: i });
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Literal, start, end: i });
+        } else {
+            i += if source[i..].starts_with("::") { 2 } else { 1 };
+            tokens.push(Token { kind: TokenKind::Punct, start, end: i });
+        }
+    }
+    tokens
+}
+
+// End of the raw string (r"..", r#".."#, br#".."#) starting at `i`, if one does
+fn raw_string_end(source: &str, i: usize) -> Option<usize> {
+    let rest = &source[i..];
+    let rest = rest.strip_prefix('b').unwrap_or(rest).strip_prefix('r')?;
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    if !rest[hashes..].starts_with('"') {
+        return None;
+    }
+    let close = format!("\"{}", "#".repeat(hashes));
+    let body_start = source.len() - rest.len() + hashes + 1;
+    let end = source[body_start..].find(&close)?;
+    Some(body_start + end + close.len())
+}
+
+#[derive(Debug)]
+struct Item {
+    // Byte range, from the end of the previous item: the comments and
+    // attributes before an item go with it
+    start: usize,
+    end: usize,
+    // Token range
+    tokens: (usize, usize),
+    kind: Option<&'static str>,
+    // Names defined (for `use`, see UseTree)
+    names: Vec<String>,
+    // impl: the type and, for a trait impl, the trait
+    self_type: Option<String>,
+    trait_name: Option<String>,
+    compiled_out: bool,
+    // Inline `mod`: the items between the braces (token index of `{`)
+    children: Option<(usize, Vec<Item>)>,
+    use_tree: Option<UseTree>,
+}
+
+// The items of tokens[from..to], the first one's text from `text_start`
+fn split_items(source: &str, tokens: &[Token], from: usize, to: usize, text_start: usize) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut start = text_start;
+    let mut i = from;
+    while i < to {
+        let first = i;
+        let mut depth = 0usize;
+        let mut head: Vec<usize> = Vec::new();
+        let mut head_done = false;
+        let mut kind: Option<&'static str> = None;
+        let mut open_brace = None;
+        loop {
+            let token = tokens[i];
+            let text = &source[token.start..token.end];
+            if depth == 0 && !head_done {
+                match token.kind {
+                    TokenKind::Ident => {
+                        head.push(i);
+                        if kind.is_none() {
+                            kind = KINDS.iter().copied().find(|k| *k == text);
+                        }
+                    }
+                    TokenKind::Punct if text == "{" || text == "=" || text == ";" => head_done = true,
+                    _ => {}
+                }
+            }
+            match text {
+                "{" | "(" | "[" if token.kind == TokenKind::Punct => {
+                    if depth == 0 && text == "{" && open_brace.is_none() {
+                        open_brace = Some(i);
+                    }
+                    depth += 1;
+                }
+                "}" | ")" | "]" if token.kind == TokenKind::Punct => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            i += 1;
+            let ends = depth == 0
+                && token.kind == TokenKind::Punct
+                && (text == ";" || (text == "}" && kind.is_some_and(|k| BRACED.contains(&k))));
+            if ends || i >= to {
+                break;
+            }
+        }
+        // `const fn` is a function
+        let texts: Vec<&str> = head.iter().map(|&t| &source[tokens[t].start..tokens[t].end]).collect();
+        if kind == Some("const") && texts.iter().skip_while(|t| **t != "const").nth(1) == Some(&"fn") {
+            kind = Some("fn");
+        }
+        let end = tokens[i - 1].end;
+        // The attributes are what comes before the head
+        let attributes = &source[start..head.first().map_or(end, |&t| tokens[t].start)];
+        let mut item = Item {
+            start,
+            end,
+            tokens: (first, i),
+            kind,
+            names: Vec::new(),
+            self_type: None,
+            trait_name: None,
+            compiled_out: attributes.contains("#[cfg(any())]"),
+            children: None,
+            use_tree: None,
+        };
+        describe(source, tokens, &texts, &mut item);
+        if kind == Some("mod") {
+            if let Some(open) = open_brace {
+                let children = split_items(source, tokens, open + 1, i - 1, tokens[open].end);
+                item.children = Some((open, children));
+            }
+        }
+        items.push(item);
+        start = end;
+    }
+    items
+}
+
+// The names an item defines, from its head
+fn describe(source: &str, tokens: &[Token], head: &[&str], item: &mut Item) {
+    let text = |t: usize| &source[tokens[t].start..tokens[t].end];
+    let after = |keyword: &str| -> Option<String> {
+        let position = head.iter().position(|t| *t == keyword)?;
+        head[position + 1..].iter().find(|t| **t != "mut").map(|t| t.to_string())
+    };
+    match item.kind {
+        Some("thread_local") => {
+            let (from, to) = item.tokens;
+            for t in from..to.saturating_sub(1) {
+                if text(t) == "static" && tokens[t + 1].kind == TokenKind::Ident {
+                    item.names.push(text(t + 1).to_string());
+                }
+            }
+        }
+        Some("macro_rules") => item.names.extend(after("macro_rules")),
+        Some("impl") => {
+            // impl<..> Trait<..> for Type<..> / impl<..> Type<..>: the last
+            // segment of each path, generics skipped
+            let (from, to) = item.tokens;
+            let mut paths: Vec<String> = Vec::new();
+            let mut angle = 0usize;
+            let mut last: Option<String> = None;
+            for token in &tokens[from..to] {
+                match &source[token.start..token.end] {
+                    "{" | "where" if angle == 0 => break,
+                    "<" => angle += 1,
+                    ">" => angle = angle.saturating_sub(1),
+                    "for" if angle == 0 => paths.extend(last.take()),
+                    "impl" | "dyn" | "mut" | "unsafe" => {}
+                    name if angle == 0 && token.kind == TokenKind::Ident => last = Some(name.to_string()),
+                    _ => {}
+                }
+            }
+            paths.extend(last);
+            item.self_type = paths.pop();
+            item.trait_name = paths.pop();
+        }
+        Some("use") => {
+            let (from, to) = item.tokens;
+            let position = (from..to).find(|&t| text(t) == "use").expect("use item");
+            let mut parser = UseParser { source, tokens, position: position + 1, end: to };
+            item.use_tree = parser.tree();
+        }
+        Some(kind) => item.names.extend(after(kind)),
+        None => {}
+    }
+}
+
+// ============================================================================
+// `use` trees
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum UseTree {
+    // a::b::c or a::b::c as d
+    Leaf(Vec<String>, Option<String>),
+    // a::b::*
+    Glob(Vec<String>),
+    // a::b::{..}
+    Group(Vec<String>, Vec<UseTree>),
+}
+
+struct UseParser<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    position: usize,
+    end: usize,
+}
+
+impl<'a> UseParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        let token = self.tokens[..self.end].get(self.position)?;
+        Some(&self.source[token.start..token.end])
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let text = self.peek()?;
+        self.position += 1;
+        Some(text)
+    }
+
+    fn tree(&mut self) -> Option<UseTree> {
+        let mut path = Vec::new();
+        loop {
+            match self.peek()? {
+                "{" => {
+                    self.next();
+                    let mut trees = Vec::new();
+                    while self.peek()? != "}" {
+                        if self.peek()? == "," {
+                            self.next();
+                            continue;
+                        }
+                        trees.push(self.tree()?);
+                    }
+                    self.next();
+                    return Some(UseTree::Group(path, trees));
+                }
+                "*" => {
+                    self.next();
+                    return Some(UseTree::Glob(path));
+                }
+                _ => {
+                    path.push(self.next()?.to_string());
+                    match self.peek() {
+                        Some("::") => {
+                            self.next();
+                        }
+                        Some("as") => {
+                            self.next();
+                            let alias = self.next()?.to_string();
+                            return Some(UseTree::Leaf(path, Some(alias)));
+                        }
+                        _ => return Some(UseTree::Leaf(path, None)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl UseTree {
+    // Each leaf's (imported name, bound name); `parent` is the segment a
+    // `self` leaf imports
+    fn leaves(&self, parent: Option<&str>, out: &mut Vec<(String, String)>) {
+        match self {
+            UseTree::Leaf(path, alias) => {
+                let last = path.last().map(String::as_str);
+                let original = match last {
+                    Some("self") => path.iter().rev().nth(1).map(String::as_str).or(parent),
+                    _ => last,
+                };
+                if let Some(original) = original {
+                    out.push((original.to_string(), alias.clone().unwrap_or_else(|| original.to_string())));
+                }
+            }
+            UseTree::Glob(_) => {}
+            UseTree::Group(path, trees) => {
+                let parent = path.last().map(String::as_str).or(parent);
+                trees.iter().for_each(|tree| tree.leaves(parent, out));
+            }
+        }
+    }
+
+    // The tree without the leaves `keep` refuses; None if nothing is left
+    fn retain(&self, parent: Option<&str>, keep: &dyn Fn(&str, &str) -> bool) -> Option<UseTree> {
+        match self {
+            UseTree::Leaf(..) => {
+                let mut leaves = Vec::new();
+                self.leaves(parent, &mut leaves);
+                leaves.iter().all(|(original, bound)| keep(original, bound)).then(|| self.clone())
+            }
+            UseTree::Glob(_) => Some(self.clone()),
+            UseTree::Group(path, trees) => {
+                let inner = path.last().map(String::as_str).or(parent);
+                let trees: Vec<UseTree> = trees.iter().filter_map(|tree| tree.retain(inner, keep)).collect();
+                (!trees.is_empty()).then(|| UseTree::Group(path.clone(), trees))
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let prefix = |path: &[String]| path.iter().map(|segment| format!("{}::", segment)).collect::<String>();
+        match self {
+            UseTree::Leaf(path, None) => path.join("::"),
+            UseTree::Leaf(path, Some(alias)) => format!("{} as {}", path.join("::"), alias),
+            UseTree::Glob(path) => format!("{}*", prefix(path)),
+            UseTree::Group(path, trees) => {
+                let trees: Vec<String> = trees.iter().map(UseTree::render).collect();
+                format!("{}{{{}}}", prefix(path), trees.join(", "))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Pruning
+// ============================================================================
+
+struct Unit<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    items: Vec<Item>,
+}
+
+/// `sources` without the items that nothing reachable from the names used
+/// in `roots` needs; names are shared between the sources
+pub fn prune(sources: &[&str], roots: &[&str]) -> Vec<String> {
+    let units: Vec<Unit> = sources
+        .iter()
+        .map(|source| {
+            let tokens = tokenize(source);
+            let items = split_items(source, &tokens, 0, tokens.len(), 0);
+            Unit { source, tokens, items }
+        })
+        .collect();
+
+    let mut reached: HashSet<String> = HashSet::new();
+    for root in roots {
+        add_idents(root, &tokenize(root), (0, usize::MAX), &mut reached);
+    }
+    let mut defined: HashSet<String> = HashSet::new();
+    for unit in &units {
+        for_each_item(&unit.items, &mut |item| defined.extend(item.names.iter().cloned()));
+    }
+
+    // Until nothing more is reached
+    let mut kept: HashSet<(usize, usize)> = HashSet::new();
+    loop {
+        let mut changed = false;
+        for (index, unit) in units.iter().enumerate() {
+            for_each_item(&unit.items, &mut |item| {
+                if item.compiled_out || kept.contains(&(index, item.start)) {
+                    return;
+                }
+                if let Some(tree) = &item.use_tree {
+                    // `a as b`: a is needed when b is
+                    let mut leaves = Vec::new();
+                    tree.leaves(None, &mut leaves);
+                    for (original, bound) in leaves {
+                        if reached.contains(&bound) && reached.insert(original) {
+                            changed = true;
+                        }
+                    }
+                    return;
+                }
+                if needed(item, &reached, &defined) {
+                    kept.insert((index, item.start));
+                    // A module's items are reached on their own
+                    if item.children.is_none() {
+                        add_idents(unit.source, &unit.tokens, item.tokens, &mut reached);
+                    }
+                    changed = true;
+                }
+            });
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    units
+        .iter()
+        .enumerate()
+        .map(|(index, unit)| {
+            let mut out = String::new();
+            let end = render_items(unit, &unit.items, index, &kept, &reached, &defined, &mut out);
+            out.push_str(&unit.source[end..]);
+            out
+        })
+        .collect()
+}
+
+fn for_each_item(items: &[Item], f: &mut dyn FnMut(&Item)) {
+    for item in items {
+        f(item);
+        if let Some((_, children)) = &item.children {
+            for_each_item(children, f);
+        }
+    }
+}
+
+// Whether a kept item or a root names the item, its type or its trait
+fn needed(item: &Item, reached: &HashSet<String>, defined: &HashSet<String>) -> bool {
+    if item.children.is_some() {
+        return true;
+    }
+    if let Some(self_type) = &item.self_type {
+        let local_trait = item.trait_name.as_ref().filter(|name| defined.contains(*name));
+        return match local_trait {
+            Some(trait_name) if !defined.contains(self_type) => reached.contains(trait_name),
+            Some(trait_name) => reached.contains(self_type) && reached.contains(trait_name),
+            None => reached.contains(self_type),
+        };
+    }
+    // An item that defines nothing (a stray `;`) costs nothing to keep
+    item.names.is_empty() || item.names.iter().any(|name| reached.contains(name))
+}
+
+fn add_idents(source: &str, tokens: &[Token], (from, to): (usize, usize), reached: &mut HashSet<String>) {
+    for token in &tokens[from..to.min(tokens.len())] {
+        if token.kind == TokenKind::Ident {
+            reached.insert(source[token.start..token.end].to_string());
+        }
+    }
+}
+
+// Writes the kept items; returns the end of the last one written
+fn render_items(
+    unit: &Unit,
+    items: &[Item],
+    index: usize,
+    kept: &HashSet<(usize, usize)>,
+    reached: &HashSet<String>,
+    defined: &HashSet<String>,
+    out: &mut String,
+) -> usize {
+    let mut end = 0;
+    for item in items {
+        end = item.end;
+        if item.compiled_out {
+            continue;
+        }
+        if let Some(tree) = &item.use_tree {
+            let keep = |original: &str, bound: &str| !defined.contains(original) || reached.contains(bound);
+            match tree.retain(None, &keep) {
+                Some(retained) if retained == *tree => out.push_str(&unit.source[item.start..item.end]),
+                Some(retained) => {
+                    let (from, to) = item.tokens;
+                    let keyword = (from..to).find(|&t| &unit.source[unit.tokens[t].start..unit.tokens[t].end] == "use");
+                    let head_end = keyword.map_or(item.start, |t| unit.tokens[t].end);
+                    out.push_str(&unit.source[item.start..head_end]);
+                    out.push_str(&format!(" {};", retained.render()));
+                }
+                None => {}
+            }
+            continue;
+        }
+        if !kept.contains(&(index, item.start)) {
+            continue;
+        }
+        match &item.children {
+            Some((open, children)) => {
+                out.push_str(&unit.source[item.start..unit.tokens[*open].end]);
+                let inner_end = render_items(unit, children, index, kept, reached, defined, out);
+                let close = unit.tokens[item.tokens.1 - 1].start;
+                out.push_str(&unit.source[inner_end.max(unit.tokens[*open].end)..close]);
+                out.push_str(&unit.source[close..item.end]);
+            }
+            None => out.push_str(&unit.source[item.start..item.end]),
+        }
+    }
+    end
+}
@@ -0,0 +1,94 @@
+// Tests for pruning the standalone runtime (rust_items.rs)
+
+use crate::runtime::rust_items::prune;
+
+const HELPERS: &str = r##"
+use std::fmt;
+use crate::runtime::other::{Shape, area, unused_helper};
+
+/// Reached from the root
+pub fn describe(shape: Shape) -> String {
+    format!("{} {}", shape_name(&shape), area(&shape))
+}
+
+fn shape_name(shape: &Shape) -> &'static str {
+    // a `}` in a comment and in a string: "}"
+    let _brace = '}';
+    let _raw = r#"fn never_reached() {"#;
+    "shape"
+}
+
+fn never_reached() -> u32 {
+    1
+}
+
+#[cfg(any())]
+pub fn describe(shape: Shape) -> String {
+    never_reached().to_string()
+}
+"##;
+
+const OTHER: &str = "
+pub struct Shape {
+    pub sides: u32,
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, \"{}\", self.sides)
+    }
+}
+
+pub struct Unused;
+
+impl Unused {
+    pub fn new() -> Self {
+        Unused
+    }
+}
+
+pub fn area(shape: &Shape) -> u32 {
+    shape.sides
+}
+
+pub fn unused_helper() {}
+
+thread_local! {
+    static CACHE: u32 = 0;
+}
+";
+
+fn pruned() -> Vec<String> {
+    prune(&[HELPERS, OTHER], &["fn main() { describe(make()); }"])
+}
+
+#[test]
+fn test_prune_keeps_what_the_root_reaches() {
+    let pruned = pruned();
+    assert!(pruned[0].contains("pub fn describe(shape: Shape)"));
+    assert!(pruned[0].contains("fn shape_name"), "helper of a kept function");
+    assert!(pruned[1].contains("pub fn area"), "reached through another source");
+    assert!(pruned[1].contains("pub struct Shape"));
+    assert!(pruned[1].contains("impl fmt::Display for Shape"), "impl kept with its type");
+}
+
+#[test]
+fn test_prune_drops_what_nothing_reaches() {
+    let pruned = pruned();
+    // Only named in a string, and by a compiled out item
+    assert!(!pruned[0].contains("fn never_reached() -> u32"), "{}", pruned[0]);
+    assert!(!pruned[0].contains("#[cfg(any())]"));
+    assert!(!pruned[1].contains("pub fn unused_helper"));
+    assert!(!pruned[1].contains("pub struct Unused"));
+    assert!(!pruned[1].contains("impl Unused"), "impl dropped with its type");
+    assert!(!pruned[1].contains("CACHE"));
+}
+
+#[test]
+fn test_prune_removes_dropped_names_from_use_lists() {
+    let pruned = pruned();
+    assert!(pruned[0].contains("use std::fmt;"), "names from elsewhere are left alone");
+    assert!(pruned[0].contains("use crate::runtime::other::{Shape, area};"), "{}", pruned[0]);
+    let pruned = prune(&["use crate::x::{gone};\nfn gone() {}\n"], &["fn main() {}"]);
+    assert_eq!(pruned[0].trim(), "");
+}
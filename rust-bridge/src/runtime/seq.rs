@@ -0,0 +1,228 @@
+// Lazy sequences (Value::Seq)
+//
+// A Seq computes its items one at a time, when they are first asked for,
+// and remembers them: `seq_map(f, s)` calls f on an item only once,
+// however many times the result is read. A pipeline such as
+//
+//   seq_to_list(seq_take(seq_filter(is_ident, seq_unfold(next_token, src)), 10))
+//
+// runs next_token until it has ten identifiers and never builds the list
+// of every token. A Seq is immutable; copies share the items computed so
+// far. Items no remaining copy can reach are freed as the sequence is
+// consumed.
+//
+// The function given to seq_map, seq_filter or seq_unfold is a SeqFn:
+// emit_rust passes a top-level function, or a lambda as a closure that
+// owns copies of the locals it uses (the validator accepts nothing else,
+// E_FUNCTION_ARG). It runs when an item is computed, not at the call.
+//
+// A Seq holds Rcs, so like a StringBuilder it cannot be sent to another
+// thread (concurrency.rs).
+
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::value::{intern_tag, truthy, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A function value given to a sequence primitive
+#[derive(Clone)]
+pub struct SeqFn(Rc<dyn Fn(Value) -> Value>);
+
+impl SeqFn {
+    pub fn new(function: impl Fn(Value) -> Value + 'static) -> SeqFn {
+        SeqFn(Rc::new(function))
+    }
+
+    pub(crate) fn call(&self, arg: Value) -> Value {
+        (self.0)(arg)
+    }
+}
+
+// The first item and the rest, or None at the end
+type Step = Option<(Value, Seq)>;
+
+enum Node {
+    Pending(Box<dyn FnOnce() -> Step>),
+    // The step is running; reaching the node again means the sequence
+    // depends on itself
+    Forcing,
+    Done(Step),
+}
+
+#[derive(Clone)]
+pub struct Seq(Rc<RefCell<Node>>);
+
+impl Seq {
+    fn lazy(step: impl FnOnce() -> Step + 'static) -> Seq {
+        Seq(Rc::new(RefCell::new(Node::Pending(Box::new(step)))))
+    }
+
+    fn empty() -> Seq {
+        Seq(Rc::new(RefCell::new(Node::Done(None))))
+    }
+
+    /// The first item and the rest, computed on first use
+    pub fn next(&self) -> Step {
+        if let Node::Done(step) = &*self.0.borrow() {
+            return step.clone();
+        }
+        let node = std::mem::replace(&mut *self.0.borrow_mut(), Node::Forcing);
+        let step = match node {
+            Node::Done(step) => step,
+            Node::Pending(compute) => compute(),
+            Node::Forcing => axis_runtime_error("seq: an item of the sequence depends on itself", None),
+        };
+        *self.0.borrow_mut() = Node::Done(step.clone());
+        step
+    }
+
+    // The rest of a computed sequence whose last copy this is, detached
+    // so that dropping the node does not drop the rest with it
+    fn detach_rest(&self) -> Option<Seq> {
+        if Rc::strong_count(&self.0) > 1 {
+            return None;
+        }
+        match std::mem::replace(&mut *self.0.borrow_mut(), Node::Done(None)) {
+            Node::Done(Some((_, rest))) => Some(rest),
+            _ => None,
+        }
+    }
+}
+
+// A long computed sequence is dropped node by node, not by recursion
+impl Drop for Seq {
+    fn drop(&mut self) {
+        let mut rest = self.detach_rest();
+        while let Some(seq) = rest {
+            rest = seq.detach_rest();
+        }
+    }
+}
+
+// Sequences are compared by identity
+impl PartialEq for Seq {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for Seq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Seq")
+    }
+}
+
+impl std::fmt::Debug for SeqFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SeqFn")
+    }
+}
+
+fn seq_arg(value: Value, what: &str) -> Seq {
+    match value {
+        Value::Seq(seq) => seq,
+        other => axis_runtime_error(&format!("{}: expected Seq, got {}", what, other.kind_name()), None),
+    }
+}
+
+fn from_items(items: Rc<Vec<Value>>, index: usize) -> Seq {
+    Seq::lazy(move || {
+        let item = items.get(index)?.clone();
+        Some((item, from_items(items, index + 1)))
+    })
+}
+
+fn map(function: SeqFn, seq: Seq) -> Seq {
+    Seq::lazy(move || {
+        let (item, rest) = seq.next()?;
+        Some((function.call(item), map(function, rest)))
+    })
+}
+
+fn filter(predicate: SeqFn, seq: Seq) -> Seq {
+    Seq::lazy(move || {
+        let mut seq = seq;
+        loop {
+            let (item, rest) = seq.next()?;
+            if truthy(&predicate.call(item.clone())) {
+                return Some((item, filter(predicate, rest)));
+            }
+            seq = rest;
+        }
+    })
+}
+
+fn take(seq: Seq, count: i64) -> Seq {
+    if count <= 0 {
+        return Seq::empty();
+    }
+    Seq::lazy(move || {
+        let (item, rest) = seq.next()?;
+        Some((item, take(rest, count - 1)))
+    })
+}
+
+fn unfold(step: SeqFn, state: Value) -> Seq {
+    Seq::lazy(move || match step.call(state) {
+        Value::Ctor { tag, fields } if tag == intern_tag("None") && fields.is_empty() => None,
+        Value::Ctor { tag, mut fields } if tag == intern_tag("Some") && fields.len() == 1 => match fields.pop() {
+            Some(Value::Tuple(mut pair)) if pair.len() == 2 => {
+                let next = pair.pop().unwrap_or(Value::Unit);
+                let item = pair.pop().unwrap_or(Value::Unit);
+                Some((item, unfold(step, next)))
+            }
+            _ => axis_runtime_error("seq_unfold: the step function must return Some((item, state)) or None", None),
+        },
+        _ => axis_runtime_error("seq_unfold: the step function must return Some((item, state)) or None", None),
+    })
+}
+
+// Axis primitives
+
+// The items of a list, as a sequence
+pub fn seq_from_list(list: Value) -> Value {
+    match list {
+        Value::List(items) => Value::Seq(from_items(Rc::new(items), 0)),
+        other => axis_runtime_error(&format!("seq_from_list: expected List, got {}", other.kind_name()), None),
+    }
+}
+
+// f applied to each item
+pub fn seq_map(function: SeqFn, seq: Value) -> Value {
+    Value::Seq(map(function, seq_arg(seq, "seq_map")))
+}
+
+// The items for which the predicate is true
+pub fn seq_filter(predicate: SeqFn, seq: Value) -> Value {
+    Value::Seq(filter(predicate, seq_arg(seq, "seq_filter")))
+}
+
+// The items step(state) produces: Some((item, next_state)) continues the
+// sequence, None ends it
+pub fn seq_unfold(step: SeqFn, state: Value) -> Value {
+    Value::Seq(unfold(step, state))
+}
+
+// At most the first n items; the items after them are never computed
+// UNARY CONTRACT: Accepts Value::Tuple containing [seq, n]
+pub fn seq_take(args: Value) -> Value {
+    match args {
+        Value::Tuple(mut elems) if elems.len() == 2 => {
+            let count = elems.pop().unwrap_or(Value::Unit).as_int();
+            let seq = seq_arg(elems.pop().unwrap_or(Value::Unit), "seq_take");
+            Value::Seq(take(seq, count))
+        }
+        _ => axis_runtime_error("seq_take expects (seq, n)", None),
+    }
+}
+
+// Every item, computed now, as a list
+pub fn seq_to_list(seq: Value) -> Value {
+    let mut items = Vec::new();
+    let mut seq = seq_arg(seq, "seq_to_list");
+    while let Some((item, rest)) = seq.next() {
+        items.push(item);
+        seq = rest;
+    }
+    Value::List(items)
+}
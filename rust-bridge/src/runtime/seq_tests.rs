@@ -0,0 +1,124 @@
+// Tests for lazy sequences (seq.rs) and their closures in emit_rust
+
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::runtime::concurrency::{channel_new, channel_send};
+use crate::runtime::emit_rust::emit_rust_from_core;
+use crate::runtime::seq::{seq_filter, seq_from_list, seq_map, seq_take, seq_to_list, seq_unfold, SeqFn};
+use crate::runtime::value::{init_runtime, intern_tag, Value};
+use std::cell::Cell;
+use std::rc::Rc;
+use crate::runtime::panic_on_runtime_errors;
+
+fn ints(values: &[i64]) -> Value {
+    Value::List(values.iter().map(|n| Value::Int(*n)).collect())
+}
+
+fn take(seq: Value, n: i64) -> Value {
+    seq_take(Value::Tuple(vec![seq, Value::Int(n)]))
+}
+
+// 0, 1, 2, ... counting the steps run in `steps`
+fn naturals(steps: Rc<Cell<i64>>) -> Value {
+    let step = SeqFn::new(move |n: Value| {
+        steps.set(steps.get() + 1);
+        let next = Value::Int(n.as_int() + 1);
+        Value::Ctor { tag: intern_tag("Some"), fields: vec![Value::Tuple(vec![n, next])] }
+    });
+    seq_unfold(step, Value::Int(0))
+}
+
+#[test]
+fn test_items_are_computed_when_read_and_once() {
+    init_runtime();
+    let steps = Rc::new(Cell::new(0));
+    let first = take(naturals(steps.clone()), 3);
+    assert_eq!(steps.get(), 0);
+
+    assert_eq!(seq_to_list(first.clone()), ints(&[0, 1, 2]));
+    // take stops without computing the fourth item
+    assert_eq!(steps.get(), 3);
+    assert_eq!(seq_to_list(first), ints(&[0, 1, 2]));
+    assert_eq!(steps.get(), 3);
+}
+
+#[test]
+fn test_map_and_filter_run_their_functions_lazily() {
+    init_runtime();
+    let calls = Rc::new(Cell::new(0));
+    let counted = calls.clone();
+    let double = SeqFn::new(move |n: Value| {
+        counted.set(counted.get() + 1);
+        Value::Int(n.as_int() * 2)
+    });
+    let step = 3;
+    let multiple = SeqFn::new(move |n: Value| Value::Bool(n.as_int() % step == 0));
+
+    let evens = seq_map(double, seq_from_list(ints(&[1, 2, 3, 4, 5, 6, 7])));
+    let result = take(seq_filter(multiple, evens), 2);
+    assert_eq!(calls.get(), 0);
+    assert_eq!(seq_to_list(result), ints(&[6, 12]));
+    assert_eq!(calls.get(), 6);
+}
+
+#[test]
+fn test_finite_sequences_end() {
+    init_runtime();
+    let countdown = SeqFn::new(|n: Value| match n.as_int() {
+        0 => Value::Ctor { tag: intern_tag("None"), fields: Vec::new() },
+        k => Value::Ctor { tag: intern_tag("Some"), fields: vec![Value::Tuple(vec![Value::Int(k), Value::Int(k - 1)])] },
+    });
+    assert_eq!(seq_to_list(take(seq_unfold(countdown, Value::Int(3)), 10)), ints(&[3, 2, 1]));
+    assert_eq!(seq_to_list(seq_from_list(ints(&[]))), ints(&[]));
+}
+
+#[test]
+fn test_long_sequences_are_dropped_without_recursion() {
+    init_runtime();
+    let items = seq_from_list(Value::List(vec![Value::Unit; 1_000_000]));
+    match seq_to_list(items.clone()) {
+        Value::List(all) => assert_eq!(all.len(), 1_000_000),
+        other => panic!("expected a list, got {:?}", other),
+    }
+    drop(items);
+}
+
+#[test]
+#[should_panic(expected = "seq_unfold: the step function must return Some((item, state)) or None")]
+fn test_unfold_rejects_other_results() {
+    panic_on_runtime_errors();
+    init_runtime();
+    seq_to_list(seq_unfold(SeqFn::new(|n: Value| n), Value::Int(1)));
+}
+
+#[test]
+#[should_panic(expected = "channel_send: a Seq cannot be sent to another thread")]
+fn test_sequences_cannot_be_sent() {
+    panic_on_runtime_errors();
+    init_runtime();
+    let seq = seq_from_list(ints(&[1]));
+    channel_send(Value::Tuple(vec![channel_new(Value::Unit), Value::Tuple(vec![seq])]));
+}
+
+#[test]
+fn test_lambdas_become_closures_owning_their_captures() {
+    // axis_entry = λk. axis_seq_to_list(axis_seq_filter(λx. __eq__(x, k), axis_seq_from_list(k)))
+    let var = |name: &str| CoreTerm::Var(name.into(), None);
+    let app = |f: CoreTerm, args: Vec<CoreTerm>| {
+        args.into_iter().fold(f, |f, arg| CoreTerm::App(Rc::new(f), Rc::new(arg), None))
+    };
+    let predicate = CoreTerm::Lam("x".into(), Rc::new(app(var("__eq__"), vec![var("x"), var("k")])), None);
+    let items = app(var("axis_seq_from_list"), vec![var("k")]);
+    let body = app(var("axis_seq_to_list"), vec![app(var("axis_seq_filter"), vec![predicate, items])]);
+    let program = CoreTerm::Let(
+        "axis_entry".into(),
+        Rc::new(CoreTerm::Lam("k".into(), Rc::new(body), None)),
+        Rc::new(CoreTerm::UnitLit(None)),
+        None,
+    );
+
+    let generated = emit_rust_from_core(ArenaProgram::from_term(&program).root_term(), "test.ax", "axis_entry");
+    assert!(generated.contains("shim::seq_filter("), "{}", generated);
+    assert!(generated.contains("let k = k.clone();"), "{}", generated);
+    assert!(generated.contains("SeqFn::new(move |x: Value| -> Value"), "{}", generated);
+}
@@ -13,27 +13,116 @@
 //! 5. **Load-bearing**: Semantic collapse here is considered a bug
 
 use crate::runtime::value::{Value, get_str, intern_str};
+#[cfg(feature = "io")]
 use crate::runtime::io;
+use crate::runtime::error::{axis_assertion_failure, axis_runtime_error, RuntimeSpan};
+use crate::runtime::inspect::{first_difference, pretty_text, PRETTY_WIDTH};
 
 // ============================================================================
 // IO Operations
 // ============================================================================
 
 /// Print a value to stdout
+#[cfg(feature = "io")]
 pub fn io_print(val: Value) -> Value {
     io::io_print(val)
 }
 
+/// Print a value to stdout without flushing it (see io::STDOUT_BUFFER)
+#[cfg(feature = "io")]
+pub fn io_print_buffered(val: Value) -> Value {
+    io::io_print_buffered(val)
+}
+
+/// Print a value and a newline to stdout without flushing it
+#[cfg(feature = "io")]
+pub fn io_println(val: Value) -> Value {
+    io::io_println(val)
+}
+
+/// Write out buffered stdout output
+#[cfg(feature = "io")]
+pub fn io_flush(unit: Value) -> Value {
+    io::io_flush(unit)
+}
+
 /// Print a value to stderr
+#[cfg(feature = "io")]
 pub fn io_eprint(val: Value) -> Value {
     io::io_eprint(val)
 }
 
 /// Read a line from stdin
+#[cfg(feature = "io")]
 pub fn io_read() -> Value {
     io::io_read()
 }
 
+// ============================================================================
+// Assertions
+// ============================================================================
+//
+// Failures are reported with both values and the call's span (when the
+// bundle carries one) and recorded rather than aborting; see
+// error::axis_assertion_failure. Each returns Unit.
+
+/// Check two values for structural equality: (left, right)
+pub fn assert_eq(args: Value, span: Option<RuntimeSpan>) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() == 2 => {
+            if let Some(details) = assert_eq_details(&elems[0], &elems[1]) {
+                axis_assertion_failure("assertion failed: left == right", &details, span);
+            }
+            Value::Unit
+        }
+        _ => axis_runtime_error("assert_eq expects (left, right)", span),
+    }
+}
+
+// What a failed assert_eq reports: both values and, if they are
+// aggregates, where they first differ; None if they are equal
+pub(crate) fn assert_eq_details(left: &Value, right: &Value) -> Option<Vec<(&'static str, String)>> {
+    let (path, report) = first_difference(left, right)?;
+    let mut details = vec![("left", assertion_text(left)), ("right", assertion_text(right))];
+    let aggregate = |value: &Value| matches!(value, Value::Tuple(_) | Value::List(_) | Value::Ctor { .. });
+    if aggregate(left) && aggregate(right) {
+        details.push(("diff", format!("value{}: {}", path, report)));
+    }
+    Some(details)
+}
+
+// A value in a failure report: on one line, or indented if that is too long
+fn assertion_text(value: &Value) -> String {
+    let text = value.to_string();
+    if text.len() <= PRETTY_WIDTH {
+        text
+    } else {
+        pretty_text(value)
+    }
+}
+
+/// Check that a value is `true`
+pub fn assert_true(val: Value, span: Option<RuntimeSpan>) -> Value {
+    if val != Value::Bool(true) {
+        axis_assertion_failure(
+            "assertion failed: expected true",
+            &[("got", val.to_string())],
+            span,
+        );
+    }
+    Value::Unit
+}
+
+/// Unconditionally fail the current test with a message
+pub fn fail(msg: Value, span: Option<RuntimeSpan>) -> Value {
+    let text = match msg {
+        Value::Str(s) => s.text().to_string(),
+        other => other.to_string(),
+    };
+    axis_assertion_failure(&format!("failed: {}", text), &[], span);
+    Value::Unit
+}
+
 // ============================================================================
 // String Operations
 // ============================================================================
@@ -44,28 +133,26 @@ pub fn io_read() -> Value {
 /// Caller must ensure index is valid
 /// UNARY CONTRACT: Accepts Value::Tuple containing [string, index]
 pub fn str_char(args: Value) -> Value {
-    let (string_handle, idx) = match args {
+    let (text, idx) = match args {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
-            let handle = match elems[0] {
-                Value::Str(h) => h,
-                _ => panic!("str_char: first argument must be a string"),
+            let text = match &elems[0] {
+                Value::Str(s) => s.text(),
+                _ => axis_runtime_error("str_char: first argument must be a string", None),
             };
             let index = match elems[1] {
                 Value::Int(n) => n as usize,
-                _ => panic!("str_char: second argument must be an integer"),
+                _ => axis_runtime_error("str_char: second argument must be an integer", None),
             };
-            (handle, index)
+            (text, index)
         },
-        _ => panic!("str_char: expects tuple of (string, index)"),
+        _ => axis_runtime_error("str_char: expects tuple of (string, index)", None),
     };
     
-    let string_content = get_str(string_handle);
-    
     // UNCHECKED: Direct indexing without bounds validation
-    let chars: Vec<char> = string_content.chars().collect();
+    let chars: Vec<char> = text.chars().collect();
     let ch = chars[idx]; // Will panic on out-of-bounds - this is the unchecked behavior
     
-    Value::Str(intern_str(&ch.to_string()))
+    Value::string(&ch.to_string())
 }
 
 /// Get a character from a string with bounds checking
@@ -73,11 +160,11 @@ pub fn str_char(args: Value) -> Value {
 /// Returns None (as a Value) if index is out of bounds
 /// UNARY CONTRACT: Accepts Value::Tuple containing [string, index]
 pub fn str_char_at(args: Value) -> Value {
-    let (string_handle, idx) = match args {
+    let (text, idx) = match args {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
-            let handle = match elems[0] {
-                Value::Str(h) => h,
-                _ => panic!("str_char_at: first argument must be a string"),
+            let text = match &elems[0] {
+                Value::Str(s) => s.text(),
+                _ => axis_runtime_error("str_char_at: first argument must be a string", None),
             };
             let index = match elems[1] {
                 Value::Int(n) => {
@@ -86,49 +173,43 @@ pub fn str_char_at(args: Value) -> Value {
                     }
                     n as usize
                 },
-                _ => panic!("str_char_at: second argument must be an integer"),
+                _ => axis_runtime_error("str_char_at: second argument must be an integer", None),
             };
-            (handle, index)
+            (text, index)
         },
-        _ => panic!("str_char_at: expects tuple of (string, index)"),
+        _ => axis_runtime_error("str_char_at: expects tuple of (string, index)", None),
     };
     
-    let string_content = get_str(string_handle);
-    
     // CHECKED: Bounds validation before access
-    let chars: Vec<char> = string_content.chars().collect();
+    let chars: Vec<char> = text.chars().collect();
     if idx >= chars.len() {
         return option_none();
     }
     
     let ch = chars[idx];
-    option_some(Value::Str(intern_str(&ch.to_string())))
+    option_some(Value::string(&ch.to_string()))
 }
 
 /// Get character code from a string (returns integer, defensive against emitter bugs)
 pub fn str_char_code(args: Value) -> Value {
-    let (string_handle, idx) = match args {
+    let (text, idx) = match args {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
-            let handle = match elems[0] {
-                Value::Str(h) => h,
-                _ => panic!("str_char_code: first argument must be a string"),
+            let text = match &elems[0] {
+                Value::Str(s) => s.text(),
+                _ => axis_runtime_error("str_char_code: first argument must be a string", None),
             };
             let index = match elems[1] {
                 Value::Int(n) => n as usize,
                 // Handle string literals that should be integers (emitter bug defensive)
-                Value::Str(id) => {
-                    let s = get_str(id);
-                    s.parse::<i32>().unwrap_or(0) as usize
-                }
-                _ => panic!("str_char_code: second argument must be an integer or numeric string"),
+                Value::Str(ref s) => s.text().parse::<i32>().unwrap_or(0) as usize,
+                _ => axis_runtime_error("str_char_code: second argument must be an integer or numeric string", None),
             };
-            (handle, index)
+            (text, index)
         },
-        _ => panic!("str_char_code: expects tuple of (string, index)"),
+        _ => axis_runtime_error("str_char_code: expects tuple of (string, index)", None),
     };
     
-    let string_content = get_str(string_handle);
-    let chars: Vec<char> = string_content.chars().collect();
+    let chars: Vec<char> = text.chars().collect();
     
     if idx >= chars.len() {
         return Value::Int(0); // Out of bounds
@@ -140,13 +221,12 @@ pub fn str_char_code(args: Value) -> Value {
 
 /// Get the length of a string
 pub fn str_len(s: Value) -> Value {
-    let string_handle = match s {
-        Value::Str(handle) => handle,
-        _ => panic!("str_len: argument must be a string"),
+    let text = match &s {
+        Value::Str(s) => s.text(),
+        _ => axis_runtime_error("str_len: argument must be a string", None),
     };
     
-    let string_content = get_str(string_handle);
-    Value::Int(string_content.chars().count() as i64)
+    Value::Int(text.chars().count() as i64)
 }
 
 /// Convert a character (single-char string) to a string
@@ -157,12 +237,12 @@ pub fn char_to_str(c: Value) -> Value {
         Value::Int(n) if n >= 0 && n <= 0x10FFFF => {
             // Treat integer as Unicode code point
             if let Some(ch) = char::from_u32(n as u32) {
-                Value::Str(intern_str(&ch.to_string()))
+                Value::string(&ch.to_string())
             } else {
-                Value::Str(intern_str("�")) // Replacement character
+                Value::string("�") // Replacement character
             }
         }
-        _ => Value::Str(intern_str("")),
+        _ => Value::string(""),
     }
 }
 
@@ -170,23 +250,21 @@ pub fn char_to_str(c: Value) -> Value {
 pub fn str_concat(args: Value) -> Value {
     match args {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
-            let handle_a = match &elems[0] {
-                Value::Str(handle) => *handle,
-                _ => panic!("str_concat: first argument must be a string"),
+            let str_a = match &elems[0] {
+                Value::Str(s) => s.text(),
+                _ => axis_runtime_error("str_concat: first argument must be a string", None),
             };
             
-            let handle_b = match &elems[1] {
-                Value::Str(handle) => *handle,
-                _ => panic!("str_concat: second argument must be a string"),
+            let str_b = match &elems[1] {
+                Value::Str(s) => s.text(),
+                _ => axis_runtime_error("str_concat: second argument must be a string", None),
             };
             
-            let str_a = get_str(handle_a);
-            let str_b = get_str(handle_b);
             let result = format!("{}{}", str_a, str_b);
             
-            Value::Str(intern_str(&result))
+            Value::string(&result)
         },
-        _ => panic!("str_concat: expected tuple with 2 elements"),
+        _ => axis_runtime_error("str_concat: expected tuple with 2 elements", None),
     }
 }
 
@@ -201,12 +279,12 @@ pub fn str_concat(args: Value) -> Value {
 pub fn list_get(list: &Value, index: &Value) -> Value {
     let elements = match list {
         Value::List(elems) => elems,
-        _ => panic!("list_get: first argument must be a list"),
+        _ => axis_runtime_error("list_get: first argument must be a list", None),
     };
     
     let idx = match index {
         Value::Int(n) => *n as usize,
-        _ => panic!("list_get: second argument must be an integer"),
+        _ => axis_runtime_error("list_get: second argument must be an integer", None),
     };
     
     // UNCHECKED: Direct indexing without bounds validation
@@ -219,7 +297,7 @@ pub fn list_get(list: &Value, index: &Value) -> Value {
 pub fn list_get_at(list: &Value, index: &Value) -> Value {
     let elements = match list {
         Value::List(elems) => elems,
-        _ => panic!("list_get_at: first argument must be a list"),
+        _ => axis_runtime_error("list_get_at: first argument must be a list", None),
     };
     
     let idx = match index {
@@ -229,7 +307,7 @@ pub fn list_get_at(list: &Value, index: &Value) -> Value {
             }
             *n as usize
         },
-        _ => panic!("list_get_at: second argument must be an integer"),
+        _ => axis_runtime_error("list_get_at: second argument must be an integer", None),
     };
     
     // CHECKED: Bounds validation before access
@@ -244,7 +322,7 @@ pub fn list_get_at(list: &Value, index: &Value) -> Value {
 pub fn list_len(list: &Value) -> Value {
     let elements = match list {
         Value::List(elems) => elems,
-        _ => panic!("list_len: argument must be a list"),
+        _ => axis_runtime_error("list_len: argument must be a list", None),
     };
     
     Value::Int(elements.len() as i64)
@@ -256,7 +334,7 @@ pub fn list_append(args: Value) -> Value {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
             let elements = match &elems[0] {
                 Value::List(list_elems) => list_elems,
-                _ => panic!("list_append: first argument must be a list"),
+                _ => axis_runtime_error("list_append: first argument must be a list", None),
             };
             
             let mut new_elements = elements.clone();
@@ -264,7 +342,7 @@ pub fn list_append(args: Value) -> Value {
             
             Value::List(new_elements)
         },
-        _ => panic!("list_append: expected tuple with 2 elements"),
+        _ => axis_runtime_error("list_append: expected tuple with 2 elements", None),
     }
 }
 
@@ -276,12 +354,12 @@ pub fn list_append(args: Value) -> Value {
 pub fn int_add(a: &Value, b: &Value) -> Value {
     let x = match a {
         Value::Int(n) => *n,
-        _ => panic!("int_add: first argument must be an integer"),
+        _ => axis_runtime_error("int_add: first argument must be an integer", None),
     };
     
     let y = match b {
         Value::Int(n) => *n,
-        _ => panic!("int_add: second argument must be an integer"),
+        _ => axis_runtime_error("int_add: second argument must be an integer", None),
     };
     
     Value::Int(x + y)
@@ -291,12 +369,12 @@ pub fn int_add(a: &Value, b: &Value) -> Value {
 pub fn int_sub(a: &Value, b: &Value) -> Value {
     let x = match a {
         Value::Int(n) => *n,
-        _ => panic!("int_sub: first argument must be an integer"),
+        _ => axis_runtime_error("int_sub: first argument must be an integer", None),
     };
     
     let y = match b {
         Value::Int(n) => *n,
-        _ => panic!("int_sub: second argument must be an integer"),
+        _ => axis_runtime_error("int_sub: second argument must be an integer", None),
     };
     
     Value::Int(x - y)
@@ -306,12 +384,12 @@ pub fn int_sub(a: &Value, b: &Value) -> Value {
 pub fn int_mul(a: &Value, b: &Value) -> Value {
     let x = match a {
         Value::Int(n) => *n,
-        _ => panic!("int_mul: first argument must be an integer"),
+        _ => axis_runtime_error("int_mul: first argument must be an integer", None),
     };
     
     let y = match b {
         Value::Int(n) => *n,
-        _ => panic!("int_mul: second argument must be an integer"),
+        _ => axis_runtime_error("int_mul: second argument must be an integer", None),
     };
     
     Value::Int(x * y)
@@ -323,12 +401,12 @@ pub fn int_mul(a: &Value, b: &Value) -> Value {
 pub fn int_div_checked(a: &Value, b: &Value) -> Value {
     let x = match a {
         Value::Int(n) => *n,
-        _ => panic!("int_div_checked: first argument must be an integer"),
+        _ => axis_runtime_error("int_div_checked: first argument must be an integer", None),
     };
     
     let y = match b {
         Value::Int(n) => *n,
-        _ => panic!("int_div_checked: second argument must be an integer"),
+        _ => axis_runtime_error("int_div_checked: second argument must be an integer", None),
     };
     
     if y == 0 {
@@ -351,12 +429,12 @@ pub fn value_eq(a: &Value, b: &Value) -> Value {
 pub fn int_lt(a: &Value, b: &Value) -> Value {
     let x = match a {
         Value::Int(n) => *n,
-        _ => panic!("int_lt: first argument must be an integer"),
+        _ => axis_runtime_error("int_lt: first argument must be an integer", None),
     };
     
     let y = match b {
         Value::Int(n) => *n,
-        _ => panic!("int_lt: second argument must be an integer"),
+        _ => axis_runtime_error("int_lt: second argument must be an integer", None),
     };
     
     Value::Bool(x < y)
@@ -402,16 +480,16 @@ pub fn option_is_some(opt: &Value) -> Value {
     }
 }
 
-/// Unwrap a Some value, panic if None
+/// Unwrap a Some value, runtime error if None
 pub fn option_unwrap(opt: &Value) -> Value {
     match opt {
         Value::Ctor { tag, fields } if get_str(*tag) == "Some" && fields.len() == 1 => {
             fields[0].clone()
         }
         Value::Ctor { tag, fields } if get_str(*tag) == "None" && fields.is_empty() => {
-            panic!("Called option_unwrap on None value")
+            axis_runtime_error("Called option_unwrap on None value", None)
         }
-        _ => panic!("option_unwrap called on non-option value")
+        _ => axis_runtime_error("option_unwrap called on non-option value", None)
     }
 }
 
@@ -423,12 +501,12 @@ pub fn option_unwrap(opt: &Value) -> Value {
 pub fn bool_and(a: &Value, b: &Value) -> Value {
     let x = match a {
         Value::Bool(b) => *b,
-        _ => panic!("bool_and: first argument must be a boolean"),
+        _ => axis_runtime_error("bool_and: first argument must be a boolean", None),
     };
     
     let y = match b {
         Value::Bool(b) => *b,
-        _ => panic!("bool_and: second argument must be a boolean"),
+        _ => axis_runtime_error("bool_and: second argument must be a boolean", None),
     };
     
     Value::Bool(x && y)
@@ -438,12 +516,12 @@ pub fn bool_and(a: &Value, b: &Value) -> Value {
 pub fn bool_or(a: &Value, b: &Value) -> Value {
     let x = match a {
         Value::Bool(b) => *b,
-        _ => panic!("bool_or: first argument must be a boolean"),
+        _ => axis_runtime_error("bool_or: first argument must be a boolean", None),
     };
     
     let y = match b {
         Value::Bool(b) => *b,
-        _ => panic!("bool_or: second argument must be a boolean"),
+        _ => axis_runtime_error("bool_or: second argument must be a boolean", None),
     };
     
     Value::Bool(x || y)
@@ -453,7 +531,7 @@ pub fn bool_or(a: &Value, b: &Value) -> Value {
 pub fn bool_not(a: &Value) -> Value {
     let x = match a {
         Value::Bool(b) => *b,
-        _ => panic!("bool_not: argument must be a boolean"),
+        _ => axis_runtime_error("bool_not: argument must be a boolean", None),
     };
     
     Value::Bool(!x)
@@ -478,8 +556,8 @@ pub fn axis_io_make_error(msg: Value) -> Value {
 /// Supports: {"key": "value", "key2": "value2"}
 /// Returns: List[(Str, Str)]
 pub fn axis_json_parse(json_str: Value) -> Value {
-    let json_text = match json_str {
-        Value::Str(handle) => get_str(handle),
+    let json_text = match &json_str {
+        Value::Str(s) => s.text(),
         _ => return Value::List(vec![]),
     };
     
@@ -502,8 +580,8 @@ pub fn axis_json_parse(json_str: Value) -> Value {
         let key = parts[0].trim().trim_matches('"');
         let value = parts[1].trim().trim_matches('"');
         
-        let key_val = Value::Str(intern_str(key));
-        let value_val = Value::Str(intern_str(value));
+        let key_val = Value::string(key);
+        let value_val = Value::string(value);
         
         pairs.push(Value::Tuple(vec![key_val, value_val]));
     }
@@ -519,7 +597,9 @@ pub use crate::runtime::value::{
     __add__, __sub__, __mul__, __div__, __mod__,
     __eq__, __lt__, __lte__, __gt__, __gte__,
     __and__, __or__, __not__, __concat__,
-    int_to_str, str_to_int, str_slice
+    int_to_str, str_to_int, str_slice,
+    bytes_len, bytes_get, bytes_slice,
+    sb_new, sb_push, sb_push_int, sb_build
 };
 
 pub use crate::runtime::tuple::{
@@ -530,8 +610,61 @@ pub use crate::runtime::list::{
     list_nil, list_cons, list_reverse, list_concat, list_contains_str, list_index_of_str
 };
 
+// Capability primitives, present when their feature is (see capability.rs)
+#[cfg(feature = "proc")]
+pub use crate::runtime::list::{
+    args_flag, args_has, args_positional
+};
+
+#[cfg(feature = "fs")]
 pub use crate::runtime::io::{
-    fs_read_text, fs_write_text
+    fs_read_text, fs_write_text,
+    fs_read_bytes, fs_write_bytes
+};
+
+#[cfg(feature = "fs")]
+pub use crate::runtime::files::{
+    fs_list_dir, fs_create_dir_all, fs_remove_file, fs_copy,
+    path_join, path_stem, path_parent
+};
+
+#[cfg(feature = "io")]
+pub use crate::runtime::io::io_read_bytes;
+
+pub use crate::runtime::json::{
+    json_parse, json_encode
+};
+
+pub use crate::runtime::inspect::{value_pretty, value_diff};
+
+pub use crate::runtime::memo::{memo_get, memo_put};
+
+pub use crate::runtime::overflow::{checked_add, checked_sub, checked_mul, checked_div};
+
+pub use crate::runtime::order::{value_cmp, list_sort_by};
+
+pub use crate::runtime::strings::{
+    str_split, str_trim, str_contains, str_starts_with, str_ends_with, str_replace, str_to_lower, str_to_upper,
+    str_join
+};
+
+pub use crate::runtime::chars::{
+    char_is_alpha, char_is_digit, char_is_alnum, char_is_whitespace, char_is_ident_start, char_is_ident_char,
+    char_to_digit, char_to_lower, char_to_upper, char_from_str
+};
+
+pub use crate::runtime::seq::{
+    seq_from_list, seq_map, seq_filter, seq_unfold, seq_take, seq_to_list
+};
+
+pub use crate::runtime::concurrency::{
+    spawn, join, channel_new, channel_send, channel_recv
+};
+
+#[cfg(feature = "proc")]
+pub use crate::runtime::process::{
+    env_get, env_set, proc_run, proc_exit
 };
 
-pub use crate::runtime::core_emit::axis_emit_core_bundle_to_file;
\ No newline at end of file
+#[cfg(feature = "fs")]
+pub use crate::runtime::core_emit::{axis_emit_core_bundle_to_file, axis_load_core_bundle_from_file};
\ No newline at end of file
@@ -4,7 +4,8 @@
 //! and that the shim functions behave correctly in isolation.
 
 use crate::runtime::shim::*;
-use crate::runtime::value::{Value, intern_str, init_runtime};
+use crate::runtime::value::{Value, init_runtime, str_lit};
+use crate::runtime::panic_on_runtime_errors;
 
 fn setup() {
     init_runtime();
@@ -22,14 +23,14 @@ mod string_tests {
     fn test_str_char_vs_str_char_at_semantic_distinction() {
         setup();
         
-        let test_str = Value::Str(intern_str("hello"));
+        let test_str = str_lit("hello");
         let valid_index = Value::Int(1);
         let invalid_index = Value::Int(10);
         
         // str_char should return the character at valid index
         let result = str_char(Value::Tuple(vec![test_str.clone(), valid_index.clone()]));
-        if let Value::Str(handle) = result {
-            let ch = crate::runtime::value::get_str(handle);
+        if let Value::Str(s) = result {
+            let ch = s.text().to_string();
             assert_eq!(ch, "e");
         } else {
             panic!("str_char should return a string");
@@ -39,8 +40,8 @@ mod string_tests {
         let result = str_char_at(Value::Tuple(vec![test_str.clone(), valid_index.clone()]));
         assert!(option_is_some(&result).as_bool());
         let inner = option_unwrap(&result);
-        if let Value::Str(handle) = inner {
-            let ch = crate::runtime::value::get_str(handle);
+        if let Value::Str(s) = inner {
+            let ch = s.text().to_string();
             assert_eq!(ch, "e");
         }
         
@@ -52,9 +53,10 @@ mod string_tests {
     #[test]
     #[should_panic]
     fn test_str_char_panics_on_out_of_bounds() {
+        panic_on_runtime_errors();
         setup();
         
-        let test_str = Value::Str(intern_str("hello"));
+        let test_str = str_lit("hello");
         let invalid_index = Value::Int(10);
         
         // This should panic - demonstrating unchecked behavior
@@ -65,7 +67,7 @@ mod string_tests {
     fn test_str_char_at_handles_negative_index() {
         setup();
         
-        let test_str = Value::Str(intern_str("hello"));
+        let test_str = str_lit("hello");
         let negative_index = Value::Int(-1);
         
         let result = str_char_at(Value::Tuple(vec![test_str.clone(), negative_index.clone()]));
@@ -76,9 +78,9 @@ mod string_tests {
     fn test_str_len() {
         setup();
         
-        let empty_str = Value::Str(intern_str(""));
-        let test_str = Value::Str(intern_str("hello"));
-        let unicode_str = Value::Str(intern_str("héllo")); // accent should count as 1 char
+        let empty_str = str_lit("");
+        let test_str = str_lit("hello");
+        let unicode_str = str_lit("héllo"); // accent should count as 1 char
         
         assert_eq!(str_len(empty_str).as_int(), 0);
         assert_eq!(str_len(test_str).as_int(), 5);
@@ -89,23 +91,40 @@ mod string_tests {
     fn test_str_concat() {
         setup();
         
-        let str1 = Value::Str(intern_str("hello"));
-        let str2 = Value::Str(intern_str(" world"));
-        let empty = Value::Str(intern_str(""));
+        let str1 = str_lit("hello");
+        let str2 = str_lit(" world");
+        let empty = str_lit("");
         
         let result = str_concat(Value::Tuple(vec![str1.clone(), str2.clone()]));
-        if let Value::Str(handle) = result {
-            let concat_result = crate::runtime::value::get_str(handle);
+        if let Value::Str(s) = result {
+            let concat_result = s.text().to_string();
             assert_eq!(concat_result, "hello world");
         }
         
         // Test concatenation with empty string
         let result = str_concat(Value::Tuple(vec![str1.clone(), empty.clone()]));
-        if let Value::Str(handle) = result {
-            let concat_result = crate::runtime::value::get_str(handle);
+        if let Value::Str(s) = result {
+            let concat_result = s.text().to_string();
             assert_eq!(concat_result, "hello");
         }
     }
+
+    #[test]
+    fn test_computed_strings_are_not_interned() {
+        setup();
+        use crate::runtime::value::StrValue;
+
+        let joined = str_concat(Value::Tuple(vec![str_lit("ab"), str_lit("cd")]));
+        assert!(matches!(joined, Value::Str(StrValue::Owned(_))));
+        assert!(matches!(int_to_str(Value::Int(7)), Value::Str(StrValue::Owned(_))));
+        assert!(matches!(str_char(Value::Tuple(vec![str_lit("ab"), Value::Int(0)])), Value::Str(StrValue::Owned(_))));
+
+        // Equality is by text, whichever way the strings are held
+        assert_eq!(joined, str_lit("abcd"));
+        assert_eq!(str_lit("abcd"), joined);
+        assert_ne!(joined, str_lit("abc"));
+        assert!(__eq__(Value::Tuple(vec![joined, str_lit("abcd")])).as_bool());
+    }
 }
 
 // ============================================================================
@@ -146,6 +165,7 @@ mod list_tests {
     #[test]
     #[should_panic]
     fn test_list_get_panics_on_out_of_bounds() {
+        panic_on_runtime_errors();
         setup();
         
         let test_list = Value::List(vec![Value::Int(10)]);
@@ -202,6 +222,66 @@ mod list_tests {
     }
 }
 
+// ============================================================================
+// CLI Argument Helper Tests
+// ============================================================================
+
+#[cfg(test)]
+mod args_tests {
+    use super::*;
+    use crate::runtime::value::{get_tag_name, intern_tag};
+
+    /// Build argv the way the bridge wrapper does: a Nil/Cons chain of Str
+    fn argv(items: &[&str]) -> Value {
+        let mut list = Value::Ctor { tag: intern_tag("Nil"), fields: vec![] };
+        for item in items.iter().rev() {
+            list = Value::Ctor { tag: intern_tag("Cons"), fields: vec![str_lit(item), list] };
+        }
+        list
+    }
+
+    fn flag(args: &Value, name: &str) -> Option<Value> {
+        match args_flag(Value::Tuple(vec![args.clone(), str_lit(name)])) {
+            Value::Ctor { tag, mut fields } if get_tag_name(tag) == "Some" => Some(fields.remove(0)),
+            Value::Ctor { tag, .. } if get_tag_name(tag) == "None" => None,
+            other => panic!("args_flag should return an option ctor, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_args_flag_forms() {
+        setup();
+        
+        let args = argv(&["input.ax", "--out", "bin", "--mode=fast", "--debug"]);
+        assert_eq!(flag(&args, "--out"), Some(str_lit("bin")));
+        assert_eq!(flag(&args, "--mode"), Some(str_lit("fast")));
+        assert_eq!(flag(&args, "--debug"), None);
+        assert_eq!(flag(&args, "--missing"), None);
+    }
+
+    #[test]
+    fn test_args_has() {
+        setup();
+        
+        let args = argv(&["--debug", "--mode=fast", "--", "--late"]);
+        let has = |name: &str| args_has(Value::Tuple(vec![args.clone(), str_lit(name)])).as_bool();
+        assert!(has("--debug"));
+        assert!(has("--mode"));
+        assert!(!has("--late"));
+    }
+
+    #[test]
+    fn test_args_positional_preserves_representation() {
+        setup();
+        
+        let args = argv(&["a.ax", "--out", "bin", "b.ax", "-", "--", "--c"]);
+        assert_eq!(args_positional(args), argv(&["a.ax", "b.ax", "-", "--c"]));
+        
+        let plain = Value::List(vec![str_lit("x"), str_lit("--v=1")]);
+        assert_eq!(args_positional(plain), Value::List(vec![str_lit("x")]));
+    }
+}
+
 // ============================================================================
 // Arithmetic Operation Tests
 // ============================================================================
@@ -319,6 +399,7 @@ mod option_tests {
     #[test]
     #[should_panic(expected = "Called option_unwrap on None value")]
     fn test_option_unwrap_none_panics() {
+        panic_on_runtime_errors();
         setup();
         
         let none_val = option_none();
@@ -326,6 +407,75 @@ mod option_tests {
     }
 }
 
+// ============================================================================
+// Constructor Tag Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tag_tests {
+    use crate::runtime::value::{get_tag_name, intern_tag, register_tags, WELL_KNOWN_TAGS};
+
+    #[test]
+    fn test_runtime_constructors_have_fixed_tags() {
+        for (tag, name) in WELL_KNOWN_TAGS.iter().enumerate() {
+            assert_eq!(intern_tag(name), tag as u32);
+            assert_eq!(get_tag_name(tag as u32), *name);
+        }
+        // A generated table always starts with them
+        assert!(register_tags(WELL_KNOWN_TAGS).is_ok());
+    }
+
+    #[test]
+    fn test_qualified_constructors_show_their_short_name() {
+        use crate::runtime::value::{tag_display_name, Value};
+
+        let circle = intern_tag("geo.Shape.Circle2401");
+        assert_eq!(tag_display_name(circle), "Circle2401");
+        let value = Value::Ctor { tag: circle, fields: vec![Value::Int(2)] };
+        assert_eq!(value.to_string(), "Circle2401(2)");
+        // Two types with a constructor of the same name: shown whole
+        let ok = intern_tag("http.Status.Ok");
+        assert_eq!(tag_display_name(ok), "http.Status.Ok", "the runtime has its own Ok");
+        assert_eq!(tag_display_name(intern_tag("Ok")), "Ok");
+        let missing = intern_tag("db.Reply.Missing2401");
+        let other = intern_tag("http.Status.Missing2401");
+        assert_eq!(tag_display_name(missing), "db.Reply.Missing2401");
+        assert_eq!(tag_display_name(other), "http.Status.Missing2401");
+    }
+
+    #[test]
+    fn test_misaligned_tag_table_is_rejected() {
+        let error = register_tags(&["Cons"]).unwrap_err();
+        assert_eq!(error, "constructor tag 0 is `Cons` in the program but `Nil` in the runtime");
+    }
+
+    #[test]
+    fn test_threads_agree_on_interned_names() {
+        use crate::runtime::value::{get_str, intern_str, str_lit, Value};
+
+        let names: Vec<String> = (0..50).map(|i| format!("ThreadTestName{}", i)).collect();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let names = names.clone();
+                std::thread::spawn(move || {
+                    let handles: Vec<(u32, u32)> = names.iter().map(|name| (intern_str(name), intern_tag(name))).collect();
+                    // Equal to the literal another thread interned
+                    assert_eq!(str_lit(&names[0]), Value::string(&names[0]));
+                    handles
+                })
+            })
+            .collect();
+        let results: Vec<_> = workers.into_iter().map(|worker| worker.join().unwrap()).collect();
+        for handles in &results[1..] {
+            assert_eq!(handles, &results[0]);
+        }
+        for (name, (handle, tag)) in names.iter().zip(&results[0]) {
+            assert_eq!(get_str(*handle), *name);
+            assert_eq!(get_tag_name(*tag), *name);
+        }
+    }
+}
+
 // ============================================================================
 // Boolean Operation Tests
 // ============================================================================
@@ -359,6 +509,374 @@ mod boolean_tests {
     }
 }
 
+// ============================================================================
+// String Builder Tests
+// ============================================================================
+
+#[cfg(test)]
+mod builder_tests {
+    use super::*;
+
+    fn built(builder: &Value) -> String {
+        match sb_build(builder.clone()) {
+            Value::Str(s) => s.text().to_string(),
+            other => panic!("sb_build should return a string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sb_push_appends_in_place() {
+        setup();
+
+        let sb = sb_new(Value::Unit);
+        assert_eq!(built(&sb), "");
+        let sb = sb_push(Value::Tuple(vec![sb, str_lit("x = ")]));
+        let sb = sb_push_int(Value::Tuple(vec![sb, Value::Int(-42)]));
+        assert_eq!(built(&sb), "x = -42");
+
+        // Building does not reset the builder
+        let sb = sb_push(Value::Tuple(vec![sb, str_lit(";")]));
+        assert_eq!(built(&sb), "x = -42;");
+    }
+
+    #[test]
+    fn test_builder_copies_share_the_buffer() {
+        setup();
+
+        let sb = sb_new(Value::Unit);
+        let copy = sb.clone();
+        sb_push(Value::Tuple(vec![sb.clone(), str_lit("shared")]));
+        assert_eq!(built(&copy), "shared");
+        assert_eq!(copy.kind_name(), "StringBuilder");
+    }
+}
+
+// ============================================================================
+// Concurrency Tests
+// ============================================================================
+
+#[cfg(test)]
+mod concurrency_tests {
+    use super::*;
+
+    // (channel, n): sends n * n, returns n + 1
+    fn square_into(args: Value) -> Value {
+        let elems = args.as_tuple();
+        let n = elems[1].as_int();
+        channel_send(Value::Tuple(vec![elems[0].clone(), Value::Int(n * n)]));
+        Value::Int(n + 1)
+    }
+
+    #[test]
+    fn test_spawned_threads_send_and_return_values() {
+        setup();
+
+        let channel = channel_new(Value::Unit);
+        let tasks: Vec<Value> = (1..=3)
+            .map(|n| spawn(square_into, Value::Tuple(vec![channel.clone(), Value::Int(n)])))
+            .collect();
+        let mut received: Vec<i64> = (0..3).map(|_| channel_recv(channel.clone()).as_int()).collect();
+        received.sort();
+        assert_eq!(received, [1, 4, 9]);
+
+        let results: Vec<i64> = tasks.into_iter().map(|task| join(task).as_int()).collect();
+        assert_eq!(results, [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_channel_keeps_sending_order() {
+        setup();
+
+        let channel = channel_new(Value::Unit);
+        for text in ["a", "b", "c"] {
+            channel_send(Value::Tuple(vec![channel.clone(), str_lit(text)]));
+        }
+        let received: Vec<String> = (0..3).map(|_| channel_recv(channel.clone()).to_string()).collect();
+        assert_eq!(received, ["a", "b", "c"]);
+        assert_eq!(channel.kind_name(), "Channel");
+    }
+
+    #[test]
+    #[should_panic(expected = "channel_send: a StringBuilder cannot be sent to another thread")]
+    fn test_builders_cannot_be_sent() {
+        panic_on_runtime_errors();
+        setup();
+
+        let nested = Value::List(vec![Value::Tuple(vec![Value::Int(1), sb_new(Value::Unit)])]);
+        channel_send(Value::Tuple(vec![channel_new(Value::Unit), nested]));
+    }
+
+    #[test]
+    #[should_panic(expected = "join: the task was already joined")]
+    fn test_a_task_is_joined_once() {
+        panic_on_runtime_errors();
+        setup();
+
+        let task = spawn(|x| x, Value::Int(1));
+        assert_eq!(join(task.clone()), Value::Int(1));
+        join(task);
+    }
+}
+
+// ============================================================================
+// Byte Buffer Tests
+// ============================================================================
+
+#[cfg(test)]
+mod bytes_tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_len_get_slice() {
+        setup();
+        
+        let data = Value::Bytes(vec![0x00, 0x7f, 0xff, 0x10]);
+        
+        assert_eq!(bytes_len(data.clone()), Value::Int(4));
+        assert_eq!(bytes_get(Value::Tuple(vec![data.clone(), Value::Int(2)])), Value::Int(255));
+        
+        // Out-of-range reads return -1 (0 is a valid byte)
+        assert_eq!(bytes_get(Value::Tuple(vec![data.clone(), Value::Int(4)])), Value::Int(-1));
+        assert_eq!(bytes_get(Value::Tuple(vec![data.clone(), Value::Int(-1)])), Value::Int(-1));
+        
+        // Slices clamp to the buffer like str_slice
+        let slice = bytes_slice(Value::Tuple(vec![data.clone(), Value::Int(1), Value::Int(100)]));
+        assert_eq!(slice, Value::Bytes(vec![0x7f, 0xff, 0x10]));
+        let empty = bytes_slice(Value::Tuple(vec![data.clone(), Value::Int(3), Value::Int(1)]));
+        assert_eq!(empty, Value::Bytes(vec![]));
+    }
+
+    #[test]
+    fn test_fs_bytes_round_trip() {
+        setup();
+        
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blob.bin");
+        let path_val = str_lit(path.to_str().unwrap());
+        let payload = vec![0u8, 1, 2, 0xfe, 0xff];
+        
+        let written = fs_write_bytes(Value::Tuple(vec![path_val.clone(), Value::Bytes(payload.clone())]));
+        match written {
+            Value::Ctor { tag, .. } => assert_eq!(crate::runtime::value::get_tag_name(tag), "Ok"),
+            other => panic!("fs_write_bytes should return a Result ctor, got {:?}", other),
+        }
+        
+        match fs_read_bytes(path_val) {
+            Value::Ctor { tag, fields } => {
+                assert_eq!(crate::runtime::value::get_tag_name(tag), "Ok");
+                assert_eq!(fields[0], Value::Bytes(payload));
+            }
+            other => panic!("fs_read_bytes should return a Result ctor, got {:?}", other),
+        }
+    }
+}
+
+// ============================================================================
+// JSON Tests
+// ============================================================================
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+    use crate::runtime::value::get_tag_name;
+
+    fn unwrap_ok(result: Value) -> Value {
+        match result {
+            Value::Ctor { tag, mut fields } if get_tag_name(tag) == "Ok" => fields.remove(0),
+            other => panic!("Expected Ok(..), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        setup();
+        
+        let text = r#"{"name":"axis","tags":["a","b\n\u00e9"],"n":-42,"ok":true,"none":null,"nested":{}}"#;
+        let tree = unwrap_ok(json_parse(str_lit(text)));
+        let encoded = unwrap_ok(json_encode(tree.clone()));
+        
+        // Re-parsing the encoding yields the same tree
+        assert_eq!(unwrap_ok(json_parse(encoded)), tree);
+        
+        match tree {
+            Value::Ctor { tag, fields } => {
+                assert_eq!(get_tag_name(tag), "JObj");
+                assert_eq!(fields[0].as_list().len(), 6);
+            }
+            other => panic!("Expected JObj, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_json_parse_error_has_position() {
+        setup();
+        
+        let result = json_parse(str_lit("{\n  \"a\": tru\n}"));
+        match result {
+            Value::Ctor { tag, fields } if get_tag_name(tag) == "Err" => match &fields[0] {
+                Value::Ctor { tag, fields } => {
+                    assert_eq!(get_tag_name(*tag), "JsonError");
+                    assert_eq!(fields[1], Value::Int(2));
+                    assert_eq!(fields[2], Value::Int(11));
+                    assert!(matches!(fields[0], Value::Str(_)));
+                }
+                other => panic!("Expected JsonError, got {:?}", other),
+            },
+            other => panic!("Expected Err(..), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_json_rejects_floats_and_trailing_input() {
+        setup();
+        
+        for bad in ["1.5", "[1] x", "[01]", "\"\\ud800\""] {
+            match json_parse(str_lit(bad)) {
+                Value::Ctor { tag, .. } => assert_eq!(get_tag_name(tag), "Err", "input {:?}", bad),
+                other => panic!("Expected Result ctor, got {:?}", other),
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Environment / Process Tests
+// ============================================================================
+
+#[cfg(test)]
+mod process_tests {
+    use super::*;
+    use crate::runtime::value::get_tag_name;
+
+    #[test]
+    fn test_env_set_then_get() {
+        setup();
+        
+        let name = str_lit("AXIS_SHIM_TEST_VAR");
+        let value = str_lit("42");
+        env_set(Value::Tuple(vec![name.clone(), value.clone()]));
+        
+        match env_get(name) {
+            Value::Ctor { tag, fields } => {
+                assert_eq!(get_tag_name(tag), "Some");
+                assert_eq!(fields[0], value);
+            }
+            other => panic!("env_get should return an option ctor, got {:?}", other),
+        }
+        
+        let missing = env_get(str_lit("AXIS_SHIM_TEST_UNSET_VAR"));
+        match missing {
+            Value::Ctor { tag, .. } => assert_eq!(get_tag_name(tag), "None"),
+            other => panic!("env_get should return an option ctor, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_env_set_rejects_nul_bytes() {
+        setup();
+
+        for (name, value) in [("AXIS_SHIM_TEST_NUL\0", "1"), ("AXIS_SHIM_TEST_NUL_VALUE", "a\0b")] {
+            match env_set(Value::Tuple(vec![str_lit(name), str_lit(value)])) {
+                Value::Ctor { tag, .. } => assert_eq!(get_tag_name(tag), "Err"),
+                other => panic!("env_set should return a result ctor, got {:?}", other),
+            }
+        }
+        match env_get(str_lit("AXIS_SHIM_TEST_NUL_VALUE")) {
+            Value::Ctor { tag, .. } => assert_eq!(get_tag_name(tag), "None"),
+            other => panic!("env_get should return an option ctor, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_proc_run_captures_output_and_exit_code() {
+        setup();
+        
+        let args = Value::List(vec![
+            str_lit("-c"),
+            str_lit("echo out; echo err 1>&2; exit 3"),
+        ]);
+        let result = proc_run(Value::Tuple(vec![str_lit("sh"), args]));
+        
+        let proc_result = match result {
+            Value::Ctor { tag, fields } if get_tag_name(tag) == "Ok" => fields[0].clone(),
+            other => panic!("proc_run should succeed, got {:?}", other),
+        };
+        match proc_result {
+            Value::Ctor { tag, fields } => {
+                assert_eq!(get_tag_name(tag), "ProcResult");
+                assert_eq!(fields[0], Value::Int(3));
+                assert_eq!(fields[1], str_lit("out\n"));
+                assert_eq!(fields[2], str_lit("err\n"));
+            }
+            other => panic!("Expected ProcResult ctor, got {:?}", other),
+        }
+    }
+}
+
+// ============================================================================
+// Runtime Error Channel Tests
+// ============================================================================
+
+#[cfg(test)]
+mod runtime_error_tests {
+    use super::*;
+    use crate::runtime::error::{
+        axis_runtime_error, CallFrame, format_axis_backtrace, format_runtime_error, AXIS_RUNTIME_ERROR_EXIT_CODE,
+    };
+
+    #[test]
+    fn test_format_runtime_error_with_and_without_span() {
+        assert_eq!(
+            format_runtime_error("Pattern mismatch", Some(("main.ax", 3, 7))),
+            "error[runtime]: Pattern mismatch\n  --> main.ax:3:7"
+        );
+        assert_eq!(format_runtime_error("boom", None), "error[runtime]: boom");
+    }
+
+    #[test]
+    fn test_call_frames_form_axis_backtrace() {
+        assert_eq!(format_axis_backtrace(), "");
+        {
+            let _outer = CallFrame::enter("main", Some(("main.ax", 1, 1)));
+            let _inner = CallFrame::enter("parse", None);
+            assert_eq!(
+                format_axis_backtrace(),
+                "Axis backtrace (most recent call first):\n  #0 parse\n  #1 main at main.ax:1:1"
+            );
+        }
+        // Frames are popped when their guards drop
+        assert_eq!(format_axis_backtrace(), "");
+    }
+
+    // The shipped path, in a child process: the test binary runs this test
+    // again with AXIS_RUNTIME_ERROR_CHILD set, and there the error exits
+    #[test]
+    fn test_runtime_errors_report_and_exit() {
+        if std::env::var_os("AXIS_RUNTIME_ERROR_CHILD").is_some() {
+            axis_runtime_error("Pattern mismatch", Some(("main.ax", 3, 7)));
+        }
+        let module = module_path!().split_once("::").unwrap().1;
+        let name = format!("{}::test_runtime_errors_report_and_exit", module);
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", &name, "--nocapture"])
+            .env("AXIS_RUNTIME_ERROR_CHILD", "1")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(AXIS_RUNTIME_ERROR_EXIT_CODE));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("error[runtime]: Pattern mismatch\n  --> main.ax:3:7"), "{}", stderr);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected Int, got Str")]
+    fn test_type_confusion_routes_through_runtime_error() {
+        panic_on_runtime_errors();
+        setup();
+
+        str_lit("not a number").as_int();
+    }
+}
+
 // ============================================================================
 // Regression Tests (Anti-Semantic Collapse)
 // ============================================================================
@@ -373,7 +891,7 @@ mod regression_tests {
     fn test_string_functions_are_distinct() {
         setup();
         
-        let test_str = Value::Str(intern_str("test"));
+        let test_str = str_lit("test");
         let out_of_bounds = Value::Int(100);
         
         // These functions must behave differently on out-of-bounds access
@@ -418,4 +936,115 @@ mod regression_tests {
         // undefined behavior - but we deliberately don't expose such a function
         // in this shim to maintain safety
     }
-}
\ No newline at end of file
+}
+// ============================================================================
+// Assertion Tests
+// ============================================================================
+
+#[cfg(test)]
+mod assertion_tests {
+    use super::*;
+    use crate::runtime::error::{assertion_failures, format_assertion_failure};
+
+    #[test]
+    fn test_assertions_record_failures_without_aborting() {
+        setup();
+        let before = assertion_failures();
+
+        assert_eq(Value::Tuple(vec![Value::Int(3), Value::Int(3)]), None);
+        assert_true(Value::Bool(true), None);
+        assert_eq!(assertion_failures(), before);
+
+        assert_eq(Value::Tuple(vec![Value::Int(3), Value::Int(4)]), Some(("t.ax", 2, 5)));
+        assert_true(Value::Bool(false), None);
+        fail(str_lit("unreachable"), None);
+        assert_eq!(assertion_failures(), before + 3);
+    }
+
+    #[test]
+    fn test_assertion_failure_message_shows_values_and_span() {
+        let msg = format_assertion_failure(
+            "assertion failed: left == right",
+            &[("left", "3".to_string()), ("right", "4".to_string())],
+            Some(("t.ax", 2, 5)),
+        );
+        assert_eq!(
+            msg,
+            "error[assertion]: assertion failed: left == right\n  --> t.ax:2:5\n   left: 3\n  right: 4"
+        );
+    }
+}
+
+// ============================================================================
+// Pretty-Printing and Diff Tests
+// ============================================================================
+
+#[cfg(test)]
+mod inspect_tests {
+    use super::*;
+    use crate::runtime::inspect::{diff_text, pretty_text};
+    use crate::runtime::value::intern_tag;
+
+    fn ctor(name: &str, fields: Vec<Value>) -> Value {
+        Value::Ctor { tag: intern_tag(name), fields }
+    }
+
+    fn text(value: Value) -> String {
+        match value {
+            Value::Str(s) => s.text().to_string(),
+            other => panic!("expected Str, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_value_pretty_breaks_long_values() {
+        setup();
+        let short = Value::Tuple(vec![Value::Int(1), str_lit("a"), ctor("None", vec![])]);
+        assert_eq!(text(value_pretty(short)), "(1, \"a\", None)");
+
+        let names: Vec<Value> = (0..6).map(|i| Value::string(&format!("name number {}", i))).collect();
+        let long = ctor("Some", vec![Value::Tuple(vec![Value::Int(7), Value::List(names)])]);
+        assert_eq!(
+            pretty_text(&long),
+            "Some(\n  (\n    7,\n    [\n      \"name number 0\",\n      \"name number 1\",\n      \"name number 2\",\n      \
+             \"name number 3\",\n      \"name number 4\",\n      \"name number 5\",\n    ],\n  ),\n)"
+        );
+    }
+
+    #[test]
+    fn test_value_diff_names_the_first_differing_path() {
+        setup();
+        let list = |items: &[i64]| Value::List(items.iter().map(|&n| Value::Int(n)).collect());
+        let left = Value::Tuple(vec![Value::Int(1), ctor("Some", vec![list(&[2, 3])])]);
+        let right = Value::Tuple(vec![Value::Int(1), ctor("Some", vec![list(&[2, 4])])]);
+        assert_eq!(text(value_diff(Value::Tuple(vec![left.clone(), right]))), "value.1.0[1]: left 3, right 4");
+        assert_eq!(text(value_diff(Value::Tuple(vec![left.clone(), left]))), "");
+
+        assert_eq!(diff_text(&list(&[1, 2]), &list(&[1, 2, 3])), "value: left has 2 items, right has 3 items");
+        assert_eq!(diff_text(&Value::Int(1), &str_lit("1")), "value: left 1, right \"1\"");
+        assert_eq!(diff_text(&ctor("Some", vec![Value::Int(1)]), &ctor("None", vec![])), "value: left Some(1), right None");
+    }
+
+    #[test]
+    fn test_assert_eq_failure_reports_the_difference() {
+        setup();
+        let report = |left: &Value, right: &Value| {
+            let details = crate::runtime::shim::assert_eq_details(left, right).expect("the values differ");
+            crate::runtime::error::format_assertion_failure("assertion failed: left == right", &details, None)
+        };
+        let pair = |n| Value::Tuple(vec![str_lit("k"), Value::Int(n)]);
+        assert_eq!(
+            report(&pair(1), &pair(2)),
+            "error[assertion]: assertion failed: left == right\n   left: (k, 1)\n  right: (k, 2)\n   diff: value.1: left 1, right 2"
+        );
+        assert!(crate::runtime::shim::assert_eq_details(&pair(1), &pair(1)).is_none());
+        // Scalars: the values say it all
+        assert_eq!(report(&Value::Int(3), &Value::Int(4)).lines().count(), 3);
+
+        // Too long for a line: indented, continuing under the first line
+        let long = Value::List((0..20).map(Value::Int).collect());
+        let text = report(&long, &Value::List(vec![]));
+        assert!(text.contains("\n   left: [\n           0,\n           1,\n"), "{}", text);
+        assert!(text.ends_with("\n         ]\n  right: []\n   diff: value: left has 20 items, right has 0 items"), "{}", text);
+    }
+}
@@ -0,0 +1,147 @@
+// Single-file Rust programs (`build --standalone`)
+//
+// The Rust backend's project depends on this crate for its runtime. With
+// --standalone the program is one .rs file instead, with no dependencies,
+// built with plain `rustc`:
+//
+//   #![allow(...)]
+//   <the main wrapper>
+//   mod axis_generated { <the generated module> }
+//   mod abi { <abi.rs> }
+//   mod runtime { pub mod value { <runtime/value.rs> } ... }
+//
+// The runtime is this crate's own source, embedded in the bridge when it is
+// built, under the same module paths, so the `crate::abi::` and
+// `crate::runtime::` paths in it resolve as they do here. Only the modules
+// the generated code can reach are inlined (not the emitters), and the cargo
+// features are resolved as the backend's project would set them (--allow,
+// --runtime): each `feature = "..."` becomes `all()` when on and `any()`
+// when off. Of those modules only the items the program uses are kept, with
+// what they use in turn (rust_items::prune): hello-world inlines printing
+// and the value tables, not JSON or the reactor.
+//
+// NOTE: the Core bundle primitives (axis_emit_core_bundle_to_file,
+// axis_load_core_bundle_from_file) need the Cap'n Proto encoder, a
+// dependency; a program that uses them is refused.
+
+use crate::backend::BRIDGE_VERSION;
+use crate::runtime::rust_items;
+
+// The runtime modules generated code reaches through abi.rs, in
+// runtime/mod.rs order, with the feature that gates the module itself
+const RUNTIME_MODULES: &[(&str, Option<&str>, &str)] = &[
+    ("value", None, include_str!("value.rs")),
+    ("error", None, include_str!("error.rs")),
+    ("tuple", None, include_str!("tuple.rs")),
+    ("list", None, include_str!("list.rs")),
+    ("io", None, include_str!("io.rs")),
+    ("files", Some("fs"), include_str!("files.rs")),
+    ("process", Some("proc"), include_str!("process.rs")),
+    ("json", None, include_str!("json.rs")),
+    ("inspect", None, include_str!("inspect.rs")),
+    ("memo", None, include_str!("memo.rs")),
+    ("entry", None, include_str!("entry.rs")),
+    ("overflow", None, include_str!("overflow.rs")),
+    ("order", None, include_str!("order.rs")),
+    ("strings", None, include_str!("strings.rs")),
+    ("chars", None, include_str!("chars.rs")),
+    ("seq", None, include_str!("seq.rs")),
+    ("concurrency", None, include_str!("concurrency.rs")),
+    ("reactor", None, include_str!("reactor.rs")),
+    ("shim", None, include_str!("shim.rs")),
+];
+
+const ABI_SOURCE: &str = include_str!("../abi.rs");
+
+/// Primitives a standalone program cannot use
+pub const UNAVAILABLE: &[&str] = &["axis_emit_core_bundle_to_file", "axis_load_core_bundle_from_file"];
+
+/// The single source of a program: `main` is the backend's main wrapper,
+/// `generated` the emitted module, `features` the cargo features of this
+/// crate to build the runtime with
+pub fn standalone_program(main: &str, generated: &str, features: &[&str]) -> Result<String, String> {
+    if let Some(name) = UNAVAILABLE.iter().find(|name| generated.contains(&format!("shim::{}", name))) {
+        return Err(format!("{} needs the axis-rust-bridge crate; it is not available with --standalone", name));
+    }
+
+    let mut out = format!(
+        "// Generated by axis-rust-bridge {} (build --standalone); no dependencies:\n\
+         //   rustc --edition 2021 -O axis_emitted.rs\n\
+         #![allow(unused, non_snake_case, non_camel_case_types, non_upper_case_globals)]\n\n",
+        BRIDGE_VERSION
+    );
+    let main = main.replace("mod axis_generated;\n", "").replace(BRIDGE_IMPORT, STANDALONE_IMPORT);
+    let generated = generated.replace(BRIDGE_IMPORT, STANDALONE_IMPORT);
+
+    // abi.rs first, then the modules this configuration compiles
+    let modules: Vec<&(&str, Option<&str>, &str)> = RUNTIME_MODULES
+        .iter()
+        .filter(|(_, feature, _)| feature.is_none_or(|feature| features.contains(&feature)))
+        .collect();
+    let sources: Vec<String> = std::iter::once(ABI_SOURCE)
+        .chain(modules.iter().map(|(_, _, source)| *source))
+        .map(|source| runtime_source(source, features))
+        .collect();
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    let pruned = rust_items::prune(&sources, &[&main, &generated]);
+
+    out.push_str(&main);
+    out.push_str("\nmod axis_generated {\n");
+    out.push_str(&generated);
+    out.push_str("}\n\nmod abi {\n");
+    out.push_str(&pruned[0]);
+    out.push_str("}\n\nmod runtime {\n");
+    for ((name, _, _), source) in modules.iter().zip(&pruned[1..]) {
+        out.push_str(&format!("pub mod {} {{\n", name));
+        out.push_str(source);
+        out.push_str("}\n");
+    }
+    out.push_str("}\n");
+    Ok(out)
+}
+
+// The import the generated module and main wrapper start with
+const BRIDGE_IMPORT: &str = "use axis_rust_bridge::abi::*;";
+const STANDALONE_IMPORT: &str = "use crate::abi::*;";
+
+// A runtime source as inlined: features resolved, the bundle primitives
+// (runtime::core_emit) left out
+fn runtime_source(source: &str, features: &[&str]) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in source.lines() {
+        if line.contains("runtime::core_emit") {
+            // With the attribute that gates it
+            if lines.last().is_some_and(|last| last.trim_start().starts_with("#[cfg(")) {
+                lines.pop();
+            }
+            continue;
+        }
+        lines.push(line);
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    for name in UNAVAILABLE {
+        text = text.replace(&format!("{}, ", name), "");
+    }
+    text = text.replace("env!(\"CARGO_PKG_VERSION\")", &format!("{:?}", BRIDGE_VERSION));
+    resolve_features(&text, features)
+}
+
+/// `feature = "name"` in every cfg as `all()` (true) when `name` is in
+/// `features`, `any()` (false) otherwise
+pub fn resolve_features(source: &str, features: &[&str]) -> String {
+    const KEY: &str = "feature = \"";
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find(KEY) {
+        let after = &rest[start + KEY.len()..];
+        let Some(end) = after.find('"') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(if features.contains(&&after[..end]) { "all()" } else { "any()" });
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
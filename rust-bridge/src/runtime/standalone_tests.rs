@@ -0,0 +1,107 @@
+// Tests for `build --standalone` (standalone.rs)
+// The end-to-end tests compile the output with `rustc` and are skipped when
+// it is not installed.
+
+use crate::backend::{CodegenBackend, EmitArtifacts, RustBackend};
+use crate::core_arena::ArenaProgram;
+use crate::core_ir::CoreTerm;
+use crate::emit_rust::EmitOptions;
+use crate::runtime::capability::Capability;
+use crate::runtime::standalone::resolve_features;
+use std::path::PathBuf;
+use std::process::Command;
+use std::rc::Rc;
+
+fn var(name: &str) -> CoreTerm {
+    CoreTerm::Var(name.into(), None)
+}
+
+fn str_lit(s: &str) -> CoreTerm {
+    CoreTerm::StrLit(s.to_string(), None)
+}
+
+// Curried application f(a)(b)..., as lowered from f(a, b)
+fn call(f: &str, args: Vec<CoreTerm>) -> CoreTerm {
+    args.into_iter().fold(var(f), |func, arg| CoreTerm::App(Rc::new(func), Rc::new(arg), None))
+}
+
+// axis_entry(args) = body, the whole program
+fn entry(body: CoreTerm) -> CoreTerm {
+    let value = CoreTerm::Lam("args".into(), Rc::new(body), None);
+    CoreTerm::Let("axis_entry".into(), Rc::new(value), Rc::new(CoreTerm::UnitLit(None)), None)
+}
+
+fn emit(core: &CoreTerm, opts: &EmitOptions) -> EmitArtifacts {
+    let backend = RustBackend { runtime_crate: PathBuf::from("/nonexistent") };
+    let opts = EmitOptions { standalone: true, ..opts.clone() };
+    backend.emit(&ArenaProgram::from_term(core), &opts).unwrap_or_else(|e| panic!("emit failed: {}", e))
+}
+
+// Write the artifacts, run their build command and return the directory
+// holding the program; None without rustc
+fn build(artifacts: &EmitArtifacts) -> Option<tempfile::TempDir> {
+    if Command::new("rustc").arg("--version").output().is_err() {
+        eprintln!("skipping: no rustc on PATH");
+        return None;
+    }
+    let dir = tempfile::tempdir().unwrap();
+    for (path, contents) in &artifacts.files {
+        std::fs::write(dir.path().join(path), contents).unwrap();
+    }
+    let (program, args) = artifacts.build_command.split_first().unwrap();
+    let build = Command::new(program).args(args).current_dir(dir.path()).output().unwrap();
+    assert!(build.status.success(), "{} failed:\n{}", program, String::from_utf8_lossy(&build.stderr));
+    Some(dir)
+}
+
+#[test]
+fn test_standalone_is_one_file_without_the_bridge() {
+    let artifacts = emit(&entry(call("io_println", vec![str_lit("hi")])), &EmitOptions::default());
+    assert_eq!(artifacts.files.len(), 1);
+    let (path, source) = &artifacts.files[0];
+    assert_eq!(path, &PathBuf::from("axis_emitted.rs"));
+    let imports = source.lines().filter(|line| line.trim_start().starts_with("use axis_rust_bridge"));
+    assert_eq!(imports.count(), 0, "still imports the bridge crate");
+    assert!(!source.contains("feature = "), "unresolved cargo feature");
+    assert!(!source.contains("fn json_parse"), "runtime items the program does not use are pruned");
+    assert_eq!(artifacts.build_command[0], "rustc");
+}
+
+#[test]
+fn test_standalone_program_runs() {
+    let artifacts = emit(&entry(call("io_println", vec![str_lit("hello, standalone")])), &EmitOptions::default());
+    let Some(dir) = build(&artifacts) else {
+        return;
+    };
+    let run = Command::new(dir.path().join("axis_emitted")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "hello, standalone\n");
+    assert_eq!(run.status.code(), Some(0));
+}
+
+#[test]
+fn test_standalone_leaves_out_capabilities_outside_allow() {
+    let opts = EmitOptions { allow: Some(vec![Capability::Io]), ..Default::default() };
+    let artifacts = emit(&entry(call("io_println", vec![str_lit("sandboxed")])), &opts);
+    assert!(!artifacts.files[0].1.contains("pub mod process {"), "process is behind proc");
+    let Some(dir) = build(&artifacts) else {
+        return;
+    };
+    let run = Command::new(dir.path().join("axis_emitted")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "sandboxed\n");
+}
+
+#[test]
+fn test_standalone_refuses_the_bundle_primitives() {
+    let core = entry(call("axis_load_core_bundle_from_file", vec![str_lit("main.coreir")]));
+    let backend = RustBackend { runtime_crate: PathBuf::from("/nonexistent") };
+    let opts = EmitOptions { standalone: true, ..Default::default() };
+    let error = backend.emit(&ArenaProgram::from_term(&core), &opts).err().expect("refused");
+    assert!(error.to_string().contains("axis_load_core_bundle_from_file"), "{}", error);
+}
+
+#[test]
+fn test_resolve_features() {
+    let source = "#[cfg(all(feature = \"io\", not(feature = \"async\")))]";
+    assert_eq!(resolve_features(source, &["io"]), "#[cfg(all(all(), not(any())))]");
+    assert_eq!(resolve_features(source, &["async"]), "#[cfg(all(any(), not(all())))]");
+}
@@ -0,0 +1,113 @@
+// String utilities: split, trim, search, replace, case and join
+//
+// Everything works on the text of Str values, so positions never split a
+// character. A separator or pattern that is the empty string has no
+// occurrences to split at or replace, except that str_split on "" splits
+// the string into its characters:
+//
+//   str_split("a,b,,c", ",")     ["a", "b", "", "c"]
+//   str_split("abc", "")         ["a", "b", "c"]
+//   str_replace("aaa", "", "x")  "aaa"
+//
+// str_split returns, and str_join takes, an Axis list (Nil/Cons); str_join
+// also takes a Value::List. str_to_lower and str_to_upper map every
+// character as Unicode does; the C runtime (runtime/c) only maps ASCII
+// letters. The compiler's interpreter implements them as here.
+
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::list::{cons_list, list_elements};
+use crate::runtime::value::Value;
+
+// The text of the Str argument `index` of a primitive
+fn text_arg<'a>(args: &'a [Value], index: usize, primitive: &str) -> &'a str {
+    match args.get(index) {
+        Some(Value::Str(s)) => s.text(),
+        Some(other) => axis_runtime_error(
+            &format!("{}: argument {} must be a string, got {}", primitive, index + 1, other.kind_name()),
+            None,
+        ),
+        None => axis_runtime_error(&format!("{}: missing argument {}", primitive, index + 1), None),
+    }
+}
+
+// The fields of a packed argument tuple
+fn tuple_args<'a>(args: &'a Value, primitive: &str) -> &'a [Value] {
+    match args {
+        Value::Tuple(elems) => elems,
+        other => axis_runtime_error(&format!("{}: expected a tuple of arguments, got {}", primitive, other.kind_name()), None),
+    }
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [s, sep]
+pub fn str_split(args: Value) -> Value {
+    let args = tuple_args(&args, "str_split");
+    let (text, sep) = (text_arg(args, 0, "str_split"), text_arg(args, 1, "str_split"));
+    let parts: Vec<Value> = if sep.is_empty() {
+        text.chars().map(|c| Value::string(&c.to_string())).collect()
+    } else {
+        text.split(sep).map(Value::string).collect()
+    };
+    cons_list(parts)
+}
+
+// Without leading and trailing whitespace
+pub fn str_trim(s: Value) -> Value {
+    Value::string(text_arg(std::slice::from_ref(&s), 0, "str_trim").trim())
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [s, needle]
+pub fn str_contains(args: Value) -> Value {
+    let args = tuple_args(&args, "str_contains");
+    Value::Bool(text_arg(args, 0, "str_contains").contains(text_arg(args, 1, "str_contains")))
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [s, prefix]
+pub fn str_starts_with(args: Value) -> Value {
+    let args = tuple_args(&args, "str_starts_with");
+    Value::Bool(text_arg(args, 0, "str_starts_with").starts_with(text_arg(args, 1, "str_starts_with")))
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [s, suffix]
+pub fn str_ends_with(args: Value) -> Value {
+    let args = tuple_args(&args, "str_ends_with");
+    Value::Bool(text_arg(args, 0, "str_ends_with").ends_with(text_arg(args, 1, "str_ends_with")))
+}
+
+// Every occurrence of `from` replaced by `to`, left to right
+// UNARY CONTRACT: Accepts Value::Tuple containing [s, from, to]
+pub fn str_replace(args: Value) -> Value {
+    let args = tuple_args(&args, "str_replace");
+    let text = text_arg(args, 0, "str_replace");
+    let (from, to) = (text_arg(args, 1, "str_replace"), text_arg(args, 2, "str_replace"));
+    if from.is_empty() {
+        return Value::string(text);
+    }
+    Value::string(&text.replace(from, to))
+}
+
+pub fn str_to_lower(s: Value) -> Value {
+    Value::string(&text_arg(std::slice::from_ref(&s), 0, "str_to_lower").to_lowercase())
+}
+
+pub fn str_to_upper(s: Value) -> Value {
+    Value::string(&text_arg(std::slice::from_ref(&s), 0, "str_to_upper").to_uppercase())
+}
+
+// The parts with sep between each two
+// UNARY CONTRACT: Accepts Value::Tuple containing [parts, sep]
+pub fn str_join(args: Value) -> Value {
+    let args = tuple_args(&args, "str_join");
+    let sep = text_arg(args, 1, "str_join");
+    let parts = list_elements(&args[0]);
+    let mut joined = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            joined.push_str(sep);
+        }
+        match part {
+            Value::Str(s) => joined.push_str(s.text()),
+            other => axis_runtime_error(&format!("str_join: parts must be strings, got {}", other.kind_name()), None),
+        }
+    }
+    Value::string(&joined)
+}
@@ -0,0 +1,83 @@
+// Tests for the string utilities (strings.rs)
+
+use crate::runtime::list::list_elements;
+use crate::runtime::strings::{
+    str_contains, str_ends_with, str_join, str_replace, str_split, str_starts_with, str_to_lower, str_to_upper,
+    str_trim,
+};
+use crate::runtime::value::{get_tag_name, init_runtime, Value};
+use crate::runtime::panic_on_runtime_errors;
+
+fn s(text: &str) -> Value {
+    Value::string(text)
+}
+
+fn args(values: &[&str]) -> Value {
+    Value::Tuple(values.iter().map(|text| s(text)).collect())
+}
+
+fn texts(list: &Value) -> Vec<String> {
+    list_elements(list)
+        .iter()
+        .map(|part| match part {
+            Value::Str(text) => text.text().to_string(),
+            other => panic!("expected a string, got {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_split_returns_an_axis_list() {
+    init_runtime();
+    let parts = str_split(args(&["a,b,,c", ","]));
+    match &parts {
+        Value::Ctor { tag, .. } => assert_eq!(get_tag_name(*tag), "Cons"),
+        other => panic!("expected a Cons list, got {:?}", other),
+    }
+    assert_eq!(texts(&parts), ["a", "b", "", "c"]);
+    assert_eq!(texts(&str_split(args(&["aé c", ""]))), ["a", "é", " ", "c"]);
+    assert_eq!(texts(&str_split(args(&["", ","]))), [""]);
+    assert!(texts(&str_split(args(&["", ""]))).is_empty());
+}
+
+#[test]
+fn test_join_is_the_inverse_of_split() {
+    init_runtime();
+    for (text, sep) in [("a,b,,c", ","), ("no separator", "::"), ("", "-"), ("x--y--", "--")] {
+        let parts = str_split(args(&[text, sep]));
+        assert_eq!(str_join(Value::Tuple(vec![parts, s(sep)])), s(text));
+    }
+    let list = Value::List(vec![s("a"), s("b")]);
+    assert_eq!(str_join(Value::Tuple(vec![list, s(", ")])), s("a, b"));
+}
+
+#[test]
+fn test_search_and_trim() {
+    init_runtime();
+    assert_eq!(str_trim(s(" \t hi there\n")), s("hi there"));
+    assert_eq!(str_contains(args(&["haystack", "st"])), Value::Bool(true));
+    assert_eq!(str_contains(args(&["haystack", "ts"])), Value::Bool(false));
+    assert_eq!(str_contains(args(&["haystack", ""])), Value::Bool(true));
+    assert_eq!(str_starts_with(args(&["haystack", "hay"])), Value::Bool(true));
+    assert_eq!(str_starts_with(args(&["hay", "haystack"])), Value::Bool(false));
+    assert_eq!(str_ends_with(args(&["haystack", "stack"])), Value::Bool(true));
+    assert_eq!(str_ends_with(args(&["haystack", "hay"])), Value::Bool(false));
+}
+
+#[test]
+fn test_replace_and_case() {
+    init_runtime();
+    assert_eq!(str_replace(args(&["one two one", "one", "1"])), s("1 two 1"));
+    assert_eq!(str_replace(args(&["aaa", "aa", "b"])), s("ba"));
+    assert_eq!(str_replace(args(&["aaa", "", "x"])), s("aaa"));
+    assert_eq!(str_to_lower(s("HeLLo É")), s("hello é"));
+    assert_eq!(str_to_upper(s("straße")), s("STRASSE"));
+}
+
+#[test]
+#[should_panic(expected = "str_contains: argument 2 must be a string, got Int")]
+fn test_arguments_must_be_strings() {
+    panic_on_runtime_errors();
+    init_runtime();
+    str_contains(Value::Tuple(vec![s("abc"), Value::Int(1)]));
+}
@@ -1,6 +1,7 @@
 // Tuple and Constructor field access helpers
 // Extracted from emit_rust.rs generate_value_runtime()
 
+use super::error::axis_runtime_error;
 use super::value::Value;
 
 // Tuple constructor
@@ -54,3 +55,15 @@ pub fn ctor_field(args: Value) -> Value {
         _ => Value::Unit,
     }
 }
+
+// Field `index` of a constructor, or element of a tuple, by reference.
+// Generated `match` code (emit_rust::emit_match_tree) reads positions of
+// the scrutinee the decision tree has already matched.
+pub fn match_field(value: &Value, index: usize) -> &Value {
+    match value {
+        Value::Ctor { fields, .. } | Value::Tuple(fields) => fields
+            .get(index)
+            .unwrap_or_else(|| axis_runtime_error(&format!("Pattern mismatch: no field {} in {}", index, value.kind_name()), None)),
+        _ => axis_runtime_error(&format!("Pattern mismatch: expected constructor or tuple, got {}", value.kind_name()), None),
+    }
+}
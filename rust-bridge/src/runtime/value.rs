@@ -1,46 +1,91 @@
 // ANDL Loop 6: Value Runtime Implementation
 // Extracted from emit_rust.rs generate_value_runtime()
 
-use std::sync::{OnceLock, Mutex};
+use std::sync::{Arc, OnceLock, RwLock};
 use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::runtime::concurrency::{Channel, Task};
+use crate::runtime::seq::Seq;
+use crate::runtime::error::axis_runtime_error;
+use crate::runtime::order::compare_values;
+use crate::runtime::overflow::{arith_primitive, IntOp};
+use std::cmp::Ordering;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     Int(i64),
     Bool(bool),
-    Str(u32),      // String handle
+    Str(StrValue), // Interned handle or owned text
     Unit,
     Tuple(Vec<Value>),
     List(Vec<Value>),
     Ctor { tag: u32, fields: Vec<Value> }, // Constructor with tag and fields
+    Bytes(Vec<u8>), // Raw binary data (not interned)
+    Builder(Rc<RefCell<String>>), // String builder (not interned; copies share the buffer)
+    Channel(Arc<Channel>), // Copies are the same channel
+    Task(Arc<Task>), // A spawned thread, until joined
+    Seq(Seq), // Lazy sequence (seq.rs); copies share the computed items
 }
 
 impl Value {
+    // Axis-facing name of the value's runtime kind (used in runtime errors)
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "Int",
+            Value::Bool(_) => "Bool",
+            Value::Str(_) => "Str",
+            Value::Unit => "Unit",
+            Value::Tuple(_) => "Tuple",
+            Value::List(_) => "List",
+            Value::Ctor { .. } => "Ctor",
+            Value::Bytes(_) => "Bytes",
+            Value::Builder(_) => "StringBuilder",
+            Value::Channel(_) => "Channel",
+            Value::Task(_) => "Task",
+            Value::Seq(_) => "Seq",
+        }
+    }
+
     pub fn as_int(&self) -> i64 {
         match self {
             Value::Int(n) => *n,
-            _ => panic!("Expected Int, got {:?}", self),
+            _ => axis_runtime_error(&format!("Expected Int, got {}", self.kind_name()), None),
         }
     }
 
     pub fn as_bool(&self) -> bool {
         match self {
             Value::Bool(b) => *b,
-            _ => panic!("Expected Bool, got {:?}", self),
+            _ => axis_runtime_error(&format!("Expected Bool, got {}", self.kind_name()), None),
         }
     }
 
     pub fn as_tuple(&self) -> &Vec<Value> {
         match self {
             Value::Tuple(elems) => elems,
-            _ => panic!("Expected Tuple, got {:?}", self),
+            _ => axis_runtime_error(&format!("Expected Tuple, got {}", self.kind_name()), None),
         }
     }
 
     pub fn as_list(&self) -> &Vec<Value> {
         match self {
             Value::List(elems) => elems,
-            _ => panic!("Expected List, got {:?}", self),
+            _ => axis_runtime_error(&format!("Expected List, got {}", self.kind_name()), None),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &Vec<u8> {
+        match self {
+            Value::Bytes(data) => data,
+            _ => axis_runtime_error(&format!("Expected Bytes, got {}", self.kind_name()), None),
+        }
+    }
+
+    pub fn as_builder(&self) -> &Rc<RefCell<String>> {
+        match self {
+            Value::Builder(buffer) => buffer,
+            _ => axis_runtime_error(&format!("Expected StringBuilder, got {}", self.kind_name()), None),
         }
     }
 }
@@ -50,7 +95,7 @@ impl std::fmt::Display for Value {
         match self {
             Value::Int(n) => write!(f, "{}", n),
             Value::Bool(b) => write!(f, "{}", b),
-            Value::Str(handle) => write!(f, "{}", get_str(*handle)),
+            Value::Str(s) => write!(f, "{}", s.text()),
             Value::Unit => write!(f, "()"),
             Value::Tuple(elems) => {
                 write!(f, "(")?;
@@ -69,154 +114,288 @@ impl std::fmt::Display for Value {
                 write!(f, "]")
             },
             Value::Ctor { tag, fields } => {
-                write!(f, "{}(", get_tag_name(*tag))?;
+                write!(f, "{}(", tag_display_name(*tag))?;
                 for (i, field) in fields.iter().enumerate() {
                     if i > 0 { write!(f, ", ")?; }
                     write!(f, "{}", field)?;
                 }
                 write!(f, ")")
             },
+            Value::Bytes(data) => write!(f, "<bytes:{}>", data.len()),
+            Value::Builder(buffer) => write!(f, "<builder:{}>", buffer.borrow().len()),
+            Value::Channel(_) => write!(f, "<channel>"),
+            Value::Task(_) => write!(f, "<task>"),
+            Value::Seq(_) => write!(f, "<seq>"),
         }
     }
 }
 
-// String table (thread-safe lazy statics)
-static STRING_TABLE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
-static STRING_MAP: OnceLock<Mutex<HashMap<String, u32>>> = OnceLock::new();
+// String values
+//
+// The string table is never freed, so only strings whose number is bounded
+// by the program text are interned: literals (str_lit, emitted for every
+// StrLit) and names. Strings computed at run time (concatenation, slices,
+// file contents, ...) are owned and freed with their last copy; interning
+// them made a long-running program leak every temporary it ever built.
+// An interned value carries its text along with its handle, so reading a
+// string never goes to the table.
+//
+// POLICY: compare strings with ==, never by handle: the same text may be
+// interned in one value and owned in another.
+#[derive(Clone, Debug)]
+pub enum StrValue {
+    // Handle into the string table, and its text
+    Interned(u32, Arc<str>),
+    Owned(Arc<str>),
+}
 
-pub fn init_runtime() {
-    let table_mutex = STRING_TABLE.get_or_init(|| Mutex::new(Vec::new()));
-    let mut table = table_mutex.lock().unwrap();
-    if table.is_empty() {
-        table.push("".to_string()); // Reserve handle 0 for empty string
+impl StrValue {
+    pub fn text(&self) -> &str {
+        match self {
+            StrValue::Interned(_, text) | StrValue::Owned(text) => text,
+        }
     }
-    // ensure map exists
-    STRING_MAP.get_or_init(|| Mutex::new(HashMap::new()));
 }
 
-pub fn intern_str(s: &str) -> u32 {
-    let map_mutex = STRING_MAP.get_or_init(|| Mutex::new(HashMap::new()));
-    let mut map = map_mutex.lock().unwrap();
-    if let Some(&handle) = map.get(s) {
+impl PartialEq for StrValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // The table holds each text once
+            (StrValue::Interned(a, _), StrValue::Interned(b, _)) => a == b,
+            _ => self.text() == other.text(),
+        }
+    }
+}
+
+impl Value {
+    /// A string computed at run time (not interned)
+    pub fn string(text: &str) -> Value {
+        Value::Str(StrValue::Owned(Arc::from(text)))
+    }
+}
+
+// Shared tables
+//
+// The string and tag tables are shared by all threads. Both only grow and
+// sit behind an RwLock: a lookup takes the read lock, and only a name seen
+// for the first time takes the write lock. On top of that, each thread
+// keeps the literals it has interned (LITERALS), so a literal evaluated in
+// a loop takes no lock after the first time, and the well-known
+// constructors have fixed tags that need no table at all.
+struct NameTable {
+    names: Vec<Arc<str>>,
+    index: HashMap<Arc<str>, u32>,
+}
+
+impl NameTable {
+    fn new(names: &[&str]) -> NameTable {
+        let mut table = NameTable { names: Vec::new(), index: HashMap::new() };
+        for name in names {
+            table.push(name);
+        }
+        table
+    }
+
+    fn push(&mut self, name: &str) -> u32 {
+        let handle = self.names.len() as u32;
+        let name: Arc<str> = Arc::from(name);
+        self.names.push(name.clone());
+        self.index.insert(name, handle);
+        handle
+    }
+}
+
+// The handle of `name` in `table`, added if new
+fn intern_in(table: &RwLock<NameTable>, name: &str) -> u32 {
+    if let Some(&handle) = table.read().unwrap().index.get(name) {
         return handle;
     }
-    let table_mutex = STRING_TABLE.get_or_init(|| Mutex::new(Vec::new()));
-    let mut table = table_mutex.lock().unwrap();
-    let handle = table.len() as u32;
-    table.push(s.to_string());
-    map.insert(s.to_string(), handle);
-    handle
+    let mut table = table.write().unwrap();
+    // Another thread may have added it since the read lock was released
+    match table.index.get(name) {
+        Some(&handle) => handle,
+        None => table.push(name),
+    }
 }
 
-pub fn get_str(handle: u32) -> String {
-    let table_mutex = STRING_TABLE.get_or_init(|| Mutex::new(Vec::new()));
-    let table = table_mutex.lock().unwrap();
-    table.get(handle as usize).cloned().unwrap_or_else(|| {
-        // This should never happen in correct code - log the error
-        eprintln!("[FATAL] get_str: invalid handle {} (table size: {})", handle, table.len());
-        format!("<invalid-str-handle-{}>", handle)
+static STRING_TABLE: OnceLock<RwLock<NameTable>> = OnceLock::new();
+
+fn string_table() -> &'static RwLock<NameTable> {
+    // Handle 0 is the empty string
+    STRING_TABLE.get_or_init(|| RwLock::new(NameTable::new(&[""])))
+}
+
+thread_local! {
+    // Literals this thread has interned. Each thread has its own copy of
+    // the text, so literal copies on different threads share no refcount.
+    static LITERALS: RefCell<HashMap<Arc<str>, StrValue>> = RefCell::new(HashMap::new());
+}
+
+/// A string literal of the program (interned)
+pub fn str_lit(text: &str) -> Value {
+    LITERALS.with(|literals| {
+        if let Some(value) = literals.borrow().get(text) {
+            return Value::Str(value.clone());
+        }
+        let copy: Arc<str> = Arc::from(text);
+        let value = StrValue::Interned(intern_str(text), copy.clone());
+        literals.borrow_mut().insert(copy, value.clone());
+        Value::Str(value)
     })
 }
 
+pub fn init_runtime() {
+    string_table();
+    tag_table();
+}
+
+/// init_runtime for generated code stamped with AXIS_ABI_VERSION (the
+/// generated main passes it)
+/// FAIL-FAST: code emitted for another version of the interface may not
+/// mean what this runtime would run; refuse it before it runs
+pub fn init_runtime_for(abi_version: u32) {
+    if abi_version != crate::abi::ABI_VERSION {
+        axis_runtime_error(
+            &format!(
+                "this program was generated for runtime interface version {}, but axis-rust-bridge {} provides version {}; \
+                 regenerate it with this bridge (or build it against a bridge that provides version {})",
+                abi_version,
+                env!("CARGO_PKG_VERSION"),
+                crate::abi::ABI_VERSION,
+                abi_version
+            ),
+            None,
+        );
+    }
+    init_runtime();
+}
+
+pub fn intern_str(s: &str) -> u32 {
+    intern_in(string_table(), s)
+}
+
+pub fn get_str(handle: u32) -> String {
+    let table = string_table().read().unwrap();
+    match table.names.get(handle as usize) {
+        Some(text) => text.to_string(),
+        None => {
+            // This should never happen in correct code - log the error
+            eprintln!("[FATAL] get_str: invalid handle {} (table size: {})", handle, table.names.len());
+            format!("<invalid-str-handle-{}>", handle)
+        }
+    }
+}
+
+/// Number of interned strings, for tests and diagnostics
+pub fn interned_count() -> usize {
+    string_table().read().unwrap().names.len()
+}
+
 pub fn truthy(v: &Value) -> bool {
     match v {
         Value::Bool(b) => *b,
         Value::Int(n) => *n != 0,
-        Value::Str(h) => *h != 0,
+        Value::Str(s) => !s.text().is_empty(),
         Value::Unit => false,
         Value::Tuple(elems) => !elems.is_empty(),
         Value::List(elems) => !elems.is_empty(),
         Value::Ctor { .. } => true,
+        Value::Bytes(data) => !data.is_empty(),
+        Value::Builder(buffer) => !buffer.borrow().is_empty(),
+        Value::Channel(_) | Value::Task(_) | Value::Seq(_) => true,
     }
 }
 
 // Tag table for constructors (similar to string table)
-static TAG_TABLE: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
-static TAG_MAP: OnceLock<Mutex<HashMap<String, u32>>> = OnceLock::new();
+//
+// Generated code carries constructor tags as integer literals assigned
+// by the emitter (emit_rust::constructor_tag), and the generated `main`
+// registers the program's table (register_tags) before anything else runs.
+// The constructors the runtime itself builds come first, at fixed tags, so
+// intern_tag returns the same tag for them with or without a program.
+pub const WELL_KNOWN_TAGS: &[&str] = &["Nil", "Cons", "None", "Some", "Ok", "Err", "ProcResult"];
+
+static TAG_TABLE: OnceLock<RwLock<NameTable>> = OnceLock::new();
+
+fn tag_table() -> &'static RwLock<NameTable> {
+    TAG_TABLE.get_or_init(|| RwLock::new(NameTable::new(WELL_KNOWN_TAGS)))
+}
 
 pub fn intern_tag(name: &str) -> u32 {
-    let map_mutex = TAG_MAP.get_or_init(|| Mutex::new(HashMap::new()));
-    let mut map = map_mutex.lock().unwrap();
-    if let Some(&tag) = map.get(name) {
-        return tag;
+    if let Some(tag) = WELL_KNOWN_TAGS.iter().position(|known| *known == name) {
+        return tag as u32;
+    }
+    intern_in(tag_table(), name)
+}
+
+/// Register a program's constructor table: `names[i]` gets tag `i`.
+/// FAIL-FAST: a name already interned at another tag (a table from another
+/// compiler, or a constructor built before registration) would make every
+/// match on it silently fail.
+pub fn register_tags(names: &[&str]) -> Result<(), String> {
+    let mut table = tag_table().write().unwrap();
+    for (tag, name) in names.iter().enumerate() {
+        if table.index.get(*name) == Some(&(tag as u32)) {
+            continue;
+        }
+        if table.index.contains_key(*name) || tag < table.names.len() {
+            return Err(format!(
+                "constructor tag {} is `{}` in the program but `{}` in the runtime",
+                tag,
+                name,
+                table.names.get(tag).map(|name| &**name).unwrap_or("unassigned")
+            ));
+        }
+        table.push(name);
     }
-    let table_mutex = TAG_TABLE.get_or_init(|| Mutex::new(Vec::new()));
-    let mut table = table_mutex.lock().unwrap();
-    let tag = table.len() as u32;
-    table.push(name.to_string());
-    map.insert(name.to_string(), tag);
-    tag
+    Ok(())
 }
 
 pub fn get_tag_name(tag: u32) -> String {
-    let table_mutex = TAG_TABLE.get_or_init(|| Mutex::new(Vec::new()));
-    let table = table_mutex.lock().unwrap();
-    table.get(tag as usize).cloned().unwrap_or_else(|| "Unknown".to_string())
+    if let Some(name) = WELL_KNOWN_TAGS.get(tag as usize) {
+        return name.to_string();
+    }
+    let table = tag_table().read().unwrap();
+    table.names.get(tag as usize).map(|name| name.to_string()).unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// The name a constructor is shown by. A declared constructor's tag is its
+/// type's path and its own name (`geo.Shape.Circle`, emit_rust
+/// constructor_name); it is shown as `Circle`, unless another constructor
+/// of the program is also `Circle`, when the whole name tells them apart.
+pub fn tag_display_name(tag: u32) -> String {
+    let name = get_tag_name(tag);
+    let short = short_tag_name(&name);
+    let table = tag_table().read().unwrap();
+    let shared = table.names.iter().any(|other| **other != *name && short_tag_name(other) == short);
+    if shared { name.clone() } else { short.to_string() }
+}
+
+fn short_tag_name(name: &str) -> &str {
+    name.rsplit_once('.').map_or(name, |(_, short)| short)
 }
 
 // Arithmetic primitives - UNARY CONTRACT
+// Overflow and division by zero follow the program's --int-overflow mode
+// (overflow.rs)
 pub fn __add__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => Value::Int(x.wrapping_add(*y)),
-                _ => Value::Int(0), // Error fallback
-            }
-        },
-        _ => Value::Int(0),
-    }
+    arith_primitive(args, IntOp::Add)
 }
 
 pub fn __sub__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => Value::Int(x.wrapping_sub(*y)),
-                _ => Value::Int(0),
-            }
-        },
-        _ => Value::Int(0),
-    }
+    arith_primitive(args, IntOp::Sub)
 }
 
 pub fn __mul__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => Value::Int(x.wrapping_mul(*y)),
-                _ => Value::Int(0),
-            }
-        },
-        _ => Value::Int(0),
-    }
+    arith_primitive(args, IntOp::Mul)
 }
 
 pub fn __div__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => {
-                    if *y == 0 { Value::Int(0) } else { Value::Int(x / y) }
-                },
-                _ => Value::Int(0),
-            }
-        },
-        _ => Value::Int(0),
-    }
+    arith_primitive(args, IntOp::Div)
 }
 
 pub fn __mod__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => {
-                    if *y == 0 { Value::Int(0) } else { Value::Int(x % y) }
-                },
-                _ => Value::Int(0),
-            }
-        },
-        _ => Value::Int(0),
-    }
+    arith_primitive(args, IntOp::Rem)
 }
 
 // Comparison primitives - UNARY CONTRACT
@@ -238,52 +417,28 @@ pub fn __neq__(args: Value) -> Value {
     }
 }
 
-pub fn __lt__(args: Value) -> Value {
+// Ordered as value_cmp orders them (order.rs); false unless given a pair
+fn compare(args: Value, holds: fn(Ordering) -> bool) -> Value {
     match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => Value::Bool(x < y),
-                _ => Value::Bool(false),
-            }
-        },
+        Value::Tuple(ref elems) if elems.len() >= 2 => Value::Bool(holds(compare_values(&elems[0], &elems[1]))),
         _ => Value::Bool(false),
     }
 }
 
+pub fn __lt__(args: Value) -> Value {
+    compare(args, Ordering::is_lt)
+}
+
 pub fn __lte__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => Value::Bool(x <= y),
-                _ => Value::Bool(false),
-            }
-        },
-        _ => Value::Bool(false),
-    }
+    compare(args, Ordering::is_le)
 }
 
 pub fn __gt__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => Value::Bool(x > y),
-                _ => Value::Bool(false),
-            }
-        },
-        _ => Value::Bool(false),
-    }
+    compare(args, Ordering::is_gt)
 }
 
 pub fn __gte__(args: Value) -> Value {
-    match args {
-        Value::Tuple(ref elems) if elems.len() >= 2 => {
-            match (&elems[0], &elems[1]) {
-                (Value::Int(x), Value::Int(y)) => Value::Bool(x >= y),
-                _ => Value::Bool(false),
-            }
-        },
-        _ => Value::Bool(false),
-    }
+    compare(args, Ordering::is_ge)
 }
 
 // Logical primitives - UNARY CONTRACT
@@ -312,9 +467,8 @@ pub fn __not__(a: Value) -> Value {
 // String primitives
 pub fn str_len(s: Value) -> Value {
     match s {
-        Value::Str(handle) => {
-            let string = get_str(handle);
-            Value::Int(string.len() as i64)
+        Value::Str(s) => {
+            Value::Int(s.text().len() as i64)
         },
         _ => Value::Int(0),
     }
@@ -322,9 +476,8 @@ pub fn str_len(s: Value) -> Value {
 
 pub fn str_char(s: Value, idx: Value) -> Value {
     match (s, idx) {
-        (Value::Str(handle), Value::Int(i)) => {
-            let string = get_str(handle);
-            if let Some(c) = string.chars().nth(i as usize) {
+        (Value::Str(s), Value::Int(i)) => {
+            if let Some(c) = s.text().chars().nth(i as usize) {
                 Value::Int(c as i64)
             } else {
                 Value::Int(0)
@@ -342,25 +495,24 @@ pub fn str_slice(args: Value) -> Value {
     match args {
         Value::Tuple(ref elems) if elems.len() >= 3 => {
             match (&elems[0], &elems[1], &elems[2]) {
-                (Value::Str(handle), Value::Int(s_idx), Value::Int(e_idx)) => {
-                    let string = get_str(*handle);
+                (Value::Str(s), Value::Int(s_idx), Value::Int(e_idx)) => {
+                    let string = s.text();
                     let start = (*s_idx).min(string.len() as i64) as usize;
                     let end = (*e_idx).min(string.len() as i64) as usize;
                     let slice = &string[start..end];
-                    Value::Str(intern_str(slice))
+                    Value::string(slice)
                 },
-                _ => Value::Str(0),
+                _ => Value::string(""),
             }
         },
-        _ => Value::Str(0),
+        _ => Value::string(""),
     }
 }
 
 pub fn str_to_int(s: Value) -> Value {
     match s {
-        Value::Str(handle) => {
-            let string = get_str(handle);
-            Value::Int(string.parse().unwrap_or(0))
+        Value::Str(s) => {
+            Value::Int(s.text().parse().unwrap_or(0))
         },
         _ => Value::Int(0),
     }
@@ -370,15 +522,13 @@ pub fn str_concat(args: Value) -> Value {
     match args {
         Value::Tuple(ref elems) if elems.len() >= 2 => {
             match (&elems[0], &elems[1]) {
-                (Value::Str(h1), Value::Str(h2)) => {
-                    let s1 = get_str(*h1);
-                    let s2 = get_str(*h2);
-                    Value::Str(intern_str(&format!("{}{}", s1, s2)))
+                (Value::Str(s1), Value::Str(s2)) => {
+                    Value::string(&format!("{}{}", s1.text(), s2.text()))
                 },
-                _ => Value::Str(0),
+                _ => Value::string(""),
             }
         },
-        _ => Value::Str(0),
+        _ => Value::string(""),
     }
 }
 
@@ -392,19 +542,113 @@ pub fn int_to_str(n: Value) -> Value {
             let s = i.to_string();
             // Safety check: ensure we never return empty for valid integers
             if s.is_empty() {
-                panic!("int_to_str: to_string() returned empty for {}", i);
+                axis_runtime_error(&format!("int_to_str: to_string() returned empty for {}", i), None);
             }
-            let handle = intern_str(&s);
-            // Verify the string can be retrieved correctly
-            let retrieved = get_str(handle);
-            if retrieved.is_empty() && i != 0 {
-                panic!("int_to_str: intern_str/get_str corrupted string '{}' for {}", s, i);
-            }
-            Value::Str(handle)
+            Value::string(&s)
         },
         _ => {
             // Non-integer values should never happen in well-typed code
-            Value::Str(intern_str("<not-an-int>"))
+            Value::string("<not-an-int>")
+        }
+    }
+}
+
+// Byte buffer primitives
+// Bytes are never interned: file contents can be large and are rarely shared.
+
+pub fn bytes_len(b: Value) -> Value {
+    match b {
+        Value::Bytes(data) => Value::Int(data.len() as i64),
+        _ => Value::Int(0),
+    }
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [bytes, index]
+// Returns the byte as an Int in 0..=255, or -1 when the index is out of range
+// (0 is a valid byte, so it cannot double as the error fallback).
+pub fn bytes_get(args: Value) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() >= 2 => {
+            match (&elems[0], &elems[1]) {
+                (Value::Bytes(data), Value::Int(i)) if *i >= 0 => {
+                    data.get(*i as usize).map(|b| Value::Int(*b as i64)).unwrap_or(Value::Int(-1))
+                },
+                _ => Value::Int(-1),
+            }
+        },
+        _ => Value::Int(-1),
+    }
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [bytes, start, end]
+// Indices are clamped to the buffer, mirroring str_slice.
+pub fn bytes_slice(args: Value) -> Value {
+    match args {
+        Value::Tuple(ref elems) if elems.len() >= 3 => {
+            match (&elems[0], &elems[1], &elems[2]) {
+                (Value::Bytes(data), Value::Int(s_idx), Value::Int(e_idx)) => {
+                    let len = data.len() as i64;
+                    let start = (*s_idx).clamp(0, len) as usize;
+                    let end = (*e_idx).clamp(0, len) as usize;
+                    if start >= end {
+                        return Value::Bytes(vec![]);
+                    }
+                    Value::Bytes(data[start..end].to_vec())
+                },
+                _ => Value::Bytes(vec![]),
+            }
+        },
+        _ => Value::Bytes(vec![]),
+    }
+}
+
+// ============================================================================
+// String builder
+// ============================================================================
+//
+// Every `++` copies both operands into a new string, so building a large
+// output one piece at a time is quadratic. A builder appends in place and
+// copies once, in sb_build.
+//
+// POLICY: a builder is a mutable buffer and copies of it share it. Use each
+// builder linearly: push to the builder the last sb_push returned, and do
+// not keep an older copy around expecting its old contents. The registry
+// marks these primitives non-deterministic, so the optimizer neither folds
+// nor shares their calls.
+
+// Takes a (unit) argument, as io_read_bytes
+pub fn sb_new(_unit: Value) -> Value {
+    Value::Builder(Rc::new(RefCell::new(String::new())))
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [builder, str]
+// Returns the builder
+pub fn sb_push(args: Value) -> Value {
+    let elems = args.as_tuple();
+    match elems.get(1) {
+        Some(Value::Str(piece)) => {
+            elems[0].as_builder().borrow_mut().push_str(piece.text());
+            elems[0].clone()
+        }
+        _ => axis_runtime_error("sb_push expects (builder, Str)", None),
+    }
+}
+
+// UNARY CONTRACT: Accepts Value::Tuple containing [builder, int]
+// Appends the decimal form of the Int; returns the builder
+pub fn sb_push_int(args: Value) -> Value {
+    let elems = args.as_tuple();
+    match elems.get(1) {
+        Some(Value::Int(n)) => {
+            use std::fmt::Write;
+            let _ = write!(elems[0].as_builder().borrow_mut(), "{}", n);
+            elems[0].clone()
         }
+        _ => axis_runtime_error("sb_push_int expects (builder, Int)", None),
     }
 }
+
+// The builder's contents as a string; the builder can still be pushed to
+pub fn sb_build(builder: Value) -> Value {
+    Value::string(&builder.as_builder().borrow())
+}
@@ -0,0 +1,83 @@
+// The command line of axis-rust-bridge (src/main.rs): subcommands, flag
+// validation and the global flags
+
+use std::fs;
+use std::process::{Command, Output};
+
+fn bridge() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_axis-rust-bridge"))
+}
+
+fn run(command: &mut Command) -> (i32, String, String) {
+    let Output { status, stdout, stderr } = command.output().unwrap();
+    let code = status.code().expect("killed by a signal");
+    (code, String::from_utf8(stdout).unwrap(), String::from_utf8(stderr).unwrap())
+}
+
+#[test]
+fn test_help_lists_the_subcommands() {
+    let (code, help, _) = run(bridge().arg("help"));
+    assert_eq!(code, 0);
+    for subcommand in ["build", "emit", "inspect", "clean"] {
+        assert!(help.contains(&format!("\n  {} ", subcommand)), "{}: {}", subcommand, help);
+    }
+    let (code, help, _) = run(bridge().args(["build", "--help"]));
+    assert_eq!(code, 0);
+    assert!(help.contains("[possible values: blocking, async]"), "{}", help);
+    assert!(help.contains("[default: rust]"), "{}", help);
+}
+
+#[test]
+fn test_flag_values_are_checked_before_the_bundle_is_read() {
+    let usage = |args: &[&str]| run(bridge().args(args)).0;
+    // The bundle does not exist: each is refused as usage (2), not I/O (5)
+    assert_eq!(usage(&["build", "missing.coreir", "--out", "main", "--int-overflow", "panic"]), 2);
+    assert_eq!(usage(&["build", "missing.coreir", "--out", "main", "--entry-args-format", "json"]), 2);
+    assert_eq!(usage(&["build", "missing.coreir", "--out", "main", "--require-bridge-version", "^99"]), 2);
+    assert_eq!(usage(&["build", "missing.coreir", "--out", "main", "--axis-source", "main.ax"]), 2);
+    assert_eq!(usage(&["build", "missing.coreir", "--out", "main", "--stream", "--standalone"]), 2);
+    assert_eq!(usage(&["build", "missing.coreir", "--out", "main", "--emit-source", "out"]), 2);
+    // emit needs its directory, and builds nothing to make reproducible
+    assert_eq!(usage(&["emit", "missing.coreir"]), 2);
+    assert_eq!(usage(&["emit", "missing.coreir", "--out", "out", "--reproducible"]), 2);
+    // --depth is a positive number of levels, for --deep only
+    assert_eq!(usage(&["inspect", "missing.coreir", "--depth", "3"]), 2);
+    assert_eq!(usage(&["inspect", "missing.coreir", "--deep", "--depth", "0"]), 2);
+}
+
+#[test]
+fn test_positional_usage_still_works() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.coreir");
+    // Flags after the bundle, as before, or before it
+    let (code, _, stderr) = run(bridge().arg("build").arg(&missing).arg("--out").arg(dir.path().join("main")));
+    assert_eq!(code, 5);
+    assert!(stderr.contains("Core IR file not found"), "{}", stderr);
+    let (code, _, _) = run(bridge().args(["build", "--target", "c", "--out"]).arg(dir.path().join("main")).arg(&missing));
+    assert_eq!(code, 5);
+}
+
+#[test]
+fn test_timings_print_the_phases() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.coreir");
+    let (code, _, stderr) = run(bridge().arg("--timings").arg("emit").arg(&missing).arg("--out").arg(dir.path()));
+    assert_eq!(code, 5);
+    let timings = stderr.split("Timings:\n").nth(1).expect(&stderr);
+    assert!(timings.starts_with("        time  phase\n"), "{}", timings);
+    assert!(timings.contains(" ms  phase4_axis_rust_bridge_run\n"), "{}", timings);
+}
+
+#[test]
+fn test_clean_removes_the_build_directories() {
+    let temp = tempfile::tempdir().unwrap();
+    for name in ["axis_rust_bridge_build_1234", "axis_rust_bridge_stream_1234", "unrelated"] {
+        fs::create_dir_all(temp.path().join(name).join("src")).unwrap();
+    }
+    let (code, _, stderr) = run(bridge().args(["clean", "--verbose"]).env("TMPDIR", temp.path()));
+    assert_eq!(code, 0, "{}", stderr);
+    assert!(stderr.contains("Removed 2 build directories"), "{}", stderr);
+    assert!(stderr.contains("axis_rust_bridge_build_1234"), "{}", stderr);
+    let left: Vec<_> = fs::read_dir(temp.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(left, vec!["unrelated"]);
+}
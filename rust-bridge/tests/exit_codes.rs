@@ -0,0 +1,43 @@
+// Exit codes of axis-rust-bridge (axis_ir::exit_codes): each kind of failure
+// ends the process with its own code
+
+use std::process::Command;
+
+fn bridge() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_axis-rust-bridge"))
+}
+
+fn exit_code(command: &mut Command) -> i32 {
+    let output = command.output().unwrap();
+    output.status.code().expect("killed by a signal")
+}
+
+#[test]
+fn test_usage_errors_exit_2() {
+    assert_eq!(exit_code(&mut bridge()), 2);
+    assert_eq!(exit_code(bridge().arg("frobnicate")), 2);
+    assert_eq!(exit_code(bridge().args(["build", "main.coreir", "--out", "main", "--no-such-flag"])), 2);
+    assert_eq!(exit_code(bridge().args(["build", "main.coreir", "--out", "main", "--runtime", "threads"])), 2);
+    assert_eq!(exit_code(bridge().args(["build", "main.coreir", "--out", "main", "--allow", "network"])), 2);
+    // Neither --out nor --emit-source
+    assert_eq!(exit_code(bridge().args(["build", "main.coreir", "--debug"])), 2);
+    assert_eq!(exit_code(bridge().args(["build", "main.coreir", "--out", "main", "--backend", "cobol"])), 2);
+}
+
+#[test]
+fn test_io_errors_exit_5() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.coreir");
+    let out = dir.path().join("main");
+    assert_eq!(exit_code(bridge().arg("build").arg(&missing).arg("--out").arg(&out)), 5);
+    assert_eq!(exit_code(bridge().arg("inspect").arg(&missing)), 5);
+}
+
+#[test]
+fn test_help_lists_the_exit_codes() {
+    let output = bridge().arg("--help").output().unwrap();
+    assert!(output.status.success());
+    let help = String::from_utf8(output.stdout).unwrap();
+    assert!(help.contains("Usage: axis-rust-bridge"), "{}", help);
+    assert!(help.contains("Exit codes:\n  0  success\n  2  usage error"), "{}", help);
+}
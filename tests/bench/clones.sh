@@ -0,0 +1,107 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Compare the Rust backend's code with and without redundant clones
+# Usage: tests/bench/clones.sh <program.coreir> [--runs N] [-- program args ...]
+#
+# Builds the bundle twice with axis-rust-bridge:
+#   moved   last uses moved, fresh values not cloned (the default)
+#   cloned  --keep-clones, every clone the emitter writes
+# and times each build (mostly rustc), then runs each binary N times
+# (default 10), alternating between them, and reports the build time, the
+# mean wall time per run, the size of the generated Rust and of the
+# binary. For a realistic picture build the self-hosted compiler's bundle
+# and pass it an .ax file to compile.
+#
+# The two builds must print the same thing; a difference is reported.
+
+usage() {
+    echo "Usage: $0 <program.coreir> [--runs N] [-- program args ...]"
+    exit 1
+}
+
+[[ $# -ge 1 ]] || usage
+BUNDLE="$1"
+shift
+RUNS=10
+PROGRAM_ARGS=()
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --runs)
+            [[ $# -ge 2 ]] || usage
+            RUNS="$2"
+            shift 2
+            ;;
+        --)
+            shift
+            PROGRAM_ARGS=("$@")
+            break
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+[[ -f "$BUNDLE" ]] || { echo "Error: $BUNDLE not found"; exit 1; }
+
+ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/../.." && pwd)"
+BRIDGE_BIN="$ROOT/rust-bridge/target/release/axis-rust-bridge"
+
+echo "--- Building toolchain ---"
+(cd "$ROOT/rust-bridge" && cargo build --release --quiet)
+
+WORK_DIR="$(mktemp -d)"
+trap 'rm -rf "$WORK_DIR"' EXIT
+
+declare -A FLAGS=([moved]="" [cloned]="--keep-clones")
+VARIANTS=(moved cloned)
+declare -A TOTAL_NS
+declare -A BUILD_NS
+for clones in "${VARIANTS[@]}"; do
+    echo "--- Building $clones ---"
+    # shellcheck disable=SC2086
+    "$BRIDGE_BIN" build "$BUNDLE" ${FLAGS[$clones]} --emit-source "$WORK_DIR/$clones.src" > "$WORK_DIR/$clones.log" 2>&1
+    start=$(date +%s%N)
+    # shellcheck disable=SC2086
+    if ! "$BRIDGE_BIN" build "$BUNDLE" ${FLAGS[$clones]} --out "$WORK_DIR/$clones.bin" > "$WORK_DIR/$clones.log" 2>&1; then
+        echo "Error: $clones build failed"
+        tail -20 "$WORK_DIR/$clones.log" | sed 's/^/    /'
+        exit 1
+    fi
+    BUILD_NS[$clones]=$(( $(date +%s%N) - start ))
+    TOTAL_NS[$clones]=0
+done
+
+run() {
+    local clones="$1"
+    local start end
+    set +e
+    start=$(date +%s%N)
+    "$WORK_DIR/$clones.bin" "${PROGRAM_ARGS[@]+"${PROGRAM_ARGS[@]}"}" > "$WORK_DIR/$clones.stdout" 2> /dev/null
+    echo "exit: $?" >> "$WORK_DIR/$clones.stdout"
+    end=$(date +%s%N)
+    set -e
+    TOTAL_NS[$clones]=$(( ${TOTAL_NS[$clones]} + end - start ))
+}
+
+echo "--- Running $RUNS times each ---"
+for ((i = 0; i < RUNS; i++)); do
+    for clones in "${VARIANTS[@]}"; do
+        run "$clones"
+    done
+done
+
+printf "%-8s %10s %12s %16s %14s\n" clones "build ms" "ms/run" "generated lines" "binary bytes"
+for clones in "${VARIANTS[@]}"; do
+    printf "%-8s %10d %12d %16d %14d\n" "$clones" \
+        $(( ${BUILD_NS[$clones]} / 1000000 )) \
+        $(( ${TOTAL_NS[$clones]} / RUNS / 1000000 )) \
+        "$(wc -l < "$WORK_DIR/$clones.src/src/axis_generated.rs")" \
+        "$(wc -c < "$WORK_DIR/$clones.bin")"
+done
+
+if ! diff -u --label cloned --label moved "$WORK_DIR/cloned.stdout" "$WORK_DIR/moved.stdout" > "$WORK_DIR/output.diff"; then
+    echo ""
+    echo "Note: the two builds printed different output:"
+    sed 's/^/    /' "$WORK_DIR/output.diff"
+fi
@@ -0,0 +1,104 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Compare the two match lowerings of the Rust backend on one program
+# Usage: tests/bench/match_lowering.sh <program.coreir> [--runs N] [-- program args ...]
+#
+# Builds the bundle twice with axis-rust-bridge:
+#   tree    decision trees (the default)
+#   linear  --linear-matches, one guarded arm per Axis arm
+# then runs each binary N times (default 10), alternating between them,
+# and reports the mean wall time per run, the size of the generated Rust
+# and of the binary. Match-heavy code shows the difference best: build
+# the self-hosted compiler's bundle and pass it an .ax file to compile.
+#
+# The two builds should print the same thing; a difference is reported.
+# (The linear lowering does not check literals nested in a constructor
+# pattern, so a program relying on them can differ.)
+
+usage() {
+    echo "Usage: $0 <program.coreir> [--runs N] [-- program args ...]"
+    exit 1
+}
+
+[[ $# -ge 1 ]] || usage
+BUNDLE="$1"
+shift
+RUNS=10
+PROGRAM_ARGS=()
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --runs)
+            [[ $# -ge 2 ]] || usage
+            RUNS="$2"
+            shift 2
+            ;;
+        --)
+            shift
+            PROGRAM_ARGS=("$@")
+            break
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+[[ -f "$BUNDLE" ]] || { echo "Error: $BUNDLE not found"; exit 1; }
+
+ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/../.." && pwd)"
+BRIDGE_BIN="$ROOT/rust-bridge/target/release/axis-rust-bridge"
+
+echo "--- Building toolchain ---"
+(cd "$ROOT/rust-bridge" && cargo build --release --quiet)
+
+WORK_DIR="$(mktemp -d)"
+trap 'rm -rf "$WORK_DIR"' EXIT
+
+declare -A FLAGS=([tree]="" [linear]="--linear-matches")
+LOWERINGS=(tree linear)
+declare -A TOTAL_NS
+for lowering in "${LOWERINGS[@]}"; do
+    echo "--- Building $lowering ---"
+    # shellcheck disable=SC2086
+    "$BRIDGE_BIN" build "$BUNDLE" ${FLAGS[$lowering]} --emit-source "$WORK_DIR/$lowering.src" > "$WORK_DIR/$lowering.log" 2>&1
+    # shellcheck disable=SC2086
+    if ! "$BRIDGE_BIN" build "$BUNDLE" ${FLAGS[$lowering]} --out "$WORK_DIR/$lowering.bin" > "$WORK_DIR/$lowering.log" 2>&1; then
+        echo "Error: $lowering build failed"
+        tail -20 "$WORK_DIR/$lowering.log" | sed 's/^/    /'
+        exit 1
+    fi
+    TOTAL_NS[$lowering]=0
+done
+
+run() {
+    local lowering="$1"
+    local start end
+    set +e
+    start=$(date +%s%N)
+    "$WORK_DIR/$lowering.bin" "${PROGRAM_ARGS[@]+"${PROGRAM_ARGS[@]}"}" > "$WORK_DIR/$lowering.stdout" 2> /dev/null
+    echo "exit: $?" >> "$WORK_DIR/$lowering.stdout"
+    end=$(date +%s%N)
+    set -e
+    TOTAL_NS[$lowering]=$(( ${TOTAL_NS[$lowering]} + end - start ))
+}
+
+echo "--- Running $RUNS times each ---"
+for ((i = 0; i < RUNS; i++)); do
+    for lowering in "${LOWERINGS[@]}"; do
+        run "$lowering"
+    done
+done
+
+printf "%-8s %12s %16s %14s\n" lowering "ms/run" "generated lines" "binary bytes"
+for lowering in "${LOWERINGS[@]}"; do
+    printf "%-8s %12d %16d %14d\n" "$lowering" \
+        $(( ${TOTAL_NS[$lowering]} / RUNS / 1000000 )) \
+        "$(wc -l < "$WORK_DIR/$lowering.src/src/axis_generated.rs")" \
+        "$(wc -c < "$WORK_DIR/$lowering.bin")"
+done
+
+if ! diff -u --label linear --label tree "$WORK_DIR/linear.stdout" "$WORK_DIR/tree.stdout" > "$WORK_DIR/output.diff"; then
+    echo ""
+    echo "Note: the two builds printed different output:"
+    sed 's/^/    /' "$WORK_DIR/output.diff"
+fi
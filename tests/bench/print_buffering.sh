@@ -0,0 +1,125 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Compare printing through io_print with the buffered output primitives
+# Usage: tests/bench/print_buffering.sh [--lines N] [--runs N]
+#
+# Compiles one program three ways, each printing N lines (default 200000)
+# one token at a time, the way the self-hosted compiler emits code:
+#   print      axis_io_print, which flushes stdout on every call
+#   buffered   axis_io_print_buffered, flushed once at exit
+#   println    axis_io_println, flushed once at exit
+# builds each with axis-rust-bridge, runs each N times (default 5),
+# alternating between them with stdout to a file, and reports the mean
+# wall time per run. The three must print the same thing; a difference
+# is reported.
+
+usage() {
+    echo "Usage: $0 [--lines N] [--runs N]"
+    exit 1
+}
+
+LINES=200000
+RUNS=5
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --lines)
+            [[ $# -ge 2 ]] || usage
+            LINES="$2"
+            shift 2
+            ;;
+        --runs)
+            [[ $# -ge 2 ]] || usage
+            RUNS="$2"
+            shift 2
+            ;;
+        *)
+            usage
+            ;;
+    esac
+done
+
+ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/../.." && pwd)"
+COMPILER_BIN="$ROOT/core-compiler/target/release/axis-compiler"
+BRIDGE_BIN="$ROOT/rust-bridge/target/release/axis-rust-bridge"
+
+echo "--- Building toolchain ---"
+(cd "$ROOT/core-compiler" && cargo build --release --quiet)
+(cd "$ROOT/rust-bridge" && cargo build --release --quiet)
+
+WORK_DIR="$(mktemp -d)"
+trap 'rm -rf "$WORK_DIR"' EXIT
+
+# One line is two tokens; emit_lines recurses at most 1000 deep
+declare -A LINE_CODE=(
+    [print]='axis_io_print(int_to_str(i)); axis_io_print("\n");'
+    [buffered]='axis_io_print_buffered(int_to_str(i)); axis_io_print_buffered("\n");'
+    [println]='axis_io_println(int_to_str(i));'
+)
+VARIANTS=(print buffered println)
+declare -A TOTAL_NS
+for variant in "${VARIANTS[@]}"; do
+    echo "--- Building $variant ---"
+    cat > "$WORK_DIR/$variant.ax" <<AX
+fn emit_lines(i: Int, end: Int) -> Unit {
+    if i < end {
+        ${LINE_CODE[$variant]}
+        emit_lines(i + 1, end)
+    } else {
+        ()
+    }
+}
+
+fn emit_blocks(block: Int, blocks: Int) -> Unit {
+    if block < blocks {
+        emit_lines(block * 1000, block * 1000 + 1000);
+        emit_blocks(block + 1, blocks)
+    } else {
+        ()
+    }
+}
+
+fn axis_entry(args: Unit) -> Unit {
+    emit_blocks(0, $(( (LINES + 999) / 1000 )))
+}
+AX
+    if ! "$COMPILER_BIN" --sources "$WORK_DIR/$variant.ax" --registries "$ROOT/registries/axis.axreg" \
+            --out "$WORK_DIR/$variant.coreir" > "$WORK_DIR/$variant.log" 2>&1 \
+        || ! "$BRIDGE_BIN" build "$WORK_DIR/$variant.coreir" --out "$WORK_DIR/$variant.bin" \
+            >> "$WORK_DIR/$variant.log" 2>&1; then
+        echo "Error: $variant build failed"
+        tail -20 "$WORK_DIR/$variant.log" | sed 's/^/    /'
+        exit 1
+    fi
+    TOTAL_NS[$variant]=0
+done
+
+run() {
+    local variant="$1"
+    local start end
+    start=$(date +%s%N)
+    "$WORK_DIR/$variant.bin" > "$WORK_DIR/$variant.stdout" 2> /dev/null
+    end=$(date +%s%N)
+    TOTAL_NS[$variant]=$(( ${TOTAL_NS[$variant]} + end - start ))
+}
+
+echo "--- Running $RUNS times each ---"
+for ((i = 0; i < RUNS; i++)); do
+    for variant in "${VARIANTS[@]}"; do
+        run "$variant"
+    done
+done
+
+printf "%-10s %12s %14s\n" variant "ms/run" "stdout bytes"
+for variant in "${VARIANTS[@]}"; do
+    printf "%-10s %12d %14d\n" "$variant" \
+        $(( ${TOTAL_NS[$variant]} / RUNS / 1000000 )) \
+        "$(wc -c < "$WORK_DIR/$variant.stdout")"
+done
+
+for variant in buffered println; do
+    if ! cmp -s "$WORK_DIR/print.stdout" "$WORK_DIR/$variant.stdout"; then
+        echo ""
+        echo "Note: $variant printed something other than print"
+    fi
+done
@@ -0,0 +1,307 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Axis end-to-end golden-file harness
+# Usage: tests/e2e/run.sh [--bless | --optimize | --differential | --native-args] [fixture-name ...]
+#
+# For every fixture under tests/e2e/fixtures/:
+#   <name>.ax        single-file program,
+#   <name>/*.ax      multi-file program (sources concatenated in sorted order), or
+#   <name>.coretext  hand-written textual Core IR (compiled with --from-core-text)
+# the harness runs the full pipeline
+#   axis-compiler (parse -> lower -> validate -> serialize Core IR)
+#   axis-rust-bridge build (emit Rust -> cargo build)
+#   execute the binary
+# and compares its stdout and exit code against <name>.expected:
+#
+#   exit: <code>
+#   --- stdout
+#   <captured stdout, verbatim>
+#
+# Every fixture is also compiled and emitted a second time: the .coreir
+# bundle and the generated Rust sources must be byte-identical.
+#
+# Optional <name>.args holds one program argument per line.
+# Optional <name>.ast is a surface AST snapshot (axis-compiler --dump-ast)
+# checked before compiling; --bless refreshes existing snapshots.
+# --bless rewrites .expected files from the observed results instead of comparing.
+# --optimize compiles with axis-compiler --optimize and compares against the
+# same .expected files: the optimizer must not change what a program does.
+# --differential additionally runs each bundle with the interpreter
+# (axis-compiler run) and compares its stdout and exit code with the
+# binary's. A fixture using something the interpreter does not implement
+# (exit 125) is reported as "skip" for that comparison only.
+# --native-args builds with axis-rust-bridge --native-args (goldens are
+# unchanged); combine it with --differential to check the native calling
+# convention against the interpreter.
+
+usage() {
+    echo "Usage: $0 [--bless | --optimize | --differential | --native-args] [fixture-name ...]"
+    echo ""
+    echo "Options:"
+    echo "  --bless         Update .expected files with the observed output"
+    echo "  --optimize      Compile with the optimizer (goldens are unchanged)"
+    echo "  --differential  Also compare each binary with the interpreter"
+    echo "  --native-args   Build with native argument lists (goldens are unchanged)"
+    echo "  -h, --help      Show this help message"
+    exit 1
+}
+
+BLESS=false
+DIFFERENTIAL=false
+COMPILER_FLAGS=()
+BRIDGE_FLAGS=()
+SELECTED=()
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --bless)
+            BLESS=true
+            shift
+            ;;
+        --optimize)
+            COMPILER_FLAGS+=(--optimize)
+            shift
+            ;;
+        --differential)
+            DIFFERENTIAL=true
+            shift
+            ;;
+        --native-args)
+            BRIDGE_FLAGS+=(--native-args)
+            shift
+            ;;
+        -h|--help)
+            usage
+            ;;
+        -*)
+            echo "Error: Unknown option: $1"
+            echo ""
+            usage
+            ;;
+        *)
+            SELECTED+=("$1")
+            shift
+            ;;
+    esac
+done
+
+# Goldens are only ever recorded from unoptimized builds
+if $BLESS && [[ ${#COMPILER_FLAGS[@]} -gt 0 || ${#BRIDGE_FLAGS[@]} -gt 0 ]]; then
+    echo "Error: --bless cannot be combined with --optimize or --native-args"
+    exit 1
+fi
+
+E2E_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+ROOT="$(cd "$E2E_DIR/../.." && pwd)"
+FIXTURES_DIR="$E2E_DIR/fixtures"
+REGISTRY="$ROOT/registries/axis.axreg"
+
+COMPILER_BIN="$ROOT/core-compiler/target/release/axis-compiler"
+BRIDGE_BIN="$ROOT/rust-bridge/target/release/axis-rust-bridge"
+
+# Always rebuild so the harness exercises the working tree
+echo "--- Building toolchain ---"
+(cd "$ROOT/core-compiler" && cargo build --release --quiet)
+(cd "$ROOT/rust-bridge" && cargo build --release --quiet)
+echo ""
+
+WORK_DIR="$(mktemp -d)"
+trap 'rm -rf "$WORK_DIR"' EXIT
+
+# Collect fixture names (files and directories), sorted
+FIXTURES=()
+if [[ ${#SELECTED[@]} -gt 0 ]]; then
+    FIXTURES=("${SELECTED[@]}")
+else
+    for entry in "$FIXTURES_DIR"/*; do
+        name="$(basename "$entry")"
+        if [[ -d "$entry" ]]; then
+            FIXTURES+=("$name")
+        elif [[ "$name" == *.ax ]]; then
+            FIXTURES+=("${name%.ax}")
+        elif [[ "$name" == *.coretext ]]; then
+            FIXTURES+=("${name%.coretext}")
+        fi
+    done
+fi
+
+PASSED=0
+FAILED=0
+FAILED_NAMES=()
+SKIPPED_NAMES=()
+
+for name in "${FIXTURES[@]}"; do
+    sources=()
+    input_args=()
+    if [[ -d "$FIXTURES_DIR/$name" ]]; then
+        for src in "$FIXTURES_DIR/$name"/*.ax; do
+            sources+=("$src")
+        done
+        input_args=(--sources "${sources[@]}")
+    elif [[ -f "$FIXTURES_DIR/$name.ax" ]]; then
+        sources=("$FIXTURES_DIR/$name.ax")
+        input_args=(--sources "${sources[@]}")
+    elif [[ -f "$FIXTURES_DIR/$name.coretext" ]]; then
+        sources=("$FIXTURES_DIR/$name.coretext")
+        input_args=(--from-core-text "${sources[@]}")
+    fi
+    if [[ ${#sources[@]} -eq 0 ]]; then
+        echo "Error: fixture '$name' not found in $FIXTURES_DIR"
+        exit 1
+    fi
+
+    expected_file="$FIXTURES_DIR/$name.expected"
+    coreir_file="$WORK_DIR/$name.coreir"
+    binary="$WORK_DIR/$name.bin"
+    log_file="$WORK_DIR/$name.log"
+
+    program_args=()
+    if [[ -f "$FIXTURES_DIR/$name.args" ]]; then
+        while IFS= read -r arg; do
+            program_args+=("$arg")
+        done < "$FIXTURES_DIR/$name.args"
+    fi
+
+    # Optional surface AST snapshot: <name>.ast is compared with --dump-ast
+    ast_file="$FIXTURES_DIR/$name.ast"
+    if [[ -f "$ast_file" ]]; then
+        if ! "$COMPILER_BIN" "${input_args[@]}" --registries "$REGISTRY" --dump-ast - \
+                > "$WORK_DIR/$name.ast" 2> "$log_file"; then
+            echo "FAIL $name (axis-compiler --dump-ast)"
+            tail -20 "$log_file" | sed 's/^/    /'
+            FAILED=$((FAILED + 1))
+            FAILED_NAMES+=("$name")
+            continue
+        fi
+        if $BLESS; then
+            cp "$WORK_DIR/$name.ast" "$ast_file"
+        elif ! diff -u "$ast_file" "$WORK_DIR/$name.ast" > "$WORK_DIR/$name.diff"; then
+            echo "FAIL $name (AST snapshot mismatch)"
+            sed 's/^/    /' "$WORK_DIR/$name.diff"
+            FAILED=$((FAILED + 1))
+            FAILED_NAMES+=("$name")
+            continue
+        fi
+    fi
+
+    # Pipeline failures are reported with the tool log, never blessed
+    if ! "$COMPILER_BIN" "${input_args[@]}" --registries "$REGISTRY" \
+            ${COMPILER_FLAGS[@]+"${COMPILER_FLAGS[@]}"} --out "$coreir_file" > "$log_file" 2>&1; then
+        echo "FAIL $name (axis-compiler)"
+        tail -20 "$log_file" | sed 's/^/    /'
+        FAILED=$((FAILED + 1))
+        FAILED_NAMES+=("$name")
+        continue
+    fi
+
+    # Deterministic output: a second compile and emit reproduce every byte
+    if ! "$COMPILER_BIN" "${input_args[@]}" --registries "$REGISTRY" \
+            ${COMPILER_FLAGS[@]+"${COMPILER_FLAGS[@]}"} --out "$WORK_DIR/$name.again.coreir" > "$log_file" 2>&1 \
+        || ! cmp -s "$coreir_file" "$WORK_DIR/$name.again.coreir"; then
+        echo "FAIL $name (.coreir differs between two compiles)"
+        FAILED=$((FAILED + 1))
+        FAILED_NAMES+=("$name")
+        continue
+    fi
+    # (emit failures are left for the build below to report)
+    if "$BRIDGE_BIN" build "$coreir_file" ${BRIDGE_FLAGS[@]+"${BRIDGE_FLAGS[@]}"} \
+            --emit-source "$WORK_DIR/$name.rs.1" > "$log_file" 2>&1 \
+        && "$BRIDGE_BIN" build "$coreir_file" ${BRIDGE_FLAGS[@]+"${BRIDGE_FLAGS[@]}"} \
+            --emit-source "$WORK_DIR/$name.rs.2" > "$log_file" 2>&1 \
+        && ! diff -r "$WORK_DIR/$name.rs.1" "$WORK_DIR/$name.rs.2" > "$WORK_DIR/$name.diff"; then
+        echo "FAIL $name (generated Rust differs between two emits)"
+        sed 's/^/    /' "$WORK_DIR/$name.diff"
+        FAILED=$((FAILED + 1))
+        FAILED_NAMES+=("$name")
+        continue
+    fi
+
+    if ! "$BRIDGE_BIN" build "$coreir_file" ${BRIDGE_FLAGS[@]+"${BRIDGE_FLAGS[@]}"} --out "$binary" > "$log_file" 2>&1; then
+        echo "FAIL $name (axis-rust-bridge)"
+        tail -20 "$log_file" | sed 's/^/    /'
+        FAILED=$((FAILED + 1))
+        FAILED_NAMES+=("$name")
+        continue
+    fi
+
+    set +e
+    "$binary" "${program_args[@]+"${program_args[@]}"}" > "$WORK_DIR/$name.stdout" 2> "$WORK_DIR/$name.stderr"
+    exit_code=$?
+    set -e
+
+    actual_file="$WORK_DIR/$name.actual"
+    {
+        echo "exit: $exit_code"
+        echo "--- stdout"
+        cat "$WORK_DIR/$name.stdout"
+    } > "$actual_file"
+
+    # Differential: the interpreter must agree with the binary, golden or not
+    if $DIFFERENTIAL; then
+        set +e
+        "$COMPILER_BIN" run "$coreir_file" ${program_args[@]+"${program_args[@]}"} \
+            > "$WORK_DIR/$name.interp.stdout" 2> "$WORK_DIR/$name.interp.stderr"
+        interp_exit=$?
+        set -e
+        if [[ $interp_exit -eq 125 ]]; then
+            echo "skip $name (interpreter: $(tail -1 "$WORK_DIR/$name.interp.stderr"))"
+            SKIPPED_NAMES+=("$name")
+        else
+            {
+                echo "exit: $interp_exit"
+                echo "--- stdout"
+                cat "$WORK_DIR/$name.interp.stdout"
+            } > "$WORK_DIR/$name.interp"
+            if ! diff -u --label interpreter --label binary "$WORK_DIR/$name.interp" "$actual_file" \
+                    > "$WORK_DIR/$name.diff"; then
+                echo "FAIL $name (interpreter and binary disagree)"
+                sed 's/^/    /' "$WORK_DIR/$name.diff"
+                if [[ -s "$WORK_DIR/$name.interp.stderr" ]]; then
+                    echo "    --- interpreter stderr"
+                    tail -20 "$WORK_DIR/$name.interp.stderr" | sed 's/^/    /'
+                fi
+                FAILED=$((FAILED + 1))
+                FAILED_NAMES+=("$name")
+                continue
+            fi
+        fi
+    fi
+
+    if $BLESS; then
+        cp "$actual_file" "$expected_file"
+        echo "BLESS $name"
+        PASSED=$((PASSED + 1))
+        continue
+    fi
+
+    if [[ ! -f "$expected_file" ]]; then
+        echo "FAIL $name (missing $name.expected; run with --bless to create it)"
+        FAILED=$((FAILED + 1))
+        FAILED_NAMES+=("$name")
+        continue
+    fi
+
+    if diff -u "$expected_file" "$actual_file" > "$WORK_DIR/$name.diff"; then
+        echo "ok   $name"
+        PASSED=$((PASSED + 1))
+    else
+        echo "FAIL $name (output mismatch)"
+        sed 's/^/    /' "$WORK_DIR/$name.diff"
+        if [[ -s "$WORK_DIR/$name.stderr" ]]; then
+            echo "    --- stderr"
+            tail -20 "$WORK_DIR/$name.stderr" | sed 's/^/    /'
+        fi
+        FAILED=$((FAILED + 1))
+        FAILED_NAMES+=("$name")
+    fi
+done
+
+echo ""
+echo "e2e: $PASSED passed, $FAILED failed"
+if [[ ${#SKIPPED_NAMES[@]} -gt 0 ]]; then
+    echo "Not compared with the interpreter: ${SKIPPED_NAMES[*]}"
+fi
+if [[ $FAILED -gt 0 ]]; then
+    echo "Failed: ${FAILED_NAMES[*]}"
+    exit 1
+fi